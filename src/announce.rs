@@ -0,0 +1,346 @@
+use std::fs;
+use std::path::Path;
+use log::{info, warn};
+use regex::Regex;
+use serde::Deserialize;
+use crate::sync::cross_seed_to_qbittorrent;
+use crate::trackers::seedpool::{check_seedpool, check_seedpool_dupes};
+use crate::types::QbittorrentConfig;
+use crate::utils::generate_release_name;
+
+/// What to do when an announcement passes a rule's filters.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AnnounceAction {
+    #[default]
+    Notify,    // Only highlight the announcement in the IRC window
+    DupeCheck, // Report whether Seedpool already has an exact match
+    CrossSeed, // Add the Seedpool torrent to qBittorrent when the release exists in `data_dirs`
+}
+
+/// One announce channel: how to read its lines and which releases are interesting.
+#[derive(Deserialize, Clone, Debug)]
+pub struct AnnounceRule {
+    pub network: Option<String>,   // Network name from irc.yaml; defaults to the first network
+    pub channel: String,
+    pub announcer: Option<String>, // Only lines from this nick are parsed
+    pub pattern: String,           // Regex with a `name` group and optional `category`/`url` groups
+    #[serde(default)]
+    pub categories: Vec<String>,   // Empty = any category
+    #[serde(default)]
+    pub resolutions: Vec<String>,  // e.g. ["1080p", "2160p"]; empty = any
+    #[serde(default)]
+    pub groups: Vec<String>,       // Release group whitelist; empty = any
+    #[serde(default)]
+    pub exclude: Vec<String>,      // Skip names containing any of these words
+    #[serde(default)]
+    pub action: AnnounceAction,
+}
+
+/// Contents of `config/announce.yaml`.
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct AnnounceConfig {
+    #[serde(default)]
+    pub data_dirs: Vec<String>, // Searched for local copies of announced releases when cross-seeding
+    #[serde(default)]
+    pub rules: Vec<AnnounceRule>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Announcement {
+    pub network: String,
+    pub channel: String,
+    pub name: String,
+    pub category: Option<String>,
+    pub resolution: Option<String>,
+    pub group: Option<String>,
+    pub url: Option<String>,
+}
+
+pub struct AnnounceParser {
+    rules: Vec<(AnnounceRule, Regex)>,
+}
+
+/// Removes mIRC color/bold/underline/reset codes so patterns match the plain text.
+pub fn strip_irc_formatting(message: &str) -> String {
+    let color = Regex::new(r"\x03(\d{1,2}(,\d{1,2})?)?").unwrap();
+    color
+        .replace_all(message, "")
+        .chars()
+        .filter(|c| !matches!(c, '\x02' | '\x0F' | '\x16' | '\x1D' | '\x1F'))
+        .collect()
+}
+
+fn detect_resolution(name: &str) -> Option<String> {
+    let re = Regex::new(r"(?i)\b(2160p|1080p|1080i|720p|576p|480p)\b").unwrap();
+    re.captures(name).map(|caps| caps[1].to_lowercase())
+}
+
+fn detect_group(name: &str) -> Option<String> {
+    name.rsplit_once('-')
+        .map(|(_, group)| group.trim().to_string())
+        .filter(|group| !group.is_empty() && !group.contains(' '))
+}
+
+impl AnnounceParser {
+    pub fn new(config: &AnnounceConfig) -> Result<AnnounceParser, String> {
+        let mut rules = Vec::new();
+        for rule in &config.rules {
+            let regex = Regex::new(&rule.pattern)
+                .map_err(|e| format!("Invalid announce pattern for {}: {}", rule.channel, e))?;
+            if regex.capture_names().flatten().all(|name| name != "name") {
+                return Err(format!("Announce pattern for {} needs a (?P<name>...) group", rule.channel));
+            }
+            rules.push((rule.clone(), regex));
+        }
+        Ok(AnnounceParser { rules })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Parses a channel message and returns the announcement with its rule when it matches
+    /// a rule for that network/channel and passes the rule's filters.
+    /// `default_network` is the network that rules without a `network` belong to.
+    pub fn parse(
+        &self,
+        network: &str,
+        default_network: &str,
+        channel: &str,
+        nick: Option<&str>,
+        message: &str,
+    ) -> Option<(Announcement, &AnnounceRule)> {
+        let text = strip_irc_formatting(message);
+        for (rule, regex) in &self.rules {
+            let rule_network = rule.network.as_deref().unwrap_or(default_network);
+            if !rule_network.eq_ignore_ascii_case(network) || !rule.channel.eq_ignore_ascii_case(channel) {
+                continue;
+            }
+            if let Some(announcer) = &rule.announcer
+                && !nick.is_some_and(|nick| nick.eq_ignore_ascii_case(announcer))
+            {
+                continue;
+            }
+            let Some(caps) = regex.captures(&text) else { continue };
+            let name = caps["name"].trim().to_string();
+            let announcement = Announcement {
+                network: network.to_string(),
+                channel: channel.to_string(),
+                resolution: detect_resolution(&name),
+                group: detect_group(&name),
+                category: caps.name("category").map(|m| m.as_str().trim().to_string()),
+                url: caps.name("url").map(|m| m.as_str().trim().to_string()),
+                name,
+            };
+            if passes_filters(rule, &announcement) {
+                return Some((announcement, rule));
+            }
+        }
+        None
+    }
+}
+
+fn passes_filters(rule: &AnnounceRule, announcement: &Announcement) -> bool {
+    let contains_ignore_case = |list: &[String], value: &Option<String>| {
+        list.is_empty()
+            || value
+                .as_ref()
+                .is_some_and(|value| list.iter().any(|item| item.eq_ignore_ascii_case(value)))
+    };
+    let lower_name = announcement.name.to_lowercase();
+    contains_ignore_case(&rule.categories, &announcement.category)
+        && contains_ignore_case(&rule.resolutions, &announcement.resolution)
+        && contains_ignore_case(&rule.groups, &announcement.group)
+        && !rule.exclude.iter().any(|word| lower_name.contains(&word.to_lowercase()))
+}
+
+/// Loads `announce.yaml`; a missing file means no announce rules.
+pub fn load_announce_config(path: &Path) -> Result<AnnounceConfig, String> {
+    if !path.exists() {
+        return Ok(AnnounceConfig::default());
+    }
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_yaml::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+/// Finds a file or directory named like the release directly inside one of `data_dirs`.
+fn find_local_release(name: &str, data_dirs: &[String]) -> Option<String> {
+    let normalized = generate_release_name(name);
+    for dir in data_dirs {
+        let Ok(entries) = fs::read_dir(dir) else {
+            warn!("Cannot read cross-seed data dir {}", dir);
+            continue;
+        };
+        for entry in entries.filter_map(|e| e.ok()) {
+            let entry_name = entry.file_name().to_string_lossy().to_string();
+            if entry_name == name || generate_release_name(&entry_name) == normalized {
+                return Some(dir.clone());
+            }
+        }
+    }
+    None
+}
+
+/// Runs a rule's action for an announcement and returns a status line for the IRC window.
+/// Blocking (HTTP requests); call from `spawn_blocking`.
+pub fn run_announce_action(
+    announcement: &Announcement,
+    action: AnnounceAction,
+    data_dirs: &[String],
+    seedpool_api_key: &str,
+    qbittorrent_configs: &[QbittorrentConfig],
+) -> String {
+    info!("Announce match in {}: {} ({:?})", announcement.channel, announcement.name, action);
+    match action {
+        AnnounceAction::Notify => format!("★ {}", announcement.name),
+        AnnounceAction::DupeCheck => match check_seedpool_dupes(&announcement.name, seedpool_api_key) {
+            Ok(Some(_)) => format!("★ {}: already on Seedpool", announcement.name),
+            Ok(None) => format!("★ {}: not on Seedpool yet", announcement.name),
+            Err(e) => format!("★ {}: dupe check failed: {}", announcement.name, e),
+        },
+        AnnounceAction::CrossSeed => {
+            let Some(save_path) = find_local_release(&announcement.name, data_dirs) else {
+                return format!("★ {}: no local copy, not cross-seeding", announcement.name);
+            };
+            let download_link = match check_seedpool(&announcement.name, seedpool_api_key) {
+                Ok(Some(link)) => link,
+                Ok(None) => return format!("★ {}: local copy found but no Seedpool torrent", announcement.name),
+                Err(e) => return format!("★ {}: Seedpool lookup failed: {}", announcement.name, e),
+            };
+            let mut added = 0;
+            for config in qbittorrent_configs {
+                match cross_seed_to_qbittorrent(config, &download_link, &save_path) {
+                    Ok(()) => added += 1,
+                    Err(e) => warn!("Cross-seed of '{}' to {} failed: {}", announcement.name, config.webui_url, e),
+                }
+            }
+            if added > 0 {
+                format!("★ {}: cross-seeding from {}", announcement.name, save_path)
+            } else {
+                format!("★ {}: cross-seed failed, see seed-tools.log", announcement.name)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RULES: &str = r##"
+rules:
+  - channel: "#announce"
+    announcer: Announcer
+    pattern: 'New in \[(?P<category>[^\]]+)\]: (?P<name>\S+) - (?P<url>https://\S+)'
+    categories: [Movies, TV]
+    resolutions: [1080p, 2160p]
+    exclude: [cam]
+    action: cross_seed
+  - network: Other
+    channel: "#announce"
+    pattern: '^(?P<name>\S+)$'
+    groups: [GROUP]
+"##;
+
+    fn parser() -> AnnounceParser {
+        AnnounceParser::new(&serde_yaml::from_str(RULES).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn strips_irc_formatting() {
+        assert_eq!(strip_irc_formatting("\x02New\x02 in \x0304,01[Movies]\x03: \x1Fname\x0F"), "New in [Movies]: name");
+    }
+
+    #[test]
+    fn rejects_patterns_without_a_name_group() {
+        let config = |pattern: &str| AnnounceConfig {
+            rules: vec![AnnounceRule {
+                network: None,
+                channel: "#announce".to_string(),
+                announcer: None,
+                pattern: pattern.to_string(),
+                categories: Vec::new(),
+                resolutions: Vec::new(),
+                groups: Vec::new(),
+                exclude: Vec::new(),
+                action: AnnounceAction::Notify,
+            }],
+            ..Default::default()
+        };
+        assert!(AnnounceParser::new(&config("(?P<release>.+)")).err().unwrap().contains("needs a (?P<name>...) group"));
+        assert!(AnnounceParser::new(&config("(?P<name>.+")).err().unwrap().starts_with("Invalid announce pattern for #announce"));
+        assert!(!AnnounceParser::new(&config("(?P<name>.+)")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn parses_announcements_that_pass_the_filters() {
+        let parser = parser();
+        let line = "New in \x0304[Movies]\x03: Some.Movie.2020.1080p.BluRay.x264-GROUP - https://tracker.example/t/1";
+        let (announcement, rule) = parser.parse("Main", "main", "#Announce", Some("announcer"), line).unwrap();
+        assert_eq!(announcement.name, "Some.Movie.2020.1080p.BluRay.x264-GROUP");
+        assert_eq!(announcement.category.as_deref(), Some("Movies"));
+        assert_eq!(announcement.resolution.as_deref(), Some("1080p"));
+        assert_eq!(announcement.group.as_deref(), Some("GROUP"));
+        assert_eq!(announcement.url.as_deref(), Some("https://tracker.example/t/1"));
+        assert_eq!(rule.action, AnnounceAction::CrossSeed);
+
+        // Other announcers, channels and networks are ignored
+        assert!(parser.parse("main", "main", "#announce", Some("someone"), line).is_none());
+        assert!(parser.parse("main", "main", "#announce", None, line).is_none());
+        assert!(parser.parse("main", "main", "#chat", Some("Announcer"), line).is_none());
+        assert!(parser.parse("other", "main", "#announce", Some("Announcer"), line).is_none());
+    }
+
+    #[test]
+    fn filters_on_category_resolution_group_and_excluded_words() {
+        let parser = parser();
+        let parse = |line: &str| parser.parse("main", "main", "#announce", Some("Announcer"), line).map(|(announcement, _)| announcement.name);
+        assert!(parse("New in [Music]: Some.Album.2020.FLAC-GROUP - https://tracker.example/t/2").is_none());
+        assert!(parse("New in [Movies]: Some.Movie.2020.720p.WEB-GROUP - https://tracker.example/t/3").is_none());
+        assert!(parse("New in [Movies]: Some.Movie.2020.1080p.CAM-GROUP - https://tracker.example/t/4").is_none());
+        assert!(parse("New in [tv]: Some.Show.S01E01.2160p.WEB-GROUP - https://tracker.example/t/5").is_some());
+
+        // Rules with a network only apply there
+        let parse_other = |line: &str| parser.parse("Other", "main", "#announce", None, line).map(|(announcement, _)| announcement.name);
+        assert_eq!(parse_other("Some.Movie.2020.720p.WEB-group").as_deref(), Some("Some.Movie.2020.720p.WEB-group"));
+        assert!(parse_other("Some.Movie.2020.720p.WEB-OTHER").is_none());
+    }
+
+    #[test]
+    fn finds_local_copies_by_normalized_name() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("Some Movie (2020) 1080p BluRay-GROUP")).unwrap();
+        let data_dirs = vec![dir.path().join("missing").to_string_lossy().to_string(), dir.path().to_string_lossy().to_string()];
+        assert_eq!(find_local_release("Some.Movie.2020.1080p.BluRay-GROUP", &data_dirs), Some(data_dirs[1].clone()));
+        assert_eq!(find_local_release("Other.Movie.2020.1080p.BluRay-GROUP", &data_dirs), None);
+
+        let announcement = Announcement {
+            network: "main".to_string(),
+            channel: "#announce".to_string(),
+            name: "Other.Movie.2020.1080p.BluRay-GROUP".to_string(),
+            category: None,
+            resolution: None,
+            group: None,
+            url: None,
+        };
+        assert_eq!(
+            run_announce_action(&announcement, AnnounceAction::CrossSeed, &data_dirs, "", &[]),
+            "★ Other.Movie.2020.1080p.BluRay-GROUP: no local copy, not cross-seeding"
+        );
+    }
+
+    #[test]
+    fn loads_announce_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("announce.yaml");
+        assert!(load_announce_config(&path).unwrap().rules.is_empty());
+        fs::write(&path, RULES).unwrap();
+        let config = load_announce_config(&path).unwrap();
+        assert_eq!(config.rules.len(), 2);
+        assert_eq!(config.rules[1].action, AnnounceAction::Notify);
+        fs::write(&path, "rules: [").unwrap();
+        assert!(load_announce_config(&path).is_err());
+    }
+}
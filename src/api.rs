@@ -0,0 +1,318 @@
+use std::net::SocketAddr;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use axum::{
+    extract::{Path as UrlPath, State},
+    http::{header, Request, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use log::{info, warn};
+use serde::Deserialize;
+use crate::jobs::JobManager;
+use crate::reload::{watch_config_dir, Live};
+use crate::trumping::Verdict;
+use crate::types::{ApiConfig, Config, UploadOverrides, WebhookConfig};
+
+#[derive(Clone)]
+struct ApiState {
+    settings: Arc<Live<ApiSettings>>,
+    jobs: Arc<JobManager>,
+}
+
+/// The parts of the `api` section that a config reload changes on the fly.
+struct ApiSettings {
+    root: Option<PathBuf>,
+    token: String,
+    webhook: WebhookConfig,
+}
+
+impl ApiSettings {
+    fn new(config: &ApiConfig) -> Result<Self, String> {
+        if config.token.trim().is_empty() {
+            return Err("The api section needs a non-empty token".to_string());
+        }
+        let root = match &config.root_dir {
+            Some(dir) => Some(
+                PathBuf::from(dir)
+                    .canonicalize()
+                    .map_err(|e| format!("Invalid api root_dir '{}': {}", dir, e))?,
+            ),
+            None => None,
+        };
+        Ok(ApiSettings { root, token: config.token.clone(), webhook: config.webhook.clone() })
+    }
+}
+
+#[derive(Deserialize)]
+struct PreflightRequest {
+    path: String,
+}
+
+#[derive(Deserialize)]
+struct UploadRequest {
+    path: String,
+    trackers: Vec<String>,
+    #[serde(default)]
+    overrides: UploadOverrides,
+}
+
+/// An autobrr webhook action's payload, e.g.
+/// `{"release_name": "{{ .TorrentName }}", "path": "/data/{{ .TorrentName }}"}`.
+#[derive(Deserialize)]
+struct WebhookRequest {
+    release_name: String,
+    path: Option<String>, // Defaults to `download_dir` joined with the release name
+    #[serde(default)]
+    trackers: Vec<String>,
+}
+
+/// The pre-flight fields the webhook decides on, read from `--pre --output json`.
+#[derive(Deserialize)]
+struct WebhookPreflight {
+    dupe: bool,
+    dupe_check: String,
+    trump: Option<Verdict>,
+    trump_check: String,
+}
+
+fn error_response(status: StatusCode, message: impl Into<String>) -> Response {
+    (status, Json(serde_json::json!({ "error": message.into() }))).into_response()
+}
+
+/// Accepts absolute paths only (API clients run elsewhere, so relative paths are ambiguous),
+/// restricted to `root_dir` when one is configured.
+fn resolve_path(root: Option<&Path>, path: &str) -> Result<PathBuf, String> {
+    if !Path::new(path).is_absolute() {
+        return Err(format!("Path '{}' must be absolute", path));
+    }
+    let candidate = Path::new(path)
+        .canonicalize()
+        .map_err(|e| format!("Invalid path '{}': {}", path, e))?;
+    if let Some(root) = root
+        && !candidate.starts_with(root)
+    {
+        return Err(format!("Path '{}' is outside the allowed root_dir", path));
+    }
+    Ok(candidate)
+}
+
+/// The directory an announced release lands in under `download_dir`. The release name comes
+/// from the announce, so it must be a single file name: no separators, `.` or `..`.
+fn release_dir(download_dir: &str, release_name: &str) -> Result<PathBuf, String> {
+    let mut components = Path::new(release_name).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(name)), None) if !release_name.contains(['/', '\\']) => Ok(Path::new(download_dir).join(name)),
+        _ => Err(format!("Invalid release name '{}'", release_name)),
+    }
+}
+
+async fn require_token<B>(State(state): State<ApiState>, request: Request<B>, next: Next<B>) -> Response {
+    let token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    if !token_matches(token, &state.settings.get().token) {
+        return error_response(StatusCode::UNAUTHORIZED, "Missing or invalid token");
+    }
+    next.run(request).await
+}
+
+/// Compares a client's token with the configured one without bailing out at the first wrong
+/// byte, so response times do not reveal how much of a guess was right.
+pub(crate) fn token_matches(token: Option<&str>, expected: &str) -> bool {
+    let Some(token) = token else { return false };
+    token.len() == expected.len() && token.bytes().zip(expected.bytes()).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// `GET /metrics` - unauthenticated Prometheus metrics of every upload run with this work_dir.
+async fn metrics() -> Response {
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], crate::metrics::render()).into_response()
+}
+
+/// `GET /health` - unauthenticated liveness check.
+async fn health() -> Response {
+    Json(serde_json::json!({
+        "status": "ok",
+        "version": env!("CARGO_PKG_VERSION"),
+    }))
+    .into_response()
+}
+
+/// `POST /preflight` `{"path": "/abs/path"}` - runs a pre-flight check and returns its results.
+async fn preflight(State(state): State<ApiState>, Json(request): Json<PreflightRequest>) -> Response {
+    let path = match resolve_path(state.settings.get().root.as_deref(), &request.path) {
+        Ok(path) => path,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, e),
+    };
+    match state.jobs.run_preflight(&path).await {
+        Ok(result) => Json(result).into_response(),
+        Err(e) => error_response(StatusCode::UNPROCESSABLE_ENTITY, e),
+    }
+}
+
+/// `POST /uploads` `{"path": "/abs/path", "trackers": ["SP"], "overrides": {...}}` - starts an upload.
+async fn start_upload(State(state): State<ApiState>, Json(request): Json<UploadRequest>) -> Response {
+    let path = match resolve_path(state.settings.get().root.as_deref(), &request.path) {
+        Ok(path) => path,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, e),
+    };
+    match state.jobs.start_upload(&path, &request.trackers, &request.overrides) {
+        Ok(id) => (
+            StatusCode::ACCEPTED,
+            Json(serde_json::json!({ "id": id, "url": format!("/uploads/{}", id) })),
+        )
+            .into_response(),
+        Err(e) => error_response(StatusCode::BAD_REQUEST, e),
+    }
+}
+
+/// `POST /webhook/autobrr` - runs the pre-flight check on an announced release and starts the
+/// upload when it passes. Answers `202` with the upload id, or `409` with the reason it was
+/// skipped (a dupe, or worse than what the tracker has), so autobrr can act on the status.
+async fn autobrr_webhook(State(state): State<ApiState>, Json(request): Json<WebhookRequest>) -> Response {
+    let (root, webhook) = {
+        let settings = state.settings.get();
+        (settings.root.clone(), settings.webhook.clone())
+    };
+    let path = match (&request.path, &webhook.download_dir) {
+        (Some(path), _) => path.clone(),
+        (None, Some(dir)) => match release_dir(dir, &request.release_name) {
+            Ok(path) => path.to_string_lossy().to_string(),
+            Err(e) => return error_response(StatusCode::BAD_REQUEST, e),
+        },
+        (None, None) => return error_response(StatusCode::BAD_REQUEST, "No path given and no webhook download_dir configured"),
+    };
+    let path = match resolve_path(root.as_deref(), &path) {
+        Ok(path) => path,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, e),
+    };
+    info!("Webhook for '{}' at {}", request.release_name, path.display());
+
+    let skip = |reason: String| {
+        info!("Skipping '{}': {}", request.release_name, reason);
+        (StatusCode::CONFLICT, Json(serde_json::json!({ "decision": "skip", "reason": reason }))).into_response()
+    };
+    let result: WebhookPreflight = match state.jobs.run_preflight_json(&path).await {
+        Ok(result) => result,
+        Err(e) => return error_response(StatusCode::UNPROCESSABLE_ENTITY, e),
+    };
+    if result.dupe {
+        return skip(format!("Dupe check: {}", result.dupe_check));
+    }
+    if webhook.skip_lower_quality && result.trump == Some(Verdict::LowerQuality) {
+        return skip(format!("Trump check: {}", result.trump_check));
+    }
+    let dupe_check = result.dupe_check;
+
+    let trackers = if request.trackers.is_empty() { &webhook.trackers } else { &request.trackers };
+    match state.jobs.start_upload(&path, trackers, &UploadOverrides::default()) {
+        Ok(id) => (
+            StatusCode::ACCEPTED,
+            Json(serde_json::json!({ "decision": "upload", "id": id, "url": format!("/uploads/{}", id), "dupe_check": dupe_check })),
+        )
+            .into_response(),
+        Err(e) => error_response(StatusCode::BAD_REQUEST, e),
+    }
+}
+
+/// `GET /uploads` - every upload started since the server came up.
+async fn list_uploads(State(state): State<ApiState>) -> Response {
+    Json(state.jobs.list()).into_response()
+}
+
+/// `GET /uploads/:id` - status, exit code and output of one upload.
+async fn get_upload(State(state): State<ApiState>, UrlPath(id): UrlPath<u64>) -> Response {
+    match state.jobs.get(id) {
+        Some(job) => Json(job).into_response(),
+        None => error_response(StatusCode::NOT_FOUND, format!("No upload with id {}", id)),
+    }
+}
+
+/// Runs the `--api` server until the process is stopped. Changes to `config.yaml` in
+/// `config_dir` are applied while it runs, except for the bind address.
+pub async fn launch_api(config: &Config, config_dir: &Path) -> Result<(), String> {
+    let api = config.api.as_ref().ok_or("The --api mode requires an `api` section in config.yaml")?;
+    let addr: SocketAddr = api
+        .bind
+        .parse()
+        .map_err(|e| format!("Invalid api bind address '{}': {}", api.bind, e))?;
+
+    let state = ApiState {
+        settings: Arc::new(Live::new(ApiSettings::new(api)?)),
+        jobs: JobManager::new(&config.maintenance)?,
+    };
+    let (settings, jobs, bind) = (state.settings.clone(), state.jobs.clone(), api.bind.clone());
+    let reloaded = watch_config_dir(config_dir, move |config| {
+        jobs.set_maintenance(&config.maintenance);
+        let Some(api) = &config.api else {
+            warn!("The reloaded config.yaml has no `api` section; keeping the running API settings");
+            return;
+        };
+        if api.bind != bind {
+            warn!("The API bind address changed to {}; restart seed-tools to listen there", api.bind);
+        }
+        match ApiSettings::new(api) {
+            Ok(new_settings) => settings.replace(new_settings),
+            Err(e) => warn!("Keeping the running API settings: {}", e),
+        }
+    });
+    if let Err(e) = reloaded {
+        warn!("{}; config changes need a restart", e);
+    }
+
+    let authenticated = Router::new()
+        .route("/preflight", post(preflight))
+        .route("/uploads", get(list_uploads).post(start_upload))
+        .route("/uploads/:id", get(get_upload))
+        .route("/webhook/autobrr", post(autobrr_webhook))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_token));
+    let app = Router::new()
+        .route("/health", get(health))
+        .route("/metrics", get(metrics))
+        .merge(authenticated)
+        .with_state(state);
+
+    info!("API listening on http://{}", addr);
+    println!("API listening on http://{}", addr);
+    axum::Server::bind(&addr)
+        .serve(app.into_make_service())
+        .await
+        .map_err(|e| format!("API server error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_only_the_exact_token() {
+        assert!(token_matches(Some("change-me"), "change-me"));
+        assert!(!token_matches(Some("change-mE"), "change-me"));
+        assert!(!token_matches(Some("change"), "change-me"));
+        assert!(!token_matches(Some("change-me-too"), "change-me"));
+        assert!(!token_matches(Some(""), "change-me"));
+        assert!(!token_matches(None, "change-me"));
+    }
+
+    #[test]
+    fn release_names_stay_in_the_download_dir() {
+        assert_eq!(release_dir("/data", "Movie.2020.1080p-GRP").unwrap(), Path::new("/data/Movie.2020.1080p-GRP"));
+        for name in ["..", ".", "", "../etc", "../../etc/passwd", "a/b", "a\\..\\..", "/etc"] {
+            assert!(release_dir("/data", name).is_err(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn webhook_reads_the_structured_preflight_fields() {
+        let result: WebhookPreflight = serde_json::from_str(
+            r#"{"dupe_check": "⚠️ PASS, near duplicates: X", "dupe": false, "trump_check": "⚠️ Lower quality than Y (resolution)", "trump": "lower_quality", "tmdb_id": 1}"#,
+        )
+        .unwrap();
+        assert!(!result.dupe);
+        assert_eq!(result.trump, Some(Verdict::LowerQuality));
+    }
+}
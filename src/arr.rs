@@ -0,0 +1,138 @@
+use std::path::Path;
+use log::{info, warn};
+use serde_json::{json, Value};
+use crate::types::{to_client_path, ArrConfig, ArrInstance};
+
+// Radarr and Sonarr integration: a release they already manage takes its TMDB, IMDb and TVDB
+// IDs from them instead of a title search, and after an upload they are asked to rescan the
+// item (or import the release when they do not know it yet).
+
+/// Which app manages a release: Radarr for movies, Sonarr for series.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArrKind {
+    Radarr,
+    Sonarr,
+}
+
+impl ArrKind {
+    /// Sonarr for TV episodes, season packs and boxsets, Radarr for everything else.
+    pub fn for_release_type(release_type: &str) -> ArrKind {
+        match release_type {
+            "tv" | "boxset" => ArrKind::Sonarr,
+            _ => ArrKind::Radarr,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            ArrKind::Radarr => "Radarr",
+            ArrKind::Sonarr => "Sonarr",
+        }
+    }
+
+    fn instance<'a>(&self, config: &'a ArrConfig) -> Option<&'a ArrInstance> {
+        match self {
+            ArrKind::Radarr => config.radarr.as_ref(),
+            ArrKind::Sonarr => config.sonarr.as_ref(),
+        }
+    }
+
+    /// The API collection of managed items.
+    fn items(&self) -> &'static str {
+        match self {
+            ArrKind::Radarr => "movie",
+            ArrKind::Sonarr => "series",
+        }
+    }
+}
+
+/// The IDs Radarr or Sonarr has for a release.
+#[derive(Debug, Clone, Default)]
+pub struct ArrIds {
+    pub tmdb_id: Option<u32>,
+    pub imdb_id: Option<String>, // Without "tt"
+    pub tvdb_id: Option<u32>,
+}
+
+fn get(kind: ArrKind, instance: &ArrInstance, path: &str) -> Result<Value, String> {
+    let url = format!("{}/api/v3/{}", instance.url.trim_end_matches('/'), path);
+    let response = crate::http::client()
+        .get(&url)
+        .header("X-Api-Key", &instance.api_key)
+        .send()
+        .map_err(|e| format!("Failed to reach {}: {}", kind.name(), e))?;
+    if !response.status().is_success() {
+        return Err(format!("{} answered {} for {}", kind.name(), response.status(), path));
+    }
+    response.json().map_err(|e| format!("Failed to parse {} response: {}", kind.name(), e))
+}
+
+/// The managed item whose folder holds `input_path`, as the app sees the path.
+fn find_item(kind: ArrKind, instance: &ArrInstance, input_path: &str) -> Result<Option<Value>, String> {
+    let app_path = to_client_path(&instance.path_mappings, input_path);
+    let items = get(kind, instance, kind.items())?;
+    Ok(items
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|item| item["path"].as_str().is_some_and(|folder| Path::new(&app_path).starts_with(folder)))
+        .cloned())
+}
+
+/// The IDs of `input_path` when the Radarr or Sonarr for `release_type` manages it. Failures
+/// are logged and give `None`, so the usual TMDB search takes over.
+pub fn lookup_ids(config: &ArrConfig, input_path: &str, release_type: &str) -> Option<ArrIds> {
+    let kind = ArrKind::for_release_type(release_type);
+    let instance = kind.instance(config).filter(|instance| instance.resolve_ids)?;
+    let item = match find_item(kind, instance, input_path) {
+        Ok(Some(item)) => item,
+        Ok(None) => {
+            info!("'{}' is not managed by {}.", input_path, kind.name());
+            return None;
+        }
+        Err(e) => {
+            warn!("{}", e);
+            return None;
+        }
+    };
+    let id = |field: &str| item[field].as_u64().filter(|id| *id != 0).map(|id| id as u32);
+    let ids = ArrIds {
+        tmdb_id: id("tmdbId"),
+        imdb_id: item["imdbId"].as_str().filter(|id| !id.is_empty()).map(|id| id.trim_start_matches("tt").to_string()),
+        tvdb_id: id("tvdbId"),
+    };
+    info!("{} has '{}' as {}: {:?}", kind.name(), input_path, item["title"].as_str().unwrap_or_default(), ids);
+    Some(ids)
+}
+
+/// Tells the Radarr or Sonarr for `release_type` about an uploaded release: a rescan of the
+/// item when it manages the path, otherwise an import of the path with `import_mode` (Copy by
+/// default, so the seeded files stay where they are). Failures are logged only.
+pub fn notify_uploaded(config: &ArrConfig, input_path: &str, release_type: &str) {
+    let kind = ArrKind::for_release_type(release_type);
+    let Some(instance) = kind.instance(config).filter(|instance| instance.notify_after_upload) else { return };
+    let command = match find_item(kind, instance, input_path) {
+        Ok(Some(item)) => match kind {
+            ArrKind::Radarr => json!({ "name": "RescanMovie", "movieId": item["id"] }),
+            ArrKind::Sonarr => json!({ "name": "RescanSeries", "seriesId": item["id"] }),
+        },
+        Ok(None) => {
+            let name = match kind {
+                ArrKind::Radarr => "DownloadedMoviesScan",
+                ArrKind::Sonarr => "DownloadedEpisodesScan",
+            };
+            json!({ "name": name, "path": to_client_path(&instance.path_mappings, input_path), "importMode": instance.import_mode })
+        }
+        Err(e) => {
+            warn!("{}", e);
+            return;
+        }
+    };
+    let name = command["name"].as_str().unwrap_or_default();
+    let url = format!("{}/api/v3/command", instance.url.trim_end_matches('/'));
+    match crate::http::client().post(&url).header("X-Api-Key", &instance.api_key).json(&command).send() {
+        Ok(response) if response.status().is_success() => info!("Sent {} to {} for '{}'", name, kind.name(), input_path),
+        Ok(response) => warn!("{} refused {}: HTTP {}", kind.name(), name, response.status()),
+        Err(e) => warn!("Failed to reach {}: {}", kind.name(), e),
+    }
+}
@@ -0,0 +1,442 @@
+use regex::Regex;
+use crate::types::BbcodeDialect;
+
+/// A piece of a BBCode document: plain text or a tag.
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Text(String),
+    Open { name: String, arg: Option<String>, attrs: String }, // [url=...], [img width=720]
+    Close(String),
+}
+
+// Anything else in brackets is kept as text
+const TAGS: [&str; 17] = [
+    "b", "i", "u", "s", "center", "color", "size", "url", "img", "spoiler", "code", "quote", "table", "tr", "td", "list", "*",
+];
+
+fn tokenize(bbcode: &str) -> Vec<Token> {
+    let tag = Regex::new(r"\[(/?)([a-zA-Z]+|\*)(?:=([^\]]*))?((?:\s[^\]]*)?)\]").unwrap();
+    let mut tokens = Vec::new();
+    let mut last = 0;
+    for captures in tag.captures_iter(bbcode) {
+        let found = captures.get(0).unwrap();
+        let name = captures[2].to_lowercase();
+        if !TAGS.contains(&name.as_str()) {
+            continue;
+        }
+        if found.start() > last {
+            tokens.push(Token::Text(bbcode[last..found.start()].to_string()));
+        }
+        tokens.push(if &captures[1] == "/" {
+            Token::Close(name)
+        } else {
+            Token::Open {
+                name,
+                arg: captures.get(3).map(|arg| arg.as_str().trim_matches(['"', '\'']).to_string()),
+                attrs: captures[4].trim().to_string(),
+            }
+        });
+        last = found.end();
+    }
+    if last < bbcode.len() {
+        tokens.push(Token::Text(bbcode[last..].to_string()));
+    }
+    tokens
+}
+
+/// The text up to the `[/name]` closing the tag opened before `tokens[start]`, for tags whose
+/// content is a URL. Returns it and the index after the closing tag.
+fn raw_content(tokens: &[Token], start: usize, name: &str) -> (String, usize) {
+    let mut content = String::new();
+    for (i, token) in tokens.iter().enumerate().skip(start) {
+        match token {
+            Token::Close(close) if close == name => return (content, i + 1),
+            Token::Text(text) => content.push_str(text),
+            _ => {}
+        }
+    }
+    (content, tokens.len())
+}
+
+/// An opening tag as BBCode again.
+fn open_tag(name: &str, arg: &Option<String>, attrs: &str) -> String {
+    let mut tag = format!("[{}", name);
+    if let Some(arg) = arg {
+        tag.push_str(&format!("={}", arg));
+    }
+    if !attrs.is_empty() {
+        tag.push_str(&format!(" {}", attrs));
+    }
+    tag.push(']');
+    tag
+}
+
+/// Rewrites `bbcode` for a tracker whose BBCode is `dialect`. Tags it does not render are
+/// replaced by ones it does: spoilers by a bold heading, tables by a list with an item per
+/// row, lists by "• " lines and sized images by plain ones. Size, color and center are dropped,
+/// keeping their content.
+pub fn degrade(bbcode: &str, dialect: &BbcodeDialect) -> String {
+    let mut output = String::new();
+    for token in tokenize(bbcode) {
+        match token {
+            Token::Text(text) => output.push_str(&text),
+            Token::Open { name, arg, attrs } => match name.as_str() {
+                "spoiler" if !dialect.spoiler => output.push_str(&format!("[b]{}[/b]\n", arg.as_deref().unwrap_or("Spoiler"))),
+                "table" if !dialect.table && dialect.list => output.push_str("[list]"),
+                "tr" if !dialect.table && dialect.list => output.push_str("[*]"),
+                "table" | "td" if !dialect.table => {}
+                "tr" if !dialect.table => output.push_str("\n• "),
+                "img" if !dialect.img_width => output.push_str("[img]"),
+                "size" if !dialect.size => {}
+                "color" if !dialect.color => {}
+                "center" if !dialect.center => {}
+                "list" if !dialect.list => {}
+                "*" if !dialect.list => output.push_str("\n• "),
+                _ => output.push_str(&open_tag(&name, &arg, &attrs)),
+            },
+            Token::Close(name) => match name.as_str() {
+                "spoiler" if !dialect.spoiler => output.push('\n'),
+                "table" if !dialect.table && dialect.list => output.push_str("[/list]"),
+                "td" if !dialect.table => output.push(' '),
+                "table" | "tr" if !dialect.table => {}
+                "size" if !dialect.size => {}
+                "color" if !dialect.color => {}
+                "center" if !dialect.center => {}
+                "list" if !dialect.list => output.push('\n'),
+                _ => output.push_str(&format!("[/{}]", name)),
+            },
+        }
+    }
+    output
+}
+
+/// Builds a description for one tracker. Everything is written in full BBCode and `build`
+/// degrades what the tracker's dialect lacks, so the layout code does not branch on it.
+pub struct Builder<'a> {
+    dialect: &'a BbcodeDialect,
+    bbcode: String,
+}
+
+impl<'a> Builder<'a> {
+    pub fn new(dialect: &'a BbcodeDialect) -> Self {
+        Builder { dialect, bbcode: String::new() }
+    }
+
+    /// BBCode or text as is, e.g. a user's custom description.
+    pub fn raw(&mut self, bbcode: &str) -> &mut Self {
+        self.bbcode.push_str(bbcode);
+        self
+    }
+
+    pub fn bold(&mut self, bbcode: &str) -> &mut Self {
+        self.raw(&format!("[b]{}[/b]", bbcode))
+    }
+
+    pub fn centered(&mut self, bbcode: &str) -> &mut Self {
+        self.raw(&format!("[center]{}[/center]", bbcode))
+    }
+
+    /// An image, `width` pixels wide where the dialect allows it.
+    pub fn image(&mut self, url: &str, width: Option<u32>) -> &mut Self {
+        match width {
+            Some(width) => self.raw(&format!("[img width={}]{}[/img]", width, url)),
+            None => self.raw(&format!("[img]{}[/img]", url)),
+        }
+    }
+
+    pub fn link(&mut self, url: &str, label: &str) -> &mut Self {
+        self.raw(&format!("[url={}]{}[/url]", url, label))
+    }
+
+    pub fn spoiler(&mut self, title: &str, bbcode: &str) -> &mut Self {
+        self.raw(&format!("[spoiler={}]{}[/spoiler]", title, bbcode))
+    }
+
+    /// A table of `rows`, each a list of cells in BBCode.
+    pub fn table(&mut self, rows: &[Vec<String>]) -> &mut Self {
+        self.raw("[table]\n");
+        for row in rows {
+            let cells: String = row.iter().map(|cell| format!("[td]{}[/td]", cell)).collect();
+            self.raw(&format!("[tr]{}[/tr]\n", cells));
+        }
+        self.raw("[/table]\n")
+    }
+
+    pub fn list(&mut self, items: &[String]) -> &mut Self {
+        self.raw("[list]\n");
+        for item in items {
+            self.raw(&format!("[*] {}\n", item));
+        }
+        self.raw("[/list]\n")
+    }
+
+    /// Ends the current paragraph with a blank line.
+    pub fn paragraph(&mut self) -> &mut Self {
+        self.raw("\n\n")
+    }
+
+    pub fn build(&self) -> String {
+        degrade(&self.bbcode, self.dialect)
+    }
+}
+
+fn ansi_color(color: &str) -> Option<String> {
+    let named = match color.to_lowercase().as_str() {
+        "red" => Some("31"),
+        "green" => Some("32"),
+        "yellow" | "orange" | "gold" => Some("33"),
+        "blue" => Some("34"),
+        "magenta" | "purple" | "pink" => Some("35"),
+        "cyan" => Some("36"),
+        "white" => Some("37"),
+        "gray" | "grey" => Some("90"),
+        _ => None,
+    };
+    if let Some(code) = named {
+        return Some(code.to_string());
+    }
+    let hex = color.strip_prefix('#').filter(|hex| hex.len() == 6 && hex.is_ascii())?;
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some(format!("38;2;{};{};{}", channel(0)?, channel(2)?, channel(4)?))
+}
+
+/// Renders BBCode for a terminal with ANSI colors. Images and links are shown by their URL.
+pub fn render_ansi(bbcode: &str) -> String {
+    let tokens = tokenize(bbcode);
+    let mut output = String::new();
+    let mut styles: Vec<(String, String)> = Vec::new(); // Open tag and its SGR code
+    let restyle = |output: &mut String, styles: &[(String, String)]| {
+        output.push_str("\x1b[0m");
+        for (_, code) in styles {
+            output.push_str(&format!("\x1b[{}m", code));
+        }
+    };
+
+    let mut i = 0;
+    while i < tokens.len() {
+        match &tokens[i] {
+            Token::Text(text) => output.push_str(text),
+            Token::Open { name, arg, .. } => {
+                let code = match name.as_str() {
+                    "b" => Some("1".to_string()),
+                    "i" | "quote" => Some("3".to_string()),
+                    "u" => Some("4".to_string()),
+                    "s" => Some("9".to_string()),
+                    "code" => Some("2".to_string()),
+                    "color" => arg.as_deref().and_then(ansi_color),
+                    "url" if arg.is_some() => Some("4;36".to_string()),
+                    _ => None,
+                };
+                match name.as_str() {
+                    "img" => {
+                        let (url, next) = raw_content(&tokens, i + 1, "img");
+                        output.push_str(&format!("\x1b[36m[image: {}]", url.trim()));
+                        restyle(&mut output, &styles);
+                        i = next;
+                        continue;
+                    }
+                    "url" if arg.is_none() => {
+                        let (url, next) = raw_content(&tokens, i + 1, "url");
+                        output.push_str(&format!("\x1b[4;36m{}", url.trim()));
+                        restyle(&mut output, &styles);
+                        i = next;
+                        continue;
+                    }
+                    "spoiler" => output.push_str(&format!("\x1b[1m▼ {}\x1b[0m\n", arg.as_deref().unwrap_or("Spoiler"))),
+                    "*" => output.push_str("\n  • "),
+                    _ => {}
+                }
+                if let Some(code) = code {
+                    styles.push((name.clone(), code));
+                    restyle(&mut output, &styles);
+                }
+            }
+            Token::Close(name) => {
+                if let Some(position) = styles.iter().rposition(|(open, _)| open == name) {
+                    styles.remove(position);
+                    restyle(&mut output, &styles);
+                }
+                match name.as_str() {
+                    "td" => output.push_str("  "),
+                    "tr" | "spoiler" | "list" => output.push('\n'),
+                    _ => {}
+                }
+            }
+        }
+        i += 1;
+    }
+    output.push_str("\x1b[0m");
+    output
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Local paths become `file://` URLs so a browser shows screenshots that were not uploaded.
+fn html_url(url: &str) -> String {
+    let url = url.trim();
+    if url.starts_with('/') {
+        escape_html(&format!("file://{}", url))
+    } else {
+        escape_html(url)
+    }
+}
+
+/// Renders BBCode as a standalone HTML page titled `title`.
+pub fn render_html(bbcode: &str, title: &str) -> String {
+    let tokens = tokenize(bbcode);
+    let width = Regex::new(r"(?i)\bwidth=(\d+)").unwrap();
+    let mut body = String::new();
+    let mut in_code: usize = 0;
+
+    let mut i = 0;
+    while i < tokens.len() {
+        match &tokens[i] {
+            Token::Text(text) if in_code > 0 => body.push_str(&escape_html(text)),
+            Token::Text(text) => body.push_str(&escape_html(text).replace('\n', "<br>\n")),
+            Token::Open { name, arg, attrs } => {
+                let arg = arg.as_deref().unwrap_or("");
+                let safe_arg: String = arg.chars().filter(|c| c.is_ascii_alphanumeric() || *c == '#').collect();
+                match name.as_str() {
+                    "b" | "i" | "u" | "s" | "table" | "tr" | "td" => body.push_str(&format!("<{}>", name)),
+                    "center" => body.push_str("<div style=\"text-align:center\">"),
+                    "color" => body.push_str(&format!("<span style=\"color:{}\">", safe_arg)),
+                    "size" => {
+                        // Sizes 1-7 follow the old HTML font sizes; anything larger is in pixels
+                        let size = match safe_arg.parse::<usize>().unwrap_or(3) {
+                            size @ 0..=7 => [10, 10, 13, 16, 18, 24, 32, 48][size],
+                            size => size,
+                        };
+                        body.push_str(&format!("<span style=\"font-size:{}px\">", size));
+                    }
+                    "url" if !arg.is_empty() => body.push_str(&format!("<a href=\"{}\">", html_url(arg))),
+                    "url" => {
+                        let (url, next) = raw_content(&tokens, i + 1, "url");
+                        body.push_str(&format!("<a href=\"{0}\">{0}</a>", html_url(&url)));
+                        i = next;
+                        continue;
+                    }
+                    "img" => {
+                        let (url, next) = raw_content(&tokens, i + 1, "img");
+                        let width = width.captures(attrs).or_else(|| width.captures(arg)).map(|captures| captures[1].to_string());
+                        body.push_str(&format!(
+                            "<img src=\"{}\"{}>",
+                            html_url(&url),
+                            width.map(|width| format!(" width=\"{}\"", width)).unwrap_or_default()
+                        ));
+                        i = next;
+                        continue;
+                    }
+                    "spoiler" => body.push_str(&format!(
+                        "<details><summary>{}</summary>",
+                        escape_html(if arg.is_empty() { "Spoiler" } else { arg })
+                    )),
+                    "code" => {
+                        in_code += 1;
+                        body.push_str("<pre>");
+                    }
+                    "quote" => body.push_str("<blockquote>"),
+                    "list" => body.push_str("<ul>"),
+                    "*" => body.push_str("<li>"),
+                    _ => {}
+                }
+            }
+            Token::Close(name) => match name.as_str() {
+                "b" | "i" | "u" | "s" | "table" | "tr" | "td" => body.push_str(&format!("</{}>", name)),
+                "center" => body.push_str("</div>"),
+                "color" | "size" => body.push_str("</span>"),
+                "url" => body.push_str("</a>"),
+                "spoiler" => body.push_str("</details>"),
+                "code" => {
+                    in_code = in_code.saturating_sub(1);
+                    body.push_str("</pre>");
+                }
+                "quote" => body.push_str("</blockquote>"),
+                "list" => body.push_str("</ul>"),
+                _ => {}
+            },
+        }
+        i += 1;
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n\
+         body {{ background: #1c1f24; color: #d8dde3; font-family: sans-serif; max-width: 1200px; margin: 2em auto; }}\n\
+         a {{ color: #6cb4ff; }} img {{ max-width: 100%; }} td {{ padding: 4px; }}\n\
+         details {{ border: 1px solid #444; padding: 0.5em; margin: 0.5em 0; }} pre {{ white-space: pre-wrap; }}\n\
+         </style>\n</head>\n<body>\n{}\n</body>\n</html>\n",
+        escape_html(title),
+        body
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_everything_a_full_dialect_renders() {
+        let bbcode = "[Release] [b]Name[/b] [img width=720]https://img.example/a.png[/img] [url=https://example.org]Link[/url]";
+        assert_eq!(degrade(bbcode, &BbcodeDialect::default()), bbcode);
+    }
+
+    #[test]
+    fn degrades_tags_the_dialect_lacks() {
+        let dialect = BbcodeDialect { spoiler: false, table: false, img_width: false, size: false, color: false, center: false, list: true };
+        let description = Builder::new(&dialect)
+            .centered("[size=4][color=#ff0000]Title[/color][/size]")
+            .paragraph()
+            .image("https://img.example/a.png", Some(720))
+            .paragraph()
+            .spoiler("MediaInfo", "General")
+            .table(&[vec!["Video".to_string(), "H.264".to_string()]])
+            .build();
+        assert_eq!(
+            description,
+            "Title\n\n[img]https://img.example/a.png[/img]\n\n[b]MediaInfo[/b]\nGeneral\n[list]\n[*]Video H.264 \n[/list]\n"
+        );
+    }
+
+    #[test]
+    fn degrades_tables_and_lists_to_bullet_lines() {
+        let dialect = BbcodeDialect { table: false, list: false, ..Default::default() };
+        let description = Builder::new(&dialect)
+            .table(&[vec!["Video".to_string()], vec!["Audio".to_string()]])
+            .list(&["English".to_string(), "French".to_string()])
+            .build();
+        assert!(!description.contains('['), "{}", description);
+        let lines: Vec<&str> = description.lines().map(str::trim).filter(|line| !line.is_empty()).collect();
+        assert_eq!(lines, ["• Video", "• Audio", "•  English", "•  French"]);
+    }
+
+    #[test]
+    fn renders_ansi() {
+        assert_eq!(
+            render_ansi("[b]Bold[/b] [color=red]Red[/color] [img]https://img.example/a.png[/img]"),
+            "\x1b[0m\x1b[1mBold\x1b[0m \x1b[0m\x1b[31mRed\x1b[0m \x1b[36m[image: https://img.example/a.png]\x1b[0m\x1b[0m"
+        );
+        // Closing an outer tag keeps the styles still open
+        assert_eq!(render_ansi("[b]a[u]b[/b]c[/u]"), "\x1b[0m\x1b[1ma\x1b[0m\x1b[1m\x1b[4mb\x1b[0m\x1b[4mc\x1b[0m\x1b[0m");
+        assert_eq!(ansi_color("#ff8000").as_deref(), Some("38;2;255;128;0"));
+        assert_eq!(ansi_color("Grey").as_deref(), Some("90"));
+        assert_eq!(ansi_color("#ff80"), None);
+    }
+
+    #[test]
+    fn renders_escaped_html() {
+        let html = render_html(
+            "[b]<Tom & Jerry>[/b]\n[url]https://example.org/?a=1&b=2[/url] [img width=720]/tmp/shot.png[/img] \
+             [spoiler]Hidden[/spoiler][code]a\nb[/code][color=red;background:url(x)]c[/color]",
+            "A & B",
+        );
+        assert!(html.contains("<title>A &amp; B</title>"));
+        assert!(html.contains("<b>&lt;Tom &amp; Jerry&gt;</b><br>\n"));
+        assert!(html.contains("<a href=\"https://example.org/?a=1&amp;b=2\">https://example.org/?a=1&amp;b=2</a>"));
+        assert!(html.contains("<img src=\"file:///tmp/shot.png\" width=\"720\">"));
+        assert!(html.contains("<details><summary>Spoiler</summary>Hidden</details>"));
+        assert!(html.contains("<pre>a\nb</pre>"));
+        // Tag arguments cannot add styles of their own
+        assert!(html.contains("<span style=\"color:redbackgroundurlx\">c</span>"));
+    }
+}
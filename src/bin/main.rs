@@ -1,72 +1,57 @@
 use std::{
     fs,
-    env,
-    path::{Path, PathBuf},
-    collections::HashMap,
+    path::PathBuf,
+    sync::Arc,
 };
-use serde::Deserialize;
-use log::{info, error, debug, LevelFilter};
-use simplelog::{Config as SimpleLogConfig, CombinedLogger, WriteLogger};
-use std::fs::File;
+use log::{info, error, debug, warn};
 use std::error::Error;
-use reqwest::blocking::Client;
 use seed_tools::utils;
 use seed_tools::utils::generate_release_name;
-use seed_tools::types::{Config, SeedpoolConfig, TorrentLeechConfig, QbittorrentConfig, DelugeConfig};
+use seed_tools::types::{Config, SeedpoolConfig, TorrentLeechConfig};
 use seed_tools::sync;
+use seed_tools::notifications::{notify, Notification};
+use seed_tools::pipeline::ReleaseCheckpoint;
+use seed_tools::metrics;
+use seed_tools::history;
+use seed_tools::orphans;
+use seed_tools::readiness;
+use seed_tools::state;
+use seed_tools::trackers::unit3d::{find_unit3d_config, load_unit3d_configs};
+use seed_tools::trackers::gazelle::{find_gazelle_config, load_gazelle_configs};
+use seed_tools::logging;
+use seed_tools::offline::Offline;
+use seed_tools::profile;
+use seed_tools::output::{self, OutputFormat};
+use seed_tools::error::SeedToolsError;
+use seed_tools::trackers::TRACKER_UNAVAILABLE_EXIT_CODE;
 use seed_tools::irc::launch_irc_client;
-use seed_tools::types::PreflightCheckResult;
-use trackers::seedpool::preflight_check;
+use seed_tools::types::{PreflightCheckResult, UploadOverrides};
+use seed_tools::estimate::format_duration;
+use seed_tools::utils::format_size;
+use seed_tools::trackers::release::{self as trackers, Target, Tools, UploadOptions};
 use seed_tools::ui;
-use tokio::main;
-mod trackers {
-    pub mod seedpool;
-    pub mod torrentleech;
-    pub mod common;
-}
-use std::fs::OpenOptions;
-use trackers::common::{process_custom_upload, sanitize_game_title, process_game_upload, Tracker};
-use clap::{Parser, CommandFactory};
-#[derive(Deserialize)]
-struct GeneralConfig {
-    pub tmdb_api_key: String,
+use trackers::common::{process_custom_upload, process_game_upload};
+use clap::{Parser, Subcommand, CommandFactory};
+fn load_yaml_config<T: serde::de::DeserializeOwned>(path: &str) -> Result<T, String> {
+    // ${ENV_VAR} and ${keyring:name} references are filled in before parsing
+    let content = seed_tools::secrets::read_config(path).map_err(|e| format!("Failed to read config file: {}", e))?;
+    serde_yaml::from_str(&content).map_err(|e| format!("Failed to parse '{}': {}", path, e))
 }
 
-fn load_yaml_config<T: serde::de::DeserializeOwned>(path: &str) -> T {
-    serde_yaml::from_str(&fs::read_to_string(path).expect("Failed to read config file"))
-        .expect("Failed to parse YAML config")
+/// What `--type` was given: a type ID override, or a release kind with its own upload pipeline.
+#[derive(Clone, Debug, PartialEq)]
+enum TypeArg {
+    Id(u32),
+    Software,
+    Adult,
 }
 
-fn extract_binaries(config_path: &str) -> Result<String, String> {
-    let config: serde_yaml::Value = serde_yaml::from_str(
-        &fs::read_to_string(config_path).map_err(|e| format!("Failed to read config file: {}", e))?,
-    )
-    .map_err(|e| format!("Failed to parse config file: {}", e))?;
-    let paths = config["paths"]
-        .as_mapping()
-        .ok_or("Missing or invalid 'paths' field in config")?;
-    let required_binaries = ["ffmpeg", "ffprobe", "mkbrr", "mediainfo"];
-    for binary in &required_binaries {
-        if !paths.contains_key(binary) {
-            return Err(format!("Missing '{}' in 'paths' field of config", binary));
-        }
-        let binary_path = paths[binary]
-            .as_str()
-            .ok_or(format!("Invalid path for '{}'", binary))?;
-        if !Path::new(binary_path).exists() {
-            return Err(format!("Binary '{}' not found at '{}'", binary, binary_path));
-        }
+fn parse_type_arg(value: &str) -> Result<TypeArg, String> {
+    match value.to_lowercase().as_str() {
+        "software" => Ok(TypeArg::Software),
+        "adult" => Ok(TypeArg::Adult),
+        _ => value.parse().map(TypeArg::Id).map_err(|_| format!("expected a type ID, 'software' or 'adult', got '{}'", value)),
     }
-    let bin_dir = Path::new(
-        paths["ffmpeg"]
-            .as_str()
-            .ok_or("Invalid path for 'ffmpeg'")?,
-    )
-    .parent()
-    .ok_or("Failed to determine bin directory")?
-    .to_string_lossy()
-    .to_string();
-    Ok(bin_dir)
 }
 
 #[derive(Parser, Debug)]
@@ -75,12 +60,28 @@ struct Cli {
     #[arg(long, conflicts_with_all = ["sp", "tl", "custom_cat_type", "command", "irc"])]
     sync: bool,
 
+    /// With --sync, list torrents whose data is gone or that the tracker deleted, and offer to remove them
+    #[arg(long, requires = "sync")]
+    prune_dead: bool,
+
+    /// Answer yes to the --sync prompts: re-seed uploads and dead torrent removal
+    #[arg(long, requires = "sync")]
+    yes: bool,
+
     #[arg(long = "SP", requires = "input_path")]
     sp: bool,
 
     #[arg(long = "TL", requires = "input_path")]
     tl: bool,
 
+    /// Upload to a UNIT3D tracker set up in config/trackers/unit3d/<NAME>.yaml; repeat for several
+    #[arg(long, value_name = "NAME", requires = "input_path", conflicts_with = "custom_cat_type")]
+    unit3d: Vec<String>,
+
+    /// Upload a music release to a Gazelle tracker set up in config/trackers/gazelle/<NAME>.yaml
+    #[arg(long, value_name = "NAME", requires = "input_path", conflicts_with = "custom_cat_type")]
+    gazelle: Vec<String>,
+
     #[arg(short = 'c', long, value_name = "CAT_TYPE", requires = "input_path")]
     custom_cat_type: Option<String>,
 
@@ -93,6 +94,123 @@ struct Cli {
     #[arg(long, conflicts_with_all = ["sync", "sp", "tl", "custom_cat_type", "command"])]
     pre: bool, // Add the `pre` argument
 
+    /// With --pre, add the existing Seedpool torrent of a dupe to the torrent clients
+    #[arg(long, requires = "pre")]
+    inject_dupes: bool,
+
+    /// Serve the web UI configured in the `web` section of config.yaml
+    #[arg(long, conflicts_with_all = ["sync", "sp", "tl", "custom_cat_type", "command", "ui", "irc", "pre", "input_path"])]
+    web: bool,
+
+    /// Serve the JSON automation API configured in the `api` section of config.yaml
+    #[arg(long, conflicts_with_all = ["sync", "sp", "tl", "custom_cat_type", "command", "ui", "irc", "pre", "web", "input_path"])]
+    api: bool,
+
+    /// Review and edit the detected upload details before uploading
+    #[arg(long, requires = "input_path", conflicts_with = "custom_cat_type")]
+    edit: bool,
+
+    /// Ignore upload checkpoints from earlier failed runs and start from scratch
+    #[arg(long, requires = "input_path")]
+    fresh: bool,
+
+    /// Upload even if the local upload history already has this release for the tracker
+    #[arg(long, requires = "input_path")]
+    allow_reupload: bool,
+
+    /// Postpone the upload (exit code 76) while the release is still being copied or downloaded;
+    /// set by the upload queue and web/API jobs
+    #[arg(long, requires = "input_path")]
+    check_ready: bool,
+
+    /// Upload each season folder (S01, Season 2, ...) of a multi-season collection as its own
+    /// season pack instead of one boxset
+    #[arg(long, requires = "input_path", conflicts_with_all = ["custom_cat_type", "season", "episode", "release_name"])]
+    per_season: bool,
+
+    /// Run the upload without contacting trackers or torrent clients: uploads are recorded in
+    /// DIR (default ./offline) and checked against the release data. Pass --tmdb and the other
+    /// IDs, as no lookups are made.
+    #[arg(long, value_name = "DIR", num_args = 0..=1, default_missing_value = "offline", requires = "input_path")]
+    offline: Option<PathBuf>,
+
+    /// Seed the screenshot timestamps so runs on the same file take the same screenshots
+    #[arg(long, value_name = "N", requires = "input_path")]
+    seed: Option<u64>,
+
+    /// Take the screenshots at these points instead of at random, e.g. 10%,30%,60%,85% or
+    /// seconds
+    #[arg(long, value_name = "LIST", value_delimiter = ',', requires = "input_path")]
+    timestamps: Vec<String>,
+
+    /// Override the generated release name
+    #[arg(long, value_name = "NAME", requires = "input_path")]
+    release_name: Option<String>,
+
+    /// Override the detected TMDB ID
+    #[arg(long, value_name = "ID", requires = "input_path")]
+    tmdb: Option<u32>,
+
+    /// Override the detected IMDb ID (e.g. tt0111161)
+    #[arg(long, value_name = "ID", requires = "input_path")]
+    imdb: Option<String>,
+
+    /// Override the detected TVDB ID
+    #[arg(long, value_name = "ID", requires = "input_path")]
+    tvdb: Option<u32>,
+
+    /// Override the detected season number
+    #[arg(long, value_name = "NUMBER", requires = "input_path")]
+    season: Option<u32>,
+
+    /// Override the detected episode number
+    #[arg(long, value_name = "NUMBER", requires = "input_path")]
+    episode: Option<u32>,
+
+    /// Override the detected category ID
+    #[arg(long, value_name = "ID", requires = "input_path", conflicts_with = "custom_cat_type")]
+    category: Option<u32>,
+
+    /// Override the detected type ID, or `software`/`adult` to upload a software or adult release
+    #[arg(long = "type", value_name = "ID", value_parser = parse_type_arg, requires = "input_path", conflicts_with = "custom_cat_type")]
+    type_id: Option<TypeArg>,
+
+    /// Warn about (or with --strict-limits stop) uploads larger than this many GiB
+    #[arg(long, value_name = "GIB", requires = "input_path")]
+    max_size: Option<f64>,
+
+    /// Warn about (or with --strict-limits stop) uploads with more files than this
+    #[arg(long, value_name = "COUNT", requires = "input_path")]
+    max_files: Option<usize>,
+
+    /// Stop the upload when it breaks a size, file count or extension limit instead of warning
+    #[arg(long, requires = "input_path")]
+    strict_limits: bool,
+
+    /// Mark the Seedpool upload freeleech: 100% or `--freeleech=50` for a percentage
+    #[arg(long, value_name = "PERCENT", num_args = 0..=1, require_equals = true, default_missing_value = "100", requires = "input_path")]
+    freeleech: Option<u8>,
+
+    /// Mark the Seedpool upload as an internal release
+    #[arg(long, requires = "input_path")]
+    internal: bool,
+
+    /// Upload to Seedpool anonymously
+    #[arg(long, requires = "input_path")]
+    anon: bool,
+
+    /// Output format of `check` and `--pre` results
+    #[arg(long, value_enum, default_value_t = OutputFormat::Table, global = true)]
+    output: OutputFormat,
+
+    /// Log level (trace, debug, info, warn, error or off); overrides `logging.level` in config.yaml
+    #[arg(long, value_name = "LEVEL")]
+    log_level: Option<String>,
+
+    /// Use the config files in config/profiles/<NAME> (defaults to $SEED_TOOLS_PROFILE)
+    #[arg(long, value_name = "NAME", global = true)]
+    profile: Option<String>,
+
     #[command(subcommand)]
     command: Option<Commands>,
 
@@ -108,21 +226,122 @@ enum Commands {
         #[arg(index = 1)]
         name: String,
     },
+    /// Show or manage the upload history
+    #[command(args_conflicts_with_subcommands = true)]
+    History {
+        #[command(subcommand)]
+        action: Option<HistoryAction>,
+        /// Only show uploads to this tracker (SP, TL or the tracker name)
+        #[arg(long, value_name = "TRACKER")]
+        tracker: Option<String>,
+        /// Only show uploads of the last e.g. 12h, 7d or 2w, or since a date like 2024-05-01
+        #[arg(long, value_name = "AGE")]
+        since: Option<String>,
+        /// Print JSON instead of a table
+        #[arg(long)]
+        json: bool,
+    },
+    /// List data that no torrent in qBittorrent or Deluge points to
+    Orphans {
+        /// Data directories to check; defaults to the clients' default save paths
+        dirs: Vec<PathBuf>,
+        /// Also write the list to this CSV file
+        #[arg(long, value_name = "FILE")]
+        csv: Option<PathBuf>,
+    },
+    /// Show the infohashes, files, trackers and flags of a .torrent file
+    Inspect {
+        /// The .torrent file to inspect
+        torrent: PathBuf,
+        /// Print JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+    /// Hash local data against a .torrent file and report how much of it matches
+    Verify {
+        /// The .torrent file to check against
+        torrent: PathBuf,
+        /// The content, or the folder it is saved in
+        path: PathBuf,
+        /// Print JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+    /// Rewrite a .torrent file's announce URLs, source tag and private flag for another tracker
+    Retag {
+        /// The .torrent file to retag
+        torrent: PathBuf,
+        /// Tracker to retag for (SP, TL or the tracker name)
+        #[arg(long, value_name = "TRACKER")]
+        tracker: String,
+        /// Where to write the new torrent; defaults to `<name>.<tracker>.torrent` next to the input
+        #[arg(long, short, value_name = "FILE")]
+        output: Option<PathBuf>,
+        /// Source tag to set instead of the tracker's default
+        #[arg(long)]
+        source: Option<String>,
+    },
+    /// Bundle the config, upload history and caches into a tarball, e.g. to move to a new seedbox
+    ExportState {
+        /// The archive to write
+        #[arg(default_value = "seed-tools-state.tar.gz")]
+        output: PathBuf,
+        /// Keep API keys, passwords and passkeys in the exported config
+        #[arg(long)]
+        include_secrets: bool,
+    },
+    /// Restore a tarball written by export-state
+    ImportState {
+        /// The archive to restore
+        archive: PathBuf,
+        /// Replace existing files (the old ones are kept as .bak)
+        #[arg(long)]
+        overwrite: bool,
+    },
+    /// Check config.yaml, the tracker configs and the external binaries
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Render the Seedpool description of a release locally, without uploading anything
+    Preview {
+        /// The release to describe
+        path: PathBuf,
+        /// Write an HTML page to a temp file instead of printing to the terminal
+        #[arg(long)]
+        html: bool,
+        /// Leave out the screenshots, which otherwise are taken with ffmpeg
+        #[arg(long)]
+        no_screenshots: bool,
+    },
+    /// Remove torrents, screenshots, samples and covers older than their retention in config.yaml
+    Clean {
+        /// List what would be removed without removing it
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigAction {
+    /// Parse every config file and report the path and version of each binary
+    Validate,
+}
+
+#[derive(Subcommand, Debug)]
+enum HistoryAction {
+    /// Add torrents that are already seeding to the history
+    Import {
+        /// Read the torrents of every configured qBittorrent instance
+        #[arg(long)]
+        from_qbittorrent: bool,
+    },
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
-    // --- Initialize Logging ---
-    let log_path = Path::new("seed-tools.log");
-    CombinedLogger::init(vec![WriteLogger::new(
-        LevelFilter::Debug,
-        SimpleLogConfig::default(),
-        OpenOptions::new()
-            .create(true) // Create the file if it doesn't exist
-            .append(true) // Append to the file instead of truncating it
-            .open(&log_path)?,
-    )])?;
-    info!("Logging initialized.");
+    // Parse CLI arguments first; `--log-level` is needed to set up logging
+    let cli = Cli::parse();
 
     // Determine the executable directory
     let exe_dir = std::env::current_exe()
@@ -130,11 +349,23 @@ async fn main() -> Result<(), Box<dyn Error>> {
         .parent()
         .ok_or("Failed to determine executable directory")?
         .to_path_buf();
-    info!("Executable directory determined as: {:?}", exe_dir);
+    profile::init(cli.profile.as_deref());
+    profile::check(&exe_dir.join("config"))?;
 
-    // Parse CLI arguments
-    info!("Parsing arguments...");
-    let cli = Cli::parse();
+    // --- Initialize Logging ---
+    // Uploads also get their own log file, named after the release
+    let logging_config = logging::load_config(&profile::config_path(&exe_dir.join("config"), "config.yaml"));
+    let run_name = cli
+        .input_path
+        .as_ref()
+        .and_then(|path| path.file_name())
+        .map(|name| name.to_string_lossy().to_string());
+    let run_log = logging::init(&logging_config, cli.log_level.as_deref(), run_name.as_deref())?;
+    info!("Logging initialized.");
+    if let Some(run_log) = run_log {
+        info!("Logging this run to {}", run_log.display());
+    }
+    info!("Executable directory determined as: {:?}", exe_dir);
     debug!("Parsed arguments: {:?}", cli);
 
     // --- Handle IRC Mode ---
@@ -144,7 +375,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
     }    
 
     // --- Handle UI Mode (Default) ---
-    if cli.ui || (cli.command.is_none() && cli.input_path.is_none() && !cli.sync && !cli.pre) {
+    if cli.ui || (cli.command.is_none() && cli.input_path.is_none() && !cli.sync && !cli.pre && !cli.web && !cli.api) {
         info!("Launching UI mode...");
         return ui::launch_ui();
     }
@@ -152,72 +383,147 @@ async fn main() -> Result<(), Box<dyn Error>> {
     // --- Build Configuration Paths ---
     info!("Building configuration paths...");
     let config_dir = exe_dir.join("config");
-    let main_config_path = config_dir.join("config.yaml");
-    let seedpool_config_path = config_dir.join("trackers/seedpool.yaml");
-    let torrentleech_config_path = config_dir.join("trackers/torrentleech.yaml");
+    if let Some(profile) = profile::name() {
+        info!("Using profile '{}'", profile);
+    }
+    // Files the profile does not have come from the config directory itself
+    let main_config_path = profile::config_path(&config_dir, "config.yaml");
+    let seedpool_config_path = profile::config_path(&config_dir, "trackers/seedpool.yaml");
+    let torrentleech_config_path = profile::config_path(&config_dir, "trackers/torrentleech.yaml");
     info!("Configuration paths built.");
 
     // --- Load Configurations ---
     info!("Loading configurations...");
     let main_config_path_str = main_config_path.to_str()
         .ok_or_else(|| format!("Invalid non-UTF8 path for main config: {:?}", main_config_path))?;
-    let binaries_dir = extract_binaries(main_config_path_str).unwrap_or_else(|e| {
-        error!("Failed to extract binaries using config {:?}: {}", main_config_path, e);
-        std::process::exit(1);
-    });
-
-    let ffmpeg_path = Path::new(&binaries_dir).join("ffmpeg");
-    let ffprobe_path = Path::new(&binaries_dir).join("ffprobe");
-    let mkbrr_path = Path::new(&binaries_dir).join("mkbrr");
-    let mediainfo_path = Path::new(&binaries_dir).join("mediainfo");
-    debug!(
-        "Binary paths: ffmpeg={:?}, ffprobe={:?}, mkbrr={:?}, mediainfo={:?}",
-        ffmpeg_path, ffprobe_path, mkbrr_path, mediainfo_path
-    );
-
     let seedpool_config_path_str = seedpool_config_path.to_str()
         .ok_or_else(|| format!("Invalid non-UTF8 path for seedpool config: {:?}", seedpool_config_path))?;
     let torrentleech_config_path_str = torrentleech_config_path.to_str()
         .ok_or_else(|| format!("Invalid non-UTF8 path for torrentleech config: {:?}", torrentleech_config_path))?;
 
-    let mut main_config: Config = load_yaml_config::<Config>(main_config_path_str);
-    let seedpool_config: SeedpoolConfig = load_yaml_config(seedpool_config_path_str);
-    let torrentleech_config: TorrentLeechConfig = load_yaml_config(torrentleech_config_path_str);
+    let validating = matches!(cli.command, Some(Commands::Config { .. }));
+    let loaded = (
+        load_yaml_config::<Config>(main_config_path_str),
+        load_yaml_config::<SeedpoolConfig>(seedpool_config_path_str),
+        load_yaml_config::<TorrentLeechConfig>(torrentleech_config_path_str),
+    );
+    let (mut main_config, mut seedpool_config, torrentleech_config) = match loaded {
+        (Ok(main_config), Ok(seedpool_config), Ok(torrentleech_config)) => (main_config, seedpool_config, torrentleech_config),
+        (main_config, seedpool_config, torrentleech_config) => {
+            let errors: Vec<String> = [main_config.err(), seedpool_config.err(), torrentleech_config.err()].into_iter().flatten().collect();
+            if !validating {
+                return Err(errors.join("; ").into());
+            }
+            // `config validate` reports every file that does not load instead of stopping at the first
+            println!("Config: {}", main_config_path.display());
+            for error in &errors {
+                println!("  FAIL {}", error);
+            }
+            println!("{} problem(s) found.", errors.len());
+            std::process::exit(1);
+        }
+    };
+    // Upload flags from the command line add to the ones in seedpool.yaml
+    if let Some(percent) = cli.freeleech {
+        seedpool_config.upload_flags.freeleech = percent;
+    }
+    seedpool_config.upload_flags.internal |= cli.internal;
+    seedpool_config.upload_flags.anonymous |= cli.anon;
     info!("Configurations loaded.");
 
+    // --- Locate External Binaries ---
+    // Paths left out of config.yaml are looked up on $PATH; `config validate` reports problems itself.
+    // Only uploads, pre-flight checks, sync (re-seeds are uploads), previews and `config validate`
+    // run the tools; --web and --api start uploads as child processes, which resolve them.
+    let needs_binaries = !cli.web
+        && !cli.api
+        && matches!(cli.command, None | Some(Commands::Preview { .. } | Commands::Config { .. }));
+    let binaries = if needs_binaries { seed_tools::binaries::resolve(&mut main_config.paths) } else { Vec::new() };
+    for status in &binaries {
+        match (&status.path, &status.version, &status.problem) {
+            (Some(path), Some(version), None) => info!("{}: {} ({})", status.name, version, path.display()),
+            (_, _, Some(_)) if validating => {}
+            (_, _, Some(problem)) if seed_tools::probe::can_replace(status.name) => {
+                warn!("{}; using the built-in prober", problem);
+            }
+            (_, _, Some(problem)) => {
+                error!("{}", problem);
+                std::process::exit(1);
+            }
+            _ => {}
+        }
+    }
+    // ffmpeg.exe etc. on Windows when found on $PATH
+    let ffmpeg_path = PathBuf::from(&main_config.paths.ffmpeg);
+    let ffprobe_path = PathBuf::from(&main_config.paths.ffprobe);
+    let mkbrr_path = PathBuf::from(&main_config.paths.mkbrr);
+    let mediainfo_path = PathBuf::from(&main_config.paths.mediainfo);
+    debug!(
+        "Binary paths: ffmpeg={:?}, ffprobe={:?}, mkbrr={:?}, mediainfo={:?}",
+        ffmpeg_path, ffprobe_path, mkbrr_path, mediainfo_path
+    );
+
+    // Offline runs get no network at all; the stand-ins are passed to the upload below
+    let offline = match &cli.offline {
+        Some(dir) => {
+            main_config.network.offline = true;
+            // Screenshots go to the offline CDN directory instead
+            main_config.imgbb = None;
+            Some(Arc::new(Offline::new(dir)?))
+        }
+        None => None,
+    };
+    seed_tools::http::init(&main_config.http, &main_config.network);
+    if let Some(imgbb) = &main_config.imgbb {
+        seed_tools::imgbb::init(imgbb);
+    }
+    if cli.seed.is_some() {
+        main_config.screenshots.seed = cli.seed;
+    }
+    if !cli.timestamps.is_empty() {
+        main_config.screenshots.timestamps = cli.timestamps.clone();
+    }
+    seed_tools::utils::init_screenshots(&main_config.screenshots)?;
+    seed_tools::schedule::init(&main_config.transfers)?;
+    seed_tools::space::init(&main_config.paths, &main_config.disk_space);
+    metrics::init(&main_config.paths);
+    seed_tools::dupe_cache::init(&main_config.paths, &main_config.dupe_cache);
+    let _metrics = metrics::FlushGuard; // Writes this run's upload metrics for the --web/--api /metrics endpoint
+
+    // --- Handle Web Mode ---
+    if cli.web {
+        info!("Launching web mode...");
+        seed_tools::web::launch_web(&main_config, &config_dir).await?;
+        return Ok(());
+    }
+
+    // --- Handle API Mode ---
+    if cli.api {
+        info!("Launching API mode...");
+        seed_tools::api::launch_api(&main_config, &config_dir).await?;
+        return Ok(());
+    }
+
     if cli.pre {
         info!("Running pre-flight check...");
         if let Some(input_path) = cli.input_path {
             let input_path_str = input_path.to_str().ok_or("Invalid input path string")?;
             info!("Input path for pre-flight check: {}", input_path_str);
     
-            match preflight_check(
-                input_path_str,
-                &main_config,
-                &seedpool_config,
-                &ffmpeg_path,
-                &ffprobe_path,
-                &mediainfo_path,
-            ) {
+            match trackers::preflight(input_path_str, &main_config, &seedpool_config) {
                 Ok(result) => {
-                    println!("Pre-flight Check Results:");
-                    println!("Title: {}", result.release_name);
-                    println!("Release Name: {}", result.generated_release_name);
-                    println!("Dupe Check: {}", result.dupe_check);
-                    println!("Release Type: {}", result.release_type); // New line
-                    println!(
-                        "Season Number: {}",
-                        result.season_number.map_or("N/A".to_string(), |s| s.to_string())
-                    ); // New line
-                    println!(
-                        "Episode Number: {}",
-                        result.episode_number.map_or("N/A".to_string(), |e| e.to_string())
-                    ); // New line
-                    println!("TMDB ID: {}", result.tmdb_id);
-                    println!("IMDb ID: {}", result.imdb_id.unwrap_or_else(|| "N/A".to_string()));
-                    println!("TVDB ID: {}", result.tvdb_id.map_or("N/A".to_string(), |id| id.to_string()));
-                    println!("Excluded Files: {}", result.excluded_files);
-                    println!("Audio Languages: {:?}", result.audio_languages);
+                    if cli.output != OutputFormat::Table {
+                        println!("{}", output::render(cli.output, std::slice::from_ref(&result))?.unwrap_or_default().trim_end());
+                    } else {
+                        print_preflight(&result);
+                    }
+                    if cli.inject_dupes && result.dupe {
+                        match trackers::cross_seed_dupe(input_path_str, &main_config, &result) {
+                            Ok(true) => info!("Added the existing Seedpool torrent of '{}' to the clients.", result.release_name),
+                            Ok(false) => warn!("The existing Seedpool torrent of '{}' was not added to the clients.", result.release_name),
+                            Err(e) => error!("Failed to cross-seed '{}': {}", result.release_name, e),
+                        }
+                    }
                 }
                 Err(e) => {
                     error!("Pre-flight check failed: {}", e);
@@ -234,10 +540,63 @@ async fn main() -> Result<(), Box<dyn Error>> {
     // --- Handle Sync Mode ---
     if cli.sync {
         info!("Running in --sync mode.");
-        if let Err(e) = sync::sync_qbittorrent(&main_config.qbittorrent, &seedpool_config.general.api_key) {
-            error!("Error syncing qBittorrent: {}", e);
-        } else {
-            info!("Sync operation completed.");
+        let result = sync::sync_qbittorrent(&main_config.qbittorrent, &seedpool_config.general.api_key);
+        match &result {
+            Ok(added) => info!("Sync operation completed, {} torrent(s) added.", added),
+            Err(e) => error!("Error syncing qBittorrent: {}", e),
+        }
+        notify(&main_config.notifications, &Notification::sync(&result));
+
+        // Torrents in a reseed_category that Seedpool lacks go through the upload pipeline
+        if main_config.qbittorrent.iter().any(|config| config.reseed_category.is_some()) {
+            match sync::find_reseed_candidates(&main_config.qbittorrent, &seedpool_config.general.api_key) {
+                Ok(candidates) => {
+                    info!("{} torrent(s) to re-seed to Seedpool.", candidates.len());
+                    for (name, result) in sync::reseed(&candidates, &mut main_config, &seedpool_config, cli.yes) {
+                        match result {
+                            Ok(()) => info!("Successfully re-seeded '{}' to Seedpool.", name),
+                            Err(e) => error!("Error re-seeding '{}' to Seedpool: {}", name, e),
+                        }
+                    }
+                }
+                Err(e) => error!("Error finding torrents to re-seed: {}", e),
+            }
+        }
+
+        // Dead torrents are always reported before anything is removed
+        if cli.prune_dead {
+            println!("Checking qBittorrent only; torrents in Deluge are not checked or removed.");
+            let dead = match sync::find_dead_torrents(&main_config.qbittorrent) {
+                Ok(dead) => dead,
+                Err(e) => {
+                    error!("Error finding dead torrents: {}", e);
+                    return Ok(());
+                }
+            };
+            for torrent in &dead {
+                println!("{}  {}  ({})", torrent.webui_url, torrent.name, torrent.reason);
+            }
+            println!("{} dead torrent(s) found.", dead.len());
+            if dead.is_empty() {
+                return Ok(());
+            }
+            let confirmed = cli.yes
+                || dialoguer::Confirm::new()
+                    .with_prompt(format!("Remove {} torrent(s) from qBittorrent? Their remaining data is kept.", dead.len()))
+                    .default(false)
+                    .interact()
+                    .unwrap_or(false);
+            if !confirmed {
+                println!("Nothing removed.");
+                return Ok(());
+            }
+            let results = sync::remove_dead_torrents(&dead, &main_config.qbittorrent, &main_config.paths);
+            for (name, result) in &results {
+                if let Err(e) = result {
+                    error!("Error removing '{}': {}", name, e);
+                }
+            }
+            println!("Removed {} of {} dead torrent(s).", results.iter().filter(|(_, result)| result.is_ok()).count(), dead.len());
         }
         return Ok(()); // Exit after sync
     }
@@ -249,14 +608,24 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 info!("Running check for duplicates with name: {}", name);
 
                 // Call check_seedpool
-                match sync::check_seedpool(&name, &seedpool_config.general.api_key) {
-                    Ok(Some(download_link)) => {
-                        println!("Duplicate found for '{}'. Download link: {}", name, download_link);
-                        std::process::exit(1); // Exit with non-zero code if duplicate is found
-                    }
-                    Ok(None) => {
-                        println!("No duplicate found for '{}'.", name);
-                        std::process::exit(0); // Exit with zero code if no duplicate is found
+                match seed_tools::trackers::seedpool::check_seedpool(&name, &seedpool_config.general.api_key) {
+                    Ok(download_link) => {
+                        #[derive(serde::Serialize)]
+                        struct CheckResult<'a> {
+                            name: &'a str,
+                            duplicate: bool,
+                            download_link: Option<&'a str>,
+                        }
+                        let result = CheckResult { name: &name, duplicate: download_link.is_some(), download_link: download_link.as_deref() };
+                        match output::render(cli.output, &[result])? {
+                            Some(rendered) => println!("{}", rendered.trim_end()),
+                            None => match &download_link {
+                                Some(link) => println!("Duplicate found for '{}'. Download link: {}", name, link),
+                                None => println!("No duplicate found for '{}'.", name),
+                            },
+                        }
+                        // Non-zero exit code if a duplicate is found
+                        std::process::exit(if download_link.is_some() { 1 } else { 0 });
                     }
                     Err(e) => {
                         error!("Error checking for duplicate: {}", e);
@@ -264,6 +633,297 @@ async fn main() -> Result<(), Box<dyn Error>> {
                     }
                 }
             }
+            Commands::History { action: None, tracker, since, json } => {
+                let since = match since.as_deref().map(history::parse_since).transpose() {
+                    Ok(since) => since,
+                    Err(e) => {
+                        println!("{}", e);
+                        std::process::exit(2);
+                    }
+                };
+                let entries = match history::query(&main_config.paths, tracker.as_deref(), since) {
+                    Ok(entries) => entries,
+                    Err(e) => {
+                        error!("Failed to read the upload history: {}", e);
+                        println!("Failed to read the upload history: {}", e);
+                        std::process::exit(1);
+                    }
+                };
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&entries)?);
+                } else {
+                    print_history_table(&entries);
+                }
+                return Ok(());
+            }
+            Commands::History { action: Some(HistoryAction::Import { from_qbittorrent }), .. } => {
+                if !from_qbittorrent {
+                    error!("Nothing to import from. Pass --from-qbittorrent.");
+                    return Ok(());
+                }
+                let trackers = [
+                    ("seedpool", vec![seedpool_config.settings.announce_url.clone()]),
+                    (
+                        "torrentleech",
+                        vec![
+                            torrentleech_config.general.announce_url_1.clone(),
+                            torrentleech_config.general.announce_url_2.clone(),
+                        ],
+                    ),
+                ];
+                match history::import_from_qbittorrent(&main_config.paths, &main_config.qbittorrent, &trackers) {
+                    Ok(imported) => println!("Imported {} torrent(s) into the upload history.", imported),
+                    Err(e) => {
+                        error!("History import failed: {}", e);
+                        println!("History import failed: {}", e);
+                    }
+                }
+                return Ok(());
+            }
+            Commands::Inspect { torrent, json } => {
+                let summary = fs::read(&torrent)
+                    .map_err(|e| format!("Failed to read '{}': {}", torrent.display(), e))
+                    .and_then(|data| seed_tools::torrent::inspect(&data));
+                let summary = match summary {
+                    Ok(summary) => summary,
+                    Err(e) => {
+                        println!("{}", e);
+                        std::process::exit(1);
+                    }
+                };
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&summary)?);
+                } else {
+                    print_torrent_summary(&summary);
+                }
+                return Ok(());
+            }
+            Commands::Verify { torrent, path, json } => {
+                let report = fs::read(&torrent)
+                    .map_err(|e| format!("Failed to read '{}': {}", torrent.display(), e))
+                    .and_then(|data| seed_tools::torrent::verify(&data, &path));
+                let report = match report {
+                    Ok(report) => report,
+                    Err(e) => {
+                        println!("{}", e);
+                        std::process::exit(2);
+                    }
+                };
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&report)?);
+                } else {
+                    println!("Content:  {}", report.content_path);
+                    println!("Complete: {:.1}% ({} of {} pieces)", report.percent, report.valid_pieces, report.pieces);
+                    for file in &report.missing_files {
+                        println!("  missing     {}", file);
+                    }
+                    for file in &report.mismatched_files {
+                        println!("  mismatched  {}", file);
+                    }
+                }
+                // Non-zero exit code unless everything matches
+                std::process::exit(if report.is_complete() { 0 } else { 1 });
+            }
+            Commands::Retag { torrent, tracker, output, source } => {
+                let tracker = history::tracker_name(&tracker);
+                let (announce_urls, default_source) = match tracker.as_str() {
+                    "seedpool" => (vec![seedpool_config.settings.announce_url.clone()], "seedpool.org"),
+                    "torrentleech" => (
+                        vec![
+                            torrentleech_config.general.announce_url_1.clone(),
+                            torrentleech_config.general.announce_url_2.clone(),
+                        ],
+                        "TorrentLeech.org",
+                    ),
+                    other => {
+                        println!("Unknown tracker '{}' (use SP or TL).", other);
+                        std::process::exit(2);
+                    }
+                };
+                let output = output.unwrap_or_else(|| {
+                    let stem = torrent.file_stem().unwrap_or_default().to_string_lossy();
+                    torrent.with_file_name(format!("{}.{}.torrent", stem, tracker))
+                });
+                let result = fs::read(&torrent)
+                    .map_err(|e| format!("Failed to read '{}': {}", torrent.display(), e))
+                    .and_then(|data| seed_tools::torrent::retag(&data, &announce_urls, source.as_deref().unwrap_or(default_source)))
+                    .and_then(|data| {
+                        fs::write(&output, &data).map_err(|e| format!("Failed to write '{}': {}", output.display(), e))?;
+                        seed_tools::torrent::inspect(&data)
+                    });
+                match result {
+                    Ok(summary) => {
+                        info!("Retagged {} for {} as {}", torrent.display(), tracker, output.display());
+                        println!(
+                            "Wrote {} (info hash {})",
+                            output.display(),
+                            summary.info_hash_v1.or(summary.info_hash_v2).unwrap_or_default()
+                        );
+                    }
+                    Err(e) => {
+                        error!("Retag failed: {}", e);
+                        println!("Retag failed: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+                return Ok(());
+            }
+            Commands::Orphans { dirs, csv } => {
+                let dirs = if dirs.is_empty() {
+                    let mut dirs: Vec<PathBuf> = main_config
+                        .qbittorrent
+                        .iter()
+                        .map(|config| PathBuf::from(&config.default_save_path))
+                        .chain([PathBuf::from(&main_config.deluge.default_save_path)])
+                        .collect();
+                    dirs.sort();
+                    dirs.dedup();
+                    dirs
+                } else {
+                    dirs
+                };
+                info!("Looking for orphaned data in {:?}", dirs);
+
+                let orphans = match orphans::find_orphans(&dirs, &main_config.qbittorrent, &main_config.deluge) {
+                    Ok(orphans) => orphans,
+                    Err(e) => {
+                        error!("Orphan search failed: {}", e);
+                        println!("Orphan search failed: {}", e);
+                        std::process::exit(1);
+                    }
+                };
+                for orphan in &orphans {
+                    println!("{:>12}  {}", utils::format_size(orphan.size), orphan.path.display());
+                }
+                let total: u64 = orphans.iter().map(|orphan| orphan.size).sum();
+                println!(
+                    "{} orphaned item(s), {} in total, not referenced by any torrent.",
+                    orphans.len(),
+                    utils::format_size(total)
+                );
+                if let Some(csv) = csv {
+                    match orphans::write_csv(&csv, &orphans) {
+                        Ok(()) => println!("Wrote {}", csv.display()),
+                        Err(e) => error!("{}", e),
+                    }
+                }
+                return Ok(());
+            }
+            Commands::ExportState { output, include_secrets } => {
+                match state::export_state(&config_dir, &main_config.paths, &output, include_secrets) {
+                    Ok(()) => {
+                        println!("Wrote {}", output.display());
+                        if !include_secrets {
+                            println!("Credentials were left out; pass --include-secrets to keep them.");
+                        }
+                    }
+                    Err(e) => {
+                        error!("State export failed: {}", e);
+                        println!("State export failed: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+                return Ok(());
+            }
+            Commands::ImportState { archive, overwrite } => {
+                match state::import_state(&archive, &config_dir, &main_config.paths, overwrite) {
+                    Ok(restored) => println!("Restored {} file(s) from {}.", restored, archive.display()),
+                    Err(e) => {
+                        error!("State import failed: {}", e);
+                        println!("State import failed: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+                return Ok(());
+            }
+            Commands::Config { action: ConfigAction::Validate } => {
+                // config.yaml and the Seedpool/TorrentLeech configs already parsed to get here
+                println!("Config: {}", main_config_path.display());
+                if let Some(profile) = profile::name() {
+                    println!("Profile: {}", profile);
+                }
+                let mut problems = 0;
+                let tracker_dirs = [Some(config_dir.join("trackers")), profile::profile_dir(&config_dir).map(|dir| dir.join("trackers"))];
+                for entry in tracker_dirs.iter().flatten().flat_map(walkdir::WalkDir::new).filter_map(|entry| entry.ok()) {
+                    let path = entry.path();
+                    if !matches!(path.extension().and_then(|ext| ext.to_str()), Some("yaml" | "yml")) {
+                        continue;
+                    }
+                    match seed_tools::secrets::read_config(path)
+                        .and_then(|content| serde_yaml::from_str::<serde_yaml::Value>(&content).map_err(|e| e.to_string()))
+                    {
+                        Ok(_) => println!("  OK   {}", path.display()),
+                        Err(e) => {
+                            problems += 1;
+                            println!("  FAIL {}: {}", path.display(), e);
+                        }
+                    }
+                }
+                println!("Binaries:");
+                for status in &binaries {
+                    let path = status.path.as_ref().map_or("-".to_string(), |path| path.display().to_string());
+                    match &status.problem {
+                        None => println!("  OK   {:<10} {} ({})", status.name, status.version.as_deref().unwrap_or_default(), path),
+                        Some(problem) => {
+                            problems += 1;
+                            println!("  FAIL {:<10} {}", status.name, problem);
+                        }
+                    }
+                }
+                if problems > 0 {
+                    println!("{} problem(s) found.", problems);
+                    std::process::exit(1);
+                }
+                println!("No problems found.");
+                return Ok(());
+            }
+            Commands::Preview { path, html, no_screenshots } => {
+                let description = trackers::seedpool::preview_description(
+                    &path.to_string_lossy(),
+                    &main_config,
+                    &seedpool_config,
+                    &ffmpeg_path,
+                    &ffprobe_path,
+                    &mediainfo_path,
+                    !no_screenshots,
+                );
+                let description = match description {
+                    Ok(description) => description,
+                    Err(e) => {
+                        println!("Preview failed: {}", e);
+                        std::process::exit(1);
+                    }
+                };
+                if html {
+                    let title = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+                    let page = std::env::temp_dir().join(format!("{}.preview.html", generate_release_name(&title)));
+                    fs::write(&page, seed_tools::bbcode::render_html(&description, &title))?;
+                    println!("Preview written to {}", page.display());
+                } else {
+                    println!("{}", seed_tools::bbcode::render_ansi(&description));
+                }
+                return Ok(());
+            }
+            Commands::Clean { dry_run } => {
+                let expired = seed_tools::retention::find_expired(&main_config.paths, &main_config.retention, std::time::SystemTime::now());
+                for item in &expired {
+                    println!("{:>12}  {:<10}  {}", utils::format_size(item.size), item.artifact.name(), item.path.display());
+                }
+                let total: u64 = expired.iter().map(|item| item.size).sum();
+                if dry_run {
+                    println!("{} file(s) would be removed, {} in total.", expired.len(), utils::format_size(total));
+                    return Ok(());
+                }
+                let (reclaimed, errors) = seed_tools::retention::remove(&expired);
+                for e in &errors {
+                    error!("{}", e);
+                }
+                println!("Removed {} file(s), reclaimed {}.", expired.len() - errors.len(), utils::format_size(reclaimed));
+                if !errors.is_empty() {
+                    std::process::exit(1);
+                }
+                return Ok(());
+            }
         }
     }
 
@@ -272,17 +932,40 @@ async fn main() -> Result<(), Box<dyn Error>> {
         let input_path_str = input_path.to_str().ok_or("Invalid input path string")?;
         info!("Processing input path: {}", input_path_str);
 
+        // Leave releases that are still being copied or downloaded for a later queue or job attempt
+        if cli.check_ready
+            && let Err(reason) = readiness::check_release_ready(input_path_str, &main_config.readiness)
+        {
+            error!("Release not ready: {}", reason);
+            metrics::flush(); // process::exit skips the flush guard
+            std::process::exit(readiness::NOT_READY_EXIT_CODE);
+        }
+        if let Err(reason) = seed_tools::schedule::wait_for_window().await {
+            error!("{}", reason);
+            metrics::flush();
+            std::process::exit(seed_tools::schedule::OUTSIDE_WINDOW_EXIT_CODE);
+        }
+
+        // Limits given for this run replace the ones in config.yaml
+        if cli.max_size.is_some() {
+            main_config.limits.max_size_gb = cli.max_size;
+        }
+        if cli.max_files.is_some() {
+            main_config.limits.max_files = cli.max_files;
+        }
+        main_config.limits.abort |= cli.strict_limits;
+
         // Generate release name
         let sanitized_name = generate_release_name(
             &input_path
                 .file_name()
                 .ok_or("Could not get filename from input path")?
-                .to_string_lossy()
-                .to_string(),
+                .to_string_lossy(),
         );
         info!("Generated sanitized release name: {}", sanitized_name);
 
-        let mut errors = Vec::new();
+        let mut errors: Vec<(String, SeedToolsError)> = Vec::new();
+        let mut succeeded = Vec::new(); // Trackers that took the upload, for the partial success report
 
         // --- Custom Upload Mode ---
         if let Some(category_type_arg) = cli.custom_cat_type {
@@ -294,25 +977,30 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 return Ok(()); // Exit cleanly
             }
 
-            if category_type_arg == "0720" || category_type_arg == "0740" || category_type_arg == "0741" {
+            // eBook and newspaper codes follow the IDs in seedpool.yaml
+            let ids = &seedpool_config.ids;
+            let code = |type_name: &str| seed_tools::types::custom_cat_type_code(ids.category("ebook"), ids.type_id(type_name));
+            if ["ebook", "comic", "magazine"].iter().any(|type_name| category_type_arg == code(type_name)) {
                 info!("Detected eBook upload mode with argument: {}", category_type_arg);
             
                 // Assuming `config` and `seedpool_config` are already initialized
-                if let Err(e) = utils::process_ebook_upload(input_path_str, &main_config, &seedpool_config) {
+                if let Err(e) = utils::process_ebook_upload(input_path_str, &main_config, &seedpool_config, offline.as_deref()) {
                     error!("Error processing eBook upload: {}", e);
                 } else {
                     info!("Successfully processed eBook upload.");
+                    seed_tools::retention::after_success(&main_config.retention);
                 }
                 return Ok(()); // Exit after eBook upload
             }
 
-            if category_type_arg == "0742" {
+            if category_type_arg == code("newspaper") {
                 info!("Detected Newspaper upload mode with argument: {}", category_type_arg);
 
-                if let Err(e) = utils::process_newspaper_upload(input_path_str, &main_config, &seedpool_config) {
+                if let Err(e) = utils::process_newspaper_upload(input_path_str, &main_config, &seedpool_config, offline.as_deref()) {
                     error!("Error processing Newspaper upload: {}", e);
                 } else {
                     info!("Successfully processed Newspaper upload.");
+                    seed_tools::retention::after_success(&main_config.retention);
                 }
                 return Ok(()); // Exit after Newspaper upload
             }
@@ -321,45 +1009,27 @@ async fn main() -> Result<(), Box<dyn Error>> {
             let type_id: u32 = category_type_arg[2..4].parse()?;
             info!("Parsed Category ID: {}, Type ID: {}", category_id, type_id);
 
-            let target_tracker = if cli.sp {
-                "seedpool"
+            let (target_tracker, target) = if cli.sp {
+                ("seedpool", Target::Seedpool(&seedpool_config))
             } else {
-                "torrentleech"
+                ("torrentleech", Target::TorrentLeech(&torrentleech_config))
             };
 
-            let base_name = input_path
-                .file_name()
-                .ok_or("Could not get filename from input path")?
-                .to_string_lossy()
-                .to_string();
-
             if category_type_arg == "1416" || category_type_arg == "1915" {
-                let igdb_client_id = &main_config.general.igdb_client_id;
-                let igdb_bearer_token = &main_config.general.igdb_bearer_token;
-                let game_title = &sanitize_game_title(&base_name);
-
-                if let Err(e) = process_game_upload(
-                    input_path_str,
-                    category_id,
-                    type_id,
-                    &main_config.qbittorrent,
-                    &main_config.deluge,
-                    target_tracker,
-                    Some(&seedpool_config),
-                    Some(&torrentleech_config),
-                    mkbrr_path.to_str().ok_or("Invalid mkbrr_path")?,
-                    &main_config.paths,
-                    igdb_client_id,
-                    igdb_bearer_token,
-                ) {
+                let result = process_game_upload(input_path_str, category_id, type_id, &main_config, &target, offline.as_deref());
+                metrics::record_upload(target_tracker, &result);
+                let checkpoint = ReleaseCheckpoint::new(target_tracker, input_path_str);
+                notify(&main_config.notifications, &Notification::upload(target_tracker, &checkpoint, &result));
+                if let Err(e) = result {
                     error!("Error processing game upload for {}: {}", target_tracker, e);
+                    exit_if_tracker_unavailable(&[&e]);
                 } else {
                     info!("Successfully processed game upload for {}.", target_tracker);
                 }
                 return Ok(());
             }            
             
-            if category_type_arg.len() != 4 || !category_type_arg.chars().all(|c| c.is_digit(10)) {
+            if category_type_arg.len() != 4 || !category_type_arg.chars().all(|c| c.is_ascii_digit()) {
                 error!("Invalid format for custom upload specifier (-c/--custom-cat-type). Expected 4 digits (e.g., 0819), got: {}", category_type_arg);
                 return Ok(()); // Exit cleanly
             }
@@ -368,25 +1038,13 @@ async fn main() -> Result<(), Box<dyn Error>> {
             let type_id: u32 = category_type_arg[2..4].parse()?;
             info!("Parsed Category ID: {}, Type ID: {}", category_id, type_id);
 
-            let target_tracker = if cli.sp {
-                "seedpool"
-            } else {
-                "torrentleech"
-            };
-
-            if let Err(e) = process_custom_upload(
-                input_path_str,
-                category_id,
-                type_id,
-                &main_config.qbittorrent,
-                &main_config.deluge,
-                target_tracker,
-                Some(&seedpool_config),
-                Some(&torrentleech_config),
-                mkbrr_path.to_str().ok_or("Invalid mkbrr_path")?,
-                &main_config.paths,
-            ) {
+            let result = process_custom_upload(input_path_str, category_id, type_id, &main_config, &target, offline.as_deref());
+            metrics::record_upload(target_tracker, &result);
+            let checkpoint = ReleaseCheckpoint::new(target_tracker, input_path_str);
+            notify(&main_config.notifications, &Notification::upload(target_tracker, &checkpoint, &result));
+            if let Err(e) = result {
                 error!("Error processing custom upload for {}: {}", target_tracker, e);
+                exit_if_tracker_unavailable(&[&e]);
             } else {
                 info!("Successfully processed custom upload for {}.", target_tracker);
             }
@@ -395,53 +1053,139 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
         // --- Standard Upload Mode ---
         info!("Running in standard upload mode.");
-        let imgbb_api_key = main_config.imgbb.as_ref().map(|imgbb| imgbb.imgbb_api_key.clone());
-        debug!("Loaded imgbb API key: {:?}", imgbb_api_key);
+        let overrides = UploadOverrides {
+            release_name: cli.release_name.clone(),
+            tmdb_id: cli.tmdb,
+            imdb_id: cli.imdb.clone(),
+            tvdb_id: cli.tvdb,
+            season_number: cli.season,
+            episode_number: cli.episode,
+            category_id: cli.category,
+            type_id: match cli.type_id {
+                Some(TypeArg::Id(id)) => Some(id),
+                _ => None,
+            },
+            release_type: (cli.type_id == Some(TypeArg::Adult)).then(|| "adult".to_string()),
+        };
+        if !overrides.is_empty() {
+            info!("Upload overrides from command line: {:?}", overrides);
+        }
+        let options = UploadOptions {
+            overrides,
+            imgbb_api_key: main_config.imgbb.as_ref().map(|imgbb| imgbb.imgbb_api_key.clone()),
+            edit: cli.edit,
+            fresh: cli.fresh,
+            allow_reupload: cli.allow_reupload,
+            offline: offline.clone(),
+        };
+        debug!("Loaded imgbb API key: {:?}", options.imgbb_api_key);
         
-        // Pass the imgbb_api_key to the relevant functions
-        if cli.sp {
-            if let Err(e) = trackers::seedpool::process_seedpool_release(
+        // Software skips the video pipeline altogether; only Seedpool takes it for now
+        if cli.type_id == Some(TypeArg::Software) {
+            if !cli.sp || cli.tl || !cli.unit3d.is_empty() || !cli.gazelle.is_empty() {
+                error!("--type software uploads to Seedpool only; pass --SP and no other tracker.");
+                return Ok(());
+            }
+            match trackers::software::process_software_release(
                 input_path_str,
-                &sanitized_name,
-                &mut main_config,
+                &main_config,
                 &seedpool_config,
-                &ffmpeg_path,
-                &ffprobe_path,
-                &mkbrr_path,
-                &mediainfo_path,
-                imgbb_api_key.as_deref(), // Pass the imgbb API key
+                &Tools::from_paths(&main_config.paths),
+                &options,
             ) {
-                error!("Error processing Seedpool release: {}", e);
-                errors.push(format!("Seedpool: {}", e));
-            } else {
-                info!("Successfully processed Seedpool release for: {}", sanitized_name);
+                Ok(()) => info!("Successfully processed software release for: {}", sanitized_name),
+                Err(e) => {
+                    error!("Error processing software release: {}", e);
+                    exit_if_tracker_unavailable(&[&e]);
+                }
             }
+            return Ok(());
+        }
+
+        if cli.type_id == Some(TypeArg::Adult) && (cli.tl || !cli.gazelle.is_empty()) {
+            error!("--type adult uploads to Seedpool and UNIT3D trackers only.");
+            return Ok(());
         }
 
+        let unit3d_configs = if cli.unit3d.is_empty() { Vec::new() } else { load_unit3d_configs(&profile::config_path(&config_dir, "trackers/unit3d"))? };
+        let gazelle_configs = if cli.gazelle.is_empty() { Vec::new() } else { load_gazelle_configs(&profile::config_path(&config_dir, "trackers/gazelle"))? };
+        let mut targets = Vec::new();
+        if cli.sp {
+            targets.push(Target::Seedpool(&seedpool_config));
+        }
         if cli.tl {
-            if let Err(e) = trackers::torrentleech::process_torrentleech_release(
-                input_path_str,
-                &sanitized_name,
-                &mut main_config,
-                &torrentleech_config,
-                &mkbrr_path,
-                &mediainfo_path,
-            ) {
-                error!("Error processing TorrentLeech release: {}", e);
-                errors.push(format!("TorrentLeech: {}", e));
-            } else {
-                info!("Successfully processed TorrentLeech release for: {}", sanitized_name);
+            targets.push(Target::TorrentLeech(&torrentleech_config));
+        }
+        for name in &cli.unit3d {
+            match find_unit3d_config(&unit3d_configs, name) {
+                Ok(tracker) => targets.push(Target::Unit3d(tracker)),
+                Err(e) => {
+                    error!("Error processing {} release: {}", name, e);
+                    errors.push((name.clone(), e));
+                }
+            }
+        }
+        for name in &cli.gazelle {
+            match find_gazelle_config(&gazelle_configs, name) {
+                Ok(tracker) => targets.push(Target::Gazelle(tracker)),
+                Err(e) => {
+                    error!("Error processing {} release: {}", name, e);
+                    errors.push((name.clone(), e));
+                }
+            }
+        }
+
+        let results = if cli.per_season {
+            trackers::process_per_season(input_path_str, &mut main_config, &targets, &options)
+        } else {
+            trackers::process_release(input_path_str, &mut main_config, &targets, &options)
+        };
+        let results = match results {
+            Ok(results) => results,
+            Err(e) => {
+                error!("{}", e);
+                return Ok(());
+            }
+        };
+        for (tracker, result) in results {
+            match result {
+                Ok(()) => {
+                    info!("Successfully processed {} release for: {}", tracker, sanitized_name);
+                    succeeded.push(tracker);
+                }
+                Err(e) => {
+                    error!("Error processing {} release: {}", tracker, e);
+                    errors.push((tracker, e));
+                }
             }
         }
 
-        if !cli.sp && !cli.tl {
-            error!("No tracker specified for upload (--SP or --TL required for standard upload).");
+        if !cli.sp && !cli.tl && cli.unit3d.is_empty() && cli.gazelle.is_empty() {
+            error!("No tracker specified for upload (--SP, --TL, --unit3d or --gazelle required for standard upload).");
         }
 
         if errors.is_empty() {
             info!("Upload completed successfully for all specified trackers.");
+            seed_tools::retention::after_success(&main_config.retention);
         } else {
-            error!("Upload completed with errors: {:?}", errors);
+            let messages: Vec<String> = errors.iter().map(|(tracker, e)| format!("{}: {}", tracker, e)).collect();
+            error!("Upload completed with errors: {:?}", messages);
+            // Uploads parked for an unavailable tracker are resumed later; anything else
+            // leaves the release on only some trackers, which neither API can undo
+            let failed: Vec<String> = errors
+                .iter()
+                .filter(|(_, e)| !e.is_retryable())
+                .map(|(tracker, e)| format!("{}: {}", tracker, e))
+                .collect();
+            if !succeeded.is_empty() && !failed.is_empty() {
+                error!(
+                    "Partial success: '{}' is on {} but failed on the others. Fix the failed upload or remove it from the trackers that accepted it.",
+                    sanitized_name,
+                    succeeded.join(", ")
+                );
+                notify(&main_config.notifications, &Notification::partial(&sanitized_name, &succeeded, &failed));
+            }
+            exit_if_tracker_unavailable(&errors.iter().map(|(_, e)| e).collect::<Vec<_>>());
         }
     } else {
         error!("Usage error: An input path is required unless using --sync.");
@@ -451,4 +1195,102 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
     info!("Seed Tools finished.");
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Prints history entries as a table, newest first.
+fn print_history_table(entries: &[history::HistoryEntry]) {
+    println!("{:<16}  {:<12}  {:>8}  {:>10}  {:<8}  RELEASE", "UPLOADED", "TRACKER", "CATEGORY", "TORRENT", "INFOHASH");
+    for entry in entries {
+        let uploaded_at = chrono::DateTime::parse_from_rfc3339(&entry.uploaded_at)
+            .map(|time| time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_else(|_| entry.uploaded_at.clone());
+        let category = entry.category_id.map(|id| id.to_string()).unwrap_or_else(|| "-".to_string());
+        let info_hash = entry.info_hash.as_deref().map(|hash| &hash[..hash.len().min(8)]).unwrap_or("-");
+        println!(
+            "{:<16}  {:<12}  {:>8}  {:>10}  {:<8}  {}{}",
+            uploaded_at,
+            entry.tracker,
+            category,
+            entry.torrent_id.as_deref().unwrap_or("-"),
+            info_hash,
+            entry.release_name,
+            if entry.imported { " (imported)" } else { "" },
+        );
+    }
+    println!("{} upload(s).", entries.len());
+}
+
+/// Prints what `inspect` found in a .torrent file.
+fn print_torrent_summary(summary: &seed_tools::torrent::TorrentSummary) {
+    println!("Name:          {}", summary.name);
+    println!("Info hash v1:  {}", summary.info_hash_v1.as_deref().unwrap_or("- (v2 only)"));
+    if let Some(hash) = &summary.info_hash_v2 {
+        println!("Info hash v2:  {}", hash);
+    }
+    println!("Piece size:    {}", utils::format_size(summary.piece_size));
+    println!("Total size:    {} ({} file(s))", utils::format_size(summary.total_size), summary.files.len());
+    println!("Private:       {}", if summary.private { "yes" } else { "no" });
+    println!("Source:        {}", summary.source.as_deref().unwrap_or("-"));
+    println!("Created by:    {}", summary.created_by.as_deref().unwrap_or("-"));
+    println!("Created at:    {}", summary.creation_date.as_deref().unwrap_or("-"));
+    if let Some(comment) = &summary.comment {
+        println!("Comment:       {}", comment);
+    }
+    println!("Announce URLs:");
+    for url in &summary.announce_urls {
+        println!("  {}", url);
+    }
+    println!("Files:");
+    for file in &summary.files {
+        println!("  {:>12}  {}", utils::format_size(file.size), file.path);
+    }
+}
+
+/// Exits with `TRACKER_UNAVAILABLE_EXIT_CODE` when every error was a tracker being down or
+/// another error worth retrying, so the UI queue and web/API jobs park the upload and retry it later.
+fn exit_if_tracker_unavailable(errors: &[&SeedToolsError]) {
+    if !errors.is_empty() && errors.iter().all(|e| e.is_retryable()) {
+        error!("Tracker unavailable, the upload can be resumed later.");
+        metrics::flush(); // process::exit skips the flush guard
+        std::process::exit(TRACKER_UNAVAILABLE_EXIT_CODE);
+    }
+}
+
+/// Prints the pre-flight check results as the "Key: Value" lines the UI and jobs read back.
+fn print_preflight(result: &PreflightCheckResult) {
+    let or_na = |value: Option<String>| value.unwrap_or_else(|| "N/A".to_string());
+    println!("Pre-flight Check Results:");
+    println!("Title: {}", result.release_name);
+    println!("Release Name: {}", result.generated_release_name);
+    println!("Dupe Check: {}", result.dupe_check);
+    if let Some(dupe_torrent) = &result.dupe_torrent {
+        println!("Dupe Torrent: {}", dupe_torrent);
+    }
+    println!("Trump Check: {}", result.trump_check);
+    println!("Release Type: {}", result.release_type);
+    println!("Season Number: {}", or_na(result.season_number.map(|s| s.to_string())));
+    println!("Episode Number: {}", or_na(result.episode_number.map(|e| e.to_string())));
+    println!("TMDB ID: {}", result.tmdb_id);
+    println!("IMDb ID: {}", or_na(result.imdb_id.clone()));
+    println!("TVDB ID: {}", or_na(result.tvdb_id.map(|id| id.to_string())));
+    println!("Excluded Files: {}", result.excluded_files);
+    println!("Album Cover: {}", result.album_cover);
+    println!("Audio Languages: {:?}", result.audio_languages);
+    println!("Subtitles: {:?}", result.subtitle_languages);
+    println!("Source Type: {}", result.source_type); // Change the type with --type or the edit form
+    if let Some(disc) = &result.disc {
+        println!("Disc: {}", disc);
+    }
+    for sample in &result.existing_samples {
+        println!("Existing Sample: {}", sample);
+    }
+    let estimate = &result.estimate;
+    let read_speed = if estimate.read_bytes_per_sec > 0 {
+        format!(" (disk reads {}/s)", format_size(estimate.read_bytes_per_sec))
+    } else {
+        String::new()
+    };
+    println!("Content Size: {}{}", format_size(estimate.content_bytes), read_speed);
+    println!("Upload Size: {}", format_size(estimate.upload_bytes));
+    println!("Estimated Time: ~{}", format_duration(estimate.seconds));
+}
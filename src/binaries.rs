@@ -0,0 +1,102 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use log::warn;
+use regex::Regex;
+use crate::types::PathsConfig;
+
+/// What was found for one of the external tools.
+pub struct BinaryStatus {
+    pub name: &'static str,
+    pub path: Option<PathBuf>,
+    pub version: Option<String>, // First line of its version output
+    pub problem: Option<String>, // Missing, does not run, or too old
+}
+
+/// `name` (`name.exe` on Windows) in the first $PATH directory that has it.
+pub fn find_in_path(name: &str) -> Option<PathBuf> {
+    let file_name = format!("{}{}", name, std::env::consts::EXE_SUFFIX);
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path).map(|dir| dir.join(&file_name)).find(|candidate| candidate.is_file())
+}
+
+/// The configured path of `name`, or the one on $PATH when `paths` leaves it out or points at
+/// a file that does not exist.
+pub fn locate(name: &str, configured: &str) -> Result<PathBuf, String> {
+    if configured.trim().is_empty() {
+        return find_in_path(name).ok_or_else(|| format!("'{}' is not set in paths and was not found on $PATH", name));
+    }
+    let configured = Path::new(configured);
+    if configured.exists() {
+        return Ok(configured.to_path_buf());
+    }
+    match find_in_path(name) {
+        Some(found) => {
+            warn!("'{}' not found at '{}'; using {}", name, configured.display(), found.display());
+            Ok(found)
+        }
+        None => Err(format!("'{}' not found at '{}' or on $PATH", name, configured.display())),
+    }
+}
+
+fn run(path: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new(path).args(args).output().map_err(|e| format!("Failed to run {}: {}", path.display(), e))?;
+    if !output.status.success() {
+        return Err(format!("{} {} failed: {}", path.display(), args.join(" "), String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Runs `name` to read its version and checks that it is new enough: ffmpeg and ffprobe 4 or
+/// newer, and a mediainfo that knows `--Output`.
+pub fn check_version(name: &str, path: &Path) -> Result<String, String> {
+    match name {
+        "ffmpeg" | "ffprobe" => {
+            let output = run(path, &["-version"])?;
+            let version = output.lines().next().unwrap_or_default().trim().to_string();
+            // Git builds ("ffmpeg version N-112345-g...") have no release number and are taken as new
+            let major = Regex::new(r"version n?(\d+)\.").unwrap().captures(&version).and_then(|captures| captures[1].parse::<u32>().ok());
+            if let Some(major) = major.filter(|major| *major < 4) {
+                return Err(format!("{} {} is too old; 4.0 or newer is needed ({})", name, major, version));
+            }
+            Ok(version)
+        }
+        "mediainfo" => {
+            let output = run(path, &["--Version"])?;
+            let version = output.lines().map(str::trim).rev().find(|line| !line.is_empty()).unwrap_or_default().to_string();
+            if !run(path, &["--Help"])?.contains("--Output") {
+                return Err(format!("mediainfo at {} has no --Output support ({})", path.display(), version));
+            }
+            Ok(version)
+        }
+        _ => {
+            let output = run(path, &["version"]).or_else(|_| run(path, &["--version"]))?;
+            Ok(output.lines().next().unwrap_or_default().trim().to_string())
+        }
+    }
+}
+
+/// Locates and checks ffmpeg, ffprobe, mkbrr and mediainfo, and writes the paths found into
+/// `paths` so the rest of the run uses them.
+pub fn resolve(paths: &mut PathsConfig) -> Vec<BinaryStatus> {
+    let mut statuses = Vec::new();
+    for (name, configured) in [
+        ("ffmpeg", &mut paths.ffmpeg),
+        ("ffprobe", &mut paths.ffprobe),
+        ("mkbrr", &mut paths.mkbrr),
+        ("mediainfo", &mut paths.mediainfo),
+    ] {
+        let status = match locate(name, configured) {
+            Ok(path) => {
+                *configured = path.to_string_lossy().to_string();
+                let (version, problem) = match check_version(name, &path) {
+                    Ok(version) => (Some(version), None),
+                    Err(e) => (None, Some(e)),
+                };
+                BinaryStatus { name, path: Some(path), version, problem }
+            }
+            Err(e) => BinaryStatus { name, path: None, version: None, problem: Some(e) },
+        };
+        statuses.push(status);
+    }
+    statuses
+}
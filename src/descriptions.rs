@@ -0,0 +1,348 @@
+use std::collections::BTreeSet;
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::Value;
+use crate::utils::default_non_video_description;
+
+// Description rendering from details that were already gathered, without network or disk
+// access. The pipelines look things up (mediainfo, Open Library, the CDN) and hand the results
+// to these; the tests below render the fixtures in fixtures/descriptions with them and compare
+// the output with the .bbcode snapshot next to each fixture.
+
+/// One row of the track table of a music description.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct MusicTrack {
+    pub artist: String,
+    pub title: String,
+    pub duration: String,
+    pub size: String,
+    pub format: String,
+    pub bitrate: String,
+    pub sampling_rate: String,
+}
+
+/// A music release as its description shows it.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct MusicRelease {
+    pub base_name: String, // Folder or file name, the first line
+    pub artist: String,
+    pub album: String,
+    pub year: String,
+    pub genre: String,
+    pub audio: String, // e.g. "FLAC 24 bits 96 kHz"
+    pub tracks: Vec<MusicTrack>,
+    pub cover_url: Option<String>,
+    pub custom_description: Option<String>,
+}
+
+pub fn music(release: &MusicRelease) -> String {
+    let mut description = String::new();
+
+    // The input folder/file name as the first line
+    description.push_str(&format!("[b]{}[/b]\n", release.base_name));
+
+    // Artist, album, year, genre, and audio info
+    description.push_str(&format!(
+        "[b]Artist:[/b] {}\n[b]Album:[/b] {}\n[b]Year:[/b] {}\n[b]Genre:[/b] {}\n[b]Audio:[/b] {}\n",
+        release.artist, release.album, release.year, release.genre, release.audio
+    ));
+
+    description.push_str("[table]\n[tr][th]Nr.[/th][th]Artist[/th][th]Title[/th][th]Duration[/th][th]Size[/th][th]Format[/th][th]Bitrate[/th][th]kHz[/th][/tr]\n");
+    for (i, track) in release.tracks.iter().enumerate() {
+        description.push_str(&format!(
+            "[tr][td]{}[/td][td]{}[/td][td]{}[/td][td]{}[/td][td]{}[/td][td]{}[/td][td]{}[/td][td]{}[/td][/tr]\n",
+            i + 1,
+            track.artist,
+            track.title,
+            track.duration,
+            track.size,
+            track.format,
+            track.bitrate,
+            track.sampling_rate
+        ));
+    }
+    description.push_str("[/table]\n");
+
+    if let Some(cover_url) = &release.cover_url {
+        description.push_str(&format!("\n[img]{}[/img]\n", cover_url));
+    }
+
+    if let Some(custom_desc) = &release.custom_description {
+        description.push_str(custom_desc);
+        description.push_str("\n\n");
+    }
+
+    description.push_str(&default_non_video_description());
+    description
+}
+
+/// What Open Library returned for a book: the work, its author and the author's other works.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct EbookDetails {
+    pub title: String, // From the EPUB, used when Open Library has none
+    pub author: String,
+    pub work_key: String, // e.g. "OL45804W"
+    pub author_key: String,
+    pub work: Value, // /works/<key>.json
+    pub author_info: Value, // /authors/<key>.json
+    pub author_works: Value, // /authors/<key>/works.json
+}
+
+/// The description and the subjects (for keywords) of a book.
+pub fn ebook(details: &EbookDetails) -> (String, Vec<String>) {
+    let mut description = String::new();
+
+    // Subjects become keywords, not part of the description
+    let subjects = details.work["subjects"]
+        .as_array()
+        .map(|subjects| subjects.iter().filter_map(|s| s.as_str().map(|s| s.to_string())).collect())
+        .unwrap_or_default();
+
+    description.push_str(&format!(
+        "[center][b][size=32][color=#2E86C1]{}[/color][/size][/b][/center]\n\n",
+        details.work["title"].as_str().unwrap_or(&details.title)
+    ));
+    description.push_str(&format!(
+        "[center][b][size=16][color=#117A65]By:[/color][/size][/b] [i]{}[/i][/center]\n\n",
+        details.author_info["name"].as_str().unwrap_or(&details.author)
+    ));
+
+    if let Some(book_description) = details.work["description"]
+        .as_str()
+        .or_else(|| details.work["description"]["value"].as_str())
+    {
+        // Links are listed below the synopsis instead of inline
+        let link_regex = Regex::new(r#"https?://[^\s\]]+"#).unwrap();
+        let extracted_links: Vec<String> = link_regex.find_iter(book_description).map(|link| link.as_str().to_string()).collect();
+
+        // Remove links and lines containing "Contain" or brackets "[]" from the description
+        let sanitized_description: String = link_regex
+            .replace_all(book_description, "")
+            .to_string()
+            .lines()
+            .filter(|line| !line.contains("Contain") && !line.contains('[') && !line.contains(']'))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        description.push_str("[b][size=15][color=#6C3483]Synopsis:[/color][/size][/b]\n");
+        description.push_str("[quote]\n");
+        description.push_str(sanitized_description.trim());
+        description.push_str("\n[/quote]\n\n");
+
+        if !extracted_links.is_empty() {
+            description.push_str("[b][size=14][color=#2874A6]Additional Editions:[/color][/size][/b]\n");
+            for link in extracted_links {
+                description.push_str(&format!("- [url={}][color=#1ABC9C]{}[/color][/url]\n", link.trim_end_matches(')'), link.trim_end_matches(')')));
+            }
+            description.push('\n');
+        }
+    }
+
+    if let Some(author_bio) = details.author_info["bio"]
+        .as_str()
+        .or_else(|| details.author_info["bio"]["value"].as_str())
+    {
+        // Remove the "([Source][1])" line and trim extra blank lines
+        let source_regex = Regex::new(r"\(\[Source\]\[\d+\]\)").unwrap();
+        let sanitized_bio = source_regex
+            .replace_all(author_bio, "")
+            .to_string()
+            .replace("on Wikipedia", "")
+            .replace("*", "")
+            .trim()
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        description.push_str("[b][size=15][color=#AF601A]About the Author:[/color][/size][/b]\n");
+        description.push_str(&format!("[quote]{}\n\n", sanitized_bio));
+
+        let wikipedia_link_regex = Regex::new(r#"href="([^"]+)""#).unwrap();
+        if let Some(wikipedia_link) = wikipedia_link_regex.captures(author_bio).and_then(|captures| captures.get(1)) {
+            description.push_str(&format!(
+                "\n[b]Source:[/b] [url={}][color=#1ABC9C]Wikipedia[/color][/url]",
+                wikipedia_link.as_str()
+            ));
+        }
+
+        description.push_str("[/quote]\n\n");
+    }
+
+    // Other books by the author, sorted so the description is the same on every run
+    let other_books: BTreeSet<&str> = details.author_works["entries"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry["title"].as_str())
+        .filter(|book_title| *book_title != details.title)
+        .collect();
+    if !other_books.is_empty() {
+        description.push_str(&format!("[b][size=15][color=#1F618D]More by {}:[/color][/size][/b]\n", details.author));
+        description.push_str("[list]\n");
+        for book in other_books {
+            description.push_str(&format!("[*] {}\n", book));
+        }
+        description.push_str("[/list]\n\n");
+    }
+
+    description.push_str("[b][size=14][color=#2874A6]Links:[/color][/size][/b]\n");
+    description.push_str(&format!(
+        "- [url=https://openlibrary.org/works/{}][color=#1ABC9C]View this book on Open Library[/color][/url]\n",
+        details.work_key
+    ));
+    description.push_str(&format!(
+        "- [url=https://openlibrary.org/authors/{}][color=#1ABC9C]View author on Open Library[/color][/url]\n\n",
+        details.author_key
+    ));
+
+    description.push_str(&format!("[center]{}[/center]", default_non_video_description()));
+    (description, subjects)
+}
+
+/// A comic or magazine: its name over a two-column table of page images.
+pub fn comic(torrent_name: &str, image_urls: &[String]) -> String {
+    let mut description = format!(
+        "[center][b][size=18][color=#2E86C1]{}[/color][/size][/b]\n\n[table]\n",
+        torrent_name
+    );
+    for (i, url) in image_urls.iter().enumerate() {
+        if i % 2 == 0 {
+            description.push_str("  [tr]\n");
+        }
+        description.push_str(&format!("    [td][img width=720]{}[/img][/td]\n", url));
+        if i % 2 == 1 {
+            description.push_str("  [/tr]\n");
+        }
+    }
+    // If odd number of images, close the last row
+    if !image_urls.len().is_multiple_of(2) {
+        description.push_str("    [td][/td]\n  [/tr]\n");
+    }
+    description.push_str("[/table][/center]\n\n");
+    description.push_str(&format!("[center]{}[/center]", default_non_video_description()));
+    description
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use crate::utils::{generate_description, generate_game_description};
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/descriptions");
+
+    /// Inputs of a movie or TV description (`generate_description`).
+    #[derive(Debug, Default, Deserialize)]
+    #[serde(default)]
+    struct VideoFixture {
+        screenshots: Vec<String>,
+        sample_url: String,
+        subtitles: Vec<String>,
+        file_tree: String,
+        custom_description: Option<String>,
+        youtube_trailer_url: Option<String>,
+    }
+
+    /// Inputs of a game description (`generate_game_description`).
+    #[derive(Debug, Default, Deserialize)]
+    #[serde(default)]
+    struct GameFixture {
+        base_name: String,
+        screenshots: Vec<String>,
+        custom_description: Option<String>,
+        youtube_trailer_url: Option<String>,
+    }
+
+    #[derive(Debug, Default, Deserialize)]
+    #[serde(default)]
+    struct ComicFixture {
+        torrent_name: String,
+        image_urls: Vec<String>,
+    }
+
+    /// A fixture file: `kind` picks the description, the other fields are its inputs.
+    #[derive(Debug, Deserialize)]
+    #[serde(tag = "kind", rename_all = "lowercase")]
+    enum Fixture {
+        Movie(VideoFixture),
+        Game(GameFixture),
+        Music(MusicRelease),
+        Ebook(EbookDetails),
+        Comic(ComicFixture),
+    }
+
+    fn render(fixture: &Fixture) -> String {
+        match fixture {
+            Fixture::Movie(video) => generate_description(
+                &video.screenshots,
+                &video.sample_url,
+                &video.subtitles,
+                None,
+                &video.file_tree,
+                video.custom_description.as_deref(),
+                video.youtube_trailer_url.as_deref(),
+            ),
+            Fixture::Game(game) => generate_game_description(
+                &game.screenshots,
+                game.custom_description.as_deref(),
+                game.youtube_trailer_url.as_deref(),
+                &game.base_name,
+            ),
+            Fixture::Music(release) => music(release),
+            Fixture::Ebook(details) => ebook(details).0,
+            Fixture::Comic(comic_fixture) => comic(&comic_fixture.torrent_name, &comic_fixture.image_urls),
+        }
+    }
+
+    /// The first line where `rendered` differs from `expected`.
+    fn compare(expected: &str, rendered: &str) -> Result<(), String> {
+        if expected == rendered {
+            return Ok(());
+        }
+        let (mut expected_lines, mut rendered_lines) = (expected.split('\n'), rendered.split('\n'));
+        for line in 1.. {
+            match (expected_lines.next(), rendered_lines.next()) {
+                (Some(a), Some(b)) if a == b => continue,
+                (a, b) => {
+                    return Err(format!("line {}: expected {:?}, got {:?}", line, a.unwrap_or("<end>"), b.unwrap_or("<end>")));
+                }
+            }
+        }
+        unreachable!()
+    }
+
+    // Renders every <name>.yaml fixture and compares it with <name>.bbcode. After changing a
+    // description on purpose, rewrite the snapshots with UPDATE_SNAPSHOTS=1 cargo test descriptions.
+    #[test]
+    fn fixtures_match_their_snapshots() {
+        let update = std::env::var_os("UPDATE_SNAPSHOTS").is_some();
+        let mut fixtures: Vec<_> = fs::read_dir(FIXTURES)
+            .unwrap()
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("yaml"))
+            .collect();
+        fixtures.sort();
+        assert!(!fixtures.is_empty(), "no fixtures in {}", FIXTURES);
+
+        let mut failures = Vec::new();
+        for path in fixtures {
+            let fixture: Fixture = serde_yaml::from_str(&fs::read_to_string(&path).unwrap())
+                .unwrap_or_else(|e| panic!("Failed to parse '{}': {}", path.display(), e));
+            let rendered = render(&fixture);
+            let snapshot = path.with_extension("bbcode");
+            if update {
+                fs::write(&snapshot, &rendered).unwrap();
+                continue;
+            }
+            let expected = fs::read_to_string(&snapshot).unwrap_or_else(|e| panic!("Failed to read '{}': {}", snapshot.display(), e));
+            if let Err(e) = compare(&expected.replace("\r\n", "\n"), &rendered) {
+                failures.push(format!("{}: {}", path.file_stem().unwrap_or_default().to_string_lossy(), e));
+            }
+        }
+        assert!(failures.is_empty(), "descriptions differ from their snapshots:\n{}", failures.join("\n"));
+    }
+}
@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use crate::pipeline::work_dir;
+use crate::types::{DupeCacheConfig, PathsConfig};
+
+// A pre-flight check and the upload after it are separate processes, so searches are kept in
+// work_dir as well as in memory. Without `init` (or with ttl_secs: 0) nothing is cached.
+static SETTINGS: OnceLock<(PathBuf, DupeCacheConfig)> = OnceLock::new();
+static ENTRIES: OnceLock<Mutex<HashMap<String, CachedSearch>>> = OnceLock::new();
+
+/// The results of one tracker search.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CachedSearch {
+    pub fetched_at: u64, // Unix seconds
+    pub etag: Option<String>, // Sent as If-None-Match once the entry is stale
+    pub results: Vec<(String, Option<String>)>, // Name and download link
+}
+
+impl CachedSearch {
+    pub fn is_fresh(&self) -> bool {
+        SETTINGS
+            .get()
+            .is_some_and(|(_, config)| now().saturating_sub(self.fetched_at) < config.ttl_secs)
+    }
+}
+
+/// Sets the cache file to `work_dir/dupe_cache.json`. Call once at startup.
+pub fn init(paths: &PathsConfig, config: &DupeCacheConfig) {
+    let _ = SETTINGS.set((work_dir(paths).join("dupe_cache.json"), config.clone()));
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs())
+}
+
+fn enabled() -> Option<&'static (PathBuf, DupeCacheConfig)> {
+    SETTINGS.get().filter(|(_, config)| config.ttl_secs > 0)
+}
+
+/// Entries from this process, or from the cache file the first time.
+fn entries() -> &'static Mutex<HashMap<String, CachedSearch>> {
+    ENTRIES.get_or_init(|| {
+        let entries = enabled()
+            .and_then(|(path, _)| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Mutex::new(entries)
+    })
+}
+
+fn save(entries: &HashMap<String, CachedSearch>) {
+    let Some((path, config)) = enabled() else {
+        return;
+    };
+    // Stale entries are kept a while longer for their ETags
+    let keep_secs = config.ttl_secs.saturating_mul(12);
+    let kept: HashMap<&String, &CachedSearch> =
+        entries.iter().filter(|(_, entry)| now().saturating_sub(entry.fetched_at) < keep_secs).collect();
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .map_err(|e| e.to_string())
+        .and_then(|_| serde_json::to_string(&kept).map_err(|e| e.to_string()))
+        .and_then(|json| {
+            let temp = path.with_extension("json.tmp");
+            fs::write(&temp, json).and_then(|_| fs::rename(&temp, path)).map_err(|e| e.to_string())
+        });
+    if let Err(e) = result {
+        warn!("Failed to write the dupe check cache {}: {}", path.display(), e);
+    }
+}
+
+/// Cache key of a search: the same query on the same site, whatever the case.
+pub fn key(base_url: &str, search_term: &str, extra_params: &str) -> String {
+    format!("{}|{}|{}", base_url.trim_end_matches('/').to_lowercase(), search_term.to_lowercase(), extra_params)
+}
+
+/// The cached search for `key`, fresh or not.
+pub fn lookup(key: &str) -> Option<CachedSearch> {
+    enabled()?;
+    entries().lock().unwrap().get(key).cloned()
+}
+
+/// Caches the results of a search.
+pub fn store(key: &str, etag: Option<String>, results: &[(String, Option<String>)]) {
+    if enabled().is_none() {
+        return;
+    }
+    let mut entries = entries().lock().unwrap();
+    entries.insert(
+        key.to_string(),
+        CachedSearch {
+            fetched_at: now(),
+            etag,
+            results: results.to_vec(),
+        },
+    );
+    save(&entries);
+}
+
+/// Marks a stale entry fresh again after the site answered 304 Not Modified.
+pub fn refresh(key: &str) {
+    let mut entries = entries().lock().unwrap();
+    if let Some(entry) = entries.get_mut(key) {
+        entry.fetched_at = now();
+        save(&entries);
+    }
+}
+
+/// Drops every cached search, e.g. after an upload changed what a dupe check would find.
+pub fn clear() {
+    if enabled().is_none() {
+        return;
+    }
+    debug!("Clearing the dupe check cache");
+    let mut entries = entries().lock().unwrap();
+    entries.clear();
+    save(&entries);
+}
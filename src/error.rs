@@ -0,0 +1,187 @@
+use std::error::Error as StdError;
+use std::io;
+use thiserror::Error;
+
+type Source = Box<dyn StdError + Send + Sync>;
+
+/// What went wrong, by where it went wrong, so callers can tell a tracker that is down from a
+/// broken config. Messages name the tracker, tool or client themselves; the fields are for
+/// callers that decide what to do. `String` errors from modules that have no kinds of their own
+/// convert into `Other` with `?`.
+#[derive(Debug, Error)]
+pub enum SeedToolsError {
+    /// A config file is missing, unreadable or invalid.
+    #[error("{message}")]
+    Config {
+        message: String,
+        #[source]
+        source: Option<Source>,
+    },
+    /// ffmpeg, ffprobe, mediainfo, mkbrr, scp or another program failed or could not be started.
+    #[error("{message}")]
+    ExternalTool {
+        tool: String,
+        message: String,
+        #[source]
+        source: Option<io::Error>,
+    },
+    /// A tracker or web API refused a request or could not be reached. `status` is the HTTP
+    /// status when there was a response.
+    #[error("{message}")]
+    Tracker {
+        tracker: String,
+        status: Option<u16>,
+        message: String,
+        #[source]
+        source: Option<reqwest::Error>,
+    },
+    /// A torrent client (qBittorrent or Deluge) refused a request or could not be reached.
+    #[error("{message}")]
+    Client {
+        client: String,
+        message: String,
+        #[source]
+        source: Option<Source>,
+    },
+    /// A tracker is down for maintenance, behind a challenge page or answering with gateway
+    /// errors. The upload is parked and resumed later.
+    #[error("Tracker unavailable: {tracker} ({reason})")]
+    Unavailable { tracker: String, reason: String },
+    /// The upload should run later: the release is still being written or it is outside the
+    /// transfer window.
+    #[error("{0}")]
+    Postponed(String),
+    #[error("{context}: {source}")]
+    Io {
+        context: String,
+        #[source]
+        source: io::Error,
+    },
+    /// TMDB, IGDB or the release itself did not give the details an upload needs.
+    #[error("{0}")]
+    Metadata(String),
+    /// Not classified; from code that still returns `String` errors.
+    #[error("{0}")]
+    Other(String),
+}
+
+pub type Result<T, E = SeedToolsError> = std::result::Result<T, E>;
+
+impl SeedToolsError {
+    pub fn config(message: impl Into<String>) -> Self {
+        SeedToolsError::Config { message: message.into(), source: None }
+    }
+
+    pub fn tool(tool: &str, message: impl Into<String>) -> Self {
+        SeedToolsError::ExternalTool { tool: tool.to_string(), message: message.into(), source: None }
+    }
+
+    /// `tool` could not be started at all.
+    pub fn tool_spawn(tool: &str, source: io::Error) -> Self {
+        SeedToolsError::ExternalTool { tool: tool.to_string(), message: format!("Failed to run {}: {}", tool, source), source: Some(source) }
+    }
+
+    pub fn tracker(tracker: &str, status: Option<u16>, message: impl Into<String>) -> Self {
+        SeedToolsError::Tracker { tracker: tracker.to_string(), status, message: message.into(), source: None }
+    }
+
+    /// A request to `tracker` that failed without a response (DNS, connect, TLS or timeout).
+    pub fn request(tracker: &str, source: reqwest::Error) -> Self {
+        SeedToolsError::Tracker {
+            tracker: tracker.to_string(),
+            status: source.status().map(|status| status.as_u16()),
+            message: source.to_string(),
+            source: Some(source),
+        }
+    }
+
+    pub fn unavailable(tracker: &str, reason: impl Into<String>) -> Self {
+        SeedToolsError::Unavailable { tracker: tracker.to_string(), reason: reason.into() }
+    }
+
+    pub fn client(client: &str, message: impl Into<String>) -> Self {
+        SeedToolsError::Client { client: client.to_string(), message: message.into(), source: None }
+    }
+
+    /// For `map_err` on requests to a torrent client, keeping the reqwest error as the source.
+    pub fn client_request(client: &str, context: impl Into<String>) -> impl FnOnce(reqwest::Error) -> Self {
+        let (client, context) = (client.to_string(), context.into());
+        move |source| SeedToolsError::Client { client, message: format!("{}: {}", context, source), source: Some(Box::new(source)) }
+    }
+
+    /// For `map_err` on file operations: `fs::read(&path).map_err(SeedToolsError::io(format!(...)))`.
+    /// Also takes the errors of walkdir and others that wrap an `io::Error`.
+    pub fn io<E: Into<io::Error>>(context: impl Into<String>) -> impl FnOnce(E) -> Self {
+        let context = context.into();
+        move |source| SeedToolsError::Io { context, source: source.into() }
+    }
+
+    /// Prefixes the message with what was being done, keeping the kind, status and source.
+    pub fn context(mut self, context: &str) -> Self {
+        match &mut self {
+            SeedToolsError::Config { message, .. }
+            | SeedToolsError::ExternalTool { message, .. }
+            | SeedToolsError::Tracker { message, .. }
+            | SeedToolsError::Client { message, .. }
+            | SeedToolsError::Metadata(message)
+            | SeedToolsError::Postponed(message)
+            | SeedToolsError::Other(message) => *message = format!("{}: {}", context, message),
+            SeedToolsError::Unavailable { reason, .. } => *reason = format!("{}: {}", context, reason),
+            SeedToolsError::Io { context: existing, .. } => *existing = format!("{}: {}", context, existing),
+        }
+        self
+    }
+
+    /// The HTTP status of a tracker error, if the tracker answered.
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            SeedToolsError::Tracker { status, .. } => *status,
+            _ => None,
+        }
+    }
+
+    /// Whether the same request may succeed later: the tracker was unreachable, timed out,
+    /// rate limited the request, answered with a 5xx or a maintenance page, or the upload was
+    /// postponed. Config, metadata and tool errors need someone to fix them first.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            SeedToolsError::Unavailable { .. } | SeedToolsError::Postponed(_) => true,
+            SeedToolsError::Tracker { status: Some(status), .. } => *status == 429 || (500..600).contains(status),
+            SeedToolsError::Tracker { source: Some(source), .. } => source.is_connect() || source.is_timeout(),
+            _ => false,
+        }
+    }
+}
+
+impl From<String> for SeedToolsError {
+    fn from(message: String) -> Self {
+        SeedToolsError::Other(message)
+    }
+}
+
+impl From<&str> for SeedToolsError {
+    fn from(message: &str) -> Self {
+        SeedToolsError::Other(message.to_string())
+    }
+}
+
+impl From<io::Error> for SeedToolsError {
+    fn from(source: io::Error) -> Self {
+        SeedToolsError::Io { context: "I/O error".to_string(), source }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retries_depend_on_the_kind_not_the_message() {
+        assert!(SeedToolsError::unavailable("Seedpool", "HTTP 503").is_retryable());
+        assert!(SeedToolsError::Postponed("Release not ready".to_string()).is_retryable());
+        assert!(SeedToolsError::tracker("Seedpool", Some(502), "Bad gateway").is_retryable());
+        assert!(!SeedToolsError::tracker("Seedpool", Some(422), "Invalid category").is_retryable());
+        assert!(!SeedToolsError::from("Tracker unavailable: Seedpool (HTTP 503)").is_retryable());
+        assert!(!SeedToolsError::config("Tracker unavailable").is_retryable());
+    }
+}
@@ -0,0 +1,91 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::time::Instant;
+use serde::Serialize;
+use walkdir::WalkDir;
+use crate::types::SampleConfig;
+use crate::utils::{get_video_duration, sample_start, screenshot_count, total_size};
+
+// Rough figures for the parts that can not be measured up front
+const BENCHMARK_BYTES: usize = 256 * 1024 * 1024; // Read from the largest file to measure the disk
+const HASH_BYTES_PER_SEC: f64 = 600.0 * 1024.0 * 1024.0; // SHA-1 on one core
+const UPLOAD_BYTES_PER_SEC: f64 = 10.0 * 1024.0 * 1024.0; // scp to the CDN
+const SCREENSHOT_BYTES: u64 = 700 * 1024; // Full-size JPEG plus its thumbnail
+const SCREENSHOT_SECS: f64 = 3.0; // Seek, decode and encode one frame
+const TRACKER_SECS: f64 = 30.0; // Dupe check, TMDB lookups and the upload request
+
+/// What a run over one release is expected to cost, shown by the pre-flight check.
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct ProcessingEstimate {
+    pub content_bytes: u64,      // Read while hashing the torrent
+    pub upload_bytes: u64,       // Screenshots and sample sent to the CDN or ImgBB
+    pub read_bytes_per_sec: u64, // Measured on the largest file; 0 when it could not be read
+    pub seconds: u64,
+}
+
+/// Estimates the processing time and transfer size for `input_path`. `video_file` is the file
+/// screenshots are taken from (`None` for music and other releases without them) and `sample`
+/// the settings of the sample cut from it, if one is.
+pub fn estimate_processing(input_path: &str, video_file: Option<&str>, ffprobe_path: &str, sample: Option<&SampleConfig>) -> ProcessingEstimate {
+    let content_bytes = total_size(input_path);
+    let read_bytes_per_sec = largest_file(input_path).and_then(|file| read_throughput(&file)).unwrap_or(0.0);
+    // Page-cached reads can beat the hasher, so the slower of the two decides
+    let hash_rate = if read_bytes_per_sec > 0.0 { read_bytes_per_sec.min(HASH_BYTES_PER_SEC) } else { HASH_BYTES_PER_SEC };
+    let mut seconds = content_bytes as f64 / hash_rate + TRACKER_SECS;
+    let mut upload_bytes = 0;
+
+    if let Some(video_file) = video_file {
+        let duration = get_video_duration(video_file, ffprobe_path).unwrap_or(0.0);
+        let screenshots = screenshot_count(duration) as u64;
+        upload_bytes += screenshots * SCREENSHOT_BYTES;
+        seconds += screenshots as f64 * SCREENSHOT_SECS;
+        if let Some(sample) = sample.filter(|sample| sample_start(duration, sample).is_some()) {
+            // Stream copy, so the sample is as large as the same stretch of the video
+            let video_bytes = total_size(video_file) as f64;
+            upload_bytes += (video_bytes / duration * sample.duration_secs as f64) as u64;
+        }
+        seconds += upload_bytes as f64 / UPLOAD_BYTES_PER_SEC;
+    }
+
+    ProcessingEstimate {
+        content_bytes,
+        upload_bytes,
+        read_bytes_per_sec: read_bytes_per_sec as u64,
+        seconds: seconds.ceil() as u64,
+    }
+}
+
+fn largest_file(input_path: &str) -> Option<std::path::PathBuf> {
+    WalkDir::new(input_path)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| Some((entry.metadata().ok().filter(|metadata| metadata.is_file())?.len(), entry.into_path())))
+        .max_by_key(|(size, _)| *size)
+        .map(|(_, path)| path)
+}
+
+/// Bytes per second read from the start of `file`, up to `BENCHMARK_BYTES`.
+fn read_throughput(file: &Path) -> Option<f64> {
+    let mut reader = File::open(file).ok()?;
+    let mut buffer = vec![0; 4 * 1024 * 1024];
+    let mut read = 0;
+    let started = Instant::now();
+    while read < BENCHMARK_BYTES {
+        match reader.read(&mut buffer).ok()? {
+            0 => break,
+            n => read += n,
+        }
+    }
+    let elapsed = started.elapsed().as_secs_f64();
+    (read > 0 && elapsed > 0.0).then(|| read as f64 / elapsed)
+}
+
+/// Formats seconds like "2h 05m", "12m 30s" or "45s".
+pub fn format_duration(seconds: u64) -> String {
+    match seconds {
+        0..=59 => format!("{}s", seconds),
+        60..=3599 => format!("{}m {:02}s", seconds / 60, seconds % 60),
+        _ => format!("{}h {:02}m", seconds / 3600, seconds % 3600 / 60),
+    }
+}
@@ -0,0 +1,448 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use log::{info, warn};
+use reqwest::Url;
+use rusqlite::{params, Connection, Row};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::pipeline::{history_db, work_dir};
+use crate::types::{PathsConfig, QbittorrentConfig};
+
+/// One finished upload, kept for cross-seed matching and later lookups.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct HistoryEntry {
+    pub uploaded_at: String, // RFC 3339, UTC
+    pub tracker: String,
+    pub release_name: String,
+    pub input_path: String,
+    #[serde(default)]
+    pub category_id: Option<u32>,
+    pub torrent_id: Option<String>,
+    pub info_hash: Option<String>,         // Of the torrent created locally
+    pub tracker_info_hash: Option<String>, // Of the torrent the tracker serves; differs when the tracker rewrote it
+    #[serde(default)]
+    pub response: Option<String>,          // Upload response body
+    #[serde(default)]
+    pub imported: bool,                    // Found seeding in a client, not uploaded by seed-tools
+}
+
+const COLUMNS: &str = "uploaded_at, tracker, release_name, input_path, category_id, torrent_id, info_hash, tracker_info_hash, response, imported";
+
+/// Opens `work_dir/history.db`, creating it on first use. Entries of the `history.jsonl`
+/// file written by earlier versions are moved into it once.
+fn open(paths: &PathsConfig) -> Result<Connection, String> {
+    let path = history_db(paths);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create '{}': {}", dir.display(), e))?;
+    }
+    let connection = Connection::open(&path).map_err(|e| format!("Failed to open '{}': {}", path.display(), e))?;
+    // Parallel uploads record their results at the same time
+    connection
+        .busy_timeout(Duration::from_secs(10))
+        .map_err(|e| format!("Failed to configure '{}': {}", path.display(), e))?;
+    connection
+        .execute_batch(
+            "CREATE TABLE IF NOT EXISTS uploads (
+                id INTEGER PRIMARY KEY,
+                uploaded_at TEXT NOT NULL,
+                tracker TEXT NOT NULL,
+                release_name TEXT NOT NULL,
+                input_path TEXT NOT NULL,
+                category_id INTEGER,
+                torrent_id TEXT,
+                info_hash TEXT,
+                tracker_info_hash TEXT,
+                response TEXT,
+                imported INTEGER NOT NULL DEFAULT 0
+            );
+            CREATE INDEX IF NOT EXISTS uploads_uploaded_at ON uploads (uploaded_at);
+            CREATE TABLE IF NOT EXISTS removals (
+                id INTEGER PRIMARY KEY,
+                removed_at TEXT NOT NULL,
+                client TEXT NOT NULL,
+                release_name TEXT NOT NULL,
+                info_hash TEXT NOT NULL,
+                reason TEXT NOT NULL
+            );",
+        )
+        .map_err(|e| format!("Failed to set up '{}': {}", path.display(), e))?;
+    migrate_jsonl(&work_dir(paths).join("history.jsonl"), &connection)?;
+    Ok(connection)
+}
+
+fn migrate_jsonl(path: &Path, connection: &Connection) -> Result<(), String> {
+    let Ok(content) = fs::read_to_string(path) else { return Ok(()) };
+    let entries: Vec<HistoryEntry> = content.lines().filter_map(|line| serde_json::from_str(line).ok()).collect();
+    for entry in &entries {
+        insert(connection, entry)?;
+    }
+    let migrated = path.with_extension("jsonl.migrated");
+    fs::rename(path, &migrated).map_err(|e| format!("Failed to rename '{}': {}", path.display(), e))?;
+    info!("Moved {} entries from {} into the history database.", entries.len(), path.display());
+    Ok(())
+}
+
+fn insert(connection: &Connection, entry: &HistoryEntry) -> Result<(), String> {
+    connection
+        .execute(
+            &format!("INSERT INTO uploads ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)", COLUMNS),
+            params![
+                entry.uploaded_at,
+                entry.tracker,
+                entry.release_name,
+                entry.input_path,
+                entry.category_id,
+                entry.torrent_id,
+                entry.info_hash,
+                entry.tracker_info_hash,
+                entry.response,
+                entry.imported,
+            ],
+        )
+        .map(|_| ())
+        .map_err(|e| format!("Failed to write history entry: {}", e))
+}
+
+fn from_row(row: &Row) -> rusqlite::Result<HistoryEntry> {
+    Ok(HistoryEntry {
+        uploaded_at: row.get(0)?,
+        tracker: row.get(1)?,
+        release_name: row.get(2)?,
+        input_path: row.get(3)?,
+        category_id: row.get(4)?,
+        torrent_id: row.get(5)?,
+        info_hash: row.get(6)?,
+        tracker_info_hash: row.get(7)?,
+        response: row.get(8)?,
+        imported: row.get(9)?,
+    })
+}
+
+/// Adds an entry to the history database.
+pub fn record(paths: &PathsConfig, entry: &HistoryEntry) -> Result<(), String> {
+    insert(&open(paths)?, entry)
+}
+
+/// A torrent that `--sync --prune-dead` removed from a client.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Removal {
+    pub removed_at: String, // RFC 3339, UTC
+    pub client: String,     // Web UI URL of the client
+    pub release_name: String,
+    pub info_hash: String,
+    pub reason: String,
+}
+
+/// Adds a removal to the history database.
+pub fn record_removal(paths: &PathsConfig, removal: &Removal) -> Result<(), String> {
+    open(paths)?
+        .execute(
+            "INSERT INTO removals (removed_at, client, release_name, info_hash, reason) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![removal.removed_at, removal.client, removal.release_name, removal.info_hash, removal.reason],
+        )
+        .map(|_| ())
+        .map_err(|e| format!("Failed to write removal: {}", e))
+}
+
+/// Reads every entry, newest first.
+pub fn load(paths: &PathsConfig) -> Result<Vec<HistoryEntry>, String> {
+    query(paths, None, None)
+}
+
+/// Reads the entries of `tracker` (see `tracker_name`) uploaded at or after `since`, newest first.
+pub fn query(
+    paths: &PathsConfig,
+    tracker: Option<&str>,
+    since: Option<chrono::DateTime<chrono::Utc>>,
+) -> Result<Vec<HistoryEntry>, String> {
+    let connection = open(paths)?;
+    let mut statement = connection
+        .prepare(&format!(
+            "SELECT {} FROM uploads WHERE (?1 IS NULL OR tracker = ?1) AND (?2 IS NULL OR uploaded_at >= ?2) ORDER BY uploaded_at DESC",
+            COLUMNS
+        ))
+        .map_err(|e| format!("Failed to query the history: {}", e))?;
+    let rows = statement
+        .query_map(params![tracker.map(tracker_name), since.map(|since| since.to_rfc3339())], from_row)
+        .map_err(|e| format!("Failed to query the history: {}", e))?;
+    rows.collect::<Result<_, _>>().map_err(|e| format!("Failed to read the history: {}", e))
+}
+
+/// Writes a consistent copy of the history database to `target`, even while uploads write to it.
+pub fn snapshot(paths: &PathsConfig, target: &Path) -> Result<(), String> {
+    let connection = open(paths)?;
+    connection
+        .execute("VACUUM INTO ?1", params![target.to_string_lossy()])
+        .map_err(|e| format!("Failed to copy the history to '{}': {}", target.display(), e))?;
+    Ok(())
+}
+
+/// Lowercase letters and digits only, so `Some.Movie.2020.1080p` matches `Some Movie 2020 1080p`.
+pub fn normalize_release_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+/// Finds an earlier upload to `tracker` with the same infohash or normalized release name.
+pub fn find_previous_upload(
+    paths: &PathsConfig,
+    tracker: &str,
+    release_name: &str,
+    info_hash: Option<&str>,
+) -> Result<Option<HistoryEntry>, String> {
+    let name = normalize_release_name(release_name);
+    Ok(query(paths, Some(tracker), None)?.into_iter().find(|entry| {
+        let same_hash = info_hash.is_some_and(|hash| {
+            entry.info_hash.as_deref() == Some(hash) || entry.tracker_info_hash.as_deref() == Some(hash)
+        });
+        same_hash || (!name.is_empty() && normalize_release_name(&entry.release_name) == name)
+    }))
+}
+
+/// Maps the tracker flags of the command line (`SP`, `TL`) to the names stored in the history.
+pub fn tracker_name(name: &str) -> String {
+    match name.to_lowercase().as_str() {
+        "sp" => "seedpool".to_string(),
+        "tl" => "torrentleech".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Parses `--since`: an age like `30m`, `12h`, `7d` or `2w`, or a date like `2024-05-01`.
+pub fn parse_since(value: &str) -> Result<chrono::DateTime<chrono::Utc>, String> {
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc());
+    }
+    let invalid = || format!("Invalid --since '{}' (use e.g. 12h, 7d, 2w or 2024-05-01)", value);
+    let digits = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+    let (number, unit) = value.split_at(digits);
+    let number: i64 = number.parse().map_err(|_| invalid())?;
+    let age = match unit {
+        "m" => chrono::Duration::minutes(number),
+        "h" => chrono::Duration::hours(number),
+        "d" | "" => chrono::Duration::days(number),
+        "w" => chrono::Duration::weeks(number),
+        _ => return Err(invalid()),
+    };
+    Ok(chrono::Utc::now() - age)
+}
+
+fn host(url: &str) -> Option<String> {
+    Url::parse(url).ok().and_then(|url| url.host_str().map(|host| host.to_lowercase()))
+}
+
+/// Adds every torrent seeding in the configured qBittorrent instances whose announce URL points
+/// to one of `trackers` (name and its announce URLs) to the history, skipping known infohashes.
+/// Returns the number of imported torrents.
+pub fn import_from_qbittorrent(
+    paths: &PathsConfig,
+    configs: &[QbittorrentConfig],
+    trackers: &[(&str, Vec<String>)],
+) -> Result<usize, String> {
+    let tracker_hosts: Vec<(&str, Vec<String>)> = trackers
+        .iter()
+        .map(|(name, urls)| (*name, urls.iter().filter_map(|url| host(url)).collect()))
+        .collect();
+    let match_tracker = |url: &str| {
+        let url_host = host(url)?;
+        tracker_hosts
+            .iter()
+            .find(|(_, hosts)| hosts.contains(&url_host))
+            .map(|(name, _)| name.to_string())
+    };
+
+    let mut known: HashSet<String> = load(paths)?
+        .into_iter()
+        .flat_map(|entry| entry.info_hash.into_iter().chain(entry.tracker_info_hash))
+        .collect();
+    let mut imported = 0;
+
+    for config in configs {
+        let client = crate::http::client_builder()
+            .cookie_store(true)
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+        let login_response = client
+            .post(format!("{}/api/v2/auth/login", config.webui_url))
+            .form(&[
+                ("username", config.username.as_str()),
+                ("password", config.password.as_str()),
+            ])
+            .send()
+            .map_err(|e| format!("Failed to log in to qBittorrent: {}", e))?;
+        if !login_response.status().is_success() {
+            warn!("Failed to log in to qBittorrent at {}: {}", config.webui_url, login_response.status());
+            continue;
+        }
+
+        let torrents: Vec<Value> = client
+            .get(format!("{}/api/v2/torrents/info", config.webui_url))
+            .send()
+            .and_then(|response| response.json())
+            .map_err(|e| format!("Failed to fetch torrents from {}: {}", config.webui_url, e))?;
+        info!("Checking {} torrents in qBittorrent at {}.", torrents.len(), config.webui_url);
+
+        for torrent in &torrents {
+            // Hybrid torrents report their v1 hash separately
+            let hash = torrent["infohash_v1"]
+                .as_str()
+                .filter(|hash| !hash.is_empty())
+                .or_else(|| torrent["hash"].as_str())
+                .unwrap_or_default()
+                .to_lowercase();
+            if hash.is_empty() || known.contains(&hash) {
+                continue;
+            }
+
+            // `tracker` is only set once an announce worked; otherwise ask for the full list
+            let mut tracker = torrent["tracker"].as_str().and_then(&match_tracker);
+            if tracker.is_none() {
+                let urls: Vec<Value> = client
+                    .get(format!("{}/api/v2/torrents/trackers", config.webui_url))
+                    .query(&[("hash", torrent["hash"].as_str().unwrap_or_default())])
+                    .send()
+                    .and_then(|response| response.json())
+                    .unwrap_or_default();
+                tracker = urls.iter().find_map(|entry| entry["url"].as_str().and_then(&match_tracker));
+            }
+            let Some(tracker) = tracker else { continue };
+
+            let name = torrent["name"].as_str().unwrap_or_default().to_string();
+            let entry = HistoryEntry {
+                uploaded_at: chrono::DateTime::from_timestamp(torrent["added_on"].as_i64().unwrap_or(0), 0)
+                    .map(|time| time.to_rfc3339())
+                    .unwrap_or_default(),
+                tracker,
+                release_name: name.clone(),
+                input_path: torrent["content_path"].as_str().unwrap_or_default().to_string(),
+                info_hash: Some(hash.clone()),
+                imported: true,
+                ..Default::default()
+            };
+            record(paths, &entry)?;
+            info!("Imported '{}' ({}) into the history.", name, entry.tracker);
+            known.insert(hash);
+            imported += 1;
+        }
+    }
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(dir: &Path) -> PathsConfig {
+        PathsConfig {
+            torrent_dir: dir.join("torrents").to_string_lossy().to_string(),
+            screenshots_dir: dir.join("screenshots").to_string_lossy().to_string(),
+            ffmpeg: String::new(),
+            ffprobe: String::new(),
+            mkbrr: String::new(),
+            mediainfo: String::new(),
+            work_dir: Some(dir.join("work").to_string_lossy().to_string()),
+            staging_dir: None,
+            staging_copy: false,
+            temp_dir: None,
+        }
+    }
+
+    fn entry(uploaded_at: &str, tracker: &str, release_name: &str, info_hash: &str) -> HistoryEntry {
+        HistoryEntry {
+            uploaded_at: uploaded_at.to_string(),
+            tracker: tracker.to_string(),
+            release_name: release_name.to_string(),
+            input_path: format!("/data/{}", release_name),
+            info_hash: Some(info_hash.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn queries_entries_by_tracker_and_age() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = paths(dir.path());
+        record(&paths, &entry("2024-01-10T12:00:00+00:00", "seedpool", "Movie.A.2020.1080p-GROUP", "aaaa")).unwrap();
+        record(&paths, &entry("2024-03-10T12:00:00+00:00", "torrentleech", "Movie.B.2021.1080p-GROUP", "bbbb")).unwrap();
+        record(&paths, &entry("2024-05-10T12:00:00+00:00", "seedpool", "Movie.C.2022.1080p-GROUP", "cccc")).unwrap();
+
+        let names = |entries: Vec<HistoryEntry>| entries.into_iter().map(|entry| entry.release_name).collect::<Vec<_>>();
+        assert_eq!(names(load(&paths).unwrap()), ["Movie.C.2022.1080p-GROUP", "Movie.B.2021.1080p-GROUP", "Movie.A.2020.1080p-GROUP"]);
+        assert_eq!(names(query(&paths, Some("SP"), None).unwrap()), ["Movie.C.2022.1080p-GROUP", "Movie.A.2020.1080p-GROUP"]);
+        let since = parse_since("2024-03-01").unwrap();
+        assert_eq!(names(query(&paths, None, Some(since)).unwrap()), ["Movie.C.2022.1080p-GROUP", "Movie.B.2021.1080p-GROUP"]);
+        assert_eq!(names(query(&paths, Some("tl"), Some(since)).unwrap()), ["Movie.B.2021.1080p-GROUP"]);
+    }
+
+    #[test]
+    fn finds_previous_uploads_by_name_or_infohash() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = paths(dir.path());
+        let mut uploaded = entry("2024-01-10T12:00:00+00:00", "seedpool", "Some.Movie.2020.1080p.BluRay-GROUP", "aaaa");
+        uploaded.tracker_info_hash = Some("ffff".to_string());
+        record(&paths, &uploaded).unwrap();
+
+        let found = |tracker: &str, name: &str, hash: Option<&str>| {
+            find_previous_upload(&paths, tracker, name, hash).unwrap().map(|entry| entry.release_name)
+        };
+        assert!(found("seedpool", "Some Movie 2020 1080p BluRay GROUP", None).is_some());
+        assert!(found("SP", "Renamed.Upload", Some("aaaa")).is_some());
+        assert!(found("seedpool", "Renamed.Upload", Some("ffff")).is_some());
+        assert!(found("seedpool", "Other.Movie.2020.1080p.BluRay-GROUP", Some("bbbb")).is_none());
+        assert!(found("torrentleech", "Some.Movie.2020.1080p.BluRay-GROUP", Some("aaaa")).is_none());
+        assert!(found("seedpool", "...", None).is_none());
+    }
+
+    #[test]
+    fn moves_jsonl_history_into_the_database_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = paths(dir.path());
+        let jsonl = work_dir(&paths).join("history.jsonl");
+        fs::create_dir_all(work_dir(&paths)).unwrap();
+        let line = serde_json::to_string(&entry("2023-06-01T08:00:00+00:00", "seedpool", "Old.Upload.2019-GROUP", "aaaa")).unwrap();
+        fs::write(&jsonl, format!("{}\nnot json\n", line)).unwrap();
+
+        assert_eq!(load(&paths).unwrap().len(), 1);
+        assert!(!jsonl.exists());
+        assert!(jsonl.with_extension("jsonl.migrated").exists());
+        record(&paths, &entry("2024-01-10T12:00:00+00:00", "seedpool", "New.Upload.2024-GROUP", "bbbb")).unwrap();
+        assert_eq!(load(&paths).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn snapshots_the_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = paths(dir.path());
+        record(&paths, &entry("2024-01-10T12:00:00+00:00", "seedpool", "Movie.2020-GROUP", "aaaa")).unwrap();
+        let copy = dir.path().join("backup.db");
+        snapshot(&paths, &copy).unwrap();
+
+        let count: i64 = Connection::open(&copy).unwrap().query_row("SELECT COUNT(*) FROM uploads", [], |row| row.get(0)).unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn parses_since() {
+        assert_eq!(parse_since("2024-05-01").unwrap().to_rfc3339(), "2024-05-01T00:00:00+00:00");
+        // In whole minutes, rounded, as a little time passes between the two clock reads
+        let age = |value: &str| ((chrono::Utc::now() - parse_since(value).unwrap()).num_seconds() + 30) / 60;
+        assert_eq!(age("30m"), 30);
+        assert_eq!(age("12h"), 12 * 60);
+        assert_eq!(age("7"), 7 * 24 * 60);
+        assert_eq!(age("2w"), 14 * 24 * 60);
+        assert!(parse_since("3y").is_err());
+        assert!(parse_since("yesterday").is_err());
+    }
+
+    #[test]
+    fn normalizes_names_and_trackers() {
+        assert_eq!(normalize_release_name("Some.Movie.2020.1080p-GROUP"), normalize_release_name("some movie 2020 1080p GROUP"));
+        assert_eq!(tracker_name("SP"), "seedpool");
+        assert_eq!(tracker_name("tl"), "torrentleech");
+        assert_eq!(tracker_name("Aither"), "aither");
+    }
+}
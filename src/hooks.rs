@@ -0,0 +1,134 @@
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+use log::{error, info, warn};
+use crate::pipeline::ReleaseCheckpoint;
+use crate::types::{HookCommand, HooksConfig};
+
+/// Points in the upload pipeline where user scripts can run.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HookPoint {
+    PreTorrent,      // Before the .torrent is created
+    PostScreenshots, // After screenshots and sample were uploaded
+    PreUpload,       // Right before submitting to the tracker
+    PostUpload,      // After the tracker accepted the upload
+}
+
+impl HookPoint {
+    pub fn name(&self) -> &'static str {
+        match self {
+            HookPoint::PreTorrent => "pre_torrent",
+            HookPoint::PostScreenshots => "post_screenshots",
+            HookPoint::PreUpload => "pre_upload",
+            HookPoint::PostUpload => "post_upload",
+        }
+    }
+
+    fn commands<'a>(&self, hooks: &'a HooksConfig) -> &'a [HookCommand] {
+        match self {
+            HookPoint::PreTorrent => &hooks.pre_torrent,
+            HookPoint::PostScreenshots => &hooks.post_screenshots,
+            HookPoint::PreUpload => &hooks.pre_upload,
+            HookPoint::PostUpload => &hooks.post_upload,
+        }
+    }
+}
+
+/// Runs every script configured for `point`. Scripts get the release context as
+/// `SEED_TOOLS_*` environment variables and as JSON on stdin.
+/// A failing script aborts the upload when its `abort_on_failure` is set, except at
+/// `post_upload`, where the torrent is already on the tracker and failures are only logged.
+pub fn run_hooks(hooks: &HooksConfig, point: HookPoint, release: &ReleaseCheckpoint) -> Result<(), String> {
+    let commands = point.commands(hooks);
+    if commands.is_empty() {
+        return Ok(());
+    }
+
+    let payload = serde_json::json!({
+        "hook": point.name(),
+        "release": release,
+    })
+    .to_string();
+
+    for hook in commands {
+        info!("Running {} hook: {}", point.name(), hook.command);
+        if let Err(e) = run_hook(hook, point, release, &payload) {
+            if hook.abort_on_failure && point != HookPoint::PostUpload {
+                error!("{} hook '{}' failed: {}", point.name(), hook.command, e);
+                return Err(format!("{} hook '{}' failed: {}", point.name(), hook.command, e));
+            }
+            warn!("{} hook '{}' failed (continuing): {}", point.name(), hook.command, e);
+        }
+    }
+    Ok(())
+}
+
+fn run_hook(hook: &HookCommand, point: HookPoint, release: &ReleaseCheckpoint, payload: &str) -> Result<(), String> {
+    let mut child = Command::new(&hook.command)
+        .args(&hook.args)
+        .env("SEED_TOOLS_HOOK", point.name())
+        .env("SEED_TOOLS_TRACKER", &release.tracker)
+        .env("SEED_TOOLS_INPUT_PATH", &release.input_path)
+        .env("SEED_TOOLS_RELEASE_NAME", &release.release_name)
+        .env("SEED_TOOLS_RELEASE_TYPE", &release.release_type)
+        .env("SEED_TOOLS_CATEGORY_ID", release.category_id.to_string())
+        .env("SEED_TOOLS_TYPE_ID", release.type_id.to_string())
+        .env("SEED_TOOLS_TMDB_ID", release.tmdb_id.to_string())
+        .env("SEED_TOOLS_IMDB_ID", release.imdb_id.as_deref().unwrap_or(""))
+        .env("SEED_TOOLS_TVDB_ID", release.tvdb_id.map(|id| id.to_string()).unwrap_or_default())
+        .env("SEED_TOOLS_TORRENT_FILE", release.torrent_file.as_deref().unwrap_or(""))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start: {}", e))?;
+
+    // Scripts that ignore stdin close it early; that is not an error
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(payload.as_bytes());
+    }
+
+    // Forward script output to the log from separate threads so a full pipe never blocks it
+    let mut readers = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        let name = hook.command.clone();
+        readers.push(thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                info!("[hook {}] {}", name, line);
+            }
+        }));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        let name = hook.command.clone();
+        readers.push(thread::spawn(move || {
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                warn!("[hook {}] {}", name, line);
+            }
+        }));
+    }
+
+    let timeout = Duration::from_secs(hook.timeout_secs);
+    let started = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if started.elapsed() >= timeout => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("Timed out after {} seconds", hook.timeout_secs));
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(100)),
+            Err(e) => return Err(format!("Failed to wait for script: {}", e)),
+        }
+    };
+    for reader in readers {
+        let _ = reader.join();
+    }
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("Exited with code {}", status.code().unwrap_or(-1)))
+    }
+}
@@ -0,0 +1,331 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+use log::{info, warn};
+use reqwest::blocking::{Client, ClientBuilder, RequestBuilder, Response};
+use reqwest::header::{HeaderMap, COOKIE, RETRY_AFTER, USER_AGENT};
+use reqwest::{Certificate, NoProxy, Proxy, StatusCode, Url};
+use serde_json::json;
+use crate::error::SeedToolsError;
+use crate::types::{HttpConfig, NetworkConfig};
+
+static CONFIG: OnceLock<HttpConfig> = OnceLock::new();
+static NETWORK: OnceLock<NetworkConfig> = OnceLock::new();
+// Cookies and user agent FlareSolverr obtained per host; clearance cookies only work with that user agent
+static SOLVED: OnceLock<Mutex<HashMap<String, (String, String)>>> = OnceLock::new();
+static CLIENT: OnceLock<Client> = OnceLock::new();
+// Earliest time the next request to each rate limited API may go out
+static NEXT_SLOT: OnceLock<Mutex<HashMap<String, Instant>>> = OnceLock::new();
+
+/// Applies the `http` and `network` sections of config.yaml to all later requests. Call once at startup.
+pub fn init(config: &HttpConfig, network: &NetworkConfig) {
+    let _ = CONFIG.set(config.clone());
+    let _ = NETWORK.set(network.clone());
+}
+
+fn config() -> &'static HttpConfig {
+    CONFIG.get_or_init(HttpConfig::default)
+}
+
+/// The `network` section: proxy, CA bundle and default user agent.
+pub fn network() -> &'static NetworkConfig {
+    NETWORK.get_or_init(NetworkConfig::default)
+}
+
+/// The PEM blocks of every certificate in `path`.
+pub fn ca_certificates_pem(path: &str) -> Result<Vec<String>, String> {
+    let bundle = std::fs::read_to_string(path).map_err(|e| format!("Failed to read CA bundle '{}': {}", path, e))?;
+    let end_marker = "-----END CERTIFICATE-----";
+    let certificates: Vec<String> = bundle
+        .split_inclusive(end_marker)
+        .filter_map(|block| block.find("-----BEGIN CERTIFICATE-----").map(|start| block[start..].to_string()))
+        .filter(|block| block.ends_with(end_marker))
+        .collect();
+    if certificates.is_empty() {
+        return Err(format!("No PEM certificates found in '{}'", path));
+    }
+    Ok(certificates)
+}
+
+/// A client builder with the `network` proxy, CA bundle and user agent. Every outbound HTTP
+/// client starts from this one.
+pub fn client_builder() -> ClientBuilder {
+    let network = network();
+    let mut builder = Client::builder();
+    if let Some(user_agent) = &network.user_agent {
+        builder = builder.user_agent(user_agent.as_str());
+    }
+    if let Some(proxy_url) = network.proxy.as_deref().filter(|url| !url.is_empty()) {
+        match Proxy::all(proxy_url) {
+            Ok(proxy) => builder = builder.proxy(proxy.no_proxy(NoProxy::from_string(&network.no_proxy.join(",")))),
+            Err(e) => warn!("Ignoring invalid proxy '{}': {}", proxy_url, e),
+        }
+    }
+    if let Some(path) = &network.ca_bundle {
+        match ca_certificates_pem(path) {
+            Ok(certificates) => {
+                for pem in certificates {
+                    match Certificate::from_pem(pem.as_bytes()) {
+                        Ok(certificate) => builder = builder.add_root_certificate(certificate),
+                        Err(e) => warn!("Skipping a certificate in '{}': {}", path, e),
+                    }
+                }
+            }
+            Err(e) => warn!("{}", e),
+        }
+    }
+    builder
+}
+
+fn solved() -> &'static Mutex<HashMap<String, (String, String)>> {
+    SOLVED.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// The shared client, with the configured `timeout_secs`.
+pub fn client() -> &'static Client {
+    CLIENT.get_or_init(|| {
+        client_builder()
+            .timeout(Duration::from_secs(config().timeout_secs))
+            .build()
+            .unwrap_or_else(|e| {
+                warn!("Failed to build HTTP client, using defaults: {}", e);
+                Client::new()
+            })
+    })
+}
+
+/// Waits until a request to `api` fits its `rate_limits` entry.
+fn throttle(api: &str) {
+    let Some(per_second) = config().rate_limits.get(api).filter(|limit| **limit > 0.0) else {
+        return;
+    };
+    let interval = Duration::from_secs_f64(1.0 / per_second);
+    let wait = {
+        let mut slots = NEXT_SLOT.get_or_init(|| Mutex::new(HashMap::new())).lock().unwrap();
+        let now = Instant::now();
+        let slot = slots.get(api).copied().filter(|slot| *slot > now).unwrap_or(now);
+        slots.insert(api.to_string(), slot + interval);
+        slot - now
+    };
+    if !wait.is_zero() {
+        thread::sleep(wait);
+    }
+}
+
+fn backoff(attempt: u32) -> Duration {
+    let retry = &config().retry;
+    let delay = retry.initial_backoff_ms.saturating_mul(1u64 << attempt.min(16));
+    Duration::from_millis(delay.min(retry.max_backoff_ms))
+}
+
+/// Sends an API request built by `build`, retrying connection errors, timeouts, 429s and 5xx
+/// responses with exponential backoff (or the server's Retry-After) up to `retry.max_retries`
+/// times. Only use it for requests that are safe to repeat; `api` names the API for
+/// `rate_limits` and metrics.
+pub fn send_with_retry(api: &str, build: impl Fn(&Client) -> RequestBuilder) -> Result<Response, SeedToolsError> {
+    if network().offline {
+        return Err(SeedToolsError::tracker(api, None, format!("Not calling {} in offline mode", api)));
+    }
+    let max_retries = config().retry.max_retries;
+    let mut attempt = 0;
+    loop {
+        throttle(api);
+        let result = build(client()).send();
+        crate::metrics::record_request(api, result.as_ref().is_ok_and(|r| r.status().is_success()));
+        let (error, retry_after) = match result {
+            Ok(response) if response.status().as_u16() == 429 || response.status().is_server_error() => {
+                let retry_after = response
+                    .headers()
+                    .get(RETRY_AFTER)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| value.trim().parse().ok())
+                    .map(Duration::from_secs);
+                if attempt >= max_retries {
+                    return Ok(response);
+                }
+                (format!("HTTP {}", response.status()), retry_after)
+            }
+            Ok(response) => return Ok(response),
+            Err(e) if (e.is_connect() || e.is_timeout() || e.is_request()) && attempt < max_retries => (e.to_string(), None),
+            Err(e) => return Err(SeedToolsError::request(api, e)),
+        };
+        let delay = retry_after.unwrap_or_else(|| backoff(attempt)).min(Duration::from_millis(config().retry.max_backoff_ms));
+        attempt += 1;
+        warn!("{} request failed ({}); retrying in {}ms ({}/{})", api, error, delay.as_millis(), attempt, max_retries);
+        thread::sleep(delay);
+    }
+}
+
+pub(crate) fn host(url: &str) -> String {
+    Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(|host| host.to_string()))
+        .unwrap_or_default()
+}
+
+/// Whether a response body is a Cloudflare or DDoS-Guard challenge page.
+pub fn is_challenge_page(body: &str) -> bool {
+    let body = body.to_lowercase();
+    body.contains("cf-chl")
+        || body.contains("just a moment...")
+        || body.contains("attention required! | cloudflare")
+        || body.contains("ddos-guard")
+}
+
+/// Configured cookies for `host`, matching the domain itself and its subdomains.
+fn configured_cookies(host: &str) -> Vec<String> {
+    config()
+        .cookies
+        .iter()
+        .filter(|(domain, _)| host == domain.as_str() || host.ends_with(&format!(".{}", domain)))
+        .map(|(_, cookie)| cookie.clone())
+        .collect()
+}
+
+/// Adds the configured user agent, headers and cookies (plus any FlareSolverr clearance) for `url`.
+pub fn apply(mut request: RequestBuilder, url: &str) -> RequestBuilder {
+    let config = config();
+    let host = host(url);
+    let solved = solved().lock().unwrap().get(&host).cloned();
+
+    let user_agent = solved.as_ref().map(|(_, user_agent)| user_agent.clone()).or_else(|| config.user_agent.clone());
+    if let Some(user_agent) = user_agent {
+        request = request.header(USER_AGENT, user_agent);
+    }
+    for (name, value) in &config.headers {
+        request = request.header(name.as_str(), value.as_str());
+    }
+    let mut cookies = configured_cookies(&host);
+    if let Some((cookie, _)) = solved {
+        cookies.push(cookie);
+    }
+    if !cookies.is_empty() {
+        request = request.header(COOKIE, cookies.join("; "));
+    }
+    request
+}
+
+/// The same passthrough as `apply`, as arguments for uploads that go through curl.
+pub fn curl_args(url: &str) -> Vec<String> {
+    let config = config();
+    let mut args = Vec::new();
+    if let Some(user_agent) = &config.user_agent {
+        args.extend(["-A".to_string(), user_agent.clone()]);
+    }
+    for (name, value) in &config.headers {
+        args.extend(["-H".to_string(), format!("{}: {}", name, value)]);
+    }
+    let cookies = configured_cookies(&host(url));
+    if !cookies.is_empty() {
+        args.extend(["-b".to_string(), cookies.join("; ")]);
+    }
+    let network = network();
+    if let Some(proxy) = network.proxy.as_deref().filter(|proxy| !proxy.is_empty()) {
+        args.extend(["-x".to_string(), proxy.to_string()]);
+        if !network.no_proxy.is_empty() {
+            args.extend(["--noproxy".to_string(), network.no_proxy.join(",")]);
+        }
+    }
+    if let Some(ca_bundle) = &network.ca_bundle {
+        args.extend(["--cacert".to_string(), ca_bundle.clone()]);
+    }
+    if let (None, Some(user_agent)) = (&config.user_agent, &network.user_agent) {
+        args.extend(["-A".to_string(), user_agent.clone()]);
+    }
+    args
+}
+
+/// Asks FlareSolverr to pass the challenge at `url` and remembers the cookies it got.
+fn solve_challenge(endpoint: &str, url: &str) -> Result<(), String> {
+    let timeout_ms = config().flaresolverr_timeout_ms;
+    info!("Solving challenge for {} via FlareSolverr", host(url));
+    let response: serde_json::Value = client_builder()
+        .timeout(Duration::from_millis(timeout_ms + 10_000))
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?
+        .post(endpoint)
+        .json(&json!({ "cmd": "request.get", "url": url, "maxTimeout": timeout_ms }))
+        .send()
+        .map_err(|e| format!("Failed to reach FlareSolverr at {}: {}", endpoint, e))?
+        .json()
+        .map_err(|e| format!("Failed to parse FlareSolverr response: {}", e))?;
+    if response["status"] != "ok" {
+        return Err(format!("FlareSolverr could not solve the challenge: {}", response["message"]));
+    }
+
+    let solution = &response["solution"];
+    let cookies = solution["cookies"]
+        .as_array()
+        .map(|cookies| {
+            cookies
+                .iter()
+                .filter_map(|cookie| Some(format!("{}={}", cookie["name"].as_str()?, cookie["value"].as_str()?)))
+                .collect::<Vec<_>>()
+                .join("; ")
+        })
+        .unwrap_or_default();
+    let user_agent = solution["userAgent"].as_str().unwrap_or_default().to_string();
+    solved().lock().unwrap().insert(host(url), (cookies, user_agent));
+    Ok(())
+}
+
+/// Sends a tracker request and returns its status and body. When the response is a challenge
+/// page and `flaresolverr_url` is configured, solves it and retries once. `build` runs again
+/// for the retry, so it must create a fresh request (multipart forms cannot be reused).
+pub fn send_text(
+    url: &str,
+    build: impl Fn(&Client) -> Result<RequestBuilder, SeedToolsError>,
+) -> Result<(StatusCode, String), SeedToolsError> {
+    send_with_headers(url, build).map(|(status, _, body)| (status, body))
+}
+
+/// `send_text`, also returning the response headers (e.g. for an ETag).
+pub fn send_with_headers(
+    url: &str,
+    build: impl Fn(&Client) -> Result<RequestBuilder, SeedToolsError>,
+) -> Result<(StatusCode, HeaderMap, String), SeedToolsError> {
+    if network().offline {
+        return Err(SeedToolsError::tracker(&host(url), None, format!("Not calling {} in offline mode", host(url))));
+    }
+    let client = client();
+    let mut retried = false;
+    let mut connect_retries = 0;
+    loop {
+        // Uploads are not repeated once sent; only failures to connect are retried
+        let response = match apply(build(client)?, url).send() {
+            Ok(response) => response,
+            Err(e) if e.is_connect() && connect_retries < config().retry.max_retries => {
+                crate::metrics::record_request(&host(url), false);
+                let delay = backoff(connect_retries);
+                connect_retries += 1;
+                warn!("Failed to connect to {} ({}); retrying in {}ms", host(url), e, delay.as_millis());
+                thread::sleep(delay);
+                continue;
+            }
+            Err(e) => {
+                crate::metrics::record_request(&host(url), false);
+                return Err(SeedToolsError::request(&host(url), e));
+            }
+        };
+        let status = response.status();
+        crate::metrics::record_request(&host(url), status.is_success() || status == StatusCode::NOT_MODIFIED);
+        let headers = response.headers().clone();
+        let body = response.text().unwrap_or_else(|_| "Failed to read response body".to_string());
+
+        let challenged = matches!(status.as_u16(), 403 | 429 | 503) && is_challenge_page(&body);
+        if challenged && !retried
+            && let Some(endpoint) = &config().flaresolverr_url
+        {
+            warn!("{} answered with a challenge page (HTTP {}), trying FlareSolverr", host(url), status);
+            match solve_challenge(endpoint, url) {
+                Ok(()) => {
+                    retried = true;
+                    continue;
+                }
+                Err(e) => warn!("{}", e),
+            }
+        }
+        return Ok((status, headers, body));
+    }
+}
@@ -0,0 +1,109 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use log::{info, warn};
+use serde_json::Value;
+use crate::types::ImdbConfig;
+
+// IMDb fallback for titles TMDB does not know: the IMDb suggestion API (what imdb.com's search
+// box uses) and, when configured, a local copy of the title.basics dataset from
+// https://datasets.imdbws.com. Only the IMDb ID comes from here; TMDB and TVDB stay 0.
+
+/// Title words compared between the release and IMDb: lowercase letters and digits.
+fn normalize(title: &str) -> String {
+    title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Whether an IMDb title type fits the release type: feature films for movies, series for TV.
+fn kind_matches(kind: &str, release_type: &str) -> bool {
+    match release_type {
+        "movie" => matches!(kind, "movie" | "tvMovie" | "video" | "feature" | "TV movie"),
+        "tv" | "boxset" => matches!(kind, "tvSeries" | "tvMiniSeries" | "TV series" | "TV mini-series"),
+        _ => true,
+    }
+}
+
+/// Whether IMDb's year fits the release's; a year apart is allowed for festival and
+/// release-date differences.
+fn year_matches(imdb_year: Option<u32>, year: Option<u32>) -> bool {
+    match (imdb_year, year) {
+        (Some(imdb_year), Some(year)) => imdb_year.abs_diff(year) <= 1,
+        _ => true,
+    }
+}
+
+/// The IMDb ID (without "tt") of `title` from the suggestion API.
+fn suggest(title: &str, year: Option<u32>, release_type: &str) -> Result<Option<String>, String> {
+    let query = normalize(title);
+    let first = query.chars().next().ok_or("Empty title")?;
+    let url = format!("https://v3.sg.media-imdb.com/suggestion/{}/{}.json", first, urlencoding::encode(&query));
+    let response = crate::http::client().get(&url).send().map_err(|e| format!("Failed to query IMDb suggestions: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("IMDb suggestions failed: HTTP {}", response.status()));
+    }
+    let json: Value = response.json().map_err(|e| format!("Failed to parse IMDb suggestions: {}", e))?;
+    let found = json["d"].as_array().into_iter().flatten().find(|entry| {
+        let kind = entry["qid"].as_str().or_else(|| entry["q"].as_str()).unwrap_or_default();
+        entry["id"].as_str().is_some_and(|id| id.starts_with("tt"))
+            && normalize(entry["l"].as_str().unwrap_or_default()) == query
+            && kind_matches(kind, release_type)
+            && year_matches(entry["y"].as_u64().map(|y| y as u32), year)
+    });
+    Ok(found.and_then(|entry| entry["id"].as_str()).map(|id| id.trim_start_matches("tt").to_string()))
+}
+
+/// The IMDb ID (without "tt") of `title` from a title.basics.tsv(.gz) file. The file is read
+/// line by line, so the full dataset does not have to fit in memory.
+fn search_dataset(path: &str, title: &str, year: Option<u32>, release_type: &str) -> Result<Option<String>, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open IMDb dataset '{}': {}", path, e))?;
+    let reader: Box<dyn Read> = if path.ends_with(".gz") { Box::new(flate2::read::GzDecoder::new(file)) } else { Box::new(file) };
+    let query = normalize(title);
+    // tconst, titleType, primaryTitle, originalTitle, isAdult, startYear, endYear, runtimeMinutes, genres
+    for line in BufReader::new(reader).lines().skip(1) {
+        let line = line.map_err(|e| format!("Failed to read IMDb dataset '{}': {}", path, e))?;
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() < 6 || !kind_matches(fields[1], release_type) {
+            continue;
+        }
+        if (normalize(fields[2]) == query || normalize(fields[3]) == query) && year_matches(fields[5].parse().ok(), year) {
+            return Ok(Some(fields[0].trim_start_matches("tt").to_string()));
+        }
+    }
+    Ok(None)
+}
+
+/// `imdb_id`, or when TMDB found nothing (`tmdb_id` 0) and the fallback is enabled, the IMDb ID
+/// of `title` from the suggestion API or the dataset. Lookup failures are logged, not returned,
+/// as the upload can still go out without it.
+pub fn fill_missing(imdb_id: Option<String>, tmdb_id: u32, title: &str, year: Option<&str>, release_type: &str, config: &ImdbConfig) -> Option<String> {
+    if imdb_id.is_some() || tmdb_id != 0 || !config.enabled || matches!(release_type, "sport" | "adult") {
+        return imdb_id;
+    }
+    let year = year.and_then(|year| year.parse().ok());
+    if config.suggestions {
+        match suggest(title, year, release_type) {
+            Ok(Some(id)) => {
+                info!("TMDB has no '{}'; IMDb suggests tt{}", title, id);
+                return Some(id);
+            }
+            Ok(None) => {}
+            Err(e) => warn!("{}", e),
+        }
+    }
+    if let Some(dataset) = &config.dataset {
+        match search_dataset(dataset, title, year, release_type) {
+            Ok(Some(id)) => {
+                info!("TMDB has no '{}'; found tt{} in the IMDb dataset", title, id);
+                return Some(id);
+            }
+            Ok(None) => {}
+            Err(e) => warn!("{}", e),
+        }
+    }
+    info!("No IMDb ID found for '{}' either.", title);
+    None
+}
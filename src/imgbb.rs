@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+use log::{info, warn};
+use reqwest::blocking::multipart::Form;
+use crate::types::ImgBBConfig;
+
+/// Start of the error returned when every key stayed rate limited through all retries.
+pub const RATE_LIMITED: &str = "ImgBB rate limit reached";
+
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+static CONFIG: OnceLock<ImgBBConfig> = OnceLock::new();
+static KEYS: OnceLock<Mutex<HashMap<String, KeyState>>> = OnceLock::new();
+
+/// A key's uploads in the last hour, and when it may be used again after a 429.
+#[derive(Default)]
+struct KeyState {
+    uploads: Vec<Instant>,
+    limited_until: Option<Instant>,
+}
+
+enum Attempt {
+    Uploaded(String, String), // Image and thumbnail URL
+    RateLimited(Option<Duration>), // With the Retry-After the server sent
+    Failed(String),
+}
+
+/// Applies the `imgbb` section of config.yaml (extra keys, budgets and retries). Call once at startup.
+pub fn init(config: &ImgBBConfig) {
+    let _ = CONFIG.set(config.clone());
+}
+
+fn keys() -> &'static Mutex<HashMap<String, KeyState>> {
+    KEYS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn key_hint(key: &str) -> String {
+    format!("...{}", &key[key.len().saturating_sub(4)..])
+}
+
+/// The first of `candidates` that is not cooling down after a 429 and has budget left this hour.
+fn pick_key(candidates: &[String], uploads_per_hour: Option<u32>) -> Option<String> {
+    let now = Instant::now();
+    let mut keys = keys().lock().unwrap();
+    candidates
+        .iter()
+        .find(|key| {
+            let state = keys.entry(key.to_string()).or_default();
+            state.uploads.retain(|at| now.duration_since(*at) < Duration::from_secs(3600));
+            state.limited_until.is_none_or(|until| until <= now)
+                && uploads_per_hour.is_none_or(|budget| (state.uploads.len() as u32) < budget)
+        })
+        .cloned()
+}
+
+fn upload_once(image_path: &str, api_key: &str) -> Attempt {
+    let form = match Form::new().file("image", image_path) {
+        Ok(form) => form,
+        Err(e) => return Attempt::Failed(format!("Failed to attach image file: {}", e)),
+    };
+    let url = format!("https://api.imgbb.com/1/upload?key={}", api_key);
+    let response = crate::http::client().post(&url).multipart(form).send();
+    crate::metrics::record_request("imgbb", response.as_ref().is_ok_and(|r| r.status().is_success()));
+    let response = match response {
+        Ok(response) => response,
+        Err(e) => return Attempt::Failed(format!("Failed to upload image to ImgBB: {}", e)),
+    };
+
+    let status = response.status();
+    let retry_after = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok())
+        .map(Duration::from_secs);
+    let body = response.text().unwrap_or_else(|_| "Failed to read response body".to_string());
+    // ImgBB answers some rate limits with a 400 and a message instead of a 429
+    if status.as_u16() == 429 || body.to_lowercase().contains("rate limit") {
+        return Attempt::RateLimited(retry_after);
+    }
+    if !status.is_success() {
+        log::error!("ImgBB API Error: HTTP Status: {}, Response: {}", status, body);
+        return Attempt::Failed(format!("Failed to upload image to ImgBB. HTTP Status: {}. Response: {}", status, body));
+    }
+
+    let json: serde_json::Value = match serde_json::from_str(&body) {
+        Ok(json) => json,
+        Err(e) => return Attempt::Failed(format!("Failed to parse ImgBB response: {}", e)),
+    };
+    match (json["data"]["image"]["url"].as_str(), json["data"]["thumb"]["url"].as_str()) {
+        (Some(image), Some(thumb)) => Attempt::Uploaded(image.to_string(), thumb.to_string()),
+        (None, _) => Attempt::Failed("Failed to extract full image URL from ImgBB response".to_string()),
+        (_, None) => Attempt::Failed("Failed to extract thumbnail URL from ImgBB response".to_string()),
+    }
+}
+
+/// Uploads an image to ImgBB and returns its URL and thumbnail URL. A rate-limited key rests
+/// while the `extra_api_keys` take over; when all of them are limited or out of their hourly
+/// budget, the upload waits with exponential backoff, up to `max_retries` times.
+pub fn upload(image_path: &str, api_key: &str) -> Result<(String, String), String> {
+    let config = CONFIG.get();
+    let mut candidates = vec![api_key.to_string()];
+    for key in config.map(|config| config.extra_api_keys.as_slice()).unwrap_or_default() {
+        if !key.is_empty() && !candidates.contains(key) {
+            candidates.push(key.clone());
+        }
+    }
+    let max_retries = config.map_or(4, |config| config.max_retries);
+    let uploads_per_hour = config.and_then(|config| config.uploads_per_hour);
+
+    let mut backoff = Duration::from_secs(2);
+    for attempt in 0..=max_retries {
+        while let Some(key) = pick_key(&candidates, uploads_per_hour) {
+            match upload_once(image_path, &key) {
+                Attempt::Uploaded(image, thumb) => {
+                    keys().lock().unwrap().entry(key).or_default().uploads.push(Instant::now());
+                    info!("ImgBB Upload Successful: full_image_url={}, thumb_url={}", image, thumb);
+                    return Ok((image, thumb));
+                }
+                Attempt::RateLimited(retry_after) => {
+                    let rest = retry_after.unwrap_or(backoff).min(MAX_BACKOFF * 10);
+                    warn!("ImgBB rate limited key {}; resting it for {}s", key_hint(&key), rest.as_secs());
+                    keys().lock().unwrap().entry(key).or_default().limited_until = Some(Instant::now() + rest);
+                }
+                Attempt::Failed(e) => return Err(e),
+            }
+        }
+        if attempt < max_retries {
+            warn!(
+                "Every ImgBB key is rate limited or over its budget; retrying in {}s ({}/{})",
+                backoff.as_secs(),
+                attempt + 1,
+                max_retries
+            );
+            thread::sleep(backoff);
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+        }
+    }
+    Err(format!("{} for '{}' after {} retries", RATE_LIMITED, image_path, max_retries))
+}
@@ -1,140 +1,468 @@
-use irc::client::Client;
+use irc::client::{Client, ClientStream};
 use irc::client::prelude::*;
-use std::io::{self, Write}; // Use `std::io` for synchronous I/O
+use irc::client::data::ProxyType;
+use base64::Engine;
 use futures_util::stream::StreamExt;
 use tokio::sync::{mpsc, Mutex};
 use tui::{
-    backend::CrosstermBackend,
+    backend::{Backend, CrosstermBackend},
     layout::{Constraint, Direction, Layout},
-    style::{Color, Style},
+    style::{Color, Modifier, Style},
     text::{Span, Spans},
-    widgets::{Block, Borders, Paragraph},
-    Terminal,
+    widgets::{Block, Borders, List, ListItem, Paragraph, Tabs},
+    Frame, Terminal,
 };
 use crossterm::{
-    event::{self, Event, KeyCode},
+    event::{Event, KeyCode, KeyModifiers},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode},
 };
 use serde::Deserialize;
-use std::{fs, sync::Arc};
-use crate::types::SeedpoolGeneralConfig;
-use std::collections::HashMap;
+use std::{fs, path::Path, sync::Arc};
+use crate::types::{IrcConfig, IrcNetworkConfig, QbittorrentConfig, SeedpoolGeneralConfig, UploadAnnounceConfig};
+use crate::announce::{load_announce_config, run_announce_action, AnnounceAction, AnnounceConfig, AnnounceParser};
+use crate::profile::config_path;
+use crate::terminal::TerminalGuard;
+use std::collections::{BTreeSet, HashMap};
 
 #[derive(Deserialize)]
 struct SeedpoolConfig {
     general: SeedpoolGeneralConfig, // Use the struct from types.rs
 }
 
+#[derive(Deserialize)]
+struct QbittorrentOnlyConfig {
+    qbittorrent: Vec<QbittorrentConfig>, // Only needed for announce cross-seeding
+}
+
+/// Message history key: (network name, channel).
+type MessageKey = (String, String);
+
+/// Sent from the network reader tasks to the UI loop.
+enum IrcEvent {
+    Message { network: String, channel: String, text: String, nick: Option<String> },
+    Names { network: String, channel: String, nicks: Vec<String> }, // RPL_NAMREPLY
+}
+
+/// State of the IRC window: joined channels, history, unread counts and known nicks per network.
+struct IrcView {
+    network_names: Vec<String>,
+    active_network: usize,                        // Index into `network_names`
+    channels: HashMap<String, Vec<String>>,       // Channels per network, in sidebar order
+    active_channels: HashMap<String, String>,     // Active channel per network
+    messages: HashMap<MessageKey, Vec<String>>,
+    unread: HashMap<MessageKey, usize>,
+    nicks: HashMap<MessageKey, BTreeSet<String>>, // Nicks seen per channel, for tab completion
+}
+
+impl IrcView {
+    fn network(&self) -> &str {
+        &self.network_names[self.active_network]
+    }
+
+    fn active_channel(&self) -> String {
+        self.active_channels.get(self.network()).cloned().unwrap_or_default()
+    }
+
+    fn active_key(&self) -> MessageKey {
+        (self.network().to_string(), self.active_channel())
+    }
+
+    fn network_channels(&self) -> &[String] {
+        self.channels.get(self.network()).map(|channels| channels.as_slice()).unwrap_or(&[])
+    }
+
+    fn add_channel(&mut self, network: &str, channel: &str) {
+        let channels = self.channels.entry(network.to_string()).or_default();
+        if !channels.iter().any(|c| c.eq_ignore_ascii_case(channel)) {
+            channels.push(channel.to_string());
+        }
+    }
+
+    fn remove_channel(&mut self, network: &str, channel: &str) {
+        if let Some(channels) = self.channels.get_mut(network) {
+            channels.retain(|c| !c.eq_ignore_ascii_case(channel));
+        }
+        self.unread.remove(&(network.to_string(), channel.to_string()));
+        if self.active_channels.get(network).is_some_and(|c| c.eq_ignore_ascii_case(channel)) {
+            let next = self
+                .channels
+                .get(network)
+                .and_then(|channels| channels.first())
+                .cloned()
+                .unwrap_or_else(|| "#server".to_string());
+            self.active_channels.insert(network.to_string(), next);
+        }
+    }
+
+    /// Makes `channel` the active channel of the active network and marks it read.
+    fn switch_channel(&mut self, channel: &str) {
+        let network = self.network().to_string();
+        self.add_channel(&network, channel);
+        self.unread.remove(&(network.clone(), channel.to_string()));
+        self.active_channels.insert(network, channel.to_string());
+    }
+
+    fn switch_network(&mut self) {
+        self.active_network = (self.active_network + 1) % self.network_names.len();
+        let key = self.active_key();
+        self.unread.remove(&key);
+    }
+
+    fn push_message(&mut self, network: &str, channel: &str, text: String) {
+        self.add_channel(network, channel);
+        let key = (network.to_string(), channel.to_string());
+        let channel_messages = self.messages.entry(key.clone()).or_default();
+        channel_messages.push(text);
+        if channel_messages.len() > 100 {
+            channel_messages.remove(0); // Keep the message history manageable
+        }
+        // Server notices are too chatty to count as unread
+        if key != self.active_key() && channel != "#server" {
+            *self.unread.entry(key).or_insert(0) += 1;
+        }
+    }
+
+    fn add_nicks(&mut self, network: &str, channel: &str, nicks: Vec<String>) {
+        self.nicks
+            .entry((network.to_string(), channel.to_string()))
+            .or_default()
+            .extend(nicks);
+    }
+
+    /// Completion candidates for `word`: channels of the active network when it starts
+    /// with '#', otherwise nicks seen in the active channel.
+    fn completions(&self, word: &str) -> Vec<String> {
+        let word = word.to_lowercase();
+        let candidates: Vec<String> = if word.starts_with('#') {
+            self.network_channels().to_vec()
+        } else {
+            self.nicks
+                .get(&self.active_key())
+                .map(|nicks| nicks.iter().cloned().collect())
+                .unwrap_or_default()
+        };
+        candidates
+            .into_iter()
+            .filter(|candidate| candidate.to_lowercase().starts_with(&word))
+            .collect()
+    }
+}
+
+/// Everything a network reader task needs to act on announce channel messages.
+#[derive(Clone)]
+struct AnnounceContext {
+    parser: Arc<AnnounceParser>,
+    default_network: String, // Network that rules without `network` belong to
+    data_dirs: Arc<Vec<String>>,
+    qbittorrent_configs: Arc<Vec<QbittorrentConfig>>,
+    api_key: String,
+}
+
+/// Loads `config/irc.yaml`. Without the file, connects to the Seedpool network like earlier versions.
+pub fn load_irc_config(path: &Path, username: &str) -> Result<IrcConfig, Box<dyn std::error::Error>> {
+    if !path.exists() {
+        return Ok(IrcConfig {
+            networks: vec![IrcNetworkConfig {
+                name: "seedpool".to_string(),
+                server: "irc.seedpool.org".to_string(),
+                port: 6697,
+                tls: true,
+                nickname: Some(username.to_string()),
+                channels: vec!["#lobby".to_string()],
+                nickserv_password: None,
+                seedserv_auth: true,
+            }],
+            upload_announce: None,
+        });
+    }
+    let config: IrcConfig = serde_yaml::from_str(&crate::secrets::read_config(path)?)?;
+    if config.networks.is_empty() {
+        return Err(format!("{} defines no networks", path.display()).into());
+    }
+    Ok(config)
+}
+
+/// Routes an IRC connection through the `network` proxy (socks5 only, since IRC is not HTTP)
+/// and trusts the first certificate of its CA bundle, which the TLS connector wants as DER.
+fn apply_network_settings(config: &mut Config, server: &str) {
+    let network = crate::http::network();
+    let bypassed = network
+        .no_proxy
+        .iter()
+        .any(|host| server.eq_ignore_ascii_case(host) || server.to_lowercase().ends_with(&format!(".{}", host.to_lowercase())));
+    if let Some(proxy) = network.proxy.as_deref().filter(|proxy| !proxy.is_empty() && !bypassed) {
+        match reqwest::Url::parse(proxy) {
+            Ok(url) if url.scheme().starts_with("socks5") => {
+                config.proxy_type = Some(ProxyType::Socks5);
+                config.proxy_server = url.host_str().map(str::to_string);
+                config.proxy_port = Some(url.port().unwrap_or(1080));
+                if !url.username().is_empty() {
+                    config.proxy_username = Some(url.username().to_string());
+                    config.proxy_password = url.password().map(str::to_string);
+                }
+            }
+            Ok(_) => log::warn!("IRC can only use socks5 proxies; connecting to {} directly", server),
+            Err(e) => log::warn!("Ignoring invalid proxy '{}': {}", proxy, e),
+        }
+    }
+    if let Some(ca_bundle) = &network.ca_bundle {
+        let der = crate::http::ca_certificates_pem(ca_bundle).and_then(|certificates| {
+            let base64: String = certificates[0].lines().filter(|line| !line.starts_with("-----")).collect();
+            base64::engine::general_purpose::STANDARD
+                .decode(base64.trim())
+                .map_err(|e| format!("Invalid certificate in '{}': {}", ca_bundle, e))
+        });
+        let cert_path = std::env::temp_dir().join("seed-tools-irc-ca.der");
+        match der.and_then(|der| fs::write(&cert_path, der).map_err(|e| e.to_string())) {
+            Ok(()) => config.cert_path = Some(cert_path.to_string_lossy().to_string()),
+            Err(e) => log::warn!("Not using the CA bundle for IRC: {}", e),
+        }
+    }
+}
+
+/// Connects to `network`, authenticates, joins `channel`, posts `message` and disconnects.
+/// Used for one-off announcements outside the interactive IRC mode.
+pub async fn post_message(
+    network: &IrcNetworkConfig,
+    nickname: &str,
+    passkey: Option<&str>,
+    channel: &str,
+    message: &str,
+) -> Result<(), String> {
+    let mut config = Config {
+        nickname: Some(nickname.to_string()),
+        alt_nicks: vec![format!("{}_announce", nickname)], // The interactive client may be using the nick
+        server: Some(network.server.clone()),
+        port: Some(network.port),
+        use_tls: Some(network.tls),
+        ..Default::default()
+    };
+    apply_network_settings(&mut config, &network.server);
+    let mut client = Client::from_config(config)
+        .await
+        .map_err(|e| format!("Failed to connect to {}: {}", network.name, e))?;
+    client.identify().map_err(|e| format!("Failed to identify on {}: {}", network.name, e))?;
+    let mut stream = client.stream().map_err(|e| format!("Failed to read from {}: {}", network.name, e))?;
+
+    let session = async {
+        while let Some(message_result) = stream.next().await {
+            let incoming = message_result.map_err(|e| format!("IRC error on {}: {}", network.name, e))?;
+            match &incoming.command {
+                Command::Response(Response::RPL_WELCOME, _) => {
+                    if let Some(password) = &network.nickserv_password {
+                        client
+                            .send_privmsg("NickServ", format!("IDENTIFY {}", password))
+                            .map_err(|e| e.to_string())?;
+                    }
+                    if let (true, Some(passkey)) = (network.seedserv_auth, passkey) {
+                        client.send_privmsg("SeedServ", passkey).map_err(|e| e.to_string())?;
+                    }
+                    client.send_join(channel).map_err(|e| e.to_string())?;
+                }
+                // End of the NAMES list: the join went through
+                Command::Response(Response::RPL_ENDOFNAMES, _) => {
+                    client.send_privmsg(channel, message).map_err(|e| e.to_string())?;
+                    client.send_quit("").map_err(|e| e.to_string())?;
+                    return Ok(());
+                }
+                Command::Response(
+                    response @ (Response::ERR_NOSUCHCHANNEL
+                    | Response::ERR_CANNOTSENDTOCHAN
+                    | Response::ERR_CHANNELISFULL
+                    | Response::ERR_INVITEONLYCHAN
+                    | Response::ERR_BANNEDFROMCHAN
+                    | Response::ERR_BADCHANNELKEY),
+                    args,
+                ) => {
+                    return Err(format!("{} refused the announcement: {:?} {}", network.name, response, args.join(" ")));
+                }
+                _ => {}
+            }
+        }
+        Err(format!("Disconnected from {} before the announcement was sent", network.name))
+    };
+    tokio::time::timeout(std::time::Duration::from_secs(60), session)
+        .await
+        .map_err(|_| format!("Timed out announcing on {}", network.name))?
+}
+
+/// Posts the `upload_announce` line from `config/irc.yaml` for a finished upload. Does nothing
+/// when no announce channel is configured. Blocking: runs the IRC session on its own thread.
+pub fn announce_upload(general: &SeedpoolGeneralConfig, name: &str, category: &str, size: &str, link: &str) -> Result<(), String> {
+    let exe_dir = std::env::current_exe()
+        .map_err(|e| format!("Failed to get executable path: {}", e))?
+        .parent()
+        .ok_or("Failed to determine executable directory")?
+        .to_path_buf();
+    let irc_config = load_irc_config(&config_path(&exe_dir.join("config"), "irc.yaml"), &general.username).map_err(|e| e.to_string())?;
+    let Some(UploadAnnounceConfig { network, channel, template }) = irc_config.upload_announce.clone() else {
+        return Ok(());
+    };
+    let network = match &network {
+        Some(name) => irc_config
+            .networks
+            .iter()
+            .find(|n| n.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| format!("upload_announce network '{}' is not in irc.yaml", name))?,
+        None => &irc_config.networks[0],
+    }
+    .clone();
+    let message = template
+        .replace("{name}", name)
+        .replace("{category}", category)
+        .replace("{size}", size)
+        .replace("{link}", link);
+    let nickname = network.nickname.clone().unwrap_or_else(|| general.username.clone());
+    let passkey = general.passkey.clone();
+
+    // The upload code may already run inside a Tokio runtime, so use a separate one on its own thread
+    std::thread::spawn(move || {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| format!("Failed to start IRC runtime: {}", e))?;
+        runtime.block_on(post_message(&network, &nickname, Some(&passkey), &channel, &message))?;
+        log::info!("Announced upload in {} on {}: {}", channel, network.name, message);
+        Ok(())
+    })
+    .join()
+    .map_err(|_| "IRC announcement thread panicked".to_string())?
+}
+
 pub async fn launch_irc_client() -> Result<(), Box<dyn std::error::Error>> {
     // Dynamically determine the config path relative to the executable directory
     let exe_dir = std::env::current_exe()?
         .parent()
         .ok_or("Failed to determine executable directory")?
         .to_path_buf();
-    let config_path = exe_dir.join("config/trackers/seedpool.yaml");
+    let config_dir = exe_dir.join("config");
+    let seedpool_config_path = config_path(&config_dir, "trackers/seedpool.yaml");
 
     // Load the Seedpool configuration from the YAML file
-    let seedpool_config: SeedpoolConfig = serde_yaml::from_str(&fs::read_to_string(&config_path)?)?;
+    let seedpool_config: SeedpoolConfig = serde_yaml::from_str(&crate::secrets::read_config(&seedpool_config_path)?)?;
     let passkey = seedpool_config.general.passkey.clone(); // Get the passkey
     let username = seedpool_config.general.username.clone(); // Get the username
-
-    // Create the IRC client configuration
-    let config = Config {
-        nickname: Some(username.clone()), // Use the username as the nickname
-        server: Some("irc.seedpool.org".to_string()),
-        port: Some(6697), // Specify the port for TLS
-        use_tls: Some(true), // Enable TLS
-        channels: vec!["#lobby".to_string()],
-        ..Default::default()
+    let api_key = seedpool_config.general.api_key.clone();
+
+    // Load the networks to connect to
+    let irc_config = load_irc_config(&config_path(&config_dir, "irc.yaml"), &username)?;
+    let default_network = irc_config.networks[0].name.clone();
+
+    // Load announce channel rules; a broken file only disables announce parsing
+    let (announce_config, announce_parser) = match load_announce_config(&config_path(&config_dir, "announce.yaml"))
+        .and_then(|config| AnnounceParser::new(&config).map(|parser| (config, parser)))
+    {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            log::error!("Announce parsing disabled: {}", e);
+            (AnnounceConfig::default(), AnnounceParser::new(&AnnounceConfig::default())?)
+        }
+    };
+    let qbittorrent_configs: Vec<QbittorrentConfig> =
+        if announce_config.rules.iter().any(|rule| rule.action == AnnounceAction::CrossSeed) {
+            crate::secrets::read_config(config_path(&config_dir, "config.yaml"))
+                .ok()
+                .and_then(|content| serde_yaml::from_str::<QbittorrentOnlyConfig>(&content).ok())
+                .map(|config| config.qbittorrent)
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+    let announce = AnnounceContext {
+        parser: Arc::new(announce_parser),
+        default_network: default_network.clone(),
+        data_dirs: Arc::new(announce_config.data_dirs.clone()),
+        qbittorrent_configs: Arc::new(qbittorrent_configs),
+        api_key,
     };
 
-    // Create the IRC client
-    let client = Arc::new(Mutex::new(Client::from_config(config.clone()).await?));
-    client.lock().await.identify()?; // Identify the client with the server
-    println!("Connected to the server as {}.", client.lock().await.current_nickname());
+    // Channels for communication
+    let (tx_display, mut rx_display) = mpsc::channel::<IrcEvent>(100);
+    let (tx_input, mut rx_input) = mpsc::channel::<String>(10);
 
-    // Create a stream for incoming messages
-    let mut stream = client.lock().await.stream()?;
+    // Connect to every network; each one gets its own reader task
+    let mut clients: Vec<(String, Arc<Mutex<Client>>)> = Vec::new();
+    let mut active_channels: HashMap<String, String> = HashMap::new(); // Active channel per network
+    let mut network_channels: HashMap<String, Vec<String>> = HashMap::new();
+    for network in &irc_config.networks {
+        // Join announce channels that belong to this network as well
+        let mut channels = network.channels.clone();
+        for rule in &announce_config.rules {
+            let rule_network = rule.network.as_deref().unwrap_or(&default_network);
+            if rule_network.eq_ignore_ascii_case(&network.name)
+                && !channels.iter().any(|c| c.eq_ignore_ascii_case(&rule.channel))
+            {
+                channels.push(rule.channel.clone());
+            }
+        }
+
+        // Create the IRC client configuration
+        let mut config = Config {
+            nickname: Some(network.nickname.clone().unwrap_or_else(|| username.clone())),
+            server: Some(network.server.clone()),
+            port: Some(network.port),
+            use_tls: Some(network.tls),
+            channels: channels.clone(),
+            ..Default::default()
+        };
+        apply_network_settings(&mut config, &network.server);
+
+        // One unreachable network should not prevent using the others
+        let mut client = match Client::from_config(config).await {
+            Ok(client) => client,
+            Err(e) => {
+                log::error!("Failed to connect to {} ({}:{}): {}", network.name, network.server, network.port, e);
+                println!("Failed to connect to {}: {}", network.name, e);
+                continue;
+            }
+        };
+        client.identify()?; // Identify the client with the server
+        println!("Connected to {} as {}.", network.name, client.current_nickname());
+
+        // Create a stream for incoming messages
+        let stream = client.stream()?;
+        let client = Arc::new(Mutex::new(client));
+        spawn_network_reader(
+            network.clone(),
+            Arc::clone(&client),
+            stream,
+            passkey.clone(),
+            tx_display.clone(),
+            announce.clone(),
+        );
+
+        active_channels.insert(
+            network.name.clone(),
+            channels.first().cloned().unwrap_or_else(|| "#server".to_string()),
+        );
+        network_channels.insert(network.name.clone(), channels);
+        clients.push((network.name.clone(), client));
+    }
+    if clients.is_empty() {
+        return Err("Could not connect to any IRC network.".into());
+    }
 
     // Set up the terminal UI
+    let _terminal_guard = TerminalGuard::new(); // Restores the terminal on panic, signals, or early return
     enable_raw_mode()?; // Enable raw mode for terminal
     let mut stdout = std::io::stdout(); // Use synchronous `std::io::stdout()`
     execute!(stdout, crossterm::terminal::EnterAlternateScreen)?; // Enter alternate screen
     let backend = CrosstermBackend::new(stdout); // Create the backend for tui
     let mut terminal = Terminal::new(backend)?; // Create the terminal instance
 
-    // Channels for communication
-    let (tx_display, mut rx_display) = mpsc::channel::<(String, String)>(100); // (channel, message)
-    let (tx_input, mut rx_input) = mpsc::channel::<String>(10);
-
-    // Clone `client` and `passkey` for the task
-    let client_clone = Arc::clone(&client);
-    let passkey_clone = passkey.clone();
-
-    // Spawn a task to handle incoming messages
-    tokio::spawn(async move {
-        while let Some(message) = stream.next().await {
-            if let Ok(message) = message {
-                // Check for the RPL_WELCOME response to send the passkey
-                if let Command::Response(Response::RPL_WELCOME, _) = message.command {
-                    if let Err(e) = client_clone.lock().await.send_privmsg("SeedServ", &passkey_clone) {
-                        log::error!("Failed to send passkey to SeedServ: {}", e);
-                    } else {
-                        log::info!("Passkey sent to SeedServ.");
-                    }
-                }
-
-                let formatted_message = match &message.command {
-                    Command::PRIVMSG(target, content) => {
-                        if let Some(nickname) = message.source_nickname() {
-                            format!("[{}] {}: {}", target, nickname, content)
-                        } else {
-                            format!("[{}] [Unknown]: {}", target, content)
-                        }
-                    }
-                    Command::JOIN(channel, ..) => {
-                        if let Some(nickname) = message.source_nickname() {
-                            format!("* {} has joined {}", nickname, channel)
-                        } else {
-                            format!("* Unknown has joined {}", channel)
-                        }
-                    }
-                    Command::PART(channel, ..) => {
-                        if let Some(nickname) = message.source_nickname() {
-                            format!("* {} has left {}", nickname, channel)
-                        } else {
-                            format!("* Unknown has left {}", channel)
-                        }
-                    }
-                    Command::QUIT(reason) => {
-                        if let Some(nickname) = message.source_nickname() {
-                            format!("* {} has quit ({})", nickname, reason.as_deref().unwrap_or("No reason"))
-                        } else {
-                            "* Unknown has quit".to_string()
-                        }
-                    }
-                    _ => format!("* Unhandled command: {:?}", message.command),
-                };
-
-                // Determine the target channel for the message
-                let target_channel = match &message.command {
-                    Command::PRIVMSG(target, _) => target.clone(),
-                    Command::JOIN(channel, ..) => channel.clone(),
-                    _ => "#server".to_string(), // Default to server messages
-                };
-
-                if tx_display.send((target_channel, formatted_message)).await.is_err() {
-                    break;
-                }
-            }
-        }
-    });
-
-    // Message history and input buffer
-    let mut messages: HashMap<String, Vec<String>> = HashMap::new(); // Store messages by channel
+    // Channel state and input buffer
+    let mut view = IrcView {
+        network_names: clients.iter().map(|(name, _)| name.clone()).collect(),
+        active_network: 0,
+        channels: network_channels,
+        active_channels,
+        messages: HashMap::new(),
+        unread: HashMap::new(),
+        nicks: HashMap::new(),
+    };
     let mut input = String::new();
-    let mut active_channel = "#lobby".to_string(); // Default to the first channel
+    let mut completion: Option<(String, String, usize)> = None; // (text before the word, word, next match)
 
     // Create a periodic timer for refreshing the UI
     let mut ui_refresh_interval = tokio::time::interval(std::time::Duration::from_millis(100));
@@ -147,164 +475,153 @@ pub async fn launch_irc_client() -> Result<(), Box<dyn std::error::Error>> {
         tokio::select! {
             // Periodic UI refresh
             _ = ui_refresh_interval.tick() => {
-                terminal.draw(|f| {
-                    let chunks = Layout::default()
-                        .direction(Direction::Vertical)
-                        .constraints([Constraint::Min(1), Constraint::Length(3)].as_ref())
-                        .split(f.size());
-
-                    // Create a longer-lived empty vector
-                    let empty_vec = vec![];
-
-                    // Display messages for the active channel
-                    let message_spans: Vec<Spans> = messages
-                        .get(&active_channel)
-                        .unwrap_or(&empty_vec) // Use the longer-lived `empty_vec`
-                        .iter()
-                        .map(|msg| Spans::from(parse_irc_colors(msg)))
-                        .collect();
-                    let message_widget = Paragraph::new(message_spans)
-                        .block(Block::default().borders(Borders::ALL).title(Span::raw(active_channel.clone())));
-                    f.render_widget(message_widget, chunks[0]);
-
-                    // Display input
-                    let input_widget = Paragraph::new(input.as_ref())
-                        .style(Style::default().fg(Color::Yellow))
-                        .block(Block::default().borders(Borders::ALL).title("Input"));
-                    f.render_widget(input_widget, chunks[1]);
-                })?;
+                terminal.draw(|f| draw_irc_ui(f, &view, &input))?;
             }
 
             // Handle keypress events
             _ = async {
-                if crossterm::event::poll(std::time::Duration::from_millis(10))? {
-                    if let Event::Key(key) = crossterm::event::read()? {
-                        match key.code {
-                            KeyCode::Char(c) => {
-                                input.push(c);
-                                history_position = None; // Reset history navigation when typing
+                if crossterm::event::poll(std::time::Duration::from_millis(10))?
+                    && let Event::Key(key) = crossterm::event::read()?
+                {
+                    if key.code != KeyCode::Tab {
+                        completion = None; // Any other key ends a completion cycle
+                    }
+                    match key.code {
+                        KeyCode::Tab => {
+                            // Complete the last word; pressing Tab again cycles through the matches
+                            let (base, word, index) = completion.take().unwrap_or_else(|| {
+                                let split = input.rfind(' ').map_or(0, |i| i + 1);
+                                (input[..split].to_string(), input[split..].to_string(), 0)
+                            });
+                            let matches = view.completions(&word);
+                            if !matches.is_empty() {
+                                let choice = &matches[index % matches.len()];
+                                // Address nicks completed at the start of the line
+                                let suffix = if base.is_empty() && !choice.starts_with('#') { ": " } else { " " };
+                                input = format!("{}{}{}", base, choice, suffix);
                             }
-                            KeyCode::Backspace => {
-                                input.pop();
-                                history_position = None; // Reset history navigation when typing
+                            completion = Some((base, word, index + 1));
+                        }
+                        KeyCode::BackTab => {
+                            // Switch to the next network tab
+                            view.switch_network();
+                        }
+                        KeyCode::Esc | KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            // Exit on Ctrl+Esc or Ctrl+C; checked before the typing arms below
+                            return Ok(());
+                        }
+                        KeyCode::Char(c) if key.modifiers.contains(KeyModifiers::ALT) && c.is_ascii_digit() => {
+                            // Alt+1..9 (and Alt+0 for the tenth) switch to a channel in the sidebar
+                            let position = c.to_digit(10).map_or(0, |d| if d == 0 { 10 } else { d as usize });
+                            if let Some(channel) = view.network_channels().get(position - 1).cloned() {
+                                view.switch_channel(&channel);
                             }
-                            KeyCode::Enter => {
-                                if !input.is_empty() {
-                                    command_history.push(input.clone()); // Save the command to history
-                                    if command_history.len() > 100 {
-                                        command_history.remove(0); // Limit history size to 100 commands
-                                    }
-                                }
-                                if tx_input.send(input.clone()).await.is_err() {
-                                    return Ok(());
+                        }
+                        KeyCode::Char(c) => {
+                            input.push(c);
+                            history_position = None; // Reset history navigation when typing
+                        }
+                        KeyCode::Backspace => {
+                            input.pop();
+                            history_position = None; // Reset history navigation when typing
+                        }
+                        KeyCode::Enter => {
+                            if !input.is_empty() {
+                                command_history.push(input.clone()); // Save the command to history
+                                if command_history.len() > 100 {
+                                    command_history.remove(0); // Limit history size to 100 commands
                                 }
-                                input.clear();
-                                history_position = None; // Reset history navigation
                             }
-                            KeyCode::Up => {
-                                if let Some(pos) = history_position {
-                                    if pos > 0 {
-                                        history_position = Some(pos - 1);
-                                    }
-                                } else if !command_history.is_empty() {
-                                    history_position = Some(command_history.len() - 1);
-                                }
-                                if let Some(pos) = history_position {
-                                    input = command_history[pos].clone();
-                                }
+                            if tx_input.send(input.clone()).await.is_err() {
+                                return Ok(());
                             }
-                            KeyCode::Down => {
-                                if let Some(pos) = history_position {
-                                    if pos + 1 < command_history.len() {
-                                        history_position = Some(pos + 1);
-                                    } else {
-                                        history_position = None;
-                                        input.clear();
-                                    }
+                            input.clear();
+                            history_position = None; // Reset history navigation
+                        }
+                        KeyCode::Up => {
+                            if let Some(pos) = history_position {
+                                if pos > 0 {
+                                    history_position = Some(pos - 1);
                                 }
-                                if let Some(pos) = history_position {
-                                    input = command_history[pos].clone();
+                            } else if !command_history.is_empty() {
+                                history_position = Some(command_history.len() - 1);
+                            }
+                            if let Some(pos) = history_position {
+                                input = command_history[pos].clone();
+                            }
+                        }
+                        KeyCode::Down => {
+                            if let Some(pos) = history_position {
+                                if pos + 1 < command_history.len() {
+                                    history_position = Some(pos + 1);
+                                } else {
+                                    history_position = None;
+                                    input.clear();
                                 }
                             }
-                            KeyCode::Esc | KeyCode::Char('c') if key.modifiers.contains(crossterm::event::KeyModifiers::CONTROL) => {
-                                // Exit on Esc or Ctrl+C
-                                return Ok(());
+                            if let Some(pos) = history_position {
+                                input = command_history[pos].clone();
                             }
-                            _ => {}
                         }
+                        _ => {}
                     }
                 }
                 // Explicitly redraw the UI after handling input
-                terminal.draw(|f| {
-                    let chunks = Layout::default()
-                        .direction(Direction::Vertical)
-                        .constraints([Constraint::Min(1), Constraint::Length(3)].as_ref())
-                        .split(f.size());
-    
-                    // Display messages for the active channel
-                    let empty_vec = vec![];
-                    let message_spans: Vec<Spans> = messages
-                        .get(&active_channel)
-                        .unwrap_or(&empty_vec)
-                        .iter()
-                        .map(|msg| Spans::from(parse_irc_colors(msg)))
-                        .collect();
-                    let message_widget = Paragraph::new(message_spans)
-                        .block(Block::default().borders(Borders::ALL).title(Span::raw(active_channel.clone())));
-                    f.render_widget(message_widget, chunks[0]);
-    
-                    // Display input
-                    let input_widget = Paragraph::new(input.as_ref())
-                        .style(Style::default().fg(Color::Yellow))
-                        .block(Block::default().borders(Borders::ALL).title("Input"));
-                    f.render_widget(input_widget, chunks[1]);
-                })?;
+                terminal.draw(|f| draw_irc_ui(f, &view, &input))?;
                 Ok::<(), Box<dyn std::error::Error>>(())
             } => {}
 
             // Handle incoming messages
-            Some((channel, message)) = rx_display.recv() => {
-                messages.entry(channel.clone()).or_insert_with(Vec::new).push(message);
-                if let Some(channel_messages) = messages.get_mut(&channel) {
-                    if channel_messages.len() > 100 {
-                        channel_messages.remove(0); // Keep the message history manageable
+            Some(event) = rx_display.recv() => {
+                match event {
+                    IrcEvent::Message { network, channel, text, nick } => {
+                        if let Some(nick) = nick {
+                            view.add_nicks(&network, &channel, vec![nick]);
+                        }
+                        view.push_message(&network, &channel, text);
                     }
+                    IrcEvent::Names { network, channel, nicks } => view.add_nicks(&network, &channel, nicks),
                 }
             }
 
-            // Handle user input
+            // Handle user input; commands apply to the active network
             Some(input) = rx_input.recv() => {
+                let (network, client) = &clients[view.active_network];
+                let active_channel = view.active_channel();
                 if input.starts_with("/") {
                     // Handle commands (e.g., /join, /msg, /part, /quit)
                     let parts: Vec<&str> = input.splitn(3, ' ').collect();
                     match parts.as_slice() {
                         ["/join", channel] => {
                             client.lock().await.send_join(channel)?;
-                            active_channel = channel.to_string(); // Switch to the new channel
-                            messages.entry(active_channel.clone()).or_insert_with(Vec::new);
-                            messages.get_mut(&active_channel).unwrap().push(format!("Joined channel: {}", channel));
+                            view.switch_channel(channel); // Switch to the new channel
+                            view.push_message(network, channel, format!("Joined channel: {}", channel));
                         }
                         ["/msg", target, message] => {
                             client.lock().await.send_privmsg(target, message)?;
-                            messages.entry(target.to_string()).or_insert_with(Vec::new).push(format!("You to {}: {}", target, message));
+                            view.push_message(network, target, format!("You to {}: {}", target, message));
                         }
                         ["/part", channel] => {
                             client.lock().await.send_part(channel)?;
-                            messages.entry(channel.to_string()).or_insert_with(Vec::new).push(format!("Left channel: {}", channel));
+                            view.remove_channel(network, channel);
+                            let key = view.active_key();
+                            view.push_message(&key.0, &key.1, format!("Left channel: {}", channel));
                         }
                         ["/quit"] => {
-                            client.lock().await.send_quit("")?;
-                            messages.entry("#server".to_string()).or_insert_with(Vec::new).push("Disconnected from the server.".to_string());
+                            // Leave every network, not just the active one
+                            for (_, client) in &clients {
+                                client.lock().await.send_quit("")?;
+                            }
                             break Ok(()); // Explicitly return Ok(()) when breaking
                         }
                         _ => {
-                            messages.entry(active_channel.clone()).or_insert_with(Vec::new).push(format!("Unknown command: {}", input));
+                            view.push_message(network, &active_channel, format!("Unknown command: {}", input));
                         }
                     }
                 } else {
                     // Send input as a message to the active channel
                     client.lock().await.send_privmsg(&active_channel, &input)?;
-                    messages.entry(active_channel.clone()).or_insert_with(Vec::new).push(format!("You: {}", input));
+                    view.push_message(network, &active_channel, format!("You: {}", input));
                 }
             }
         }
@@ -318,6 +635,218 @@ pub async fn launch_irc_client() -> Result<(), Box<dyn std::error::Error>> {
     result
 }
 
+/// Reads one network's messages, authenticates after the welcome reply, runs announce
+/// rules, and forwards everything to the UI tagged with the network name.
+fn spawn_network_reader(
+    network: IrcNetworkConfig,
+    client: Arc<Mutex<Client>>,
+    mut stream: ClientStream,
+    passkey: String,
+    tx_display: mpsc::Sender<IrcEvent>,
+    announce: AnnounceContext,
+) {
+    tokio::spawn(async move {
+        while let Some(message) = stream.next().await {
+            if let Ok(message) = message {
+                // Check for the RPL_WELCOME response to authenticate
+                if let Command::Response(Response::RPL_WELCOME, _) = message.command {
+                    if let Some(password) = &network.nickserv_password {
+                        if let Err(e) = client.lock().await.send_privmsg("NickServ", format!("IDENTIFY {}", password)) {
+                            log::error!("Failed to identify with NickServ on {}: {}", network.name, e);
+                        } else {
+                            log::info!("Identified with NickServ on {}.", network.name);
+                        }
+                    }
+                    if network.seedserv_auth {
+                        if let Err(e) = client.lock().await.send_privmsg("SeedServ", &passkey) {
+                            log::error!("Failed to send passkey to SeedServ: {}", e);
+                        } else {
+                            log::info!("Passkey sent to SeedServ.");
+                        }
+                    }
+                }
+
+                // Nick lists for tab completion
+                if let Command::Response(Response::RPL_NAMREPLY, args) = &message.command
+                    && let (Some(channel), Some(names)) = (args.get(2), args.get(3))
+                {
+                    let nicks = names
+                        .split_whitespace()
+                        .map(|nick| nick.trim_start_matches(['~', '&', '@', '%', '+']).to_string())
+                        .collect();
+                    let _ = tx_display
+                        .send(IrcEvent::Names { network: network.name.clone(), channel: channel.clone(), nicks })
+                        .await;
+                }
+
+                let formatted_message = match &message.command {
+                    Command::PRIVMSG(target, content) => {
+                        if let Some(nickname) = message.source_nickname() {
+                            format!("[{}] {}: {}", target, nickname, content)
+                        } else {
+                            format!("[{}] [Unknown]: {}", target, content)
+                        }
+                    }
+                    Command::JOIN(channel, ..) => {
+                        if let Some(nickname) = message.source_nickname() {
+                            format!("* {} has joined {}", nickname, channel)
+                        } else {
+                            format!("* Unknown has joined {}", channel)
+                        }
+                    }
+                    Command::PART(channel, ..) => {
+                        if let Some(nickname) = message.source_nickname() {
+                            format!("* {} has left {}", nickname, channel)
+                        } else {
+                            format!("* Unknown has left {}", channel)
+                        }
+                    }
+                    Command::QUIT(reason) => {
+                        if let Some(nickname) = message.source_nickname() {
+                            format!("* {} has quit ({})", nickname, reason.as_deref().unwrap_or("No reason"))
+                        } else {
+                            "* Unknown has quit".to_string()
+                        }
+                    }
+                    _ => format!("* Unhandled command: {:?}", message.command),
+                };
+
+                // Determine the target channel for the message; private messages go under the sender's nick
+                let target_channel = match &message.command {
+                    Command::PRIVMSG(target, _) if !target.starts_with('#') => {
+                        message.source_nickname().unwrap_or(target.as_str()).to_string()
+                    }
+                    Command::PRIVMSG(target, _) => target.clone(),
+                    Command::JOIN(channel, ..) => channel.clone(),
+                    _ => "#server".to_string(), // Default to server messages
+                };
+
+                // Run the matching announce rule's action in the background
+                if let Command::PRIVMSG(target, content) = &message.command
+                    && let Some((announcement, rule)) = announce.parser.parse(
+                        &network.name,
+                        &announce.default_network,
+                        target,
+                        message.source_nickname(),
+                        content,
+                    ) {
+                        let action = rule.action;
+                        let tx_status = tx_display.clone();
+                        let announce = announce.clone();
+                        tokio::spawn(async move {
+                            let (network, channel) = (announcement.network.clone(), announcement.channel.clone());
+                            let status = tokio::task::spawn_blocking(move || {
+                                run_announce_action(
+                                    &announcement,
+                                    action,
+                                    &announce.data_dirs,
+                                    &announce.api_key,
+                                    &announce.qbittorrent_configs,
+                                )
+                            })
+                            .await
+                            .unwrap_or_else(|e| format!("★ Announce action failed: {}", e));
+                            let _ = tx_status.send(IrcEvent::Message { network, channel, text: status, nick: None }).await;
+                        });
+                    }
+
+                let nick = match &message.command {
+                    Command::PRIVMSG(..) | Command::JOIN(..) => message.source_nickname().map(|nick| nick.to_string()),
+                    _ => None,
+                };
+                let event = IrcEvent::Message {
+                    network: network.name.clone(),
+                    channel: target_channel,
+                    text: formatted_message,
+                    nick,
+                };
+                if tx_display.send(event).await.is_err() {
+                    break;
+                }
+            }
+        }
+    });
+}
+
+// Draws the network tabs, the channel sidebar, the active channel's messages and the input line
+fn draw_irc_ui<B: Backend>(f: &mut Frame<B>, view: &IrcView, input: &str) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(1), Constraint::Length(3)].as_ref())
+        .split(f.size());
+    let body = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(24), Constraint::Min(1)].as_ref())
+        .split(chunks[1]);
+
+    // Network tabs; a '*' marks networks with unread messages
+    let titles = view
+        .network_names
+        .iter()
+        .map(|name| {
+            if view.unread.keys().any(|(network, _)| network == name) {
+                Spans::from(format!("{} *", name))
+            } else {
+                Spans::from(name.clone())
+            }
+        })
+        .collect();
+    let tabs = Tabs::new(titles)
+        .select(view.active_network)
+        .block(Block::default().borders(Borders::ALL).title("Networks (Shift+Tab to switch)"))
+        .highlight_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    f.render_widget(tabs, chunks[0]);
+
+    // Channel sidebar with Alt+number positions and unread counts
+    let network = view.network();
+    let active_channel = view.active_channel();
+    let items: Vec<ListItem> = view
+        .network_channels()
+        .iter()
+        .enumerate()
+        .map(|(i, channel)| {
+            let unread = view.unread.get(&(network.to_string(), channel.clone())).copied().unwrap_or(0);
+            let label = if unread > 0 {
+                format!("{} {} ({})", i + 1, channel, unread)
+            } else {
+                format!("{} {}", i + 1, channel)
+            };
+            let style = if *channel == active_channel {
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+            } else if unread > 0 {
+                Style::default().fg(Color::Cyan)
+            } else {
+                Style::default()
+            };
+            ListItem::new(label).style(style)
+        })
+        .collect();
+    let sidebar = List::new(items).block(Block::default().borders(Borders::ALL).title("Channels (Alt+1-9)"));
+    f.render_widget(sidebar, body[0]);
+
+    // Display messages for the active channel
+    let empty_vec = vec![];
+    let message_spans: Vec<Spans> = view
+        .messages
+        .get(&view.active_key())
+        .unwrap_or(&empty_vec)
+        .iter()
+        .map(|msg| Spans::from(parse_irc_colors(msg)))
+        .collect();
+    let message_widget = Paragraph::new(message_spans).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(Span::raw(format!("{} — {}", network, active_channel))),
+    );
+    f.render_widget(message_widget, body[1]);
+
+    // Display input
+    let input_widget = Paragraph::new(input)
+        .style(Style::default().fg(Color::Yellow))
+        .block(Block::default().borders(Borders::ALL).title("Input (Tab to complete)"));
+    f.render_widget(input_widget, chunks[2]);
+}
+
 // Function to restore the terminal
 fn restore_terminal(terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>) -> Result<(), Box<dyn std::error::Error>> {
     disable_raw_mode()?; // Disable raw mode
@@ -327,7 +856,7 @@ fn restore_terminal(terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>)
 }
 
 // Helper function to parse IRC color codes
-fn parse_irc_colors(message: &str) -> Vec<Span> {
+fn parse_irc_colors(message: &str) -> Vec<Span<'_>> {
     use tui::style::{Color, Style};
     let mut spans = Vec::new();
     let mut chars = message.chars().peekable();
@@ -345,32 +874,32 @@ fn parse_irc_colors(message: &str) -> Vec<Span> {
                 let mut fg_color = None;
                 let mut bg_color = None;
 
-                if let Some(next) = chars.peek() {
-                    if next.is_ascii_digit() {
-                        let mut color_code = String::new();
+                if let Some(next) = chars.peek()
+                    && next.is_ascii_digit()
+                {
+                    let mut color_code = String::new();
+                    color_code.push(chars.next().unwrap());
+                    if let Some(next) = chars.peek()
+                        && next.is_ascii_digit()
+                    {
                         color_code.push(chars.next().unwrap());
-                        if let Some(next) = chars.peek() {
-                            if next.is_ascii_digit() {
-                                color_code.push(chars.next().unwrap());
-                            }
-                        }
-                        fg_color = Some(map_irc_color(color_code.parse::<u8>().unwrap_or(0)));
                     }
+                    fg_color = Some(map_irc_color(color_code.parse::<u8>().unwrap_or(0)));
                 }
 
                 if let Some(',') = chars.peek() {
                     chars.next();
-                    if let Some(next) = chars.peek() {
-                        if next.is_ascii_digit() {
-                            let mut color_code = String::new();
+                    if let Some(next) = chars.peek()
+                        && next.is_ascii_digit()
+                    {
+                        let mut color_code = String::new();
+                        color_code.push(chars.next().unwrap());
+                        if let Some(next) = chars.peek()
+                            && next.is_ascii_digit()
+                        {
                             color_code.push(chars.next().unwrap());
-                            if let Some(next) = chars.peek() {
-                                if next.is_ascii_digit() {
-                                    color_code.push(chars.next().unwrap());
-                                }
-                            }
-                            bg_color = Some(map_irc_color(color_code.parse::<u8>().unwrap_or(0)));
                         }
+                        bg_color = Some(map_irc_color(color_code.parse::<u8>().unwrap_or(0)));
                     }
                 }
 
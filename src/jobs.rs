@@ -0,0 +1,311 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use log::{error, info, warn};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::{Child, Command};
+use tokio::sync::broadcast;
+use crate::readiness::NOT_READY_EXIT_CODE;
+use crate::reload::Live;
+use crate::schedule::OUTSIDE_WINDOW_EXIT_CODE;
+use crate::trackers::TRACKER_UNAVAILABLE_EXIT_CODE;
+use crate::types::{MaintenanceConfig, UploadOverrides};
+
+/// Output lines kept per job; older lines are still in seed-tools.log.
+const MAX_JOB_OUTPUT_LINES: usize = 2000;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Running,
+    Parked, // Tracker unavailable or release not ready; retried automatically
+    Succeeded,
+    Failed,
+}
+
+/// An upload started through the web UI or API, run as a child `seed-tools` process.
+#[derive(Serialize, Clone, Debug)]
+pub struct Job {
+    pub id: u64,
+    pub path: String,
+    pub trackers: Vec<String>,
+    pub status: JobStatus,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    pub exit_code: Option<i32>,
+    pub output: Vec<String>,
+}
+
+/// Progress notification broadcast to web UI/API subscribers.
+#[derive(Serialize, Clone, Debug)]
+pub struct JobEvent {
+    pub job_id: u64,
+    pub line: Option<String>,
+    pub status: JobStatus,
+}
+
+/// Starts and tracks upload jobs. Jobs run the same binary in CLI mode, exactly like the TUI does.
+pub struct JobManager {
+    jobs: Mutex<Vec<Job>>,
+    next_id: AtomicU64,
+    events: broadcast::Sender<JobEvent>,
+    seed_tools_path: PathBuf,
+    maintenance: Live<MaintenanceConfig>,
+}
+
+/// Maps tracker short names ("SP", "TL") to the CLI flags of the upload process.
+pub fn tracker_flag(tracker: &str) -> Option<&'static str> {
+    match tracker.to_uppercase().as_str() {
+        "SP" | "SEEDPOOL" => Some("--SP"),
+        "TL" | "TORRENTLEECH" => Some("--TL"),
+        _ => None,
+    }
+}
+
+impl JobManager {
+    pub fn new(maintenance: &MaintenanceConfig) -> Result<Arc<Self>, String> {
+        let seed_tools_path = std::env::current_exe()
+            .map_err(|e| format!("Failed to get executable path: {}", e))?;
+        let (events, _) = broadcast::channel(1024);
+        Ok(Arc::new(JobManager {
+            jobs: Mutex::new(Vec::new()),
+            next_id: AtomicU64::new(1),
+            events,
+            seed_tools_path,
+            maintenance: Live::new(maintenance.clone()),
+        }))
+    }
+
+    /// Uses `maintenance` for the next retries of parked uploads, e.g. after a config reload.
+    pub fn set_maintenance(&self, maintenance: &MaintenanceConfig) {
+        self.maintenance.replace(maintenance.clone());
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<JobEvent> {
+        self.events.subscribe()
+    }
+
+    pub fn list(&self) -> Vec<Job> {
+        self.jobs.lock().unwrap().clone()
+    }
+
+    pub fn get(&self, id: u64) -> Option<Job> {
+        self.jobs.lock().unwrap().iter().find(|job| job.id == id).cloned()
+    }
+
+    fn update(&self, id: u64, line: Option<String>, status: JobStatus, exit_code: Option<i32>) {
+        {
+            let mut jobs = self.jobs.lock().unwrap();
+            if let Some(job) = jobs.iter_mut().find(|job| job.id == id) {
+                if let Some(line) = &line {
+                    if job.output.len() >= MAX_JOB_OUTPUT_LINES {
+                        job.output.remove(0);
+                    }
+                    job.output.push(line.clone());
+                }
+                if job.status != status {
+                    job.status = status;
+                    job.exit_code = exit_code;
+                    if matches!(status, JobStatus::Succeeded | JobStatus::Failed) {
+                        job.finished_at = Some(chrono::Utc::now().to_rfc3339());
+                    }
+                }
+            }
+        }
+        // No subscribers is fine
+        let _ = self.events.send(JobEvent { job_id: id, line, status });
+    }
+
+    /// Starts `seed-tools <path> --SP/--TL [overrides]` in the background and returns the job id.
+    pub fn start_upload(
+        self: &Arc<Self>,
+        path: &Path,
+        trackers: &[String],
+        overrides: &UploadOverrides,
+    ) -> Result<u64, String> {
+        let mut flags = Vec::new();
+        for tracker in trackers {
+            let flag = tracker_flag(tracker).ok_or_else(|| format!("Unknown tracker: {}", tracker))?;
+            if !flags.contains(&flag) {
+                flags.push(flag);
+            }
+        }
+        if flags.is_empty() {
+            return Err("No trackers selected.".to_string());
+        }
+
+        // Releases still being copied are postponed and retried instead of uploaded half done
+        let mut args = vec![path.display().to_string(), "--check-ready".to_string()];
+        args.extend(flags.iter().map(|flag| flag.to_string()));
+        args.extend(overrides.to_args().map_err(|e| e.to_string())?);
+        let child = self.spawn_upload(&args)?;
+
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        self.jobs.lock().unwrap().push(Job {
+            id,
+            path: path.display().to_string(),
+            trackers: trackers.to_vec(),
+            status: JobStatus::Running,
+            started_at: chrono::Utc::now().to_rfc3339(),
+            finished_at: None,
+            exit_code: None,
+            output: Vec::new(),
+        });
+        info!("Started upload job {} for '{}' ({:?})", id, path.display(), flags);
+
+        let manager = Arc::clone(self);
+        tokio::spawn(async move { manager.run_job(id, args, child).await });
+
+        Ok(id)
+    }
+
+    fn spawn_upload(&self, args: &[String]) -> Result<Child, String> {
+        Command::new(&self.seed_tools_path)
+            .args(args)
+            .envs(crate::profile::child_env())
+            .env("SEED_TOOLS_LOG_STDERR", "1") // Stream log records for progress
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| format!("Failed to start upload process: {}", e))
+    }
+
+    /// Streams the upload's output until it exits. Uploads that stopped because a tracker
+    /// was unavailable or the release was not ready are parked and restarted on the
+    /// maintenance backoff schedule, up to `max_retries` times; the upload checkpoint makes the
+    /// restart continue where it stopped.
+    async fn run_job(self: Arc<Self>, id: u64, args: Vec<String>, mut child: Child) {
+        let mut attempt = 0;
+        loop {
+            let mut readers = Vec::new();
+            if let Some(stdout) = child.stdout.take() {
+                let manager = Arc::clone(&self);
+                readers.push(tokio::spawn(async move {
+                    let mut lines = BufReader::new(stdout).lines();
+                    while let Ok(Some(line)) = lines.next_line().await {
+                        manager.update(id, Some(line), JobStatus::Running, None);
+                    }
+                }));
+            }
+            if let Some(stderr) = child.stderr.take() {
+                let manager = Arc::clone(&self);
+                readers.push(tokio::spawn(async move {
+                    let mut lines = BufReader::new(stderr).lines();
+                    while let Ok(Some(line)) = lines.next_line().await {
+                        manager.update(id, Some(line), JobStatus::Running, None);
+                    }
+                }));
+            }
+
+            let status = child.wait().await;
+            for reader in readers {
+                let _ = reader.await;
+            }
+            match status {
+                Ok(status) if status.success() => {
+                    info!("Upload job {} finished successfully.", id);
+                    self.update(id, None, JobStatus::Succeeded, status.code());
+                }
+                Ok(status) if matches!(status.code(), Some(TRACKER_UNAVAILABLE_EXIT_CODE | NOT_READY_EXIT_CODE | OUTSIDE_WINDOW_EXIT_CODE)) => {
+                    let reason = match status.code() {
+                        Some(NOT_READY_EXIT_CODE) => "release not ready",
+                        Some(OUTSIDE_WINDOW_EXIT_CODE) => "outside the transfer window",
+                        _ => "tracker unavailable",
+                    };
+                    attempt += 1;
+                    let maintenance = self.maintenance.get();
+                    if !maintenance.may_retry(attempt) {
+                        error!("Upload job {} failed: {} after {} retries.", id, reason, maintenance.max_retries);
+                        self.update(
+                            id,
+                            Some(format!("ERROR: {} after {} retries", reason, maintenance.max_retries)),
+                            JobStatus::Failed,
+                            status.code(),
+                        );
+                        break;
+                    }
+                    let delay = maintenance.backoff(attempt);
+                    warn!("Upload job {} parked: {}, retrying in {}s.", id, reason, delay.as_secs());
+                    self.update(
+                        id,
+                        Some(format!("Upload parked ({}), retrying in {}s (attempt {})", reason, delay.as_secs(), attempt)),
+                        JobStatus::Parked,
+                        status.code(),
+                    );
+                    tokio::time::sleep(delay).await;
+                    match self.spawn_upload(&args) {
+                        Ok(next) => {
+                            child = next;
+                            self.update(id, Some("Resuming upload".to_string()), JobStatus::Running, None);
+                            continue;
+                        }
+                        Err(e) => {
+                            error!("Upload job {} could not be restarted: {}", id, e);
+                            self.update(id, Some(format!("ERROR: {}", e)), JobStatus::Failed, None);
+                        }
+                    }
+                }
+                Ok(status) => {
+                    error!("Upload job {} failed with exit code {:?}.", id, status.code());
+                    self.update(id, None, JobStatus::Failed, status.code());
+                }
+                Err(e) => {
+                    error!("Upload job {} could not be awaited: {}", id, e);
+                    self.update(id, Some(format!("ERROR: {}", e)), JobStatus::Failed, None);
+                }
+            }
+            break;
+        }
+    }
+
+    /// Runs `seed-tools --pre <path>` and returns its "Key: Value" result lines.
+    pub async fn run_preflight(&self, path: &Path) -> Result<BTreeMap<String, String>, String> {
+        let stdout = self.preflight_output(path, &[]).await?;
+        Ok(stdout
+            .lines()
+            .filter_map(|line| line.split_once(": "))
+            .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+            .collect())
+    }
+
+    /// Runs `seed-tools --pre <path> --output json` and reads the result into `T`, for callers
+    /// that decide on the result's fields rather than show them.
+    pub async fn run_preflight_json<T: DeserializeOwned>(&self, path: &Path) -> Result<T, String> {
+        let stdout = self.preflight_output(path, &["--output", "json"]).await?;
+        let mut results: Vec<T> = serde_json::from_str(&stdout)
+            .map_err(|e| format!("Failed to parse the pre-flight result: {}", e))?;
+        if results.is_empty() {
+            return Err("The pre-flight check returned no result".to_string());
+        }
+        Ok(results.remove(0))
+    }
+
+    async fn preflight_output(&self, path: &Path, args: &[&str]) -> Result<String, String> {
+        info!("Running pre-flight check for '{}'", path.display());
+        let output = Command::new(&self.seed_tools_path)
+            .envs(crate::profile::child_env())
+            .arg("--pre")
+            .arg(path)
+            .args(args)
+            .output()
+            .await
+            .map_err(|e| format!("Failed to run pre-flight check: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "Pre-flight check failed with exit code: {}",
+                output.status.code().unwrap_or(-1)
+            ));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        if let Some(line) = stdout.lines().find(|line| line.starts_with("Pre-flight check failed:")) {
+            return Err(line.to_string());
+        }
+        Ok(stdout)
+    }
+}
@@ -2,4 +2,50 @@ pub mod utils;
 pub mod types;
 pub mod sync;
 pub mod irc;
-pub mod ui;
\ No newline at end of file
+pub mod ui;
+pub mod terminal;
+pub mod log_buffer;
+pub mod logging;
+pub mod pipeline;
+pub mod jobs;
+pub mod web;
+pub mod api;
+pub mod hooks;
+pub mod trackers;
+pub mod announce;
+pub mod http;
+pub mod notifications;
+pub mod metrics;
+pub mod history;
+pub mod orphans;
+pub mod torrent;
+pub mod output;
+pub mod estimate;
+pub mod seeding;
+pub mod slots;
+pub mod readiness;
+pub mod state;
+pub mod software;
+pub mod limits;
+pub mod bbcode;
+pub mod imgbb;
+pub mod dupe_cache;
+pub mod secrets;
+pub mod reload;
+pub mod profile;
+pub mod probe;
+pub mod binaries;
+pub mod error;
+pub mod offline;
+pub mod descriptions;
+pub mod schedule;
+pub mod space;
+pub mod retention;
+pub mod trumping;
+pub mod imdb;
+pub mod arr;
+
+// The entry points for automation that links against seed-tools
+pub use error::SeedToolsError;
+pub use trackers::{cross_seed_dupe, preflight, process_release, upload, Target, Tools, UploadOptions};
+pub use types::{Config, GazelleConfig, PreflightCheckResult, SeedpoolConfig, TorrentLeechConfig, Unit3dConfig, UploadOverrides};
\ No newline at end of file
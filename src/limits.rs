@@ -0,0 +1,133 @@
+use std::path::Path;
+use log::warn;
+use walkdir::WalkDir;
+use crate::types::LimitsConfig;
+use crate::utils::format_size;
+
+/// What about a release breaks the configured limits: total size, file count, empty files and
+/// extensions not allowed for its release type (movie, tv, music, ebook, ...).
+pub fn find_limit_violations(input_path: &str, release_type: &str, config: &LimitsConfig) -> Vec<String> {
+    let files: Vec<(String, u64)> = WalkDir::new(input_path)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| (entry.path().to_string_lossy().to_string(), entry.metadata().map(|metadata| metadata.len()).unwrap_or(0)))
+        .collect();
+    let mut violations = Vec::new();
+
+    let total: u64 = files.iter().map(|(_, size)| size).sum();
+    if let Some(max_size_gb) = config.max_size_gb
+        && total as f64 > max_size_gb * 1024.0 * 1024.0 * 1024.0
+    {
+        violations.push(format!("{} is larger than the {} GiB limit", format_size(total), max_size_gb));
+    }
+    if let Some(max_files) = config.max_files
+        && files.len() > max_files
+    {
+        violations.push(format!("{} files, more than the limit of {}", files.len(), max_files));
+    }
+    if config.zero_byte_files {
+        let empty: Vec<&str> = files.iter().filter(|(_, size)| *size == 0).map(|(file, _)| file.as_str()).collect();
+        if !empty.is_empty() {
+            violations.push(format!("empty files: {}", empty.join(", ")));
+        }
+    }
+
+    // "all" applies to every release type
+    let disallowed: Vec<String> = ["all", release_type]
+        .iter()
+        .filter_map(|key| config.disallowed_extensions.get(*key))
+        .flatten()
+        .map(|ext| ext.trim_start_matches('.').to_lowercase())
+        .collect();
+    let offending: Vec<&str> = files
+        .iter()
+        .map(|(file, _)| file.as_str())
+        .filter(|file| {
+            Path::new(file)
+                .extension()
+                .is_some_and(|ext| disallowed.contains(&ext.to_string_lossy().to_lowercase()))
+        })
+        .collect();
+    if !offending.is_empty() {
+        violations.push(format!("files not allowed for {} uploads: {}", release_type, offending.join(", ")));
+    }
+    violations
+}
+
+/// Checks the limits before a torrent is created: breaking them is logged, or stops the
+/// upload when `abort` is set.
+pub fn check_release_limits(input_path: &str, release_type: &str, config: &LimitsConfig) -> Result<(), String> {
+    let violations = find_limit_violations(input_path, release_type, config);
+    if violations.is_empty() {
+        return Ok(());
+    }
+    if config.abort {
+        return Err(format!("'{}' breaks the upload limits: {}", input_path, violations.join("; ")));
+    }
+    for violation in &violations {
+        warn!("Upload limit: {}", violation);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(dir: &Path) -> String {
+        let release = dir.join("Release");
+        std::fs::create_dir_all(release.join("Extras")).unwrap();
+        std::fs::write(release.join("release.mkv"), vec![0; 2000]).unwrap();
+        std::fs::write(release.join("release.nfo"), "").unwrap();
+        std::fs::write(release.join("Extras").join("setup.EXE"), "MZ").unwrap();
+        release.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn finds_nothing_without_limits() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(find_limit_violations(&release(dir.path()), "movie", &LimitsConfig::default()).is_empty());
+    }
+
+    #[test]
+    fn finds_each_broken_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let release = release(dir.path());
+        let config: LimitsConfig = serde_yaml::from_str(
+            r#"
+max_size_gb: 0.000001
+max_files: 2
+zero_byte_files: true
+disallowed_extensions:
+  all: [".exe"]
+  tv: [mkv]
+"#,
+        )
+        .unwrap();
+
+        let violations = find_limit_violations(&release, "movie", &config);
+        assert_eq!(violations.len(), 4, "{:?}", violations);
+        assert!(violations[0].ends_with("is larger than the 0.000001 GiB limit"), "{}", violations[0]);
+        assert_eq!(violations[1], "3 files, more than the limit of 2");
+        assert!(violations[2].starts_with("empty files: ") && violations[2].ends_with("release.nfo"), "{}", violations[2]);
+        assert!(violations[3].starts_with("files not allowed for movie uploads: ") && violations[3].ends_with("setup.EXE"), "{}", violations[3]);
+
+        // Extensions of the release type add to those of "all"
+        let violations = find_limit_violations(&release, "tv", &config);
+        assert!(violations[3].contains("release.mkv") && violations[3].contains("setup.EXE"), "{}", violations[3]);
+    }
+
+    #[test]
+    fn aborts_only_when_configured() {
+        let dir = tempfile::tempdir().unwrap();
+        let release = release(dir.path());
+        let mut config = LimitsConfig { max_files: Some(1), ..Default::default() };
+        assert_eq!(check_release_limits(&release, "movie", &config), Ok(()));
+
+        config.abort = true;
+        config.zero_byte_files = true;
+        let error = check_release_limits(&release, "movie", &config).unwrap_err();
+        assert!(error.contains("breaks the upload limits: 3 files, more than the limit of 1; empty files: "), "{}", error);
+    }
+}
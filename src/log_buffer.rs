@@ -0,0 +1,339 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex, OnceLock};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+use log::Level;
+use notify::{Config as NotifyConfig, RecommendedWatcher, RecursiveMode, Watcher};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+
+/// Maximum number of lines kept in memory for the UI log viewer.
+const LOG_BUFFER_CAPACITY: usize = 5000;
+/// Directly pushed records still waiting for their copy in the log file.
+const MAX_PENDING_ECHOES: usize = 500;
+
+static LOG_BUFFER: OnceLock<Mutex<VecDeque<LogLine>>> = OnceLock::new();
+// Level and message of records pushed directly that the log file tailer will read again: this
+// process's own events and the records children stream on stderr. Each matches one line.
+static PENDING_ECHOES: OnceLock<Mutex<VecDeque<(Level, String)>>> = OnceLock::new();
+static LOG_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Clone, Debug)]
+pub struct LogLine {
+    pub level: Level,
+    pub text: String,
+}
+
+fn buffer() -> &'static Mutex<VecDeque<LogLine>> {
+    LOG_BUFFER.get_or_init(|| Mutex::new(VecDeque::with_capacity(LOG_BUFFER_CAPACITY)))
+}
+
+fn pending_echoes() -> &'static Mutex<VecDeque<(Level, String)>> {
+    PENDING_ECHOES.get_or_init(|| Mutex::new(VecDeque::with_capacity(MAX_PENDING_ECHOES)))
+}
+
+/// Notes that the record `text` was pushed directly and is also written to the log file, so
+/// the tailer skips that one copy of it.
+fn expect_echo(level: Level, text: &str) {
+    let Some((_, message)) = text.split_once("] ") else { return };
+    if message.is_empty() {
+        return;
+    }
+    let mut pending = pending_echoes().lock().unwrap();
+    if pending.len() >= MAX_PENDING_ECHOES {
+        pending.pop_front();
+    }
+    pending.push_back((level, message.to_string()));
+}
+
+/// Appends a line to the ring buffer, dropping the oldest line once full.
+pub fn push_line(level: Level, text: &str) {
+    let mut buffer = buffer().lock().unwrap();
+    if buffer.len() >= LOG_BUFFER_CAPACITY {
+        buffer.pop_front();
+    }
+    buffer.push_back(LogLine {
+        level,
+        text: text.to_string(),
+    });
+    LOG_GENERATION.fetch_add(1, Ordering::SeqCst);
+}
+
+/// Appends a raw output line (e.g. from a child `seed-tools` process), guessing its level
+/// from its `[LEVEL]` tag. Untagged lines use `default_level`.
+pub fn push_raw_line(text: &str, default_level: Level) {
+    match parse_level(text) {
+        // A log record of the child, which it also wrote to the shared log file
+        Some(level) => {
+            push_line(level, text);
+            expect_echo(level, text);
+        }
+        None => push_line(default_level, text),
+    }
+}
+
+pub fn parse_level(text: &str) -> Option<Level> {
+    if text.contains("[ERROR]") {
+        Some(Level::Error)
+    } else if text.contains("[WARN]") {
+        Some(Level::Warn)
+    } else if text.contains("[INFO]") {
+        Some(Level::Info)
+    } else if text.contains("[DEBUG]") {
+        Some(Level::Debug)
+    } else if text.contains("[TRACE]") {
+        Some(Level::Trace)
+    } else {
+        None
+    }
+}
+
+/// Appends a line read from the shared log file, unless it is the copy of a record this
+/// process (or a child streaming its stderr) already pushed directly. Each direct push skips
+/// one line, so the same message logged again, e.g. by another process, still shows up.
+fn push_tailed_line(text: &str) {
+    let normalized = normalize_json_line(text);
+    let text = normalized.as_deref().unwrap_or(text);
+    let level = parse_level(text).unwrap_or(Level::Info);
+    {
+        let mut pending = pending_echoes().lock().unwrap();
+        let echo = pending.iter().position(|(echo_level, message)| *echo_level == level && text.ends_with(message.as_str()));
+        if let Some(index) = echo {
+            pending.remove(index);
+            return;
+        }
+    }
+    push_line(level, text);
+}
+
+/// Turns a line of the JSON log format back into `HH:MM:SS [LEVEL] message`.
+fn normalize_json_line(text: &str) -> Option<String> {
+    if !text.starts_with('{') {
+        return None;
+    }
+    let value: serde_json::Value = serde_json::from_str(text).ok()?;
+    let level = value["level"].as_str()?;
+    let time = value["timestamp"]
+        .as_str()
+        .and_then(|time| chrono::DateTime::parse_from_rfc3339(time).ok())
+        .map(|time| time.with_timezone(&chrono::Local).format("%H:%M:%S").to_string())
+        .unwrap_or_default();
+    Some(format!("{} [{}] {}", time, level, value["fields"]["message"].as_str().unwrap_or_default()))
+}
+
+/// Returns a copy of the buffered lines, oldest first.
+pub fn snapshot() -> Vec<LogLine> {
+    buffer().lock().unwrap().iter().cloned().collect()
+}
+
+/// Increments every time a line is added; lets the UI redraw only when something changed.
+pub fn generation() -> u64 {
+    LOG_GENERATION.load(Ordering::SeqCst)
+}
+
+/// Layer that feeds every event into the in-memory ring buffer, including records from the
+/// `log` macros. Added next to the file layers in `logging::init`.
+pub struct RingBufferLayer;
+
+impl<S: Subscriber> Layer<S> for RingBufferLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        let level = match *event.metadata().level() {
+            tracing::Level::ERROR => Level::Error,
+            tracing::Level::WARN => Level::Warn,
+            tracing::Level::INFO => Level::Info,
+            tracing::Level::DEBUG => Level::Debug,
+            tracing::Level::TRACE => Level::Trace,
+        };
+        let text = format!(
+            "{} [{}] {}{}",
+            chrono::Local::now().format("%H:%M:%S"),
+            level,
+            visitor.message,
+            visitor.fields
+        );
+        push_line(level, &text);
+        expect_echo(level, &text);
+    }
+}
+
+/// Collects the message and any structured fields of an event.
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.record_debug(field, &value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        match field.name() {
+            "message" => self.message = format!("{:?}", value),
+            // Target, module and file of records forwarded from the `log` macros
+            name if name.starts_with("log.") => {}
+            name => self.fields.push_str(&format!(" {}={:?}", name, value)),
+        }
+    }
+}
+
+/// Follows a log file from its current end and feeds new lines into the ring buffer.
+/// Uses a filesystem watcher with a short polling fallback, so it needs no `tail` binary.
+/// The background thread stops when the tailer is dropped.
+pub struct LogTailer {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl LogTailer {
+    pub fn start(path: impl Into<PathBuf>) -> LogTailer {
+        let path = path.into();
+        let stop = Arc::new(AtomicBool::new(false));
+        let handle = {
+            let stop = Arc::clone(&stop);
+            thread::spawn(move || follow_file(&path, &stop))
+        };
+        LogTailer {
+            stop,
+            handle: Some(handle),
+        }
+    }
+}
+
+impl Drop for LogTailer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn follow_file(path: &Path, stop: &AtomicBool) {
+    let (tx, rx) = mpsc::channel();
+    // Watch the parent directory so truncation and re-creation of the file are noticed too
+    let watch_dir = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."))
+        .to_path_buf();
+    let _watcher = match RecommendedWatcher::new(tx, NotifyConfig::default()) {
+        Ok(mut watcher) => match watcher.watch(&watch_dir, RecursiveMode::NonRecursive) {
+            Ok(()) => Some(watcher),
+            Err(e) => {
+                log::warn!("Failed to watch {}: {}. Falling back to polling.", watch_dir.display(), e);
+                None
+            }
+        },
+        Err(e) => {
+            log::warn!("Failed to create log file watcher: {}. Falling back to polling.", e);
+            None
+        }
+    };
+
+    let mut reader: Option<BufReader<File>> = None;
+    let mut position = 0u64;
+    let mut partial = String::new();
+
+    while !stop.load(Ordering::SeqCst) {
+        if reader.is_none() {
+            // Start at the end: earlier lines belong to previous sessions
+            if let Ok(mut file) = File::open(path) {
+                position = file.seek(SeekFrom::End(0)).unwrap_or(0);
+                reader = Some(BufReader::new(file));
+                partial.clear();
+            }
+        }
+
+        if let Some(current) = reader.as_mut() {
+            // The file was truncated or replaced with a shorter one: start over from the top
+            let file_len = std::fs::metadata(path).map(|m| m.len()).ok();
+            match file_len {
+                Some(len) if len < position => {
+                    position = current.seek(SeekFrom::Start(0)).unwrap_or(0);
+                    partial.clear();
+                }
+                None => {
+                    reader = None; // Removed; reopen once it comes back
+                    wait_for_change(&rx, stop);
+                    continue;
+                }
+                _ => {}
+            }
+
+            loop {
+                match current.read_line(&mut partial) {
+                    Ok(0) => break,
+                    Ok(n) => {
+                        position += n as u64;
+                        // Keep incomplete lines until the writer finishes them
+                        if !partial.ends_with('\n') {
+                            break;
+                        }
+                        let line = partial.trim_end();
+                        if !line.is_empty() {
+                            push_tailed_line(line);
+                        }
+                        partial.clear();
+                    }
+                    Err(_) => break,
+                }
+            }
+        }
+
+        wait_for_change(&rx, stop);
+    }
+}
+
+/// Waits for a filesystem event or a short timeout, whichever comes first.
+fn wait_for_change(rx: &mpsc::Receiver<notify::Result<notify::Event>>, stop: &AtomicBool) {
+    if stop.load(Ordering::SeqCst) {
+        return;
+    }
+    let _ = rx.recv_timeout(Duration::from_millis(250));
+    // Drain bursts of events; the next read picks up everything at once
+    while rx.try_recv().is_ok() {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(message: &str) -> usize {
+        snapshot().iter().filter(|line| line.text.ends_with(message)).count()
+    }
+
+    #[test]
+    fn tailed_copies_of_direct_records_are_skipped_once() {
+        let message = "Uploading chunk 3 of 7 (log_buffer test)";
+        push_raw_line(&format!("12:00:00 [INFO] {}", message), Level::Info);
+        // The child's copy in the shared file, written a second later
+        push_tailed_line(&format!("12:00:01 [INFO] {}", message));
+        assert_eq!(count(message), 1);
+
+        // The same message logged again by a process that is not streamed is still shown
+        push_tailed_line(&format!("12:00:05 [INFO] {}", message));
+        assert_eq!(count(message), 2);
+    }
+
+    #[test]
+    fn untagged_output_is_not_expected_in_the_log_file() {
+        let message = "Pre-flight Check Results (log_buffer test)";
+        push_raw_line(message, Level::Info);
+        push_tailed_line(&format!("12:00:00 [INFO] {}", message));
+        assert_eq!(count(message), 2);
+    }
+}
@@ -0,0 +1,160 @@
+use std::env;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use serde::Deserialize;
+use tracing::{Event, Subscriber};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{Layer, Registry};
+use crate::log_buffer::RingBufferLayer;
+use crate::types::{LogFormat, LoggingConfig};
+
+/// Shared log file of every seed-tools process; the UI log viewer follows it.
+pub const LOG_FILE: &str = "seed-tools.log";
+
+type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+/// Writes `HH:MM:SS [LEVEL] message`, the format the UI log viewer parses.
+pub struct ClassicFormat;
+
+impl<S, N> FormatEvent<S, N> for ClassicFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(&self, ctx: &FmtContext<'_, S, N>, mut writer: Writer<'_>, event: &Event<'_>) -> fmt::Result {
+        write!(writer, "{} [{}] ", chrono::Local::now().format("%H:%M:%S"), event.metadata().level())?;
+        ctx.field_format().format_fields(writer.by_ref(), event)?;
+        writeln!(writer)
+    }
+}
+
+/// Reads only the `logging` section of config.yaml, so logging can start before the full
+/// config is loaded. A missing or broken file means defaults; the full load reports it later.
+pub fn load_config(path: &Path) -> LoggingConfig {
+    #[derive(Deserialize)]
+    struct LoggingOnly {
+        #[serde(default)]
+        logging: LoggingConfig,
+    }
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_yaml::from_str::<LoggingOnly>(&content).ok())
+        .map(|config| config.logging)
+        .unwrap_or_default()
+}
+
+/// Sets up logging for this process: the shared `seed-tools.log` (rotated first if due), the UI
+/// ring buffer, a per-run log under `dir/runs` when `run_name` is given, and stderr when
+/// `SEED_TOOLS_LOG_STDERR` is set. `level` overrides the configured level.
+/// Records from the `log` macros are forwarded too. Returns the per-run log path.
+pub fn init(config: &LoggingConfig, level: Option<&str>, run_name: Option<&str>) -> Result<Option<PathBuf>, String> {
+    let level_name = level.unwrap_or(&config.level);
+    let level: LevelFilter = level_name
+        .parse()
+        .map_err(|_| format!("Invalid log level '{}' (use trace, debug, info, warn, error or off)", level_name))?;
+    let log_dir = PathBuf::from(&config.dir);
+    rotate(Path::new(LOG_FILE), &log_dir, config)?;
+
+    let mut layers: Vec<BoxedLayer> = vec![
+        file_layer(open_append(Path::new(LOG_FILE))?, config.format, level),
+        // In-memory copy of the log for the UI log viewer
+        RingBufferLayer.with_filter(level).boxed(),
+    ];
+
+    let mut run_log = None;
+    if let Some(name) = run_name {
+        let runs_dir = log_dir.join("runs");
+        fs::create_dir_all(&runs_dir).map_err(|e| format!("Failed to create '{}': {}", runs_dir.display(), e))?;
+        let name = name.replace(|c: char| !c.is_ascii_alphanumeric() && c != '.' && c != '-' && c != '_', "_");
+        let path = runs_dir.join(format!("{}_{}.log", chrono::Local::now().format("%Y%m%d-%H%M%S"), name));
+        layers.push(file_layer(open_append(&path)?, config.format, level));
+        prune(&runs_dir, "", config.keep_runs);
+        run_log = Some(path);
+    }
+
+    // The UI spawns uploads as child processes and streams their stderr into its log viewer,
+    // so stderr always uses the classic format
+    if env::var_os("SEED_TOOLS_LOG_STDERR").is_some() {
+        layers.push(
+            tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_writer(std::io::stderr)
+                .event_format(ClassicFormat)
+                .with_filter(level.min(LevelFilter::INFO))
+                .boxed(),
+        );
+    }
+
+    tracing_subscriber::registry()
+        .with(layers)
+        .try_init()
+        .map_err(|e| format!("Failed to initialize logging: {}", e))?;
+    Ok(run_log)
+}
+
+fn open_append(path: &Path) -> Result<File, String> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open log file '{}': {}", path.display(), e))
+}
+
+fn file_layer(file: File, format: LogFormat, level: LevelFilter) -> BoxedLayer {
+    let layer = tracing_subscriber::fmt::layer().with_ansi(false).with_writer(Mutex::new(file));
+    match format {
+        LogFormat::Pretty => layer.event_format(ClassicFormat).with_filter(level).boxed(),
+        LogFormat::Json => layer.json().with_filter(level).boxed(),
+    }
+}
+
+/// Moves the shared log file into `dir` when it reached `max_size_mb` or, with `daily`, was last
+/// written on an earlier day, then keeps only the newest `keep` rotated files.
+fn rotate(path: &Path, dir: &Path, config: &LoggingConfig) -> Result<(), String> {
+    let Ok(metadata) = fs::metadata(path) else { return Ok(()) };
+    let too_big = config.max_size_mb > 0 && metadata.len() >= config.max_size_mb * 1024 * 1024;
+    let from_earlier_day = config.daily
+        && metadata.modified().is_ok_and(|modified| {
+            chrono::DateTime::<chrono::Local>::from(modified).date_naive() < chrono::Local::now().date_naive()
+        });
+    if !too_big && !from_earlier_day {
+        return Ok(());
+    }
+
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create '{}': {}", dir.display(), e))?;
+    let target = dir.join(format!("seed-tools-{}.log", chrono::Local::now().format("%Y%m%d-%H%M%S")));
+    match fs::rename(path, &target) {
+        Ok(()) => {}
+        // Another process starting at the same time rotated it already
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(format!("Failed to rotate '{}' to '{}': {}", path.display(), target.display(), e)),
+    }
+    prune(dir, "seed-tools-", config.keep);
+    Ok(())
+}
+
+/// Deletes the oldest `.log` files starting with `prefix` in `dir` beyond `keep`.
+/// The names start with a timestamp, so they sort by age.
+fn prune(dir: &Path, prefix: &str, keep: usize) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    let mut logs: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.is_file()
+                && path.extension().is_some_and(|ext| ext == "log")
+                && path.file_name().is_some_and(|name| name.to_string_lossy().starts_with(prefix))
+        })
+        .collect();
+    logs.sort();
+    let excess = logs.len().saturating_sub(keep);
+    for path in logs.into_iter().take(excess) {
+        let _ = fs::remove_file(path);
+    }
+}
@@ -0,0 +1,138 @@
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use log::warn;
+use crate::pipeline::work_dir;
+use crate::types::PathsConfig;
+
+// Upload runs are separate processes (the web/API jobs spawn the CLI), so each process appends
+// its counters to a journal in work_dir when it finishes and `/metrics` sums the journal up.
+static JOURNAL: OnceLock<PathBuf> = OnceLock::new();
+// Counters of this process not written to the journal yet, keyed by series, e.g. `name{label="value"}`
+static PENDING: OnceLock<Mutex<BTreeMap<String, f64>>> = OnceLock::new();
+
+/// Metric families with their type and help text, in output order.
+const FAMILIES: &[(&str, &str, &str)] = &[
+    ("seed_tools_uploads_attempted_total", "counter", "Uploads started, per tracker."),
+    ("seed_tools_uploads_succeeded_total", "counter", "Uploads accepted by the tracker, per tracker."),
+    ("seed_tools_uploads_failed_total", "counter", "Uploads that failed, per tracker."),
+    ("seed_tools_bytes_hashed_total", "counter", "Bytes of release data hashed into torrents."),
+    ("seed_tools_external_requests_total", "counter", "Requests to external APIs, per API."),
+    ("seed_tools_external_errors_total", "counter", "Failed requests to external APIs, per API."),
+    ("seed_tools_stage_duration_seconds", "summary", "Time spent per upload pipeline stage."),
+];
+
+/// Sets the journal location to `work_dir/metrics.jsonl`. Call once at startup.
+pub fn init(paths: &PathsConfig) {
+    let _ = JOURNAL.set(work_dir(paths).join("metrics.jsonl"));
+}
+
+fn pending() -> &'static Mutex<BTreeMap<String, f64>> {
+    PENDING.get_or_init(|| Mutex::new(BTreeMap::new()))
+}
+
+fn series(name: &str, labels: &[(&str, &str)]) -> String {
+    if labels.is_empty() {
+        return name.to_string();
+    }
+    let labels: Vec<String> = labels
+        .iter()
+        .map(|(key, value)| format!("{}=\"{}\"", key, value.replace('\\', "\\\\").replace('"', "\\\"")))
+        .collect();
+    format!("{}{{{}}}", name, labels.join(","))
+}
+
+/// Adds `value` to a counter.
+pub fn add(name: &str, labels: &[(&str, &str)], value: f64) {
+    *pending().lock().unwrap().entry(series(name, labels)).or_insert(0.0) += value;
+}
+
+/// Records one attempted upload and its outcome.
+pub fn record_upload<T, E>(tracker: &str, result: &Result<T, E>) {
+    let labels = [("tracker", tracker)];
+    add("seed_tools_uploads_attempted_total", &labels, 1.0);
+    match result {
+        Ok(_) => add("seed_tools_uploads_succeeded_total", &labels, 1.0),
+        Err(_) => add("seed_tools_uploads_failed_total", &labels, 1.0),
+    }
+}
+
+/// Records one request to an external API (TMDB, ImgBB, a tracker, ...).
+pub fn record_request(api: &str, ok: bool) {
+    let labels = [("api", api)];
+    add("seed_tools_external_requests_total", &labels, 1.0);
+    if !ok {
+        add("seed_tools_external_errors_total", &labels, 1.0);
+    }
+}
+
+/// Records how long a pipeline stage took.
+pub fn record_stage(tracker: &str, stage: &str, duration: Duration) {
+    let labels = [("tracker", tracker), ("stage", stage)];
+    add("seed_tools_stage_duration_seconds_sum", &labels, duration.as_secs_f64());
+    add("seed_tools_stage_duration_seconds_count", &labels, 1.0);
+}
+
+/// Flushes the counters when dropped, so every return path of a CLI run writes them.
+pub struct FlushGuard;
+
+impl Drop for FlushGuard {
+    fn drop(&mut self) {
+        flush();
+    }
+}
+
+/// Appends this process's counters to the journal. Call before the process exits.
+pub fn flush() {
+    let Some(journal) = JOURNAL.get() else { return };
+    let counters = std::mem::take(&mut *pending().lock().unwrap());
+    if counters.is_empty() {
+        return;
+    }
+    let result = serde_json::to_string(&counters)
+        .map_err(|e| e.to_string())
+        .and_then(|line| {
+            if let Some(dir) = journal.parent() {
+                fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+            }
+            // One short line per run; appends from parallel uploads do not interleave
+            let mut file = OpenOptions::new().create(true).append(true).open(journal).map_err(|e| e.to_string())?;
+            writeln!(file, "{}", line).map_err(|e| e.to_string())
+        });
+    if let Err(e) = result {
+        warn!("Failed to write metrics to {}: {}", journal.display(), e);
+    }
+}
+
+/// Renders the journal plus this process's counters in the Prometheus text format.
+pub fn render() -> String {
+    let mut totals = pending().lock().unwrap().clone();
+    if let Some(content) = JOURNAL.get().and_then(|journal| fs::read_to_string(journal).ok()) {
+        for line in content.lines() {
+            let Ok(counters) = serde_json::from_str::<BTreeMap<String, f64>>(line) else { continue };
+            for (series, value) in counters {
+                *totals.entry(series).or_insert(0.0) += value;
+            }
+        }
+    }
+
+    let mut output = String::new();
+    for (family, kind, help) in FAMILIES {
+        output.push_str(&format!("# HELP {} {}\n# TYPE {} {}\n", family, help, family, kind));
+        for (series, value) in &totals {
+            let name = series.split('{').next().unwrap_or(series);
+            let belongs = if *kind == "summary" {
+                name == format!("{}_sum", family) || name == format!("{}_count", family)
+            } else {
+                name == *family
+            };
+            if belongs {
+                output.push_str(&format!("{} {}\n", series, value));
+            }
+        }
+    }
+    output
+}
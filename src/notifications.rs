@@ -0,0 +1,303 @@
+use std::time::Duration;
+use log::{info, warn};
+use reqwest::blocking::Client;
+use serde::Serialize;
+use serde_json::json;
+use crate::error::SeedToolsError;
+use crate::pipeline::ReleaseCheckpoint;
+use serde::Deserialize;
+use crate::types::{NotificationSinkKind, NotificationsConfig};
+
+/// What a notification reports on.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationEvent {
+    Upload,  // An upload to one tracker finished or failed
+    Dupe,    // The tracker already had the release; the existing torrent was cross-seeded instead
+    Sync,    // A `--sync` cross-seeding run finished or failed
+    Batch,   // The UI upload queue ran empty
+    Partial, // A multi-tracker upload went through on some trackers but failed for good on others
+}
+
+/// One result sent to every configured sink. Webhooks receive it as JSON as-is.
+#[derive(Serialize, Clone, Debug)]
+pub struct Notification {
+    pub event: NotificationEvent,
+    pub success: bool,
+    pub title: String,             // Release name, or a summary for sync runs
+    pub message: String,           // Outcome or error
+    pub tracker: Option<String>,
+    pub dupe: Option<String>,      // Dupe check result, e.g. "passed" or the existing torrent's link
+    pub link: Option<String>,      // Torrent page on the tracker
+    pub thumbnail: Option<String>, // First screenshot thumbnail
+}
+
+impl Notification {
+    /// Builds the notification for an upload that stopped at `checkpoint`.
+    pub fn upload<E: std::fmt::Display>(tracker: &str, checkpoint: &ReleaseCheckpoint, result: &Result<(), E>) -> Notification {
+        let title = if checkpoint.release_name.is_empty() {
+            checkpoint.input_path.clone()
+        } else {
+            checkpoint.release_name.clone()
+        };
+        // A matched dupe is its own event, so nobody mistakes it for an upload that silently did nothing
+        let (event, mut message, dupe, link) = match (result, &checkpoint.dupe_link) {
+            (Ok(()), Some(link)) => (
+                NotificationEvent::Dupe,
+                format!("Already on {}, cross-seeded the existing torrent instead of uploading", tracker),
+                Some("dupe found".to_string()),
+                Some(link.clone()),
+            ),
+            (Ok(()), None) => (NotificationEvent::Upload, format!("Uploaded to {}", tracker), Some("passed".to_string()), checkpoint.torrent_url.clone()),
+            (Err(e), _) => {
+                let message = match checkpoint.stage {
+                    Some(stage) => format!("Upload to {} failed after stage {:?}: {}", tracker, stage, e),
+                    None => format!("Upload to {} failed: {}", tracker, e),
+                };
+                (NotificationEvent::Upload, message, None, checkpoint.torrent_url.clone())
+            }
+        };
+        // Client states show whether the injected torrent is really seeding
+        for state in &checkpoint.client_states {
+            message.push_str(&format!("\n{}", state));
+        }
+        Notification {
+            event,
+            success: result.is_ok(),
+            title,
+            message,
+            tracker: Some(tracker.to_string()),
+            dupe,
+            link,
+            thumbnail: checkpoint.thumbnails.first().cloned(),
+        }
+    }
+
+    /// Builds the notification for a finished `--sync` run.
+    pub fn sync(result: &Result<usize, SeedToolsError>) -> Notification {
+        let message = match result {
+            Ok(added) => format!("Added {} cross-seed torrent(s) to qBittorrent", added),
+            Err(e) => format!("Sync failed: {}", e),
+        };
+        Notification {
+            event: NotificationEvent::Sync,
+            success: result.is_ok(),
+            title: "qBittorrent sync".to_string(),
+            message,
+            tracker: Some("Seedpool".to_string()),
+            dupe: None,
+            link: None,
+            thumbnail: None,
+        }
+    }
+
+    /// Builds the summary for a drained upload queue; it fails when any item failed.
+    pub fn batch(succeeded: usize, failed: &[String]) -> Notification {
+        let mut message = format!("{} upload(s) succeeded, {} failed", succeeded, failed.len());
+        for path in failed {
+            message.push_str(&format!("\nFailed: {}", path));
+        }
+        Notification {
+            event: NotificationEvent::Batch,
+            success: failed.is_empty(),
+            title: "Upload queue finished".to_string(),
+            message,
+            tracker: None,
+            dupe: None,
+            link: None,
+            thumbnail: None,
+        }
+    }
+
+    /// Builds the report for an upload of `title` that only some trackers accepted. Trackers
+    /// can not take back an upload through their APIs, so this asks the user to step in.
+    pub fn partial(title: &str, succeeded: &[String], failed: &[String]) -> Notification {
+        let mut message = format!(
+            "Uploaded to {} but failed on {} tracker(s); needs your attention",
+            succeeded.join(", "),
+            failed.len()
+        );
+        for error in failed {
+            message.push_str(&format!("\nFailed: {}", error));
+        }
+        Notification {
+            event: NotificationEvent::Partial,
+            success: false,
+            title: title.to_string(),
+            message,
+            tracker: None,
+            dupe: None,
+            link: None,
+            thumbnail: None,
+        }
+    }
+
+    fn headline(&self) -> String {
+        let icon = match (self.event, self.success) {
+            (NotificationEvent::Dupe, _) => "♻️",
+            (NotificationEvent::Partial, _) => "⚠️",
+            (_, true) => "✅",
+            (_, false) => "❌",
+        };
+        format!("{} {}", icon, self.title)
+    }
+
+    /// Message, tracker, dupe result and link as plain text lines.
+    fn body(&self) -> String {
+        let mut lines = vec![self.message.clone()];
+        if let Some(tracker) = &self.tracker {
+            lines.push(format!("Tracker: {}", tracker));
+        }
+        if let Some(dupe) = &self.dupe {
+            lines.push(format!("Dupe check: {}", dupe));
+        }
+        if let Some(link) = &self.link {
+            lines.push(link.clone());
+        }
+        lines.join("\n")
+    }
+}
+
+/// Sends `notification` to every sink in the `notifications` section of config.yaml.
+/// Failures are only logged; a broken sink never fails the upload or sync it reports on.
+pub fn notify(config: &NotificationsConfig, notification: &Notification) {
+    let sinks: Vec<_> = config
+        .sinks
+        .iter()
+        .filter(|sink| sink.wants(notification.event, notification.success))
+        .collect();
+    if sinks.is_empty() {
+        return;
+    }
+    let client = match crate::http::client_builder().timeout(Duration::from_secs(15)).build() {
+        Ok(client) => client,
+        Err(e) => {
+            warn!("Failed to create notification client: {}", e);
+            return;
+        }
+    };
+    for sink in sinks {
+        match send(&client, &sink.kind, notification) {
+            Ok(()) => info!("Sent {} notification to {}", notification.title, sink.kind.name()),
+            Err(e) => warn!("{} notification failed: {}", sink.kind.name(), e),
+        }
+    }
+}
+
+fn send(client: &Client, sink: &NotificationSinkKind, notification: &Notification) -> Result<(), String> {
+    let request = match sink {
+        NotificationSinkKind::Discord { webhook_url } => {
+            let mut fields = Vec::new();
+            if let Some(tracker) = &notification.tracker {
+                fields.push(json!({ "name": "Tracker", "value": tracker, "inline": true }));
+            }
+            if let Some(dupe) = &notification.dupe {
+                fields.push(json!({ "name": "Dupe check", "value": dupe, "inline": true }));
+            }
+            let mut embed = json!({
+                "title": notification.headline(),
+                "description": notification.message,
+                "color": match (notification.event, notification.success) {
+                    (NotificationEvent::Dupe, _) => 0xf1c40f,
+                    (NotificationEvent::Partial, _) => 0xe67e22,
+                    (_, true) => 0x2ecc71,
+                    (_, false) => 0xe74c3c,
+                },
+                "fields": fields,
+            });
+            if let Some(link) = &notification.link {
+                embed["url"] = json!(link);
+            }
+            if let Some(thumbnail) = &notification.thumbnail {
+                embed["thumbnail"] = json!({ "url": thumbnail });
+            }
+            client.post(webhook_url).json(&json!({ "embeds": [embed] }))
+        }
+        NotificationSinkKind::Telegram { bot_token, chat_id } => {
+            let text = format!("{}\n{}", notification.headline(), notification.body());
+            match &notification.thumbnail {
+                Some(thumbnail) => client
+                    .post(format!("https://api.telegram.org/bot{}/sendPhoto", bot_token))
+                    .json(&json!({ "chat_id": chat_id, "photo": thumbnail, "caption": text })),
+                None => client
+                    .post(format!("https://api.telegram.org/bot{}/sendMessage", bot_token))
+                    .json(&json!({ "chat_id": chat_id, "text": text })),
+            }
+        }
+        NotificationSinkKind::Gotify { url, token, priority } => {
+            let mut extras = json!({ "client::display": { "contentType": "text/plain" } });
+            if let Some(link) = &notification.link {
+                extras["client::notification"] = json!({ "click": { "url": link } });
+            }
+            if let Some(thumbnail) = &notification.thumbnail {
+                extras["client::notification"]["bigImageUrl"] = json!(thumbnail);
+            }
+            client
+                .post(format!("{}/message", url.trim_end_matches('/')))
+                .header("X-Gotify-Key", token)
+                .json(&json!({
+                    "title": notification.headline(),
+                    "message": notification.body(),
+                    "priority": priority,
+                    "extras": extras,
+                }))
+        }
+        NotificationSinkKind::Webhook { url, headers } => {
+            let mut request = client.post(url).json(notification);
+            for (name, value) in headers {
+                request = request.header(name, value);
+            }
+            request
+        }
+        NotificationSinkKind::Ntfy { url, topic, token, priority } => {
+            let mut body = json!({
+                "topic": topic,
+                "title": notification.headline(),
+                "message": notification.body(),
+                "priority": priority,
+                "tags": [match (notification.event, notification.success) {
+                    (NotificationEvent::Dupe, _) => "recycle",
+                    (NotificationEvent::Partial, _) => "warning",
+                    (_, true) => "white_check_mark",
+                    (_, false) => "x",
+                }],
+            });
+            if let Some(link) = &notification.link {
+                body["click"] = json!(link);
+            }
+            if let Some(thumbnail) = &notification.thumbnail {
+                body["attach"] = json!(thumbnail);
+            }
+            // Publishing as JSON goes to the server root, not the topic URL
+            let mut request = client.post(url.trim_end_matches('/')).json(&body);
+            if let Some(token) = token {
+                request = request.bearer_auth(token);
+            }
+            request
+        }
+        NotificationSinkKind::Pushover { user_key, api_token, device, priority } => {
+            let mut form = vec![
+                ("token", api_token.clone()),
+                ("user", user_key.clone()),
+                ("title", notification.headline()),
+                ("message", notification.body()),
+                ("priority", priority.to_string()),
+            ];
+            if let Some(device) = device {
+                form.push(("device", device.clone()));
+            }
+            if let Some(link) = &notification.link {
+                form.push(("url", link.clone()));
+            }
+            client.post("https://api.pushover.net/1/messages.json").form(&form)
+        }
+    };
+
+    let response = request.send().map_err(|e| format!("Request failed: {}", e))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().unwrap_or_default();
+        return Err(format!("HTTP {}: {}", status, body.chars().take(200).collect::<String>()));
+    }
+    Ok(())
+}
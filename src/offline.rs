@@ -0,0 +1,288 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use log::info;
+use serde_json::{json, Value};
+use crate::error::SeedToolsError;
+use crate::seeding::{SeedingState, TorrentClient};
+use crate::trackers::seedpool::{extract_torrent_id, UploadRequest};
+use crate::trackers::Tracker;
+
+// Offline mode (`--offline`): the upload pipelines run end to end against local stand-ins.
+// Uploads are recorded by a MockTracker and torrents "seeded" by a MockTorrentClient, both
+// writing into one directory. The stand-ins are passed to the pipelines as an `Offline`
+// (`UploadOptions.offline`, or the `offline` parameter of the lower-level functions); the
+// pipelines then skip dupe searches. The binary also sets `network.offline`, so no request
+// leaves the machine and lookups that need the network (TMDB, IGDB) fail; pass the IDs as
+// overrides.
+
+/// Host of the download links the mock tracker hands out.
+pub const MOCK_HOST: &str = "offline.invalid";
+
+pub struct Offline {
+    pub dir: PathBuf,
+    pub tracker: MockTracker,
+    pub client: MockTorrentClient,
+}
+
+/// The tracker an upload goes to: `tracker`, or the mock tracker in offline mode.
+pub fn tracker_or<'a>(offline: Option<&'a Offline>, tracker: &'a dyn Tracker) -> &'a dyn Tracker {
+    match offline {
+        Some(offline) => &offline.tracker,
+        None => tracker,
+    }
+}
+
+impl Offline {
+    /// Offline stand-ins recording into `dir`, which is created if needed.
+    pub fn new(dir: &Path) -> Result<Offline, String> {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create offline directory '{}': {}", dir.display(), e))?;
+        info!("Offline mode: uploads are recorded in {}", dir.display());
+        Ok(Offline {
+            dir: dir.to_path_buf(),
+            tracker: MockTracker::new(dir.join("uploads")),
+            client: MockTorrentClient,
+        })
+    }
+
+    /// Stands in for the CDN upload: copies `file` under `cdn/` with the remote path's folder
+    /// name, e.g. `cdn/covers/torrent-cover_12.jpg`.
+    pub fn copy_to_cdn(&self, file: &Path, remote: &str) -> Result<(), SeedToolsError> {
+        let remote_dir = remote.rsplit(['/', ':']).find(|part| !part.is_empty()).unwrap_or("cdn");
+        let dir = self.dir.join("cdn").join(remote_dir);
+        fs::create_dir_all(&dir).map_err(SeedToolsError::io(format!("Failed to create '{}'", dir.display())))?;
+        let target = dir.join(file.file_name().unwrap_or_default());
+        fs::copy(file, &target).map_err(SeedToolsError::io(format!("Failed to copy '{}' to '{}'", file.display(), target.display())))?;
+        info!("Offline: stored '{}' as {}", file.display(), target.display());
+        Ok(())
+    }
+}
+
+/// A tracker that accepts every upload. Each upload gets the next ID and is kept as
+/// `<id>.torrent` plus `<id>.json` with the fields that would have been sent.
+pub struct MockTracker {
+    dir: PathBuf,
+}
+
+impl MockTracker {
+    pub fn new(dir: PathBuf) -> Self {
+        MockTracker { dir }
+    }
+
+    /// The uploads recorded so far, oldest first, as written to `<id>.json`.
+    pub fn uploads(&self) -> Vec<Value> {
+        let mut uploads: Vec<(u32, Value)> = fs::read_dir(&self.dir)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("json"))
+            .filter_map(|path| {
+                let id = path.file_stem()?.to_str()?.parse().ok()?;
+                let upload = serde_json::from_str(&fs::read_to_string(&path).ok()?).ok()?;
+                Some((id, upload))
+            })
+            .collect();
+        uploads.sort_by_key(|(id, _)| *id);
+        uploads.into_iter().map(|(_, upload)| upload).collect()
+    }
+
+    /// Records an upload of `torrent_file` to `tracker` and returns its ID.
+    pub fn record(&self, tracker: &str, release_name: &str, torrent_file: &str, fields: Value) -> Result<u32, String> {
+        fs::create_dir_all(&self.dir).map_err(|e| format!("Failed to create '{}': {}", self.dir.display(), e))?;
+        let id = self.uploads().len() as u32 + 1;
+        let torrent_copy = self.dir.join(format!("{}.torrent", id));
+        fs::copy(torrent_file, &torrent_copy).map_err(|e| format!("Failed to copy '{}': {}", torrent_file, e))?;
+        let upload = json!({
+            "id": id,
+            "tracker": tracker,
+            "name": release_name,
+            "torrent_file": torrent_file,
+            "fields": fields,
+        });
+        let upload_file = self.dir.join(format!("{}.json", id));
+        fs::write(&upload_file, serde_json::to_string_pretty(&upload).unwrap_or_default())
+            .map_err(|e| format!("Failed to write '{}': {}", upload_file.display(), e))?;
+        info!("Offline: recorded '{}' for {} as upload {}", release_name, tracker, id);
+        Ok(id)
+    }
+
+    /// Records a UNIT3D upload and answers like UNIT3D does, with a download link.
+    pub fn submit(&self, tracker: &str, upload: &UploadRequest) -> Result<String, SeedToolsError> {
+        upload.validate()?;
+        let fields = json!({
+            "category_id": upload.category_id,
+            "type_id": upload.type_id,
+            "resolution_id": upload.resolution_id,
+            "tmdb": upload.tmdb_id,
+            "imdb": upload.imdb_id,
+            "tvdb": upload.tvdb_id,
+            "season_number": upload.season_number,
+            "episode_number": upload.episode_number,
+            "description": upload.description,
+            "mediainfo": upload.mediainfo,
+            "nfo_file": upload.nfo_file,
+            "anonymous": upload.anonymous,
+            "internal": upload.internal,
+        });
+        let id = self.record(tracker, &upload.name, &upload.torrent_file, fields)?;
+        Ok(json!({
+            "success": true,
+            "data": format!("https://{}/torrent/download/{}.{:032x}", MOCK_HOST, id, id),
+            "message": "Torrent uploaded successfully.",
+        })
+        .to_string())
+    }
+
+    /// The .torrent behind a download link from `submit`.
+    pub fn download(&self, download_url: &str) -> Result<Vec<u8>, SeedToolsError> {
+        let id = extract_torrent_id(download_url)?;
+        let path = self.dir.join(format!("{}.torrent", id));
+        fs::read(&path).map_err(SeedToolsError::io(format!("Failed to read '{}'", path.display())))
+    }
+}
+
+impl Tracker for MockTracker {
+    fn requires_screenshots(&self) -> bool {
+        false
+    }
+
+    fn requires_sample(&self) -> bool {
+        false
+    }
+
+    fn requires_tmdb_id(&self) -> bool {
+        false
+    }
+
+    fn requires_remote_path(&self) -> bool {
+        false
+    }
+
+    fn upload(&self, upload: UploadRequest) -> Result<String, SeedToolsError> {
+        self.submit("offline", &upload)
+    }
+
+    fn generate_metadata(&self, _torrent_file: &str) -> Result<HashMap<String, String>, SeedToolsError> {
+        Ok(HashMap::new())
+    }
+}
+
+/// A torrent client that checks the torrent against the release data, as a client's recheck
+/// would, and reports the state it would end up in.
+#[derive(Default)]
+pub struct MockTorrentClient;
+
+impl TorrentClient for MockTorrentClient {
+    fn name(&self) -> String {
+        "Offline client".to_string()
+    }
+
+    fn add_torrent(&self, torrent_file: &str, input_path: &str) -> Result<SeedingState, SeedToolsError> {
+        let torrent = fs::read(torrent_file).map_err(SeedToolsError::io(format!("Failed to read '{}'", torrent_file)))?;
+        let report = crate::torrent::verify(&torrent, Path::new(input_path))?;
+        Ok(if report.is_complete() {
+            SeedingState::Seeding
+        } else if report.valid_pieces == 0 && !report.missing_files.is_empty() {
+            SeedingState::MissingFiles
+        } else {
+            SeedingState::Incomplete(report.percent / 100.0)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::trackers::{Tools, UploadOptions};
+    use crate::types::{Config, TorrentLeechConfig, TorrentOptions, TorrentVersion};
+
+    const RELEASE: &str = "Some.Movie.2020.1080p.BluRay.x264-GRP";
+
+    // A release folder with one small "video" and a hybrid torrent of it, made without mkbrr
+    fn release(dir: &Path) -> (PathBuf, PathBuf) {
+        let release = dir.join(RELEASE);
+        fs::create_dir(&release).unwrap();
+        fs::write(release.join(format!("{}.mkv", RELEASE)), vec![7u8; 100_000]).unwrap();
+        let torrent_file = dir.join(format!("{}.torrent", RELEASE));
+        let options = TorrentOptions { version: TorrentVersion::Hybrid, ..Default::default() };
+        crate::torrent::build(&release, &torrent_file, "https://tracker.invalid/announce", "seedpool.org", None, &options).unwrap();
+        (release, torrent_file)
+    }
+
+    #[test]
+    fn mock_tracker_serves_the_torrent_it_recorded() {
+        let dir = tempfile::tempdir().unwrap();
+        let (_, torrent_file) = release(dir.path());
+        let offline = Offline::new(&dir.path().join("offline")).unwrap();
+        let upload = UploadRequest {
+            torrent_file: torrent_file.to_string_lossy().to_string(),
+            name: RELEASE.to_string(),
+            category_id: 1,
+            type_id: 3,
+            tv: Some(false),
+            ..Default::default()
+        };
+
+        let response = offline.tracker.submit("Seedpool", &upload).unwrap();
+        let download_url = crate::trackers::seedpool::extract_download_url(&response).unwrap();
+
+        assert_eq!(offline.tracker.download(&download_url).unwrap(), fs::read(&torrent_file).unwrap());
+        let uploads = offline.tracker.uploads();
+        assert_eq!(uploads.len(), 1);
+        assert_eq!(uploads[0]["name"], RELEASE);
+        assert_eq!(uploads[0]["fields"]["category_id"], 1);
+    }
+
+    #[test]
+    fn mock_client_seeds_only_complete_data() {
+        let dir = tempfile::tempdir().unwrap();
+        let (release, torrent_file) = release(dir.path());
+        let client = MockTorrentClient;
+        let torrent_file = torrent_file.to_string_lossy();
+
+        assert_eq!(client.add_torrent(&torrent_file, &release.to_string_lossy()).unwrap(), SeedingState::Seeding);
+        fs::remove_file(release.join(format!("{}.mkv", RELEASE))).unwrap();
+        assert_eq!(client.add_torrent(&torrent_file, &release.to_string_lossy()).unwrap(), SeedingState::MissingFiles);
+    }
+
+    #[test]
+    #[ignore = "needs mediainfo on $PATH; run with --ignored"]
+    fn torrentleech_upload_runs_end_to_end_offline() {
+        let mediainfo = crate::binaries::find_in_path("mediainfo").expect("mediainfo not found on $PATH");
+        let dir = tempfile::tempdir().unwrap();
+        let (release, _) = release(dir.path());
+        // A real (one second, 320x240 H.264) Matroska file, so MediaInfo has something to read
+        fs::copy("fixtures/media/video.mkv", release.join(format!("{}.mkv", RELEASE))).unwrap();
+        let mut config: Config = serde_yaml::from_str(include_str!("../config/config.yaml")).unwrap();
+        config.paths.torrent_dir = dir.path().join("torrents").to_string_lossy().to_string();
+        config.paths.screenshots_dir = dir.path().join("screenshots").to_string_lossy().to_string();
+        config.paths.work_dir = Some(dir.path().join("work").to_string_lossy().to_string());
+        config.paths.staging_dir = None;
+        let mut torrentleech: TorrentLeechConfig = serde_yaml::from_str(include_str!("../config/trackers/torrentleech.yaml")).unwrap();
+        torrentleech.torrent.version = TorrentVersion::Hybrid;
+        torrentleech.proof.enabled = false;
+        let offline = Arc::new(Offline::new(&dir.path().join("offline")).unwrap());
+        let tools = Tools {
+            ffmpeg: PathBuf::from("ffmpeg"),
+            ffprobe: PathBuf::from("ffprobe"),
+            mkbrr: PathBuf::from("mkbrr"), // Not run for hybrid torrents
+            mediainfo,
+        };
+        let options = UploadOptions { fresh: true, offline: Some(Arc::clone(&offline)), ..Default::default() };
+
+        crate::trackers::release::torrentleech::process_torrentleech_release(
+            &release.to_string_lossy(),
+            &mut config,
+            &torrentleech,
+            &tools,
+            &options,
+        )
+        .unwrap();
+
+        let uploads = offline.tracker.uploads();
+        assert_eq!(uploads.len(), 1);
+        assert_eq!(uploads[0]["tracker"], "TorrentLeech");
+        assert_eq!(uploads[0]["name"], RELEASE);
+    }
+}
@@ -0,0 +1,130 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use log::info;
+use serde_json::{json, Value};
+use crate::types::{from_client_path, DelugeConfig, QbittorrentConfig};
+use crate::utils::{format_size, total_size};
+
+/// A file or folder in a data directory that no torrent in the clients points to.
+#[derive(Debug, Clone)]
+pub struct OrphanedEntry {
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+/// Lists the entries directly below `data_dirs` that are not part of any torrent in the
+/// configured qBittorrent instances or Deluge, largest first. Fails when a client can not be
+/// asked, since everything it seeds would otherwise show up as orphaned.
+pub fn find_orphans(
+    data_dirs: &[PathBuf],
+    qbittorrent: &[QbittorrentConfig],
+    deluge: &DelugeConfig,
+) -> Result<Vec<OrphanedEntry>, String> {
+    let mut content_paths = HashSet::new();
+    for config in qbittorrent {
+        content_paths.extend(qbittorrent_content_paths(config)?);
+    }
+    content_paths.extend(deluge_content_paths(deluge)?);
+    info!("Found {} torrent content paths in the clients.", content_paths.len());
+
+    let mut orphans = Vec::new();
+    for dir in data_dirs {
+        let entries = fs::read_dir(dir).map_err(|e| format!("Failed to read '{}': {}", dir.display(), e))?;
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let path = entry.path();
+            // Matches the torrent's own folder or file, and folders holding single-file torrents
+            let referenced = content_paths.iter().any(|content| content.starts_with(&path));
+            if !referenced {
+                let size = total_size(&path.to_string_lossy());
+                orphans.push(OrphanedEntry { path, size });
+            }
+        }
+    }
+    orphans.sort_by_key(|orphan| std::cmp::Reverse(orphan.size));
+    Ok(orphans)
+}
+
+/// Content paths of every torrent in one qBittorrent instance.
+fn qbittorrent_content_paths(config: &QbittorrentConfig) -> Result<Vec<PathBuf>, String> {
+    let client = crate::http::client_builder()
+        .cookie_store(true)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let login_response = client
+        .post(format!("{}/api/v2/auth/login", config.webui_url))
+        .form(&[
+            ("username", config.username.as_str()),
+            ("password", config.password.as_str()),
+        ])
+        .send()
+        .map_err(|e| format!("Failed to log in to qBittorrent at {}: {}", config.webui_url, e))?;
+    if !login_response.status().is_success() {
+        return Err(format!("Failed to log in to qBittorrent at {}: {}", config.webui_url, login_response.status()));
+    }
+
+    let torrents: Vec<Value> = client
+        .get(format!("{}/api/v2/torrents/info", config.webui_url))
+        .send()
+        .and_then(|response| response.json())
+        .map_err(|e| format!("Failed to fetch torrents from {}: {}", config.webui_url, e))?;
+    Ok(torrents
+        .iter()
+        .filter_map(|torrent| torrent["content_path"].as_str())
+        .filter(|path| !path.is_empty())
+        .map(|path| PathBuf::from(from_client_path(&config.path_mappings, path)))
+        .collect())
+}
+
+/// Content paths (save path plus torrent name) of every torrent in Deluge.
+fn deluge_content_paths(config: &DelugeConfig) -> Result<Vec<PathBuf>, String> {
+    let client = crate::http::client_builder()
+        .cookie_store(true)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let login: Value = client
+        .post(format!("{}/json", config.webui_url))
+        .json(&json!({ "method": "auth.login", "params": [config.password], "id": 1 }))
+        .send()
+        .and_then(|response| response.json())
+        .map_err(|e| format!("Failed to log in to Deluge: {}", e))?;
+    if !login["result"].as_bool().unwrap_or(false) {
+        return Err("Failed to log in to Deluge: Invalid credentials".to_string());
+    }
+
+    let status: Value = client
+        .post(format!("{}/json", config.webui_url))
+        .json(&json!({ "method": "core.get_torrents_status", "params": [{}, ["save_path", "name"]], "id": 2 }))
+        .send()
+        .and_then(|response| response.json())
+        .map_err(|e| format!("Failed to fetch torrents from Deluge: {}", e))?;
+    if !status["error"].is_null() {
+        return Err(format!("Deluge returned an error while listing torrents: {}", status["error"]));
+    }
+    Ok(status["result"]
+        .as_object()
+        .map(|torrents| {
+            torrents
+                .values()
+                .filter_map(|torrent| {
+                    let save_path = from_client_path(&config.path_mappings, torrent["save_path"].as_str()?);
+                    Some(Path::new(&save_path).join(torrent["name"].as_str()?))
+                })
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Writes the orphans as CSV with a header line.
+pub fn write_csv(path: &Path, orphans: &[OrphanedEntry]) -> Result<(), String> {
+    let mut csv = String::from("path,size_bytes,size\n");
+    for orphan in orphans {
+        csv.push_str(&format!(
+            "\"{}\",{},{}\n",
+            orphan.path.to_string_lossy().replace('"', "\"\""),
+            orphan.size,
+            format_size(orphan.size)
+        ));
+    }
+    fs::write(path, csv).map_err(|e| format!("Failed to write '{}': {}", path.display(), e))
+}
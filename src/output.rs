@@ -0,0 +1,76 @@
+use serde::Serialize;
+use serde_json::Value;
+
+/// How commands print their results (`--output`). `Table` is the human-readable text the
+/// UI parses, so it stays the default.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Default)]
+pub enum OutputFormat {
+    #[default]
+    Table,
+    Json,
+    Yaml,
+    Csv,
+}
+
+/// Renders `records` as JSON, YAML or CSV. Returns `None` for `Table`, which every command
+/// prints its own way.
+pub fn render<T: Serialize>(format: OutputFormat, records: &[T]) -> Result<Option<String>, String> {
+    match format {
+        OutputFormat::Table => Ok(None),
+        OutputFormat::Json => serde_json::to_string_pretty(records)
+            .map(Some)
+            .map_err(|e| format!("Failed to write JSON: {}", e)),
+        OutputFormat::Yaml => serde_yaml::to_string(records)
+            .map(Some)
+            .map_err(|e| format!("Failed to write YAML: {}", e)),
+        OutputFormat::Csv => to_csv(records).map(Some),
+    }
+}
+
+/// One row per record with a header taken from the first record's fields. Nested values are
+/// written as JSON, lists of plain values joined with `;`.
+fn to_csv<T: Serialize>(records: &[T]) -> Result<String, String> {
+    let rows: Vec<Value> = records
+        .iter()
+        .map(serde_json::to_value)
+        .collect::<Result<_, _>>()
+        .map_err(|e| format!("Failed to write CSV: {}", e))?;
+    let columns: Vec<String> = match rows.first() {
+        Some(Value::Object(fields)) => fields.keys().cloned().collect(),
+        _ => vec!["value".to_string()],
+    };
+
+    let mut csv = columns.iter().map(|column| csv_field(column)).collect::<Vec<_>>().join(",");
+    csv.push('\n');
+    for row in &rows {
+        let fields: Vec<String> = match row {
+            Value::Object(fields) => columns
+                .iter()
+                .map(|column| csv_field(&csv_value(fields.get(column).unwrap_or(&Value::Null))))
+                .collect(),
+            other => vec![csv_field(&csv_value(other))],
+        };
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    Ok(csv)
+}
+
+fn csv_value(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        Value::Array(items) if items.iter().all(|item| !item.is_array() && !item.is_object()) => {
+            items.iter().map(csv_value).collect::<Vec<_>>().join(";")
+        }
+        other => other.to_string(),
+    }
+}
+
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
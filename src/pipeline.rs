@@ -0,0 +1,190 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use crate::types::PathsConfig;
+
+/// Processing stages of a release upload, in order. A checkpoint stores the last completed stage.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PipelineStage {
+    Detected,       // Release type, title, season/episode and category determined, dupe check passed
+    Analyzed,       // TMDB/IMDb/TVDB IDs fetched, video files found, mediainfo generated
+    TorrentCreated, // .torrent file written
+    ImagesUploaded, // Screenshots and sample uploaded
+    Submitted,      // Accepted by the tracker
+    Injected,       // Added to the torrent clients
+}
+
+/// Persisted state of one release on one tracker, so a failed run can resume where it stopped.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ReleaseCheckpoint {
+    pub input_path: String,
+    pub tracker: String,
+    pub stage: Option<PipelineStage>,
+    pub updated_at: String,
+    pub last_error: Option<String>,
+
+    // Detected
+    pub release_type: String,
+    pub title: String,
+    pub year: Option<String>,
+    pub season_number: Option<u32>,
+    pub episode_number: Option<u32>,
+    pub category_id: u32,
+    pub type_id: u32,
+    pub dupe_link: Option<String>, // Set when the tracker already had the release and it was cross-seeded instead
+
+    // Analyzed
+    pub release_name: String,
+    pub tmdb_id: u32,
+    pub imdb_id: Option<String>,
+    pub tvdb_id: Option<u32>,
+    pub video_files: Vec<String>,
+    pub nfo_file: Option<String>,
+    pub mediainfo: String,
+    pub existing_sample: Option<String>, // Sample shipped with the release, uploaded instead of cutting one
+    pub episode_title: Option<String>, // "S02E05 – Title (2023-04-01)" of a single episode, from TMDB
+
+    // TorrentCreated
+    pub torrent_file: Option<String>,
+    pub staged_path: Option<String>, // Hardlinked copy under `staging_dir` the torrent was made from
+
+    // ImagesUploaded
+    pub screenshots: Vec<String>,
+    pub thumbnails: Vec<String>,
+    pub sample_url: String,
+    pub proof_url: Option<String>, // Proof-of-ownership image, when enabled
+
+    // Submitted
+    pub torrent_url: Option<String>, // Torrent page on the tracker, when known
+
+    // Injected
+    #[serde(default)]
+    pub client_states: Vec<String>, // What each client reported after the add, e.g. "qBittorrent at ...: seeding"
+
+    #[serde(skip)]
+    pub stage_started: Option<Instant>, // When the current stage began in this run, for metrics
+}
+
+/// Working directory for checkpoints and caches: `paths.work_dir`, or `./work` when unset.
+pub fn work_dir(paths: &PathsConfig) -> PathBuf {
+    PathBuf::from(paths.work_dir.as_deref().unwrap_or("./work"))
+}
+
+/// Directory holding checkpoint files.
+pub fn checkpoint_dir(paths: &PathsConfig) -> PathBuf {
+    work_dir(paths).join("checkpoints")
+}
+
+/// Upload history database.
+pub fn history_db(paths: &PathsConfig) -> PathBuf {
+    work_dir(paths).join("history.db")
+}
+
+/// Cached Seedpool categories/types/resolutions.
+pub fn seedpool_metadata_cache(paths: &PathsConfig) -> PathBuf {
+    work_dir(paths).join("seedpool_metadata.json")
+}
+
+fn checkpoint_path(dir: &Path, tracker: &str, input_path: &str) -> PathBuf {
+    let base_name = Path::new(input_path)
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .replace(|c: char| !c.is_ascii_alphanumeric() && c != '.' && c != '-' && c != '_', "_");
+    dir.join(format!("{}.{}.json", base_name, tracker))
+}
+
+impl ReleaseCheckpoint {
+    pub fn new(tracker: &str, input_path: &str) -> Self {
+        ReleaseCheckpoint {
+            input_path: input_path.to_string(),
+            tracker: tracker.to_string(),
+            stage_started: Some(Instant::now()),
+            ..Default::default()
+        }
+    }
+
+    /// Loads the checkpoint for a release, or starts a new one when none exists (or `fresh` is set).
+    pub fn load_or_new(dir: &Path, tracker: &str, input_path: &str, fresh: bool) -> Self {
+        let path = checkpoint_path(dir, tracker, input_path);
+        if fresh {
+            if path.exists() {
+                info!("Ignoring existing checkpoint {} (fresh start requested).", path.display());
+                let _ = fs::remove_file(&path);
+            }
+            return Self::new(tracker, input_path);
+        }
+
+        let mut checkpoint = match fs::read_to_string(&path) {
+            Ok(content) => match serde_json::from_str::<ReleaseCheckpoint>(&content) {
+                Ok(checkpoint) if checkpoint.input_path == input_path => {
+                    info!(
+                        "Resuming '{}' for {} after stage {:?} (last error: {})",
+                        input_path,
+                        tracker,
+                        checkpoint.stage,
+                        checkpoint.last_error.as_deref().unwrap_or("none")
+                    );
+                    checkpoint
+                }
+                Ok(_) => {
+                    warn!("Checkpoint {} belongs to a different path. Starting fresh.", path.display());
+                    Self::new(tracker, input_path)
+                }
+                Err(e) => {
+                    warn!("Failed to parse checkpoint {}: {}. Starting fresh.", path.display(), e);
+                    Self::new(tracker, input_path)
+                }
+            },
+            Err(_) => Self::new(tracker, input_path),
+        };
+        checkpoint.stage_started = Some(Instant::now());
+        checkpoint
+    }
+
+    /// True when `stage` was already completed by an earlier run.
+    pub fn completed(&self, stage: PipelineStage) -> bool {
+        self.stage.is_some_and(|done| done >= stage)
+    }
+
+    /// Marks `stage` as completed and persists the checkpoint.
+    pub fn advance(&mut self, dir: &Path, stage: PipelineStage) -> Result<(), String> {
+        if let Some(started) = self.stage_started.replace(Instant::now()) {
+            crate::metrics::record_stage(&self.tracker, &format!("{:?}", stage), started.elapsed());
+        }
+        self.stage = Some(stage);
+        self.last_error = None;
+        info!("Pipeline '{}' ({}): reached stage {:?}", self.input_path, self.tracker, stage);
+        self.save(dir)
+    }
+
+    /// Records a failure so the next run reports why it is resuming.
+    pub fn record_error(&mut self, dir: &Path, error: &str) {
+        self.last_error = Some(error.to_string());
+        if let Err(e) = self.save(dir) {
+            warn!("Failed to save checkpoint after error: {}", e);
+        }
+    }
+
+    pub fn save(&mut self, dir: &Path) -> Result<(), String> {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create checkpoint directory '{}': {}", dir.display(), e))?;
+        self.updated_at = chrono::Utc::now().to_rfc3339();
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize checkpoint: {}", e))?;
+        let path = checkpoint_path(dir, &self.tracker, &self.input_path);
+        fs::write(&path, content).map_err(|e| format!("Failed to write checkpoint '{}': {}", path.display(), e))
+    }
+
+    /// Removes the checkpoint once the release went through every stage.
+    pub fn finish(&self, dir: &Path) {
+        let path = checkpoint_path(dir, &self.tracker, &self.input_path);
+        if path.exists()
+            && let Err(e) = fs::remove_file(&path)
+        {
+            warn!("Failed to remove checkpoint {}: {}", path.display(), e);
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+// Pure-Rust stand-ins for ffprobe and mediainfo, for static builds and containers that ship
+// without them. Only compiled in with the `native-probe` feature; without it every function
+// returns an error, so callers can try them unconditionally after the binary failed to start.
+
+/// Whether seed-tools was built with the native prober.
+pub const AVAILABLE: bool = cfg!(feature = "native-probe");
+
+/// Whether a missing `binary` (from `paths` in config.yaml) can be replaced by the native
+/// prober. Only ffprobe can: mediainfo output is uploaded as is.
+pub fn can_replace(binary: &str) -> bool {
+    AVAILABLE && binary == "ffprobe"
+}
+
+/// Duration of a video or audio file in seconds.
+pub fn duration(path: &Path) -> Result<f64, String> {
+    #[cfg(feature = "native-probe")]
+    {
+        native::duration(path)
+    }
+    #[cfg(not(feature = "native-probe"))]
+    {
+        Err(unavailable(path))
+    }
+}
+
+/// Codec of the first video track, named like ffprobe does, e.g. "h264".
+pub fn video_codec(path: &Path) -> Result<String, String> {
+    #[cfg(feature = "native-probe")]
+    {
+        native::video_codec(path)
+    }
+    #[cfg(not(feature = "native-probe"))]
+    {
+        Err(unavailable(path))
+    }
+}
+
+/// Format and tags of an audio file, under the field names mediainfo prints ("Format",
+/// "Sampling rate", "Performer", "Album", ...).
+pub fn audio_details(path: &Path) -> Result<HashMap<String, String>, String> {
+    #[cfg(feature = "native-probe")]
+    {
+        native::audio_details(path)
+    }
+    #[cfg(not(feature = "native-probe"))]
+    {
+        Err(unavailable(path))
+    }
+}
+
+#[cfg(not(feature = "native-probe"))]
+fn unavailable(path: &Path) -> String {
+    format!("cannot probe '{}': seed-tools was built without the native-probe feature", path.display())
+}
+
+#[cfg(feature = "native-probe")]
+mod native {
+    use std::collections::HashMap;
+    use std::fs::File;
+    use std::path::Path;
+    use matroska::{Matroska, Tracktype};
+    use mp4::{MediaType, TrackType};
+    use symphonia::core::formats::FormatOptions;
+    use symphonia::core::io::MediaSourceStream;
+    use symphonia::core::meta::{MetadataOptions, StandardTagKey, Tag};
+    use symphonia::core::probe::Hint;
+
+    enum Container {
+        Matroska,
+        Mp4,
+        Other,
+    }
+
+    fn container(path: &Path) -> Container {
+        match path.extension().and_then(|ext| ext.to_str()).map(|ext| ext.to_lowercase()).as_deref() {
+            Some("mkv" | "mka" | "webm") => Container::Matroska,
+            Some("mp4" | "m4v" | "m4a" | "mov") => Container::Mp4,
+            _ => Container::Other,
+        }
+    }
+
+    fn open(path: &Path) -> Result<File, String> {
+        File::open(path).map_err(|e| format!("Failed to open '{}': {}", path.display(), e))
+    }
+
+    fn read_matroska(path: &Path) -> Result<Matroska, String> {
+        Matroska::open(open(path)?).map_err(|e| format!("Failed to read the Matroska headers of '{}': {}", path.display(), e))
+    }
+
+    fn read_mp4(path: &Path) -> Result<mp4::Mp4Reader<std::io::BufReader<File>>, String> {
+        let file = open(path)?;
+        let size = file.metadata().map_err(|e| e.to_string())?.len();
+        mp4::Mp4Reader::read_header(std::io::BufReader::new(file), size)
+            .map_err(|e| format!("Failed to read the MP4 headers of '{}': {}", path.display(), e))
+    }
+
+    pub fn duration(path: &Path) -> Result<f64, String> {
+        match container(path) {
+            Container::Matroska => read_matroska(path)?
+                .info
+                .duration
+                .map(|duration| duration.as_secs_f64())
+                .ok_or_else(|| format!("'{}' has no duration in its headers", path.display())),
+            Container::Mp4 => Ok(read_mp4(path)?.duration().as_secs_f64()),
+            Container::Other => {
+                let probed = probe_audio(path)?;
+                let track = probed.format.default_track().ok_or_else(|| format!("'{}' has no tracks", path.display()))?;
+                let params = &track.codec_params;
+                match (params.time_base, params.n_frames) {
+                    (Some(time_base), Some(frames)) => {
+                        let time = time_base.calc_time(frames);
+                        Ok(time.seconds as f64 + time.frac)
+                    }
+                    _ => Err(format!("'{}' has no duration in its headers", path.display())),
+                }
+            }
+        }
+    }
+
+    pub fn video_codec(path: &Path) -> Result<String, String> {
+        let codec = match container(path) {
+            Container::Matroska => {
+                let mkv = read_matroska(path)?;
+                let track = mkv
+                    .tracks
+                    .iter()
+                    .find(|track| matches!(track.tracktype, Tracktype::Video))
+                    .ok_or_else(|| format!("'{}' has no video track", path.display()))?;
+                match track.codec_id.as_str() {
+                    "V_MPEG4/ISO/AVC" => "h264".to_string(),
+                    "V_MPEGH/ISO/HEVC" => "hevc".to_string(),
+                    "V_AV1" => "av1".to_string(),
+                    "V_VP9" => "vp9".to_string(),
+                    "V_VP8" => "vp8".to_string(),
+                    "V_MPEG2" => "mpeg2video".to_string(),
+                    "V_MPEG4/ISO/ASP" => "mpeg4".to_string(),
+                    other => other.trim_start_matches("V_").to_lowercase(),
+                }
+            }
+            Container::Mp4 => {
+                let reader = read_mp4(path)?;
+                let track = reader
+                    .tracks()
+                    .values()
+                    .find(|track| matches!(track.track_type(), Ok(TrackType::Video)))
+                    .ok_or_else(|| format!("'{}' has no video track", path.display()))?;
+                match track.media_type() {
+                    Ok(MediaType::H264) => "h264".to_string(),
+                    Ok(MediaType::H265) => "hevc".to_string(),
+                    Ok(MediaType::VP9) => "vp9".to_string(),
+                    Ok(other) => other.to_string().to_lowercase(),
+                    Err(e) => return Err(format!("Unknown video codec in '{}': {}", path.display(), e)),
+                }
+            }
+            Container::Other => return Err(format!("Cannot read the video track of '{}' without ffprobe", path.display())),
+        };
+        Ok(codec)
+    }
+
+    fn probe_audio(path: &Path) -> Result<symphonia::core::probe::ProbeResult, String> {
+        let source = MediaSourceStream::new(Box::new(open(path)?), Default::default());
+        let mut hint = Hint::new();
+        if let Some(ext) = path.extension().and_then(|ext| ext.to_str()) {
+            hint.with_extension(ext);
+        }
+        symphonia::default::get_probe()
+            .format(&hint, source, &FormatOptions::default(), &MetadataOptions::default())
+            .map_err(|e| format!("Failed to read '{}': {}", path.display(), e))
+    }
+
+    pub fn audio_details(path: &Path) -> Result<HashMap<String, String>, String> {
+        let mut probed = probe_audio(path)?;
+        let mut details = HashMap::new();
+
+        if let Some(track) = probed.format.default_track() {
+            let params = &track.codec_params;
+            if let Some(codec) = symphonia::default::get_codecs().get_codec(params.codec) {
+                let format = match codec.short_name {
+                    "flac" => "FLAC".to_string(),
+                    "mp3" => "MPEG Audio".to_string(),
+                    "alac" => "ALAC".to_string(),
+                    "vorbis" => "Vorbis".to_string(),
+                    "opus" => "Opus".to_string(),
+                    other => other.to_uppercase(),
+                };
+                details.insert("Format".to_string(), format);
+            }
+            if let Some(rate) = params.sample_rate {
+                details.insert("Sampling rate".to_string(), format!("{} kHz", rate as f64 / 1000.0));
+            }
+            if let Some(bits) = params.bits_per_sample.or(params.bits_per_coded_sample) {
+                details.insert("Bit depth".to_string(), format!("{} bits", bits));
+            }
+        }
+
+        // ID3v2 tags come with the probe, FLAC and Vorbis comments with the format reader
+        let mut tags: Vec<Tag> = probed.metadata.get().and_then(|metadata| metadata.current().map(|rev| rev.tags().to_vec())).unwrap_or_default();
+        if let Some(rev) = probed.format.metadata().current() {
+            tags.extend(rev.tags().iter().cloned());
+        }
+        for tag in tags {
+            let field = match tag.std_key {
+                Some(StandardTagKey::Artist) => "Performer",
+                Some(StandardTagKey::Album) => "Album",
+                Some(StandardTagKey::Genre) => "Genre",
+                Some(StandardTagKey::Date) => "Recorded date",
+                Some(StandardTagKey::Label) => "Label",
+                Some(StandardTagKey::IdentCatalogNumber) => "Catalog number",
+                _ => continue,
+            };
+            details.entry(field.to_string()).or_insert_with(|| tag.value.to_string());
+        }
+        Ok(details)
+    }
+}
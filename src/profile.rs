@@ -0,0 +1,52 @@
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Environment variable selecting a profile when `--profile` is not given.
+pub const PROFILE_ENV: &str = "SEED_TOOLS_PROFILE";
+
+static PROFILE: OnceLock<Option<String>> = OnceLock::new();
+
+/// Selects the profile from `--profile`, or else from SEED_TOOLS_PROFILE. Call once at startup.
+pub fn init(cli_profile: Option<&str>) {
+    let profile = cli_profile
+        .map(str::to_string)
+        .or_else(|| std::env::var(PROFILE_ENV).ok())
+        .map(|profile| profile.trim().to_string())
+        .filter(|profile| !profile.is_empty());
+    let _ = PROFILE.set(profile);
+}
+
+/// The environment for seed-tools processes started by the TUI, web UI or API, so they use the
+/// same profile: `command.envs(profile::child_env())`.
+pub fn child_env() -> Option<(&'static str, &'static str)> {
+    name().map(|name| (PROFILE_ENV, name))
+}
+
+/// Name of the active profile, if any.
+pub fn name() -> Option<&'static str> {
+    PROFILE.get_or_init(|| std::env::var(PROFILE_ENV).ok().filter(|profile| !profile.trim().is_empty())).as_deref()
+}
+
+/// Directory of the active profile: `config_dir/profiles/<name>`.
+pub fn profile_dir(config_dir: &Path) -> Option<PathBuf> {
+    name().map(|name| config_dir.join("profiles").join(name))
+}
+
+/// `relative` (e.g. `config.yaml`, `trackers/seedpool.yaml` or `trackers/unit3d`) inside the
+/// active profile's directory when the profile has it, otherwise inside `config_dir`. A profile
+/// therefore only needs the files it changes.
+pub fn config_path(config_dir: &Path, relative: &str) -> PathBuf {
+    profile_dir(config_dir)
+        .map(|dir| dir.join(relative))
+        .filter(|path| path.exists())
+        .unwrap_or_else(|| config_dir.join(relative))
+}
+
+/// Fails when a profile is selected but `config_dir/profiles/<name>` does not exist, which is
+/// almost always a typo that would otherwise silently use the default config.
+pub fn check(config_dir: &Path) -> Result<(), String> {
+    match profile_dir(config_dir) {
+        Some(dir) if !dir.is_dir() => Err(format!("Profile '{}' not found: {} does not exist", name().unwrap_or_default(), dir.display())),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,200 @@
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+use log::info;
+use walkdir::WalkDir;
+use crate::types::ReadinessConfig;
+
+/// Exit code of an upload process that left a release alone because it is still being
+/// written (`--check-ready`). The upload queue and web/API jobs retry it later.
+pub const NOT_READY_EXIT_CODE: i32 = 76;
+
+const NOT_READY: &str = "Release not ready";
+
+/// The error an upload queue reports for a release that exited with `NOT_READY_EXIT_CODE`.
+pub fn not_ready_error(path: &str) -> String {
+    format!("{}: '{}' is still being written, upload postponed", NOT_READY, path)
+}
+
+// Left behind by clients and browsers while a file is still being written
+const PARTIAL_EXTENSIONS: [&str; 5] = ["part", "partial", "!qb", "!ut", "crdownload"];
+
+/// Checks that `input_path` is done copying or downloading: nothing modified within the
+/// settle time, no file still growing, no partial files and, when enabled, every SFV
+/// checksum matching. Returns why the release is not ready yet.
+pub fn check_release_ready(input_path: &str, config: &ReadinessConfig) -> Result<(), String> {
+    let files = release_files(input_path)?;
+    if files.is_empty() {
+        return Err(format!("'{}' contains no files", input_path));
+    }
+
+    if let Some(partial) = files.iter().find(|(path, _, _)| is_partial(path)) {
+        return Err(format!("'{}' is still being written", partial.0.display()));
+    }
+
+    let newest = files.iter().map(|(_, _, modified)| *modified).max().unwrap_or(SystemTime::UNIX_EPOCH);
+    let age = SystemTime::now().duration_since(newest).unwrap_or_default().as_secs();
+    if age < config.settle_secs {
+        return Err(format!(
+            "'{}' was modified {}s ago, waiting until it has settled for {}s",
+            input_path, age, config.settle_secs
+        ));
+    }
+
+    // A copy that preserves timestamps passes the settle time, so also watch the sizes
+    if config.growth_check_secs > 0 {
+        thread::sleep(Duration::from_secs(config.growth_check_secs));
+        for (path, size, _) in &files {
+            let now = fs::metadata(path).map(|metadata| metadata.len()).unwrap_or(0);
+            if now != *size {
+                return Err(format!("'{}' is still growing ({} -> {} bytes)", path.display(), size, now));
+            }
+        }
+    }
+
+    if config.verify_sfv {
+        for (sfv, _, _) in files.iter().filter(|(path, _, _)| has_extension(path, "sfv")) {
+            verify_sfv(sfv)?;
+        }
+    }
+
+    info!("'{}' is complete and ready for upload.", input_path);
+    Ok(())
+}
+
+/// Every file under `input_path` with its size and modification time.
+fn release_files(input_path: &str) -> Result<Vec<(PathBuf, u64, SystemTime)>, String> {
+    let mut files = Vec::new();
+    for entry in WalkDir::new(input_path) {
+        let entry = entry.map_err(|e| format!("Failed to read '{}': {}", input_path, e))?;
+        let metadata = entry.metadata().map_err(|e| format!("Failed to stat '{}': {}", entry.path().display(), e))?;
+        if metadata.is_file() {
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            files.push((entry.into_path(), metadata.len(), modified));
+        }
+    }
+    Ok(files)
+}
+
+fn has_extension(path: &Path, extension: &str) -> bool {
+    path.extension().is_some_and(|ext| ext.to_string_lossy().eq_ignore_ascii_case(extension))
+}
+
+fn is_partial(path: &Path) -> bool {
+    PARTIAL_EXTENSIONS.iter().any(|extension| has_extension(path, extension))
+}
+
+/// Checks the files listed in `sfv` (relative to its folder) against their CRC32s.
+fn verify_sfv(sfv: &Path) -> Result<(), String> {
+    let listing = fs::read_to_string(sfv).map_err(|e| format!("Failed to read '{}': {}", sfv.display(), e))?;
+    let dir = sfv.parent().unwrap_or(Path::new("."));
+    for line in listing.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with(';')) {
+        let Some((name, expected)) = line.rsplit_once(char::is_whitespace) else { continue };
+        let path = dir.join(name.trim());
+        if !path.is_file() {
+            return Err(format!("'{}' listed in '{}' is missing", path.display(), sfv.display()));
+        }
+        let actual = crc32_file(&path)?;
+        if !expected.eq_ignore_ascii_case(&format!("{:08x}", actual)) {
+            return Err(format!("'{}' does not match its SFV checksum ({:08X}, expected {})", path.display(), actual, expected));
+        }
+    }
+    info!("'{}' verified.", sfv.display());
+    Ok(())
+}
+
+/// CRC-32 (IEEE) as used by SFV files.
+fn crc32_file(path: &Path) -> Result<u32, String> {
+    let mut table = [0u32; 256];
+    for (i, entry) in table.iter_mut().enumerate() {
+        let mut crc = i as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { 0xEDB8_8320 ^ (crc >> 1) } else { crc >> 1 };
+        }
+        *entry = crc;
+    }
+
+    let mut file = File::open(path).map_err(|e| format!("Failed to open '{}': {}", path.display(), e))?;
+    let mut buffer = vec![0; 4 * 1024 * 1024];
+    let mut crc = 0xFFFF_FFFFu32;
+    loop {
+        let read = file.read(&mut buffer).map_err(|e| format!("Failed to read '{}': {}", path.display(), e))?;
+        if read == 0 {
+            break;
+        }
+        for byte in &buffer[..read] {
+            crc = table[((crc ^ *byte as u32) & 0xFF) as usize] ^ (crc >> 8);
+        }
+    }
+    Ok(!crc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Settled data and no growth watch, so the checks run without waiting
+    fn config(verify_sfv: bool) -> ReadinessConfig {
+        ReadinessConfig { settle_secs: 0, growth_check_secs: 0, verify_sfv }
+    }
+
+    fn release(dir: &Path) -> String {
+        let release = dir.join("Release");
+        fs::create_dir_all(&release).unwrap();
+        fs::write(release.join("release.r00"), "123456789").unwrap();
+        release.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn computes_sfv_checksums() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("check");
+        fs::write(&path, "123456789").unwrap();
+        assert_eq!(crc32_file(&path), Ok(0xCBF4_3926));
+        fs::write(&path, "").unwrap();
+        assert_eq!(crc32_file(&path), Ok(0));
+    }
+
+    #[test]
+    fn accepts_a_complete_release() {
+        let dir = tempfile::tempdir().unwrap();
+        let release = release(dir.path());
+        fs::write(Path::new(&release).join("release.sfv"), "; made by a scene tool\nrelease.r00 cbf43926\n").unwrap();
+        assert_eq!(check_release_ready(&release, &config(true)), Ok(()));
+    }
+
+    #[test]
+    fn rejects_empty_partial_and_unsettled_releases() {
+        let dir = tempfile::tempdir().unwrap();
+        let empty = dir.path().join("Empty");
+        fs::create_dir(&empty).unwrap();
+        assert!(check_release_ready(&empty.to_string_lossy(), &config(false)).unwrap_err().contains("contains no files"));
+
+        let release = release(dir.path());
+        let error = check_release_ready(&release, &ReadinessConfig { settle_secs: 3600, ..config(false) }).unwrap_err();
+        assert!(error.contains("waiting until it has settled for 3600s"), "{}", error);
+
+        fs::write(Path::new(&release).join("release.mkv.!qB"), "").unwrap();
+        let error = check_release_ready(&release, &config(false)).unwrap_err();
+        assert!(error.contains("release.mkv.!qB' is still being written"), "{}", error);
+    }
+
+    #[test]
+    fn rejects_releases_failing_their_sfv() {
+        let dir = tempfile::tempdir().unwrap();
+        let release = release(dir.path());
+        let sfv = Path::new(&release).join("release.sfv");
+
+        fs::write(&sfv, "release.r00 DEADBEEF\n").unwrap();
+        let error = check_release_ready(&release, &config(true)).unwrap_err();
+        assert!(error.contains("does not match its SFV checksum (CBF43926, expected DEADBEEF)"), "{}", error);
+        // Only checked when enabled
+        assert_eq!(check_release_ready(&release, &config(false)), Ok(()));
+
+        fs::write(&sfv, "release.r00 CBF43926\nrelease.r01 00000000\n").unwrap();
+        let error = check_release_ready(&release, &config(true)).unwrap_err();
+        assert!(error.contains("release.r01' listed in"), "{}", error);
+    }
+}
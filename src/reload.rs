@@ -0,0 +1,167 @@
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, RwLock};
+use std::thread;
+use std::time::Duration;
+use log::{info, warn};
+use notify::event::{AccessKind, AccessMode};
+use notify::{Config as NotifyConfig, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use crate::types::Config;
+
+// Editors save in several steps (temp file, rename, chmod); wait for them to settle
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// A config value that request handlers read and a reload replaces whole, so a handler never
+/// sees half of an old and half of a new config.
+pub struct Live<T>(RwLock<Arc<T>>);
+
+impl<T> Live<T> {
+    pub fn new(value: T) -> Self {
+        Live(RwLock::new(Arc::new(value)))
+    }
+
+    pub fn get(&self) -> Arc<T> {
+        self.0.read().unwrap().clone()
+    }
+
+    pub fn replace(&self, value: T) {
+        *self.0.write().unwrap() = Arc::new(value);
+    }
+}
+
+/// Reads and parses `config.yaml`, filling in secret references.
+pub fn load_config(path: &Path) -> Result<Config, String> {
+    let content = crate::secrets::read_config(path)?;
+    serde_yaml::from_str(&content).map_err(|e| format!("Failed to parse '{}': {}", path.display(), e))
+}
+
+fn is_yaml(path: &Path) -> bool {
+    matches!(path.extension().and_then(|ext| ext.to_str()), Some("yaml" | "yml"))
+}
+
+/// `path` with its directory resolved, so paths compare equal whether the watcher reports them
+/// relative, absolute or through a symlink. The file itself may already be gone.
+fn normalize(path: &Path) -> PathBuf {
+    match (path.parent().and_then(|dir| dir.canonicalize().ok()), path.file_name()) {
+        (Some(dir), Some(name)) => dir.join(name),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// The YAML files an event wrote. Editors that save atomically write a temp file and rename it
+/// over the config, which arrives as a create or rename rather than a data change; reads of the
+/// config (including the reload's own) are ignored.
+fn written_yaml(event: notify::Event) -> Vec<PathBuf> {
+    let written = matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Access(AccessKind::Close(AccessMode::Write))
+    );
+    if !written {
+        return Vec::new();
+    }
+    event.paths.iter().filter(|path| is_yaml(path)).map(|path| normalize(path)).collect()
+}
+
+/// Checks that the tracker configs still parse. Uploads run as separate processes that read them
+/// fresh, so a broken file would only show up at the next upload otherwise.
+fn check_tracker_configs(changed: &[PathBuf]) {
+    for path in changed.iter().filter(|path| path.components().any(|component| component.as_os_str() == "trackers")) {
+        if !path.exists() {
+            continue;
+        }
+        match crate::secrets::read_config(path)
+            .and_then(|content| serde_yaml::from_str::<serde_yaml::Value>(&content).map_err(|e| e.to_string()))
+        {
+            Ok(_) => info!("{} changed; the next upload uses it", path.display()),
+            Err(e) => warn!("{} changed but does not parse: {}", path.display(), e),
+        }
+    }
+}
+
+/// Watches the YAML files in `config_dir` and calls `on_reload` with the newly parsed
+/// `config.yaml` after they change. A config that fails to parse is logged and skipped, so
+/// the running one stays in use. Runs on its own thread until the process exits.
+pub fn watch_config_dir(config_dir: &Path, on_reload: impl Fn(Config) + Send + 'static) -> Result<(), String> {
+    let config_dir = config_dir.to_path_buf();
+    let (tx, rx) = mpsc::channel::<notify::Result<notify::Event>>();
+    let mut watcher =
+        RecommendedWatcher::new(tx, NotifyConfig::default()).map_err(|e| format!("Failed to create config watcher: {}", e))?;
+    watcher
+        .watch(&config_dir, RecursiveMode::Recursive)
+        .map_err(|e| format!("Failed to watch {}: {}", config_dir.display(), e))?;
+    info!("Watching {} for config changes", config_dir.display());
+
+    thread::spawn(move || {
+        let _watcher = watcher; // Stops watching when dropped
+        let changed_paths = |event: notify::Result<notify::Event>| event.map(written_yaml).unwrap_or_default();
+        while let Ok(event) = rx.recv() {
+            let mut changed = changed_paths(event);
+            if changed.is_empty() {
+                continue;
+            }
+            while let Ok(event) = rx.recv_timeout(DEBOUNCE) {
+                changed.extend(changed_paths(event));
+            }
+            changed.sort();
+            changed.dedup();
+
+            check_tracker_configs(&changed);
+            // The active profile's config.yaml when it has one
+            let main_config = crate::profile::config_path(&config_dir, "config.yaml");
+            if changed.contains(&normalize(&main_config)) {
+                match load_config(&main_config) {
+                    Ok(config) => {
+                        info!("Reloaded {}", main_config.display());
+                        on_reload(config);
+                    }
+                    Err(e) => warn!("Keeping the running config: {}", e),
+                }
+            }
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{CreateKind, ModifyKind, RenameMode};
+    use notify::Event;
+
+    #[test]
+    fn normalizes_relative_and_absolute_paths_alike() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("config.yaml");
+        std::fs::write(&config, "paths: {}").unwrap();
+        let relative = pathdiff(&config);
+        assert_eq!(normalize(&relative), normalize(&config));
+        // A file renamed away still normalizes to its place in the directory
+        let gone = dir.path().join("gone.yaml");
+        assert_eq!(normalize(&gone), dir.path().canonicalize().unwrap().join("gone.yaml"));
+    }
+
+    #[test]
+    fn picks_up_atomic_saves() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("config.yaml");
+        let temp = dir.path().join(".config.yaml.swp");
+        std::fs::write(&config, "paths: {}").unwrap();
+
+        let renamed = Event::new(EventKind::Modify(ModifyKind::Name(RenameMode::Both))).add_path(temp.clone()).add_path(config.clone());
+        assert_eq!(written_yaml(renamed), vec![normalize(&config)]);
+        let created = Event::new(EventKind::Create(CreateKind::File)).add_path(config.clone());
+        assert_eq!(written_yaml(created), vec![normalize(&config)]);
+        let read = Event::new(EventKind::Access(AccessKind::Close(AccessMode::Read))).add_path(config.clone());
+        assert!(written_yaml(read).is_empty());
+    }
+
+    /// `path` relative to the current directory, via as many `..` as it takes.
+    fn pathdiff(path: &Path) -> PathBuf {
+        let current = std::env::current_dir().unwrap().canonicalize().unwrap();
+        let path = path.canonicalize().unwrap();
+        let common = current.ancestors().find(|ancestor| path.starts_with(ancestor)).unwrap();
+        let ups = current.strip_prefix(common).unwrap().components().count();
+        let mut relative: PathBuf = std::iter::repeat_n("..", ups).collect();
+        relative.push(path.strip_prefix(common).unwrap());
+        relative
+    }
+}
@@ -0,0 +1,179 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use log::{info, warn};
+use walkdir::WalkDir;
+use crate::types::{PathsConfig, Retention, RetentionConfig};
+use crate::utils::format_size;
+
+// Retention of what uploads leave behind in torrent_dir and screenshots_dir. Files written
+// during an upload are remembered, so `after_success` removes exactly this run's files;
+// `seed-tools clean` removes files older than their kind's age from earlier runs.
+
+// Crashed runs leave their temp directories behind; `clean` removes them after this long
+const TEMP_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+static CREATED: Mutex<Vec<(Artifact, PathBuf)>> = Mutex::new(Vec::new());
+
+/// Kinds of generated files, each with its own retention.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Artifact {
+    Torrent, // .torrent files, and the .nfo files written next to them
+    Screenshot,
+    Sample,
+    Cover,
+    Temp, // Left over in paths.temp_dir
+}
+
+impl Artifact {
+    pub fn name(self) -> &'static str {
+        match self {
+            Artifact::Torrent => "torrent",
+            Artifact::Screenshot => "screenshot",
+            Artifact::Sample => "sample",
+            Artifact::Cover => "cover",
+            Artifact::Temp => "temp",
+        }
+    }
+
+    fn retention(self, config: &RetentionConfig) -> Retention {
+        match self {
+            Artifact::Torrent => config.torrents,
+            Artifact::Screenshot => config.screenshots,
+            Artifact::Sample => config.samples,
+            Artifact::Cover => config.covers,
+            Artifact::Temp => Retention::Days(1),
+        }
+    }
+}
+
+/// A file `clean` removes.
+#[derive(Debug, Clone)]
+pub struct Expired {
+    pub artifact: Artifact,
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+/// Remembers a file this run wrote, for `after_success`.
+pub fn track(artifact: Artifact, path: impl Into<PathBuf>) {
+    CREATED.lock().unwrap().push((artifact, path.into()));
+}
+
+/// After a successful upload, removes the files this run wrote whose kind is kept only until
+/// then. Returns the space reclaimed.
+pub fn after_success(config: &RetentionConfig) -> u64 {
+    let created = std::mem::take(&mut *CREATED.lock().unwrap());
+    let mut reclaimed = 0;
+    for (artifact, path) in created {
+        if artifact.retention(config) != Retention::AfterSuccess {
+            continue;
+        }
+        let size = fs::metadata(&path).map(|metadata| metadata.len()).unwrap_or(0);
+        match fs::remove_file(&path) {
+            Ok(()) => reclaimed += size,
+            Err(e) => warn!("Failed to remove {} '{}': {}", artifact.name(), path.display(), e),
+        }
+    }
+    if reclaimed > 0 {
+        info!("Removed this upload's generated files, {} reclaimed.", format_size(reclaimed));
+    }
+    reclaimed
+}
+
+/// The kind of a file in `torrent_dir` or `screenshots_dir`, by its name.
+pub fn classify(path: &Path, in_torrent_dir: bool) -> Option<Artifact> {
+    let name = path.file_name()?.to_string_lossy().to_lowercase();
+    let extension = path.extension().map(|ext| ext.to_string_lossy().to_lowercase()).unwrap_or_default();
+    if in_torrent_dir {
+        return ["torrent", "nfo"].contains(&extension.as_str()).then_some(Artifact::Torrent);
+    }
+    if name.contains(".sample.") {
+        Some(Artifact::Sample)
+    } else if name.starts_with("torrent-cover_") {
+        Some(Artifact::Cover)
+    } else if ["jpg", "jpeg", "png", "webp"].contains(&extension.as_str()) {
+        Some(Artifact::Screenshot)
+    } else {
+        None
+    }
+}
+
+/// Files in `torrent_dir`, `screenshots_dir` and the temp dir older than their kind is kept.
+/// Files kept until a successful upload are left alone, as a failed upload resumes with them.
+pub fn find_expired(paths: &PathsConfig, config: &RetentionConfig, now: SystemTime) -> Vec<Expired> {
+    let temp_dir = paths.temp_dir.clone().map(PathBuf::from).unwrap_or_else(|| std::env::temp_dir().join("seed-tools"));
+    let mut expired = Vec::new();
+    for (dir, in_torrent_dir) in [(PathBuf::from(&paths.torrent_dir), true), (PathBuf::from(&paths.screenshots_dir), false)] {
+        for entry in WalkDir::new(&dir).into_iter().filter_map(|entry| entry.ok()).filter(|entry| entry.file_type().is_file()) {
+            let Some(artifact) = classify(entry.path(), in_torrent_dir) else { continue };
+            let Retention::Days(days) = artifact.retention(config) else { continue };
+            if let Some(size) = older_than(entry.path(), Duration::from_secs(days * 24 * 60 * 60), now) {
+                expired.push(Expired { artifact, path: entry.into_path(), size });
+            }
+        }
+    }
+    // Whole directories left by crashed runs; temp_dir may be shared, so only seed-tools' own
+    for entry in fs::read_dir(&temp_dir).into_iter().flatten().filter_map(|entry| entry.ok()) {
+        let ours = entry.file_name().to_string_lossy().starts_with(crate::space::TEMP_PREFIX) && entry.path().is_dir();
+        if ours && older_than(&entry.path(), TEMP_MAX_AGE, now).is_some() {
+            let size = crate::utils::total_size(&entry.path().to_string_lossy());
+            expired.push(Expired { artifact: Artifact::Temp, path: entry.path(), size });
+        }
+    }
+    expired.sort_by(|a, b| a.path.cmp(&b.path));
+    expired
+}
+
+/// The size of `path` when it was last modified more than `age` before `now`.
+fn older_than(path: &Path, age: Duration, now: SystemTime) -> Option<u64> {
+    let metadata = fs::metadata(path).ok()?;
+    let modified = metadata.modified().ok()?;
+    (now.duration_since(modified).unwrap_or_default() > age).then_some(metadata.len())
+}
+
+/// Removes `expired` and returns the space reclaimed and the files that could not be removed.
+pub fn remove(expired: &[Expired]) -> (u64, Vec<String>) {
+    let mut reclaimed = 0;
+    let mut errors = Vec::new();
+    for item in expired {
+        let result = if item.path.is_dir() { fs::remove_dir_all(&item.path) } else { fs::remove_file(&item.path) };
+        match result {
+            Ok(()) => reclaimed += item.size,
+            Err(e) => errors.push(format!("Failed to remove '{}': {}", item.path.display(), e)),
+        }
+    }
+    (reclaimed, errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_expired_only_takes_seed_tools_dirs_from_a_shared_temp_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let ours = dir.path().join("seed-tools-cover-a1b2c3");
+        fs::create_dir(&ours).unwrap();
+        fs::create_dir(dir.path().join("downloads")).unwrap();
+        fs::write(dir.path().join("seed-tools-notes.txt"), "not a directory").unwrap();
+        let paths = PathsConfig {
+            torrent_dir: dir.path().join("torrents").to_string_lossy().to_string(),
+            screenshots_dir: dir.path().join("screenshots").to_string_lossy().to_string(),
+            ffmpeg: String::new(),
+            ffprobe: String::new(),
+            mkbrr: String::new(),
+            mediainfo: String::new(),
+            work_dir: None,
+            staging_dir: None,
+            staging_copy: false,
+            temp_dir: Some(dir.path().to_string_lossy().to_string()),
+        };
+        let later = SystemTime::now() + 2 * TEMP_MAX_AGE;
+
+        let expired = find_expired(&paths, &RetentionConfig::default(), later);
+        let expired: Vec<&Path> = expired.iter().map(|item| item.path.as_path()).collect();
+        assert_eq!(expired, vec![ours.as_path()]);
+    }
+}
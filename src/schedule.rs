@@ -0,0 +1,128 @@
+use std::sync::OnceLock;
+use std::thread;
+use std::time::{Duration, Instant};
+use chrono::{Local, NaiveTime};
+use log::info;
+use crate::types::{OutsideWindow, TransfersConfig};
+
+/// Exit code of an upload process started outside the upload window with
+/// `outside_window: defer`. The upload queue and web/API jobs retry it later.
+pub const OUTSIDE_WINDOW_EXIT_CODE: i32 = 77;
+
+const OUTSIDE_WINDOW: &str = "Outside the upload window";
+
+/// The `transfers` section of config.yaml with the window already parsed.
+struct Schedule {
+    limit_kbps: Option<u64>,
+    window: Option<(NaiveTime, NaiveTime)>,
+    outside_window: OutsideWindow,
+}
+
+static SCHEDULE: OnceLock<Schedule> = OnceLock::new();
+
+/// Applies the `transfers` section of config.yaml. Call once at startup; fails on an invalid
+/// window.
+pub fn init(config: &TransfersConfig) -> Result<(), String> {
+    let window = config.window.as_deref().map(parse_window).transpose()?;
+    let _ = SCHEDULE.set(Schedule { limit_kbps: config.limit_kbps, window, outside_window: config.outside_window });
+    Ok(())
+}
+
+/// The CDN upload bandwidth limit in kbit/s, if any.
+pub fn limit_kbps() -> Option<u64> {
+    SCHEDULE.get().and_then(|schedule| schedule.limit_kbps).filter(|limit| *limit > 0)
+}
+
+/// The error an upload queue reports for an upload that exited with `OUTSIDE_WINDOW_EXIT_CODE`.
+pub fn outside_window_error(path: &str) -> String {
+    format!("{}: '{}' postponed until the window opens", OUTSIDE_WINDOW, path)
+}
+
+/// Parses a window like "00:00-08:00" (local time). The end may be before the start for
+/// windows that span midnight, e.g. "22:00-06:00".
+pub fn parse_window(window: &str) -> Result<(NaiveTime, NaiveTime), String> {
+    let invalid = || format!("Invalid upload window '{}': expected HH:MM-HH:MM", window);
+    let (start, end) = window.split_once('-').ok_or_else(invalid)?;
+    let start = NaiveTime::parse_from_str(start.trim(), "%H:%M").map_err(|_| invalid())?;
+    let end = NaiveTime::parse_from_str(end.trim(), "%H:%M").map_err(|_| invalid())?;
+    if start == end {
+        return Err(invalid());
+    }
+    Ok((start, end))
+}
+
+/// How long until the window opens, or `None` while `now` is inside it.
+fn until_open((start, end): (NaiveTime, NaiveTime), now: NaiveTime) -> Option<Duration> {
+    let inside = if start < end { now >= start && now < end } else { now >= start || now < end };
+    if inside {
+        return None;
+    }
+    let wait = start.signed_duration_since(now);
+    let wait = if wait < chrono::Duration::zero() { wait + chrono::Duration::days(1) } else { wait };
+    Some(wait.to_std().unwrap_or_default())
+}
+
+/// Holds an upload until the upload window is open: waits for it, or with
+/// `outside_window: defer` returns an outside the window error, for which the caller exits
+/// with `OUTSIDE_WINDOW_EXIT_CODE` so the queue parks the upload and retries it later.
+pub async fn wait_for_window() -> Result<(), String> {
+    let Some(schedule) = SCHEDULE.get() else { return Ok(()) };
+    let Some(window) = schedule.window else { return Ok(()) };
+    let Some(wait) = until_open(window, Local::now().time()) else { return Ok(()) };
+    let opens = window.0.format("%H:%M");
+    match schedule.outside_window {
+        OutsideWindow::Defer => Err(format!("{}: it opens at {}", OUTSIDE_WINDOW, opens)),
+        OutsideWindow::Wait => {
+            info!("Outside the upload window; waiting {}m until {}", wait.as_secs().div_ceil(60), opens);
+            tokio::time::sleep(wait).await;
+            Ok(())
+        }
+    }
+}
+
+/// Sleeps as long as it takes for `bytes` sent since `started` to stay under the bandwidth limit.
+pub fn throttle(started: Instant, bytes: u64) {
+    let Some(limit) = limit_kbps() else { return };
+    let due = Duration::from_secs_f64(bytes as f64 * 8.0 / 1000.0 / limit as f64);
+    if let Some(ahead) = due.checked_sub(started.elapsed()) {
+        thread::sleep(ahead);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(value: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(value, "%H:%M").unwrap()
+    }
+
+    #[test]
+    fn parses_windows() {
+        assert_eq!(parse_window("00:00-08:00"), Ok((time("00:00"), time("08:00"))));
+        assert_eq!(parse_window(" 22:00 - 06:30 "), Ok((time("22:00"), time("06:30"))));
+        assert!(parse_window("08:00").is_err());
+        assert!(parse_window("8am-noon").is_err());
+        assert!(parse_window("25:00-06:00").is_err());
+        assert!(parse_window("06:00-06:00").is_err());
+    }
+
+    #[test]
+    fn waits_for_a_daytime_window() {
+        let window = parse_window("09:00-17:00").unwrap();
+        assert_eq!(until_open(window, time("09:00")), None);
+        assert_eq!(until_open(window, time("16:59")), None);
+        assert_eq!(until_open(window, time("08:30")), Some(Duration::from_secs(30 * 60)));
+        assert_eq!(until_open(window, time("17:00")), Some(Duration::from_secs(16 * 3600)));
+    }
+
+    #[test]
+    fn waits_for_a_window_spanning_midnight() {
+        let window = parse_window("22:00-06:00").unwrap();
+        assert_eq!(until_open(window, time("23:15")), None);
+        assert_eq!(until_open(window, time("00:00")), None);
+        assert_eq!(until_open(window, time("05:59")), None);
+        assert_eq!(until_open(window, time("06:00")), Some(Duration::from_secs(16 * 3600)));
+        assert_eq!(until_open(window, time("21:45")), Some(Duration::from_secs(15 * 60)));
+    }
+}
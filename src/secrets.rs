@@ -0,0 +1,150 @@
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use regex::{Captures, Regex};
+use serde_yaml::Value;
+
+/// Keyring service the secrets are stored under.
+pub const KEYRING_SERVICE: &str = "seed-tools";
+
+/// Reads a YAML config file and fills in the secret references in its string values (see
+/// `expand`). The YAML is parsed first, so a secret may hold `#`, `: `, quotes or newlines; the
+/// result is written back as YAML with those values quoted.
+pub fn read_config(path: impl AsRef<Path>) -> Result<String, String> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read '{}': {}", path.display(), e))?;
+    if content.trim().is_empty() {
+        return Ok(content);
+    }
+    let mut document: Value = serde_yaml::from_str(&content).map_err(|e| format!("Failed to parse '{}': {}", path.display(), e))?;
+    let mut errors = Vec::new();
+    expand_values(&mut document, &lookup, &mut errors);
+    if !errors.is_empty() {
+        return Err(format!("{} in '{}'", errors.join("; "), path.display()));
+    }
+    serde_yaml::to_string(&document).map_err(|e| format!("Failed to write '{}' back: {}", path.display(), e))
+}
+
+/// Replaces `${NAME}` with the environment variable NAME and `${keyring:NAME}` with the secret
+/// stored as NAME in the OS keyring. `$${` stays a literal `${`.
+pub fn expand(value: &str) -> Result<String, String> {
+    expand_with(value, &lookup)
+}
+
+// Environment variable, or keyring secret when the flag is set
+type Lookup = dyn Fn(&str, bool) -> Result<String, String>;
+
+fn lookup(name: &str, keyring: bool) -> Result<String, String> {
+    if keyring {
+        keyring_lookup(name)
+    } else {
+        std::env::var(name).map_err(|_| format!("Environment variable {} is not set", name))
+    }
+}
+
+/// Expands every string value below `value`; keys are left alone.
+fn expand_values(value: &mut Value, lookup: &Lookup, errors: &mut Vec<String>) {
+    match value {
+        Value::String(text) => match expand_with(text, lookup) {
+            Ok(expanded) => *text = expanded,
+            Err(e) => errors.push(e),
+        },
+        Value::Sequence(items) => items.iter_mut().for_each(|item| expand_values(item, lookup, errors)),
+        Value::Mapping(mapping) => mapping.iter_mut().for_each(|(_, item)| expand_values(item, lookup, errors)),
+        Value::Tagged(tagged) => expand_values(&mut tagged.value, lookup, errors),
+        _ => {}
+    }
+}
+
+fn expand_with(value: &str, lookup: &Lookup) -> Result<String, String> {
+    let reference = Regex::new(r"\$\$\{|\$\{(keyring:)?([A-Za-z0-9_.\-]+)\}").unwrap();
+    let mut errors = Vec::new();
+    let expanded = reference
+        .replace_all(value, |captures: &Captures| {
+            let Some(name) = captures.get(2).map(|name| name.as_str()) else {
+                return "${".to_string();
+            };
+            lookup(name, captures.get(1).is_some()).unwrap_or_else(|e| {
+                errors.push(e);
+                String::new()
+            })
+        })
+        .to_string();
+    if !errors.is_empty() {
+        return Err(errors.join("; "));
+    }
+    Ok(expanded)
+}
+
+/// Looks up a secret with `secret-tool` (Secret Service, e.g. GNOME Keyring or KWallet) on
+/// Linux and `security` (Keychain) on macOS. Windows has no such command; use environment
+/// variables there.
+fn keyring_lookup(name: &str) -> Result<String, String> {
+    if cfg!(windows) {
+        return Err(format!(
+            "${{keyring:{}}} is not supported on Windows; set an environment variable and use ${{{}}} instead",
+            name,
+            name.to_uppercase()
+        ));
+    }
+    let mut command = if cfg!(target_os = "macos") {
+        let mut command = Command::new("security");
+        command.args(["find-generic-password", "-s", KEYRING_SERVICE, "-a", name, "-w"]);
+        command
+    } else {
+        let mut command = Command::new("secret-tool");
+        command.args(["lookup", "service", KEYRING_SERVICE, "account", name]);
+        command
+    };
+    let output = command
+        .output()
+        .map_err(|e| format!("Failed to read '{}' from the keyring ({:?}): {}", name, command.get_program(), e))?;
+    let secret = String::from_utf8_lossy(&output.stdout).trim_end_matches(['\r', '\n']).to_string();
+    if !output.status.success() || secret.is_empty() {
+        return Err(format!("No secret '{}' for service '{}' in the keyring", name, KEYRING_SERVICE));
+    }
+    Ok(secret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secrets(name: &str, keyring: bool) -> Result<String, String> {
+        match (name, keyring) {
+            ("API_KEY", false) => Ok("abc#def: 'x\"\n*y".to_string()),
+            ("passkey", true) => Ok("&anchor".to_string()),
+            _ => Err(format!("{} is not set", name)),
+        }
+    }
+
+    fn expand_yaml(yaml: &str) -> Result<Value, String> {
+        let mut document: Value = serde_yaml::from_str(yaml).unwrap();
+        let mut errors = Vec::new();
+        expand_values(&mut document, &secrets, &mut errors);
+        if errors.is_empty() { Ok(document) } else { Err(errors.join("; ")) }
+    }
+
+    #[test]
+    fn secrets_with_yaml_syntax_survive_a_round_trip() {
+        let document = expand_yaml("general:\n  api_key: \"${API_KEY}\"\n  passkey: \"${keyring:passkey}\"\n").unwrap();
+        let written = serde_yaml::to_string(&document).unwrap();
+        let read: Value = serde_yaml::from_str(&written).unwrap();
+
+        assert_eq!(read["general"]["api_key"].as_str(), Some("abc#def: 'x\"\n*y"));
+        assert_eq!(read["general"]["passkey"].as_str(), Some("&anchor"));
+    }
+
+    #[test]
+    fn references_in_comments_and_escapes_are_left_alone() {
+        let document = expand_yaml("# api_key: ${MISSING}\nurl: \"$${HOME}/x\"\n").unwrap();
+
+        assert_eq!(document["url"].as_str(), Some("${HOME}/x"));
+    }
+
+    #[test]
+    fn missing_references_are_errors() {
+        assert!(expand_yaml("api_key: \"${MISSING}\"\n").is_err());
+        assert_eq!(expand_with("${keyring:passkey}-${API_KEY}", &secrets), Ok(format!("&anchor-{}", secrets("API_KEY", false).unwrap())));
+    }
+}
@@ -0,0 +1,152 @@
+use std::fmt;
+use std::thread;
+use std::time::{Duration, Instant};
+use log::{info, warn};
+use reqwest::blocking::Client;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use crate::error::SeedToolsError;
+use crate::torrent::raw_info;
+use crate::utils::torrent_info_hash;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+const APPEAR_TIMEOUT: Duration = Duration::from_secs(30); // For the client to list a just-added torrent
+const CHECK_TIMEOUT: Duration = Duration::from_secs(600); // For a recheck to finish
+
+/// Where an injected torrent ended up in a client.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SeedingState {
+    Seeding,
+    Checking(f64), // Still hash checking when we stopped waiting, with progress 0-1
+    Incomplete(f64), // Checked, but the data on disk is not complete
+    MissingFiles,
+    Error(String),
+}
+
+impl SeedingState {
+    pub fn is_seeding(&self) -> bool {
+        *self == SeedingState::Seeding
+    }
+}
+
+/// A client that seeds what was uploaded. The pipelines add torrents through this, so the
+/// offline `MockTorrentClient` can stand in for qBittorrent and Deluge.
+pub trait TorrentClient {
+    /// Name used in logs and the upload report, e.g. "qBittorrent at http://localhost:8080".
+    fn name(&self) -> String;
+    /// Adds `torrent_file` on top of the data at `input_path` and returns where it ended up.
+    fn add_torrent(&self, torrent_file: &str, input_path: &str) -> Result<SeedingState, SeedToolsError>;
+}
+
+impl fmt::Display for SeedingState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SeedingState::Seeding => write!(f, "seeding"),
+            SeedingState::Checking(progress) => write!(f, "still checking ({:.1}%)", progress * 100.0),
+            SeedingState::Incomplete(progress) => write!(f, "incomplete data ({:.1}%)", progress * 100.0),
+            SeedingState::MissingFiles => write!(f, "missing files"),
+            SeedingState::Error(message) => write!(f, "error: {}", message),
+        }
+    }
+}
+
+/// IDs a client may list `torrent` under: the v1 infohash and the truncated v2 infohash
+/// qBittorrent uses for v2-only torrents.
+fn torrent_ids(torrent: &[u8]) -> Result<Vec<String>, SeedToolsError> {
+    let v2: String = Sha256::digest(raw_info(torrent)?).iter().take(20).map(|byte| format!("{:02x}", byte)).collect();
+    Ok(vec![torrent_info_hash(torrent)?, v2])
+}
+
+/// Polls `state` until the torrent has left the checking states, giving up after the timeouts.
+/// `state` returns `None` while the client does not list the torrent yet.
+fn wait_for(mut state: impl FnMut() -> Result<Option<SeedingState>, SeedToolsError>) -> SeedingState {
+    let started = Instant::now();
+    loop {
+        match state() {
+            Ok(Some(SeedingState::Checking(progress))) if started.elapsed() >= CHECK_TIMEOUT => {
+                return SeedingState::Checking(progress);
+            }
+            Ok(Some(SeedingState::Checking(_))) => {}
+            Ok(Some(state)) => return state,
+            Ok(None) if started.elapsed() >= APPEAR_TIMEOUT => {
+                return SeedingState::Error("torrent did not show up in the client".to_string());
+            }
+            Ok(None) => {}
+            Err(e) => return SeedingState::Error(e.to_string()),
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Waits for the torrent just added to qBittorrent at `webui_url` (through the logged-in `client`)
+/// to finish checking and returns its state.
+pub fn wait_for_qbittorrent(client: &Client, webui_url: &str, torrent: &[u8]) -> SeedingState {
+    let ids = match torrent_ids(torrent) {
+        Ok(ids) => ids,
+        Err(e) => return SeedingState::Error(e.to_string()),
+    };
+    info!("Waiting for qBittorrent at {} to report the torrent's state...", webui_url);
+    wait_for(|| {
+        let torrents: Vec<Value> = client
+            .get(format!("{}/api/v2/torrents/info", webui_url))
+            .query(&[("hashes", ids.join("|"))])
+            .send()
+            .and_then(|response| response.json())
+            .map_err(SeedToolsError::client_request("qBittorrent", "Failed to fetch the torrent state"))?;
+        Ok(torrents.first().map(|torrent| {
+            let progress = torrent["progress"].as_f64().unwrap_or(0.0);
+            match torrent["state"].as_str().unwrap_or_default() {
+                "checkingUP" | "checkingDL" | "checkingResumeData" | "queuedForChecking" | "allocating" | "metaDL"
+                | "moving" => SeedingState::Checking(progress),
+                "missingFiles" => SeedingState::MissingFiles,
+                "error" => SeedingState::Error("qBittorrent reports an error".to_string()),
+                "unknown" => SeedingState::Error("qBittorrent reports an unknown state".to_string()),
+                _ if progress >= 1.0 => SeedingState::Seeding,
+                _ => SeedingState::Incomplete(progress),
+            }
+        }))
+    })
+}
+
+/// Same as `wait_for_qbittorrent` for Deluge's web UI.
+pub fn wait_for_deluge(client: &Client, webui_url: &str, torrent: &[u8]) -> SeedingState {
+    let hash = match torrent_info_hash(torrent) {
+        Ok(hash) => hash,
+        Err(e) => return SeedingState::Error(e.to_string()),
+    };
+    info!("Waiting for Deluge at {} to report the torrent's state...", webui_url);
+    wait_for(|| {
+        let status: Value = client
+            .post(format!("{}/json", webui_url))
+            .json(&json!({ "method": "core.get_torrent_status", "params": [hash, ["state", "progress", "message"]], "id": 3 }))
+            .send()
+            .and_then(|response| response.json())
+            .map_err(SeedToolsError::client_request("Deluge", "Failed to fetch the torrent state"))?;
+        let torrent = &status["result"];
+        let Some(state) = torrent["state"].as_str() else { return Ok(None) };
+        // Deluge reports progress in percent
+        let progress = torrent["progress"].as_f64().unwrap_or(0.0) / 100.0;
+        Ok(Some(match state {
+            "Checking" | "Allocating" | "Moving" => SeedingState::Checking(progress),
+            "Error" => {
+                let message = torrent["message"].as_str().unwrap_or("Deluge reports an error");
+                if message.to_lowercase().contains("missing") {
+                    SeedingState::MissingFiles
+                } else {
+                    SeedingState::Error(message.to_string())
+                }
+            }
+            _ if progress >= 1.0 => SeedingState::Seeding,
+            _ => SeedingState::Incomplete(progress),
+        }))
+    })
+}
+
+/// Logs the state a client reported, warning about anything but seeding.
+pub fn log_state(client_name: &str, state: &SeedingState) {
+    if state.is_seeding() {
+        info!("{}: seeding.", client_name);
+    } else {
+        warn!("{}: {}.", client_name, state);
+    }
+}
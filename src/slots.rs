@@ -0,0 +1,66 @@
+use std::fs::{self, File, OpenOptions};
+use std::thread;
+use std::time::Duration;
+use log::info;
+use crate::pipeline::work_dir;
+use crate::types::{ConcurrencyConfig, PathsConfig};
+
+/// Kinds of work that parallel uploads compete for.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Workload {
+    Hashing,  // Torrent creation; reads the whole release from disk
+    Metadata, // TMDB lookups and mediainfo
+    Images,   // Screenshots, sample and proof image, and their uploads
+}
+
+impl Workload {
+    fn name(self) -> &'static str {
+        match self {
+            Workload::Hashing => "hashing",
+            Workload::Metadata => "metadata",
+            Workload::Images => "images",
+        }
+    }
+
+    fn limit(self, config: &ConcurrencyConfig) -> usize {
+        match self {
+            Workload::Hashing => config.hashing,
+            Workload::Metadata => config.metadata,
+            Workload::Images => config.images,
+        }
+        .max(1)
+    }
+}
+
+/// A held slot; it is released when dropped, or by the OS when the process exits.
+pub struct Slot {
+    _lock: File,
+}
+
+/// Waits until fewer than the configured number of uploads run `workload` and takes a slot.
+/// Slots are lock files in `work_dir/slots`, so the limit holds across every seed-tools
+/// process: the UI queue, web and API jobs and uploads started by hand.
+pub fn acquire(paths: &PathsConfig, config: &ConcurrencyConfig, workload: Workload) -> Result<Slot, String> {
+    let dir = work_dir(paths).join("slots");
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create '{}': {}", dir.display(), e))?;
+    let mut waiting = false;
+    loop {
+        for index in 0..workload.limit(config) {
+            let path = dir.join(format!("{}.{}.lock", workload.name(), index));
+            let file = OpenOptions::new()
+                .create(true)
+                .truncate(false)
+                .write(true)
+                .open(&path)
+                .map_err(|e| format!("Failed to open '{}': {}", path.display(), e))?;
+            if file.try_lock().is_ok() {
+                return Ok(Slot { _lock: file });
+            }
+        }
+        if !waiting {
+            info!("Waiting for a free {} slot...", workload.name());
+            waiting = true;
+        }
+        thread::sleep(Duration::from_secs(1));
+    }
+}
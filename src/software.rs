@@ -0,0 +1,110 @@
+use std::fs;
+use regex::Regex;
+use crate::utils::{format_size, render_file_tree};
+
+// Longest NFO put into a description; some ship whole changelogs
+const MAX_NFO_CHARS: usize = 8000;
+
+/// A software release parsed from its folder name, e.g.
+/// `Some.App.Pro.v2.4.1.x64.Multilingual-GROUP`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SoftwareRelease {
+    pub name: String,            // "Some App Pro"
+    pub version: Option<String>, // "2.4.1"
+    pub os: Option<String>,      // windows, macos, linux, android or ios
+    pub arch: Option<String>,    // x64, x86, arm64 or universal
+    pub group: Option<String>,
+}
+
+impl SoftwareRelease {
+    /// "Some App Pro 2.4.1 (windows, x64)".
+    pub fn display_name(&self) -> String {
+        let mut name = self.name.clone();
+        if let Some(version) = &self.version {
+            name.push_str(&format!(" {}", version));
+        }
+        let platform: Vec<&str> = [&self.os, &self.arch].iter().filter_map(|value| value.as_deref()).collect();
+        if !platform.is_empty() {
+            name.push_str(&format!(" ({})", platform.join(", ")));
+        }
+        name
+    }
+}
+
+/// Reads the app name, version, OS, architecture and group out of a software release name.
+pub fn parse_software_release(release_name: &str) -> SoftwareRelease {
+    let stem = Regex::new(r"(?i)\.(zip|rar|7z|iso|exe|msi|dmg|pkg|apk)$").unwrap().replace(release_name, "").to_string();
+    let group = stem
+        .rsplit_once('-')
+        .map(|(_, group)| group.to_string())
+        .filter(|group| !group.is_empty() && !group.contains(['.', ' ', '_']));
+    let stem = match &group {
+        Some(group) => stem[..stem.len() - group.len() - 1].to_string(),
+        None => stem,
+    };
+
+    let find = |pattern: &str| Regex::new(pattern).unwrap().find(&stem).map(|found| found.start());
+    let os = [
+        (r"(?i)\b(win(dows)?|win(32|64))\b", "windows"),
+        (r"(?i)\b(mac(os)?|osx)\b", "macos"),
+        (r"(?i)\blinux\b", "linux"),
+        (r"(?i)\bandroid\b", "android"),
+        (r"(?i)\bios\b", "ios"),
+    ]
+    .iter()
+    .find_map(|(pattern, os)| find(pattern).map(|start| (start, os.to_string())));
+    let arch = [
+        (r"(?i)\b(x64|x86[._-]64|amd64|win64)\b", "x64"),
+        (r"(?i)\b(x86|x32|win32|i[36]86)\b", "x86"),
+        (r"(?i)\b(arm64|aarch64)\b", "arm64"),
+        (r"(?i)\b(universal|u2b)\b", "universal"),
+    ]
+    .iter()
+    .find_map(|(pattern, arch)| find(pattern).map(|start| (start, arch.to_string())));
+    let version = Regex::new(r"(?i)\bv?(\d+(?:[._]\d+)+[a-z]?|v\d+)\b").unwrap().captures(&stem).map(|captures| {
+        let found = captures.get(0).unwrap();
+        (found.start(), captures[1].trim_start_matches(['v', 'V']).replace('_', "."))
+    });
+
+    // The name is everything before the first version, OS or architecture token
+    let end = [version.as_ref().map(|v| v.0), os.as_ref().map(|o| o.0), arch.as_ref().map(|a| a.0)]
+        .into_iter()
+        .flatten()
+        .min()
+        .unwrap_or(stem.len());
+    let name = stem[..end].replace(['.', '_'], " ").trim().to_string();
+    SoftwareRelease {
+        name: if name.is_empty() { stem.replace(['.', '_'], " ") } else { name },
+        version: version.map(|v| v.1),
+        os: os.map(|o| o.1),
+        arch: arch.map(|a| a.1),
+        group,
+    }
+}
+
+/// BBCode description for a software upload: the parsed details, the NFO and the file tree.
+pub fn generate_software_description(input_path: &str, release: &SoftwareRelease, nfo_file: Option<&str>, custom_description: &str) -> String {
+    let mut description = format!("[b]{}[/b]\n", release.display_name());
+    if let Some(group) = &release.group {
+        description.push_str(&format!("Group: {}\n", group));
+    }
+
+    if let Some(nfo) = nfo_file.and_then(|nfo| fs::read(nfo).ok()) {
+        // NFOs are usually CP437; the ASCII art is lost but the text survives
+        let nfo = String::from_utf8_lossy(&nfo);
+        let nfo: String = nfo.chars().take(MAX_NFO_CHARS).collect();
+        description.push_str(&format!("\n[spoiler=NFO][code]{}[/code][/spoiler]\n", nfo.trim_end()));
+    }
+
+    let file_tree = render_file_tree(input_path);
+    if !file_tree.is_empty() {
+        description.push_str(&format!("\n{}", file_tree));
+    } else if let Some(size) = fs::metadata(input_path).ok().filter(|metadata| metadata.is_file()).map(|metadata| metadata.len()) {
+        description.push_str(&format!("\nSize: {}\n", format_size(size)));
+    }
+
+    if !custom_description.is_empty() {
+        description.push_str(&format!("\n{}", custom_description));
+    }
+    description
+}
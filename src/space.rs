@@ -0,0 +1,97 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use log::debug;
+use tempfile::TempDir;
+use crate::types::{DiskSpaceConfig, PathsConfig};
+use crate::utils::format_size;
+
+// Free-space checks before the stages that write a lot, and the temp directory for
+// intermediates (ImgBB screenshots, extracted covers and pages). Temp files live in a
+// `TempDir` that is removed when it goes out of scope, whether the upload succeeded or not.
+
+static CONFIG: OnceLock<(PathBuf, DiskSpaceConfig)> = OnceLock::new();
+
+const MIB: u64 = 1024 * 1024;
+
+/// Stages that are checked for free space before they start.
+#[derive(Clone, Copy, Debug)]
+pub enum Stage {
+    Screenshots,
+    Sample,
+    Unpack,
+    Temp,
+}
+
+impl Stage {
+    fn describe(self) -> &'static str {
+        match self {
+            Stage::Screenshots => "screenshots",
+            Stage::Sample => "the sample",
+            Stage::Unpack => "unpacking",
+            Stage::Temp => "temporary files",
+        }
+    }
+}
+
+/// Applies `paths.temp_dir` and the `disk_space` section of config.yaml. Call once at startup.
+pub fn init(paths: &PathsConfig, config: &DiskSpaceConfig) {
+    let root = match &paths.temp_dir {
+        Some(dir) => PathBuf::from(dir),
+        None => std::env::temp_dir().join("seed-tools"),
+    };
+    let _ = CONFIG.set((root, config.clone()));
+}
+
+fn config() -> (PathBuf, DiskSpaceConfig) {
+    CONFIG.get().cloned().unwrap_or_else(|| (std::env::temp_dir().join("seed-tools"), DiskSpaceConfig::default()))
+}
+
+/// Free space on the filesystem holding `path`, which need not exist yet.
+pub fn free_space(path: &Path) -> Result<u64, String> {
+    let existing = path.ancestors().find(|dir| dir.exists()).unwrap_or(Path::new("."));
+    fs2::available_space(existing).map_err(|e| format!("Failed to read free space of '{}': {}", existing.display(), e))
+}
+
+/// Checks that `dir` has room for `needed` bytes plus the minimum configured for `stage`.
+pub fn ensure_free(stage: Stage, dir: &Path, needed: u64) -> Result<(), String> {
+    let (_, config) = config();
+    let minimum = match stage {
+        Stage::Screenshots => config.screenshots_mb,
+        Stage::Sample => config.sample_mb,
+        Stage::Unpack => config.unpack_mb,
+        Stage::Temp => config.temp_mb,
+    } * MIB;
+    if minimum == 0 {
+        return Ok(());
+    }
+    let free = free_space(dir)?;
+    debug!("Free space for {} in '{}': {}", stage.describe(), dir.display(), format_size(free));
+    if free < needed.saturating_add(minimum) {
+        return Err(format!(
+            "Not enough free space for {} in '{}': {} free, {} needed with {} kept free",
+            stage.describe(),
+            dir.display(),
+            format_size(free),
+            format_size(needed),
+            format_size(minimum)
+        ));
+    }
+    Ok(())
+}
+
+/// Start of the name of every directory `temp_dir` creates, so `clean` can tell them from other
+/// files in a shared `paths.temp_dir`.
+pub const TEMP_PREFIX: &str = "seed-tools-";
+
+/// A new directory under the temp directory for one step's intermediates, e.g. the pages of a
+/// comic, named `seed-tools-<label>-...`. It is deleted with everything in it when dropped.
+pub fn temp_dir(label: &str) -> Result<TempDir, String> {
+    let (root, _) = config();
+    fs::create_dir_all(&root).map_err(|e| format!("Failed to create temp directory '{}': {}", root.display(), e))?;
+    ensure_free(Stage::Temp, &root, 0)?;
+    tempfile::Builder::new()
+        .prefix(&format!("{}{}-", TEMP_PREFIX, label))
+        .tempdir_in(&root)
+        .map_err(|e| format!("Failed to create a temp directory in '{}': {}", root.display(), e))
+}
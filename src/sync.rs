@@ -1,99 +1,23 @@
 use std::fs;
 use std::{path::Path, thread, time::Duration};
 use log::{info, error};
-use regex::Regex;
-use bendy::decoding::{FromBencode, Object};
+use bendy::decoding::Object;
 use reqwest::blocking::Client;
 use serde_json;
-use crate::utils::generate_release_name;
-use crate::types::QbittorrentConfig; 
-
-
-pub fn check_seedpool(
-    name: &str,
-    seedpool_api_key: &str,
-) -> Result<Option<String>, String> {
-    let client = Client::new();
-
-    info!("Checking Seedpool for existing torrent with name: '{}'", name);
-
-    let normalized_name = generate_release_name(name);
-    info!("Normalized Name for Seedpool Query: '{}'", normalized_name);
-
-    let season_episode_regex = Regex::new(r"S(\d{2})E(\d{2})").unwrap();
-    let season_episode = season_episode_regex.captures(name).map(|caps| {
-        (
-            caps.get(1).unwrap().as_str().parse::<u32>().unwrap_or(0),
-            caps.get(2).unwrap().as_str().parse::<u32>().unwrap_or(0),
-        )
-    });
-    if let Some((season, episode)) = &season_episode {
-        info!("Detected Season/Episode: S{}E{}", season, episode);
-    }
-
-    let mut query_url = format!(
-        "https://seedpool.org/api/torrents/filter?name={}&perPage=10&sortField=name&sortDirection=asc&api_token={}",
-        urlencoding::encode(&normalized_name),
-        seedpool_api_key
-    );
-
-    if let Some((season, episode)) = &season_episode {
-        query_url = format!(
-            "{}&seasonNumber={}&episodeNumber={}",
-            query_url, season, episode
-        );
-    }
-
-    info!("Seedpool API Query URL: {}", query_url);
-
-    let search_response = client
-        .get(&query_url)
-        .send()
-        .map_err(|e| format!("Failed to query Seedpool for '{}': {}", name, e))?;
-
-    if !search_response.status().is_success() {
-        return Err(format!(
-            "Failed to query Seedpool for '{}': HTTP {}",
-            name,
-            search_response.status()
-        ));
-    }
-
-    let raw_response = search_response.text().unwrap_or_else(|_| "Failed to read response body".to_string());
-
-    let search_results: serde_json::Value = serde_json::from_str(&raw_response)
-        .map_err(|e| format!("Failed to parse Seedpool response for '{}': {}", name, e))?;
-
-    let empty_vec = vec![];
-    let data = search_results["data"].as_array().unwrap_or(&empty_vec);
-
-    for result in data {
-        if let Some(attributes) = result["attributes"].as_object() {
-            if let Some(title) = attributes.get("name").and_then(|t| t.as_str()) {
-                info!("Checking result title: {}", title);
-
-                if let Some((season, episode)) = &season_episode {
-                    if !title.contains(&format!("S{:02}E{:02}", season, episode)) {
-                        info!("Skipping result due to mismatched season/episode: {}", title);
-                        continue;
-                    }
-                }
-
-                if let Some(download_link) = attributes.get("download_link").and_then(|d| d.as_str()) {
-                    info!("Duplicate found for '{}'. Download link: {}", name, download_link);
-                    return Ok(Some(download_link.to_string()));
-                }
-            }
-        }
-    }
-
-    info!("No duplicate found for '{}'.", name);
-    Ok(None)
-}
-
-pub fn sync_qbittorrent(configs: &[QbittorrentConfig], seedpool_api_key: &str) -> Result<(), String> {
+use crate::error::SeedToolsError;
+use crate::history::{record_removal, Removal};
+use crate::trackers::seedpool::check_seedpool;
+use crate::torrent::verify_before_inject;
+use crate::trackers::release::{process_release, Target, UploadOptions};
+use crate::types::{from_client_path, to_client_path, Config, PathMapping, PathsConfig, QbittorrentConfig, SeedpoolConfig};
+
+
+/// Cross-seeds every completed qBittorrent torrent that Seedpool also has.
+/// Returns the number of torrents added.
+pub fn sync_qbittorrent(configs: &[QbittorrentConfig], seedpool_api_key: &str) -> Result<usize, SeedToolsError> {
+    let mut added = 0;
     for config in configs {
-        let client = Client::new();
+        let client = crate::http::client();
 
         info!("Logging in to qBittorrent at {}...", config.webui_url);
         let login_response = client
@@ -103,7 +27,7 @@ pub fn sync_qbittorrent(configs: &[QbittorrentConfig], seedpool_api_key: &str) -
                 ("password", config.password.as_str()),
             ])
             .send()
-            .map_err(|e| format!("Failed to log in to qBittorrent: {}", e))?;
+            .map_err(qbittorrent_error("Failed to log in to qBittorrent"))?;
 
         if !login_response.status().is_success() {
             error!(
@@ -118,18 +42,18 @@ pub fn sync_qbittorrent(configs: &[QbittorrentConfig], seedpool_api_key: &str) -
         let torrents_response = client
             .get(format!("{}/api/v2/torrents/info", config.webui_url))
             .send()
-            .map_err(|e| format!("Failed to fetch torrents info: {}", e))?;
+            .map_err(qbittorrent_error("Failed to fetch torrents info"))?;
 
         if !torrents_response.status().is_success() {
-            return Err(format!(
-                "Failed to fetch torrents info: {}",
-                torrents_response.status()
+            return Err(SeedToolsError::client(
+                "qBittorrent",
+                format!("Failed to fetch torrents info: {}", torrents_response.status()),
             ));
         }
 
         let torrents: Vec<serde_json::Value> = torrents_response
             .json()
-            .map_err(|e| format!("Failed to parse torrents info: {}", e))?;
+            .map_err(qbittorrent_error("Failed to parse torrents info"))?;
 
         let completed_torrents: Vec<&serde_json::Value> = torrents
             .iter()
@@ -157,9 +81,12 @@ pub fn sync_qbittorrent(configs: &[QbittorrentConfig], seedpool_api_key: &str) -
                 }
             };
 
+            // The client may see the data under another path than this machine
+            let local_save_path = from_client_path(&config.path_mappings, &save_path);
+
             // Ensure the save path exists
-            if let Err(e) = std::fs::create_dir_all(&save_path) {
-                error!("Failed to create save path '{}': {}", save_path, e);
+            if let Err(e) = std::fs::create_dir_all(&local_save_path) {
+                error!("Failed to create save path '{}': {}", local_save_path, e);
                 continue;
             }
 
@@ -169,6 +96,10 @@ pub fn sync_qbittorrent(configs: &[QbittorrentConfig], seedpool_api_key: &str) -
             match check_seedpool(name, seedpool_api_key) {
                 Ok(Some(download_link)) => {
                     info!("Found duplicate for '{}'. Adding to qBittorrent.", name);
+                    if let Err(e) = verify_download(client, &download_link, &local_save_path) {
+                        error!("Not cross-seeding '{}': {}", name, e);
+                        continue;
+                    }
 
                     // Add the torrent to qBittorrent with the determined save path
                     let add_torrent_response = client
@@ -181,7 +112,7 @@ pub fn sync_qbittorrent(configs: &[QbittorrentConfig], seedpool_api_key: &str) -
                             ("skip_checking", "true"),
                         ])
                         .send()
-                        .map_err(|e| format!("Failed to add torrent to qBittorrent: {}", e))?;
+                        .map_err(qbittorrent_error("Failed to add torrent to qBittorrent"))?;
 
                     if !add_torrent_response.status().is_success() {
                         error!(
@@ -194,6 +125,7 @@ pub fn sync_qbittorrent(configs: &[QbittorrentConfig], seedpool_api_key: &str) -
                             "Successfully added torrent '{}' to qBittorrent with save path '{}'.",
                             name, save_path
                         );
+                        added += 1;
                     }
                 }
                 Ok(None) => {
@@ -208,15 +140,334 @@ pub fn sync_qbittorrent(configs: &[QbittorrentConfig], seedpool_api_key: &str) -
         }
     }
 
+    Ok(added)
+}
+
+/// A completed torrent in a `reseed_category` that Seedpool does not have yet.
+pub struct ReseedCandidate {
+    pub name: String,
+    pub hash: String,
+    pub path: String, // Content path on this machine
+    pub webui_url: String,
+}
+
+/// Lists the completed torrents in the `reseed_category` of each qBittorrent instance that
+/// Seedpool has no match for, so a seeding library can be uploaded as a queue. Torrents whose
+/// data is not reachable from this machine are left out.
+pub fn find_reseed_candidates(configs: &[QbittorrentConfig], seedpool_api_key: &str) -> Result<Vec<ReseedCandidate>, SeedToolsError> {
+    let mut candidates = Vec::new();
+    for config in configs {
+        let Some(category) = config.reseed_category.as_deref().filter(|category| !category.is_empty()) else { continue };
+        let client = login(config)?;
+        let torrents_response = client
+            .get(format!("{}/api/v2/torrents/info", config.webui_url))
+            .query(&[("category", category), ("filter", "completed")])
+            .send()
+            .map_err(qbittorrent_error("Failed to fetch torrents info"))?;
+        if !torrents_response.status().is_success() {
+            return Err(SeedToolsError::client(
+                "qBittorrent",
+                format!("Failed to fetch torrents info: {}", torrents_response.status()),
+            ));
+        }
+        let torrents: Vec<serde_json::Value> = torrents_response
+            .json()
+            .map_err(qbittorrent_error("Failed to parse torrents info"))?;
+        info!("Checking {} torrent(s) in category '{}' at {} against Seedpool.", torrents.len(), category, config.webui_url);
+
+        for torrent in &torrents {
+            let name = torrent["name"].as_str().unwrap_or("Unknown");
+            let content_path = from_client_path(&config.path_mappings, torrent["content_path"].as_str().unwrap_or(""));
+            if content_path.is_empty() || !Path::new(&content_path).exists() {
+                error!("Not re-seeding '{}': its data is not at '{}' on this machine.", name, content_path);
+                continue;
+            }
+            match check_seedpool(name, seedpool_api_key) {
+                Ok(Some(_)) => info!("'{}' is already on Seedpool.", name),
+                Ok(None) => {
+                    info!("'{}' is not on Seedpool yet.", name);
+                    candidates.push(ReseedCandidate {
+                        name: name.to_string(),
+                        hash: torrent["hash"].as_str().unwrap_or("").to_string(),
+                        path: content_path,
+                        webui_url: config.webui_url.clone(),
+                    });
+                }
+                Err(e) => error!("Error checking Seedpool for '{}': {}", name, e),
+            }
+            thread::sleep(Duration::from_secs(3));
+        }
+    }
+    Ok(candidates)
+}
+
+/// Uploads each candidate to Seedpool through the standard pipeline, asking first for each one
+/// unless `assume_yes` is set. Returns the outcome per release name; declined ones are left out.
+pub fn reseed(
+    candidates: &[ReseedCandidate],
+    config: &mut Config,
+    seedpool_config: &SeedpoolConfig,
+    assume_yes: bool,
+) -> Vec<(String, Result<(), SeedToolsError>)> {
+    let options = UploadOptions {
+        imgbb_api_key: config.imgbb.as_ref().map(|imgbb| imgbb.imgbb_api_key.clone()),
+        ..Default::default()
+    };
+    let targets = [Target::Seedpool(seedpool_config)];
+    let mut results = Vec::new();
+    for candidate in candidates {
+        if !assume_yes {
+            let confirmed = dialoguer::Confirm::new()
+                .with_prompt(format!("Upload '{}' to Seedpool?", candidate.name))
+                .default(false)
+                .interact()
+                .unwrap_or(false);
+            if !confirmed {
+                info!("Skipping '{}'.", candidate.name);
+                continue;
+            }
+        }
+        info!("Re-seeding '{}' from '{}' to Seedpool.", candidate.name, candidate.path);
+        let result = match process_release(&candidate.path, config, &targets, &options) {
+            Ok(outcomes) => outcomes.into_iter().map(|(_, result)| result).next().unwrap_or(Ok(())),
+            Err(e) => Err(e),
+        };
+        results.push((candidate.name.clone(), result));
+    }
+    results
+}
+
+/// Why a torrent in a client is dead.
+#[derive(Debug, Clone)]
+pub enum DeadReason {
+    DataMissing(String),  // Content path that no longer exists on this machine
+    Unregistered(String), // Tracker message, e.g. "Unregistered torrent"
+}
+
+impl std::fmt::Display for DeadReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            DeadReason::DataMissing(path) => write!(f, "data missing at '{}'", path),
+            DeadReason::Unregistered(message) => write!(f, "deleted on the tracker ({})", message),
+        }
+    }
+}
+
+/// A torrent whose data is gone or that its tracker no longer knows.
+pub struct DeadTorrent {
+    pub name: String,
+    pub hash: String,
+    pub webui_url: String,
+    pub reason: DeadReason,
+}
+
+// Tracker messages for a torrent that was deleted or never uploaded
+const UNREGISTERED_MESSAGES: &[&str] = &["unregistered", "not registered", "torrent not found", "torrent does not exist", "has been deleted", "trumped"];
+
+/// The content paths of `torrents` (qBittorrent's torrents/info) that are gone while their save
+/// path is still there, as index into `torrents` and local path. A save path that is not
+/// reachable from this machine means the client sees another filesystem (a remote or Docker
+/// client without `path_mappings`), not that the data was deleted, so those torrents are
+/// skipped. Fails when not a single save path is reachable.
+fn missing_data(torrents: &[serde_json::Value], path_mappings: &[PathMapping]) -> Result<Vec<(usize, String)>, SeedToolsError> {
+    let mut reachable_roots = 0;
+    let mut missing = Vec::new();
+    for (index, torrent) in torrents.iter().enumerate() {
+        let save_path = from_client_path(path_mappings, torrent["save_path"].as_str().unwrap_or(""));
+        if save_path.is_empty() || !Path::new(&save_path).is_dir() {
+            continue;
+        }
+        reachable_roots += 1;
+        let content_path = from_client_path(path_mappings, torrent["content_path"].as_str().unwrap_or(""));
+        if !content_path.is_empty() && !Path::new(&content_path).exists() {
+            missing.push((index, content_path));
+        }
+    }
+    if !torrents.is_empty() && reachable_roots == 0 {
+        return Err(SeedToolsError::config("None of the client's save paths exist on this machine; set path_mappings if it runs elsewhere"));
+    }
+    Ok(missing)
+}
+
+/// Lists the torrents in every qBittorrent instance whose content path no longer exists below a
+/// reachable save path, or that no tracker works for and one answers as unregistered. Nothing
+/// is removed. Deluge is not checked.
+pub fn find_dead_torrents(configs: &[QbittorrentConfig]) -> Result<Vec<DeadTorrent>, SeedToolsError> {
+    let mut dead = Vec::new();
+    for config in configs {
+        let client = login(config)?;
+        let torrents: Vec<serde_json::Value> = client
+            .get(format!("{}/api/v2/torrents/info", config.webui_url))
+            .send()
+            .and_then(|response| response.json())
+            .map_err(qbittorrent_error("Failed to fetch torrents info"))?;
+        info!("Checking {} torrent(s) at {} for missing data and deleted uploads.", torrents.len(), config.webui_url);
+        let mut missing = missing_data(&torrents, &config.path_mappings)
+            .map_err(|e| e.context(&format!("Not checking qBittorrent at {}", config.webui_url)))?
+            .into_iter()
+            .collect::<std::collections::HashMap<_, _>>();
+
+        for (index, torrent) in torrents.iter().enumerate() {
+            let name = torrent["name"].as_str().unwrap_or("Unknown").to_string();
+            let hash = torrent["hash"].as_str().unwrap_or("").to_string();
+            let reason = if let Some(content_path) = missing.remove(&index) {
+                Some(DeadReason::DataMissing(content_path))
+            } else if torrent["tracker"].as_str().unwrap_or("").is_empty() {
+                // `tracker` is empty while no tracker works; only then are the messages worth reading
+                let trackers: Vec<serde_json::Value> = client
+                    .get(format!("{}/api/v2/torrents/trackers", config.webui_url))
+                    .query(&[("hash", hash.as_str())])
+                    .send()
+                    .and_then(|response| response.json())
+                    .unwrap_or_default();
+                trackers
+                    .iter()
+                    .filter_map(|tracker| tracker["msg"].as_str())
+                    .find(|message| {
+                        let message = message.to_lowercase();
+                        UNREGISTERED_MESSAGES.iter().any(|pattern| message.contains(pattern))
+                    })
+                    .map(|message| DeadReason::Unregistered(message.to_string()))
+            } else {
+                None
+            };
+            if let Some(reason) = reason {
+                dead.push(DeadTorrent { name, hash, webui_url: config.webui_url.clone(), reason });
+            }
+        }
+    }
+    Ok(dead)
+}
+
+/// Removes dead torrents from their qBittorrent instance, keeping whatever data is left, and
+/// logs each removal to the history database. Returns the outcome per torrent name.
+pub fn remove_dead_torrents(
+    dead: &[DeadTorrent],
+    configs: &[QbittorrentConfig],
+    paths: &PathsConfig,
+) -> Vec<(String, Result<(), SeedToolsError>)> {
+    let mut results = Vec::new();
+    for config in configs {
+        let torrents: Vec<&DeadTorrent> = dead.iter().filter(|torrent| torrent.webui_url == config.webui_url).collect();
+        if torrents.is_empty() {
+            continue;
+        }
+        let client = match login(config) {
+            Ok(client) => client,
+            Err(e) => {
+                let message = e.to_string();
+                results.extend(torrents.iter().map(|torrent| (torrent.name.clone(), Err(SeedToolsError::client("qBittorrent", message.clone())))));
+                continue;
+            }
+        };
+        for torrent in torrents {
+            let result = client
+                .post(format!("{}/api/v2/torrents/delete", config.webui_url))
+                .form(&[("hashes", torrent.hash.as_str()), ("deleteFiles", "false")])
+                .send()
+                .map_err(qbittorrent_error("Failed to remove torrent from qBittorrent"))
+                .and_then(|response| {
+                    if response.status().is_success() {
+                        Ok(())
+                    } else {
+                        Err(SeedToolsError::client("qBittorrent", format!("Failed to remove torrent from qBittorrent: {}", response.status())))
+                    }
+                });
+            if result.is_ok() {
+                info!("Removed '{}' from qBittorrent at {}: {}.", torrent.name, config.webui_url, torrent.reason);
+                let removal = Removal {
+                    removed_at: chrono::Utc::now().to_rfc3339(),
+                    client: config.webui_url.clone(),
+                    release_name: torrent.name.clone(),
+                    info_hash: torrent.hash.clone(),
+                    reason: torrent.reason.to_string(),
+                };
+                if let Err(e) = record_removal(paths, &removal) {
+                    error!("Failed to log the removal of '{}' to the history: {}", torrent.name, e);
+                }
+            }
+            results.push((torrent.name.clone(), result));
+        }
+    }
+    results
+}
+
+/// A cookie-keeping client logged in to one qBittorrent instance.
+fn login(config: &QbittorrentConfig) -> Result<Client, SeedToolsError> {
+    let client = crate::http::client_builder()
+        .cookie_store(true)
+        .build()
+        .map_err(qbittorrent_error("Failed to create HTTP client"))?;
+    let login_response = client
+        .post(format!("{}/api/v2/auth/login", config.webui_url))
+        .form(&[
+            ("username", config.username.as_str()),
+            ("password", config.password.as_str()),
+        ])
+        .send()
+        .map_err(qbittorrent_error("Failed to log in to qBittorrent"))?;
+    if !login_response.status().is_success() {
+        return Err(SeedToolsError::client(
+            "qBittorrent",
+            format!("Failed to log in to qBittorrent at {}: {}", config.webui_url, login_response.status()),
+        ));
+    }
+    Ok(client)
+}
+
+fn qbittorrent_error(context: &'static str) -> impl FnOnce(reqwest::Error) -> SeedToolsError {
+    SeedToolsError::client_request("qBittorrent", context)
+}
+
+/// Downloads the torrent behind `download_link` and checks it against the data in `save_path`,
+/// since the clients add it with `skip_checking`.
+fn verify_download(client: &Client, download_link: &str, save_path: &str) -> Result<(), SeedToolsError> {
+    let response = client
+        .get(download_link)
+        .send()
+        .map_err(|e| SeedToolsError::request("Seedpool", e).context("Failed to download torrent"))?;
+    if !response.status().is_success() {
+        return Err(SeedToolsError::tracker(
+            "Seedpool",
+            Some(response.status().as_u16()),
+            format!("Failed to download torrent. HTTP Status: {}", response.status()),
+        ));
+    }
+    let torrent = response.bytes().map_err(|e| SeedToolsError::request("Seedpool", e).context("Failed to read torrent data"))?;
+    Ok(verify_before_inject(&torrent, Path::new(save_path))?)
+}
+
+/// Logs in to qBittorrent and adds a torrent by URL on top of existing data, skipping the hash check.
+/// `save_path` is local; the client gets it through `path_mappings`.
+pub fn cross_seed_to_qbittorrent(config: &QbittorrentConfig, download_link: &str, save_path: &str) -> Result<(), SeedToolsError> {
+    let client = login(config)?;
+    verify_download(&client, download_link, save_path)?;
+
+    let add_torrent_response = client
+        .post(format!("{}/api/v2/torrents/add", config.webui_url))
+        .form(&[
+            ("urls", download_link),
+            ("savepath", &to_client_path(&config.path_mappings, save_path)),
+            ("category", config.category.as_deref().unwrap_or("")),
+            ("paused", "false"),
+            ("skip_checking", "true"),
+        ])
+        .send()
+        .map_err(qbittorrent_error("Failed to add torrent to qBittorrent"))?;
+    if !add_torrent_response.status().is_success() {
+        return Err(SeedToolsError::client("qBittorrent", format!("Failed to add torrent to qBittorrent: {}", add_torrent_response.status())));
+    }
+
+    info!("Cross-seeding '{}' from '{}' in qBittorrent at {}.", download_link, save_path, config.webui_url);
     Ok(())
 }
 
-fn get_save_path_from_fastresume(torrent_hash: &str, fastresume_dir: &str) -> Result<String, String> {
+fn get_save_path_from_fastresume(torrent_hash: &str, fastresume_dir: &str) -> Result<String, SeedToolsError> {
     let fastresume_path = Path::new(fastresume_dir).join(format!("{}.fastresume", torrent_hash));
     info!("Reading .fastresume file: {}", fastresume_path.display());
 
     let fastresume_data = fs::read(&fastresume_path)
-        .map_err(|e| format!("Failed to read .fastresume file: {}", e))?;
+        .map_err(SeedToolsError::io(format!("Failed to read '{}'", fastresume_path.display())))?;
 
     let mut decoder = bendy::decoding::Decoder::new(&fastresume_data);
     let mut qb_save_path = None;
@@ -246,5 +497,60 @@ fn get_save_path_from_fastresume(torrent_hash: &str, fastresume_dir: &str) -> Re
     // Use qBt-savePath if available, otherwise fallback to save_path
     qb_save_path
         .or(save_path)
-        .ok_or_else(|| "Neither qBt-savePath nor save_path found in .fastresume file".to_string())
-}
\ No newline at end of file
+        .ok_or_else(|| SeedToolsError::client("qBittorrent", format!("Neither qBt-savePath nor save_path found in '{}'", fastresume_path.display())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn torrent(save_path: &str, content_path: &str) -> serde_json::Value {
+        json!({ "name": "Some.Movie.2020.1080p", "hash": "abc", "save_path": save_path, "content_path": content_path })
+    }
+
+    #[test]
+    fn missing_data_reports_content_gone_below_a_reachable_save_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let save_path = dir.path().to_string_lossy().to_string();
+        let present = dir.path().join("Present.Movie");
+        fs::create_dir(&present).unwrap();
+        let gone = dir.path().join("Gone.Movie").to_string_lossy().to_string();
+        let torrents = [torrent(&save_path, &present.to_string_lossy()), torrent(&save_path, &gone)];
+
+        assert_eq!(missing_data(&torrents, &[]).unwrap(), vec![(1, gone)]);
+    }
+
+    #[test]
+    fn missing_data_skips_torrents_whose_save_path_is_unreachable() {
+        let dir = tempfile::tempdir().unwrap();
+        let save_path = dir.path().to_string_lossy().to_string();
+        let torrents = [
+            torrent(&save_path, &dir.path().join("Gone.Movie").to_string_lossy()),
+            torrent("/nonexistent/seed-tools/data", "/nonexistent/seed-tools/data/Other.Movie"),
+        ];
+
+        assert_eq!(missing_data(&torrents, &[]).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn missing_data_fails_when_no_save_path_is_reachable() {
+        // A Docker client without path_mappings: every torrent looks gone from here
+        let torrents = [
+            torrent("/data", "/data/Some.Movie"),
+            torrent("/data/tv", "/data/tv/Some.Show.S01"),
+        ];
+
+        assert!(missing_data(&torrents, &[]).is_err());
+    }
+
+    #[test]
+    fn missing_data_follows_path_mappings() {
+        let dir = tempfile::tempdir().unwrap();
+        let mappings = [PathMapping { local: dir.path().to_string_lossy().to_string(), remote: "/data".to_string() }];
+        let torrents = [torrent("/data", "/data/Gone.Movie")];
+
+        let missing = missing_data(&torrents, &mappings).unwrap();
+        assert_eq!(missing, vec![(0, dir.path().join("Gone.Movie").to_string_lossy().to_string())]);
+    }
+}
@@ -0,0 +1,76 @@
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Once;
+use crossterm::{
+    cursor::Show,
+    event::DisableMouseCapture,
+    execute,
+    terminal::{disable_raw_mode, LeaveAlternateScreen},
+};
+use log::{error, warn};
+
+static INSTALL_GUARD: Once = Once::new();
+static TERMINAL_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Restores the terminal to cooked mode, leaves the alternate screen and shows the cursor.
+/// Safe to call multiple times; errors are ignored because we may be tearing down.
+pub fn restore_terminal_state() {
+    let _ = disable_raw_mode();
+    let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture, Show);
+}
+
+/// Installs the process-wide panic hook and SIGINT/SIGTERM/SIGHUP handler used by the
+/// UI and IRC screens. Only the first call has any effect.
+pub fn install_terminal_guard() {
+    INSTALL_GUARD.call_once(|| {
+        let original_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |panic_info| {
+            let was_active = TERMINAL_ACTIVE.swap(false, Ordering::SeqCst);
+            if was_active {
+                restore_terminal_state();
+            }
+            original_hook(panic_info);
+            // A panic in a worker thread would otherwise leave the main loop drawing into a
+            // terminal that is no longer in raw mode, so bail out entirely.
+            if was_active {
+                std::process::exit(101);
+            }
+        }));
+
+        if let Err(e) = ctrlc::set_handler(|| {
+            warn!("Received termination signal. Restoring terminal and exiting.");
+            if TERMINAL_ACTIVE.swap(false, Ordering::SeqCst) {
+                restore_terminal_state();
+            }
+            std::process::exit(130);
+        }) {
+            error!("Failed to install signal handler: {}", e);
+        }
+    });
+}
+
+/// RAII guard marking the terminal as taken over by a TUI screen.
+/// Dropping the guard (including on early `?` returns) restores the terminal.
+pub struct TerminalGuard;
+
+impl TerminalGuard {
+    pub fn new() -> Self {
+        install_terminal_guard();
+        TERMINAL_ACTIVE.store(true, Ordering::SeqCst);
+        TerminalGuard
+    }
+}
+
+impl Default for TerminalGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        if TERMINAL_ACTIVE.swap(false, Ordering::SeqCst) {
+            restore_terminal_state();
+        }
+    }
+}
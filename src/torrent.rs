@@ -0,0 +1,1159 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use bendy::decoding::{Decoder, Object};
+use regex::Regex;
+use serde::Serialize;
+use sha1::{Digest, Sha1};
+use sha2::Sha256;
+use walkdir::WalkDir;
+use crate::types::{ExclusionsConfig, TorrentOptions, TorrentVersion};
+
+/// A decoded bencode value. Dictionaries keep their keys sorted, as the format requires.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Bencode {
+    Int(i64),
+    Bytes(Vec<u8>),
+    List(Vec<Bencode>),
+    Dict(BTreeMap<Vec<u8>, Bencode>),
+}
+
+impl Bencode {
+    pub fn decode(data: &[u8]) -> Result<Self, String> {
+        let mut decoder = Decoder::new(data);
+        match decoder.next_object() {
+            Ok(Some(object)) => from_object(object),
+            Ok(None) => Err("Empty bencode data".to_string()),
+            Err(e) => Err(format!("Invalid bencode data: {}", e)),
+        }
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.encode_into(&mut out);
+        out
+    }
+
+    fn encode_into(&self, out: &mut Vec<u8>) {
+        match self {
+            Bencode::Int(value) => out.extend_from_slice(format!("i{}e", value).as_bytes()),
+            Bencode::Bytes(bytes) => {
+                out.extend_from_slice(format!("{}:", bytes.len()).as_bytes());
+                out.extend_from_slice(bytes);
+            }
+            Bencode::List(items) => {
+                out.push(b'l');
+                for item in items {
+                    item.encode_into(out);
+                }
+                out.push(b'e');
+            }
+            Bencode::Dict(entries) => {
+                out.push(b'd');
+                for (key, value) in entries {
+                    Bencode::Bytes(key.clone()).encode_into(out);
+                    value.encode_into(out);
+                }
+                out.push(b'e');
+            }
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Bencode> {
+        match self {
+            Bencode::Dict(entries) => entries.get(key.as_bytes()),
+            _ => None,
+        }
+    }
+
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            Bencode::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Bencode::Bytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<String> {
+        self.as_bytes().map(|bytes| String::from_utf8_lossy(bytes).to_string())
+    }
+
+    pub fn as_list(&self) -> Option<&[Bencode]> {
+        match self {
+            Bencode::List(items) => Some(items),
+            _ => None,
+        }
+    }
+}
+
+fn from_object(object: Object) -> Result<Bencode, String> {
+    match object {
+        Object::Integer(value) => value
+            .parse()
+            .map(Bencode::Int)
+            .map_err(|e| format!("Invalid bencode integer '{}': {}", value, e)),
+        Object::Bytes(bytes) => Ok(Bencode::Bytes(bytes.to_vec())),
+        Object::List(mut list) => {
+            let mut items = Vec::new();
+            while let Some(item) = list.next_object().map_err(|e| format!("Invalid bencode list: {}", e))? {
+                items.push(from_object(item)?);
+            }
+            Ok(Bencode::List(items))
+        }
+        Object::Dict(mut dict) => {
+            let mut entries = BTreeMap::new();
+            while let Some((key, value)) = dict.next_pair().map_err(|e| format!("Invalid bencode dictionary: {}", e))? {
+                entries.insert(key.to_vec(), from_object(value)?);
+            }
+            Ok(Bencode::Dict(entries))
+        }
+    }
+}
+
+/// The info dictionary of a torrent exactly as stored, which is what the infohashes are taken over.
+pub fn raw_info(torrent: &[u8]) -> Result<&[u8], String> {
+    let mut decoder = Decoder::new(torrent);
+    let Ok(Some(Object::Dict(mut dict))) = decoder.next_object() else {
+        return Err("Torrent is not a bencoded dictionary".to_string());
+    };
+    while let Some((key, value)) = dict.next_pair().map_err(|e| format!("Invalid torrent: {}", e))? {
+        if key == b"info" {
+            let Object::Dict(info) = value else {
+                return Err("Torrent info is not a dictionary".to_string());
+            };
+            return info.into_raw().map_err(|e| format!("Invalid torrent info: {}", e));
+        }
+    }
+    Err("Torrent has no info dictionary".to_string())
+}
+
+/// Points a torrent at another tracker: replaces the announce URLs (one tier per URL) and the
+/// source tag and marks it private. The pieces stay as they are, so the data is not hashed
+/// again, but the infohash changes with the source tag as trackers expect.
+pub fn retag(torrent: &[u8], announce_urls: &[String], source: &str) -> Result<Vec<u8>, String> {
+    let Bencode::Dict(mut root) = Bencode::decode(torrent)? else {
+        return Err("Torrent is not a bencoded dictionary".to_string());
+    };
+    let Some(Bencode::Dict(info)) = root.get_mut(b"info".as_slice()) else {
+        return Err("Torrent has no info dictionary".to_string());
+    };
+    info.insert(b"source".to_vec(), Bencode::Bytes(source.as_bytes().to_vec()));
+    info.insert(b"private".to_vec(), Bencode::Int(1));
+
+    let first = announce_urls.first().ok_or("No announce URL to retag the torrent with")?;
+    root.insert(b"announce".to_vec(), Bencode::Bytes(first.as_bytes().to_vec()));
+    if announce_urls.len() > 1 {
+        let tiers = announce_urls
+            .iter()
+            .map(|url| Bencode::List(vec![Bencode::Bytes(url.as_bytes().to_vec())]))
+            .collect();
+        root.insert(b"announce-list".to_vec(), Bencode::List(tiers));
+    } else {
+        root.remove(b"announce-list".as_slice());
+    }
+    Ok(Bencode::Dict(root).encode())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[derive(Serialize, Debug)]
+pub struct TorrentFile {
+    pub path: String,
+    pub size: u64,
+}
+
+/// What `seed-tools inspect` shows about a .torrent file.
+#[derive(Serialize, Debug)]
+pub struct TorrentSummary {
+    pub name: String,
+    pub info_hash_v1: Option<String>, // Missing for v2-only torrents
+    pub info_hash_v2: Option<String>, // Only for v2 and hybrid torrents
+    pub piece_size: u64,
+    pub total_size: u64,
+    pub files: Vec<TorrentFile>,
+    pub announce_urls: Vec<String>,
+    pub source: Option<String>,
+    pub private: bool,
+    pub created_by: Option<String>,
+    pub creation_date: Option<String>, // RFC 3339, UTC
+    pub comment: Option<String>,
+}
+
+/// Parses a .torrent file into the details `seed-tools inspect` prints.
+pub fn inspect(torrent: &[u8]) -> Result<TorrentSummary, String> {
+    let root = Bencode::decode(torrent)?;
+    let raw = raw_info(torrent)?;
+    let info = root.get("info").ok_or("Torrent has no info dictionary")?;
+
+    let is_v2 = info.get("meta version").and_then(Bencode::as_int) == Some(2);
+    let info_hash_v1 = info.get("pieces").map(|_| hex(&Sha1::digest(raw)));
+    let info_hash_v2 = is_v2.then(|| hex(&Sha256::digest(raw)));
+
+    let name = info.get("name").and_then(Bencode::as_str).unwrap_or_default();
+    let mut files = Vec::new();
+    if let Some(entries) = info.get("files").and_then(Bencode::as_list) {
+        for entry in entries {
+            // Hybrid torrents pad files to piece boundaries; those are not part of the content
+            let padding = entry.get("attr").and_then(Bencode::as_str).is_some_and(|attr| attr.contains('p'));
+            if padding {
+                continue;
+            }
+            let parts: Vec<String> = entry
+                .get("path")
+                .and_then(Bencode::as_list)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(Bencode::as_str)
+                .collect();
+            files.push(TorrentFile {
+                path: parts.join("/"),
+                size: entry.get("length").and_then(Bencode::as_int).unwrap_or(0) as u64,
+            });
+        }
+    } else if let Some(length) = info.get("length").and_then(Bencode::as_int) {
+        files.push(TorrentFile { path: name.clone(), size: length as u64 });
+    } else if let Some(tree) = info.get("file tree") {
+        collect_file_tree(tree, &mut Vec::new(), &mut files);
+    }
+    let total_size = files.iter().map(|file| file.size).sum();
+
+    // `announce-list` holds tiers of URLs; clients use it instead of `announce` when present
+    let mut announce_urls: Vec<String> = root.get("announce").and_then(Bencode::as_str).into_iter().collect();
+    for tier in root.get("announce-list").and_then(Bencode::as_list).unwrap_or_default() {
+        for url in tier.as_list().unwrap_or_default().iter().filter_map(Bencode::as_str) {
+            if !announce_urls.contains(&url) {
+                announce_urls.push(url);
+            }
+        }
+    }
+
+    Ok(TorrentSummary {
+        name,
+        info_hash_v1,
+        info_hash_v2,
+        piece_size: info.get("piece length").and_then(Bencode::as_int).unwrap_or(0) as u64,
+        total_size,
+        files,
+        announce_urls,
+        source: info.get("source").and_then(Bencode::as_str),
+        private: info.get("private").and_then(Bencode::as_int) == Some(1),
+        created_by: root.get("created by").and_then(Bencode::as_str),
+        creation_date: root
+            .get("creation date")
+            .and_then(Bencode::as_int)
+            .and_then(|timestamp| chrono::DateTime::from_timestamp(timestamp, 0))
+            .map(|time| time.to_rfc3339()),
+        comment: root.get("comment").and_then(Bencode::as_str),
+    })
+}
+
+/// Walks a v2 `file tree`, where each file is a dictionary with an empty key holding its length.
+fn collect_file_tree(node: &Bencode, path: &mut Vec<String>, files: &mut Vec<TorrentFile>) {
+    let Bencode::Dict(entries) = node else { return };
+    for (key, child) in entries {
+        if key.is_empty() {
+            files.push(TorrentFile {
+                path: path.join("/"),
+                size: child.get("length").and_then(Bencode::as_int).unwrap_or(0) as u64,
+            });
+        } else {
+            path.push(String::from_utf8_lossy(key).to_string());
+            collect_file_tree(child, path, files);
+            path.pop();
+        }
+    }
+}
+
+/// Compiled `exclusions`: the files left out of torrents when `stripshit_from_videos` is set.
+pub struct Exclusions {
+    keywords: Vec<String>,
+    globs: Vec<(String, glob::Pattern)>,
+    patterns: Vec<Regex>,
+}
+
+impl Exclusions {
+    pub fn new(config: &ExclusionsConfig) -> Result<Self, String> {
+        let globs = config
+            .globs
+            .iter()
+            .map(|glob| {
+                glob::Pattern::new(glob)
+                    .map(|pattern| (glob.clone(), pattern))
+                    .map_err(|e| format!("Invalid exclusion glob '{}': {}", glob, e))
+            })
+            .collect::<Result<_, _>>()?;
+        let patterns = config
+            .patterns
+            .iter()
+            .map(|pattern| Regex::new(pattern).map_err(|e| format!("Invalid exclusion pattern '{}': {}", pattern, e)))
+            .collect::<Result<_, _>>()?;
+        Ok(Exclusions {
+            keywords: config.keywords.iter().map(|keyword| keyword.to_lowercase()).collect(),
+            globs,
+            patterns,
+        })
+    }
+
+    /// The rule that leaves the file at `relative_path` (inside the release) out, or `None`.
+    pub fn reason(&self, relative_path: &Path) -> Option<String> {
+        let name = relative_path.file_name().unwrap_or_default().to_string_lossy();
+        let lower_name = name.to_lowercase();
+        if let Some(keyword) = self.keywords.iter().find(|keyword| lower_name.contains(keyword.as_str())) {
+            return Some(format!("keyword '{}'", keyword));
+        }
+        let options = glob::MatchOptions { case_sensitive: false, ..Default::default() };
+        if let Some((glob, _)) = self.globs.iter().find(|(_, pattern)| pattern.matches_with(&name, options)) {
+            return Some(format!("glob '{}'", glob));
+        }
+        let path = relative_path.to_string_lossy().replace('\\', "/");
+        self.patterns
+            .iter()
+            .find(|pattern| pattern.is_match(&path))
+            .map(|pattern| format!("pattern '{}'", pattern.as_str()))
+    }
+}
+
+/// Files below `input_path` that `exclusions` leave out, relative to it, each logged with its reason.
+pub fn excluded_files(input_path: &Path, exclusions: &Exclusions) -> Vec<PathBuf> {
+    WalkDir::new(input_path)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| {
+            let relative = entry.path().strip_prefix(input_path).unwrap_or(entry.path()).to_path_buf();
+            let relative = if relative.as_os_str().is_empty() { PathBuf::from(entry.file_name()) } else { relative };
+            let reason = exclusions.reason(&relative)?;
+            log::info!("Excluding '{}' from the torrent ({})", relative.display(), reason);
+            Some(relative)
+        })
+        .collect()
+}
+
+/// An mkbrr `--exclude` list naming exactly `files`. mkbrr matches on file names and splits the
+/// list on commas, so glob characters are escaped and commas matched by `?`.
+pub fn mkbrr_exclude_list(files: &[PathBuf]) -> String {
+    let mut names: Vec<String> = files
+        .iter()
+        .map(|file| {
+            file.file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .chars()
+                .map(|c| match c {
+                    '*' | '?' | '[' => format!("[{}]", c),
+                    ',' | '\\' => "?".to_string(),
+                    c => c.to_string(),
+                })
+                .collect()
+        })
+        .collect();
+    names.sort();
+    names.dedup();
+    names.join(",")
+}
+
+const BLOCK_SIZE: usize = 16 * 1024; // v2 merkle tree leaves
+
+/// Piece length for `total_size` bytes under `options`, or `None` to let mkbrr choose.
+/// `auto` aims for roughly 750-1500 pieces, between 64 KiB and 16 MiB.
+pub fn piece_length(options: &TorrentOptions, total_size: u64) -> Result<Option<u64>, String> {
+    let Some(setting) = options.piece_size.as_deref().map(str::trim) else { return Ok(None) };
+    if setting.eq_ignore_ascii_case("auto") {
+        return Ok(Some(auto_piece_length(total_size)));
+    }
+    let digits = setting.find(|c: char| !c.is_ascii_digit()).unwrap_or(setting.len());
+    let (number, unit) = setting.split_at(digits);
+    let number: u64 = number.parse().map_err(|_| format!("Invalid piece_size '{}'", setting))?;
+    let multiplier = match unit.trim().to_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" | "kib" => 1 << 10,
+        "m" | "mb" | "mib" => 1 << 20,
+        _ => return Err(format!("Invalid piece_size unit in '{}' (use KiB or MiB)", setting)),
+    };
+    let length = number * multiplier;
+    // mkbrr takes the piece length as a power of two between 2^16 and 2^27
+    if !length.is_power_of_two() || !(1 << 16..=1 << 27).contains(&length) {
+        return Err(format!("piece_size '{}' must be a power of two between 64 KiB and 128 MiB", setting));
+    }
+    Ok(Some(length))
+}
+
+fn auto_piece_length(total_size: u64) -> u64 {
+    (total_size / 1500).max(1).next_power_of_two().clamp(1 << 16, 1 << 24)
+}
+
+/// Files of a torrent in the order they are hashed: relative path parts and absolute path.
+pub fn content_files(input_path: &Path, exclusions: Option<&Exclusions>) -> Result<Vec<(Vec<String>, PathBuf)>, String> {
+    let mut files = Vec::new();
+    for entry in WalkDir::new(input_path).sort_by_file_name() {
+        let entry = entry.map_err(|e| format!("Failed to read '{}': {}", input_path.display(), e))?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        if let Some(exclusions) = exclusions {
+            let relative = entry.path().strip_prefix(input_path).unwrap_or(entry.path());
+            let relative = if relative.as_os_str().is_empty() { Path::new(entry.file_name()) } else { relative };
+            if let Some(reason) = exclusions.reason(relative) {
+                log::info!("Excluding '{}' from the torrent ({})", relative.display(), reason);
+                continue;
+            }
+        }
+        let parts = entry
+            .path()
+            .strip_prefix(input_path)
+            .unwrap_or(entry.path())
+            .components()
+            .map(|part| part.as_os_str().to_string_lossy().to_string())
+            .collect();
+        files.push((parts, entry.path().to_path_buf()));
+    }
+    if files.is_empty() {
+        return Err(format!("No files to put in a torrent in '{}'", input_path.display()));
+    }
+    Ok(files)
+}
+
+/// SHA-1 pieces of the v1 part, fed with the content in order.
+struct PieceHasher {
+    piece_length: u64,
+    hasher: Sha1,
+    filled: u64,
+    pieces: Vec<u8>,
+}
+
+impl PieceHasher {
+    fn new(piece_length: u64) -> Self {
+        PieceHasher { piece_length, hasher: Sha1::new(), filled: 0, pieces: Vec::new() }
+    }
+
+    fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            let take = ((self.piece_length - self.filled) as usize).min(data.len());
+            self.hasher.update(&data[..take]);
+            self.filled += take as u64;
+            data = &data[take..];
+            if self.filled == self.piece_length {
+                self.pieces.extend_from_slice(&std::mem::take(&mut self.hasher).finalize());
+                self.filled = 0;
+            }
+        }
+    }
+
+    /// Zero bytes up to the next piece boundary, as hybrid torrents need between files.
+    fn pad(&mut self) -> u64 {
+        if self.filled == 0 {
+            return 0;
+        }
+        let padding = self.piece_length - self.filled;
+        self.update(&vec![0; padding as usize]);
+        padding
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.filled > 0 {
+            self.pieces.extend_from_slice(&self.hasher.finalize());
+        }
+        self.pieces
+    }
+}
+
+fn merkle_root(mut layer: Vec<[u8; 32]>) -> [u8; 32] {
+    while layer.len() > 1 {
+        layer = layer
+            .chunks(2)
+            .map(|pair| {
+                let mut hasher = Sha256::new();
+                hasher.update(pair[0]);
+                hasher.update(pair.get(1).copied().unwrap_or([0; 32]));
+                hasher.finalize().into()
+            })
+            .collect();
+    }
+    layer.first().copied().unwrap_or([0; 32])
+}
+
+/// Pieces root and, for files longer than a piece, the piece layer of one file's v2 merkle tree.
+/// Leaves past the end of the file are zero, per BEP 52.
+fn file_merkle(mut leaves: Vec<[u8; 32]>, file_size: u64, piece_length: u64) -> ([u8; 32], Option<Vec<u8>>) {
+    leaves.resize(leaves.len().next_power_of_two(), [0; 32]);
+    if file_size <= piece_length {
+        return (merkle_root(leaves), None);
+    }
+    let blocks_per_piece = (piece_length as usize) / BLOCK_SIZE;
+    let piece_count = file_size.div_ceil(piece_length) as usize;
+    let layer: Vec<[u8; 32]> = leaves.chunks(blocks_per_piece).map(|chunk| merkle_root(chunk.to_vec())).collect();
+    let piece_layer = layer[..piece_count].iter().flatten().copied().collect();
+    (merkle_root(layer), Some(piece_layer))
+}
+
+fn bytes(text: &str) -> Bencode {
+    Bencode::Bytes(text.as_bytes().to_vec())
+}
+
+fn dict(entries: Vec<(&str, Bencode)>) -> Bencode {
+    Bencode::Dict(entries.into_iter().map(|(key, value)| (key.as_bytes().to_vec(), value)).collect())
+}
+
+/// Creates a v2 or hybrid (v1 + v2) torrent of `input_path` in `torrent_file`, for trackers that
+/// accept them; mkbrr only writes v1 torrents. Files are read once for both hash trees.
+pub fn build(
+    input_path: &Path,
+    torrent_file: &Path,
+    announce_url: &str,
+    source: &str,
+    exclusions: Option<&Exclusions>,
+    options: &TorrentOptions,
+) -> Result<(), String> {
+    let hybrid = options.version == TorrentVersion::Hybrid;
+    let files = content_files(input_path, exclusions)?;
+    let total_size = files
+        .iter()
+        .map(|(_, path)| std::fs::metadata(path).map(|metadata| metadata.len()).unwrap_or(0))
+        .sum();
+    let piece_length = piece_length(options, total_size)?.unwrap_or_else(|| auto_piece_length(total_size));
+    let name = input_path.file_name().unwrap_or_default().to_string_lossy().to_string();
+    let single_file = input_path.is_file();
+
+    let mut v1 = PieceHasher::new(piece_length);
+    let mut v1_files = Vec::new();
+    let mut file_tree = BTreeMap::new();
+    let mut piece_layers = BTreeMap::new();
+    let mut buffer = vec![0; BLOCK_SIZE];
+    for (index, (parts, path)) in files.iter().enumerate() {
+        let mut file = File::open(path).map_err(|e| format!("Failed to open '{}': {}", path.display(), e))?;
+        let mut leaves = Vec::new();
+        let mut size = 0u64;
+        loop {
+            // Fill whole blocks so every leaf but the last covers exactly 16 KiB
+            let mut filled = 0;
+            while filled < BLOCK_SIZE {
+                let read = file
+                    .read(&mut buffer[filled..])
+                    .map_err(|e| format!("Failed to read '{}': {}", path.display(), e))?;
+                if read == 0 {
+                    break;
+                }
+                filled += read;
+            }
+            if filled == 0 {
+                break;
+            }
+            let block = &buffer[..filled];
+            leaves.push(Sha256::digest(block).into());
+            if hybrid {
+                v1.update(block);
+            }
+            size += filled as u64;
+        }
+
+        let mut entry = vec![("length", Bencode::Int(size as i64))];
+        if size > 0 {
+            let (root, layer) = file_merkle(leaves, size, piece_length);
+            if let Some(layer) = layer {
+                piece_layers.insert(root.to_vec(), Bencode::Bytes(layer));
+            }
+            entry.push(("pieces root", Bencode::Bytes(root.to_vec())));
+        }
+        insert_file(&mut file_tree, if single_file { std::slice::from_ref(&name) } else { parts.as_slice() }, dict(entry));
+
+        if hybrid {
+            v1_files.push(dict(vec![
+                ("length", Bencode::Int(size as i64)),
+                ("path", Bencode::List(parts.iter().map(|part| bytes(part)).collect())),
+            ]));
+            // Every file starts on a piece boundary so the v1 and v2 pieces line up
+            if index + 1 < files.len() {
+                let padding = v1.pad();
+                if padding > 0 {
+                    v1_files.push(dict(vec![
+                        ("attr", bytes("p")),
+                        ("length", Bencode::Int(padding as i64)),
+                        ("path", Bencode::List(vec![bytes(".pad"), bytes(&padding.to_string())])),
+                    ]));
+                }
+            }
+        }
+    }
+
+    let mut info = vec![
+        ("file tree", Bencode::Dict(file_tree)),
+        ("meta version", Bencode::Int(2)),
+        ("name", bytes(&name)),
+        ("piece length", Bencode::Int(piece_length as i64)),
+        ("private", Bencode::Int(1)),
+        ("source", bytes(source)),
+    ];
+    if hybrid {
+        match (single_file, v1_files.first()) {
+            (true, Some(file)) => info.push(("length", file.get("length").cloned().unwrap_or(Bencode::Int(0)))),
+            _ => info.push(("files", Bencode::List(v1_files))),
+        }
+        info.push(("pieces", Bencode::Bytes(v1.finish())));
+    }
+    let torrent = dict(vec![
+        ("announce", bytes(announce_url)),
+        ("created by", bytes("seed-tools")),
+        ("creation date", Bencode::Int(chrono::Utc::now().timestamp())),
+        ("info", dict(info)),
+        ("piece layers", Bencode::Dict(piece_layers)),
+    ]);
+    std::fs::write(torrent_file, torrent.encode())
+        .map_err(|e| format!("Failed to write '{}': {}", torrent_file.display(), e))
+}
+
+/// Adds a file entry below `parts` in a v2 file tree.
+fn insert_file(tree: &mut BTreeMap<Vec<u8>, Bencode>, parts: &[String], entry: Bencode) {
+    let Some((first, rest)) = parts.split_first() else { return };
+    let node = tree.entry(first.as_bytes().to_vec()).or_insert_with(|| Bencode::Dict(BTreeMap::new()));
+    let Bencode::Dict(children) = node else { return };
+    if rest.is_empty() {
+        children.insert(Vec::new(), entry);
+    } else {
+        insert_file(children, rest, entry);
+    }
+}
+
+/// How much of a torrent's content a local path holds.
+#[derive(Serialize, Debug)]
+pub struct VerifyReport {
+    pub content_path: String,
+    pub pieces: usize,       // Files for v2-only torrents, which are checked file by file
+    pub valid_pieces: usize,
+    pub percent: f64,
+    pub missing_files: Vec<String>,
+    pub mismatched_files: Vec<String>,
+}
+
+impl VerifyReport {
+    pub fn is_complete(&self) -> bool {
+        self.pieces == self.valid_pieces && self.missing_files.is_empty() && self.mismatched_files.is_empty()
+    }
+}
+
+/// The torrent's content below `path`: `path` itself, or `path/<name>` when `path` is the
+/// folder the content is saved in.
+fn content_root(path: &Path, name: &str) -> PathBuf {
+    let nested = path.join(name);
+    if path.file_name().is_none_or(|file_name| file_name != name) && nested.exists() {
+        nested
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// Compares piece hashes with the data as it is read, remembering which files each piece covers.
+struct PieceChecker<'a> {
+    piece_length: u64,
+    expected: &'a [u8],
+    hasher: Sha1,
+    filled: u64,
+    piece: usize,
+    damaged: bool, // Part of the current piece is missing
+    touched: Vec<usize>,
+    valid: usize,
+    bad_files: BTreeSet<usize>,
+}
+
+impl PieceChecker<'_> {
+    fn touch(&mut self, file: Option<usize>) {
+        if let Some(file) = file
+            && self.touched.last() != Some(&file)
+        {
+            self.touched.push(file);
+        }
+    }
+
+    fn feed(&mut self, mut data: &[u8], file: Option<usize>) {
+        while !data.is_empty() {
+            let take = ((self.piece_length - self.filled) as usize).min(data.len());
+            self.hasher.update(&data[..take]);
+            self.filled += take as u64;
+            self.touch(file);
+            data = &data[take..];
+            if self.filled == self.piece_length {
+                self.end_piece();
+            }
+        }
+    }
+
+    /// Accounts for `length` bytes that are not there, failing every piece they belong to.
+    fn skip(&mut self, mut length: u64, file: Option<usize>) {
+        while length > 0 {
+            let take = (self.piece_length - self.filled).min(length);
+            self.damaged = true;
+            self.filled += take;
+            self.touch(file);
+            length -= take;
+            if self.filled == self.piece_length {
+                self.end_piece();
+            }
+        }
+    }
+
+    fn end_piece(&mut self) {
+        let digest = std::mem::take(&mut self.hasher).finalize();
+        let expected = self.expected.get(self.piece * 20..self.piece * 20 + 20);
+        if !self.damaged && expected == Some(digest.as_slice()) {
+            self.valid += 1;
+        } else {
+            self.bad_files.extend(self.touched.drain(..));
+        }
+        self.touched.clear();
+        self.piece += 1;
+        self.filled = 0;
+        self.damaged = false;
+    }
+}
+
+/// Hashes the local data at `path` against the pieces of `torrent` and reports what matches.
+/// `path` is the content itself or the folder it is saved in.
+pub fn verify(torrent: &[u8], path: &Path) -> Result<VerifyReport, String> {
+    let root = Bencode::decode(torrent)?;
+    let info = root.get("info").ok_or("Torrent has no info dictionary")?;
+    let name = info.get("name").and_then(Bencode::as_str).unwrap_or_default();
+    let content = content_root(path, &name);
+    let Some(pieces) = info.get("pieces").and_then(Bencode::as_bytes) else {
+        return verify_v2(info, &content);
+    };
+    let piece_length = info
+        .get("piece length")
+        .and_then(Bencode::as_int)
+        .filter(|length| *length > 0)
+        .ok_or("Torrent has no piece length")? as u64;
+
+    // (relative path, size, padding) in piece order
+    let files: Vec<(String, u64, bool)> = match info.get("files").and_then(Bencode::as_list) {
+        Some(entries) => entries
+            .iter()
+            .map(|entry| {
+                let parts: Vec<String> = entry
+                    .get("path")
+                    .and_then(Bencode::as_list)
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(Bencode::as_str)
+                    .collect();
+                let padding = entry.get("attr").and_then(Bencode::as_str).is_some_and(|attr| attr.contains('p'));
+                (parts.join("/"), entry.get("length").and_then(Bencode::as_int).unwrap_or(0) as u64, padding)
+            })
+            .collect(),
+        None => vec![(String::new(), info.get("length").and_then(Bencode::as_int).unwrap_or(0) as u64, false)],
+    };
+
+    let mut checker = PieceChecker {
+        piece_length,
+        expected: pieces,
+        hasher: Sha1::new(),
+        filled: 0,
+        piece: 0,
+        damaged: false,
+        touched: Vec::new(),
+        valid: 0,
+        bad_files: BTreeSet::new(),
+    };
+    let mut missing_files = Vec::new();
+    let mut buffer = vec![0; 1 << 20];
+    for (index, (relative, size, padding)) in files.iter().enumerate() {
+        if *padding {
+            checker.feed(&vec![0; *size as usize], None);
+            continue;
+        }
+        let file_path = if relative.is_empty() { content.clone() } else { content.join(relative) };
+        let Ok(file) = File::open(&file_path) else {
+            missing_files.push(if relative.is_empty() { name.clone() } else { relative.clone() });
+            checker.skip(*size, Some(index));
+            continue;
+        };
+        let mut reader = file.take(*size);
+        let mut read_total = 0u64;
+        loop {
+            let read = reader
+                .read(&mut buffer)
+                .map_err(|e| format!("Failed to read '{}': {}", file_path.display(), e))?;
+            if read == 0 {
+                break;
+            }
+            checker.feed(&buffer[..read], Some(index));
+            read_total += read as u64;
+        }
+        // A short file fails the pieces of its missing tail
+        checker.skip(size - read_total, Some(index));
+    }
+    if checker.filled > 0 {
+        checker.end_piece();
+    }
+
+    let piece_count = pieces.len() / 20;
+    let mismatched_files = checker
+        .bad_files
+        .iter()
+        .map(|&index| if files[index].0.is_empty() { name.clone() } else { files[index].0.clone() })
+        .filter(|file| !missing_files.contains(file))
+        .collect();
+    Ok(VerifyReport {
+        content_path: content.to_string_lossy().to_string(),
+        pieces: piece_count,
+        valid_pieces: checker.valid,
+        percent: if piece_count == 0 { 100.0 } else { checker.valid as f64 * 100.0 / piece_count as f64 },
+        missing_files,
+        mismatched_files,
+    })
+}
+
+/// Fails unless the data at `path` fully matches `torrent`. Run before adding a torrent with
+/// `skip_checking`, since a client would otherwise seed whatever is on disk as complete.
+pub fn verify_before_inject(torrent: &[u8], path: &Path) -> Result<(), String> {
+    let report = verify(torrent, path)?;
+    if report.is_complete() {
+        log::info!("Verified local data at '{}' against the torrent.", report.content_path);
+        return Ok(());
+    }
+    let mut problems = report.missing_files.iter().map(|file| format!("{} (missing)", file)).collect::<Vec<_>>();
+    problems.extend(report.mismatched_files.iter().cloned());
+    Err(format!(
+        "Local data at '{}' matches only {:.1}% of the torrent, not injecting it: {}",
+        report.content_path,
+        report.percent,
+        problems.join(", ")
+    ))
+}
+
+/// v2-only torrents have no flat piece list; every file is checked against its pieces root.
+fn verify_v2(info: &Bencode, content: &Path) -> Result<VerifyReport, String> {
+    let piece_length = info.get("piece length").and_then(Bencode::as_int).unwrap_or(0) as u64;
+    let mut files = Vec::new();
+    collect_file_tree(info.get("file tree").ok_or("Torrent has neither pieces nor a file tree")?, &mut Vec::new(), &mut files);
+
+    let (mut valid, mut missing_files, mut mismatched_files) = (0, Vec::new(), Vec::new());
+    for file in &files {
+        let expected_root = lookup_pieces_root(info, &file.path);
+        // Single-file torrents list the file under its own name, which is the content itself
+        let file_path = if content.is_file() { content.to_path_buf() } else { content.join(&file.path) };
+        let Ok(mut reader) = File::open(&file_path).map(|file_handle| file_handle.take(file.size)) else {
+            missing_files.push(file.path.clone());
+            continue;
+        };
+        let mut leaves = Vec::new();
+        let mut size = 0u64;
+        let mut block = vec![0; BLOCK_SIZE];
+        loop {
+            let mut filled = 0;
+            while filled < BLOCK_SIZE {
+                let read = reader
+                    .read(&mut block[filled..])
+                    .map_err(|e| format!("Failed to read '{}': {}", file_path.display(), e))?;
+                if read == 0 {
+                    break;
+                }
+                filled += read;
+            }
+            if filled == 0 {
+                break;
+            }
+            leaves.push(Sha256::digest(&block[..filled]).into());
+            size += filled as u64;
+        }
+        let matches = size == file.size
+            && (size == 0 || Some(file_merkle(leaves, size, piece_length).0.to_vec()) == expected_root);
+        if matches {
+            valid += 1;
+        } else {
+            mismatched_files.push(file.path.clone());
+        }
+    }
+    Ok(VerifyReport {
+        content_path: content.to_string_lossy().to_string(),
+        pieces: files.len(),
+        valid_pieces: valid,
+        percent: if files.is_empty() { 100.0 } else { valid as f64 * 100.0 / files.len() as f64 },
+        missing_files,
+        mismatched_files,
+    })
+}
+
+fn lookup_pieces_root(info: &Bencode, path: &str) -> Option<Vec<u8>> {
+    let mut node = info.get("file tree")?;
+    for part in path.split('/') {
+        node = node.get(part)?;
+    }
+    node.get("")?.get("pieces root")?.as_bytes().map(<[u8]>::to_vec)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Content that differs from piece to piece, so misplaced hashes do not match by accident.
+    fn content(size: usize) -> Vec<u8> {
+        (0..size).map(|i| (i * 7 % 251) as u8).collect()
+    }
+
+    fn options(version: TorrentVersion, piece_size: &str) -> TorrentOptions {
+        TorrentOptions { piece_size: Some(piece_size.to_string()), version, exclusions: None }
+    }
+
+    fn build_torrent(input_path: &Path, options: &TorrentOptions) -> Vec<u8> {
+        let torrent_file = input_path.with_extension("torrent");
+        build(input_path, &torrent_file, "https://tracker.example/announce", "EXAMPLE", None, options).unwrap();
+        std::fs::read(torrent_file).unwrap()
+    }
+
+    #[test]
+    fn parses_piece_sizes() {
+        assert_eq!(piece_length(&TorrentOptions::default(), 1 << 30), Ok(None));
+        assert_eq!(piece_length(&options(TorrentVersion::V1, "4MiB"), 0), Ok(Some(4 << 20)));
+        assert_eq!(piece_length(&options(TorrentVersion::V1, "512 KiB"), 0), Ok(Some(512 << 10)));
+        assert_eq!(piece_length(&options(TorrentVersion::V1, "auto"), 8 << 30), Ok(Some(8 << 20)));
+        assert_eq!(piece_length(&options(TorrentVersion::V1, "auto"), 1), Ok(Some(1 << 16)));
+        assert!(piece_length(&options(TorrentVersion::V1, "3MiB"), 0).is_err());
+        assert!(piece_length(&options(TorrentVersion::V1, "32KiB"), 0).is_err());
+        assert!(piece_length(&options(TorrentVersion::V1, "4GB"), 0).is_err());
+    }
+
+    #[test]
+    fn builds_hybrid_torrents_with_aligned_v1_and_v2_hashes() {
+        let dir = tempfile::tempdir().unwrap();
+        let release = dir.path().join("Release");
+        std::fs::create_dir_all(release.join("subs")).unwrap();
+        let video = content(200 << 10);
+        let subtitle = content(1000);
+        std::fs::write(release.join("release.mkv"), &video).unwrap();
+        std::fs::write(release.join("subs").join("release.srt"), &subtitle).unwrap();
+
+        let torrent = build_torrent(&release, &options(TorrentVersion::Hybrid, "64KiB"));
+        let root = Bencode::decode(&torrent).unwrap();
+        let info = root.get("info").unwrap();
+        assert_eq!(info.get("meta version").and_then(Bencode::as_int), Some(2));
+        assert_eq!(info.get("source").and_then(Bencode::as_str).as_deref(), Some("EXAMPLE"));
+
+        // The video fills 3.125 pieces and is padded to 4, so the subtitle starts the fifth
+        let pieces = info.get("pieces").and_then(Bencode::as_bytes).unwrap();
+        assert_eq!(pieces.len(), 5 * 20);
+        assert_eq!(&pieces[..20], Sha1::digest(&video[..64 << 10]).as_slice());
+        assert_eq!(&pieces[80..], Sha1::digest(&subtitle).as_slice());
+        let files = info.get("files").and_then(Bencode::as_list).unwrap();
+        assert_eq!(files.len(), 3);
+        assert_eq!(files[1].get("attr").and_then(Bencode::as_str).as_deref(), Some("p"));
+        assert_eq!(files[1].get("length").and_then(Bencode::as_int), Some((256 - 200) << 10));
+
+        // Only the video is longer than a piece and has a piece layer, keyed by its pieces root
+        let video_root = lookup_pieces_root(info, "release.mkv").unwrap();
+        assert_eq!(lookup_pieces_root(info, "subs/release.srt"), Some(Sha256::digest(&subtitle).to_vec()));
+        let Some(Bencode::Dict(layers)) = root.get("piece layers") else { panic!("no piece layers") };
+        assert_eq!(layers.len(), 1);
+        assert_eq!(layers.get(&video_root).and_then(Bencode::as_bytes).map(<[u8]>::len), Some(4 * 32));
+
+        let summary = inspect(&torrent).unwrap();
+        let raw = raw_info(&torrent).unwrap();
+        assert_eq!(summary.info_hash_v1, Some(hex(&Sha1::digest(raw))));
+        assert_eq!(summary.info_hash_v2, Some(hex(&Sha256::digest(raw))));
+        assert_eq!(summary.piece_size, 64 << 10);
+        assert_eq!(summary.total_size, (200 << 10) + 1000);
+        let paths: Vec<&str> = summary.files.iter().map(|file| file.path.as_str()).collect();
+        assert_eq!(paths, ["release.mkv", "subs/release.srt"]);
+    }
+
+    #[test]
+    fn builds_v2_torrents_without_v1_pieces() {
+        let dir = tempfile::tempdir().unwrap();
+        let video_path = dir.path().join("release.mkv");
+        let video = content(150 << 10);
+        std::fs::write(&video_path, &video).unwrap();
+
+        let torrent = build_torrent(&video_path, &options(TorrentVersion::V2, "64KiB"));
+        let root = Bencode::decode(&torrent).unwrap();
+        let info = root.get("info").unwrap();
+        assert!(info.get("pieces").is_none());
+        assert!(info.get("files").is_none());
+        let file = info.get("file tree").and_then(|tree| tree.get("release.mkv")).and_then(|node| node.get("")).unwrap();
+        assert_eq!(file.get("length").and_then(Bencode::as_int), Some(150 << 10));
+
+        // The first piece hash is the merkle root over its four 16 KiB blocks
+        let leaves: Vec<[u8; 32]> = video[..64 << 10].chunks(BLOCK_SIZE).map(|block| Sha256::digest(block).into()).collect();
+        let pair = |left: &[u8], right: &[u8]| -> [u8; 32] { Sha256::new().chain_update(left).chain_update(right).finalize().into() };
+        let first_piece = pair(&pair(&leaves[0], &leaves[1]), &pair(&leaves[2], &leaves[3]));
+        let pieces_root = lookup_pieces_root(info, "release.mkv").unwrap();
+        let Some(Bencode::Dict(layers)) = root.get("piece layers") else { panic!("no piece layers") };
+        let layer = layers.get(&pieces_root).and_then(Bencode::as_bytes).unwrap();
+        assert_eq!(layer.len(), 3 * 32);
+        assert_eq!(&layer[..32], first_piece.as_slice());
+
+        let summary = inspect(&torrent).unwrap();
+        assert_eq!(summary.info_hash_v1, None);
+        assert_eq!(summary.info_hash_v2, Some(hex(&Sha256::digest(raw_info(&torrent).unwrap()))));
+        assert_eq!(summary.files.len(), 1);
+        assert_eq!(summary.files[0].path, "release.mkv");
+        assert_eq!(summary.total_size, 150 << 10);
+    }
+
+    /// A release folder with a video longer than a piece and a short subtitle, next to its torrent.
+    fn release_with_torrent(dir: &Path, version: TorrentVersion) -> (PathBuf, Vec<u8>) {
+        let release = dir.join("Release");
+        std::fs::create_dir_all(release.join("subs")).unwrap();
+        std::fs::write(release.join("release.mkv"), content(200 << 10)).unwrap();
+        std::fs::write(release.join("subs").join("release.srt"), content(1000)).unwrap();
+        let torrent = build_torrent(&release, &options(version, "64KiB"));
+        (release, torrent)
+    }
+
+    fn corrupt(path: &Path, offset: usize) {
+        let mut data = std::fs::read(path).unwrap();
+        data[offset] ^= 0xff;
+        std::fs::write(path, data).unwrap();
+    }
+
+    #[test]
+    fn verifies_data_from_the_content_or_its_save_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        let (release, torrent) = release_with_torrent(dir.path(), TorrentVersion::Hybrid);
+
+        for path in [release.as_path(), dir.path()] {
+            let report = verify(&torrent, path).unwrap();
+            assert!(report.is_complete());
+            assert_eq!(report.content_path, release.to_string_lossy());
+            assert_eq!((report.pieces, report.valid_pieces), (5, 5));
+            assert_eq!(report.percent, 100.0);
+        }
+        assert!(verify_before_inject(&torrent, dir.path()).is_ok());
+    }
+
+    #[test]
+    fn reports_mismatched_and_missing_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let (release, torrent) = release_with_torrent(dir.path(), TorrentVersion::Hybrid);
+        corrupt(&release.join("release.mkv"), 70 << 10);
+        std::fs::remove_file(release.join("subs").join("release.srt")).unwrap();
+
+        let report = verify(&torrent, &release).unwrap();
+        assert!(!report.is_complete());
+        // The second video piece is corrupt and the subtitle's piece is missing
+        assert_eq!((report.pieces, report.valid_pieces), (5, 3));
+        assert_eq!(report.percent, 60.0);
+        assert_eq!(report.mismatched_files, ["release.mkv"]);
+        assert_eq!(report.missing_files, ["subs/release.srt"]);
+
+        let error = verify_before_inject(&torrent, &release).unwrap_err();
+        assert!(error.contains("60.0%"), "{}", error);
+        assert!(error.contains("subs/release.srt (missing)"), "{}", error);
+    }
+
+    #[test]
+    fn fails_pieces_of_truncated_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let (release, torrent) = release_with_torrent(dir.path(), TorrentVersion::Hybrid);
+        let video_path = release.join("release.mkv");
+        std::fs::write(&video_path, &content(200 << 10)[..100 << 10]).unwrap();
+
+        let report = verify(&torrent, &release).unwrap();
+        assert_eq!((report.pieces, report.valid_pieces), (5, 2));
+        assert_eq!(report.mismatched_files, ["release.mkv"]);
+        assert!(report.missing_files.is_empty());
+    }
+
+    #[test]
+    fn verifies_v2_torrents_file_by_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let (release, torrent) = release_with_torrent(dir.path(), TorrentVersion::V2);
+        let report = verify(&torrent, &release).unwrap();
+        assert!(report.is_complete());
+        assert_eq!((report.pieces, report.valid_pieces), (2, 2));
+
+        corrupt(&release.join("subs").join("release.srt"), 10);
+        let report = verify(&torrent, &release).unwrap();
+        assert_eq!((report.pieces, report.valid_pieces), (2, 1));
+        assert_eq!(report.mismatched_files, ["subs/release.srt"]);
+        assert_eq!(report.percent, 50.0);
+    }
+
+    /// A single-file v1 torrent as mkbrr writes it, announcing to two tiers.
+    fn v1_torrent() -> Vec<u8> {
+        dict(vec![
+            ("announce", bytes("https://old.example/announce")),
+            (
+                "announce-list",
+                Bencode::List(vec![
+                    Bencode::List(vec![bytes("https://old.example/announce")]),
+                    Bencode::List(vec![bytes("https://backup.example/announce")]),
+                ]),
+            ),
+            ("comment", bytes("Uploaded elsewhere")),
+            ("created by", bytes("mkbrr")),
+            ("creation date", Bencode::Int(1_700_000_000)),
+            (
+                "info",
+                dict(vec![
+                    ("length", Bencode::Int(100_000)),
+                    ("name", bytes("release.mkv")),
+                    ("piece length", Bencode::Int(1 << 16)),
+                    ("pieces", Bencode::Bytes(vec![7; 40])),
+                    ("source", bytes("OLD")),
+                ]),
+            ),
+        ])
+        .encode()
+    }
+
+    #[test]
+    fn inspects_v1_torrents() {
+        let torrent = v1_torrent();
+        let summary = inspect(&torrent).unwrap();
+        assert_eq!(summary.name, "release.mkv");
+        assert_eq!(summary.info_hash_v1, Some(hex(&Sha1::digest(raw_info(&torrent).unwrap()))));
+        assert_eq!(summary.info_hash_v2, None);
+        assert_eq!(summary.piece_size, 1 << 16);
+        assert_eq!(summary.total_size, 100_000);
+        assert_eq!(summary.files.len(), 1);
+        assert_eq!(summary.files[0].path, "release.mkv");
+        // `announce` repeats the first tier and is listed once
+        assert_eq!(summary.announce_urls, ["https://old.example/announce", "https://backup.example/announce"]);
+        assert_eq!(summary.source.as_deref(), Some("OLD"));
+        assert!(!summary.private);
+        assert_eq!(summary.created_by.as_deref(), Some("mkbrr"));
+        assert_eq!(summary.creation_date.as_deref(), Some("2023-11-14T22:13:20+00:00"));
+        assert_eq!(summary.comment.as_deref(), Some("Uploaded elsewhere"));
+
+        assert!(inspect(b"not a torrent").is_err());
+        assert!(inspect(&dict(vec![("announce", bytes("https://old.example/announce"))]).encode()).is_err());
+    }
+
+    #[test]
+    fn retags_without_touching_the_pieces() {
+        let torrent = v1_torrent();
+        let retagged = retag(&torrent, &["https://new.example/announce/passkey".to_string()], "NEW").unwrap();
+        let summary = inspect(&retagged).unwrap();
+        assert_eq!(summary.announce_urls, ["https://new.example/announce/passkey"]);
+        assert_eq!(summary.source.as_deref(), Some("NEW"));
+        assert!(summary.private);
+        assert_ne!(summary.info_hash_v1, inspect(&torrent).unwrap().info_hash_v1);
+
+        let before = Bencode::decode(&torrent).unwrap();
+        let after = Bencode::decode(&retagged).unwrap();
+        assert!(after.get("announce-list").is_none());
+        for key in ["name", "length", "piece length", "pieces"] {
+            assert_eq!(after.get("info").and_then(|info| info.get(key)), before.get("info").and_then(|info| info.get(key)), "{}", key);
+        }
+        assert_eq!(after.get("comment"), before.get("comment"));
+    }
+
+    #[test]
+    fn retags_with_one_tier_per_announce_url() {
+        let urls = ["https://a.example/announce".to_string(), "https://b.example/announce".to_string()];
+        let retagged = Bencode::decode(&retag(&v1_torrent(), &urls, "NEW").unwrap()).unwrap();
+        assert_eq!(retagged.get("announce").and_then(Bencode::as_str).as_deref(), Some("https://a.example/announce"));
+        let tiers = retagged.get("announce-list").and_then(Bencode::as_list).unwrap();
+        assert_eq!(tiers, [Bencode::List(vec![bytes(&urls[0])]), Bencode::List(vec![bytes(&urls[1])])]);
+
+        assert!(retag(&v1_torrent(), &[], "NEW").is_err());
+        assert!(retag(b"i42e", &urls, "NEW").is_err());
+    }
+}
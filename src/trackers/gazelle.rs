@@ -0,0 +1,263 @@
+use std::path::Path;
+use log::{info, warn};
+use regex::Regex;
+use reqwest::blocking::multipart::Form;
+use serde_json::Value;
+use crate::error::SeedToolsError;
+use crate::http::send_text;
+use crate::offline::Offline;
+use crate::trackers::{detect_unavailable, load_named_configs};
+use crate::types::GazelleConfig;
+
+// Gazelle release types, see the upload form
+const RELEASE_SOUNDTRACK: u32 = 3;
+const RELEASE_EP: u32 = 5;
+const RELEASE_COMPILATION: u32 = 7;
+const RELEASE_SINGLE: u32 = 9;
+const RELEASE_LIVE_ALBUM: u32 = 11;
+
+/// Loads the Gazelle trackers set up in `dir` (usually `config/trackers/gazelle`).
+pub fn load_gazelle_configs(dir: &Path) -> Result<Vec<GazelleConfig>, SeedToolsError> {
+    Ok(load_named_configs::<GazelleConfig>(dir)?
+        .into_iter()
+        .map(|(name, config)| GazelleConfig { name, ..config })
+        .collect())
+}
+
+/// Finds the Gazelle tracker called `name` (case-insensitive).
+pub fn find_gazelle_config<'a>(configs: &'a [GazelleConfig], name: &str) -> Result<&'a GazelleConfig, SeedToolsError> {
+    configs.iter().find(|config| config.name.eq_ignore_ascii_case(name)).ok_or_else(|| {
+        let known: Vec<&str> = configs.iter().map(|config| config.name.as_str()).collect();
+        SeedToolsError::config(format!(
+            "No Gazelle tracker named '{}' in config/trackers/gazelle (found: {})",
+            name,
+            if known.is_empty() { "none".to_string() } else { known.join(", ") }
+        ))
+    })
+}
+
+/// Gazelle's `format` and `bitrate` (encoding) values for what mediainfo reports: e.g.
+/// ("FLAC", "24bit Lossless") or ("MP3", "V0 (VBR)").
+pub fn format_and_bitrate(format: &str, bit_depth: &str, bit_rate: &str, bit_rate_mode: &str, encoding_settings: &str) -> (String, String) {
+    match format {
+        "FLAC" => {
+            let bitrate = if bit_depth.starts_with("24") { "24bit Lossless" } else { "Lossless" };
+            ("FLAC".to_string(), bitrate.to_string())
+        }
+        "MPEG Audio" | "MP3" => {
+            // LAME writes its preset into the encoding settings
+            let preset = Regex::new(r"-V ?([0-2])\b").unwrap().captures(encoding_settings).map(|captures| captures[1].to_string());
+            let kbps: String = bit_rate.chars().filter(|c| c.is_ascii_digit()).collect();
+            let bitrate = match (preset, bit_rate_mode) {
+                (Some(level), _) => format!("V{} (VBR)", level),
+                (None, "Constant") if ["192", "256", "320"].contains(&kbps.as_str()) => kbps,
+                _ => "Other".to_string(),
+            };
+            ("MP3".to_string(), bitrate)
+        }
+        other => (other.to_string(), "Other".to_string()),
+    }
+}
+
+/// Gazelle release type for a release name, or `default` when the name does not tell.
+pub fn detect_release_type(release_name: &str, default: u32) -> u32 {
+    let name = release_name.to_lowercase().replace(['.', '_'], " ");
+    let has = |pattern: &str| Regex::new(pattern).unwrap().is_match(&name);
+    if has(r"\b(ost|soundtrack)\b") {
+        RELEASE_SOUNDTRACK
+    } else if has(r"\bep\b") {
+        RELEASE_EP
+    } else if has(r"\b(single|cds)\b") {
+        RELEASE_SINGLE
+    } else if has(r"\blive\b") {
+        RELEASE_LIVE_ALBUM
+    } else if has(r"\b(va|compilation|greatest hits|best of)\b") {
+        RELEASE_COMPILATION
+    } else {
+        default
+    }
+}
+
+/// A music upload to a Gazelle tracker.
+#[derive(Default, Clone, Debug)]
+pub struct GazelleUpload {
+    pub torrent_file: String,
+    pub artists: Vec<String>,
+    pub title: String,
+    pub year: Option<u32>,
+    pub release_type: u32,
+    pub media: String,
+    pub format: String,
+    pub bitrate: String,
+    pub record_label: Option<String>,
+    pub catalogue_number: Option<String>,
+    pub tags: Vec<String>,
+    pub image: Option<String>, // Cover URL
+    pub album_desc: String,
+    pub release_desc: Option<String>,
+    pub log_files: Vec<String>, // Rip logs, scored by the site's logchecker
+}
+
+impl GazelleUpload {
+    /// Catches uploads the tracker would reject before anything is sent.
+    pub fn validate(&self) -> Result<(), SeedToolsError> {
+        if !Path::new(&self.torrent_file).is_file() {
+            return Err(SeedToolsError::config(format!("Torrent file does not exist: {}", self.torrent_file)));
+        }
+        if self.artists.iter().all(|artist| artist.trim().is_empty()) {
+            return Err(SeedToolsError::Metadata(format!("No artist found for '{}'", self.title)));
+        }
+        if self.title.trim().is_empty() {
+            return Err(SeedToolsError::Metadata("Album title is empty".to_string()));
+        }
+        if self.tags.is_empty() {
+            return Err(SeedToolsError::config(format!("No tags for '{}'; add some under tags in the tracker's yaml", self.title)));
+        }
+        Ok(())
+    }
+
+    /// Validates the upload and renders the `ajax.php?action=upload` form.
+    pub fn to_form(&self) -> Result<Form, SeedToolsError> {
+        self.validate()?;
+        let mut form = Form::new()
+            .file("file_input", &self.torrent_file)
+            .map_err(SeedToolsError::io("Failed to attach torrent file"))?
+            .text("type", "0") // Music
+            .text("title", self.title.clone())
+            .text("releasetype", self.release_type.to_string())
+            .text("media", self.media.clone())
+            .text("format", self.format.clone())
+            .text("bitrate", self.bitrate.clone())
+            .text("tags", self.tags.join(","))
+            .text("album_desc", self.album_desc.clone());
+        for artist in self.artists.iter().filter(|artist| !artist.trim().is_empty()) {
+            form = form.text("artists[]", artist.clone()).text("importance[]", "1"); // Main artist
+        }
+        match self.year {
+            Some(year) => form = form.text("year", year.to_string()).text("remaster_year", year.to_string()),
+            None => form = form.text("unknown", "1"),
+        }
+        if let Some(label) = &self.record_label {
+            form = form.text("remaster_record_label", label.clone());
+        }
+        if let Some(catalogue_number) = &self.catalogue_number {
+            form = form.text("remaster_catalogue_number", catalogue_number.clone());
+        }
+        if let Some(image) = &self.image {
+            form = form.text("image", image.clone());
+        }
+        if let Some(release_desc) = &self.release_desc {
+            form = form.text("release_desc", release_desc.clone());
+        }
+        for log in &self.log_files {
+            form = form.file("logfiles[]", log).map_err(SeedToolsError::io("Failed to attach log file"))?;
+        }
+        Ok(form)
+    }
+}
+
+/// Calls an `ajax.php` action and returns the `response` field of a successful answer.
+fn ajax(config: &GazelleConfig, action: &str, query: &str, form: Option<&dyn Fn() -> Result<Form, SeedToolsError>>) -> Result<Value, SeedToolsError> {
+    let url = format!("{}/ajax.php?action={}{}", config.base_url.trim_end_matches('/'), action, query);
+    let (status, body) = send_text(&url, |client| {
+        let request = match form {
+            Some(form) => client.post(&url).multipart(form()?),
+            None => client.get(&url),
+        };
+        Ok(request.header("Authorization", &config.api_key))
+    })
+    .map_err(|e| e.context(&format!("Failed to call {} {}", config.name, action)))?;
+    let status = status.as_u16();
+    if let Some(reason) = detect_unavailable(status, &body) {
+        return Err(SeedToolsError::unavailable(&config.name, reason));
+    }
+    let json: Value = serde_json::from_str(&body).map_err(|_| {
+        SeedToolsError::tracker(&config.name, Some(status), format!("{} {} answered HTTP {}: {}", config.name, action, status, body))
+    })?;
+    if json["status"] != "success" {
+        let error = json["error"].as_str().unwrap_or(&body);
+        return Err(SeedToolsError::tracker(&config.name, Some(status), format!("{} {} failed: {}", config.name, action, error)));
+    }
+    Ok(json["response"].clone())
+}
+
+/// Looks for a torrent of the same album in the same media, format and encoding and returns
+/// its page.
+pub fn check_gazelle_dupes(config: &GazelleConfig, artist: &str, album: &str, format: &str, bitrate: &str) -> Result<Option<String>, SeedToolsError> {
+    info!("Checking {} for '{} - {}' ({} {} {})", config.name, artist, album, config.media, format, bitrate);
+    let query = format!("&artistname={}&groupname={}", urlencoding::encode(artist), urlencoding::encode(album));
+    let response = ajax(config, "browse", &query, None)?;
+    for group in response["results"].as_array().into_iter().flatten() {
+        let same_release = group["torrents"].as_array().into_iter().flatten().any(|torrent| {
+            torrent["media"] == config.media.as_str() && torrent["format"] == format && torrent["encoding"] == bitrate
+        });
+        if same_release {
+            let link = format!("{}/torrents.php?id={}", config.base_url.trim_end_matches('/'), group["groupId"]);
+            info!("Duplicate found on {}: {}", config.name, link);
+            return Ok(Some(link));
+        }
+    }
+    info!("No duplicate found on {}.", config.name);
+    Ok(None)
+}
+
+/// Scores a rip log with the site's logchecker and returns the score and issues found.
+pub fn check_log(config: &GazelleConfig, log_file: &str) -> Result<(i64, Vec<String>), SeedToolsError> {
+    let form = || Form::new().file("log", log_file).map_err(SeedToolsError::io("Failed to attach log file"));
+    let response = ajax(config, "logchecker", "", Some(&form))?;
+    let issues = response["issues"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|issue| issue.as_str().map(str::to_string))
+        .collect();
+    Ok((response["score"].as_i64().unwrap_or(0), issues))
+}
+
+/// Logs the logchecker results of every log; a failing logchecker does not stop the upload.
+pub fn check_logs(config: &GazelleConfig, log_files: &[String]) -> Vec<String> {
+    let mut results = Vec::new();
+    for log_file in log_files {
+        let name = Path::new(log_file).file_name().unwrap_or_default().to_string_lossy().to_string();
+        match check_log(config, log_file) {
+            Ok((score, issues)) if issues.is_empty() => {
+                info!("{} logchecker: {} scored {}", config.name, name, score);
+                results.push(format!("{}: {}", name, score));
+            }
+            Ok((score, issues)) => {
+                warn!("{} logchecker: {} scored {}: {}", config.name, name, score, issues.join("; "));
+                results.push(format!("{}: {} ({})", name, score, issues.join("; ")));
+            }
+            Err(e) => warn!("Could not check {}: {}", name, e),
+        }
+    }
+    results
+}
+
+/// Posts the upload and returns the new torrent's page. With `offline` the mock tracker records
+/// the upload.
+pub fn submit_gazelle_upload(config: &GazelleConfig, upload: &GazelleUpload, offline: Option<&Offline>) -> Result<String, SeedToolsError> {
+    upload.validate()?;
+    if let Some(offline) = offline {
+        let fields = serde_json::json!({
+            "artists": upload.artists,
+            "year": upload.year,
+            "releasetype": upload.release_type,
+            "media": upload.media,
+            "format": upload.format,
+            "bitrate": upload.bitrate,
+            "tags": upload.tags,
+            "album_desc": upload.album_desc,
+            "release_desc": upload.release_desc,
+        });
+        let torrent_id = offline.tracker.record(&config.name, &upload.title, &upload.torrent_file, fields)?;
+        return Ok(format!("{}/torrents.php?torrentid={}", config.base_url.trim_end_matches('/'), torrent_id));
+    }
+    let response = ajax(config, "upload", "", Some(&|| upload.to_form()))?;
+    info!("{} upload response: {}", config.name, response);
+    let torrent_id = response["torrentid"]
+        .as_u64()
+        .or_else(|| response["torrentId"].as_u64())
+        .ok_or_else(|| SeedToolsError::tracker(&config.name, None, format!("No torrent ID in the {} upload response: {}", config.name, response)))?;
+    Ok(format!("{}/torrents.php?torrentid={}", config.base_url.trim_end_matches('/'), torrent_id))
+}
@@ -0,0 +1,64 @@
+pub mod gazelle;
+pub mod release;
+pub mod seedpool;
+pub mod seedpool_metadata;
+pub mod torrentleech;
+pub mod unit3d;
+
+pub use release::{cross_seed_dupe, preflight, process_release, upload, Target, Tools, UploadOptions};
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use serde::de::DeserializeOwned;
+use crate::error::SeedToolsError;
+use crate::http::is_challenge_page;
+use seedpool::UploadRequest;
+
+/// Exit code of an upload process that stopped because a tracker was unavailable
+/// (EX_TEMPFAIL). Upload queues park such uploads and retry them later.
+pub const TRACKER_UNAVAILABLE_EXIT_CODE: i32 = 75;
+
+/// Recognizes maintenance pages, gateway errors and Cloudflare challenges in a failed response.
+pub fn detect_unavailable(status: u16, body: &str) -> Option<String> {
+    if (200..400).contains(&status) {
+        return None;
+    }
+    if is_challenge_page(body) {
+        return Some(format!("challenge page, HTTP {}", status));
+    }
+    let body = body.to_lowercase();
+    match status {
+        503 if body.contains("maintenance") => Some("maintenance mode, HTTP 503".to_string()),
+        502 | 503 | 504 | 520..=524 => Some(format!("HTTP {}", status)),
+        _ if body.contains("maintenance") => Some(format!("maintenance page, HTTP {}", status)),
+        _ => None,
+    }
+}
+
+/// Loads every `<name>.yaml` in `dir` (e.g. `config/trackers/unit3d`) with its lowercased file
+/// name, sorted by name. A missing directory means none are set up.
+pub fn load_named_configs<T: DeserializeOwned>(dir: &Path) -> Result<Vec<(String, T)>, SeedToolsError> {
+    let Ok(entries) = fs::read_dir(dir) else { return Ok(Vec::new()) };
+    let mut configs = Vec::new();
+    for path in entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()) {
+        if path.extension().and_then(|ext| ext.to_str()) != Some("yaml") {
+            continue;
+        }
+        let content = crate::secrets::read_config(&path)?;
+        let config = serde_yaml::from_str(&content).map_err(|e| SeedToolsError::Config { message: format!("Failed to parse '{}': {}", path.display(), e), source: Some(Box::new(e)) })?;
+        configs.push((path.file_stem().unwrap_or_default().to_string_lossy().to_lowercase(), config));
+    }
+    configs.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(configs)
+}
+
+/// Capabilities and upload entry point shared by the supported trackers.
+pub trait Tracker {
+    fn requires_screenshots(&self) -> bool;
+    fn requires_sample(&self) -> bool;
+    fn requires_tmdb_id(&self) -> bool;
+    fn requires_remote_path(&self) -> bool;
+    fn upload(&self, upload: UploadRequest) -> Result<String, SeedToolsError>; // The tracker's response body
+    fn generate_metadata(&self, torrent_file: &str) -> Result<HashMap<String, String>, SeedToolsError>;
+}
@@ -0,0 +1,472 @@
+use std::path::Path;
+use log::{info, warn};
+use crate::error::SeedToolsError;
+use crate::history;
+use crate::http::send_with_retry;
+use crate::offline::Offline;
+use crate::pipeline::seedpool_metadata_cache;
+use crate::trackers::seedpool::{submit_upload, UploadRequest};
+use crate::trackers::seedpool_metadata::validate_seedpool_ids;
+use crate::utils::{create_torrent, add_torrent_to_all_qbittorrent_instances, torrent_info_hash};
+use crate::types::{Config, PathsConfig, TorrentOptions, UploadFlags};
+use super::Target;
+use regex::Regex;
+
+/// Stops an upload the local history already has for `tracker` (same infohash or release name),
+/// whatever the tracker's own dupe check says. `allow_reupload` skips the check.
+pub fn check_local_dupe(
+    paths: &PathsConfig,
+    tracker: &str,
+    release_name: &str,
+    torrent_file: Option<&str>,
+    allow_reupload: bool,
+) -> Result<(), SeedToolsError> {
+    if allow_reupload {
+        return Ok(());
+    }
+    let info_hash = torrent_file
+        .and_then(|file| std::fs::read(file).ok())
+        .and_then(|torrent| torrent_info_hash(&torrent).ok());
+    match history::find_previous_upload(paths, tracker, release_name, info_hash.as_deref()) {
+        Ok(Some(entry)) => Err(SeedToolsError::tracker(
+            tracker,
+            None,
+            format!(
+                "'{}' was already uploaded to {} on {} as '{}'{}. Pass --allow-reupload to upload it anyway.",
+                release_name,
+                tracker,
+                entry.uploaded_at,
+                entry.release_name,
+                entry.torrent_id.map(|id| format!(" (torrent {})", id)).unwrap_or_default(),
+            ),
+        )),
+        Ok(None) => Ok(()),
+        Err(e) => {
+            warn!("Skipping the local dupe check: {}", e);
+            Ok(())
+        }
+    }
+}
+
+/// Where a custom or game upload goes: the announce URL, the upload URL, the torrent options and
+/// the upload flags (Seedpool's own, none for TorrentLeech).
+fn custom_upload_target(
+    target: &Target,
+    paths: &PathsConfig,
+    category_id: u32,
+    type_id: u32,
+) -> Result<(String, String, TorrentOptions, UploadFlags), SeedToolsError> {
+    match target {
+        Target::Seedpool(config) => {
+            // Category/type codes come from upload_categories, which can drift from the site
+            validate_seedpool_ids(
+                &config.settings.upload_url,
+                &config.general.api_key,
+                &seedpool_metadata_cache(paths),
+                category_id,
+                type_id,
+                None,
+            )?;
+            Ok((config.settings.announce_url.clone(), config.settings.upload_url.clone(), config.torrent.clone(), config.upload_flags.clone()))
+        }
+        Target::TorrentLeech(config) => {
+            Ok((config.general.announce_url_1.clone(), config.settings.upload_url.clone(), config.torrent.clone(), UploadFlags::default()))
+        }
+        _ => Err(SeedToolsError::config(format!("Custom uploads go to Seedpool or TorrentLeech, not {}", target.name()))),
+    }
+}
+
+pub fn process_custom_upload(
+    input_path: &str,
+    category_id: u32,
+    type_id: u32,
+    config: &Config,
+    target: &Target, // Seedpool or TorrentLeech
+    offline: Option<&Offline>, // Record the upload and seed with the offline stand-ins
+) -> Result<(), SeedToolsError> {
+    let base_name = Path::new(input_path)
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+
+    info!(
+        "Processing custom upload with category_id={} and type_id={} for tracker={}",
+        category_id, type_id, target.name()
+    );
+
+    // Determine the announce and upload URLs based on the tracker
+    let (announce_url, upload_url, torrent_options, flags) = custom_upload_target(target, &config.paths, category_id, type_id)?;
+
+    let torrent_file = create_torrent(
+        input_path, // Pass the input path directly as a &str
+        "./torrents", // Output directory for torrents
+        &announce_url,
+        &config.paths.mkbrr, // Path to mkbrr binary
+        None, // Disable filtering for non-Standard Upload Mode
+        &torrent_options,
+    )?;
+
+    // Check for an .nfo file
+    let nfo_file = if Path::new(input_path).is_file() {
+        // If input_path is a file, check for a sibling .nfo file
+        let nfo_path = Path::new(input_path).with_extension("nfo");
+        if nfo_path.exists() {
+            Some(nfo_path.to_string_lossy().to_string())
+        } else {
+            None
+        }
+    } else {
+        // If input_path is a directory, look for any .nfo file inside it
+        std::fs::read_dir(input_path)
+            .ok()
+            .and_then(|mut entries| {
+                entries.find_map(|entry| {
+                    let entry = entry.ok()?;
+                    let path = entry.path();
+                    if path.extension().map(|ext| ext.eq_ignore_ascii_case("nfo")).unwrap_or(false) {
+                        Some(path.to_string_lossy().to_string())
+                    } else {
+                        None
+                    }
+                })
+            })
+    };
+    // Prepare the upload form
+    let upload = UploadRequest {
+        torrent_file: torrent_file.clone(),
+        name: base_name,
+        category_id,
+        type_id,
+        description: Some("Custom upload".to_string()),
+        nfo_file,
+        ..Default::default()
+    }
+    .with_flags(&flags);
+
+    // Send the upload request
+    submit_upload(&upload_url, None, &upload, offline)?;
+
+    // Inject the torrent into qBittorrent
+    add_torrent_to_all_qbittorrent_instances(
+        &[torrent_file], // Use the single torrent file wrapped in a slice
+        &config.qbittorrent,
+        &config.deluge,
+        input_path, // Pass the input_path argument
+        &config.paths,
+        offline,
+    )?;
+
+    Ok(())
+}
+
+pub fn igdb_lookup_id(game_title: &str, client_id: &str, bearer_token: &str) -> Result<Option<u64>, SeedToolsError> {
+    // Step 1: Search for candidate game IDs
+    let search_url = "https://api.igdb.com/v4/search";
+    let search_body = format!("fields game; search \"{}\"; limit 10;", game_title);
+
+    let search_resp = send_with_retry("igdb", |client| {
+        client
+            .post(search_url)
+            .header("Client-ID", client_id)
+            .header("Authorization", format!("Bearer {}", bearer_token))
+            .header("Accept", "application/json")
+            .body(search_body.clone())
+    })
+    .map_err(|e| e.context("IGDB search request failed"))?;
+
+    let search_json: serde_json::Value = search_resp.json().map_err(|e| SeedToolsError::request("IGDB", e).context("IGDB search response parse failed"))?;
+    let mut game_ids: Vec<u64> = vec![];
+    if let Some(arr) = search_json.as_array() {
+        for item in arr {
+            if let Some(id) = item.get("game").and_then(|id| id.as_u64()) {
+                game_ids.push(id);
+            }
+        }
+    }
+
+    // If no results, try again with the last word stripped (if possible)
+    if game_ids.is_empty() {
+        if let Some(pos) = game_title.trim().rfind(' ') {
+            let shorter = &game_title[..pos];
+            if !shorter.trim().is_empty() {
+                return igdb_lookup_id(shorter.trim(), client_id, bearer_token);
+            }
+        }
+        return Ok(Some(14591)); // Default to 1 if no results and nothing left to strip
+    }
+
+    // ...rest of your function unchanged...
+    // Step 2: Query /games for details (request more fields for better matching)
+    let games_url = "https://api.igdb.com/v4/games";
+    let ids_str = game_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(",");
+    let games_body = format!(
+        "fields id, name, slug, alternative_names.name, first_release_date; where id = ({}); limit 10;",
+        ids_str
+    );
+
+    let games_resp = send_with_retry("igdb", |client| {
+        client
+            .post(games_url)
+            .header("Client-ID", client_id)
+            .header("Authorization", format!("Bearer {}", bearer_token))
+            .header("Accept", "application/json")
+            .body(games_body.clone())
+    })
+    .map_err(|e| e.context("IGDB games request failed"))?;
+
+    let games_json: serde_json::Value = games_resp.json().map_err(|e| SeedToolsError::request("IGDB", e).context("IGDB games response parse failed"))?;
+
+    // Handle both array and single-object responses
+    let games: Vec<serde_json::Value> = if let Some(arr) = games_json.as_array() {
+        arr.clone()
+    } else if games_json.is_object() {
+        vec![games_json]
+    } else {
+        vec![]
+    };
+
+    // Step 3: Try to find the best match
+    let sanitized_query = sanitize_game_title(game_title).to_lowercase();
+    let mut best_match: Option<u64> = None;
+
+    for game in &games {
+        let id = game.get("id").and_then(|v| v.as_u64());
+        let name = game.get("name").and_then(|v| v.as_str()).unwrap_or("");
+        let slug = game.get("slug").and_then(|v| v.as_str()).unwrap_or("");
+        let alt_names = game.get("alternative_names")
+            .and_then(|v| v.as_array())
+            .map(|arr| arr.iter().filter_map(|n| n.get("name").and_then(|n| n.as_str())).collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        // 1. Exact match on sanitized name
+        if sanitize_game_title(name).to_lowercase() == sanitized_query {
+            best_match = id;
+            break;
+        }
+        // 2. Exact match on slug (replace dashes with spaces for comparison)
+        if slug.replace("-", " ").to_lowercase() == sanitized_query.replace("-", " ") {
+            best_match = id;
+            break;
+        }
+        // 3. Match on any alternative name
+        if alt_names.iter().any(|alt| sanitize_game_title(alt).to_lowercase() == sanitized_query) {
+            best_match = id;
+            break;
+        }
+    }
+
+    // 4. Fallback to first result
+    if best_match.is_none() {
+        best_match = games.first().and_then(|game| game.get("id").and_then(|v| v.as_u64()));
+    }
+    Ok(best_match.or(Some(14591)))
+}
+
+pub fn process_game_upload(
+    input_path: &str,
+    category_id: u32,
+    type_id: u32,
+    config: &Config,
+    target: &Target, // Seedpool or TorrentLeech
+    offline: Option<&Offline>,
+) -> Result<(), SeedToolsError> {
+    use crate::utils::{upload_to_cdn, generate_game_description, download_igdb_screenshots, render_file_tree};
+    use std::path::Path;
+
+    let base_name = Path::new(input_path)
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+
+    let game_title = sanitize_game_title(&base_name);
+    let igdb_client_id = config.general.igdb_client_id.as_str();
+    let igdb_bearer_token = config.general.igdb_bearer_token.as_str();
+    let seedpool_config = match target {
+        Target::Seedpool(seedpool_config) => Some(*seedpool_config),
+        _ => None,
+    };
+
+    let igdb_id = igdb_lookup_id(&game_title, igdb_client_id, igdb_bearer_token)?
+        .map(|id| id.to_string())
+        .unwrap_or_else(|| "0".to_string());
+
+    info!("IGDB ID for '{}': {}", game_title, igdb_id);
+
+    // --- IGDB screenshots logic ---
+    let mut screenshot_urls = Vec::new();
+    if igdb_id != "0" && igdb_id != "1"
+        && let Some(seedpool) = seedpool_config
+    {
+        let image_path = seedpool.screenshots.image_path.trim_end_matches('/');
+        let remote_path = seedpool.screenshots.remote_path.trim_end_matches('/');
+
+        // 1. Get screenshot IDs from IGDB
+        let screenshots_body = format!("fields screenshots; where id = {}; limit 1;", igdb_id);
+        let resp = send_with_retry("igdb", |client| {
+            client
+                .post("https://api.igdb.com/v4/games")
+                .header("Client-ID", igdb_client_id)
+                .header("Authorization", format!("Bearer {}", igdb_bearer_token))
+                .header("Accept", "application/json")
+                .body(screenshots_body.clone())
+        })
+        .map_err(|e| e.context("IGDB screenshots request failed"))?;
+        let json: serde_json::Value = resp.json().map_err(|e| SeedToolsError::request("IGDB", e).context("IGDB screenshots response parse failed"))?;
+        let screenshot_ids: Vec<u64> = json.as_array()
+            .and_then(|arr| arr.first())
+            .and_then(|game| game.get("screenshots"))
+            .and_then(|ss| ss.as_array())
+            .map(|arr| arr.iter().filter_map(|v| v.as_u64()).collect())
+            .unwrap_or_default();
+
+        // 2. Get image_ids for those screenshots
+        if !screenshot_ids.is_empty() {
+            let ids_str = screenshot_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(",");
+            let screenshots_body = format!("fields id,image_id; where id = ({});", ids_str);
+            let resp = send_with_retry("igdb", |client| {
+                client
+                    .post("https://api.igdb.com/v4/screenshots")
+                    .header("Client-ID", igdb_client_id)
+                    .header("Authorization", format!("Bearer {}", igdb_bearer_token))
+                    .header("Accept", "application/json")
+                    .body(screenshots_body.clone())
+            })
+            .map_err(|e| e.context("IGDB screenshots image_id request failed"))?;
+            let json: serde_json::Value = resp.json().map_err(|e| SeedToolsError::request("IGDB", e).context("IGDB screenshots image_id response parse failed"))?;
+            let image_ids: Vec<String> = json.as_array()
+                .map(|arr| arr.iter().filter_map(|v| v.get("image_id").and_then(|id| id.as_str()).map(|s| s.to_string())).collect())
+                .unwrap_or_default();
+
+            // 3. Download screenshots, set permissions, upload to CDN, collect CDN URLs
+            let safe_base_name = url_safe_filename(&base_name);
+            let local_paths = download_igdb_screenshots(&image_ids, &safe_base_name, "./screenshots")?;
+            for local_path in &local_paths {
+                let file_name = Path::new(local_path).file_name().unwrap().to_string_lossy();
+                let remote_file = format!("{}/{}", remote_path, file_name);
+                upload_to_cdn(local_path, &remote_file, offline)?;
+                let cdn_url = format!("{}/{}", image_path, file_name);
+                screenshot_urls.push(cdn_url);
+            }
+        }
+    }
+    // --- End IGDB screenshots logic ---
+
+    let (announce_url, upload_url, torrent_options, flags) = custom_upload_target(target, &config.paths, category_id, type_id)?;
+
+    let torrent_file = create_torrent(
+        input_path,
+        "./torrents",
+        &announce_url,
+        &config.paths.mkbrr,
+        None,
+        &torrent_options,
+    )?;
+
+    // Check for an .nfo file
+    let nfo_file = if Path::new(input_path).is_file() {
+        let nfo_path = Path::new(input_path).with_extension("nfo");
+        if nfo_path.exists() {
+            Some(nfo_path.to_string_lossy().to_string())
+        } else {
+            None
+        }
+    } else {
+        std::fs::read_dir(input_path)
+            .ok()
+            .and_then(|mut entries| {
+                entries.find_map(|entry| {
+                    let entry = entry.ok()?;
+                    let path = entry.path();
+                    if path.extension().map(|ext| ext.eq_ignore_ascii_case("nfo")).unwrap_or(false) {
+                        Some(path.to_string_lossy().to_string())
+                    } else {
+                        None
+                    }
+                })
+            })
+    };
+
+    // Use the new game description generator
+    let description = if !screenshot_urls.is_empty() {
+        generate_game_description(
+            &screenshot_urls,
+            seedpool_config.map(|c| c.settings.custom_description_for("game", &base_name, &game_title)).as_deref(),
+            None, // youtube_trailer_url
+            &base_name,
+        )
+    } else {
+        base_name.clone()
+    };
+    let description = format!("{}\n{}", description, render_file_tree(input_path));
+
+    let upload = UploadRequest {
+        torrent_file: torrent_file.clone(),
+        name: base_name,
+        category_id,
+        type_id,
+        igdb_id: Some(igdb_id),
+        description: Some(description),
+        nfo_file,
+        ..Default::default()
+    }
+    .with_flags(&flags);
+
+    submit_upload(&upload_url, None, &upload, offline)?;
+
+    add_torrent_to_all_qbittorrent_instances(
+        &[torrent_file],
+        &config.qbittorrent,
+        &config.deluge,
+        input_path,
+        &config.paths,
+        offline,
+    )?;
+
+    Ok(())
+}
+
+pub fn sanitize_game_title(raw: &str) -> String {
+    // Remove extension if present
+    let mut name = Regex::new(r"\.[a-z0-9]{2,4}$").unwrap().replace(raw, "").to_string();
+
+    // Always remove everything after the last dash (including the dash)
+    if let Some(idx) = name.rfind('-') {
+        name = name[..idx].to_string();
+    }
+
+    // Remove everything after v1, v2, v3, ... (case-insensitive)
+    name = Regex::new(r"(?i)[ _.-]?v\d[\w.]*.*").unwrap().replace(&name, "").to_string();
+
+    // Remove group in brackets (e.g. [GROUP])
+    name = Regex::new(r"\[.*?\]$").unwrap().replace(&name, "").to_string();
+
+    // Replace dots, underscores, and multiple spaces with a single space
+    name = Regex::new(r"[._]+").unwrap().replace_all(&name, " ").to_string();
+    name = Regex::new(r"\s+").unwrap().replace_all(&name, " ").to_string();
+
+    // Remove year (e.g. 2023, 1999)
+    name = Regex::new(r"\b(19|20)\d{2}\b").unwrap().replace(&name, "").to_string();
+
+    // Remove common tags (add more as needed)
+    name = Regex::new(r"(?i)\b(REPACK|PROPER|MULTI\d+|FULL|NSW|Unlocker|Update|UPDATE|Pack|RELOADED|FLT|GOG|CODEX|SKIDROW|PLAZA|CPY|Razor1911|FitGirl|ElAmigos|DODI|GoldBerg|DOGE|P2P|SteamRip|Switch|XCI|NSP|PC|ISO|DARKSiDERS|Chronos|TiNYiSO|Unleashed|GOG|FIX)\b")
+        .unwrap()
+        .replace_all(&name, "")
+        .to_string();
+
+    // Remove extra spaces again after tag removal
+    name = Regex::new(r"\s+").unwrap().replace_all(&name, " ").to_string();
+
+    // Trim whitespace
+    name.trim().to_string()
+}
+
+fn url_safe_filename(name: &str) -> String {
+    use regex::Regex;
+    // Replace spaces and consecutive whitespace with underscores
+    let name = Regex::new(r"\s+").unwrap().replace_all(name, "_");
+    // Remove any character that is not alphanumeric, underscore, dash, or dot
+    let name = Regex::new(r"[^A-Za-z0-9_\-\.]").unwrap().replace_all(&name, "");
+    name.to_string()
+}
\ No newline at end of file
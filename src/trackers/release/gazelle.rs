@@ -0,0 +1,196 @@
+use std::path::Path;
+use crate::error::SeedToolsError;
+use crate::types::Config;
+use log::{info, warn};
+use walkdir::WalkDir;
+use crate::utils::{add_torrent_to_all_qbittorrent_instances, create_torrent, torrent_info_hash, upload_to_imgbb};
+use crate::descriptions::{self, MusicRelease};
+use crate::history::{self, HistoryEntry};
+use crate::limits::check_release_limits;
+use crate::metrics;
+use crate::notifications::{notify, Notification};
+use crate::pipeline::ReleaseCheckpoint;
+use crate::slots::{self, Workload};
+use crate::torrent::retag;
+use crate::trackers::gazelle::{
+    check_gazelle_dupes, check_logs, detect_release_type, format_and_bitrate, submit_gazelle_upload, GazelleUpload,
+};
+use crate::types::GazelleConfig;
+use super::common::check_local_dupe;
+use super::{Tools, UploadOptions};
+use super::seedpool::{music_tracks, read_music_details};
+
+/// Uploads a music release (the same MP3/FLAC folders `process_music_release` takes) to a
+/// Gazelle tracker set up in `config/trackers/gazelle`.
+pub fn process_gazelle_release(
+    input_path: &str,
+    config: &Config,
+    tracker: &GazelleConfig,
+    tools: &Tools,
+    options: &UploadOptions,
+) -> Result<(), SeedToolsError> {
+    let mut checkpoint = ReleaseCheckpoint::new(&tracker.name, input_path);
+    let result = upload_gazelle_release(&mut checkpoint, input_path, config, tracker, tools, options);
+    metrics::record_upload(&tracker.name, &result);
+    notify(&config.notifications, &Notification::upload(&tracker.name, &checkpoint, &result));
+    result
+}
+
+fn upload_gazelle_release(
+    checkpoint: &mut ReleaseCheckpoint,
+    input_path: &str,
+    config: &Config,
+    tracker: &GazelleConfig,
+    tools: &Tools,
+    options: &UploadOptions,
+) -> Result<(), SeedToolsError> {
+    let imgbb_api_key = options.imgbb_api_key.as_deref();
+    let offline = options.offline.as_deref();
+    let base_name = Path::new(input_path)
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    checkpoint.release_name = base_name.clone();
+
+    let details = read_music_details(input_path)?;
+    let (format, bitrate) = format_and_bitrate(
+        &details.format,
+        &details.bit_depth,
+        &details.bit_rate,
+        &details.bit_rate_mode,
+        &details.encoding_settings,
+    );
+    info!("{}: {} - {} ({}, {} {})", tracker.name, details.artist, details.album, tracker.media, format, bitrate);
+
+    // Offline runs have nothing to search
+    let dupe = match offline {
+        Some(_) => None,
+        None => check_gazelle_dupes(tracker, &details.artist, &details.album, &format, &bitrate)?,
+    };
+    if let Some(link) = dupe {
+        return Err(SeedToolsError::tracker(&tracker.name, None, format!("'{} - {}' is already on {}: {}", details.artist, details.album, tracker.name, link)));
+    }
+
+    // Rip logs go along with the upload; the logchecker scores them first
+    let log_files: Vec<String> = WalkDir::new(input_path)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext.eq_ignore_ascii_case("log")))
+        .map(|entry| entry.path().to_string_lossy().to_string())
+        .collect();
+    let log_scores = if tracker.logchecker { check_logs(tracker, &log_files) } else { Vec::new() };
+
+    check_release_limits(input_path, "music", &config.limits)?;
+    // Torrents go to a folder per tracker, so they do not replace the Seedpool torrent of the same release
+    let torrent_file = {
+        let _slot = slots::acquire(&config.paths, &config.concurrency, Workload::Hashing)?;
+        let torrent_dir = Path::new(&config.paths.torrent_dir).join(&tracker.name);
+        let torrent_file = create_torrent(
+            input_path,
+            &torrent_dir.to_string_lossy(),
+            &tracker.announce_url,
+            &tools.mkbrr.to_string_lossy(),
+            None, // Keep the logs, cue sheets and artwork
+            &tracker.torrent,
+        )?;
+        // Gazelle trackers only accept torrents with their own source tag
+        let torrent = std::fs::read(&torrent_file).map_err(SeedToolsError::io(format!("Failed to read '{}'", torrent_file)))?;
+        let torrent = retag(&torrent, std::slice::from_ref(&tracker.announce_url), &tracker.source)?;
+        std::fs::write(&torrent_file, torrent).map_err(SeedToolsError::io(format!("Failed to write '{}'", torrent_file)))?;
+        torrent_file
+    };
+    checkpoint.torrent_file = Some(torrent_file.clone());
+    check_local_dupe(&config.paths, &tracker.name, &base_name, Some(&torrent_file), options.allow_reupload)?;
+
+    // The largest image in the folder is taken as the cover
+    let cover = WalkDir::new(input_path)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            entry.path().extension().and_then(|ext| ext.to_str()).is_some_and(|ext| {
+                ext.eq_ignore_ascii_case("jpg") || ext.eq_ignore_ascii_case("jpeg") || ext.eq_ignore_ascii_case("png")
+            })
+        })
+        .max_by_key(|entry| entry.metadata().map(|metadata| metadata.len()).unwrap_or(0));
+    let image = match (cover, imgbb_api_key.filter(|key| !key.is_empty())) {
+        (Some(cover), Some(api_key)) => match upload_to_imgbb(&cover.path().to_string_lossy(), api_key) {
+            Ok((url, _)) => Some(url),
+            Err(e) => {
+                warn!("Failed to upload the cover: {}", e);
+                None
+            }
+        },
+        (Some(_), None) => {
+            warn!("No ImgBB API key configured; uploading to {} without a cover.", tracker.name);
+            None
+        }
+        (None, _) => None,
+    };
+
+    let mut tags: Vec<String> = details
+        .genre
+        .split([',', ';', '/'])
+        .map(|tag| tag.trim().to_lowercase().replace(' ', "."))
+        .filter(|tag| !tag.is_empty() && tag != "unknown.genre")
+        .collect();
+    tags.extend(tracker.tags.iter().cloned());
+    tags.sort();
+    tags.dedup();
+
+    let album_desc = descriptions::music(&MusicRelease {
+        base_name: base_name.clone(),
+        artist: details.artist.clone(),
+        album: details.album.clone(),
+        year: details.year.clone(),
+        genre: details.genre.clone(),
+        audio: format!("{} {}", format, bitrate),
+        tracks: music_tracks(input_path, &details.artist),
+        cover_url: None,
+        custom_description: Some(tracker.description.clone()).filter(|description| !description.is_empty()),
+    });
+
+    let upload = GazelleUpload {
+        torrent_file: torrent_file.clone(),
+        artists: vec![details.artist.clone()],
+        title: details.album.clone(),
+        year: details.year.parse().ok(),
+        release_type: detect_release_type(&base_name, tracker.release_type),
+        media: tracker.media.clone(),
+        format,
+        bitrate,
+        record_label: details.label.clone(),
+        catalogue_number: details.catalog_number.clone(),
+        tags,
+        image,
+        album_desc,
+        release_desc: (!log_scores.is_empty()).then(|| format!("Logchecker: {}", log_scores.join(", "))),
+        log_files,
+    };
+    let torrent_url = submit_gazelle_upload(tracker, &upload, offline)?;
+    info!("Uploaded '{}' to {}: {}", base_name, tracker.name, torrent_url);
+    checkpoint.torrent_url = Some(torrent_url.clone());
+
+    let entry = HistoryEntry {
+        uploaded_at: chrono::Utc::now().to_rfc3339(),
+        tracker: tracker.name.clone(),
+        release_name: base_name,
+        input_path: input_path.to_string(),
+        torrent_id: torrent_url.rsplit('=').next().map(str::to_string),
+        info_hash: std::fs::read(&torrent_file).ok().and_then(|torrent| torrent_info_hash(&torrent).ok()),
+        ..Default::default()
+    };
+    if let Err(e) = history::record(&config.paths, &entry) {
+        warn!("Failed to record upload history: {}", e);
+    }
+
+    checkpoint.client_states = add_torrent_to_all_qbittorrent_instances(
+        &[torrent_file],
+        &config.qbittorrent,
+        &config.deluge,
+        input_path,
+        &config.paths,
+        offline,
+    )?;
+    Ok(())
+}
@@ -0,0 +1,203 @@
+// Release processing for each tracker: pre-flight checks, descriptions and the upload
+// pipelines. The functions below are the entry points for code that links against seed-tools;
+// the per-tracker modules stay public for the finer-grained steps.
+pub mod common;
+pub mod gazelle;
+pub mod seedpool;
+pub mod software;
+pub mod torrentleech;
+pub mod unit3d;
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use regex::Regex;
+use crate::error::SeedToolsError;
+use crate::offline::Offline;
+use crate::types::{Config, GazelleConfig, PathsConfig, PreflightCheckResult, SeedpoolConfig, TorrentLeechConfig, Unit3dConfig, UploadOverrides};
+use crate::utils::{generate_release_name, stage_release, unpack_video_release};
+
+/// The outcome of an upload per target name, e.g. `("Seedpool", Ok(()))`.
+pub type TargetResults = Vec<(String, Result<(), SeedToolsError>)>;
+
+/// A tracker to upload a release to.
+pub enum Target<'a> {
+    Seedpool(&'a SeedpoolConfig),
+    TorrentLeech(&'a TorrentLeechConfig),
+    Unit3d(&'a Unit3dConfig),
+    Gazelle(&'a GazelleConfig),
+}
+
+impl Target<'_> {
+    /// Name used in logs and partial success reports.
+    pub fn name(&self) -> String {
+        match self {
+            Target::Seedpool(_) => "Seedpool".to_string(),
+            Target::TorrentLeech(_) => "TorrentLeech".to_string(),
+            Target::Unit3d(tracker) => tracker.name.clone(),
+            Target::Gazelle(tracker) => tracker.name.clone(),
+        }
+    }
+}
+
+/// How an upload runs; the command line flags of a standard upload.
+#[derive(Default)]
+pub struct UploadOptions {
+    pub overrides: UploadOverrides,
+    pub imgbb_api_key: Option<String>,
+    pub edit: bool, // Prompt to review detected details before uploading (Seedpool)
+    pub fresh: bool, // Ignore checkpoints from earlier runs
+    pub allow_reupload: bool, // Skip the local upload history dupe check
+    pub offline: Option<Arc<Offline>>, // Record uploads and seed with the offline stand-ins instead
+}
+
+/// The external tools a release is processed with, as set in (or resolved into) `paths`.
+pub struct Tools {
+    pub ffmpeg: PathBuf,
+    pub ffprobe: PathBuf,
+    pub mkbrr: PathBuf,
+    pub mediainfo: PathBuf,
+}
+
+impl Tools {
+    pub fn from_paths(paths: &PathsConfig) -> Self {
+        Tools {
+            ffmpeg: PathBuf::from(&paths.ffmpeg),
+            ffprobe: PathBuf::from(&paths.ffprobe),
+            mkbrr: PathBuf::from(&paths.mkbrr),
+            mediainfo: PathBuf::from(&paths.mediainfo),
+        }
+    }
+}
+
+/// Checks a release against Seedpool without uploading it: detected names and IDs, dupes and
+/// the processing estimate. Nothing is written or added to the torrent clients.
+pub fn preflight(input_path: &str, config: &Config, seedpool_config: &SeedpoolConfig) -> Result<PreflightCheckResult, SeedToolsError> {
+    let tools = Tools::from_paths(&config.paths);
+    seedpool::preflight_check(input_path, config, seedpool_config, &tools.ffprobe, &tools.mediainfo)
+}
+
+/// Adds the Seedpool torrent of the exact dupe `preflight` found to the torrent clients, so the
+/// release is seeded instead of uploaded. Returns `false` when there is nothing to cross-seed
+/// or the local files do not match the torrent.
+pub fn cross_seed_dupe(input_path: &str, config: &Config, result: &PreflightCheckResult) -> Result<bool, SeedToolsError> {
+    match &result.dupe_download {
+        Some(download_link) => seedpool::cross_seed_dupe(input_path, config, &result.release_name, download_link),
+        None => Ok(false),
+    }
+}
+
+/// Uploads a release to one tracker.
+pub fn upload(input_path: &str, config: &mut Config, target: &Target, options: &UploadOptions) -> Result<(), SeedToolsError> {
+    let tools = Tools::from_paths(&config.paths);
+    match target {
+        Target::Seedpool(seedpool_config) => seedpool::process_seedpool_release(input_path, config, seedpool_config, &tools, options),
+        Target::TorrentLeech(torrentleech_config) => torrentleech::process_torrentleech_release(input_path, config, torrentleech_config, &tools, options),
+        Target::Unit3d(tracker) => unit3d::process_unit3d_release(input_path, config, tracker, &tools, options),
+        Target::Gazelle(tracker) => gazelle::process_gazelle_release(input_path, config, tracker, &tools, options),
+    }
+}
+
+/// Uploads a release to each target in turn, after unpacking it when `unpack_before_upload` is
+/// set, and returns the outcome per target name. A failed tracker does not stop the others.
+pub fn process_release(
+    input_path: &str,
+    config: &mut Config,
+    targets: &[Target],
+    options: &UploadOptions,
+) -> Result<TargetResults, SeedToolsError> {
+    // Rar sets are unpacked once here so every tracker uploads the extracted video
+    if config.unpack.unpack_before_upload {
+        unpack_video_release(input_path, &config.unpack)
+            .map_err(|e| SeedToolsError::tool("unrar", format!("Failed to unpack '{}': {}", input_path, e)))?;
+    }
+    let results: Vec<_> = targets.iter().map(|target| (target.name(), upload(input_path, config, target, options))).collect();
+    // Radarr/Sonarr hear about the release once, however many trackers took it
+    if results.iter().any(|(_, result)| result.is_ok()) {
+        let release_type = options.overrides.release_type.clone().unwrap_or_else(|| seedpool::determine_release_type_and_title(input_path).0);
+        crate::arr::notify_uploaded(&config.arr, input_path, &release_type);
+    }
+    Ok(results)
+}
+
+/// The season folders of a multi-season collection (`S01`, `Season 2`, `Show.S03.1080p...`),
+/// by season number. Episode folders (`S01E02`) are not seasons.
+pub fn season_folders(input_path: &str) -> Result<Vec<(u32, PathBuf)>, SeedToolsError> {
+    let season = Regex::new(r"(?i)(?:^|[. _-])(?:S|Season[. _-]?)(\d{1,3})(?:$|[. _-])").unwrap();
+    let episode = Regex::new(r"(?i)\bS\d{1,3}E\d{1,3}").unwrap();
+    let mut seasons: Vec<(u32, PathBuf)> = std::fs::read_dir(input_path)
+        .map_err(SeedToolsError::io(format!("Failed to read '{}'", input_path)))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_dir())
+        .filter_map(|path| {
+            let name = path.file_name()?.to_string_lossy().to_string();
+            if episode.is_match(&name) {
+                return None;
+            }
+            let number = season.captures(&name)?[1].parse().ok()?;
+            Some((number, path))
+        })
+        .collect();
+    seasons.sort();
+    Ok(seasons)
+}
+
+/// The release name of season `season` of the collection `collection_name`: its season range
+/// (`S01-S05`, `Seasons 1-5`, `Complete Series`) becomes `S02`, or `S02` is put before the
+/// year or resolution when the name has no range.
+pub fn season_release_name(collection_name: &str, season: u32) -> String {
+    let name = generate_release_name(collection_name);
+    let label = format!("S{:02}", season);
+    let range = Regex::new(r"(?i)\b(?:S\d{1,3}(?:-S?\d{1,3})?|Seasons?\.\d{1,3}(?:-\d{1,3})?|Complete(?:\.Series)?)\b").unwrap();
+    if range.is_match(&name) {
+        return range.replace(&name, label.as_str()).to_string();
+    }
+    let anchor = Regex::new(r"(?i)\b(?:19|20)\d{2}\b|\b\d{3,4}[pi]\b").unwrap();
+    match anchor.find(&name) {
+        Some(found) => format!("{}{}.{}", &name[..found.start()], label, &name[found.start()..]),
+        None => format!("{}.{}", name, label),
+    }
+}
+
+/// Uploads each season folder of a multi-season collection as its own season pack, with its
+/// season number set, instead of one boxset. Folders named only for their season (`S02`,
+/// `Season 2`) are staged under `staging_dir` with the collection's name, as the upload needs
+/// a title to look up. Returns the outcome per target and season, e.g. "Seedpool S02".
+pub fn process_per_season(
+    input_path: &str,
+    config: &mut Config,
+    targets: &[Target],
+    options: &UploadOptions,
+) -> Result<TargetResults, SeedToolsError> {
+    let seasons = season_folders(input_path)?;
+    if seasons.is_empty() {
+        return Err(SeedToolsError::Other(format!("No season folders (S01, Season 2, ...) in '{}'", input_path)));
+    }
+    let collection_name = Path::new(input_path).file_name().unwrap_or_default().to_string_lossy().to_string();
+    let bare = Regex::new(r"(?i)^(?:S|Season[. _-]?)\d{1,3}$").unwrap();
+    let mut results = Vec::new();
+    for (season, folder) in seasons {
+        let label = format!("S{:02}", season);
+        let folder_name = folder.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let season_input = if bare.is_match(&folder_name) {
+            let release_name = season_release_name(&collection_name, season);
+            if config.paths.staging_dir.is_none() {
+                let message = format!("Season folder '{}' has no release name; set paths.staging_dir to stage it as '{}'", folder_name, release_name);
+                results.extend(targets.iter().map(|target| (format!("{} {}", target.name(), label), Err(SeedToolsError::config(message.clone())))));
+                continue;
+            }
+            stage_release(&folder.to_string_lossy(), &release_name, &config.paths)?
+        } else {
+            folder.to_string_lossy().to_string()
+        };
+        log::info!("Uploading season {} of '{}' from '{}'", season, collection_name, season_input);
+
+        let mut overrides = options.overrides.clone();
+        overrides.season_number = Some(season);
+        overrides.episode_number = Some(0); // Season pack
+        let season_options = UploadOptions { overrides, imgbb_api_key: options.imgbb_api_key.clone(), offline: options.offline.clone(), ..*options };
+        for (tracker, result) in process_release(&season_input, config, targets, &season_options)? {
+            results.push((format!("{} {}", tracker, label), result));
+        }
+    }
+    Ok(results)
+}
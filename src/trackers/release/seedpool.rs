@@ -0,0 +1,1618 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+use std::fs;
+use crate::error::SeedToolsError;
+use crate::types::{Config, SeedpoolConfig};
+use crate::offline::{self, Offline};
+use crate::utils::{
+    generate_release_name, unpack_video_release, find_video_files, create_torrent, generate_mediainfo, generate_sample,
+    generate_screenshots, create_proof_image, stage_release, fetch_tmdb_id, generate_screenshots_imgbb, fetch_external_ids, generate_description,
+    add_torrent_to_all_qbittorrent_instances, prompt_upload_overrides, format_size, ffmpeg_file_arg, copy_to_cdn, total_size, torrent_info_hash, DetectedUploadDetails,
+    extract_subtitle_languages, find_external_subtitles, extract_audio_languages, tag_audio_languages, check_claimed_languages,
+    check_existing_samples, upload_existing_sample, ExistingSample, detect_disc, detect_source_type, parse_sport_event, normalize_event_date, probe_mkv_extras, render_file_tree,
+    capture_screenshots, fetch_episode_details, add_episode_title,
+};
+use crate::trackers::gazelle::format_and_bitrate;
+use regex::Regex;
+use log::{info, warn};
+use crate::limits::check_release_limits;
+use crate::torrent::verify_before_inject;
+use crate::slots::{self, Workload};
+use crate::estimate::{estimate_processing, ProcessingEstimate};
+use crate::types::{PreflightCheckResult, ProofConfig, ProofPlacement, SampleSource, SeedpoolIds};
+use crate::pipeline::{checkpoint_dir, seedpool_metadata_cache, PipelineStage, ReleaseCheckpoint};
+use crate::hooks::{run_hooks, HookPoint};
+use crate::trackers::seedpool::{
+    check_seedpool_dupes, download_torrent, extract_download_url, extract_torrent_id, find_by_fingerprint, find_seedpool_dupes, submit_upload, torrent_page_url, Seedpool,
+    TorrentMatch, UploadRequest,
+};
+use crate::history::{self, HistoryEntry};
+use crate::trackers::seedpool_metadata::load_metadata;
+use crate::irc::announce_upload;
+use crate::notifications::{notify, Notification};
+use crate::metrics;
+use crate::descriptions::{self, MusicRelease, MusicTrack};
+use super::common::check_local_dupe;
+use super::{Tools, UploadOptions};
+use walkdir::WalkDir;
+pub fn process_seedpool_release(
+    input_path: &str,
+    config: &mut Config,
+    seedpool_config: &SeedpoolConfig,
+    tools: &Tools,
+    options: &UploadOptions,
+) -> Result<(), SeedToolsError> {
+    let offline = options.offline.as_deref();
+    log::debug!("Processing release for input_path: {}", input_path);
+
+    // Check for music files early; process_music_release picks the MP3 or FLAC type itself
+    let music_extensions = ["mp3", "flac"];
+    let found_music_file = WalkDir::new(input_path).into_iter().filter_map(|e| e.ok()).any(|entry| {
+        entry
+            .path()
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|ext| music_extensions.contains(&ext.to_lowercase().as_str()))
+    });
+
+    if found_music_file {
+        log::debug!("Music release detected: {}", input_path);
+        let result = process_music_release(input_path, config, seedpool_config, &tools.mkbrr, &tools.ffmpeg, offline);
+        metrics::record_upload("seedpool", &result);
+        let checkpoint = ReleaseCheckpoint::new("seedpool", input_path);
+        notify(&config.notifications, &Notification::upload("Seedpool", &checkpoint, &result));
+        return result;
+    }
+
+    if !unpack_video_release(input_path, &config.unpack)? {
+        log::info!("No RAR archives to extract in the input path.");
+    }
+
+    // Resume from the last completed stage of an earlier failed run, if any
+    let checkpoint_dir = checkpoint_dir(&config.paths);
+    let mut checkpoint = ReleaseCheckpoint::load_or_new(&checkpoint_dir, "seedpool", input_path, options.fresh);
+    let result = run_seedpool_stages(
+        &mut checkpoint,
+        &checkpoint_dir,
+        input_path,
+        config,
+        seedpool_config,
+        tools,
+        options,
+    );
+    match &result {
+        Ok(()) => checkpoint.finish(&checkpoint_dir),
+        Err(e) => {
+            log::error!("Seedpool upload stopped after stage {:?}: {}", checkpoint.stage, e);
+            checkpoint.record_error(&checkpoint_dir, &e.to_string());
+        }
+    }
+    metrics::record_upload("seedpool", &result);
+    notify(&config.notifications, &Notification::upload("Seedpool", &checkpoint, &result));
+    result
+}
+
+/// Runs the upload stages that `checkpoint` has not completed yet, saving it after each one.
+fn run_seedpool_stages(
+    checkpoint: &mut ReleaseCheckpoint,
+    checkpoint_dir: &Path,
+    input_path: &str,
+    config: &mut Config,
+    seedpool_config: &SeedpoolConfig,
+    tools: &Tools,
+    options: &UploadOptions,
+) -> Result<(), SeedToolsError> {
+    let imgbb_api_key = options.imgbb_api_key.as_deref();
+    let overrides = &options.overrides;
+    let offline = options.offline.as_deref();
+    let base_name = Path::new(input_path)
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+
+    // --- Detected ---
+    if !checkpoint.completed(PipelineStage::Detected) {
+        // Determine release type and title
+        let (release_type, title, year, season_number, mut episode_number) =
+            determine_release_type_and_title(input_path);
+        // Adult uploads are only made on request and only where seedpool.yaml allows them
+        let release_type = match &overrides.release_type {
+            Some(forced) if forced == "adult" && !seedpool_config.adult.enabled => {
+                return Err(SeedToolsError::config("Adult uploads are turned off; set adult.enabled in seedpool.yaml"));
+            }
+            Some(forced) => forced.clone(),
+            None => release_type,
+        };
+
+        // Check for duplicates; offline runs have nothing to search
+        let dupe = match offline {
+            Some(_) => None,
+            None => check_seedpool_dupes(&base_name, &seedpool_config.general.api_key)?,
+        };
+        if let Some(download_link) = dupe {
+            log::info!("Duplicate found for '{}'. Downloading and adding to clients.", base_name);
+
+            let torrent_data = download_torrent(&download_link, offline)?;
+            verify_before_inject(&torrent_data, Path::new(input_path))?;
+            let torrent_file_path = Path::new(&config.paths.torrent_dir).join(format!("{}.torrent", base_name));
+            std::fs::write(&torrent_file_path, &torrent_data)
+                .map_err(SeedToolsError::io(format!("Failed to save '{}'", torrent_file_path.display())))?;
+
+            checkpoint.client_states = add_torrent_to_all_qbittorrent_instances(
+                &[torrent_file_path.to_string_lossy().to_string()],
+                &config.qbittorrent,
+                &config.deluge,
+                input_path,
+                &config.paths,
+                offline,
+            )?;
+            checkpoint.dupe_link = Some(download_link);
+            return Ok(());
+        }
+        let renamed = match offline {
+            Some(_) => None,
+            None => find_by_fingerprint(input_path, None, &seedpool_config.dupe_check, &seedpool_config.general.api_key)?,
+        };
+        if let Some(torrent) = renamed {
+            return Err(renamed_dupe_error(&base_name, &torrent, &seedpool_config.settings.upload_url));
+        }
+
+        // Season packs and boxsets go up as episode 0; movies carry no season or episode at all
+        if episode_number.is_none() && matches!(release_type.as_str(), "tv" | "boxset") {
+            log::warn!("Episode number is None. Adjusting to 0.");
+            episode_number = Some(0);
+        }
+
+        // Determine category and type IDs
+        let ids = &seedpool_config.ids;
+        let (category_id, type_id) = match release_type.as_str() {
+            "tv" | "movie" | "boxset" => (ids.category(&release_type), ids.type_id(&release_type)),
+            // Sports need their own category in ids.categories; the type falls back to TV's
+            "sport" => {
+                let type_id = match ids.type_id("sport") {
+                    0 => ids.type_id("tv"),
+                    id => id,
+                };
+                (ids.category("sport"), type_id)
+            }
+            // Adult releases have their own category; the type follows the source type below or movie's
+            "adult" => (ids.category("adult"), ids.type_id("movie")),
+            _ => (0, 0),
+        };
+        if (release_type == "sport" || release_type == "adult") && category_id == 0 && overrides.category_id.is_none() {
+            return Err(SeedToolsError::config(format!("No '{}' entry under ids.categories in seedpool.yaml; add one or pass --category", release_type)));
+        }
+
+        checkpoint.release_type = release_type;
+        checkpoint.title = title;
+        checkpoint.year = year;
+        checkpoint.season_number = season_number;
+        checkpoint.episode_number = episode_number;
+        checkpoint.category_id = category_id;
+        checkpoint.type_id = type_id;
+        checkpoint.advance(checkpoint_dir, PipelineStage::Detected)?;
+    }
+
+    // --- Analyzed ---
+    // Overrides and --options.edit are re-applied on resume as long as nothing was submitted yet
+    let reanalyze = !overrides.is_empty() || options.edit;
+    if !checkpoint.completed(PipelineStage::Analyzed)
+        || (reanalyze && !checkpoint.completed(PipelineStage::Submitted))
+    {
+        let _slot = slots::acquire(&config.paths, &config.concurrency, Workload::Metadata)?;
+        let release_type = checkpoint.release_type.clone();
+        let mut season_number = checkpoint.season_number;
+        let mut episode_number = checkpoint.episode_number;
+        let mut category_id = checkpoint.category_id;
+        let mut type_id = checkpoint.type_id;
+
+        // Fetch TMDB ID unless the user already provided one; Radarr/Sonarr know the releases they manage
+        let arr_ids = overrides.tmdb_id.is_none().then(|| crate::arr::lookup_ids(&config.arr, input_path, &release_type)).flatten();
+        let mut tmdb_id = match overrides.tmdb_id {
+            Some(id) => {
+                log::info!("Using TMDB ID override: {}", id);
+                id
+            }
+            None if release_type == "sport" => 0, // Sports events are not on TMDB
+            None if release_type == "adult" => 0, // Named from the file; nothing is looked up
+            None => match arr_ids.as_ref().and_then(|ids| ids.tmdb_id) {
+                Some(id) => id,
+                None => fetch_tmdb_id(&checkpoint.title, checkpoint.year.clone(), &config.general.tmdb_api_key, &release_type)?,
+            },
+        };
+
+        // Fetch external IDs; IMDb may know titles TMDB lacks
+        let (mut imdb_id, mut tvdb_id) = fetch_external_ids(tmdb_id, &release_type, &config.general.tmdb_api_key)
+            .unwrap_or((None, None));
+        if let Some(ids) = arr_ids {
+            imdb_id = ids.imdb_id.or(imdb_id);
+            tvdb_id = ids.tvdb_id.or(tvdb_id);
+        }
+        imdb_id = crate::imdb::fill_missing(imdb_id, tmdb_id, &checkpoint.title, checkpoint.year.as_deref(), &release_type, &config.imdb);
+        let mut release_name = generate_release_name(&base_name);
+
+        // Let the user review and correct the detected details before doing any heavy work
+        let overrides = if options.edit {
+            prompt_upload_overrides(
+                &DetectedUploadDetails {
+                    release_name: release_name.clone(),
+                    tmdb_id,
+                    imdb_id: imdb_id.clone(),
+                    tvdb_id,
+                    season_number,
+                    episode_number,
+                    category_id,
+                    type_id,
+                },
+                overrides,
+            )?
+        } else {
+            overrides.clone()
+        };
+        let named_by_user = overrides.release_name.is_some();
+        if release_type == "sport" && !named_by_user {
+            release_name = normalize_event_date(&release_name);
+        }
+
+        if !overrides.is_empty() {
+            log::info!("Applying upload overrides: {:?}", overrides);
+            if let Some(id) = overrides.tmdb_id
+                && id != tmdb_id
+            {
+                tmdb_id = id;
+                // Refresh the external IDs for the new TMDB entry; explicit overrides still win below
+                (imdb_id, tvdb_id) = fetch_external_ids(tmdb_id, &release_type, &config.general.tmdb_api_key)
+                    .unwrap_or((None, None));
+            }
+            if let Some(name) = overrides.release_name {
+                release_name = name;
+            }
+            if overrides.imdb_id.is_some() {
+                imdb_id = overrides.imdb_id;
+            }
+            if overrides.tvdb_id.is_some() {
+                tvdb_id = overrides.tvdb_id;
+            }
+            season_number = overrides.season_number.or(season_number);
+            episode_number = overrides.episode_number.or(episode_number);
+            category_id = overrides.category_id.unwrap_or(category_id);
+            type_id = overrides.type_id.unwrap_or(type_id);
+        }
+
+        // Single episodes are headed with their TMDB title and air date; a failed lookup only costs the header
+        let episode = match (season_number, episode_number) {
+            (Some(season), Some(episode)) if release_type == "tv" && episode > 0 && tmdb_id != 0 => {
+                match fetch_episode_details(tmdb_id, season, episode, &config.general.tmdb_api_key) {
+                    Ok(details) => Some((details.header(season, episode), details.title)),
+                    Err(e) => {
+                        log::warn!("No episode title for S{:02}E{:02}: {}", season, episode, e);
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+        if let Some((_, episode_title)) = episode.as_ref().filter(|_| seedpool_config.settings.episode_title_in_name && !named_by_user) {
+            release_name = add_episode_title(&release_name, episode_title);
+        }
+
+        let (video_files, nfo_file) = find_video_files(input_path, &config.paths, &seedpool_config.settings, seedpool_config.torrent.exclusions(&config.exclusions))?;
+        if video_files.is_empty() {
+            return Err(SeedToolsError::Metadata("No valid video files detected.".to_string()));
+        }
+
+        // Samples shipped with the release are checked, not trusted by name
+        let (video_files, existing_samples) = check_existing_samples(input_path, &video_files, &tools.ffprobe.to_string_lossy());
+        checkpoint.existing_sample = existing_samples.into_iter().find_map(|sample| match sample {
+            ExistingSample::Reuse(file) => Some(file),
+            _ => None,
+        });
+
+        // Generate mediainfo; discs are described by their main playlist or IFO
+        let disc = detect_disc(input_path);
+        let mediainfo_file = disc.as_ref().map_or(video_files[0].as_str(), |disc| disc.mediainfo_file.as_str());
+        let mediainfo_output = generate_mediainfo(mediainfo_file, &tools.mediainfo.to_string_lossy())?;
+        // Disc, remux, encode, WEB-DL, WEBRip and HDTV get their own type when seedpool.yaml names one
+        let (source_type, reason) = detect_source_type(&release_name, &mediainfo_output, disc.is_some());
+        info!("Source type: {} ({})", source_type, reason);
+        if let Some(source_type_id) = seedpool_config.ids.types.get(source_type) {
+            type_id = overrides.type_id.unwrap_or(*source_type_id);
+        }
+
+        // Tag multi-language audio in the generated name; a name the user set is left as is
+        let audio_languages = extract_audio_languages(&mediainfo_output);
+        for warning in check_claimed_languages(&release_name, &audio_languages) {
+            log::warn!("{}", warning);
+        }
+        if !named_by_user {
+            release_name = tag_audio_languages(&release_name, &audio_languages);
+        }
+
+        checkpoint.season_number = season_number;
+        checkpoint.episode_number = episode_number;
+        checkpoint.episode_title = episode.map(|(header, _)| header);
+        checkpoint.category_id = category_id;
+        checkpoint.type_id = type_id;
+        checkpoint.release_name = release_name;
+        checkpoint.tmdb_id = tmdb_id;
+        checkpoint.imdb_id = imdb_id;
+        checkpoint.tvdb_id = tvdb_id;
+        checkpoint.video_files = video_files;
+        checkpoint.nfo_file = nfo_file;
+        checkpoint.mediainfo = mediainfo_output;
+        // Re-analyzing a resumed release keeps the later stages it already completed
+        let stage = checkpoint.stage.map_or(PipelineStage::Analyzed, |done| done.max(PipelineStage::Analyzed));
+        checkpoint.advance(checkpoint_dir, stage)?;
+    }
+
+    // --- TorrentCreated ---
+    // Recreate the torrent if the file from an earlier run is gone
+    let torrent_exists = checkpoint.torrent_file.as_deref().is_some_and(|f| Path::new(f).exists());
+    if !checkpoint.completed(PipelineStage::TorrentCreated) || !torrent_exists {
+        // Disc structures are uploaded complete, menus and artwork included
+        let stripshit_from_videos = seedpool_config.settings.stripshit_from_videos && detect_disc(input_path).is_none();
+        check_release_limits(input_path, &checkpoint.release_type, &config.limits)?;
+        run_hooks(&config.hooks, HookPoint::PreTorrent, checkpoint)?;
+        let _slot = slots::acquire(&config.paths, &config.concurrency, Workload::Hashing)?;
+
+        // Generate torrent file, from a copy named after the release when staging is set up
+        let torrent_input = stage_release(input_path, &checkpoint.release_name, &config.paths)?;
+        checkpoint.staged_path = (torrent_input != input_path).then(|| torrent_input.clone());
+        let torrent_file = create_torrent(
+            &torrent_input,
+            &config.paths.torrent_dir,
+            &seedpool_config.settings.announce_url,
+            &tools.mkbrr.to_string_lossy(),
+            stripshit_from_videos.then_some(&config.exclusions),
+            &seedpool_config.torrent,
+        )?;
+        checkpoint.torrent_file = Some(torrent_file);
+        if !checkpoint.completed(PipelineStage::TorrentCreated) {
+            checkpoint.advance(checkpoint_dir, PipelineStage::TorrentCreated)?;
+        } else {
+            checkpoint.save(checkpoint_dir)?;
+        }
+    }
+    let torrent_files = vec![checkpoint.torrent_file.clone().unwrap_or_default()];
+
+    // Catch re-uploads before the screenshots are made; the tracker's dupe API can miss renamed releases
+    if !checkpoint.completed(PipelineStage::Submitted) {
+        check_local_dupe(&config.paths, "seedpool", &checkpoint.release_name, checkpoint.torrent_file.as_deref(), options.allow_reupload)?;
+        if seedpool_config.dupe_check.info_hash && offline.is_none() {
+            let torrent = fs::read(&torrent_files[0]).map_err(SeedToolsError::io(format!("Failed to read '{}'", torrent_files[0])))?;
+            let info_hash = torrent_info_hash(&torrent)?;
+            if let Some(torrent) = find_by_fingerprint(input_path, Some(&info_hash), &seedpool_config.dupe_check, &seedpool_config.general.api_key)? {
+                return Err(renamed_dupe_error(&checkpoint.release_name, &torrent, &seedpool_config.settings.upload_url));
+            }
+        }
+    }
+
+    // --- ImagesUploaded ---
+    if !checkpoint.completed(PipelineStage::ImagesUploaded) {
+        let _slot = slots::acquire(&config.paths, &config.concurrency, Workload::Images)?;
+        let video_file = checkpoint
+            .video_files
+            .first()
+            .ok_or_else(|| SeedToolsError::Metadata("No valid video files detected.".to_string()))?;
+
+        // Generate screenshots using ImgBB or Seedpool CDN
+        let blur_thumbnails = checkpoint.release_type == "adult" && seedpool_config.adult.blur_thumbnails;
+        let imgbb_fallback = config.imgbb.as_ref().is_none_or(|imgbb| imgbb.fallback);
+        let imgbb_images = match imgbb_api_key {
+            Some("") => {
+                log::warn!("ImgBB API key is empty. Falling back to Seedpool CDN for screenshots.");
+                None
+            }
+            Some(api_key) => match generate_screenshots_imgbb(video_file, &tools.ffmpeg, &tools.ffprobe, api_key, blur_thumbnails) {
+                Ok(images) => Some(images),
+                Err(e) if imgbb_fallback && e.status() == Some(429) => {
+                    log::warn!("{}. Falling back to Seedpool CDN for screenshots.", e);
+                    None
+                }
+                Err(e) => return Err(e),
+            },
+            None => None,
+        };
+        let (screenshots, thumbnails) = if let Some(images) = imgbb_images {
+            images
+        } else {
+            generate_screenshots(
+                video_file,
+                &config.paths.screenshots_dir,
+                tools,
+                &seedpool_config.screenshots,
+                &generate_release_name(&base_name),
+                blur_thumbnails,
+                offline,
+            )?
+        };
+
+        let sample = &seedpool_config.sample;
+        let existing_sample = checkpoint.existing_sample.as_ref().filter(|_| sample.source != SampleSource::Generate);
+        let sample_url = if imgbb_api_key.is_some() && !imgbb_api_key.unwrap_or("").is_empty() {
+            String::new()
+        } else if !sample.enabled {
+            info!("Samples are turned off in seedpool.yaml.");
+            String::new()
+        } else if detect_disc(input_path).is_some() {
+            info!("Disc release; uploading without a sample.");
+            String::new()
+        } else if let Some(existing_sample) = existing_sample {
+            upload_existing_sample(
+                existing_sample,
+                &config.paths.screenshots_dir,
+                &seedpool_config.screenshots.remote_path,
+                &seedpool_config.screenshots.image_path,
+                &seedpool_config.screenshots.file_name,
+                &base_name,
+                offline,
+            )?
+        } else if sample.source == SampleSource::Existing {
+            info!("No usable sample shipped with the release; uploading without one.");
+            String::new()
+        } else {
+            generate_sample(
+                video_file,
+                &config.paths.screenshots_dir,
+                &seedpool_config.screenshots,
+                tools,
+                &base_name,
+                sample,
+                offline,
+            )?
+        };
+
+        checkpoint.proof_url = if seedpool_config.proof.enabled {
+            // Seedpool proofs default to the screenshot CDN and the account's user name
+            let proof = ProofConfig {
+                remote_path: Some(seedpool_config.proof.remote_path.clone().unwrap_or_else(|| seedpool_config.screenshots.remote_path.clone())),
+                url: Some(seedpool_config.proof.url.clone().unwrap_or_else(|| seedpool_config.screenshots.image_path.clone())),
+                username: Some(seedpool_config.proof.username.clone().unwrap_or_else(|| seedpool_config.general.username.clone())),
+                ..seedpool_config.proof.clone()
+            };
+            let proof_url = create_proof_image(video_file, &config.paths.screenshots_dir, tools, &proof, &checkpoint.release_name, offline)?;
+            info!("Proof image uploaded: {}", proof_url);
+            Some(proof_url)
+        } else {
+            None
+        };
+
+        checkpoint.screenshots = screenshots;
+        checkpoint.thumbnails = thumbnails;
+        checkpoint.sample_url = sample_url;
+        run_hooks(&config.hooks, HookPoint::PostScreenshots, checkpoint)?;
+        checkpoint.advance(checkpoint_dir, PipelineStage::ImagesUploaded)?;
+    }
+
+    // --- Submitted ---
+    let mut submitted_now = false; // Only announce torrents this run submitted
+    if !checkpoint.completed(PipelineStage::Submitted) {
+        let resolution_id = get_seedpool_resolution_id(input_path, &seedpool_config.ids);
+
+        // Generate description
+        // Embedded tracks of the first video, then subtitle files shipped alongside
+        let mut subtitles = extract_subtitle_languages(&checkpoint.mediainfo);
+        subtitles.extend(find_external_subtitles(input_path));
+        subtitles.dedup();
+        // Chapters and attachments only say something for a single film or episode
+        let single_video = matches!(checkpoint.release_type.as_str(), "movie" | "tv") && checkpoint.video_files.len() == 1;
+        let mkv_extras = checkpoint
+            .video_files
+            .first()
+            .filter(|_| single_video)
+            .map(|video_file| probe_mkv_extras(video_file, &tools.ffprobe.to_string_lossy()))
+            .filter(|extras| !extras.is_empty());
+        // Season packs and other multi-file releases show what they hold
+        let file_tree = if single_video { String::new() } else { render_file_tree(input_path) };
+        let description = generate_description(
+            &checkpoint.screenshots,
+            &checkpoint.sample_url,
+            &subtitles,
+            mkv_extras.as_ref(),
+            &file_tree,
+            Some(&seedpool_config.settings.custom_description_for(&checkpoint.release_type, &checkpoint.release_name, &checkpoint.title)),
+            None,
+        );
+        let description = match &checkpoint.proof_url {
+            Some(proof_url) if seedpool_config.proof.placement == ProofPlacement::Spoiler => {
+                format!("{}\n[spoiler=Proof][img]{}[/img][/spoiler]", description, proof_url)
+            }
+            _ => description,
+        };
+        let description = match &checkpoint.episode_title {
+            Some(header) => format!("[center][b]{}[/b][/center]\n\n{}", header, description),
+            None => description,
+        };
+
+        run_hooks(&config.hooks, HookPoint::PreUpload, checkpoint)?;
+
+        // Upload to Seedpool
+        let seedpool = Seedpool {
+            upload_url: seedpool_config.settings.upload_url.clone(),
+            api_key: seedpool_config.general.api_key.clone(),
+            metadata_cache: seedpool_metadata_cache(&config.paths),
+            flags: seedpool_config.upload_flags.clone(),
+            ids: seedpool_config.ids.clone(),
+        };
+        let response_text = offline::tracker_or(offline, &seedpool).upload(UploadRequest {
+            torrent_file: torrent_files[0].clone(),
+            name: checkpoint.release_name.clone(),
+            category_id: checkpoint.category_id,
+            type_id: checkpoint.type_id,
+            resolution_id: Some(resolution_id),
+            tmdb_id: checkpoint.tmdb_id,
+            imdb_id: checkpoint.imdb_id.clone(),
+            tvdb_id: checkpoint.tvdb_id.unwrap_or(0),
+            season_number: checkpoint.season_number,
+            episode_number: checkpoint.episode_number,
+            description: Some(description),
+            mediainfo: Some(checkpoint.mediainfo.clone()),
+            nfo_file: checkpoint.nfo_file.clone(),
+            ..Default::default()
+        })?;
+        checkpoint.torrent_url = extract_torrent_id(&response_text)
+            .ok()
+            .and_then(|id| torrent_page_url(&seedpool_config.settings.upload_url, &id));
+        checkpoint.advance(checkpoint_dir, PipelineStage::Submitted)?;
+        verify_and_record_upload(config, checkpoint, &torrent_files[0], &response_text, offline);
+        run_hooks(&config.hooks, HookPoint::PostUpload, checkpoint)?;
+        submitted_now = true;
+    }
+
+    // --- Injected ---
+    // Add torrent to clients and keep what they report for the upload notification
+    checkpoint.client_states = add_torrent_to_all_qbittorrent_instances(
+        &torrent_files,
+        &config.qbittorrent,
+        &config.deluge,
+        checkpoint.staged_path.as_deref().unwrap_or(input_path),
+        &config.paths,
+        offline,
+    )?;
+    checkpoint.advance(checkpoint_dir, PipelineStage::Injected)?;
+
+    // Announce fresh uploads on IRC; a failed announcement does not fail the upload
+    if submitted_now {
+        let link = checkpoint.torrent_url.clone().unwrap_or_default();
+        let category = load_metadata(
+            &seedpool_config.settings.upload_url,
+            &seedpool_config.general.api_key,
+            &seedpool_metadata_cache(&config.paths),
+        )
+        .ok()
+        .and_then(|metadata| metadata.categories.into_iter().find(|c| c.id == checkpoint.category_id))
+        .map(|category| category.name)
+        .unwrap_or_else(|| checkpoint.category_id.to_string());
+        if let Err(e) = announce_upload(
+            &seedpool_config.general,
+            &checkpoint.release_name,
+            &category,
+            &format_size(total_size(input_path)),
+            &link,
+        ) {
+            warn!("IRC upload announcement failed: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Re-downloads the torrent Seedpool now serves, compares its infohash with the local one and
+/// records both in the upload history. Problems are only logged; the upload already went through.
+pub fn verify_and_record_upload(config: &Config, checkpoint: &ReleaseCheckpoint, torrent_file: &str, response_text: &str, offline: Option<&Offline>) {
+    let info_hash = fs::read(torrent_file)
+        .map_err(SeedToolsError::io(format!("Failed to read '{}'", torrent_file)))
+        .and_then(|torrent| torrent_info_hash(&torrent));
+    let tracker_info_hash = extract_download_url(response_text)
+        .ok_or_else(|| SeedToolsError::tracker("Seedpool", None, "No download link in the upload response"))
+        .and_then(|url| download_torrent(&url, offline))
+        .and_then(|torrent| torrent_info_hash(&torrent));
+    match (&info_hash, &tracker_info_hash) {
+        (Ok(local), Ok(remote)) if local == remote => info!("Verified upload: Seedpool serves infohash {}", local),
+        (Ok(local), Ok(remote)) => warn!("Seedpool modified the torrent: local infohash {}, tracker infohash {}", local, remote),
+        (Err(e), _) | (_, Err(e)) => warn!("Could not verify the uploaded torrent: {}", e),
+    }
+
+    let entry = HistoryEntry {
+        uploaded_at: chrono::Utc::now().to_rfc3339(),
+        tracker: "seedpool".to_string(),
+        release_name: checkpoint.release_name.clone(),
+        input_path: checkpoint.input_path.clone(),
+        category_id: Some(checkpoint.category_id),
+        torrent_id: extract_torrent_id(response_text).ok(),
+        info_hash: info_hash.ok(),
+        tracker_info_hash: tracker_info_hash.ok(),
+        response: Some(response_text.to_string()),
+        ..Default::default()
+    };
+    if let Err(e) = history::record(&config.paths, &entry) {
+        warn!("Failed to record upload history: {}", e);
+    }
+}
+
+pub fn determine_release_type_and_title(input_path: &str) -> (String, String, Option<String>, Option<u32>, Option<u32>) {
+    let base_name = Path::new(input_path)
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    log::debug!("Base name extracted: {}", base_name);
+
+    let season_episode_regex = Regex::new(r"(?i)S(\d{2})E(\d{2})").unwrap();
+    let season_only_regex = Regex::new(r"(?i)S(\d{2})").unwrap();
+    let boxset_regex = Regex::new(r"(?i)\b(boxset|complete|collection)\b").unwrap();
+    let year_regex = Regex::new(r"\b(19|20)\d{2}\b").unwrap();
+
+    // Sports events are named by date or round and looked up nowhere
+    if let Some(sport) = parse_sport_event(&base_name) {
+        log::debug!("Matched sports event: {:?}", sport);
+        return ("sport".to_string(), sport.event, sport.year, None, None);
+    }
+
+    let mut release_type = "unknown".to_string();
+    let mut season_number = None;
+    let mut episode_number = None;
+
+    if let Some(captures) = season_episode_regex.captures(&base_name) {
+        log::debug!("Matched SxxEyy pattern: {:?}", captures);
+        release_type = "tv".to_string();
+        season_number = captures.get(1).and_then(|m| m.as_str().parse::<u32>().ok());
+        episode_number = captures.get(2).and_then(|m| m.as_str().parse::<u32>().ok());
+    } else if let Some(captures) = season_only_regex.captures(&base_name) {
+        log::debug!("Matched Sxx pattern: {:?}", captures);
+        release_type = "tv".to_string();
+        season_number = captures.get(1).and_then(|m| m.as_str().parse::<u32>().ok());
+    } else if boxset_regex.is_match(&base_name) {
+        log::debug!("Matched boxset keywords in base_name: {}", base_name);
+        release_type = "boxset".to_string();
+        season_number = Some(1);
+        episode_number = Some(0);
+    } else if year_regex.is_match(&base_name) {
+        log::debug!("Matched year pattern in base_name: {}", base_name);
+        release_type = "movie".to_string();
+    }
+
+    let title = if let Some(season_match) = season_episode_regex.find(&base_name) {
+        base_name[..season_match.start()].trim().to_string()
+    } else if let Some(season_match) = season_only_regex.find(&base_name) {
+        base_name[..season_match.start()].trim().to_string()
+    } else if let Some(boxset_match) = boxset_regex.find(&base_name) {
+        base_name[..boxset_match.start()].trim().to_string()
+    } else if let Some(year_match) = year_regex.find(&base_name) {
+        base_name[..year_match.start()].trim().to_string()
+    } else {
+        base_name.trim().to_string()
+    };
+
+    let cleaned_title = title.replace(['.', '_'], " ").trim().to_string();
+
+    let year = year_regex
+        .captures(&base_name)
+        .and_then(|caps| caps.get(0).map(|m| m.as_str().to_string()));
+
+    log::debug!(
+        "determine_release_type_and_title: release_type={}, title={}, year={:?}, season_number={:?}, episode_number={:?}",
+        release_type, cleaned_title, year, season_number, episode_number
+    );
+
+    (release_type, cleaned_title, year, season_number, episode_number)
+}
+
+pub fn process_music_release(
+    input_path: &str,
+    config: &Config,
+    seedpool_config: &SeedpoolConfig,
+    mkbrr_path: &Path,
+    ffmpeg_path: &Path,
+    offline: Option<&Offline>,
+) -> Result<(), SeedToolsError> {
+    log::debug!("Processing music release for input_path: {}", input_path);
+
+    // Determine category_id and type_id
+    let category_id = seedpool_config.ids.category("music");
+    let mut type_id = 0;
+
+    let music_extensions = ["mp3", "flac"];
+    let mut found_music_file = false;
+
+    // Use WalkDir to recursively search for music files
+    for entry in WalkDir::new(input_path).into_iter().filter_map(|e| e.ok()) {
+        if let Some(ext) = entry.path().extension().and_then(|e| e.to_str())
+            && music_extensions.contains(&ext.to_lowercase().as_str())
+        {
+            found_music_file = true;
+            match ext.to_lowercase().as_str() {
+                "mp3" => {
+                    type_id = seedpool_config.ids.type_id("mp3");
+                }
+                "flac" => {
+                    type_id = seedpool_config.ids.type_id("flac");
+                }
+                _ => {}
+            }
+            break; // Exit the loop once a valid music file is found
+        }
+    }
+
+    if !found_music_file {
+        return Err(SeedToolsError::Metadata("No valid music files detected (mp3 or flac).".to_string()));
+    }
+
+    let details = read_music_details(input_path)?;
+    let artist_global = details.artist.clone();
+    let album_meta = details.album.clone();
+    let genre = details.genre.clone();
+    let extracted_year = details.year.clone();
+    let audio_format = details.format.clone();
+    let bit_depth = details.bit_depth.clone();
+    let sampling_rate = details.sampling_rate.clone();
+
+    let sampling_rate_khz = if sampling_rate.ends_with("kHz") {
+        sampling_rate.clone() // Already in kHz format
+    } else if let Ok(rate) = sampling_rate.parse::<f64>() {
+        format!("{:.1} kHz", rate / 1000.0) // Convert Hz to kHz
+    } else {
+        "Unknown".to_string()
+    };
+
+    let audio_info = if bit_depth == "Unknown" || sampling_rate_khz == "Unknown" {
+        format!("{} / {}", audio_format, sampling_rate_khz)
+    } else {
+        format!("{} {} bit / {}", audio_format, bit_depth, sampling_rate_khz)
+    };
+
+    // Find the largest image in the folder or subfolders
+    let largest_image = WalkDir::new(input_path)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            if let Some(ext) = entry.path().extension().and_then(|e| e.to_str()) {
+                ext.eq_ignore_ascii_case("jpg") || ext.eq_ignore_ascii_case("png")
+            } else {
+                false
+            }
+        })
+        .max_by_key(|entry| entry.metadata().map(|m| m.len()).unwrap_or(0));
+
+    let (album_cover_path, album_cover_url) = if let Some(image) = largest_image {
+        // Sanitize the input folder/file name to make it URL-friendly
+        let sanitized_name = Path::new(input_path)
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .replace(' ', "_")
+            .replace(|c: char| !c.is_alphanumeric() && c != '-' && c != '_', "");
+
+        let album_cover_name = format!("{}.jpg", sanitized_name);
+        let album_cover_path = Path::new(input_path).join(&album_cover_name);
+        fs::copy(image.path(), &album_cover_path)
+            .map_err(SeedToolsError::io("Failed to copy album cover"))?;
+
+        // Set permissions to 777 for the album cover
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            fs::set_permissions(&album_cover_path, fs::Permissions::from_mode(0o777))
+                .map_err(SeedToolsError::io(format!("Failed to set permissions for album cover '{}'", album_cover_path.display())))?;
+        }
+
+        // Upload the album cover via SCP
+        if let Err(e) = copy_to_cdn(&album_cover_path, &seedpool_config.screenshots.remote_path, offline) {
+            log::warn!("Failed to upload album cover via SCP: {}", e);
+        }
+
+        // Generate the public-facing URL for the album cover
+        let album_cover_url = format!(
+            "{}/{}",
+            seedpool_config.screenshots.image_path, // Base URL
+            album_cover_name
+        );
+
+        (Some(album_cover_path), Some(album_cover_url))
+    } else {
+        log::warn!("No valid album cover found in the folder.");
+        (None, None) // Proceed without an album cover
+    };
+
+    // Generate the torrent file
+    check_release_limits(input_path, "music", &config.limits)?;
+    let base_name = Path::new(input_path)
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    let torrent_file = create_torrent(
+        input_path, // Pass the input path directly
+        &config.paths.torrent_dir,
+        &seedpool_config.settings.announce_url,
+        &mkbrr_path.to_string_lossy(),
+        Some(&config.exclusions), // Enable filtering for Standard Upload Mode
+        &seedpool_config.torrent,
+    )?;
+
+    // Generate the BBCode description
+    let description = descriptions::music(&MusicRelease {
+        base_name: base_name.clone(),
+        tracks: music_tracks(input_path, &artist_global),
+        custom_description: Some(seedpool_config.settings.custom_description_for("music", &base_name, &format!("{} - {}", artist_global, album_meta))),
+        artist: artist_global,
+        album: album_meta,
+        year: extracted_year,
+        genre,
+        audio: audio_info,
+        cover_url: album_cover_url,
+    });
+
+    // Prepare the upload form
+    let upload = UploadRequest {
+        torrent_file: torrent_file.clone(),
+        name: base_name.clone(),
+        category_id,
+        type_id,
+        description: Some(description), // Add the generated BBCode description
+        ..Default::default()
+    }
+    .with_flags(&seedpool_config.upload_flags);
+
+    // Send the upload request
+    let response_text = submit_upload(
+        &seedpool_config.settings.upload_url,
+        Some(&seedpool_config.general.api_key),
+        &upload,
+        offline,
+    )?;
+
+    // Extract the torrent ID from the response
+    let torrent_id = extract_torrent_id(&response_text)?;
+
+    // Create a torrent cover using FFmpeg
+    if let Some(album_cover_path) = album_cover_path {
+        let torrent_cover_path = album_cover_path.with_file_name(format!("torrent-cover_{}.jpg", torrent_id));
+        let ffmpeg_command = Command::new(ffmpeg_path)
+            .args(["-y", "-i"])
+            .arg(ffmpeg_file_arg(&album_cover_path.to_string_lossy()))
+            .args(["-vf", "scale=320:-1", "-q:v", "1"])
+            .arg(ffmpeg_file_arg(&torrent_cover_path.to_string_lossy()))
+            .output()
+            .map_err(|e| SeedToolsError::tool_spawn("ffmpeg", e))?;
+
+        if !ffmpeg_command.status.success() {
+            return Err(SeedToolsError::tool("ffmpeg", "Failed to create torrent cover with FFmpeg."));
+        }
+        crate::retention::track(crate::retention::Artifact::Cover, &torrent_cover_path);
+
+        // Set permissions to 777 for the torrent cover
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&torrent_cover_path, fs::Permissions::from_mode(0o777))
+                .map_err(SeedToolsError::io(format!("Failed to set permissions for torrent cover '{}'", torrent_cover_path.display())))?;
+        }
+
+        // Upload the torrent cover via SCP
+        let remote_albumcovers_path = format!("{}/albumcovers", seedpool_config.screenshots.remote_path);
+        copy_to_cdn(Path::new(&torrent_cover_path), &remote_albumcovers_path, offline)
+            .map_err(|e| e.context("Failed to upload torrent cover via SCP"))?;
+    } else {
+        log::warn!("No album cover path provided. Skipping torrent cover creation.");
+    }
+
+    log::info!("Music release successfully uploaded: {}", base_name);
+
+    // Add torrent to all qBittorrent instances
+    add_torrent_to_all_qbittorrent_instances(
+        std::slice::from_ref(&torrent_file), // Use the torrent_file directly
+        &config.qbittorrent,
+        &config.deluge,
+        input_path,
+        &config.paths,
+        offline,
+    )?;
+
+    Ok(())
+}
+
+/// Tags of a music release, read with mediainfo from its first MP3 or FLAC file.
+pub struct MusicDetails {
+    pub artist: String,
+    pub album: String,
+    pub genre: String,
+    pub year: String, // "Unknown" when the tags have no date
+    pub format: String, // mediainfo's name, e.g. "FLAC" or "MPEG Audio"
+    pub bit_depth: String,
+    pub sampling_rate: String,
+    pub bit_rate: String, // e.g. "320 kb/s"
+    pub bit_rate_mode: String, // "Constant" or "Variable"
+    pub encoding_settings: String, // LAME settings like "-V 0", empty for FLAC
+    pub label: Option<String>,
+    pub catalog_number: Option<String>,
+}
+
+pub fn read_music_details(input_path: &str) -> Result<MusicDetails, SeedToolsError> {
+    // Find the first audio file in the folder or subfolders
+    let first_file = WalkDir::new(input_path)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path().to_path_buf())
+        .find(|path| {
+            if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+                ext.eq_ignore_ascii_case("mp3") || ext.eq_ignore_ascii_case("flac")
+            } else {
+                false
+            }
+        })
+        .ok_or_else(|| SeedToolsError::Metadata("No valid music files found in the folder.".to_string()))?;
+
+    // Extract metadata from the first file
+    let metadata = parse_mediainfo_log(&first_file);
+    let field = |key: &str, default: &str| metadata.get(key).cloned().unwrap_or_else(|| default.to_string());
+
+    let recorded_date = field("Recorded date", "");
+    let year = recorded_date
+        .chars()
+        .filter(|c| c.is_numeric())
+        .collect::<String>()
+        .get(0..4)
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| "Unknown".to_string());
+
+    Ok(MusicDetails {
+        artist: field("Performer", "Unknown Artist"),
+        album: field("Album", "Unknown Album"),
+        genre: field("Genre", "Unknown Genre"),
+        year,
+        format: field("Format", "Unknown Format"),
+        bit_depth: field("Bit depth", "Unknown"),
+        sampling_rate: field("Sampling rate", "Unknown"),
+        bit_rate: field("Bit rate", ""),
+        bit_rate_mode: field("Bit rate mode", ""),
+        encoding_settings: field("Encoding settings", ""),
+        label: metadata.get("Label").or_else(|| metadata.get("Publisher")).cloned(),
+        catalog_number: metadata.get("Catalog number").cloned(),
+    })
+}
+
+/// The MP3 and FLAC tracks in `input_path` and its subfolders, as the music description lists
+/// them; tracks without a performer tag are credited to `artist`.
+pub fn music_tracks(input_path: &str, artist: &str) -> Vec<MusicTrack> {
+    WalkDir::new(input_path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|entry| {
+            if let Some(ext) = entry.path().extension().and_then(|e| e.to_str()) {
+                ext.eq_ignore_ascii_case("mp3") || ext.eq_ignore_ascii_case("flac")
+            } else {
+                false
+            }
+        })
+        .map(|entry| {
+            let path = entry.path();
+            let metadata = parse_mediainfo_log(path);
+            let field = |name: &str| metadata.get(name).cloned().unwrap_or_else(|| "Unknown".to_string());
+            MusicTrack {
+                artist: metadata.get("Performer").cloned().unwrap_or_else(|| artist.to_string()),
+                title: metadata.get("Track name").cloned().unwrap_or_else(|| "Unknown Title".to_string()),
+                duration: field("Duration"),
+                size: field("File size"),
+                format: metadata.get("Format").cloned().unwrap_or_else(|| {
+                    path.extension()
+                        .and_then(|e| e.to_str())
+                        .unwrap_or("Unknown")
+                        .to_uppercase()
+                }),
+                bitrate: field("Overall bit rate"),
+                sampling_rate: field("Sampling rate"),
+            }
+        })
+        .collect()
+}
+
+pub fn parse_metadata(folder: &str) -> Result<(String, String, String, String, String), SeedToolsError> {
+    // Find the first audio file in the folder
+    let first_file = std::fs::read_dir(folder)
+        .map_err(SeedToolsError::io("Failed to read directory"))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .find(|path| {
+            if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+                ext.eq_ignore_ascii_case("mp3") || ext.eq_ignore_ascii_case("flac")
+            } else {
+                false
+            }
+        })
+        .ok_or_else(|| "No valid audio files found in the folder.".to_string())?;
+
+    // Parse the mediainfo log for the first file
+    let metadata = parse_mediainfo_log(&first_file);
+
+    // Extract fields from the metadata
+    let artist_global = metadata.get("Performer").cloned().unwrap_or_else(|| "Unknown Artist".to_string());
+    let album_meta = metadata.get("Album").cloned().unwrap_or_else(|| "Unknown Album".to_string());
+    let audio_format = metadata.get("Format").cloned().unwrap_or_else(|| "Unknown Format".to_string());
+    let bit_depth = metadata.get("Bit depth").cloned().unwrap_or_else(|| "Unknown".to_string());
+    let sampling_rate = metadata.get("Sampling rate").cloned().unwrap_or_else(|| "0".to_string());
+
+    // Return the extracted metadata
+    Ok((artist_global, album_meta, audio_format, bit_depth, sampling_rate))
+}
+
+fn parse_mediainfo_log(file_path: &Path) -> HashMap<String, String> {
+    let output = Command::new("mediainfo")
+        .arg(file_path)
+        .output();
+
+    let mut metadata = HashMap::new();
+
+    match output {
+        Ok(output) => {
+            if output.status.success() {
+                let log = String::from_utf8_lossy(&output.stdout);
+                for line in log.lines() {
+                    if let Some((key, value)) = line.split_once(':') {
+                        metadata.insert(key.trim().to_string(), value.trim().to_string());
+                    }
+                }
+            }
+        }
+        // No mediainfo installed: read the tags natively when built with native-probe
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => match crate::probe::audio_details(file_path) {
+            Ok(details) => metadata = details,
+            Err(fallback) => warn!("mediainfo is not installed and {}", fallback),
+        },
+        Err(e) => warn!("Failed to run mediainfo: {}", e),
+    }
+
+    metadata
+}
+
+fn get_seedpool_resolution_id(input_path: &str, ids: &SeedpoolIds) -> u32 {
+    let resolution_regex = Regex::new(r"(?i)(8640p|4320p|2160p|1440p|1080p|1080i|720p|576p|576i|480p|480i)").unwrap();
+    let resolution = resolution_regex
+        .captures(input_path)
+        .map(|captures| captures[1].to_lowercase())
+        .unwrap_or_else(|| "other".to_string());
+    ids.resolution(&resolution)
+}
+
+/// Builds the Seedpool description of `input_path` without uploading anything, for
+/// `seed-tools preview`. Screenshots are taken locally and linked by path; there is no sample.
+pub fn preview_description(
+    input_path: &str,
+    config: &Config,
+    seedpool_config: &SeedpoolConfig,
+    ffmpeg_path: &Path,
+    ffprobe_path: &Path,
+    mediainfo_path: &Path,
+    screenshots: bool,
+) -> Result<String, SeedToolsError> {
+    let base_name = Path::new(input_path)
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    let has_extension = |path: &Path, extensions: &[&str]| {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| extensions.contains(&ext.to_lowercase().as_str()))
+    };
+
+    if WalkDir::new(input_path).into_iter().filter_map(|entry| entry.ok()).any(|entry| has_extension(entry.path(), &["mp3", "flac"])) {
+        let details = read_music_details(input_path)?;
+        let (format, bitrate) = format_and_bitrate(
+            &details.format,
+            &details.bit_depth,
+            &details.bit_rate,
+            &details.bit_rate_mode,
+            &details.encoding_settings,
+        );
+        // The largest image is the cover, shown from disk
+        let cover = WalkDir::new(input_path)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| has_extension(entry.path(), &["jpg", "jpeg", "png"]))
+            .max_by_key(|entry| entry.metadata().map(|metadata| metadata.len()).unwrap_or(0))
+            .and_then(|entry| fs::canonicalize(entry.path()).ok())
+            .map(|path| path.to_string_lossy().to_string());
+        return Ok(descriptions::music(&MusicRelease {
+            base_name: base_name.clone(),
+            tracks: music_tracks(input_path, &details.artist),
+            custom_description: Some(seedpool_config.settings.custom_description_for("music", &base_name, &format!("{} - {}", details.artist, details.album))),
+            artist: details.artist,
+            album: details.album,
+            year: details.year,
+            genre: details.genre,
+            audio: format!("{} {}", format, bitrate),
+            cover_url: cover,
+        }));
+    }
+
+    let (video_files, _) = find_video_files(input_path, &config.paths, &seedpool_config.settings, seedpool_config.torrent.exclusions(&config.exclusions))?;
+    let (video_files, _) = check_existing_samples(input_path, &video_files, &ffprobe_path.to_string_lossy());
+    let video_file = video_files.first().ok_or_else(|| SeedToolsError::Metadata(format!("No video files found in '{}'", input_path)))?;
+    let mediainfo_file = detect_disc(input_path).map_or_else(|| video_file.clone(), |disc| disc.mediainfo_file);
+    let mediainfo = generate_mediainfo(&mediainfo_file, &mediainfo_path.to_string_lossy())?;
+
+    // The same pieces the Submitted stage puts together
+    let mut subtitles = extract_subtitle_languages(&mediainfo);
+    subtitles.extend(find_external_subtitles(input_path));
+    subtitles.dedup();
+    let (release_type, title, ..) = determine_release_type_and_title(input_path);
+    let release_name = generate_release_name(&base_name);
+    let single_video = matches!(release_type.as_str(), "movie" | "tv") && video_files.len() == 1;
+    let mkv_extras = Some(video_file)
+        .filter(|_| single_video)
+        .map(|video_file| probe_mkv_extras(video_file, &ffprobe_path.to_string_lossy()))
+        .filter(|extras| !extras.is_empty());
+    let file_tree = if single_video { String::new() } else { render_file_tree(input_path) };
+    let screenshots = if screenshots {
+        capture_screenshots(
+            video_file,
+            &config.paths.screenshots_dir,
+            &ffmpeg_path.to_string_lossy(),
+            &ffprobe_path.to_string_lossy(),
+            &base_name,
+        )?
+    } else {
+        Vec::new()
+    };
+
+    Ok(generate_description(
+        &screenshots,
+        "",
+        &subtitles,
+        mkv_extras.as_ref(),
+        &file_tree,
+        Some(&seedpool_config.settings.custom_description_for(&release_type, &release_name, &title)),
+        None,
+    ))
+}
+
+/// Stops an upload whose files are already on Seedpool under another name. Cross-seeding it
+/// is left to the user, as the client would look for the data under the other name.
+fn renamed_dupe_error(release_name: &str, torrent: &TorrentMatch, upload_url: &str) -> SeedToolsError {
+    let page = torrent.page.clone().or_else(|| torrent_page_url(upload_url, &torrent.id)).unwrap_or_else(|| torrent.id.clone());
+    SeedToolsError::tracker("Seedpool", None, format!("'{}' is already on Seedpool with the same files as '{}': {}", release_name, torrent.name, page))
+}
+
+/// Downloads the Seedpool torrent of a dupe found by `preflight_check` and adds it to the
+/// clients, seeding the local data. Returns `false` when the local files do not match it.
+pub fn cross_seed_dupe(input_path: &str, config: &Config, title: &str, download_link: &str) -> Result<bool, SeedToolsError> {
+    log::info!("Cross-seeding the existing Seedpool torrent of '{}'.", title);
+    let torrent_data = download_torrent(download_link, None)?;
+    let torrent_file_path = Path::new(&config.paths.torrent_dir).join(format!("{}.torrent", title));
+    std::fs::write(&torrent_file_path, &torrent_data)
+        .map_err(SeedToolsError::io(format!("Failed to save '{}'", torrent_file_path.display())))?;
+
+    if let Err(e) = verify_before_inject(&torrent_data, Path::new(input_path)) {
+        log::warn!("{}", e);
+        return Ok(false);
+    }
+    add_torrent_to_all_qbittorrent_instances(
+        &[torrent_file_path.to_string_lossy().to_string()],
+        &config.qbittorrent,
+        &config.deluge,
+        input_path,
+        &config.paths,
+        None,
+    )?;
+    Ok(true)
+}
+
+/// Checks a release against Seedpool without changing anything: nothing is uploaded, written
+/// or added to the clients. An exact dupe can be cross-seeded afterwards with `cross_seed_dupe`.
+pub fn preflight_check(
+    input_path: &str,
+    config: &Config,
+    seedpool_config: &SeedpoolConfig,
+    ffprobe_path: &Path,
+    mediainfo_path: &Path,
+) -> Result<PreflightCheckResult, SeedToolsError> {
+    log::debug!("Processing release for input_path: {}", input_path);
+
+    // Step 0: Check for music files
+    let music_extensions = ["mp3", "flac"];
+    let mut found_music_file = false;
+    let mut music_type = None;
+
+    for entry in WalkDir::new(input_path).into_iter().filter_map(|e| e.ok()) {
+        if let Some(ext) = entry.path().extension().and_then(|e| e.to_str())
+            && music_extensions.contains(&ext.to_lowercase().as_str())
+        {
+            found_music_file = true;
+            music_type = Some(match ext.to_lowercase().as_str() {
+                "mp3" => "🎧 MP3".to_string(), // Add 🎧 icon for MP3
+                "flac" => "🎧 FLAC".to_string(), // Add 🎧 icon for FLAC
+                _ => ext.to_uppercase(),
+            });
+            break; // Exit the loop once a valid music file is found
+        }
+    }
+
+    // If music files are found, process as a music release
+    if found_music_file {
+        log::debug!("Music files detected in input path: {}", input_path);
+
+        // Extract metadata from the first music file
+        let first_file = WalkDir::new(input_path)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path().to_path_buf())
+            .find(|path| {
+                if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+                    ext.eq_ignore_ascii_case("mp3") || ext.eq_ignore_ascii_case("flac")
+                } else {
+                    false
+                }
+            })
+            .ok_or_else(|| "No valid music files found in the folder.".to_string())?;
+
+        let metadata = parse_mediainfo_log(&first_file);
+
+        let artist = metadata.get("Performer").cloned().unwrap_or_else(|| "Unknown Artist".to_string());
+        let album = metadata.get("Album").cloned().unwrap_or_else(|| "Unknown Album".to_string());
+        let audio_format = metadata.get("Format").cloned().unwrap_or_else(|| "Unknown Format".to_string());
+        let bit_depth = metadata.get("Bit depth").cloned().unwrap_or_else(|| "Unknown".to_string());
+        let sampling_rate = metadata.get("Sampling rate").cloned().unwrap_or_else(|| "Unknown".to_string());
+
+        let sampling_rate_khz = if sampling_rate.ends_with("kHz") {
+            sampling_rate.clone()
+        } else if let Ok(rate) = sampling_rate.parse::<f64>() {
+            format!("{:.1} kHz", rate / 1000.0)
+        } else {
+            "Unknown".to_string()
+        };
+
+        let audio_info = if bit_depth == "Unknown" || sampling_rate_khz == "Unknown" {
+            format!("{} / {}", audio_format, sampling_rate_khz)
+        } else {
+            format!("{} {} / {}", audio_format, bit_depth, sampling_rate_khz)
+        };
+
+        let title = format!("{} - {}", artist, album);
+        let generated_release_name = Path::new(input_path)
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+
+        // Check for album cover (image file) in the input path or subfolders
+        let album_cover_available = WalkDir::new(input_path)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .any(|entry| {
+                if let Some(ext) = entry.path().extension().and_then(|e| e.to_str()) {
+                    ext.eq_ignore_ascii_case("jpg") || ext.eq_ignore_ascii_case("png")
+                } else {
+                    false
+                }
+            });
+
+        let album_cover_status = if album_cover_available {
+            "Available".to_string()
+        } else {
+            "Not Available".to_string()
+        };
+
+        let estimate = estimate_processing(input_path, None, &ffprobe_path.to_string_lossy(), None);
+
+        return Ok(PreflightCheckResult {
+            release_name: title,
+            generated_release_name,
+            dupe_check: "N/A".to_string(),
+            dupe: false,
+            dupe_torrent: None,
+            dupe_download: None,
+            trump_check: "N/A".to_string(),
+            trump: None,
+            tmdb_id: 0,
+            imdb_id: None,
+            tvdb_id: None,
+            excluded_files: "N/A".to_string(),
+            album_cover: album_cover_status,
+            audio_languages: vec![audio_info],
+            subtitle_languages: vec![],
+            source_type: "N/A".to_string(),
+            release_type: format!("{} Music", music_type.as_ref().unwrap().to_uppercase()),
+            season_number: None,
+            episode_number: None,
+            disc: None,
+            existing_samples: vec![],
+            estimate,
+        });
+    }
+
+    // Step 1: Determine release type and title
+    let (release_type_raw, title, year, season_number, episode_number) =
+        determine_release_type_and_title(input_path);
+    log::debug!(
+        "Release type: {}, Title: {}, Year: {:?}, Season: {:?}, Episode: {:?}",
+        release_type_raw, title, year, season_number, episode_number
+    );
+
+    // Add icons for display purposes, but keep the raw release_type for logic
+    let release_type_display = if release_type_raw == "tv" && episode_number.is_none() {
+        "📺 Boxset".to_string() // Return plain string
+    } else {
+        match release_type_raw.as_str() {
+            "tv" => "★  📺 TV Show".to_string(), // Include the star as plain text
+            "movie" => "🎥 Movie".to_string(),
+            "boxset" => "📺 Boxset".to_string(),
+            "sport" => "🏆 Sport".to_string(),
+            _ => release_type_raw.clone(),
+        }
+    };
+
+    // Step 2: Generate release name using `generate_release_name`
+    let base_name = Path::new(input_path)
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    let generated_release_name = generate_release_name(&base_name);
+    let generated_release_name = if release_type_raw == "sport" {
+        normalize_event_date(&generated_release_name)
+    } else {
+        generated_release_name
+    };
+    // Step 3: Check for duplicates by the full name, as the upload does; near duplicates are
+    // shown but do not fail the check
+    let dupes = find_seedpool_dupes(&base_name, &seedpool_config.general.api_key)?;
+    for near in &dupes.near {
+        log::warn!("Possible duplicate on Seedpool for '{}': {}", title, near);
+    }
+    let renamed = match &dupes.exact {
+        Some(_) => None,
+        None => find_by_fingerprint(input_path, None, &seedpool_config.dupe_check, &seedpool_config.general.api_key)?,
+    };
+    let trump_check = if seedpool_config.trumping.enabled {
+        let others: Vec<String> = dupes.near.iter().chain(&dupes.others).cloned().collect();
+        crate::trumping::assess(&base_name, &others, &seedpool_config.trumping)
+    } else {
+        None
+    };
+    if let Some(assessment) = &trump_check {
+        log::info!("Trump check for '{}': {}", base_name, assessment.summary);
+    }
+    let trump = trump_check.as_ref().map(|assessment| assessment.verdict);
+    let trump_check = trump_check.map(|assessment| assessment.summary).unwrap_or_else(|| "N/A".to_string());
+    if let Some(torrent) = renamed {
+        log::warn!("'{}' is already on Seedpool as '{}'", title, torrent.name);
+        return Ok(PreflightCheckResult {
+            release_name: title.clone(),
+            generated_release_name: generated_release_name.clone(),
+            dupe_check: format!("FAIL, same files as '{}'", torrent.name),
+            dupe: true,
+            dupe_torrent: torrent.page.clone().or_else(|| torrent_page_url(&seedpool_config.settings.upload_url, &torrent.id)),
+            dupe_download: None,
+            trump_check: "N/A".to_string(),
+            trump: None,
+            tmdb_id: 0,
+            imdb_id: None,
+            tvdb_id: None,
+            excluded_files: "N/A".to_string(),
+            album_cover: "N/A".to_string(),
+            audio_languages: vec![],
+            subtitle_languages: vec![],
+            source_type: "N/A".to_string(),
+            release_type: release_type_display,
+            season_number,
+            episode_number,
+            disc: None,
+            existing_samples: vec![],
+            estimate: ProcessingEstimate::default(),
+        });
+    }
+    if let Some(download_link) = dupes.exact {
+        log::info!("'{}' is already on Seedpool", title);
+        return Ok(PreflightCheckResult {
+            release_name: title.clone(),
+            generated_release_name: generated_release_name.clone(),
+            dupe_check: "FAIL".to_string(),
+            dupe: true,
+            dupe_torrent: extract_torrent_id(&download_link).ok().and_then(|id| torrent_page_url(&seedpool_config.settings.upload_url, &id)),
+            dupe_download: Some(download_link),
+            trump_check: "N/A".to_string(),
+            trump: None,
+            tmdb_id: 0,
+            imdb_id: None,
+            tvdb_id: None,
+            excluded_files: "N/A".to_string(),
+            album_cover: "N/A".to_string(),
+            audio_languages: vec![],
+            subtitle_languages: vec![],
+            source_type: "N/A".to_string(),
+            release_type: release_type_display,
+            season_number,
+            episode_number,
+            disc: None,
+            existing_samples: vec![],
+            estimate: ProcessingEstimate::default(), // Nothing to process for a dupe
+        });
+    }
+
+    // Step 4: Fetch TMDB ID
+    log::info!(
+        "Fetching TMDB ID with title: '{}', year: {:?}, release_type: '{}'",
+        title,
+        year,
+        release_type_raw
+    );
+    let tmdb_id = if release_type_raw == "sport" {
+        0 // Sports events are not on TMDB
+    } else {
+        fetch_tmdb_id(&title, year.clone(), &config.general.tmdb_api_key, &release_type_raw)?
+    };
+    log::debug!("TMDB ID: {}", tmdb_id);
+
+    // Step 5: Fetch external IDs (IMDb, TVDB)
+    let (imdb_id, tvdb_id) = fetch_external_ids(tmdb_id, &release_type_raw, &config.general.tmdb_api_key)
+        .unwrap_or((None, None));
+    let imdb_id = crate::imdb::fill_missing(imdb_id, tmdb_id, &title, year.as_deref(), &release_type_raw, &config.imdb);
+    log::debug!("IMDb ID: {:?}, TVDB ID: {:?}", imdb_id, tvdb_id);
+
+    // Step 6: Check the `strip_from_videos` setting; disc structures are always uploaded complete
+    let disc = detect_disc(input_path);
+    let excluded_files = if seedpool_config.settings.stripshit_from_videos && disc.is_none() {
+        "Yes".to_string()
+    } else {
+        "No".to_string()
+    };
+
+    // Step 7: Extract audio and subtitle languages using MediaInfo, plus external subtitle files
+    let mut audio_languages = Vec::new();
+    let mut subtitle_languages = Vec::new();
+    let (video_files, _) = find_video_files(input_path, &config.paths, &seedpool_config.settings, seedpool_config.torrent.exclusions(&config.exclusions))?;
+    let (video_files, existing_samples) = check_existing_samples(input_path, &video_files, &ffprobe_path.to_string_lossy());
+    let mediainfo_files = match &disc {
+        Some(disc) => vec![disc.mediainfo_file.clone()],
+        None => video_files.clone(),
+    };
+    let mut source_type = String::new();
+    for mediainfo_file in &mediainfo_files {
+        let mediainfo_output = generate_mediainfo(mediainfo_file, &mediainfo_path.to_string_lossy())?;
+        if source_type.is_empty() {
+            let (kind, reason) = detect_source_type(&generated_release_name, &mediainfo_output, disc.is_some());
+            source_type = format!("{} ({})", kind, reason);
+        }
+        audio_languages.extend(extract_audio_languages(&mediainfo_output));
+        subtitle_languages.extend(extract_subtitle_languages(&mediainfo_output));
+    }
+    subtitle_languages.extend(find_external_subtitles(input_path));
+    log::debug!("Audio languages: {:?}, subtitles: {:?}", audio_languages, subtitle_languages);
+    for warning in check_claimed_languages(&generated_release_name, &audio_languages) {
+        log::warn!("{}", warning);
+    }
+    let generated_release_name = tag_audio_languages(&generated_release_name, &audio_languages);
+
+    // Step 8: Estimate how long the upload takes; the sample is only cut without ImgBB
+    let use_cdn = config.imgbb.as_ref().is_none_or(|imgbb| imgbb.imgbb_api_key.is_empty());
+    let sample = &seedpool_config.sample;
+    let cuts_sample = use_cdn && sample.enabled && sample.source != SampleSource::Existing
+        && !(sample.source == SampleSource::Auto && existing_samples.iter().any(|s| matches!(s, ExistingSample::Reuse(_))));
+    let estimate = estimate_processing(
+        input_path,
+        video_files.first().map(String::as_str),
+        &ffprobe_path.to_string_lossy(),
+        cuts_sample.then_some(sample),
+    );
+    let existing_samples = existing_samples
+        .iter()
+        .map(|sample| match sample {
+            ExistingSample::Reuse(file) => format!("{} (uploaded as the sample)", file),
+            ExistingSample::Regenerate(file, reason) => format!("{} ({}; a new one is cut)", file, reason),
+            ExistingSample::Keep(file, reason) => format!("{} ({}; kept as a video)", file, reason),
+        })
+        .collect();
+
+    // Step 9: Return the preflight check result
+    Ok(PreflightCheckResult {
+        release_name: title.clone(),
+        generated_release_name, // Use the generated release name
+        dupe_check: if !dupes.near.is_empty() {
+            format!("⚠️ PASS, near duplicates: {}", dupes.near.join(", "))
+        } else if !dupes.replaced_by.is_empty() {
+            format!("⚠️ PASS, a later revision is up: {}", dupes.replaced_by.join(", "))
+        } else if !dupes.replaces.is_empty() {
+            format!("✔️ PASS, REPACK/PROPER of {}", dupes.replaces.join(", "))
+        } else {
+            "✔️ PASS".to_string()
+        },
+        dupe: false,
+        dupe_torrent: None,
+        dupe_download: None,
+        trump_check,
+        trump,
+        tmdb_id,
+        imdb_id,
+        tvdb_id,
+        excluded_files,
+        album_cover: "N/A".to_string(),
+        audio_languages,
+        subtitle_languages,
+        source_type,
+        release_type: release_type_display,
+        season_number,
+        episode_number,
+        disc: disc.map(|disc| format!("{:?} ({})", disc.kind, disc.mediainfo_file)),
+        existing_samples,
+        estimate,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::{render, OutputFormat};
+
+    #[test]
+    fn preflight_json_output_parses() {
+        // Music releases are checked without the network, so this runs the whole check
+        let dir = tempfile::tempdir().unwrap();
+        let release = dir.path().join("Artist - Album (2020) [FLAC]");
+        fs::create_dir(&release).unwrap();
+        fs::write(release.join("01 - Track.flac"), vec![0u8; 4096]).unwrap();
+        fs::write(release.join("cover.jpg"), vec![0u8; 16]).unwrap();
+        let config: Config = serde_yaml::from_str(include_str!("../../../config/config.yaml")).unwrap();
+        let seedpool_config: SeedpoolConfig = serde_yaml::from_str(include_str!("../../../config/trackers/seedpool.yaml")).unwrap();
+
+        let result = preflight_check(&release.to_string_lossy(), &config, &seedpool_config, Path::new("ffprobe"), Path::new("mediainfo")).unwrap();
+        let json = render(OutputFormat::Json, &[result]).unwrap().unwrap();
+
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let record = &parsed[0];
+        assert_eq!(record["generated_release_name"], "Artist - Album (2020) [FLAC]");
+        assert_eq!(record["release_type"], "🎧 FLAC Music");
+        assert_eq!(record["album_cover"], "Available");
+        assert_eq!(record["content_bytes"], 4096 + 16);
+        assert!(record["existing_samples"].as_array().unwrap().is_empty());
+        assert_eq!(record["dupe"], false);
+    }
+
+    #[test]
+    #[ignore = "needs mediainfo on $PATH or the native-probe feature; run with --ignored"]
+    fn music_tags_are_read_from_the_fixture() {
+        let metadata = parse_mediainfo_log(Path::new("fixtures/media/track.flac"));
+        assert_eq!(metadata.get("Performer").map(String::as_str), Some("Fixture Artist"));
+        assert_eq!(metadata.get("Album").map(String::as_str), Some("Fixture Album"));
+    }
+
+    #[test]
+    fn dupe_download_link_is_not_serialized() {
+        // The link carries the passkey, so it must not end up in --output documents or API replies
+        let config: Config = serde_yaml::from_str(include_str!("../../../config/config.yaml")).unwrap();
+        let result = PreflightCheckResult {
+            release_name: "Movie.2020.1080p.BluRay.x264-GRP".to_string(),
+            generated_release_name: "Movie.2020.1080p.BluRay.x264-GRP".to_string(),
+            dupe_check: "FAIL".to_string(),
+            dupe: true,
+            dupe_torrent: Some("https://seedpool.org/torrents/1".to_string()),
+            dupe_download: Some("https://seedpool.org/torrent/download/1.secretpasskey".to_string()),
+            trump_check: "N/A".to_string(),
+            trump: None,
+            tmdb_id: 0,
+            imdb_id: None,
+            tvdb_id: None,
+            excluded_files: "N/A".to_string(),
+            album_cover: "N/A".to_string(),
+            audio_languages: vec![],
+            subtitle_languages: vec![],
+            source_type: "N/A".to_string(),
+            release_type: "Movie".to_string(),
+            season_number: None,
+            episode_number: None,
+            disc: None,
+            existing_samples: vec![],
+            estimate: ProcessingEstimate::default(),
+        };
+        let json = render(OutputFormat::Json, std::slice::from_ref(&result)).unwrap().unwrap();
+        assert!(!json.contains("secretpasskey"));
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&json).unwrap()[0]["dupe"], true);
+
+        // Without a download link there is nothing to cross-seed, and nothing is fetched
+        let renamed = PreflightCheckResult { dupe_download: None, ..result };
+        assert!(!crate::trackers::cross_seed_dupe("/nonexistent", &config, &renamed).unwrap());
+    }
+}
@@ -0,0 +1,156 @@
+use std::path::Path;
+use crate::error::SeedToolsError;
+use crate::types::Config;
+use log::info;
+use walkdir::WalkDir;
+use crate::utils::{add_torrent_to_all_qbittorrent_instances, create_torrent, generate_release_name};
+use crate::limits::check_release_limits;
+use crate::metrics;
+use crate::notifications::{notify, Notification};
+use crate::pipeline::{seedpool_metadata_cache, ReleaseCheckpoint};
+use crate::slots::{self, Workload};
+use crate::software::{generate_software_description, parse_software_release};
+use crate::torrent::verify_before_inject;
+use crate::trackers::seedpool::{check_seedpool_dupes, download_torrent, extract_torrent_id, submit_upload, torrent_page_url, UploadRequest};
+use crate::trackers::seedpool_metadata::validate_seedpool_ids;
+use crate::types::SeedpoolConfig;
+use super::common::check_local_dupe;
+use super::{Tools, UploadOptions};
+use super::seedpool::verify_and_record_upload;
+
+/// Uploads a software release (`--type software`) to Seedpool. Nothing is looked up on TMDB;
+/// the description is built from the folder name, the NFO and the file listing.
+pub fn process_software_release(
+    input_path: &str,
+    config: &Config,
+    seedpool_config: &SeedpoolConfig,
+    tools: &Tools,
+    options: &UploadOptions,
+) -> Result<(), SeedToolsError> {
+    let mut checkpoint = ReleaseCheckpoint::new("seedpool", input_path);
+    let result = upload_software_release(&mut checkpoint, input_path, config, seedpool_config, tools, options);
+    metrics::record_upload("seedpool", &result);
+    notify(&config.notifications, &Notification::upload("seedpool", &checkpoint, &result));
+    result
+}
+
+fn upload_software_release(
+    checkpoint: &mut ReleaseCheckpoint,
+    input_path: &str,
+    config: &Config,
+    seedpool_config: &SeedpoolConfig,
+    tools: &Tools,
+    options: &UploadOptions,
+) -> Result<(), SeedToolsError> {
+    let overrides = &options.overrides;
+    let offline = options.offline.as_deref();
+    let base_name = Path::new(input_path)
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    let release = parse_software_release(&base_name);
+    info!("Software release: {} (group {:?})", release.display_name(), release.group);
+    checkpoint.release_type = "software".to_string();
+    checkpoint.title = release.name.clone();
+    checkpoint.release_name = overrides.release_name.clone().unwrap_or_else(|| generate_release_name(&base_name));
+
+    // Cross-seed Seedpool's copy instead of uploading a dupe; offline runs have nothing to search
+    let dupe = match offline {
+        Some(_) => None,
+        None => check_seedpool_dupes(&base_name, &seedpool_config.general.api_key)?,
+    };
+    if let Some(download_link) = dupe {
+        let torrent_data = download_torrent(&download_link, offline)?;
+        verify_before_inject(&torrent_data, Path::new(input_path))?;
+        let torrent_file = Path::new(&config.paths.torrent_dir).join(format!("{}.torrent", base_name));
+        std::fs::write(&torrent_file, &torrent_data).map_err(SeedToolsError::io("Failed to save torrent file"))?;
+        checkpoint.client_states = add_torrent_to_all_qbittorrent_instances(
+            &[torrent_file.to_string_lossy().to_string()],
+            &config.qbittorrent,
+            &config.deluge,
+            input_path,
+            &config.paths,
+            offline,
+        )?;
+        checkpoint.dupe_link = Some(download_link);
+        return Ok(());
+    }
+
+    // Software goes under ids.categories.software, typed per OS (software-windows, ...) or as software
+    let ids = &seedpool_config.ids;
+    let os_type = release.os.as_ref().map_or(0, |os| ids.type_id(&format!("software-{}", os)));
+    checkpoint.category_id = overrides.category_id.unwrap_or(ids.category("software"));
+    checkpoint.type_id = overrides.type_id.unwrap_or(if os_type != 0 { os_type } else { ids.type_id("software") });
+    if checkpoint.category_id == 0 || checkpoint.type_id == 0 {
+        return Err(SeedToolsError::config("No 'software' entries under ids.categories and ids.types in seedpool.yaml; add them or pass --category"));
+    }
+    validate_seedpool_ids(
+        &seedpool_config.settings.upload_url,
+        &seedpool_config.general.api_key,
+        &seedpool_metadata_cache(&config.paths),
+        checkpoint.category_id,
+        checkpoint.type_id,
+        None,
+    )?;
+
+    check_release_limits(input_path, "software", &config.limits)?;
+    let torrent_file = {
+        let _slot = slots::acquire(&config.paths, &config.concurrency, Workload::Hashing)?;
+        create_torrent(
+            input_path,
+            &config.paths.torrent_dir,
+            &seedpool_config.settings.announce_url,
+            &tools.mkbrr.to_string_lossy(),
+            None, // Installers, keygens and readmes all belong to the release
+            &seedpool_config.torrent,
+        )?
+    };
+    checkpoint.torrent_file = Some(torrent_file.clone());
+    check_local_dupe(&config.paths, "seedpool", &checkpoint.release_name, Some(&torrent_file), options.allow_reupload)?;
+
+    let nfo_file = WalkDir::new(input_path)
+        .max_depth(2)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .find(|entry| entry.path().extension().is_some_and(|ext| ext.eq_ignore_ascii_case("nfo")))
+        .map(|entry| entry.path().to_string_lossy().to_string());
+    checkpoint.nfo_file = nfo_file.clone();
+    let custom_description = seedpool_config.settings.custom_description_for("software", &checkpoint.release_name, &release.name);
+    let description = generate_software_description(input_path, &release, nfo_file.as_deref(), &custom_description);
+
+    let upload = UploadRequest {
+        torrent_file: torrent_file.clone(),
+        name: checkpoint.release_name.clone(),
+        category_id: checkpoint.category_id,
+        type_id: checkpoint.type_id,
+        description: Some(description),
+        keywords: Some(
+            [Some(release.name.clone()), release.os.clone(), release.arch.clone()]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+        nfo_file,
+        tv: Some(false),
+        ..Default::default()
+    }
+    .with_flags(&seedpool_config.upload_flags);
+    let response_text = submit_upload(&seedpool_config.settings.upload_url, Some(&seedpool_config.general.api_key), &upload, offline)?;
+    checkpoint.torrent_url = extract_torrent_id(&response_text)
+        .ok()
+        .and_then(|id| torrent_page_url(&seedpool_config.settings.upload_url, &id));
+    info!("Uploaded '{}' to Seedpool: {}", checkpoint.release_name, checkpoint.torrent_url.as_deref().unwrap_or("-"));
+    verify_and_record_upload(config, checkpoint, &torrent_file, &response_text, offline);
+
+    checkpoint.client_states = add_torrent_to_all_qbittorrent_instances(
+        &[torrent_file],
+        &config.qbittorrent,
+        &config.deluge,
+        input_path,
+        &config.paths,
+        offline,
+    )?;
+    Ok(())
+}
@@ -0,0 +1,256 @@
+use std::path::Path;
+use std::process::Command;
+use crate::error::SeedToolsError;
+use crate::types::{Config, TorrentLeechConfig};
+use log::{info, error, warn};
+use crate::utils::{generate_release_name, find_video_files, create_torrent, create_proof_image, stage_release, generate_mediainfo, torrent_info_hash, detect_disc, DiscKind};
+use crate::history::{self, HistoryEntry};
+use crate::types::ProofPlacement;
+use crate::pipeline::{checkpoint_dir, PipelineStage, ReleaseCheckpoint};
+use crate::hooks::{run_hooks, HookPoint};
+use crate::trackers::torrentleech::{build_description, determine_tl_category, parse_release_meta};
+use crate::trackers::detect_unavailable;
+use crate::http::curl_args;
+use crate::notifications::{notify, Notification};
+use crate::limits::check_release_limits;
+use crate::metrics;
+use crate::slots::{self, Workload};
+use super::common::check_local_dupe;
+use super::{Tools, UploadOptions};
+use regex::Regex;
+
+fn determine_release_type_and_title(input_path: &str) -> (String, String) {
+    let base_name = Path::new(input_path)
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+
+    let episode_regex = Regex::new(r"(?i)S\d{2}E\d{2}").unwrap();
+    let season_regex = Regex::new(r"(?i)S\d{2}").unwrap();
+    let release_type = if episode_regex.is_match(&base_name) {
+        "tv".to_string()
+    } else if season_regex.is_match(&base_name) {
+        "boxset".to_string()
+    } else {
+        "movie".to_string()
+    };
+
+    let title = generate_release_name(&base_name);
+    (release_type, title)
+}
+
+pub fn process_torrentleech_release(
+    input_path: &str,
+    config: &mut Config,
+    torrentleech_config: &TorrentLeechConfig,
+    tools: &Tools,
+    options: &UploadOptions,
+) -> Result<(), SeedToolsError> {
+    // Only the torrent is expensive to redo here; TorrentLeech uploads have no image stages
+    let checkpoint_dir = checkpoint_dir(&config.paths);
+    let mut checkpoint = ReleaseCheckpoint::load_or_new(&checkpoint_dir, "torrentleech", input_path, options.fresh);
+    let result = run_torrentleech_stages(
+        &mut checkpoint,
+        &checkpoint_dir,
+        input_path,
+        config,
+        torrentleech_config,
+        tools,
+        options,
+    );
+    match &result {
+        Ok(()) => checkpoint.finish(&checkpoint_dir),
+        Err(e) => checkpoint.record_error(&checkpoint_dir, &e.to_string()),
+    }
+    metrics::record_upload("torrentleech", &result);
+    notify(&config.notifications, &Notification::upload("TorrentLeech", &checkpoint, &result));
+    result
+}
+
+fn run_torrentleech_stages(
+    checkpoint: &mut ReleaseCheckpoint,
+    checkpoint_dir: &Path,
+    input_path: &str,
+    config: &mut Config,
+    torrentleech_config: &TorrentLeechConfig,
+    tools: &Tools,
+    options: &UploadOptions,
+) -> Result<(), SeedToolsError> {
+    let overrides = &options.overrides;
+    let offline = options.offline.as_deref();
+    if checkpoint.completed(PipelineStage::Submitted) {
+        info!("'{}' was already submitted to TorrentLeech by an earlier run.", input_path);
+        return Ok(());
+    }
+
+    let base_name = Path::new(input_path).file_name().unwrap_or_default().to_string_lossy().to_string();
+    let release_name = overrides
+        .release_name
+        .clone()
+        .unwrap_or_else(|| generate_release_name(&base_name));
+    info!("Generated release name: {}", release_name);
+
+    let (release_type, title) = determine_release_type_and_title(input_path);
+    info!("Determined release type: {}, title: {}", release_type, title);
+
+    let (video_files, _) = find_video_files(input_path, &config.paths, &torrentleech_config.settings, torrentleech_config.torrent.exclusions(&config.exclusions))?;
+    if video_files.is_empty() {
+        return Err(SeedToolsError::Metadata("No valid video files detected.".to_string()));
+    }
+    let disc = detect_disc(input_path);
+
+    if !checkpoint.completed(PipelineStage::Analyzed) {
+        checkpoint.release_type = release_type.clone();
+        checkpoint.title = title.clone();
+        checkpoint.release_name = release_name.clone();
+        checkpoint.video_files = video_files.clone();
+        checkpoint.advance(checkpoint_dir, PipelineStage::Analyzed)?;
+    }
+
+    // Reuse the torrent from an earlier run if it is still there
+    let torrent_file = match checkpoint.torrent_file.clone() {
+        Some(file) if checkpoint.completed(PipelineStage::TorrentCreated) && Path::new(&file).exists() => {
+            info!("Reusing torrent from earlier run: {}", file);
+            file
+        }
+        _ => {
+            check_release_limits(input_path, &release_type, &config.limits)?;
+            run_hooks(&config.hooks, HookPoint::PreTorrent, checkpoint)?;
+            let _slot = slots::acquire(&config.paths, &config.concurrency, Workload::Hashing)?;
+            // Hardlinked copy named after the release when staging is set up; discs go up whole
+            let torrent_source = if disc.is_some() { input_path } else { video_files[0].as_str() };
+            let torrent_input = stage_release(torrent_source, &release_name, &config.paths)?;
+            checkpoint.staged_path = (torrent_input != torrent_source).then(|| torrent_input.clone());
+            let file = create_torrent(
+                &torrent_input,
+                &config.paths.torrent_dir,
+                &torrentleech_config.general.announce_url_1,
+                &tools.mkbrr.to_string_lossy(),
+                None, // Disable filtering for non-Standard Upload Mode
+                &torrentleech_config.torrent,
+            )?;
+            checkpoint.torrent_file = Some(file.clone());
+            checkpoint.advance(checkpoint_dir, PipelineStage::TorrentCreated)?;
+            file
+        }
+    };
+
+    check_local_dupe(&config.paths, "torrentleech", &release_name, Some(&torrent_file), options.allow_reupload)?;
+
+    let nfo_path = format!("{}/{}.nfo", config.paths.torrent_dir, release_name);
+    let mediainfo_file = disc.as_ref().map_or(video_files[0].as_str(), |disc| disc.mediainfo_file.as_str());
+    let mut nfo = generate_mediainfo(mediainfo_file, &tools.mediainfo.to_string_lossy())?;
+    if torrentleech_config.proof.enabled {
+        let proof = &torrentleech_config.proof;
+        // TorrentLeech has no CDN of its own to fall back on, so torrentleech.yaml sets all three
+        let proof_url = create_proof_image(&video_files[0], &config.paths.screenshots_dir, tools, proof, &release_name, offline)?;
+        info!("Proof image uploaded: {}", proof_url);
+        if proof.placement == ProofPlacement::Nfo {
+            nfo.push_str(&format!("\n\nProof: {}\n", proof_url));
+        }
+        checkpoint.proof_url = Some(proof_url);
+    }
+    std::fs::write(&nfo_path, nfo).map_err(SeedToolsError::io("Failed to write NFO file"))?;
+
+    // TorrentLeech's own layout, not the Seedpool description with its CDN-hosted badges
+    let description = torrentleech_config.description.enabled.then(|| {
+        let proof_url = checkpoint.proof_url.as_deref().filter(|_| torrentleech_config.proof.placement == ProofPlacement::Spoiler);
+        build_description(&release_name, proof_url, &torrentleech_config.settings.custom_description, &torrentleech_config.description)
+    });
+
+    // Category from the release name (movie/TV, pack, source, resolution); IDs from torrentleech.yaml
+    let mut meta = parse_release_meta(&release_name);
+    match disc.as_ref().map(|disc| disc.kind) {
+        Some(DiscKind::Bluray) => meta.insert("is_disc".to_string(), "BDMV".to_string()),
+        Some(DiscKind::Dvd) => meta.insert("is_disc".to_string(), "DVD".to_string()),
+        None => None,
+    };
+    info!("Parsed release details: {:?}", meta);
+    let category_id = determine_tl_category(&meta, &torrentleech_config.categories)?;
+
+    // TorrentLeech only takes a category, so that is the only ID override that applies
+    let category_id = match overrides.category_id {
+        Some(id) => {
+            info!("Using category override: {} (detected {})", id, category_id);
+            id
+        }
+        None => category_id,
+    };
+
+    info!("Selected category_id: {}", category_id);
+    checkpoint.category_id = category_id;
+    run_hooks(&config.hooks, HookPoint::PreUpload, checkpoint)?;
+
+    // Upload torrent; offline runs record it with the mock tracker instead
+    let (stdout, stderr, exit_status) = match offline {
+        Some(offline) => {
+            let fields = serde_json::json!({ "category": category_id, "nfo": nfo_path, "description": description });
+            let torrent_id = offline.tracker.record("TorrentLeech", &release_name, &torrent_file, fields)?;
+            (format!("Torrent {} uploaded\n200", torrent_id), String::new(), None)
+        }
+        None => {
+            let output = Command::new("curl")
+                .args([
+                    "-sS",
+                    "-w", "\n%{http_code}", // Append the HTTP status to spot maintenance windows
+                    "-X", "POST",
+                    "-F", &format!("announcekey={}", torrentleech_config.settings.tl_key),
+                    "-F", &format!("category={}", category_id),
+                    "-F", &format!("nfo=@{}", nfo_path),
+                    "-F", &format!("torrent=@{}", torrent_file),
+                    &torrentleech_config.settings.upload_url,
+                ])
+                // --form-string so a description starting with @ or < is not read as a file name
+                .args(description.iter().flat_map(|description| ["--form-string".to_string(), format!("description={}", description)]))
+                .args(curl_args(&torrentleech_config.settings.upload_url)) // Cookie/header passthrough
+                .output()
+                .map_err(|e| SeedToolsError::tool_spawn("curl", e))?;
+            let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+            (stdout, stderr, Some(output.status))
+        }
+    };
+
+    info!("Curl stdout: {}", stdout);
+    if !stderr.is_empty() {
+        error!("Curl stderr: {}", stderr);
+    }
+
+    let (body, http_code) = stdout.rsplit_once('\n').unwrap_or(("", stdout.as_ref()));
+    if let Some(reason) = detect_unavailable(http_code.trim().parse().unwrap_or(0), body) {
+        return Err(SeedToolsError::unavailable("TorrentLeech", reason));
+    }
+
+    if stdout.contains("Duplicate torrent") {
+        return Err(SeedToolsError::tracker("TorrentLeech", None, "Duplicate torrent detected. Upload aborted."));
+    }
+
+    if let Some(status) = exit_status.filter(|status| !status.success()) {
+        return Err(SeedToolsError::tracker(
+            "TorrentLeech",
+            None,
+            format!("Failed to upload to TorrentLeech. HTTP Status: {}. Error: {}", status, stderr),
+        ));
+    }
+
+    info!("Successfully uploaded torrent to TorrentLeech.");
+    checkpoint.advance(checkpoint_dir, PipelineStage::Submitted)?;
+
+    // TorrentLeech answers without a download link, so only the local infohash is recorded
+    let entry = HistoryEntry {
+        uploaded_at: chrono::Utc::now().to_rfc3339(),
+        tracker: "torrentleech".to_string(),
+        release_name: release_name.clone(),
+        input_path: input_path.to_string(),
+        category_id: Some(category_id),
+        info_hash: std::fs::read(&torrent_file).ok().and_then(|torrent| torrent_info_hash(&torrent).ok()),
+        response: Some(body.to_string()),
+        ..Default::default()
+    };
+    if let Err(e) = history::record(&config.paths, &entry) {
+        warn!("Failed to record upload history: {}", e);
+    }
+    run_hooks(&config.hooks, HookPoint::PostUpload, checkpoint)?;
+    Ok(())
+}
@@ -0,0 +1,278 @@
+use std::path::Path;
+use crate::error::SeedToolsError;
+use crate::types::Config;
+use crate::offline;
+use log::{info, warn};
+use crate::utils::{
+    create_torrent, detect_disc, detect_source_type, fetch_external_ids, fetch_tmdb_id, find_video_files, generate_mediainfo, generate_release_name,
+    generate_screenshots_imgbb, normalize_event_date, stage_release, add_torrent_to_all_qbittorrent_instances, torrent_info_hash,
+};
+use crate::bbcode::Builder;
+use crate::history::{self, HistoryEntry};
+use crate::hooks::{run_hooks, HookPoint};
+use crate::metrics;
+use crate::notifications::{notify, Notification};
+use crate::pipeline::{checkpoint_dir, PipelineStage, ReleaseCheckpoint};
+use crate::slots::{self, Workload};
+use crate::limits::check_release_limits;
+use crate::torrent::verify_before_inject;
+use crate::trackers::seedpool::{download_torrent, extract_torrent_id, torrent_page_url, UploadRequest};
+use crate::trackers::unit3d::{check_unit3d_dupes, resolve_ids, upload_url, Unit3d};
+use crate::types::Unit3dConfig;
+use super::common::check_local_dupe;
+use super::{Tools, UploadOptions};
+use super::seedpool::determine_release_type_and_title;
+
+/// Uploads a video release to a UNIT3D tracker set up in `config/trackers/unit3d`. Works like the
+/// Seedpool upload, except that screenshots are only added when ImgBB is configured.
+pub fn process_unit3d_release(
+    input_path: &str,
+    config: &mut Config,
+    tracker: &Unit3dConfig,
+    tools: &Tools,
+    options: &UploadOptions,
+) -> Result<(), SeedToolsError> {
+    let checkpoint_dir = checkpoint_dir(&config.paths);
+    let mut checkpoint = ReleaseCheckpoint::load_or_new(&checkpoint_dir, &tracker.name, input_path, options.fresh);
+    let result = run_unit3d_stages(
+        &mut checkpoint,
+        &checkpoint_dir,
+        input_path,
+        config,
+        tracker,
+        tools,
+        options,
+    );
+    match &result {
+        Ok(()) => checkpoint.finish(&checkpoint_dir),
+        Err(e) => checkpoint.record_error(&checkpoint_dir, &e.to_string()),
+    }
+    metrics::record_upload(&tracker.name, &result);
+    notify(&config.notifications, &Notification::upload(&tracker.name, &checkpoint, &result));
+    result
+}
+
+fn run_unit3d_stages(
+    checkpoint: &mut ReleaseCheckpoint,
+    checkpoint_dir: &Path,
+    input_path: &str,
+    config: &mut Config,
+    tracker: &Unit3dConfig,
+    tools: &Tools,
+    options: &UploadOptions,
+) -> Result<(), SeedToolsError> {
+    let imgbb_api_key = options.imgbb_api_key.as_deref();
+    let overrides = &options.overrides;
+    let offline = options.offline.as_deref();
+    let base_name = Path::new(input_path)
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+
+    // --- Detected ---
+    if !checkpoint.completed(PipelineStage::Detected) {
+        // Cross-seed the tracker's copy instead of uploading a dupe; offline runs have nothing to search
+        let dupe = match offline {
+            Some(_) => None,
+            None => check_unit3d_dupes(tracker, &base_name)?,
+        };
+        if let Some(download_link) = dupe {
+            let torrent_data = download_torrent(&download_link, offline)?;
+            verify_before_inject(&torrent_data, Path::new(input_path))?;
+            let torrent_file = Path::new(&config.paths.torrent_dir).join(format!("{}.{}.torrent", base_name, tracker.name));
+            std::fs::write(&torrent_file, &torrent_data).map_err(SeedToolsError::io("Failed to save torrent file"))?;
+            checkpoint.client_states = add_torrent_to_all_qbittorrent_instances(
+                &[torrent_file.to_string_lossy().to_string()],
+                &config.qbittorrent,
+                &config.deluge,
+                input_path,
+                &config.paths,
+                offline,
+            )?;
+            checkpoint.dupe_link = Some(download_link);
+            return Ok(());
+        }
+
+        let (release_type, title, year, season_number, episode_number) = determine_release_type_and_title(input_path);
+        // Adult uploads are only made on request and only where the tracker's yaml allows them
+        checkpoint.release_type = match &overrides.release_type {
+            Some(forced) if forced == "adult" && !tracker.adult.enabled => {
+                return Err(SeedToolsError::config(format!("Adult uploads are turned off for {}; set adult.enabled in unit3d/{}.yaml", tracker.name, tracker.name)));
+            }
+            Some(forced) => forced.clone(),
+            None => release_type,
+        };
+        checkpoint.title = title;
+        checkpoint.year = year;
+        checkpoint.season_number = season_number;
+        checkpoint.episode_number = episode_number;
+        checkpoint.advance(checkpoint_dir, PipelineStage::Detected)?;
+    }
+
+    // --- Analyzed ---
+    if !checkpoint.completed(PipelineStage::Analyzed) {
+        let _slot = slots::acquire(&config.paths, &config.concurrency, Workload::Metadata)?;
+        let release_name = overrides.release_name.clone().unwrap_or_else(|| match checkpoint.release_type.as_str() {
+            "sport" => normalize_event_date(&generate_release_name(&base_name)),
+            _ => generate_release_name(&base_name),
+        });
+
+        let arr_ids = overrides.tmdb_id.is_none().then(|| crate::arr::lookup_ids(&config.arr, input_path, &checkpoint.release_type)).flatten();
+        let tmdb_id = match overrides.tmdb_id {
+            Some(id) => id,
+            None if checkpoint.release_type == "sport" || checkpoint.release_type == "adult" => 0, // Not on TMDB
+            None => match arr_ids.as_ref().and_then(|ids| ids.tmdb_id) {
+                Some(id) => id,
+                None => fetch_tmdb_id(&checkpoint.title, checkpoint.year.clone(), &config.general.tmdb_api_key, &checkpoint.release_type)?,
+            },
+        };
+        let (imdb_id, tvdb_id) = fetch_external_ids(tmdb_id, &checkpoint.release_type, &config.general.tmdb_api_key)
+            .unwrap_or((None, None));
+        let (imdb_id, tvdb_id) = match arr_ids {
+            Some(ids) => (ids.imdb_id.or(imdb_id), ids.tvdb_id.or(tvdb_id)),
+            None => (imdb_id, tvdb_id),
+        };
+        let imdb_id = crate::imdb::fill_missing(imdb_id, tmdb_id, &checkpoint.title, checkpoint.year.as_deref(), &checkpoint.release_type, &config.imdb);
+
+        let (video_files, nfo_file) = find_video_files(input_path, &config.paths, tracker, tracker.torrent.exclusions(&config.exclusions))?;
+        if video_files.is_empty() {
+            return Err(SeedToolsError::Metadata("No valid video files detected.".to_string()));
+        }
+        let disc = detect_disc(input_path);
+        let mediainfo_file = disc.as_ref().map_or(video_files[0].as_str(), |disc| disc.mediainfo_file.as_str());
+        let mediainfo = generate_mediainfo(mediainfo_file, &tools.mediainfo.to_string_lossy())?;
+        let (release_kind, reason) = detect_source_type(&release_name, &mediainfo, disc.is_some());
+        info!("Source type: {} ({})", release_kind, reason);
+        let (category_id, type_id, _) = resolve_ids(tracker, &checkpoint.release_type, &release_name, release_kind)?;
+
+        checkpoint.release_name = release_name;
+        checkpoint.category_id = overrides.category_id.unwrap_or(category_id);
+        checkpoint.type_id = overrides.type_id.unwrap_or(type_id);
+        checkpoint.tmdb_id = tmdb_id;
+        checkpoint.imdb_id = overrides.imdb_id.clone().or(imdb_id);
+        checkpoint.tvdb_id = overrides.tvdb_id.or(tvdb_id);
+        checkpoint.season_number = overrides.season_number.or(checkpoint.season_number);
+        checkpoint.episode_number = overrides.episode_number.or(checkpoint.episode_number);
+        checkpoint.mediainfo = mediainfo;
+        checkpoint.video_files = video_files;
+        checkpoint.nfo_file = nfo_file;
+        checkpoint.advance(checkpoint_dir, PipelineStage::Analyzed)?;
+    }
+
+    // --- TorrentCreated ---
+    let torrent_exists = checkpoint.torrent_file.as_deref().is_some_and(|f| Path::new(f).exists());
+    if !checkpoint.completed(PipelineStage::TorrentCreated) || !torrent_exists {
+        check_release_limits(input_path, &checkpoint.release_type, &config.limits)?;
+        run_hooks(&config.hooks, HookPoint::PreTorrent, checkpoint)?;
+        let _slot = slots::acquire(&config.paths, &config.concurrency, Workload::Hashing)?;
+        let torrent_input = stage_release(input_path, &checkpoint.release_name, &config.paths)?;
+        checkpoint.staged_path = (torrent_input != input_path).then(|| torrent_input.clone());
+        checkpoint.torrent_file = Some(create_torrent(
+            &torrent_input,
+            &config.paths.torrent_dir,
+            &tracker.announce_url,
+            &tools.mkbrr.to_string_lossy(),
+            (tracker.stripshit_from_videos && detect_disc(input_path).is_none()).then_some(&config.exclusions), // Discs go up complete
+            &tracker.torrent,
+        )?);
+        checkpoint.advance(checkpoint_dir, PipelineStage::TorrentCreated)?;
+    }
+    let torrent_file = checkpoint.torrent_file.clone().unwrap_or_default();
+
+    if !checkpoint.completed(PipelineStage::Submitted) {
+        check_local_dupe(&config.paths, &tracker.name, &checkpoint.release_name, Some(&torrent_file), options.allow_reupload)?;
+    }
+
+    // --- ImagesUploaded ---
+    if !checkpoint.completed(PipelineStage::ImagesUploaded) {
+        match imgbb_api_key.filter(|key| !key.is_empty()) {
+            Some(api_key) => {
+                let _slot = slots::acquire(&config.paths, &config.concurrency, Workload::Images)?;
+                let video_file = checkpoint.video_files.first().ok_or_else(|| SeedToolsError::Metadata("No valid video files detected.".to_string()))?;
+                let images = generate_screenshots_imgbb(
+                    video_file,
+                    &tools.ffmpeg,
+                    &tools.ffprobe,
+                    api_key,
+                    checkpoint.release_type == "adult" && tracker.adult.blur_thumbnails,
+                );
+                match images {
+                    Ok((screenshots, thumbnails)) => {
+                        checkpoint.screenshots = screenshots;
+                        checkpoint.thumbnails = thumbnails;
+                    }
+                    // Nothing else hosts screenshots for UNIT3D trackers, so go up without them
+                    Err(e) if config.imgbb.as_ref().is_none_or(|imgbb| imgbb.fallback) && e.status() == Some(429) => {
+                        warn!("{}; uploading to {} without screenshots.", e, tracker.name);
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+            None => info!("No ImgBB API key configured; uploading to {} without screenshots.", tracker.name),
+        }
+        run_hooks(&config.hooks, HookPoint::PostScreenshots, checkpoint)?;
+        checkpoint.advance(checkpoint_dir, PipelineStage::ImagesUploaded)?;
+    }
+
+    // --- Submitted ---
+    if !checkpoint.completed(PipelineStage::Submitted) {
+        let (release_kind, _) = detect_source_type(&checkpoint.release_name, &checkpoint.mediainfo, detect_disc(input_path).is_some());
+        let (_, _, resolution_id) = resolve_ids(tracker, &checkpoint.release_type, &checkpoint.release_name, release_kind)?;
+        let mut description = Builder::new(&tracker.bbcode);
+        description.raw(&tracker.description);
+        for (screenshot, thumbnail) in checkpoint.screenshots.iter().zip(&checkpoint.thumbnails) {
+            description.raw("\n").link(screenshot, &format!("[img]{}[/img]", thumbnail));
+        }
+        let description = description.build();
+
+        run_hooks(&config.hooks, HookPoint::PreUpload, checkpoint)?;
+        let response_text = offline::tracker_or(offline, &Unit3d { config: tracker }).upload(UploadRequest {
+            torrent_file: torrent_file.clone(),
+            name: checkpoint.release_name.clone(),
+            category_id: checkpoint.category_id,
+            type_id: checkpoint.type_id,
+            resolution_id,
+            tmdb_id: checkpoint.tmdb_id,
+            imdb_id: checkpoint.imdb_id.clone(),
+            tvdb_id: checkpoint.tvdb_id.unwrap_or(0),
+            season_number: checkpoint.season_number,
+            episode_number: checkpoint.episode_number,
+            description: Some(description.trim().to_string()),
+            mediainfo: Some(checkpoint.mediainfo.clone()),
+            nfo_file: checkpoint.nfo_file.clone(),
+            ..Default::default()
+        })?;
+        let torrent_id = extract_torrent_id(&response_text).ok();
+        checkpoint.torrent_url = torrent_id.as_deref().and_then(|id| torrent_page_url(&upload_url(tracker), id));
+        checkpoint.advance(checkpoint_dir, PipelineStage::Submitted)?;
+
+        let entry = HistoryEntry {
+            uploaded_at: chrono::Utc::now().to_rfc3339(),
+            tracker: tracker.name.clone(),
+            release_name: checkpoint.release_name.clone(),
+            input_path: input_path.to_string(),
+            category_id: Some(checkpoint.category_id),
+            torrent_id,
+            info_hash: std::fs::read(&torrent_file).ok().and_then(|torrent| torrent_info_hash(&torrent).ok()),
+            response: Some(response_text),
+            ..Default::default()
+        };
+        if let Err(e) = history::record(&config.paths, &entry) {
+            warn!("Failed to record upload history: {}", e);
+        }
+        run_hooks(&config.hooks, HookPoint::PostUpload, checkpoint)?;
+    }
+
+    // --- Injected ---
+    checkpoint.client_states = add_torrent_to_all_qbittorrent_instances(
+        &[torrent_file],
+        &config.qbittorrent,
+        &config.deluge,
+        checkpoint.staged_path.as_deref().unwrap_or(input_path),
+        &config.paths,
+        offline,
+    )?;
+    checkpoint.advance(checkpoint_dir, PipelineStage::Injected)?;
+    Ok(())
+}
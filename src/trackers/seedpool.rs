@@ -0,0 +1,830 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use log::{debug, info, warn};
+use regex::Regex;
+use reqwest::blocking::multipart::Form;
+use crate::dupe_cache;
+use crate::error::SeedToolsError;
+use crate::http::{apply, host, send_text, send_with_headers};
+use crate::offline::{Offline, MOCK_HOST};
+use crate::trackers::seedpool_metadata::validate_seedpool_ids;
+use crate::trackers::{detect_unavailable, Tracker};
+use crate::types::{SeedpoolIds, UploadFlags};
+use crate::utils::generate_release_name;
+
+pub struct Seedpool {
+    pub upload_url: String,
+    pub api_key: String,
+    pub metadata_cache: PathBuf, // Cached category/type/resolution lists used to validate IDs
+    pub flags: UploadFlags,
+    pub ids: SeedpoolIds, // Category and type IDs from seedpool.yaml
+}
+
+/// A UNIT3D upload (Seedpool and custom UNIT3D trackers). Anything left at its default is sent
+/// as "0"/omitted. Call `to_form` to validate it and render the multipart form.
+#[derive(Default, Clone, Debug)]
+pub struct UploadRequest {
+    pub torrent_file: String,
+    pub name: String,
+    pub category_id: u32,
+    pub type_id: u32,
+    pub resolution_id: Option<u32>,
+    pub tmdb_id: u32,
+    pub imdb_id: Option<String>,
+    pub tvdb_id: u32,
+    pub mal_id: u32,
+    pub igdb_id: Option<String>,
+    pub season_number: Option<u32>,
+    pub episode_number: Option<u32>,
+    pub description: Option<String>,
+    pub mediainfo: Option<String>,
+    pub keywords: Option<String>,
+    pub nfo_file: Option<String>,
+    pub anonymous: bool,
+    pub stream: bool,
+    pub sd: bool,
+    pub personal_release: bool,
+    pub free: u8, // Freeleech percentage
+    pub featured: bool,
+    pub doubleup: bool,
+    pub internal: bool,
+    pub tv: Option<bool>, // Whether the category takes season/episode numbers; unset uses Seedpool's TV and boxset categories
+}
+
+const SEEDPOOL_URL: &str = "https://seedpool.org";
+
+fn flag(value: bool) -> &'static str {
+    if value { "1" } else { "0" }
+}
+
+impl UploadRequest {
+    fn is_tv(&self) -> bool {
+        self.tv.unwrap_or_else(|| SeedpoolIds::default().is_tv(self.category_id))
+    }
+
+    /// Applies the configured upload flags on top of the request's own.
+    pub fn with_flags(mut self, flags: &UploadFlags) -> Self {
+        self.free = self.free.max(flags.freeleech);
+        self.featured |= flags.featured;
+        self.doubleup |= flags.doubleup;
+        self.internal |= flags.internal;
+        self.personal_release |= flags.personal_release;
+        self.anonymous |= flags.anonymous;
+        self
+    }
+
+    /// Catches uploads the tracker would reject (or misfile) before anything is sent.
+    pub fn validate(&self) -> Result<(), SeedToolsError> {
+        if self.name.trim().is_empty() {
+            return Err(SeedToolsError::Metadata("Upload name is empty".to_string()));
+        }
+        if !Path::new(&self.torrent_file).is_file() {
+            return Err(SeedToolsError::Metadata(format!("Torrent file does not exist: {}", self.torrent_file)));
+        }
+        if self.category_id == 0 {
+            return Err(SeedToolsError::Metadata(format!("No category selected for '{}'", self.name)));
+        }
+        if self.type_id == 0 {
+            return Err(SeedToolsError::Metadata(format!("No type selected for '{}'", self.name)));
+        }
+        if self.is_tv() && self.season_number.is_none() {
+            return Err(SeedToolsError::Metadata(format!("TV upload '{}' needs a season number", self.name)));
+        }
+        if self.episode_number.is_some() && self.season_number.is_none() {
+            return Err(SeedToolsError::Metadata(format!("Upload '{}' has an episode number but no season number", self.name)));
+        }
+        if let Some(nfo) = &self.nfo_file
+            && !Path::new(nfo).is_file()
+        {
+            return Err(SeedToolsError::Metadata(format!("NFO file does not exist: {}", nfo)));
+        }
+        if self.free > 100 {
+            return Err(SeedToolsError::config(format!("Freeleech must be a percentage from 0 to 100, not {}", self.free)));
+        }
+        Ok(())
+    }
+
+    /// Validates the request and renders it as a UNIT3D upload form.
+    pub fn to_form(&self) -> Result<Form, SeedToolsError> {
+        self.validate()?;
+
+        let mut form = Form::new()
+            .file("torrent", &self.torrent_file)
+            .map_err(SeedToolsError::io("Failed to attach torrent file"))?
+            .text("name", self.name.clone())
+            .text("category_id", self.category_id.to_string())
+            .text("type_id", self.type_id.to_string())
+            .text("tmdb", self.tmdb_id.to_string())
+            .text("imdb", self.imdb_id.clone().unwrap_or_else(|| "0".to_string()))
+            .text("tvdb", self.tvdb_id.to_string())
+            .text("anonymous", flag(self.anonymous))
+            .text("mal", self.mal_id.to_string())
+            .text("igdb", self.igdb_id.clone().unwrap_or_else(|| "0".to_string()))
+            .text("stream", flag(self.stream))
+            .text("sd", flag(self.sd));
+
+        // Staff-only flags are left out unless set, so regular accounts never send them
+        if self.personal_release {
+            form = form.text("personal_release", "1");
+        }
+        if self.free > 0 {
+            form = form.text("free", self.free.to_string());
+        }
+        if self.featured {
+            form = form.text("featured", "1");
+        }
+        if self.doubleup {
+            form = form.text("doubleup", "1");
+        }
+        if self.internal {
+            form = form.text("internal", "1");
+        }
+        if let Some(resolution_id) = self.resolution_id {
+            form = form.text("resolution_id", resolution_id.to_string());
+        }
+        if let Some(description) = &self.description {
+            form = form.text("description", description.clone());
+        }
+        if let Some(mediainfo) = &self.mediainfo {
+            form = form.text("mediainfo", mediainfo.clone());
+        }
+        if let Some(keywords) = &self.keywords {
+            form = form.text("keywords", keywords.clone());
+        }
+        if let Some(nfo) = &self.nfo_file {
+            form = form.file("nfo", nfo).map_err(SeedToolsError::io("Failed to attach NFO file"))?;
+        }
+
+        // Only include season_number and episode_number for TV and boxsets
+        if self.is_tv() {
+            if let Some(season) = self.season_number {
+                form = form.text("season_number", season.to_string());
+            }
+            if let Some(episode) = self.episode_number {
+                form = form.text("episode_number", episode.to_string());
+            }
+        }
+        Ok(form)
+    }
+}
+
+/// Validates and posts an upload and returns the response body. `api_key` is sent as a Bearer
+/// token when given; custom upload URLs carry their key in the query string instead. With
+/// `offline` the mock tracker records the upload.
+pub fn submit_upload(upload_url: &str, api_key: Option<&str>, upload: &UploadRequest, offline: Option<&Offline>) -> Result<String, SeedToolsError> {
+    upload.validate()?;
+    let tracker = upload_url.split('?').next().unwrap_or(upload_url);
+    if let Some(offline) = offline {
+        return offline.tracker.submit(tracker, upload);
+    }
+    let (status, response_text) = send_text(upload_url, |client| {
+        let mut request = client.post(upload_url).multipart(upload.to_form()?);
+        if let Some(api_key) = api_key {
+            request = request.header("Authorization", format!("Bearer {}", api_key));
+        }
+        Ok(request)
+    })
+    .map_err(|e| e.context("Failed to send upload request"))?;
+    info!("Upload response: HTTP {}: {}", status, response_text);
+
+    if let Some(reason) = detect_unavailable(status.as_u16(), &response_text) {
+        return Err(SeedToolsError::unavailable(tracker, reason));
+    }
+    if !status.is_success() {
+        return Err(SeedToolsError::tracker(
+            tracker,
+            Some(status.as_u16()),
+            format!("Failed to upload torrent. HTTP Status: {}. Response: {}", status, response_text),
+        ));
+    }
+    // A cached search from before the upload would no longer find every dupe
+    dupe_cache::clear();
+    Ok(response_text)
+}
+
+/// Extracts the torrent ID from the download link in an upload response.
+pub fn extract_torrent_id(response_text: &str) -> Result<String, SeedToolsError> {
+    // Unescape any escaped slashes
+    let response_text = response_text.replace(r"\/", "/");
+
+    // Match the numeric ID followed by a dot and a 32-character hash
+    let re = Regex::new(r#"/download/(\d+)\.[a-fA-F0-9]{32}"#).unwrap();
+    if let Some(captures) = re.captures(&response_text)
+        && let Some(torrent_id) = captures.get(1)
+    {
+        return Ok(torrent_id.as_str().to_string());
+    }
+    Err(SeedToolsError::tracker("Seedpool", None, "Failed to extract torrent ID from response."))
+}
+
+/// Download link in an upload response, e.g. `https://seedpool.org/torrent/download/123.<rsskey>`.
+pub fn extract_download_url(response_text: &str) -> Option<String> {
+    let response_text = response_text.replace(r"\/", "/");
+    let re = Regex::new(r#"https?://[^\s"']+/download/\d+\.[a-fA-F0-9]{32}"#).ok()?;
+    re.find(&response_text).map(|m| m.as_str().to_string())
+}
+
+/// Downloads the .torrent the tracker serves, e.g. to compare it with the uploaded one. Links
+/// from the mock tracker are read from `offline`.
+pub fn download_torrent(download_url: &str, offline: Option<&Offline>) -> Result<Vec<u8>, SeedToolsError> {
+    let tracker = host(download_url);
+    if let Some(offline) = offline.filter(|_| tracker == MOCK_HOST) {
+        return offline.tracker.download(download_url);
+    }
+    let response = apply(crate::http::client().get(download_url), download_url)
+        .send()
+        .map_err(|e| SeedToolsError::request(&tracker, e).context("Failed to download torrent"))?;
+    if !response.status().is_success() {
+        return Err(SeedToolsError::tracker(
+            &tracker,
+            Some(response.status().as_u16()),
+            format!("Failed to download torrent. HTTP Status: {}", response.status()),
+        ));
+    }
+    response
+        .bytes()
+        .map(|bytes| bytes.to_vec())
+        .map_err(|e| SeedToolsError::request(&tracker, e).context("Failed to read torrent data"))
+}
+
+/// Torrent page for an uploaded torrent, e.g. `https://seedpool.org/torrents/123`.
+pub fn torrent_page_url(upload_url: &str, torrent_id: &str) -> Option<String> {
+    upload_url
+        .split_once("/api/")
+        .map(|(host, _)| format!("{}/torrents/{}", host, torrent_id))
+}
+
+// Result pages fetched per search, at 100 results each; common titles rarely need more
+const MAX_SEARCH_PAGES: u32 = 5;
+
+/// Queries the torrent filter API of the UNIT3D site at `base_url` (e.g. `https://seedpool.org`)
+/// and returns `(name, download_link)` of each result, following the result pages. Results
+/// are cached for `dupe_cache.ttl_secs`, so a pre-flight check followed by the upload searches
+/// only once.
+pub(crate) fn search_torrents(
+    base_url: &str,
+    tracker: &str, // For log and error messages
+    name: &str,
+    search_term: &str,
+    extra_params: &str,
+    api_key: &str,
+) -> Result<Vec<(String, Option<String>)>, SeedToolsError> {
+    let query_url = format!(
+        "{}/api/torrents/filter?name={}&perPage=100&sortField=name&sortDirection=asc&api_token={}{}",
+        base_url.trim_end_matches('/'),
+        urlencoding::encode(search_term),
+        api_key,
+        extra_params
+    );
+    let cache_key = dupe_cache::key(base_url, search_term, extra_params);
+    let cached = dupe_cache::lookup(&cache_key);
+    if let Some(cached) = cached.as_ref().filter(|cached| cached.is_fresh()) {
+        info!("Using cached {} results for '{}'", tracker, search_term);
+        return Ok(cached.results.clone());
+    }
+    info!("{} API Query URL: {}", tracker, query_url);
+
+    let etag = cached.as_ref().and_then(|cached| cached.etag.clone());
+    let (status, headers, raw_response) = send_with_headers(&query_url, |client| {
+        let request = client.get(&query_url);
+        Ok(match &etag {
+            Some(etag) => request.header(reqwest::header::IF_NONE_MATCH, etag.as_str()),
+            None => request,
+        })
+    })
+    .map_err(|e| e.context(&format!("Failed to query {} for '{}'", tracker, name)))?;
+    if let (304, Some(cached)) = (status.as_u16(), cached) {
+        info!("{} results for '{}' have not changed", tracker, search_term);
+        dupe_cache::refresh(&cache_key);
+        return Ok(cached.results);
+    }
+    if let Some(reason) = detect_unavailable(status.as_u16(), &raw_response) {
+        return Err(SeedToolsError::unavailable(tracker, reason));
+    }
+    if !status.is_success() {
+        return Err(SeedToolsError::tracker(tracker, Some(status.as_u16()), format!("Failed to query {} for '{}': HTTP {}", tracker, name, status)));
+    }
+    debug!("{} API Response: {}", tracker, raw_response);
+
+    let mut page = parse_search_page(tracker, name, status.as_u16(), &raw_response)?;
+    let mut results = std::mem::take(&mut page.0);
+    let mut pages = 1;
+    while let Some(next_url) = page.1.take() {
+        if pages == MAX_SEARCH_PAGES {
+            warn!("{} has more results for '{}' than the {} pages searched", tracker, search_term, MAX_SEARCH_PAGES);
+            break;
+        }
+        pages += 1;
+        // Sites that leave the query string out of the next link also leave out the API token
+        let next_url = if next_url.contains("api_token=") {
+            next_url
+        } else {
+            let separator = if next_url.contains('?') { '&' } else { '?' };
+            format!("{}{}api_token={}", next_url, separator, api_key)
+        };
+        let (status, raw_response) = send_text(&next_url, |client| Ok(client.get(&next_url)))
+            .map_err(|e| e.context(&format!("Failed to query {} for '{}'", tracker, name)))?;
+        if !status.is_success() {
+            return Err(SeedToolsError::tracker(tracker, Some(status.as_u16()), format!("Failed to query {} for '{}': HTTP {}", tracker, name, status)));
+        }
+        page = parse_search_page(tracker, name, status.as_u16(), &raw_response)?;
+        results.append(&mut page.0);
+    }
+    // The ETag only covers the first page
+    let etag = headers
+        .get(reqwest::header::ETAG)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+        .filter(|_| pages == 1);
+    dupe_cache::store(&cache_key, etag, &results);
+    Ok(results)
+}
+
+/// The `(name, download_link)` results of one page of a torrent filter response, and the link
+/// to the next page.
+type SearchPage = (Vec<(String, Option<String>)>, Option<String>);
+
+fn parse_search_page(tracker: &str, name: &str, status: u16, raw_response: &str) -> Result<SearchPage, SeedToolsError> {
+    let search_results: serde_json::Value = serde_json::from_str(raw_response).map_err(|e| {
+        SeedToolsError::tracker(tracker, Some(status), format!("Failed to parse {} response for '{}': {}", tracker, name, e))
+    })?;
+
+    let empty_vec = vec![];
+    let results = search_results["data"]
+        .as_array()
+        .unwrap_or(&empty_vec)
+        .iter()
+        .filter_map(|result| result["attributes"].as_object())
+        .filter_map(|attributes| {
+            let title = attributes.get("name").and_then(|t| t.as_str())?;
+            let download_link = attributes.get("download_link").and_then(|d| d.as_str());
+            Some((title.to_string(), download_link.map(|d| d.to_string())))
+        })
+        .collect();
+    let next = search_results["links"]["next"].as_str().map(str::to_string);
+    Ok((results, next))
+}
+
+/// What a release name says about its content, for telling near duplicates apart from other
+/// releases: differently ordered or tagged names of the same title, year, episode, resolution
+/// and source give the same key. `revision` tells a REPACK or PROPER from the release it fixes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReleaseKey {
+    pub title: String, // Lowercase words
+    pub year: Option<String>,
+    pub episode: Option<String>, // S01E02, or S01 for season packs
+    pub resolution: Option<String>,
+    pub source: Option<String>,
+    pub revision: u32, // 0 for the first release, 1 for PROPER/REPACK/RERIP, 2 for REPACK2 or REAL.PROPER, ...
+}
+
+impl ReleaseKey {
+    pub fn parse(name: &str) -> ReleaseKey {
+        let normalized = generate_release_name(name);
+        let words: Vec<String> = normalized.split(['.', '-', '+']).filter(|word| !word.is_empty()).map(str::to_lowercase).collect();
+        let episode_regex = Regex::new(r"^s(\d{1,2})(e\d{1,3})?$").unwrap();
+        let mut key = ReleaseKey { title: String::new(), year: None, episode: None, resolution: None, source: None, revision: 0 };
+        let repack_regex = Regex::new(r"^repack(\d)?$").unwrap();
+        let mut title_words = Vec::new();
+        let mut title_done = false;
+        for (i, word) in words.iter().enumerate() {
+            let is_year = word.len() == 4 && (word.starts_with("19") || word.starts_with("20")) && word.chars().all(|c| c.is_ascii_digit());
+            if is_year && i > 0 && key.year.is_none() {
+                key.year = Some(word.clone());
+                title_done = true;
+            } else if episode_regex.is_match(word) && key.episode.is_none() {
+                key.episode = Some(word.to_uppercase());
+                title_done = true;
+            } else if let Some(resolution) = normalize_resolution(word) {
+                key.resolution.get_or_insert(resolution);
+                title_done = true;
+            } else if let Some(source) = normalize_source(word, words.get(i + 1).map(String::as_str)) {
+                // A remux is told apart from an encode of the same disc
+                if key.source.as_deref() != Some("remux") {
+                    key.source = Some(source);
+                }
+                title_done = true;
+            } else if title_done && (word == "proper" || word == "rerip" || repack_regex.is_match(word)) {
+                // Only after the title, where scene names put them; "The Proper Way" is a title
+                let number = repack_regex.captures(word).and_then(|captures| captures.get(1)).and_then(|n| n.as_str().parse().ok()).unwrap_or(1);
+                key.revision = key.revision.max(number);
+                // REAL.PROPER fixes a PROPER
+                if i > 0 && words[i - 1] == "real" {
+                    key.revision += 1;
+                }
+            } else if !title_done {
+                title_words.push(word.clone());
+            }
+        }
+        key.title = title_words.join(" ");
+        key
+    }
+
+    /// The title and year, as a search term that finds every release of it.
+    pub fn search_term(&self) -> String {
+        match &self.year {
+            Some(year) => format!("{} {}", self.title, year),
+            None => self.title.clone(),
+        }
+    }
+
+    /// Whether `other` is the same release in another revision, e.g. its REPACK.
+    pub fn same_release(&self, other: &ReleaseKey) -> bool {
+        self.same_title(other) && self.resolution == other.resolution && self.source == other.source
+    }
+
+    /// Whether `other` is a release of the same title, year and episode, of any quality.
+    pub fn same_title(&self, other: &ReleaseKey) -> bool {
+        self.title == other.title && self.year == other.year && self.episode == other.episode
+    }
+
+    /// Whether two keys are specific enough to compare: a title and a year or episode.
+    fn is_specific(&self) -> bool {
+        !self.title.is_empty() && (self.year.is_some() || self.episode.is_some())
+    }
+}
+
+fn normalize_resolution(word: &str) -> Option<String> {
+    match word {
+        "2160p" | "4k" | "uhd" => Some("2160p".to_string()),
+        "1080p" | "1080i" | "720p" | "576p" | "576i" | "480p" | "480i" => Some(word.to_string()),
+        _ => None,
+    }
+}
+
+fn normalize_source(word: &str, next: Option<&str>) -> Option<String> {
+    match (word, next) {
+        ("remux", _) => Some("remux".to_string()),
+        ("bluray" | "bdrip" | "brrip" | "bdremux", _) => Some("bluray".to_string()),
+        ("blu", Some("ray")) => Some("bluray".to_string()),
+        ("web" | "webdl" | "webrip", _) => Some("web".to_string()),
+        ("hdtv" | "pdtv", _) => Some("hdtv".to_string()),
+        ("dvdrip" | "dvd" | "dvd5" | "dvd9", _) => Some("dvd".to_string()),
+        _ => None,
+    }
+}
+
+/// A torrent on the tracker found by file name or infohash.
+#[derive(Debug, Clone)]
+pub struct TorrentMatch {
+    pub id: String,
+    pub name: String,
+    pub size: u64,
+    pub files: u64,
+    pub info_hash: Option<String>,
+    pub page: Option<String>,
+}
+
+/// Total size, file count and largest file of a release, to recognize it on the tracker under
+/// another name.
+#[derive(Debug, Clone)]
+pub struct Fingerprint {
+    pub size: u64,
+    pub files: u64,
+    pub largest_file: String, // File name only
+}
+
+impl Fingerprint {
+    pub fn of(input_path: &str) -> Option<Fingerprint> {
+        let files: Vec<(String, u64)> = walkdir::WalkDir::new(input_path)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_file())
+            .filter_map(|entry| Some((entry.file_name().to_string_lossy().to_string(), entry.metadata().ok()?.len())))
+            .collect();
+        let (largest_file, _) = files.iter().max_by_key(|(_, size)| *size)?;
+        Some(Fingerprint {
+            size: files.iter().map(|(_, size)| size).sum(),
+            files: files.len() as u64,
+            largest_file: largest_file.clone(),
+        })
+    }
+
+    /// Whether `torrent` is the same content: its size within `tolerance_percent` of ours.
+    pub fn matches(&self, torrent: &TorrentMatch, tolerance_percent: f64) -> bool {
+        let difference = self.size.abs_diff(torrent.size) as f64;
+        torrent.size > 0 && difference <= self.size as f64 * tolerance_percent / 100.0
+    }
+}
+
+/// Torrents on Seedpool with `file_name` among their files or the given infohash. Sites that
+/// do not know a filter return unrelated torrents, which the size comparison then rules out.
+fn filter_torrents(params: &str, seedpool_api_key: &str) -> Result<Vec<TorrentMatch>, SeedToolsError> {
+    let query_url = format!("{}/api/torrents/filter?{}&perPage=25&api_token={}", SEEDPOOL_URL, params, seedpool_api_key);
+    let (status, raw_response) = send_text(&query_url, |client| Ok(client.get(&query_url)))
+        .map_err(|e| e.context("Failed to query Seedpool"))?;
+    if let Some(reason) = detect_unavailable(status.as_u16(), &raw_response) {
+        return Err(SeedToolsError::unavailable("Seedpool", reason));
+    }
+    if !status.is_success() {
+        return Err(SeedToolsError::tracker("Seedpool", Some(status.as_u16()), format!("Failed to query Seedpool: HTTP {}", status)));
+    }
+    let response: serde_json::Value = serde_json::from_str(&raw_response)
+        .map_err(|e| SeedToolsError::tracker("Seedpool", Some(status.as_u16()), format!("Failed to parse Seedpool response: {}", e)))?;
+    Ok(response["data"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|result| {
+            let attributes = &result["attributes"];
+            Some(TorrentMatch {
+                id: result["id"].as_str().map(str::to_string).or_else(|| result["id"].as_u64().map(|id| id.to_string()))?,
+                name: attributes["name"].as_str()?.to_string(),
+                size: attributes["size"].as_u64().unwrap_or(0),
+                files: attributes["num_file"].as_u64().unwrap_or(0),
+                info_hash: attributes["info_hash"].as_str().map(str::to_lowercase),
+                page: attributes["details_link"].as_str().map(str::to_string),
+            })
+        })
+        .collect())
+}
+
+/// Looks for the release on Seedpool under any name: by its largest file's name and total
+/// size, and by `info_hash` when the torrent is already made.
+pub fn find_by_fingerprint(
+    input_path: &str,
+    info_hash: Option<&str>,
+    config: &crate::types::DupeCheckConfig,
+    seedpool_api_key: &str,
+) -> Result<Option<TorrentMatch>, SeedToolsError> {
+    let Some(fingerprint) = Fingerprint::of(input_path) else { return Ok(None) };
+    if let Some(info_hash) = info_hash.filter(|_| config.info_hash) {
+        let info_hash = info_hash.to_lowercase();
+        for torrent in filter_torrents(&format!("infoHash={}", info_hash), seedpool_api_key)? {
+            // Sites that leave the infohash out of results are trusted on the size
+            if torrent.info_hash.as_deref().map_or(fingerprint.matches(&torrent, config.size_tolerance_percent), |hash| hash == info_hash) {
+                info!("Seedpool has torrent {} with the same infohash: {}", torrent.id, torrent.name);
+                return Ok(Some(torrent));
+            }
+        }
+    }
+    if config.fingerprint {
+        let params = format!("file_name={}", urlencoding::encode(&fingerprint.largest_file));
+        for torrent in filter_torrents(&params, seedpool_api_key)? {
+            if fingerprint.matches(&torrent, config.size_tolerance_percent) {
+                info!(
+                    "Seedpool has torrent {} with '{}' and the same size ({} vs {} files): {}",
+                    torrent.id, fingerprint.largest_file, torrent.files, fingerprint.files, torrent.name
+                );
+                return Ok(Some(torrent));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// What the upload dupe check found on Seedpool.
+#[derive(Debug, Default)]
+pub struct SeedpoolDupes {
+    pub exact: Option<String>, // Download link of the torrent with the same name
+    pub near: Vec<String>,     // Names of other torrents of the same title, year, episode, resolution and source
+    pub others: Vec<String>,   // Names of torrents of the same title, year and episode in another quality
+    pub replaces: Vec<String>, // Names of earlier revisions this REPACK or PROPER fixes; not dupes
+    pub replaced_by: Vec<String>, // Names of later revisions (REPACK, PROPER) of this release already up
+}
+
+/// Searches Seedpool for every release of the title and sorts the results into an exact
+/// match and near duplicates.
+pub fn find_seedpool_dupes(name: &str, seedpool_api_key: &str) -> Result<SeedpoolDupes, SeedToolsError> {
+    let release_name = generate_release_name(name);
+    let key = ReleaseKey::parse(name);
+    // The title and year find differently named uploads; names without either search as they are
+    let search_term = if key.is_specific() { key.search_term() } else { release_name.clone() };
+    info!("Search Term for Seedpool Query: '{}'", search_term);
+    let results = search_torrents(SEEDPOOL_URL, "Seedpool", name, &search_term, "", seedpool_api_key)?;
+    Ok(sort_dupes(name, results))
+}
+
+/// Sorts the `(name, download_link)` search results for `name` into an exact match, near
+/// duplicates, other revisions and other qualities of the title.
+fn sort_dupes(name: &str, results: Vec<(String, Option<String>)>) -> SeedpoolDupes {
+    let release_name = generate_release_name(name);
+    let key = ReleaseKey::parse(name);
+    let mut dupes = SeedpoolDupes::default();
+    for (result_title, download_link) in results {
+        if result_title == release_name {
+            if let Some(download_link) = download_link {
+                dupes.exact.get_or_insert(download_link);
+            }
+        } else if key.is_specific() && ReleaseKey::parse(&result_title) == key {
+            dupes.near.push(result_title);
+        } else if key.is_specific() && key.same_release(&ReleaseKey::parse(&result_title)) {
+            // Same release in another revision: a REPACK or PROPER goes up next to what it fixes
+            if ReleaseKey::parse(&result_title).revision < key.revision {
+                dupes.replaces.push(result_title);
+            } else {
+                dupes.replaced_by.push(result_title);
+            }
+        } else if key.is_specific() && key.same_title(&ReleaseKey::parse(&result_title)) {
+            dupes.others.push(result_title);
+        } else {
+            debug!("Skipping result due to mismatched title: {}", result_title);
+        }
+    }
+    dupes
+}
+
+/// Upload dupe check: looks for a torrent whose name exactly matches the normalized release
+/// name. Near duplicates are only logged as warnings, as they may be a different cut or group.
+pub fn check_seedpool_dupes(name: &str, seedpool_api_key: &str) -> Result<Option<String>, SeedToolsError> {
+    info!("Checking Seedpool for existing torrent with name: '{}'", name);
+    let dupes = find_seedpool_dupes(name, seedpool_api_key)?;
+    for near in &dupes.near {
+        warn!("Possible duplicate on Seedpool for '{}': {}", name, near);
+    }
+    for replaced in &dupes.replaces {
+        info!("'{}' is a REPACK/PROPER of '{}' on Seedpool; not a duplicate.", name, replaced);
+    }
+    for newer in &dupes.replaced_by {
+        warn!("Seedpool already has a later revision of '{}': {}", name, newer);
+    }
+    match &dupes.exact {
+        Some(download_link) => info!("Duplicate found for '{}'. Download link: {}", name, download_link),
+        None => info!("No duplicate found for '{}'.", name),
+    }
+    Ok(dupes.exact)
+}
+
+/// Cross-seed lookup: finds any torrent matching the normalized name and, for episodes,
+/// the same SxxEyy.
+pub fn check_seedpool(name: &str, seedpool_api_key: &str) -> Result<Option<String>, SeedToolsError> {
+    info!("Checking Seedpool for existing torrent with name: '{}'", name);
+
+    let normalized_name = generate_release_name(name);
+    info!("Normalized Name for Seedpool Query: '{}'", normalized_name);
+
+    let season_episode_regex = Regex::new(r"S(\d{2})E(\d{2})").unwrap();
+    let season_episode = season_episode_regex.captures(name).map(|caps| {
+        (
+            caps.get(1).unwrap().as_str().parse::<u32>().unwrap_or(0),
+            caps.get(2).unwrap().as_str().parse::<u32>().unwrap_or(0),
+        )
+    });
+    let extra_params = match &season_episode {
+        Some((season, episode)) => {
+            info!("Detected Season/Episode: S{}E{}", season, episode);
+            format!("&seasonNumber={}&episodeNumber={}", season, episode)
+        }
+        None => String::new(),
+    };
+
+    for (title, download_link) in search_torrents(SEEDPOOL_URL, "Seedpool", name, &normalized_name, &extra_params, seedpool_api_key)? {
+        info!("Checking result title: {}", title);
+
+        if let Some((season, episode)) = &season_episode
+            && !title.contains(&format!("S{:02}E{:02}", season, episode))
+        {
+            info!("Skipping result due to mismatched season/episode: {}", title);
+            continue;
+        }
+
+        if let Some(download_link) = download_link {
+            info!("Duplicate found for '{}'. Download link: {}", name, download_link);
+            return Ok(Some(download_link));
+        }
+    }
+
+    info!("No duplicate found for '{}'.", name);
+    Ok(None)
+}
+
+impl Tracker for Seedpool {
+    fn requires_screenshots(&self) -> bool {
+        true
+    }
+
+    fn requires_sample(&self) -> bool {
+        true
+    }
+
+    fn requires_tmdb_id(&self) -> bool {
+        true
+    }
+
+    fn requires_remote_path(&self) -> bool {
+        true
+    }
+
+    fn generate_metadata(&self, _: &str) -> Result<HashMap<String, String>, SeedToolsError> {
+        Ok(HashMap::from([
+            ("category".to_string(), "TV".to_string()),
+            ("original_language".to_string(), "en".to_string()),
+            ("type".to_string(), "WEB".to_string()),
+        ]))
+    }
+
+    fn upload(&self, mut upload: UploadRequest) -> Result<String, SeedToolsError> {
+        debug!(
+            "upload: category_id={}, type_id={}, tmdb_id={}, imdb_id={:?}, tvdb_id={}, season_number={:?}, episode_number={:?}, resolution_id={:?}",
+            upload.category_id, upload.type_id, upload.tmdb_id, upload.imdb_id, upload.tvdb_id, upload.season_number, upload.episode_number, upload.resolution_id
+        );
+
+        // Detect and update category_id and type_id for boxsets before submitting the form
+        if upload.category_id == self.ids.category("tv") && upload.episode_number == Some(0) {
+            upload.category_id = self.ids.category("boxset");
+            upload.type_id = self.ids.type_id("boxset");
+            debug!("Detected season-only release. Setting category_id to {} (Boxset) and type_id to {}.", upload.category_id, upload.type_id);
+        }
+
+        validate_seedpool_ids(
+            &self.upload_url,
+            &self.api_key,
+            &self.metadata_cache,
+            upload.category_id,
+            upload.type_id,
+            upload.resolution_id,
+        )?;
+
+        upload.resolution_id = Some(upload.resolution_id.unwrap_or(0));
+        upload.tv = Some(self.ids.is_tv(upload.category_id));
+        let upload = upload.with_flags(&self.flags);
+
+        submit_upload(&self.upload_url, Some(&self.api_key), &upload, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(dir: &Path, category_id: u32, season_number: Option<u32>, episode_number: Option<u32>) -> UploadRequest {
+        let torrent_file = dir.join("release.torrent");
+        std::fs::write(&torrent_file, "d4:infodee").unwrap();
+        UploadRequest {
+            torrent_file: torrent_file.to_string_lossy().to_string(),
+            name: "Some.Release-GROUP".to_string(),
+            category_id,
+            type_id: 3,
+            season_number,
+            episode_number,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn validates_movie_episode_and_season_pack_requests() {
+        let dir = tempfile::tempdir().unwrap();
+        let ids = SeedpoolIds::default();
+        let (movie, tv) = (ids.category("movie"), ids.category("tv"));
+
+        assert!(request(dir.path(), movie, None, None).validate().is_ok());
+        assert!(request(dir.path(), tv, Some(1), Some(5)).validate().is_ok());
+        assert!(request(dir.path(), tv, Some(2), Some(0)).validate().is_ok());
+        assert!(request(dir.path(), ids.category("boxset"), Some(1), Some(0)).validate().is_ok());
+
+        assert!(request(dir.path(), tv, None, Some(0)).validate().is_err());
+        assert!(request(dir.path(), movie, None, Some(5)).validate().is_err());
+        assert!(request(dir.path(), 0, None, None).validate().is_err());
+        let mut missing_nfo = request(dir.path(), movie, None, None);
+        missing_nfo.nfo_file = Some(dir.path().join("release.nfo").to_string_lossy().to_string());
+        assert!(missing_nfo.validate().is_err());
+    }
+
+    #[test]
+    fn sorts_near_dupes_from_other_releases() {
+        // (release being uploaded, search result, where the result ends up)
+        let cases = [
+            ("The.Movie.2020.1080p.BluRay.x264-GROUP", "The.Movie.2020.1080p.BluRay.x264-GROUP", "exact"),
+            // Reordered and differently tagged names of the same release
+            ("The.Movie.2020.1080p.BluRay.x264-GROUP", "The Movie 2020 BluRay 1080p x264-OTHER", "near"),
+            ("The.Movie.2020.1080p.BluRay.x264-GROUP", "The.Movie.2020.1080p.Blu-ray.DTS.x264-OTHER", "near"),
+            ("Some.Show.S01E02.1080p.WEB-DL.DDP5.1-GROUP", "Some.Show.S01E02.WEB.1080p.H264-OTHER", "near"),
+            // Revisions: a REPACK or PROPER replaces what it fixes and is not a dupe of it
+            ("The.Movie.2020.1080p.BluRay.x264.REPACK2-GROUP", "The.Movie.2020.1080p.BluRay.x264-GROUP", "replaces"),
+            ("The.Movie.2020.1080p.BluRay.x264.REPACK2-GROUP", "The.Movie.2020.1080p.BluRay.x264.REPACK-GROUP", "replaces"),
+            ("The.Movie.2020.1080p.BluRay.x264-GROUP", "The.Movie.2020.1080p.BluRay.x264.REPACK2-GROUP", "replaced_by"),
+            ("The.Movie.2020.1080p.BluRay.x264.REAL.PROPER-GROUP", "The.Movie.2020.1080p.BluRay.x264.PROPER-GROUP", "replaces"),
+            ("The.Movie.2020.1080p.BluRay.x264.PROPER-GROUP", "The.Movie.2020.1080p.BluRay.x264.REAL.PROPER-GROUP", "replaced_by"),
+            // "Proper" in the title is part of it, not a revision
+            ("The.Proper.Way.2019.1080p.WEB-DL-GROUP", "The.Proper.Way.2019.1080p.WEB-DL-OTHER", "near"),
+            ("The.Proper.Way.2019.1080p.WEB-DL-GROUP", "The.Way.2019.1080p.WEB-DL-GROUP", "none"),
+            // Other qualities of the title are listed apart
+            ("The.Movie.2020.1080p.BluRay.x264-GROUP", "The.Movie.2020.2160p.BluRay.x265-GROUP", "others"),
+            ("The.Movie.2020.1080p.BluRay.x264-GROUP", "The.Movie.2020.1080p.WEB-DL.H264-GROUP", "others"),
+            ("The.Movie.2020.1080p.BluRay.x264-GROUP", "The.Movie.2020.1080p.BluRay.REMUX.AVC-GROUP", "others"),
+            // Different releases
+            ("The.Movie.2020.1080p.BluRay.x264-GROUP", "The.Movie.2021.1080p.BluRay.x264-GROUP", "none"),
+            ("The.Movie.2020.1080p.BluRay.x264-GROUP", "The.Movie.Returns.2020.1080p.BluRay.x264-GROUP", "none"),
+            ("Some.Show.S01E02.1080p.WEB-DL.DDP5.1-GROUP", "Some.Show.S01E03.1080p.WEB-DL.DDP5.1-GROUP", "none"),
+            ("Some.Show.S01E02.1080p.WEB-DL.DDP5.1-GROUP", "Some.Show.S01.1080p.WEB-DL.DDP5.1-GROUP", "none"),
+        ];
+        for (name, result, expected) in cases {
+            let dupes = sort_dupes(name, vec![(result.to_string(), Some("https://seedpool.org/download/1".to_string()))]);
+            let found = if dupes.exact.is_some() {
+                "exact"
+            } else if !dupes.near.is_empty() {
+                "near"
+            } else if !dupes.replaces.is_empty() {
+                "replaces"
+            } else if !dupes.replaced_by.is_empty() {
+                "replaced_by"
+            } else if !dupes.others.is_empty() {
+                "others"
+            } else {
+                "none"
+            };
+            assert_eq!(found, expected, "'{}' against '{}'", result, name);
+        }
+    }
+}
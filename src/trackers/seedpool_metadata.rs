@@ -0,0 +1,187 @@
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use log::{info, warn};
+use crate::error::SeedToolsError;
+use crate::http::send_text;
+use serde::{Deserialize, Serialize};
+
+// How long the cached category/type/resolution lists are trusted before refetching
+const CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MetadataOption {
+    pub id: u32,
+    pub name: String,
+}
+
+/// The upload options Seedpool currently offers.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct SeedpoolMetadata {
+    pub categories: Vec<MetadataOption>,
+    pub types: Vec<MetadataOption>,
+    pub resolutions: Vec<MetadataOption>,
+}
+
+/// Derives the API base from the upload URL, e.g.
+/// `https://seedpool.org/api/torrents/upload?api_token=...` -> `https://seedpool.org/api`.
+fn api_base(upload_url: &str) -> Result<String, SeedToolsError> {
+    upload_url
+        .split_once("/api/")
+        .map(|(host, _)| format!("{}/api", host))
+        .ok_or_else(|| SeedToolsError::config(format!("Cannot derive the API URL from upload_url '{}'", upload_url)))
+}
+
+fn fetch_options(base: &str, endpoint: &str, api_key: &str) -> Result<Vec<MetadataOption>, SeedToolsError> {
+    let url = format!("{}/{}?api_token={}", base, endpoint, api_key);
+    let (status, body) = send_text(&url, |client| Ok(client.get(&url)))
+        .map_err(|e| e.context(&format!("Failed to fetch Seedpool {}", endpoint)))?;
+    if !status.is_success() {
+        return Err(SeedToolsError::tracker("Seedpool", Some(status.as_u16()), format!("Failed to fetch Seedpool {}: HTTP {}", endpoint, status)));
+    }
+    let body: serde_json::Value = serde_json::from_str(&body)
+        .map_err(|e| SeedToolsError::tracker("Seedpool", Some(status.as_u16()), format!("Failed to parse Seedpool {} response: {}", endpoint, e)))?;
+
+    // Accept a bare array as well as UNIT3D's `{"data": [...]}` wrapper, with or without `attributes`
+    let items = body
+        .get("data")
+        .unwrap_or(&body)
+        .as_array()
+        .ok_or_else(|| SeedToolsError::tracker("Seedpool", None, format!("Unexpected Seedpool {} response: {}", endpoint, body)))?;
+    Ok(items
+        .iter()
+        .filter_map(|item| {
+            let id = item
+                .get("id")
+                .and_then(|id| id.as_u64().or_else(|| id.as_str().and_then(|id| id.parse().ok())))?;
+            let name = item
+                .get("name")
+                .or_else(|| item.get("attributes").and_then(|attributes| attributes.get("name")))
+                .and_then(|name| name.as_str())?;
+            Some(MetadataOption { id: id as u32, name: name.to_string() })
+        })
+        .collect())
+}
+
+pub fn fetch_metadata(upload_url: &str, api_key: &str) -> Result<SeedpoolMetadata, SeedToolsError> {
+    let base = api_base(upload_url)?;
+    Ok(SeedpoolMetadata {
+        categories: fetch_options(&base, "categories", api_key)?,
+        types: fetch_options(&base, "types", api_key)?,
+        resolutions: fetch_options(&base, "resolutions", api_key)?,
+    })
+}
+
+/// Describes options the site added, removed or renumbered since `previous`.
+fn diff_metadata(previous: &SeedpoolMetadata, current: &SeedpoolMetadata) -> Vec<String> {
+    let mut changes = Vec::new();
+    let lists = [
+        ("category", &previous.categories, &current.categories),
+        ("type", &previous.types, &current.types),
+        ("resolution", &previous.resolutions, &current.resolutions),
+    ];
+    for (kind, old, new) in lists {
+        for option in new {
+            match old.iter().find(|o| o.name.eq_ignore_ascii_case(&option.name)) {
+                Some(o) if o.id != option.id => changes.push(format!(
+                    "{} '{}' renumbered from {} to {}",
+                    kind, option.name, o.id, option.id
+                )),
+                Some(_) => {}
+                None => changes.push(format!("new {} '{}' (id {})", kind, option.name, option.id)),
+            }
+        }
+        for option in old {
+            if !new.iter().any(|o| o.name.eq_ignore_ascii_case(&option.name)) {
+                changes.push(format!("{} '{}' (id {}) was removed", kind, option.name, option.id));
+            }
+        }
+    }
+    changes
+}
+
+/// Returns Seedpool's categories/types/resolutions, refetching once the cache at `cache_path`
+/// is older than a day. Changes since the cached copy are logged as warnings, and a stale
+/// cache is used when the site cannot be reached.
+pub fn load_metadata(upload_url: &str, api_key: &str, cache_path: &Path) -> Result<SeedpoolMetadata, SeedToolsError> {
+    let cached: Option<SeedpoolMetadata> = fs::read_to_string(cache_path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok());
+    let is_fresh = fs::metadata(cache_path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|age| age < CACHE_TTL);
+    if let (Some(cached), true) = (&cached, is_fresh) {
+        return Ok(cached.clone());
+    }
+
+    match fetch_metadata(upload_url, api_key) {
+        Ok(metadata) => {
+            if let Some(previous) = &cached {
+                for change in diff_metadata(previous, &metadata) {
+                    warn!("Seedpool upload options changed: {}", change);
+                }
+            }
+            if let Some(parent) = cache_path.parent() {
+                let _ = fs::create_dir_all(parent);
+            }
+            let written = serde_json::to_string_pretty(&metadata)
+                .map_err(|e| e.to_string())
+                .and_then(|json| fs::write(cache_path, json).map_err(|e| e.to_string()));
+            if let Err(e) = written {
+                warn!("Failed to cache Seedpool metadata at {}: {}", cache_path.display(), e);
+            }
+            info!(
+                "Fetched Seedpool metadata: {} categories, {} types, {} resolutions",
+                metadata.categories.len(),
+                metadata.types.len(),
+                metadata.resolutions.len()
+            );
+            Ok(metadata)
+        }
+        Err(e) => match cached {
+            Some(cached) => {
+                warn!("{}; using the cached Seedpool metadata", e);
+                Ok(cached)
+            }
+            None => Err(e),
+        },
+    }
+}
+
+fn check_id(kind: &str, options: &[MetadataOption], id: u32) -> Result<(), SeedToolsError> {
+    // 0 means "not set", and an empty list means the site did not report any options
+    if id == 0 || options.is_empty() || options.iter().any(|option| option.id == id) {
+        return Ok(());
+    }
+    let known: Vec<String> = options.iter().map(|option| format!("{} ({})", option.id, option.name)).collect();
+    Err(SeedToolsError::config(format!("Seedpool has no {} with id {}. Known: {}", kind, id, known.join(", "))))
+}
+
+impl SeedpoolMetadata {
+    pub fn validate_ids(&self, category_id: u32, type_id: u32, resolution_id: Option<u32>) -> Result<(), SeedToolsError> {
+        check_id("category", &self.categories, category_id)?;
+        check_id("type", &self.types, type_id)?;
+        check_id("resolution", &self.resolutions, resolution_id.unwrap_or(0))
+    }
+}
+
+/// Checks upload IDs against the site's current options before uploading. Validation is
+/// skipped (with a warning) when the options cannot be loaded at all.
+pub fn validate_seedpool_ids(
+    upload_url: &str,
+    api_key: &str,
+    cache_path: &Path,
+    category_id: u32,
+    type_id: u32,
+    resolution_id: Option<u32>,
+) -> Result<(), SeedToolsError> {
+    match load_metadata(upload_url, api_key, cache_path) {
+        Ok(metadata) => metadata.validate_ids(category_id, type_id, resolution_id),
+        Err(e) => {
+            warn!("Skipping Seedpool category/type/resolution validation: {}", e);
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+use regex::Regex;
+use crate::error::SeedToolsError;
+use crate::bbcode::Builder;
+use crate::types::TorrentLeechDescriptionConfig;
+use crate::utils::description_footer;
+
+/// Parses the details TorrentLeech categories depend on from a scene-style release name:
+/// `category` (MOVIE or TV), `tv_pack`, `type` (REMUX, ENCODE, WEB-DL, WEBRIP, HDTV, CAM or TS),
+/// `source`, `uhd`, `sd` and `is_disc`. The language is not in the name, so English is assumed.
+pub fn parse_release_meta(release_name: &str) -> HashMap<String, String> {
+    let name = release_name.replace(['.', '_'], " ");
+    let has = |pattern: &str| Regex::new(&format!(r"(?i)\b({})\b", pattern)).unwrap().is_match(&name);
+    let mut meta = HashMap::from([("original_language".to_string(), "en".to_string())]);
+
+    let episode = has(r"S\d{1,2}E\d{1,3}|\d{1,2}x\d{2}");
+    let season = has(r"S\d{1,2}|Season \d+|Complete Series");
+    meta.insert("category".to_string(), if episode || season { "TV" } else { "MOVIE" }.to_string());
+    meta.insert("tv_pack".to_string(), (season && !episode).to_string());
+
+    let source = if has("BluRay|Blu-Ray|BDRip|BRRip|BDMV|BD25|BD50") {
+        "BluRay"
+    } else if has("HDDVD|HD-DVD") {
+        "HDDVD"
+    } else if has("DVDRip|DVD|DVD5|DVD9|VIDEO_TS") {
+        "DVD"
+    } else if has("WEB|WEB-DL|WEBDL|WEBRip|AMZN|NF|DSNP|HMAX|ATVP") {
+        "WEB"
+    } else if has("HDTV|PDTV|SDTV") {
+        "HDTV"
+    } else {
+        ""
+    };
+    meta.insert("source".to_string(), source.to_string());
+
+    let release_type = if has("CAM|HDCAM|CAMRip") {
+        "CAM"
+    } else if has("TS|HDTS|TELESYNC|TC|TELECINE") {
+        "TS"
+    } else if has("REMUX") {
+        "REMUX"
+    } else if has("WEBRip") {
+        "WEBRIP"
+    } else if source == "WEB" {
+        "WEB-DL"
+    } else if source == "HDTV" {
+        "HDTV"
+    } else {
+        "ENCODE"
+    };
+    meta.insert("type".to_string(), release_type.to_string());
+
+    if has("BDMV|BD25|BD50|COMPLETE BLURAY") {
+        meta.insert("is_disc".to_string(), "BDMV".to_string());
+    } else if has("VIDEO_TS|DVD5|DVD9|DVDR") {
+        meta.insert("is_disc".to_string(), "DVD".to_string());
+    }
+    meta.insert("uhd".to_string(), has("2160p|4K|UHD").to_string());
+    meta.insert("sd".to_string(), (has("480p|576p|480i|576i|SD") || !has(r"\d{3,4}[pi]")).to_string());
+    meta
+}
+
+/// Picks the TorrentLeech category for release metadata (`category`, `type`, `source`, ...),
+/// preferring IDs from the tracker's `categories` map over the built-in defaults.
+pub fn determine_tl_category(meta: &HashMap<String, String>, categories: &HashMap<String, u32>) -> Result<u32, SeedToolsError> {
+    if meta.get("anime").is_some_and(|v| v == "true") {
+        return Ok(*categories.get("Anime").unwrap_or(&34));
+    }
+    match meta.get("category").map(|v| v.as_str()) {
+        Some("MOVIE") => {
+            if meta.get("type").is_some_and(|v| v == "CAM") {
+                Ok(*categories.get("MovieCam").unwrap_or(&8))
+            } else if meta.get("type").is_some_and(|v| v == "TS") {
+                Ok(*categories.get("MovieTS").unwrap_or(&9))
+            } else if meta.get("original_language").is_some_and(|lang| lang != "en") {
+                Ok(*categories.get("MovieForeign").unwrap_or(&36))
+            } else if meta.get("genres").is_some_and(|genres| genres.contains("Documentary")) {
+                Ok(*categories.get("MovieDocumentary").unwrap_or(&29))
+            } else if meta.get("uhd").is_some_and(|v| v == "true") {
+                Ok(*categories.get("Movie4K").unwrap_or(&47))
+            } else if meta.get("is_disc").is_some_and(|v| v == "BDMV" || v == "HDDVD")
+                || (meta.get("type").is_some_and(|v| v == "REMUX")
+                    && meta.get("source").is_some_and(|v| v == "BluRay" || v == "HDDVD"))
+            {
+                Ok(*categories.get("MovieBluray").unwrap_or(&13))
+            } else if meta.get("type").is_some_and(|v| v == "ENCODE")
+                && meta.get("source").is_some_and(|v| v == "BluRay" || v == "HDDVD")
+            {
+                Ok(*categories.get("MovieBlurayRip").unwrap_or(&14))
+            } else if meta.get("is_disc").is_some_and(|v| v == "DVD")
+                || (meta.get("type").is_some_and(|v| v == "REMUX")
+                    && meta.get("source").is_some_and(|v| v.contains("DVD")))
+            {
+                Ok(*categories.get("MovieDvd").unwrap_or(&12))
+            } else if meta.get("type").is_some_and(|v| v == "ENCODE")
+                && meta.get("source").is_some_and(|v| v.contains("DVD"))
+            {
+                Ok(*categories.get("MovieDvdRip").unwrap_or(&11))
+            } else if meta.get("type").is_some_and(|v| v.contains("WEB")) {
+                Ok(*categories.get("MovieWebrip").unwrap_or(&37))
+            } else if meta.get("type").is_some_and(|v| v == "HDTV") || meta.get("source").is_some_and(|v| v.is_empty()) {
+                // Encodes that do not name their source land here too
+                Ok(*categories.get("MovieHdRip").unwrap_or(&43))
+            } else {
+                Err(SeedToolsError::Metadata("Failed to determine TorrentLeech movie category.".to_string()))
+            }
+        }
+        Some("TV") => {
+            if meta.get("original_language").is_some_and(|lang| lang != "en") {
+                Ok(*categories.get("TvForeign").unwrap_or(&44))
+            } else if meta.get("tv_pack").is_some_and(|v| v == "true") {
+                Ok(*categories.get("TvBoxsets").unwrap_or(&27))
+            } else if meta.get("sd").is_some_and(|v| v == "true") {
+                Ok(*categories.get("TvEpisodes").unwrap_or(&26))
+            } else {
+                Ok(*categories.get("TvEpisodesHd").unwrap_or(&32))
+            }
+        }
+        _ => Err(SeedToolsError::Metadata("Failed to determine TorrentLeech category.".to_string())),
+    }
+}
+
+/// The description of a TorrentLeech upload. The NFO is shown above it with the MediaInfo, so
+/// it stays short: the release name, the proof image when `placement: spoiler` puts it in the
+/// description, `custom_description` and the footer. Written for `config.bbcode`, which by
+/// default has no spoilers, tables or sized images.
+pub fn build_description(release_name: &str, proof_url: Option<&str>, custom_description: &str, config: &TorrentLeechDescriptionConfig) -> String {
+    let mut description = Builder::new(&config.bbcode);
+    description.centered(&format!("[b]{}[/b]", release_name)).paragraph();
+    if let Some(proof_url) = proof_url {
+        description.spoiler("Proof", &format!("[img]{}[/img]", proof_url)).paragraph();
+    }
+    if !custom_description.trim().is_empty() {
+        description.raw(custom_description.trim()).paragraph();
+    }
+    match &config.footer_text {
+        Some(text) => description.raw(text.trim()),
+        None => description.centered(&description_footer(config.footer)),
+    };
+    description.build().trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_release_names_to_categories() {
+        // (release name, TorrentLeech category id)
+        let cases = [
+            ("Movie.2020.1080p.BluRay.x264-GRP", 14),
+            ("Movie.2020.1080p.BluRay.REMUX.AVC.DTS-HD.MA.5.1-GRP", 13),
+            ("Movie.2020.COMPLETE.BLURAY-GRP", 13),
+            ("Movie.2020.2160p.UHD.BluRay.x265-GRP", 47),
+            ("Movie.2020.2160p.WEB-DL.DDP5.1.H265-GRP", 47),
+            ("Movie.2020.1080p.WEB-DL.DDP5.1.H264-GRP", 37),
+            ("Movie.2020.720p.WEBRip.x264-GRP", 37),
+            ("Movie.2020.DVDRip.x264-GRP", 11),
+            ("Movie.2020.PAL.DVD9-GRP", 12),
+            ("Movie.2020.720p.HDTV.x264-GRP", 43),
+            ("Movie.2020.1080p.x264-GRP", 43),
+            ("Movie.2020.HDCAM.x264-GRP", 8),
+            ("Movie.2020.HDTS.x264-GRP", 9),
+            ("Show.S01E02.1080p.WEB-DL.DDP5.1.H264-GRP", 32),
+            ("Show.S01E02.720p.HDTV.x264-GRP", 32),
+            ("Show.S01E02.480p.WEB.x264-GRP", 26),
+            ("Show.S01E02.DVDRip.x264-GRP", 26),
+            ("Show.S01.1080p.BluRay.x264-GRP", 27),
+            ("Show.Complete.Series.720p.WEB-DL-GRP", 27),
+        ];
+        for (name, expected) in cases {
+            let meta = parse_release_meta(name);
+            let category = determine_tl_category(&meta, &HashMap::new()).unwrap();
+            assert_eq!(category, expected, "{} ({:?})", name, meta);
+        }
+    }
+
+    #[test]
+    fn prefers_configured_category_ids() {
+        let categories = HashMap::from([("MovieBlurayRip".to_string(), 99)]);
+        let meta = parse_release_meta("Movie.2020.1080p.BluRay.x264-GRP");
+        assert_eq!(determine_tl_category(&meta, &categories).unwrap(), 99);
+        let meta = parse_release_meta("Movie.2020.1080p.WEB-DL.H264-GRP");
+        assert_eq!(determine_tl_category(&meta, &categories).unwrap(), 37);
+    }
+
+    #[test]
+    fn maps_foreign_documentary_and_anime_metadata() {
+        let mut meta = parse_release_meta("Movie.2020.1080p.BluRay.x264-GRP");
+        meta.insert("genres".to_string(), "Documentary, History".to_string());
+        assert_eq!(determine_tl_category(&meta, &HashMap::new()).unwrap(), 29);
+        meta.insert("original_language".to_string(), "fr".to_string());
+        assert_eq!(determine_tl_category(&meta, &HashMap::new()).unwrap(), 36);
+        meta.insert("anime".to_string(), "true".to_string());
+        assert_eq!(determine_tl_category(&meta, &HashMap::new()).unwrap(), 34);
+
+        let mut meta = parse_release_meta("Show.S01E02.1080p.WEB-DL-GRP");
+        meta.insert("original_language".to_string(), "ko".to_string());
+        assert_eq!(determine_tl_category(&meta, &HashMap::new()).unwrap(), 44);
+        assert!(determine_tl_category(&HashMap::new(), &HashMap::new()).is_err());
+    }
+}
@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+use std::path::Path;
+use log::{debug, info};
+use regex::Regex;
+use crate::error::SeedToolsError;
+use crate::trackers::seedpool::{search_torrents, submit_upload, UploadRequest};
+use crate::trackers::{load_named_configs, Tracker};
+use crate::types::Unit3dConfig;
+use crate::utils::generate_release_name;
+
+/// Loads the UNIT3D trackers set up in `dir` (usually `config/trackers/unit3d`).
+pub fn load_unit3d_configs(dir: &Path) -> Result<Vec<Unit3dConfig>, SeedToolsError> {
+    Ok(load_named_configs::<Unit3dConfig>(dir)?
+        .into_iter()
+        .map(|(name, config)| Unit3dConfig { name, ..config })
+        .collect())
+}
+
+/// Finds the UNIT3D tracker called `name` (case-insensitive).
+pub fn find_unit3d_config<'a>(configs: &'a [Unit3dConfig], name: &str) -> Result<&'a Unit3dConfig, SeedToolsError> {
+    configs.iter().find(|config| config.name.eq_ignore_ascii_case(name)).ok_or_else(|| {
+        let known: Vec<&str> = configs.iter().map(|config| config.name.as_str()).collect();
+        SeedToolsError::config(format!(
+            "No UNIT3D tracker named '{}' in config/trackers/unit3d (found: {})",
+            name,
+            if known.is_empty() { "none".to_string() } else { known.join(", ") }
+        ))
+    })
+}
+
+/// Key of the `types` map that fits a release name: disc, remux, encode, web-dl, webrip or hdtv.
+pub fn detect_type(release_name: &str) -> &'static str {
+    let name = release_name.to_lowercase();
+    if Regex::new(r"\b(bdmv|complete\.bluray|iso)\b").unwrap().is_match(&name) {
+        "disc"
+    } else if name.contains("remux") {
+        "remux"
+    } else if Regex::new(r"web[-. ]?dl|\bweb\b").unwrap().is_match(&name) {
+        "web-dl"
+    } else if name.contains("webrip") {
+        "webrip"
+    } else if name.contains("hdtv") {
+        "hdtv"
+    } else {
+        "encode"
+    }
+}
+
+/// Key of the `resolutions` map that fits a release name, e.g. `1080p`, or `other`.
+pub fn detect_resolution(release_name: &str) -> String {
+    Regex::new(r"(?i)\b(4320p|2160p|1440p|1080p|1080i|720p|576p|576i|480p|480i)\b")
+        .unwrap()
+        .captures(release_name)
+        .map(|captures| captures[1].to_lowercase())
+        .unwrap_or_else(|| "other".to_string())
+}
+
+/// Looks up the category, type and resolution IDs of a release in the tracker's maps.
+/// `release_type` is movie, tv or boxset and `release_kind` a key of the `types` map (see
+/// `detect_source_type`); the resolution is left out when the map has no entry.
+pub fn resolve_ids(config: &Unit3dConfig, release_type: &str, release_name: &str, release_kind: &str) -> Result<(u32, u32, Option<u32>), SeedToolsError> {
+    let lookup = |map: &HashMap<String, u32>, key: &str| {
+        map.iter().find(|(name, _)| name.eq_ignore_ascii_case(key)).map(|(_, id)| *id)
+    };
+    let category_id = lookup(&config.categories, release_type)
+        .ok_or_else(|| SeedToolsError::config(format!("No '{}' entry under categories in unit3d/{}.yaml", release_type, config.name)))?;
+    let type_id = lookup(&config.types, release_kind)
+        .ok_or_else(|| SeedToolsError::config(format!("No '{}' entry under types in unit3d/{}.yaml", release_kind, config.name)))?;
+    let resolution = detect_resolution(release_name);
+    let resolution_id = lookup(&config.resolutions, &resolution).or_else(|| lookup(&config.resolutions, "other"));
+    debug!(
+        "{}: {} -> category {}, {} -> type {}, {} -> resolution {:?}",
+        config.name, release_type, category_id, release_kind, type_id, resolution, resolution_id
+    );
+    Ok((category_id, type_id, resolution_id))
+}
+
+pub fn upload_url(config: &Unit3dConfig) -> String {
+    format!("{}/api/torrents/upload", config.base_url.trim_end_matches('/'))
+}
+
+/// Upload dupe check: looks for a torrent whose name exactly matches the normalized release name.
+pub fn check_unit3d_dupes(config: &Unit3dConfig, name: &str) -> Result<Option<String>, SeedToolsError> {
+    let search_term = generate_release_name(name);
+    info!("Checking {} for existing torrent with name: '{}'", config.name, search_term);
+    for (title, download_link) in search_torrents(&config.base_url, &config.name, name, &search_term, "", &config.api_key)? {
+        if title == search_term
+            && let Some(download_link) = download_link
+        {
+            info!("Duplicate found on {} for '{}'. Download link: {}", config.name, name, download_link);
+            return Ok(Some(download_link));
+        }
+    }
+    info!("No duplicate found on {} for '{}'.", config.name, name);
+    Ok(None)
+}
+
+/// A UNIT3D tracker set up purely from its YAML file.
+pub struct Unit3d<'a> {
+    pub config: &'a Unit3dConfig,
+}
+
+impl Tracker for Unit3d<'_> {
+    fn requires_screenshots(&self) -> bool {
+        false
+    }
+
+    fn requires_sample(&self) -> bool {
+        false
+    }
+
+    fn requires_tmdb_id(&self) -> bool {
+        true
+    }
+
+    fn requires_remote_path(&self) -> bool {
+        false
+    }
+
+    fn generate_metadata(&self, _: &str) -> Result<HashMap<String, String>, SeedToolsError> {
+        Ok(HashMap::new())
+    }
+
+    fn upload(&self, mut upload: UploadRequest) -> Result<String, SeedToolsError> {
+        // Season and episode numbers only go with the categories mapped to tv or boxset
+        let tv = self
+            .config
+            .categories
+            .iter()
+            .any(|(name, id)| *id == upload.category_id && (name.eq_ignore_ascii_case("tv") || name.eq_ignore_ascii_case("boxset")));
+        upload.tv = Some(tv);
+        let upload = upload.with_flags(&self.config.upload_flags);
+
+        submit_upload(&upload_url(self.config), Some(&self.config.api_key), &upload, None)
+    }
+}
@@ -0,0 +1,212 @@
+use std::cmp::Ordering;
+use serde::{Deserialize, Serialize};
+use crate::trackers::seedpool::ReleaseKey;
+use crate::types::TrumpingConfig;
+
+// Trump detection: compares the quality a release name claims (resolution, source, HDR,
+// audio) with the other uploads of the same title, so the pre-flight check can tell a
+// legitimate trump from an upload that is itself worse than what the tracker has.
+
+/// How a release compares with the tracker's other uploads of the title.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Trumps, // Better than all of them
+    SameQuality, // As good as one, worse than none
+    LowerQuality, // Worse than at least one
+}
+
+/// The verdict and the pre-flight line that explains it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Assessment {
+    pub verdict: Verdict,
+    pub summary: String,
+}
+
+/// Quality properties read from a release name. `None` when the name does not say.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quality {
+    pub resolution: Option<u32>, // Lines, e.g. 2160
+    pub source: Option<String>,
+    pub hdr: Option<String>,
+    pub audio: Option<String>, // The best format named, by the policy's ranking
+}
+
+impl Quality {
+    pub fn parse(name: &str, config: &TrumpingConfig) -> Quality {
+        let key = ReleaseKey::parse(name);
+        let lower = name.to_lowercase();
+        let tokens: Vec<&str> = lower.split(['.', ' ', '-', '_']).filter(|token| !token.is_empty()).collect();
+        let mut hdr = Vec::new();
+        let mut audio = Vec::new();
+        for (i, token) in tokens.iter().enumerate() {
+            let next = tokens.get(i + 1).copied().unwrap_or_default();
+            match *token {
+                "dv" | "dovi" => hdr.push("dv"),
+                "dolby" if next == "vision" => hdr.push("dv"),
+                "hdr10+" | "hdr10plus" => hdr.push("hdr10+"),
+                "hdr10" => hdr.push("hdr10"),
+                "hdr" => hdr.push("hdr"),
+                "hlg" => hdr.push("hlg"),
+                "atmos" => audio.push("atmos"),
+                "truehd" => audio.push("truehd"),
+                "dtsx" | "dts:x" => audio.push("dts:x"),
+                "dts" if next == "x" => audio.push("dts:x"),
+                "dts" if next == "hd" || next == "hdma" => audio.push("dts-hd ma"),
+                "dtshd" => audio.push("dts-hd ma"),
+                "dts" => audio.push("dts"),
+                "lpcm" | "pcm" => audio.push("lpcm"),
+                "flac" => audio.push("flac"),
+                "opus" => audio.push("opus"),
+                "mp3" => audio.push("mp3"),
+                token if token.starts_with("ddp") || token.starts_with("eac3") || token.starts_with("dd+") => audio.push("ddp"),
+                token if token == "ac3" || (token.starts_with("dd") && token[2..].chars().all(|c| c.is_ascii_digit())) => audio.push("dd"),
+                token if token.starts_with("aac") => audio.push("aac"),
+                _ => {}
+            }
+        }
+        // Names without an HDR format are taken as SDR
+        if hdr.is_empty() {
+            hdr.push("sdr");
+        }
+        Quality {
+            resolution: key.resolution.and_then(|resolution| resolution.trim_end_matches(['p', 'i']).parse().ok()),
+            source: key.source,
+            hdr: best(&hdr, &config.hdr),
+            audio: best(&audio, &config.audio),
+        }
+    }
+}
+
+/// The entry of `found` ranked best in `ranking` (best first); unranked entries lose.
+fn best(found: &[&str], ranking: &[String]) -> Option<String> {
+    found.iter().min_by_key(|value| rank(value, ranking)).map(|value| value.to_string())
+}
+
+fn rank(value: &str, ranking: &[String]) -> usize {
+    ranking.iter().position(|ranked| ranked.eq_ignore_ascii_case(value)).unwrap_or(ranking.len())
+}
+
+/// Compares `mine` with `theirs` on the policy's criteria in order; the first one both names
+/// and that differs decides. `Greater` means mine is better. Returns the deciding criterion
+/// with both values.
+pub fn compare(mine: &Quality, theirs: &Quality, config: &TrumpingConfig) -> (Ordering, Option<String>) {
+    for criterion in &config.criteria {
+        let ordering = match criterion.as_str() {
+            "resolution" => match (mine.resolution, theirs.resolution) {
+                (Some(a), Some(b)) => Some((a.cmp(&b), format!("{}p", a), format!("{}p", b))),
+                _ => None,
+            },
+            "source" => ranked(&mine.source, &theirs.source, &config.sources),
+            "hdr" => ranked(&mine.hdr, &theirs.hdr, &config.hdr),
+            "audio" => ranked(&mine.audio, &theirs.audio, &config.audio),
+            _ => None,
+        };
+        if let Some((ordering, mine, theirs)) = ordering.filter(|(ordering, _, _)| ordering.is_ne()) {
+            return (ordering, Some(format!("{} {} vs {}", criterion, mine, theirs)));
+        }
+    }
+    (Ordering::Equal, None)
+}
+
+fn ranked(mine: &Option<String>, theirs: &Option<String>, ranking: &[String]) -> Option<(Ordering, String, String)> {
+    let (mine, theirs) = (mine.as_ref()?, theirs.as_ref()?);
+    // A lower rank is better, so the comparison is reversed
+    Some((rank(theirs, ranking).cmp(&rank(mine, ranking)), mine.clone(), theirs.clone()))
+}
+
+/// Compares `release_name` with the tracker's `others` of the same title for the pre-flight
+/// result, or `None` when there are none.
+pub fn assess(release_name: &str, others: &[String], config: &TrumpingConfig) -> Option<Assessment> {
+    if others.is_empty() {
+        return None;
+    }
+    let mine = Quality::parse(release_name, config);
+    let mut better = Vec::new();
+    let mut worse = Vec::new();
+    let mut same = Vec::new();
+    for other in others {
+        match compare(&mine, &Quality::parse(other, config), config) {
+            (Ordering::Greater, reason) => better.push(format!("{} ({})", other, reason.unwrap_or_default())),
+            (Ordering::Less, reason) => worse.push(format!("{} ({})", other, reason.unwrap_or_default())),
+            (Ordering::Equal, _) => same.push(other.clone()),
+        }
+    }
+    let (verdict, summary) = if !worse.is_empty() {
+        (Verdict::LowerQuality, format!("⚠️ Lower quality than {}", worse.join(", ")))
+    } else if !same.is_empty() {
+        (Verdict::SameQuality, format!("⚠️ Same quality as {}", same.join(", ")))
+    } else {
+        (Verdict::Trumps, format!("✔️ Trumps {}", better.join(", ")))
+    };
+    Some(Assessment { verdict, summary })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_quality_from_release_names() {
+        let config = TrumpingConfig::default();
+        let remux = Quality::parse("Movie.2020.2160p.UHD.BluRay.REMUX.DV.HDR10.TrueHD.7.1.Atmos-GROUP", &config);
+        assert_eq!(
+            remux,
+            Quality {
+                resolution: Some(2160),
+                source: Some("remux".to_string()),
+                hdr: Some("dv".to_string()),
+                audio: Some("atmos".to_string()),
+            }
+        );
+        let web = Quality::parse("Movie.2020.1080p.WEB-DL.DDP5.1.H.264-GROUP", &config);
+        assert_eq!((web.resolution, web.source.as_deref()), (Some(1080), Some("web")));
+        assert_eq!((web.hdr.as_deref(), web.audio.as_deref()), (Some("sdr"), Some("ddp")));
+        assert_eq!(Quality::parse("Movie.2020.BluRay.DTS-HD.MA.5.1-GROUP", &config).audio.as_deref(), Some("dts-hd ma"));
+        assert_eq!(Quality::parse("Movie 2020 Dolby Vision DD5.1", &config).hdr.as_deref(), Some("dv"));
+        let bare = Quality::parse("Movie.2020-GROUP", &config);
+        assert_eq!((bare.resolution, bare.source, bare.audio), (None, None, None));
+    }
+
+    #[test]
+    fn first_differing_criterion_decides() {
+        let config = TrumpingConfig::default();
+        let parse = |name: &str| Quality::parse(name, &config);
+        let remux_1080 = parse("Movie.2020.1080p.BluRay.REMUX.DTS-HD.MA-GROUP");
+        let web_2160 = parse("Movie.2020.2160p.WEB-DL.DDP5.1-GROUP");
+        assert_eq!(compare(&web_2160, &remux_1080, &config), (Ordering::Greater, Some("resolution 2160p vs 1080p".to_string())));
+        assert_eq!(compare(&remux_1080, &web_2160, &config), (Ordering::Less, Some("resolution 1080p vs 2160p".to_string())));
+
+        // Criteria only one name mentions are skipped
+        let bluray = parse("Movie.2020.1080p.BluRay.x264-GROUP");
+        let unnamed = parse("Movie.2020.1080p.x264.TrueHD-GROUP");
+        assert_eq!(compare(&bluray, &unnamed, &config), (Ordering::Equal, None));
+
+        let audio_first = TrumpingConfig { criteria: vec!["audio".to_string(), "resolution".to_string()], ..Default::default() };
+        assert_eq!(compare(&remux_1080, &web_2160, &audio_first), (Ordering::Greater, Some("audio dts-hd ma vs ddp".to_string())));
+    }
+
+    #[test]
+    fn assesses_against_every_other_upload() {
+        let config = TrumpingConfig::default();
+        let others = |names: &[&str]| names.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+        let release = "Movie.2020.2160p.WEB-DL.DV.DDP5.1-GROUP";
+        assert_eq!(assess(release, &[], &config), None);
+
+        let trumps = assess(release, &others(&["Movie.2020.1080p.WEB-DL.DDP5.1-OTHER", "Movie.2020.2160p.WEB-DL.HDR10.DDP5.1-OTHER"]), &config).unwrap();
+        assert_eq!(trumps.verdict, Verdict::Trumps);
+        assert_eq!(
+            trumps.summary,
+            "✔️ Trumps Movie.2020.1080p.WEB-DL.DDP5.1-OTHER (resolution 2160p vs 1080p), Movie.2020.2160p.WEB-DL.HDR10.DDP5.1-OTHER (hdr dv vs hdr10)"
+        );
+
+        let same = assess(release, &others(&["Movie.2020.1080p.WEB-DL-OTHER", "Movie.2020.2160p.WEB.DV.DDP5.1-OTHER"]), &config).unwrap();
+        assert_eq!(same.verdict, Verdict::SameQuality);
+        assert_eq!(same.summary, "⚠️ Same quality as Movie.2020.2160p.WEB.DV.DDP5.1-OTHER");
+
+        // One better upload outweighs any number of worse ones
+        let lower = assess(release, &others(&["Movie.2020.2160p.WEB.DV.DDP5.1-OTHER", "Movie.2020.2160p.BluRay.REMUX.DV-OTHER"]), &config).unwrap();
+        assert_eq!(lower.verdict, Verdict::LowerQuality);
+        assert_eq!(lower.summary, "⚠️ Lower quality than Movie.2020.2160p.BluRay.REMUX.DV-OTHER (source web vs remux)");
+    }
+}
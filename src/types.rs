@@ -1,5 +1,9 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use crate::error::SeedToolsError;
+use crate::estimate::ProcessingEstimate;
+use crate::notifications::NotificationEvent;
+use crate::trumping::Verdict;
 
 #[derive(Deserialize)]
 pub struct GeneralConfig {
@@ -8,29 +12,135 @@ pub struct GeneralConfig {
     pub igdb_bearer_token: String,
 }
 
+#[derive(Serialize)]
 pub struct PreflightCheckResult {
     pub release_name: String,
     pub generated_release_name: String,
     pub dupe_check: String,
+    pub dupe: bool, // Already on Seedpool, by name or by its files
+    pub dupe_torrent: Option<String>, // Page of the torrent the dupe check matched
+    #[serde(skip)]
+    pub dupe_download: Option<String>, // Download link of the exact dupe; holds the passkey
+    pub trump_check: String, // How the release compares with other uploads of the title
+    pub trump: Option<Verdict>, // The trump check's outcome; None when there was nothing to compare
     pub tmdb_id: u32,
     pub imdb_id: Option<String>,
     pub tvdb_id: Option<u32>,
     pub excluded_files: String,
     pub album_cover: String,
     pub audio_languages: Vec<String>,
+    pub subtitle_languages: Vec<String>, // Embedded tracks, then external files
+    pub source_type: String, // remux, encode, web-dl, ... and why
     pub release_type: String,
     pub season_number: Option<u32>,
     pub episode_number: Option<u32>,
+    pub disc: Option<String>, // BDMV or VIDEO_TS, and the file its MediaInfo comes from
+    pub existing_samples: Vec<String>, // Sample files in the release and what happens to each
+    #[serde(flatten)]
+    pub estimate: ProcessingEstimate,
+}
+
+/// User overrides for auto-detected upload details, set from `--edit` prompts,
+/// the override flags (`--tmdb`, `--release-name`, ...) or the UI edit form.
+/// `None` keeps the detected value.
+#[derive(Deserialize, Default, Clone, Debug)]
+#[serde(default)]
+pub struct UploadOverrides {
+    pub release_name: Option<String>,
+    pub tmdb_id: Option<u32>,
+    pub imdb_id: Option<String>,
+    pub tvdb_id: Option<u32>,
+    pub season_number: Option<u32>,
+    pub episode_number: Option<u32>,
+    pub category_id: Option<u32>,
+    pub type_id: Option<u32>,
+    pub release_type: Option<String>, // "adult" from --type adult; detected from the name otherwise
+}
+
+impl UploadOverrides {
+    pub fn is_empty(&self) -> bool {
+        self.release_name.is_none()
+            && self.tmdb_id.is_none()
+            && self.imdb_id.is_none()
+            && self.tvdb_id.is_none()
+            && self.season_number.is_none()
+            && self.episode_number.is_none()
+            && self.category_id.is_none()
+            && self.type_id.is_none()
+            && self.release_type.is_none()
+    }
+
+    /// Command line flags that pass these overrides to a `seed-tools` upload process. `--type`
+    /// takes a type ID or a release kind, so overrides can not set both.
+    pub fn to_args(&self) -> Result<Vec<String>, SeedToolsError> {
+        let mut args = Vec::new();
+        if let Some(name) = &self.release_name {
+            args.push("--release-name".to_string());
+            args.push(name.clone());
+        }
+        if let Some(id) = self.tmdb_id {
+            args.push("--tmdb".to_string());
+            args.push(id.to_string());
+        }
+        if let Some(id) = &self.imdb_id {
+            args.push("--imdb".to_string());
+            args.push(id.clone());
+        }
+        if let Some(id) = self.tvdb_id {
+            args.push("--tvdb".to_string());
+            args.push(id.to_string());
+        }
+        if let Some(season) = self.season_number {
+            args.push("--season".to_string());
+            args.push(season.to_string());
+        }
+        if let Some(episode) = self.episode_number {
+            args.push("--episode".to_string());
+            args.push(episode.to_string());
+        }
+        if let Some(id) = self.category_id {
+            args.push("--category".to_string());
+            args.push(id.to_string());
+        }
+        match (self.type_id, &self.release_type) {
+            (Some(id), Some(release_type)) => {
+                return Err(SeedToolsError::config(format!(
+                    "Set either the type ID {} or the release type '{}', not both",
+                    id, release_type
+                )));
+            }
+            (Some(id), None) => {
+                args.push("--type".to_string());
+                args.push(id.to_string());
+            }
+            (None, Some(release_type)) => {
+                args.push("--type".to_string());
+                args.push(release_type.clone());
+            }
+            (None, None) => {}
+        }
+        Ok(args)
+    }
 }
 
 #[derive(Deserialize)]
 pub struct PathsConfig {
     pub torrent_dir: String,
     pub screenshots_dir: String,
+    // External tools; looked up on $PATH when left out
+    #[serde(default)]
     pub ffmpeg: String,
+    #[serde(default)]
     pub ffprobe: String,
+    #[serde(default)]
     pub mkbrr: String,
+    #[serde(default)]
     pub mediainfo: String,
+    pub work_dir: Option<String>, // Upload checkpoints; defaults to ./work
+    pub staging_dir: Option<String>, // Hardlinked copies named after the release, when it differs from the local name
+    #[serde(default)]
+    pub staging_copy: bool, // Copy files that can not be hardlinked, e.g. across filesystems
+    pub temp_dir: Option<String>, // Intermediates such as extracted covers and pages; defaults to <system temp>/seed-tools
 }
 
 #[derive(Deserialize)]
@@ -42,6 +152,16 @@ pub struct QbittorrentConfig {
     pub default_save_path: String,
     pub executable: Option<String>,
     pub fastresumes: String,
+    #[serde(default)]
+    pub path_mappings: Vec<PathMapping>, // For clients that see the data under another path
+    #[serde(default)]
+    pub auto_tmm: bool, // Let the category decide the save path instead of seed-tools
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub recheck: bool, // Hash check injected torrents instead of skipping the check
+    #[serde(default)]
+    pub reseed_category: Option<String>, // --sync uploads torrents in this category that Seedpool lacks
 }
 
 #[derive(Deserialize)]
@@ -52,6 +172,44 @@ pub struct DelugeConfig {
     pub password: String,
     pub label: Option<String>,
     pub default_save_path: String,
+    #[serde(default)]
+    pub path_mappings: Vec<PathMapping>,
+}
+
+/// The same directory as seed-tools and a torrent client see it, e.g. for a client running in
+/// Docker or on another machine with the data mounted elsewhere.
+#[derive(Deserialize, Clone, Debug)]
+pub struct PathMapping {
+    pub local: String,  // Prefix on this machine
+    pub remote: String, // Same directory as the client sees it
+}
+
+/// Translates a local path into the path the client sees, using the longest matching prefix.
+/// Paths outside every mapping are passed through unchanged.
+pub fn to_client_path(mappings: &[PathMapping], path: &str) -> String {
+    map_prefix(mappings.iter().map(|mapping| (&mapping.local, &mapping.remote)), path)
+}
+
+/// Translates a path reported by the client back into the local path.
+pub fn from_client_path(mappings: &[PathMapping], path: &str) -> String {
+    map_prefix(mappings.iter().map(|mapping| (&mapping.remote, &mapping.local)), path)
+}
+
+fn map_prefix<'a>(mappings: impl Iterator<Item = (&'a String, &'a String)>, path: &str) -> String {
+    // Compared by path components, so `/data` does not match `/database`
+    mappings
+        .filter_map(|(from, to)| {
+            let rest = std::path::Path::new(path).strip_prefix(from).ok()?;
+            let mapped = if rest.as_os_str().is_empty() {
+                to.clone()
+            } else {
+                std::path::Path::new(to).join(rest).to_string_lossy().to_string()
+            };
+            Some((from.len(), mapped))
+        })
+        .max_by_key(|(prefix_len, _)| *prefix_len)
+        .map(|(_, mapped)| mapped)
+        .unwrap_or_else(|| path.to_string())
 }
 
 #[derive(Deserialize)]
@@ -60,6 +218,27 @@ pub struct SeedpoolSettings {
     pub announce_url: String,
     pub upload_url: String,
     pub custom_description: String,
+    #[serde(default)]
+    pub custom_descriptions: HashMap<String, String>, // By release type (movie, tv, music, ebook, game, software, ...)
+    #[serde(default)]
+    pub episode_title_in_name: bool, // Add the TMDB episode title to single-episode release names
+}
+
+impl SeedpoolSettings {
+    /// The text appended to descriptions of `release_type`: its `custom_descriptions` entry, or
+    /// `custom_description`. `{release_name}`, `{tmdb_title}` and `{group}` are filled in.
+    pub fn custom_description_for(&self, release_type: &str, release_name: &str, title: &str) -> String {
+        let template = self.custom_descriptions.get(release_type).unwrap_or(&self.custom_description);
+        let group = release_name
+            .rsplit_once('-')
+            .map(|(_, group)| group.trim())
+            .filter(|group| !group.is_empty() && !group.contains([' ', '.']))
+            .unwrap_or("");
+        template
+            .replace("{release_name}", release_name)
+            .replace("{tmdb_title}", title)
+            .replace("{group}", group)
+    }
 }
 
 #[derive(Deserialize)]
@@ -75,6 +254,85 @@ pub struct TorrentLeechConfig {
     pub general: TorrentLeechGeneralConfig,
     pub settings: TorrentLeechSettings,
     pub categories: HashMap<String, u32>,
+    #[serde(default)]
+    pub upload_categories: Vec<UploadCategory>, // Shown in the UI custom upload dialog
+    #[serde(default)]
+    pub proof: ProofConfig,
+    #[serde(default)]
+    pub torrent: TorrentOptions,
+    #[serde(default)]
+    pub description: TorrentLeechDescriptionConfig,
+}
+
+/// The description sent with a TorrentLeech upload, next to the NFO.
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct TorrentLeechDescriptionConfig {
+    pub enabled: bool,
+    pub footer: Footer,
+    pub footer_text: Option<String>, // Own BBCode in place of the footer
+    pub bbcode: BbcodeDialect,
+}
+
+impl Default for TorrentLeechDescriptionConfig {
+    fn default() -> Self {
+        TorrentLeechDescriptionConfig {
+            enabled: true,
+            footer: Footer::Plain,
+            footer_text: None,
+            bbcode: BbcodeDialect { spoiler: false, table: false, img_width: false, ..Default::default() },
+        }
+    }
+}
+
+/// The BBCode tags a tracker renders. Descriptions are written with all of them and the ones
+/// a tracker lacks are rewritten by `bbcode::degrade`.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct BbcodeDialect {
+    pub spoiler: bool, // Otherwise a bold heading over the content
+    pub table: bool, // Otherwise a list with a row per item
+    pub img_width: bool, // [img width=720]; otherwise plain [img]
+    pub size: bool,
+    pub color: bool,
+    pub center: bool,
+    pub list: bool, // Otherwise one "• " line per item
+}
+
+impl Default for BbcodeDialect {
+    fn default() -> Self {
+        BbcodeDialect { spoiler: true, table: true, img_width: true, size: true, color: true, center: true, list: true }
+    }
+}
+
+/// What goes under a generated description.
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Footer {
+    None,
+    Plain, // The "Posted with seed-tools" line only
+    #[default]
+    Branded, // The line and the seed-tools, mkbrr and Rust badges from the Seedpool CDN
+}
+
+/// A tracker category offered for custom (`-c`) uploads, with its selectable types.
+#[derive(Deserialize, Clone, Debug)]
+pub struct UploadCategory {
+    pub name: String,
+    pub id: u32,
+    #[serde(default)]
+    pub types: Vec<UploadType>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct UploadType {
+    pub name: String,
+    pub id: u32,
+}
+
+/// Builds the 4-digit `-c/--custom-cat-type` code (e.g. 07 + 20 -> "0720").
+pub fn custom_cat_type_code(category_id: u32, type_id: u32) -> String {
+    format!("{:02}{:02}", category_id, type_id)
 }
 
 #[derive(Deserialize)]
@@ -89,6 +347,323 @@ pub struct SeedpoolConfig {
     pub general: SeedpoolGeneralConfig,
     pub settings: SeedpoolSettings,
     pub screenshots: SeedpoolScreenshots,
+    #[serde(default)]
+    pub upload_categories: Vec<UploadCategory>, // Shown in the UI custom upload dialog
+    #[serde(default)]
+    pub proof: ProofConfig,
+    #[serde(default)]
+    pub torrent: TorrentOptions,
+    #[serde(default)]
+    pub upload_flags: UploadFlags,
+    #[serde(default)]
+    pub ids: SeedpoolIds,
+    #[serde(default)]
+    pub sample: SampleConfig,
+    #[serde(default)]
+    pub adult: AdultConfig,
+    #[serde(default)]
+    pub dupe_check: DupeCheckConfig,
+    #[serde(default)]
+    pub trumping: TrumpingConfig,
+}
+
+/// How the pre-flight check ranks a release against other uploads of the same title. Lists
+/// are best first; see `trumping.rs`.
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct TrumpingConfig {
+    pub enabled: bool,
+    pub criteria: Vec<String>, // resolution, source, hdr, audio; the first that differs decides
+    pub sources: Vec<String>,
+    pub hdr: Vec<String>,
+    pub audio: Vec<String>,
+}
+
+impl Default for TrumpingConfig {
+    fn default() -> Self {
+        let list = |values: &[&str]| values.iter().map(|value| value.to_string()).collect();
+        TrumpingConfig {
+            enabled: true,
+            criteria: list(&["resolution", "source", "hdr", "audio"]),
+            sources: list(&["remux", "bluray", "web", "hdtv", "dvd"]),
+            hdr: list(&["dv", "hdr10+", "hdr10", "hdr", "hlg", "sdr"]),
+            audio: list(&["atmos", "truehd", "dts:x", "dts-hd ma", "lpcm", "flac", "dts", "ddp", "dd", "aac", "opus", "mp3"]),
+        }
+    }
+}
+
+/// Dupe checks beyond the release name, for uploads that were renamed.
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct DupeCheckConfig {
+    pub fingerprint: bool,           // Search by the largest file's name and compare the total size
+    pub info_hash: bool,             // Search by the new torrent's infohash once it is created
+    pub size_tolerance_percent: f64, // Sizes this close count as the same, as excluded .nfo and sample files are missing on the tracker
+}
+
+impl Default for DupeCheckConfig {
+    fn default() -> Self {
+        DupeCheckConfig { fingerprint: false, info_hash: false, size_tolerance_percent: 2.0 }
+    }
+}
+
+// Seedpool's numbering when seedpool.yaml does not name an ID
+const SEEDPOOL_CATEGORIES: [(&str, u32); 5] = [("movie", 1), ("tv", 2), ("music", 5), ("ebook", 7), ("boxset", 13)];
+const SEEDPOOL_TYPES: [(&str, u32); 9] = [
+    ("flac", 11),
+    ("mp3", 13),
+    ("ebook", 20),
+    ("movie", 22),
+    ("tv", 24),
+    ("boxset", 26),
+    ("comic", 40),
+    ("magazine", 41),
+    ("newspaper", 42),
+];
+const SEEDPOOL_RESOLUTIONS: [(&str, u32); 12] = [
+    ("4320p", 1),
+    ("2160p", 2),
+    ("1440p", 3),
+    ("1080p", 3),
+    ("1080i", 4),
+    ("720p", 5),
+    ("576p", 6),
+    ("576i", 7),
+    ("480p", 8),
+    ("480i", 9),
+    ("8640p", 10),
+    ("other", 10),
+];
+
+/// Seedpool category, type and resolution IDs by name, so a renumbering on the site only needs
+/// a change in seedpool.yaml. Names left out keep Seedpool's current IDs.
+#[derive(Deserialize, Clone, Default, Debug)]
+#[serde(default)]
+pub struct SeedpoolIds {
+    pub categories: HashMap<String, u32>, // movie, tv, boxset, music and ebook
+    pub types: HashMap<String, u32>, // movie, tv, boxset, flac, mp3, ebook, comic, magazine and newspaper
+    pub resolutions: HashMap<String, u32>, // 2160p, 1080p, ... and other
+}
+
+impl SeedpoolIds {
+    fn lookup(map: &HashMap<String, u32>, defaults: &[(&str, u32)], name: &str) -> Option<u32> {
+        map.iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, id)| *id)
+            .or_else(|| defaults.iter().find(|(key, _)| key.eq_ignore_ascii_case(name)).map(|(_, id)| *id))
+    }
+
+    /// Category ID for movie, tv, boxset, music or ebook; 0 (rejected before upload) for others.
+    pub fn category(&self, name: &str) -> u32 {
+        Self::lookup(&self.categories, &SEEDPOOL_CATEGORIES, name).unwrap_or(0)
+    }
+
+    /// Type ID for movie, tv, boxset, flac, mp3, ebook, comic, magazine or newspaper.
+    pub fn type_id(&self, name: &str) -> u32 {
+        Self::lookup(&self.types, &SEEDPOOL_TYPES, name).unwrap_or(0)
+    }
+
+    /// Resolution ID for e.g. `1080p`, or the `other` ID.
+    pub fn resolution(&self, resolution: &str) -> u32 {
+        Self::lookup(&self.resolutions, &SEEDPOOL_RESOLUTIONS, resolution)
+            .or_else(|| Self::lookup(&self.resolutions, &SEEDPOOL_RESOLUTIONS, "other"))
+            .unwrap_or(0)
+    }
+
+    /// Whether uploads in a category carry season and episode numbers (TV and boxsets).
+    pub fn is_tv(&self, category_id: u32) -> bool {
+        category_id == self.category("tv") || category_id == self.category("boxset")
+    }
+}
+
+/// UNIT3D flags sent with every Seedpool upload. Most of them need staff or internal group
+/// permissions on the site.
+#[derive(Deserialize, Clone, Default, Debug)]
+#[serde(default)]
+pub struct UploadFlags {
+    pub freeleech: u8, // Percent, 0-100
+    pub featured: bool,
+    pub doubleup: bool,
+    pub internal: bool,
+    pub personal_release: bool,
+    pub anonymous: bool,
+}
+
+/// Another UNIT3D tracker, set up in `config/trackers/unit3d/<name>.yaml` and picked with
+/// `--unit3d <name>`.
+#[derive(Deserialize, Clone)]
+pub struct Unit3dConfig {
+    #[serde(skip)]
+    pub name: String, // The file name without `.yaml`, lowercased
+    pub base_url: String, // e.g. https://tracker.example, without /api
+    pub api_key: String,
+    pub announce_url: String,
+    #[serde(default)]
+    pub description: String, // BBCode put above the screenshots
+    pub categories: HashMap<String, u32>, // movie, tv and boxset
+    pub types: HashMap<String, u32>, // disc, remux, encode, web-dl, webrip and hdtv
+    #[serde(default)]
+    pub resolutions: HashMap<String, u32>, // 2160p, 1080p, ... and other
+    #[serde(default)]
+    pub upload_flags: UploadFlags,
+    #[serde(default)]
+    pub stripshit_from_videos: bool,
+    #[serde(default)]
+    pub torrent: TorrentOptions,
+    #[serde(default)]
+    pub adult: AdultConfig,
+    #[serde(default)]
+    pub bbcode: BbcodeDialect,
+}
+
+impl VideoSettings for Unit3dConfig {
+    fn stripshit_from_videos(&self) -> bool {
+        self.stripshit_from_videos
+    }
+}
+
+/// A Gazelle music tracker (RED/OPS-style JSON API), set up in
+/// `config/trackers/gazelle/<name>.yaml` and picked with `--gazelle <name>`.
+#[derive(Deserialize, Clone)]
+pub struct GazelleConfig {
+    #[serde(skip)]
+    pub name: String, // The file name without `.yaml`, lowercased
+    pub base_url: String, // e.g. https://tracker.example, without /ajax.php
+    pub api_key: String, // Sent as the Authorization header as is; OPS expects "token <key>"
+    pub announce_url: String,
+    pub source: String, // Source tag the tracker requires in the torrent, e.g. "RED" or "OPS"
+    #[serde(default = "default_gazelle_media")]
+    pub media: String, // WEB, CD, Vinyl, ...
+    #[serde(default = "default_gazelle_release_type")]
+    pub release_type: u32, // Used when the name does not say EP, single, live, ...; 1 is Album
+    #[serde(default)]
+    pub tags: Vec<String>, // Added to the genre from the files' tags
+    #[serde(default)]
+    pub description: String, // Added to the album description
+    #[serde(default = "default_true")]
+    pub logchecker: bool, // Score .log files with the site's logchecker before uploading
+    #[serde(default)]
+    pub torrent: TorrentOptions,
+}
+
+fn default_gazelle_media() -> String {
+    "WEB".to_string()
+}
+
+fn default_gazelle_release_type() -> u32 {
+    1
+}
+
+/// How torrents for a tracker are created.
+#[derive(Deserialize, Clone, Default)]
+pub struct TorrentOptions {
+    pub piece_size: Option<String>, // "auto" or a power of two like "4MiB"; unset leaves it to mkbrr
+    #[serde(default)]
+    pub version: TorrentVersion,
+    pub exclusions: Option<ExclusionsConfig>, // Replaces the global exclusions for this tracker
+}
+
+impl TorrentOptions {
+    /// The tracker's own exclusions, or `global` when it has none.
+    pub fn exclusions<'a>(&'a self, global: &'a ExclusionsConfig) -> &'a ExclusionsConfig {
+        self.exclusions.as_ref().unwrap_or(global)
+    }
+}
+
+/// Files left out of torrents when `stripshit_from_videos` is set.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct ExclusionsConfig {
+    pub keywords: Vec<String>, // Anywhere in the file name, case-insensitive
+    pub globs: Vec<String>,    // Matched against the file name, case-insensitive
+    pub patterns: Vec<String>, // Regexes matched against the path inside the release
+}
+
+impl Default for ExclusionsConfig {
+    fn default() -> Self {
+        ExclusionsConfig {
+            keywords: ["sample", "screens", "screenshots", "proof"].map(String::from).to_vec(),
+            globs: ["[X]*", "*.txt", "*.jpg", "*.jpeg", "*.png", "*.nfo", "*.srr", "*.doc", "*.sfv", "*.r??"].map(String::from).to_vec(),
+            patterns: Vec::new(),
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TorrentVersion {
+    #[default]
+    V1,     // Created by mkbrr
+    V2,     // BitTorrent v2 only, created by seed-tools
+    Hybrid, // v1 and v2 in one torrent, created by seed-tools
+}
+
+/// The sample clip linked from the description (Seedpool CDN uploads only).
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct SampleConfig {
+    pub enabled: bool,
+    pub source: SampleSource,
+    pub start_secs: u32, // Videos too short to start here get a sample from the middle
+    pub duration_secs: u32,
+    pub min_video_secs: u32, // Shorter videos get no sample
+    pub map: Vec<String>, // ffmpeg -map arguments, e.g. ["0:v:0", "0:a:0"]; "0" keeps every stream
+}
+
+impl Default for SampleConfig {
+    fn default() -> Self {
+        SampleConfig {
+            enabled: true,
+            source: SampleSource::Auto,
+            start_secs: 300,
+            duration_secs: 20,
+            min_video_secs: 120,
+            map: vec!["0".to_string()],
+        }
+    }
+}
+
+/// Adult uploads (`--type adult`), for trackers that carry them. Off unless the tracker's yaml
+/// turns them on.
+#[derive(Deserialize, Clone, Default, Debug)]
+#[serde(default)]
+pub struct AdultConfig {
+    pub enabled: bool,
+    pub blur_thumbnails: bool, // Blur the thumbnails shown in the description; the full screenshots stay sharp
+}
+
+/// Where the sample comes from.
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SampleSource {
+    #[default]
+    Auto,     // The sample shipped with the release when it checks out, otherwise a new one is cut
+    Existing, // Only the shipped sample; none when the release has no usable one
+    Generate, // Always cut a new one
+}
+
+/// Proof-of-ownership image for trackers that ask for one: a frame of the release stamped
+/// with the uploader's name and the time, copied to a private location.
+#[derive(Deserialize, Clone, Default)]
+pub struct ProofConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    pub username: Option<String>,    // Stamped on the image; Seedpool defaults to general.username
+    pub remote_path: Option<String>, // scp target; Seedpool defaults to the screenshots remote_path
+    pub url: Option<String>,         // Base URL remote_path is served from
+    #[serde(default)]
+    pub placement: ProofPlacement,
+    pub font_file: Option<String>,   // For ffmpeg builds without fontconfig
+}
+
+/// Where the proof image link goes.
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ProofPlacement {
+    #[default]
+    None,    // Only logged and kept in the checkpoint, e.g. to hand to staff on request
+    Spoiler, // Appended to the description inside a [spoiler] (Seedpool, TorrentLeech)
+    Nfo,     // Appended to the generated NFO (TorrentLeech)
 }
 
 #[derive(Deserialize)]
@@ -99,10 +674,55 @@ pub struct SeedpoolGeneralConfig {
     pub api_key: String,
 }
 
+/// Contents of `config/irc.yaml`.
+#[derive(Deserialize, Clone)]
+pub struct IrcConfig {
+    pub networks: Vec<IrcNetworkConfig>,
+    pub upload_announce: Option<UploadAnnounceConfig>, // Post a line after each successful Seedpool upload
+}
+
+#[derive(Deserialize, Clone)]
+pub struct UploadAnnounceConfig {
+    pub network: Option<String>, // Defaults to the first network
+    pub channel: String,
+    #[serde(default = "default_upload_announce_template")]
+    pub template: String, // Placeholders: {name}, {category}, {size}, {link}
+}
+
+fn default_upload_announce_template() -> String {
+    "New upload: {name} | {category} | {size} | {link}".to_string()
+}
+
+#[derive(Deserialize, Clone)]
+pub struct IrcNetworkConfig {
+    pub name: String, // Shown as the tab title
+    pub server: String,
+    #[serde(default = "default_irc_port")]
+    pub port: u16,
+    #[serde(default = "default_true")]
+    pub tls: bool,
+    pub nickname: Option<String>, // Defaults to the Seedpool username
+    #[serde(default)]
+    pub channels: Vec<String>,
+    pub nickserv_password: Option<String>, // Sent as `IDENTIFY <password>` to NickServ after connecting
+    #[serde(default)]
+    pub seedserv_auth: bool, // Send the Seedpool passkey to SeedServ after connecting
+}
+
+fn default_irc_port() -> u16 {
+    6697
+}
+
 #[derive(Deserialize)]
 pub struct SeedpoolScreenshots {
     pub remote_path: String,
     pub image_path: String,
+    #[serde(default = "default_artifact_file_name")]
+    pub file_name: String, // CDN file name template: {name}, {timestamp}, {hash}
+}
+
+fn default_artifact_file_name() -> String {
+    "{name}.{hash}".to_string()
 }
 
 #[derive(Deserialize)]
@@ -112,11 +732,103 @@ pub struct Config {
     pub qbittorrent: Vec<QbittorrentConfig>,
     pub deluge: DelugeConfig,
     pub imgbb: Option<ImgBBConfig>, // Add this field
+    #[serde(default)]
+    pub ui: UiConfig,
+    #[serde(default)]
+    pub concurrency: ConcurrencyConfig,
+    #[serde(default)]
+    pub readiness: ReadinessConfig,
+    #[serde(default)]
+    pub transfers: TransfersConfig,
+    #[serde(default)]
+    pub disk_space: DiskSpaceConfig,
+    #[serde(default)]
+    pub retention: RetentionConfig,
+    #[serde(default)]
+    pub limits: LimitsConfig,
+    #[serde(default)]
+    pub exclusions: ExclusionsConfig,
+    #[serde(default)]
+    pub unpack: UnpackConfig,
+    pub web: Option<WebConfig>,
+    pub api: Option<ApiConfig>,
+    #[serde(default)]
+    pub hooks: HooksConfig,
+    #[serde(default)]
+    pub maintenance: MaintenanceConfig,
+    #[serde(default)]
+    pub http: HttpConfig,
+    #[serde(default)]
+    pub network: NetworkConfig,
+    #[serde(default)]
+    pub dupe_cache: DupeCacheConfig,
+    #[serde(default)]
+    pub screenshots: ScreenshotsConfig,
+    #[serde(default)]
+    pub imdb: ImdbConfig,
+    #[serde(default)]
+    pub arr: ArrConfig,
+    #[serde(default)]
+    pub notifications: NotificationsConfig,
+    #[serde(default)]
+    pub logging: LoggingConfig,
 }
 
-#[derive(Deserialize)]
+/// IMDb lookup for titles TMDB does not have (`imdb.rs`); off by default.
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct ImdbConfig {
+    pub enabled: bool,
+    pub suggestions: bool, // The IMDb suggestion API
+    pub dataset: Option<String>, // Local title.basics.tsv or .tsv.gz, searched after the API
+}
+
+impl Default for ImdbConfig {
+    fn default() -> Self {
+        ImdbConfig { enabled: false, suggestions: true, dataset: None }
+    }
+}
+
+/// Radarr and Sonarr instances (`arr.rs`); each is optional.
+#[derive(Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ArrConfig {
+    pub radarr: Option<ArrInstance>,
+    pub sonarr: Option<ArrInstance>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct ArrInstance {
+    pub url: String, // e.g. http://localhost:7878, without /api
+    pub api_key: String,
+    #[serde(default)]
+    pub path_mappings: Vec<PathMapping>, // When the app sees the data under another path
+    #[serde(default = "default_true")]
+    pub resolve_ids: bool, // Take the IDs of releases it manages instead of searching TMDB
+    #[serde(default)]
+    pub notify_after_upload: bool, // Rescan (or import) the release after uploading it
+    #[serde(default = "default_arr_import_mode")]
+    pub import_mode: String, // Copy or Move, for releases it does not manage yet
+}
+
+fn default_arr_import_mode() -> String {
+    "Copy".to_string()
+}
+
+#[derive(Deserialize, Clone)]
 pub struct ImgBBConfig {
     pub imgbb_api_key: String,
+    #[serde(default)]
+    pub extra_api_keys: Vec<String>, // Take over while a key is rate limited
+    #[serde(default = "default_imgbb_max_retries")]
+    pub max_retries: u32, // Waits with exponential backoff once every key is limited
+    pub uploads_per_hour: Option<u32>, // Budget per key; a key over it is skipped
+    #[serde(default = "default_true")]
+    pub fallback: bool, // After the last retry: Seedpool CDN for Seedpool, no screenshots elsewhere
+}
+
+fn default_imgbb_max_retries() -> u32 {
+    4
 }
 
 pub trait VideoSettings {
@@ -133,4 +845,569 @@ impl VideoSettings for TorrentLeechSettings {
     fn stripshit_from_videos(&self) -> bool {
         self.stripshit_from_videos
     }
-}
\ No newline at end of file
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum QueueMode {
+    #[default]
+    Sequential,
+    Parallel,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct UiConfig {
+    #[serde(default)]
+    pub queue_mode: QueueMode,
+    #[serde(default = "default_max_parallel_uploads")]
+    pub max_parallel_uploads: usize,
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        UiConfig {
+            queue_mode: QueueMode::default(),
+            max_parallel_uploads: default_max_parallel_uploads(),
+        }
+    }
+}
+
+fn default_max_parallel_uploads() -> usize {
+    2
+}
+
+/// How many uploads may do each kind of work at the same time, across all seed-tools processes.
+#[derive(Deserialize, Clone)]
+pub struct ConcurrencyConfig {
+    #[serde(default = "default_hashing_slots")]
+    pub hashing: usize, // Torrent creation; more than one mostly makes the disks seek
+    #[serde(default = "default_metadata_slots")]
+    pub metadata: usize, // TMDB lookups and mediainfo
+    #[serde(default = "default_image_slots")]
+    pub images: usize, // Screenshots, samples and their uploads
+}
+
+impl Default for ConcurrencyConfig {
+    fn default() -> Self {
+        ConcurrencyConfig {
+            hashing: default_hashing_slots(),
+            metadata: default_metadata_slots(),
+            images: default_image_slots(),
+        }
+    }
+}
+
+fn default_hashing_slots() -> usize {
+    1
+}
+
+fn default_metadata_slots() -> usize {
+    2
+}
+
+fn default_image_slots() -> usize {
+    4
+}
+
+/// Sanity limits checked before a torrent is created; `--max-size`, `--max-files` and
+/// `--strict-limits` override them for one run.
+#[derive(Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct LimitsConfig {
+    pub max_size_gb: Option<f64>,
+    pub max_files: Option<usize>,
+    pub zero_byte_files: bool, // Flag empty files
+    pub disallowed_extensions: HashMap<String, Vec<String>>, // By release type (movie, tv, music, ebook, software, ...) or "all"
+    pub abort: bool, // Stop the upload instead of warning
+}
+
+/// Video releases that arrive as rar sets.
+#[derive(Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct UnpackConfig {
+    pub unpack_before_upload: bool, // Extract them before any tracker upload (Seedpool always does)
+    pub delete_archives: bool, // Remove the rar volumes once the video is extracted
+}
+
+/// Checks a release must pass before it is uploaded, so data still being copied is left alone.
+#[derive(Deserialize, Clone)]
+pub struct ReadinessConfig {
+    #[serde(default = "default_settle_secs")]
+    pub settle_secs: u64, // Nothing in the release may have been modified more recently; 0 disables
+    #[serde(default = "default_growth_check_secs")]
+    pub growth_check_secs: u64, // How long to watch file sizes for growth; 0 disables
+    #[serde(default)]
+    pub verify_sfv: bool, // Check the CRC32s of any .sfv files in the release
+}
+
+impl Default for ReadinessConfig {
+    fn default() -> Self {
+        ReadinessConfig {
+            settle_secs: default_settle_secs(),
+            growth_check_secs: default_growth_check_secs(),
+            verify_sfv: false,
+        }
+    }
+}
+
+/// Free space a stage needs before it starts, on top of what it is expected to write. 0
+/// turns a check off.
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct DiskSpaceConfig {
+    pub screenshots_mb: u64, // In screenshots_dir, before screenshots are taken
+    pub sample_mb: u64,      // In screenshots_dir, before the sample is cut
+    pub unpack_mb: u64,      // In the release folder, before rar archives are extracted
+    pub temp_mb: u64,        // In the temp dir, before covers and pages are extracted
+}
+
+impl Default for DiskSpaceConfig {
+    fn default() -> Self {
+        DiskSpaceConfig { screenshots_mb: 200, sample_mb: 1024, unpack_mb: 1024, temp_mb: 500 }
+    }
+}
+
+/// How long generated files are kept, per kind. See `retention.rs`.
+#[derive(Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct RetentionConfig {
+    pub torrents: Retention,    // .torrent and .nfo files in torrent_dir
+    pub screenshots: Retention, // Screenshots and thumbnails in screenshots_dir
+    pub samples: Retention,
+    pub covers: Retention,      // torrent-cover_<id>.jpg
+}
+
+/// "forever", "after_success" or an age in days like "30d".
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+#[serde(try_from = "String")]
+pub enum Retention {
+    #[default]
+    Forever,
+    AfterSuccess, // Removed once every tracker took the upload; kept for resuming otherwise
+    Days(u64),    // Removed by `seed-tools clean` once this old
+}
+
+impl TryFrom<String> for Retention {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.trim().to_lowercase().as_str() {
+            "forever" => Ok(Retention::Forever),
+            "after_success" => Ok(Retention::AfterSuccess),
+            age => age
+                .strip_suffix('d')
+                .unwrap_or(age)
+                .parse()
+                .map(Retention::Days)
+                .map_err(|_| format!("Invalid retention '{}': use forever, after_success or an age like 30d", value)),
+        }
+    }
+}
+
+/// Bandwidth and time of day for uploads, for seedboxes that share a line.
+#[derive(Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct TransfersConfig {
+    pub limit_kbps: Option<u64>, // Cap for screenshot, sample and cover uploads to the CDN, in kbit/s
+    pub window: Option<String>,  // "00:00-08:00" local time; uploads only start inside it
+    pub outside_window: OutsideWindow,
+}
+
+/// What an upload started outside the upload window does.
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum OutsideWindow {
+    #[default]
+    Wait,  // Sleep until the window opens
+    Defer, // Stop with exit code 77, so the queue retries it later
+}
+
+fn default_settle_secs() -> u64 {
+    60
+}
+
+fn default_growth_check_secs() -> u64 {
+    2
+}
+
+/// Request passthrough for trackers behind Cloudflare/DDoS-Guard challenge pages, plus the
+/// timeout, retries and rate limits of API requests.
+#[derive(Deserialize, Clone)]
+pub struct HttpConfig {
+    pub user_agent: Option<String>, // Should match the browser the cookies came from
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    #[serde(default)]
+    pub cookies: HashMap<String, String>, // Domain -> cookie string, e.g. "seedpool.org": "cf_clearance=..."
+    pub flaresolverr_url: Option<String>, // e.g. "http://localhost:8191/v1"; solves challenges automatically
+    #[serde(default = "default_flaresolverr_timeout_ms")]
+    pub flaresolverr_timeout_ms: u64,
+    #[serde(default = "default_http_timeout_secs")]
+    pub timeout_secs: u64, // Per request, for API lookups and tracker requests alike
+    #[serde(default)]
+    pub retry: RetryConfig,
+    #[serde(default)]
+    pub rate_limits: HashMap<String, f64>, // API name -> requests per second, e.g. "tmdb": 40
+}
+
+/// Retries of API lookups after connection errors, timeouts, 429s and 5xx responses.
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct RetryConfig {
+    pub max_retries: u32,
+    pub initial_backoff_ms: u64, // Doubled after every failed attempt
+    pub max_backoff_ms: u64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        RetryConfig {
+            max_retries: 3,
+            initial_backoff_ms: 1000,
+            max_backoff_ms: 30000,
+        }
+    }
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        HttpConfig {
+            user_agent: None,
+            headers: HashMap::new(),
+            cookies: HashMap::new(),
+            flaresolverr_url: None,
+            flaresolverr_timeout_ms: default_flaresolverr_timeout_ms(),
+            timeout_secs: default_http_timeout_secs(),
+            retry: RetryConfig::default(),
+            rate_limits: HashMap::new(),
+        }
+    }
+}
+
+fn default_flaresolverr_timeout_ms() -> u64 {
+    60000
+}
+
+fn default_http_timeout_secs() -> u64 {
+    120
+}
+
+/// How long tracker dupe check searches are reused, across processes (pre-flight, then upload).
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct DupeCacheConfig {
+    pub ttl_secs: u64, // 0 disables the cache
+}
+
+impl Default for DupeCacheConfig {
+    fn default() -> Self {
+        DupeCacheConfig { ttl_secs: 300 }
+    }
+}
+
+/// Where screenshots are taken. Random by default; a seed or fixed timestamps make runs repeatable.
+#[derive(Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ScreenshotsConfig {
+    pub seed: Option<u64>,       // The same seed picks the same timestamps for the same file; `--seed` overrides it
+    pub timestamps: Vec<String>, // Fixed timestamps instead, "10%" of the video or seconds; `--timestamps` overrides it
+}
+
+/// Proxy, CA bundle and user agent for every outbound connection: API lookups, tracker requests,
+/// torrent clients, notifications and IRC.
+#[derive(Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct NetworkConfig {
+    pub proxy: Option<String>, // http://, https:// or socks5:// URL; IRC only goes through socks5 proxies
+    pub no_proxy: Vec<String>, // Hosts (and their subdomains) reached directly, e.g. "localhost", "192.168.1.10"
+    pub ca_bundle: Option<String>, // PEM file with extra CAs to trust
+    pub user_agent: Option<String>, // Default for all requests; http.user_agent still wins for trackers
+    #[serde(skip)]
+    pub offline: bool, // Set by `--offline`: every request fails without leaving the machine
+}
+
+/// Log level, format, per-run log files and rotation of `seed-tools.log`.
+#[derive(Deserialize, Clone)]
+pub struct LoggingConfig {
+    #[serde(default = "default_log_level")]
+    pub level: String, // trace, debug, info, warn, error or off; `--log-level` overrides it
+    #[serde(default)]
+    pub format: LogFormat,
+    #[serde(default = "default_log_dir")]
+    pub dir: String, // Rotated logs go here, per-run logs into its `runs` subdirectory
+    #[serde(default = "default_log_max_size_mb")]
+    pub max_size_mb: u64, // Rotate `seed-tools.log` at startup once it is this big
+    #[serde(default = "default_true")]
+    pub daily: bool, // Also rotate it at startup when it was last written on an earlier day
+    #[serde(default = "default_log_keep")]
+    pub keep: usize, // Rotated logs to keep
+    #[serde(default = "default_log_keep_runs")]
+    pub keep_runs: usize, // Per-run logs to keep
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        LoggingConfig {
+            level: default_log_level(),
+            format: LogFormat::default(),
+            dir: default_log_dir(),
+            max_size_mb: default_log_max_size_mb(),
+            daily: true,
+            keep: default_log_keep(),
+            keep_runs: default_log_keep_runs(),
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    #[default]
+    Pretty, // `HH:MM:SS [LEVEL] message`, the format the UI log viewer reads
+    Json,   // One JSON object per line, for log shippers
+}
+
+fn default_log_level() -> String {
+    "debug".to_string()
+}
+
+fn default_log_dir() -> String {
+    "logs".to_string()
+}
+
+fn default_log_max_size_mb() -> u64 {
+    50
+}
+
+fn default_log_keep() -> usize {
+    10
+}
+
+fn default_log_keep_runs() -> usize {
+    100
+}
+
+/// Where upload and sync results are sent.
+#[derive(Deserialize, Clone, Default)]
+pub struct NotificationsConfig {
+    #[serde(default)]
+    pub sinks: Vec<NotificationSink>,
+}
+
+/// One notification target, with the results it wants to hear about.
+#[derive(Deserialize, Clone)]
+pub struct NotificationSink {
+    #[serde(flatten)]
+    pub kind: NotificationSinkKind,
+    #[serde(default = "default_notify_on")]
+    pub on: Vec<NotifyOn>, // e.g. [failure] to only hear about problems
+    #[serde(default)]
+    pub events: Vec<NotificationEvent>, // e.g. [sync]; empty = every event
+}
+
+impl NotificationSink {
+    /// Whether this sink opted in to a result of `event`.
+    pub fn wants(&self, event: NotificationEvent, success: bool) -> bool {
+        let outcome = if success { NotifyOn::Success } else { NotifyOn::Failure };
+        self.on.contains(&outcome) && (self.events.is_empty() || self.events.contains(&event))
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifyOn {
+    Success,
+    Failure,
+}
+
+fn default_notify_on() -> Vec<NotifyOn> {
+    vec![NotifyOn::Success, NotifyOn::Failure]
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum NotificationSinkKind {
+    Discord {
+        webhook_url: String,
+    },
+    Telegram {
+        bot_token: String,
+        chat_id: String,
+    },
+    Gotify {
+        url: String,   // Server address, e.g. "https://gotify.example.com"
+        token: String, // Application token
+        #[serde(default = "default_gotify_priority")]
+        priority: u8,
+    },
+    Webhook {
+        url: String, // Receives the notification as JSON
+        #[serde(default)]
+        headers: HashMap<String, String>,
+    },
+    Ntfy {
+        #[serde(default = "default_ntfy_url")]
+        url: String,           // ntfy.sh or a self-hosted server
+        topic: String,
+        token: Option<String>, // Access token for protected topics
+        #[serde(default = "default_ntfy_priority")]
+        priority: u8,          // 1 (min) to 5 (max)
+    },
+    Pushover {
+        user_key: String,
+        api_token: String,      // Application token
+        device: Option<String>, // Only send to this device
+        #[serde(default)]
+        priority: i8,           // -2 (silent) to 1 (high)
+    },
+}
+
+impl NotificationSinkKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            NotificationSinkKind::Discord { .. } => "Discord",
+            NotificationSinkKind::Telegram { .. } => "Telegram",
+            NotificationSinkKind::Gotify { .. } => "Gotify",
+            NotificationSinkKind::Webhook { .. } => "Webhook",
+            NotificationSinkKind::Ntfy { .. } => "ntfy",
+            NotificationSinkKind::Pushover { .. } => "Pushover",
+        }
+    }
+}
+
+fn default_gotify_priority() -> u8 {
+    5
+}
+
+fn default_ntfy_url() -> String {
+    "https://ntfy.sh".to_string()
+}
+
+fn default_ntfy_priority() -> u8 {
+    3
+}
+
+/// Retry schedule for uploads parked while a tracker is down for maintenance or the release
+/// is not ready yet.
+#[derive(Deserialize, Clone)]
+pub struct MaintenanceConfig {
+    #[serde(default = "default_backoff_secs")]
+    pub backoff_secs: Vec<u64>, // Delay before each retry; the last one repeats
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32, // A parked upload fails after this many retries; 0 retries forever
+}
+
+impl Default for MaintenanceConfig {
+    fn default() -> Self {
+        MaintenanceConfig { backoff_secs: default_backoff_secs(), max_retries: default_max_retries() }
+    }
+}
+
+impl MaintenanceConfig {
+    /// Whether retry number `attempt` (starting at 1) is still allowed.
+    pub fn may_retry(&self, attempt: u32) -> bool {
+        self.max_retries == 0 || attempt <= self.max_retries
+    }
+
+    /// Delay before retry number `attempt` (starting at 1).
+    pub fn backoff(&self, attempt: u32) -> std::time::Duration {
+        let index = (attempt.max(1) as usize - 1).min(self.backoff_secs.len().saturating_sub(1));
+        std::time::Duration::from_secs(self.backoff_secs.get(index).copied().unwrap_or(300))
+    }
+}
+
+fn default_backoff_secs() -> Vec<u64> {
+    vec![60, 300, 900, 1800, 3600]
+}
+
+fn default_max_retries() -> u32 {
+    10
+}
+
+#[derive(Deserialize, Clone)]
+pub struct WebConfig {
+    #[serde(default = "default_web_bind")]
+    pub bind: String,
+    pub root_dir: String,             // Only files below this directory can be browsed and uploaded
+    pub auth_token: Option<String>,   // Required as a Bearer token (or ?token=) when set
+}
+
+fn default_web_bind() -> String {
+    "127.0.0.1:8420".to_string()
+}
+
+#[derive(Deserialize, Clone)]
+pub struct ApiConfig {
+    #[serde(default = "default_api_bind")]
+    pub bind: String,
+    pub token: String,            // Clients send `Authorization: Bearer <token>`
+    pub root_dir: Option<String>, // When set, only paths below this directory are accepted
+    #[serde(default)]
+    pub webhook: WebhookConfig,
+}
+
+/// `POST /webhook/autobrr` of the API server: what an announce-driven upload goes to.
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct WebhookConfig {
+    pub trackers: Vec<String>, // Used when the request names none
+    pub download_dir: Option<String>, // Where releases land, for requests with a release name only
+    pub skip_lower_quality: bool, // Skip releases the trump check finds worse than one already up
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        WebhookConfig { trackers: vec!["SP".to_string()], download_dir: None, skip_lower_quality: true }
+    }
+}
+
+fn default_api_bind() -> String {
+    "127.0.0.1:8421".to_string()
+}
+
+#[derive(Deserialize, Clone, Default)]
+pub struct HooksConfig {
+    #[serde(default)]
+    pub pre_torrent: Vec<HookCommand>,
+    #[serde(default)]
+    pub post_screenshots: Vec<HookCommand>,
+    #[serde(default)]
+    pub pre_upload: Vec<HookCommand>,
+    #[serde(default)]
+    pub post_upload: Vec<HookCommand>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct HookCommand {
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default = "default_true")]
+    pub abort_on_failure: bool, // Stop the upload when the script exits non-zero
+    #[serde(default = "default_hook_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_hook_timeout_secs() -> u64 {
+    300
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn type_overrides_map_to_one_type_flag() {
+        let by_id = UploadOverrides { type_id: Some(3), ..Default::default() };
+        assert_eq!(by_id.to_args().unwrap(), ["--type", "3"]);
+        let adult = UploadOverrides { release_type: Some("adult".to_string()), ..Default::default() };
+        assert_eq!(adult.to_args().unwrap(), ["--type", "adult"]);
+
+        let both = UploadOverrides { type_id: Some(3), release_type: Some("adult".to_string()), ..Default::default() };
+        assert!(both.to_args().is_err());
+    }
+}
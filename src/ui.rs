@@ -4,117 +4,538 @@ use tui::{
     layout::{Constraint, Direction, Layout},
     style::{Color, Modifier, Style},
     text::{Span, Spans},
-    widgets::{Block, Borders, List, ListItem, Paragraph, Widget},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
     Terminal,
 };
 use crossterm::{
-    event::{self, Event, KeyCode, KeyModifiers, EnableMouseCapture},
+    event::{self, Event, KeyCode, KeyModifiers},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use tui::layout::Rect;
 use walkdir::WalkDir;
-use simplelog::*;
 use std::sync::mpsc;
-use std::sync::mpsc::channel;
-use notify::{Config as NotifyConfig, Watcher, RecursiveMode, RecommendedWatcher, Event as NotifyEvent, EventKind};
 use serde::Deserialize;
 // --- Standard Library ---
 use std::{
     fs::File,
-    io::{self, Seek, SeekFrom, BufRead, BufReader},
+    io::{self, BufRead, BufReader},
     path::{Path, PathBuf},
     process::{Command, Stdio},
-    sync::{Arc, Mutex, Once},
+    sync::{atomic::{AtomicU64, Ordering}, Arc, Mutex},
     thread,
-    time::Duration,
+    time::{Duration, Instant},
 };
-use vte::{Parser, Perform};
-use crate::types::PreflightCheckResult;
+use crate::types::{custom_cat_type_code, MaintenanceConfig, NotificationsConfig, PathsConfig, QueueMode, SeedpoolConfig, TorrentLeechConfig, UiConfig, UploadCategory, UploadOverrides, UploadType};
+use crate::error::SeedToolsError;
+use crate::trackers::TRACKER_UNAVAILABLE_EXIT_CODE;
+use crate::profile::config_path;
+use crate::readiness::{self, NOT_READY_EXIT_CODE};
+use crate::schedule::{self, OUTSIDE_WINDOW_EXIT_CODE};
 use crate::utils;
-use std::fs::OpenOptions;
-// --- Static Variables ---
-static INIT_LOGGER: Once = Once::new();
-#[derive(Deserialize)]
-struct GeneralConfig {
-    tmdb_api_key: String,
-}
-
-#[derive(Deserialize)]
-struct PathsConfig {
-    mediainfo: String,
-    torrent_dir: String,
-    screenshots_dir: String,
-    ffmpeg: String,
-    ffprobe: String,
-    mkbrr: String,
-}
-
+use crate::terminal::TerminalGuard;
+use crate::log_buffer::{self, LogLine};
+use crate::notifications::{notify, Notification};
+use crate::history::{self, HistoryEntry};
+use log::{error, info, warn, Level};
 #[derive(Deserialize)]
 struct AppConfig {
-    general: GeneralConfig,
     paths: PathsConfig,
+    #[serde(default)]
+    ui: UiConfig,
+    #[serde(default)]
+    maintenance: MaintenanceConfig,
+    #[serde(default)]
+    notifications: NotificationsConfig,
 }
 
 fn load_config() -> AppConfig {
-    serde_yaml::from_str(&std::fs::read_to_string("config/config.yaml").expect("Failed to read config file"))
-        .expect("Failed to parse YAML config")
+    let content = crate::secrets::read_config(config_path(Path::new("config"), "config.yaml")).unwrap_or_else(|e| panic!("Failed to read config file: {}", e));
+    serde_yaml::from_str(&content).expect("Failed to parse YAML config")
 }
-// --- Enum Definitions ---
-/// Enum to wrap different widget types for rendering.
-enum UIContent<'a> {
-    List(List<'a>),
-    Paragraph(Paragraph<'a>),
+/// Which pane is shown in the middle section of the UI.
+#[derive(Clone, Copy, PartialEq)]
+enum ActiveView {
+    Files,
+    Logs,
+    Queue,
+    History,
 }
 
-impl<'a> UIContent<'a> {
-    /// Renders the UIContent (List or Paragraph) in the specified area.
-    fn render(self, f: &mut tui::Frame<CrosstermBackend<std::io::Stdout>>, area: tui::layout::Rect) {
+/// Processing state of a queued upload.
+#[derive(Clone, PartialEq)]
+enum QueueStatus {
+    Waiting,
+    Preflight,
+    Uploading,
+    Parked(Instant), // Tracker unavailable, release not ready or outside the upload window; waiting until this time to retry
+    Done,
+    Failed(String),
+}
+
+impl QueueStatus {
+    fn label(&self) -> String {
+        match self {
+            QueueStatus::Waiting => "⏸ waiting".to_string(),
+            QueueStatus::Preflight => "⏳ preflight".to_string(),
+            QueueStatus::Uploading => "🔺 uploading".to_string(),
+            QueueStatus::Parked(retry_at) => format!(
+                "🅿 parked, retry in {}s",
+                retry_at.saturating_duration_since(Instant::now()).as_secs()
+            ),
+            QueueStatus::Done => "✔️ done".to_string(),
+            QueueStatus::Failed(reason) => format!("❌ failed: {}", reason),
+        }
+    }
+
+    fn color(&self) -> Color {
         match self {
-            UIContent::List(list) => f.render_widget(list, area),
-            UIContent::Paragraph(paragraph) => f.render_widget(paragraph, area),
+            QueueStatus::Waiting => Color::DarkGray,
+            QueueStatus::Preflight | QueueStatus::Uploading => Color::Yellow,
+            QueueStatus::Parked(_) => Color::Magenta,
+            QueueStatus::Done => Color::Green,
+            QueueStatus::Failed(_) => Color::Red,
+        }
+    }
+
+    fn is_active(&self) -> bool {
+        matches!(self, QueueStatus::Preflight | QueueStatus::Uploading)
+    }
+}
+
+/// Ids of queue items; paths repeat when a release is queued again, and items move around.
+static NEXT_QUEUE_ITEM_ID: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Clone)]
+struct QueueItem {
+    id: u64,
+    path: PathBuf,
+    trackers: Vec<String>,
+    status: QueueStatus,
+    parked_count: u32, // Times the item was parked, for the backoff schedule
+}
+
+/// Steps of the custom upload wizard.
+#[derive(Clone, Copy, PartialEq)]
+enum WizardStep {
+    Tracker,
+    Category,
+    Type,
+    Preview,
+}
+
+/// Wizard for a custom (`-c`) upload: pick the tracker, category and type from the tracker's
+/// yaml, check the preview, then submit. Replaces typing `-c 0819`-style codes.
+struct CategoryDialog {
+    input_path: PathBuf,
+    trackers: Vec<String>, // Tracker options to pick from, e.g. "🐳 seedpool [SP]"
+    tracker_index: usize,
+    categories: Vec<UploadCategory>, // Of the chosen tracker
+    category_index: usize,
+    type_index: usize,
+    step: WizardStep,
+}
+
+impl CategoryDialog {
+    /// Opens the wizard for `input_path`. With exactly one tracker already selected it starts
+    /// at the category step.
+    fn open(input_path: &Path, trackers: &[&str], selected: &[&String]) -> Result<Self, SeedToolsError> {
+        let mut dialog = Self {
+            input_path: input_path.to_path_buf(),
+            trackers: trackers.iter().map(|t| t.to_string()).collect(),
+            tracker_index: 0,
+            categories: Vec::new(),
+            category_index: 0,
+            type_index: 0,
+            step: WizardStep::Tracker,
+        };
+        if let [tracker] = selected {
+            dialog.tracker_index = dialog.trackers.iter().position(|t| t == *tracker).unwrap_or(0);
+            dialog.load_categories()?;
+        }
+        Ok(dialog)
+    }
+
+    fn tracker(&self) -> &str {
+        &self.trackers[self.tracker_index]
+    }
+
+    fn load_categories(&mut self) -> Result<(), SeedToolsError> {
+        let categories = load_upload_categories(self.tracker())?;
+        if categories.is_empty() {
+            return Err(SeedToolsError::config(format!("No upload categories configured for {}", self.tracker())));
+        }
+        self.categories = categories;
+        self.category_index = 0;
+        self.type_index = 0;
+        self.step = WizardStep::Category;
+        Ok(())
+    }
+
+    fn upload_type(&self) -> Option<&UploadType> {
+        self.categories[self.category_index].types.get(self.type_index)
+    }
+
+    /// The 4-digit custom category/type code; categories without types (e.g. TorrentLeech) use type 0.
+    fn code(&self) -> String {
+        custom_cat_type_code(self.categories[self.category_index].id, self.upload_type().map_or(0, |t| t.id))
+    }
+
+    /// Lines shown for the current step: the list to pick from, or the preview.
+    fn items(&self) -> Vec<String> {
+        match self.step {
+            WizardStep::Tracker => self.trackers.clone(),
+            WizardStep::Category => self
+                .categories
+                .iter()
+                .map(|c| format!("{} ({})", c.name, c.id))
+                .collect(),
+            WizardStep::Type => self.categories[self.category_index]
+                .types
+                .iter()
+                .map(|t| format!("{} ({})", t.name, t.id))
+                .collect(),
+            WizardStep::Preview => self.preview(),
+        }
+    }
+
+    /// What will be submitted, and the equivalent command line.
+    fn preview(&self) -> Vec<String> {
+        let category = &self.categories[self.category_index];
+        let file_name = self.input_path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let flag = if self.tracker().contains("[TL]") { "--TL" } else { "--SP" };
+        vec![
+            format!("Path:          {}", self.input_path.display()),
+            format!("Release name:  {}", utils::generate_release_name(&file_name)),
+            format!("Tracker:       {}", self.tracker()),
+            format!("Category:      {} ({})", category.name, category.id),
+            format!(
+                "Type:          {}",
+                self.upload_type().map_or("none".to_string(), |t| format!("{} ({})", t.name, t.id))
+            ),
+            "File checks:   skipped, the files are uploaded as they are".to_string(),
+            String::new(),
+            format!("Same as: ./seed-tools \"{}\" {} -c {}", self.input_path.display(), flag, self.code()),
+        ]
+    }
+
+    /// Highlighted line of the current list; the preview has none.
+    fn selected_index(&self) -> Option<usize> {
+        match self.step {
+            WizardStep::Tracker => Some(self.tracker_index),
+            WizardStep::Category => Some(self.category_index),
+            WizardStep::Type => Some(self.type_index),
+            WizardStep::Preview => None,
+        }
+    }
+
+    fn move_selection(&mut self, up: bool) {
+        let len = self.items().len();
+        let index = match self.step {
+            WizardStep::Tracker => &mut self.tracker_index,
+            WizardStep::Category => &mut self.category_index,
+            WizardStep::Type => &mut self.type_index,
+            WizardStep::Preview => return,
+        };
+        if up {
+            *index = index.saturating_sub(1);
+        } else if *index + 1 < len {
+            *index += 1;
+        }
+    }
+
+    /// Handles Enter. Returns the tracker and the category/type code when confirmed on the preview.
+    fn confirm(&mut self) -> Result<Option<(String, String)>, SeedToolsError> {
+        match self.step {
+            WizardStep::Tracker => self.load_categories()?,
+            WizardStep::Category => {
+                self.type_index = 0;
+                self.step = if self.categories[self.category_index].types.is_empty() {
+                    WizardStep::Preview
+                } else {
+                    WizardStep::Type
+                };
+            }
+            WizardStep::Type => self.step = WizardStep::Preview,
+            WizardStep::Preview => return Ok(Some((self.tracker().to_string(), self.code()))),
+        }
+        Ok(None)
+    }
+
+    /// Handles Esc/Backspace by going back one step. Returns false when the wizard should close.
+    fn back(&mut self) -> bool {
+        self.step = match self.step {
+            WizardStep::Tracker => return false,
+            WizardStep::Category => WizardStep::Tracker,
+            WizardStep::Type => WizardStep::Category,
+            WizardStep::Preview if self.upload_type().is_some() => WizardStep::Type,
+            WizardStep::Preview => WizardStep::Category,
+        };
+        true
+    }
+
+    fn title(&self) -> String {
+        match self.step {
+            WizardStep::Tracker => " 🏷️ Custom upload 1/4 — tracker [Enter] select [Esc] close ".to_string(),
+            WizardStep::Category => format!(" 🏷️ Custom upload 2/4 — {} category [Enter] select [Esc] back ", self.tracker()),
+            WizardStep::Type => format!(
+                " 🏷️ Custom upload 3/4 — {} type [Enter] select [Esc] back ",
+                self.categories[self.category_index].name
+            ),
+            WizardStep::Preview => " 🏷️ Custom upload 4/4 — preview [Enter] upload [Esc] back ".to_string(),
+        }
+    }
+}
+
+/// Loads the custom upload categories for a tracker option from its tracker config.
+fn load_upload_categories(tracker: &str) -> Result<Vec<UploadCategory>, SeedToolsError> {
+    match tracker {
+        "🐳 seedpool [SP]" => {
+            let content = std::fs::read_to_string(config_path(Path::new("config"), "trackers/seedpool.yaml"))
+                .map_err(SeedToolsError::io("Failed to read seedpool.yaml"))?;
+            let config: SeedpoolConfig = serde_yaml::from_str(&content)
+                .map_err(|e| SeedToolsError::Config { message: format!("Failed to parse seedpool.yaml: {}", e), source: Some(Box::new(e)) })?;
+            Ok(config.upload_categories)
+        }
+        "🐛 TorrentLeech [TL]" => {
+            let content = std::fs::read_to_string(config_path(Path::new("config"), "trackers/torrentleech.yaml"))
+                .map_err(SeedToolsError::io("Failed to read torrentleech.yaml"))?;
+            let config: TorrentLeechConfig = serde_yaml::from_str(&content)
+                .map_err(|e| SeedToolsError::Config { message: format!("Failed to parse torrentleech.yaml: {}", e), source: Some(Box::new(e)) })?;
+            if !config.upload_categories.is_empty() {
+                return Ok(config.upload_categories);
+            }
+            // Fall back to the plain category map TorrentLeech uploads already use
+            let mut categories: Vec<UploadCategory> = config
+                .categories
+                .into_iter()
+                .map(|(name, id)| UploadCategory { name, id, types: Vec::new() })
+                .collect();
+            categories.sort_by(|a, b| a.name.cmp(&b.name));
+            Ok(categories)
+        }
+        _ => Err(SeedToolsError::config(format!("Unknown tracker: {}", tracker))),
+    }
+}
+
+/// Form for overriding the detected upload details before uploading.
+/// Empty fields keep the value seed-tools detects during the upload.
+struct EditForm {
+    fields: Vec<(&'static str, String)>,
+    selected: usize,
+}
+
+const EDIT_FORM_FIELDS: [&str; 8] = [
+    "Release Name",
+    "TMDB ID",
+    "IMDb ID",
+    "TVDB ID",
+    "Season Number",
+    "Episode Number",
+    "Category ID",
+    "Type ID",
+];
+
+impl EditForm {
+    /// Pre-fills the form from the current overrides, falling back to the last pre-flight results.
+    fn new(overrides: &UploadOverrides) -> Self {
+        let (log_data, is_pending) = parse_preflight_log(Path::new("pre-flight.log"));
+        let preflight_value = |label: &str| -> String {
+            if is_pending {
+                return String::new();
+            }
+            log_data
+                .iter()
+                .find_map(|line| line.strip_prefix(&format!("{}: ", label)))
+                .map(|value| value.trim().to_string())
+                .filter(|value| value != "N/A" && value != "0")
+                .unwrap_or_default()
+        };
+        let show = |value: Option<u32>| value.map(|v| v.to_string());
+
+        let values = [
+            overrides.release_name.clone().unwrap_or_else(|| preflight_value("Release Name")),
+            show(overrides.tmdb_id).unwrap_or_else(|| preflight_value("TMDB ID")),
+            overrides.imdb_id.clone().unwrap_or_else(|| preflight_value("IMDb ID")),
+            show(overrides.tvdb_id).unwrap_or_else(|| preflight_value("TVDB ID")),
+            show(overrides.season_number).unwrap_or_else(|| preflight_value("Season Number")),
+            show(overrides.episode_number).unwrap_or_else(|| preflight_value("Episode Number")),
+            show(overrides.category_id).unwrap_or_default(),
+            show(overrides.type_id).unwrap_or_default(),
+        ];
+
+        Self {
+            fields: EDIT_FORM_FIELDS.iter().copied().zip(values).collect(),
+            selected: 0,
+        }
+    }
+
+    fn selected_value(&mut self) -> &mut String {
+        &mut self.fields[self.selected].1
+    }
+
+    fn to_overrides(&self) -> Result<UploadOverrides, SeedToolsError> {
+        let text = |i: usize| -> Option<String> {
+            let value = self.fields[i].1.trim();
+            if value.is_empty() { None } else { Some(value.to_string()) }
+        };
+        let number = |i: usize| -> Result<Option<u32>, SeedToolsError> {
+            match text(i) {
+                Some(value) => value
+                    .parse::<u32>()
+                    .map(Some)
+                    .map_err(|_| SeedToolsError::config(format!("{} must be a number, got '{}'", self.fields[i].0, value))),
+                None => Ok(None),
+            }
+        };
+
+        Ok(UploadOverrides {
+            release_name: text(0),
+            tmdb_id: number(1)?,
+            imdb_id: text(2),
+            tvdb_id: number(3)?,
+            season_number: number(4)?,
+            episode_number: number(5)?,
+            category_id: number(6)?,
+            type_id: number(7)?,
+            release_type: None,
+        })
+    }
+}
+
+/// Returns a rectangle of the given size centered in `area`, clamped to fit.
+fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+/// State of the History pane: recorded uploads, newest first, and the scroll position.
+#[derive(Default)]
+struct HistoryView {
+    entries: Vec<HistoryEntry>,
+    error: Option<String>,
+    scroll: usize,
+}
+
+impl HistoryView {
+    /// Reads the history database again; called whenever the pane is opened.
+    fn reload(&mut self, paths: &PathsConfig) {
+        match history::load(paths) {
+            Ok(entries) => {
+                self.entries = entries;
+                self.error = None;
+            }
+            Err(e) => {
+                self.entries.clear();
+                self.error = Some(e);
+            }
         }
+        self.scroll = 0;
     }
 }
 
-struct TerminalEmulator {
-    buffer: Arc<Mutex<Vec<String>>>,
+/// State of the Logs pane: search query, level filter and scroll position.
+struct LogViewState {
+    search: String,
+    search_input: bool,        // True while Ctrl+F search input is active
+    min_level: Option<Level>,  // None shows every level
+    scroll_from_bottom: usize, // 0 follows the newest lines
 }
 
-impl TerminalEmulator {
+impl LogViewState {
     fn new() -> Self {
         Self {
-            buffer: Arc::new(Mutex::new(Vec::new())),
+            search: String::new(),
+            search_input: false,
+            min_level: None,
+            scroll_from_bottom: 0,
         }
     }
 
-    fn feed(&self, data: &str) {
-        let mut buffer = self.buffer.lock().unwrap();
-        buffer.push(data.to_string());
-        if buffer.len() > 100 {
-            buffer.remove(0); // Keep the buffer size manageable
+    /// Cycles All -> Info -> Warn -> Error -> All.
+    fn cycle_level(&mut self) {
+        self.min_level = match self.min_level {
+            None => Some(Level::Info),
+            Some(Level::Info) => Some(Level::Warn),
+            Some(Level::Warn) => Some(Level::Error),
+            _ => None,
+        };
+        self.scroll_from_bottom = 0;
+    }
+
+    fn level_label(&self) -> &'static str {
+        match self.min_level {
+            None => "all",
+            Some(Level::Info) => "info+",
+            Some(Level::Warn) => "warn+",
+            Some(Level::Error) => "error",
+            Some(_) => "debug+",
         }
     }
 
-    fn render(&self) -> Vec<String> {
-        let buffer = self.buffer.lock().unwrap();
-        buffer.clone()
+    /// Lines from the in-memory log buffer that pass the level filter and search query.
+    fn filtered_lines(&self) -> Vec<LogLine> {
+        let query = self.search.to_lowercase();
+        log_buffer::snapshot()
+            .into_iter()
+            .filter(|line| self.min_level.is_none_or(|min| line.level <= min))
+            .filter(|line| query.is_empty() || line.text.to_lowercase().contains(&query))
+            .collect()
+    }
+}
+
+fn level_color(level: Level) -> Color {
+    match level {
+        Level::Error => Color::Red,
+        Level::Warn => Color::Yellow,
+        Level::Info => Color::White,
+        Level::Debug | Level::Trace => Color::DarkGray,
+    }
+}
+
+/// Splits a log line into spans, highlighting every case-insensitive match of `query`.
+fn highlight_line(text: &str, query: &str, base: Style) -> Spans<'static> {
+    if query.is_empty() {
+        return Spans::from(Span::styled(text.to_string(), base));
+    }
+    let lower_text = text.to_lowercase();
+    let lower_query = query.to_lowercase();
+    // Lowercasing can change byte lengths for some scripts; fall back to no highlighting then
+    if lower_text.len() != text.len() {
+        return Spans::from(Span::styled(text.to_string(), base));
+    }
+
+    let highlight = Style::default().fg(Color::Black).bg(Color::Yellow).add_modifier(Modifier::BOLD);
+    let mut spans = Vec::new();
+    let mut last = 0;
+    for (start, _) in lower_text.match_indices(&lower_query) {
+        if start < last {
+            continue;
+        }
+        if start > last {
+            spans.push(Span::styled(text[last..start].to_string(), base));
+        }
+        let end = start + lower_query.len();
+        spans.push(Span::styled(text[start..end].to_string(), highlight));
+        last = end;
+    }
+    if last < text.len() {
+        spans.push(Span::styled(text[last..].to_string(), base));
     }
+    Spans::from(spans)
 }
 
 pub fn launch_ui() -> Result<(), Box<dyn std::error::Error>> {
-    // Set up a panic hook to restore the terminal state on panic
-    let original_hook = std::panic::take_hook();
     let config = load_config();
 
-    // Extract the TMDB API key and mediainfo path
-    let tmdb_api_key = config.general.tmdb_api_key;
-    let mediainfo_path = config.paths.mediainfo.clone();
-    std::panic::set_hook(Box::new(move |panic_info| {
-        let _ = disable_raw_mode();
-        let _ = execute!(io::stdout(), LeaveAlternateScreen, crossterm::event::DisableMouseCapture);
-        original_hook(panic_info);
-    }));
+    // Restore the terminal on panic (any thread), SIGINT/SIGTERM/SIGHUP, or early return
+    let _terminal_guard = TerminalGuard::new();
 
     // Enable raw mode and set up the terminal
     enable_raw_mode()?;
@@ -128,43 +549,56 @@ pub fn launch_ui() -> Result<(), Box<dyn std::error::Error>> {
     let mut file_list = get_files_in_dir(&current_dir);
     let mut selected_file_index = 0;
     let mut scroll_offset = 0;
-    let mut tracker_scroll_offset = 0;
+    let tracker_scroll_offset = 0;
     let mut selected_trackers = Vec::<String>::new();
     let mut input_path = None::<PathBuf>;
     let mut exit_requested = false;
-    let mut showing_log = false; // Flag to indicate if we're showing the log
+    let mut active_view = ActiveView::Files; // Pane shown in the middle section
+    let upload_queue = Arc::new(Mutex::new(Vec::<QueueItem>::new()));
+    let mut selected_queue_index = 0;
+    let mut category_dialog: Option<CategoryDialog> = None; // Custom upload category/type popup
+    let mut edit_form: Option<EditForm> = None; // Upload details edit form popup
+    let mut upload_overrides = UploadOverrides::default(); // Applied to the next upload of `input_path`
 
     let tracker_options = vec!["✔️ Select All", "🐳 seedpool [SP]", "🐛 TorrentLeech [TL]"];
     let log_output = Arc::new(Mutex::new(Vec::<String>::new()));
-    let log_scroll_offset = Arc::new(Mutex::new(0)); // Shared scroll offset for logs
-    let mut preflight_check_result: Option<PreflightCheckResult> = None;
-    let mut upload_running = false; // Tracks if the upload process is running
-    let mut preflight_check_running = false;
-    let terminal_emulator = Arc::new(TerminalEmulator::new());
-    let log_file_path = "seed-tools.log";
-    start_log_tail(Arc::clone(&terminal_emulator), log_file_path);
+    let mut log_view = LogViewState::new(); // Search, level filter and scroll for the Logs pane
+    let mut history_view = HistoryView::default(); // Loaded when the History pane is opened
+    let mut last_log_generation = log_buffer::generation();
+    // Follow seed-tools.log for records from other processes (pre-flight checks, other instances).
+    // Stopped and joined when dropped at the end of launch_ui.
+    let _log_tailer = log_buffer::LogTailer::start(crate::logging::LOG_FILE);
     // Channel for notifying the main loop of log updates
     let (tx, rx) = mpsc::channel::<()>();
-    let mut terminal_scroll_offset = 0; 
+    // Every redraw shows the same state; defined here so it sees all of it
+    macro_rules! draw {
+        () => {
+            terminal.draw(|f| {
+                render_ui(
+                    f,
+                    &Screen {
+                        input_path: &input_path,
+                        selected_trackers: &selected_trackers,
+                        file_list: &file_list,
+                        selected_file_index,
+                        scroll_offset,
+                        tracker_scroll_offset,
+                        tracker_options: &tracker_options,
+                        active_view,
+                        log_view: &log_view,
+                        upload_queue: &upload_queue,
+                        selected_queue_index,
+                        category_dialog: &category_dialog,
+                        edit_form: &edit_form,
+                        upload_overrides: &upload_overrides,
+                        history_view: &history_view,
+                    },
+                )
+            })?
+        };
+    }
     // Initial UI render
-    terminal.draw(|f| {
-        render_ui(
-            f,
-            &input_path,
-            &selected_trackers,
-            &file_list,
-            selected_file_index,
-            scroll_offset,
-            tracker_scroll_offset,
-            &tracker_options,
-            showing_log,
-            &terminal_emulator, // Pass the terminal emulator for logs
-            &log_scroll_offset, // Add the missing argument
-            &preflight_check_result,
-            upload_running,
-            preflight_check_running,
-        );
-    })?;
+    draw!();
 
     // Main loop
     loop {
@@ -173,30 +607,22 @@ pub fn launch_ui() -> Result<(), Box<dyn std::error::Error>> {
         }
 
         // Check for log updates and redraw the UI if necessary
-        if let Ok(_) = rx.try_recv() {
-            terminal.draw(|f| {
-                render_ui(
-                    f,
-                    &input_path,
-                    &selected_trackers,
-                    &file_list,
-                    selected_file_index,
-                    scroll_offset,
-                    tracker_scroll_offset,
-                    &tracker_options,
-                    showing_log,
-                    &terminal_emulator, // Pass the terminal emulator for logs
-                    &log_scroll_offset, // Add the missing argument
-                    &preflight_check_result,
-                    upload_running,
-                    preflight_check_running,
-                );
-            })?;
+        let log_generation = log_buffer::generation();
+        let logs_changed = active_view == ActiveView::Logs && log_generation != last_log_generation;
+        last_log_generation = log_generation;
+        if rx.try_recv().is_ok() || logs_changed {
+            draw!();
         }
 
-        if let Event::Mouse(mouse_event) = event::read()? {
+        // Poll so background queue workers can trigger redraws through `rx`
+        if !event::poll(Duration::from_millis(250))? {
+            continue;
+        }
+        let current_event = event::read()?;
+
+        if let Event::Mouse(mouse_event) = current_event {
             match mouse_event.kind {
-                crossterm::event::MouseEventKind::Down(crossterm::event::MouseButton::Left) => {
+                crossterm::event::MouseEventKind::Down(crossterm::event::MouseButton::Left) if category_dialog.is_none() && edit_form.is_none() => {
                     let y = mouse_event.row.saturating_sub(1); // Adjust for offset
                     let x = mouse_event.column;
         
@@ -239,17 +665,25 @@ pub fn launch_ui() -> Result<(), Box<dyn std::error::Error>> {
                     let logs_button_end_x = logs_button_start_x + 8;    // End X position of "📃 Logs" button
         
                     // Handle "Files" and "Logs" button clicks
+                    let queue_button_start_x = logs_button_end_x + 3;   // Start X position of "📋 Queue" button
+                    let queue_button_end_x = queue_button_start_x + 12; // End X position of "📋 Queue" button
+                    let history_button_start_x = queue_button_end_x + 3; // Start X position of "📜 History" button
+                    let history_button_end_x = history_button_start_x + 10; // End X position of "📜 History" button
+
                     if y == buttons_y {
                         if x >= files_button_start_x && x < files_button_end_x {
                             // "Files" button clicked
-                            showing_log = false;
+                            active_view = ActiveView::Files;
                         } else if x >= logs_button_start_x && x < logs_button_end_x {
                             // "Logs" button clicked
-                            showing_log = true;
-        
-                            // Start tailing the log file in the terminal emulator
-                            let log_file_path = "seed-tools.log";
-                            start_log_tail(Arc::clone(&terminal_emulator), log_file_path);
+                            active_view = ActiveView::Logs;
+                        } else if x >= queue_button_start_x && x < queue_button_end_x {
+                            // "Queue" button clicked
+                            active_view = ActiveView::Queue;
+                        } else if x >= history_button_start_x && x < history_button_end_x {
+                            // "History" button clicked
+                            active_view = ActiveView::History;
+                            history_view.reload(&config.paths);
                         }
                     }
         
@@ -259,16 +693,13 @@ pub fn launch_ui() -> Result<(), Box<dyn std::error::Error>> {
                         if relative_y == 0 {
                             // Upload button clicked
                             if input_path.is_some() && !selected_trackers.is_empty() {
-                                showing_log = true; // Switch to log view
-                                upload_running = true; // Set spinner state to true
-        
-                                // Start tailing the log file in the terminal emulator
-                                let log_file_path = "seed-tools.log";
-                                start_log_tail(Arc::clone(&terminal_emulator), log_file_path);
+                                active_view = ActiveView::Logs; // Switch to log view
+                                log_view.scroll_from_bottom = 0; // Follow the upload output
         
                                 // Start the upload process in a separate thread
                                 let input_path = input_path.clone();
                                 let selected_trackers = selected_trackers.clone();
+                                let upload_overrides = upload_overrides.clone();
                                 thread::spawn({
                                     let log_output = Arc::clone(&log_output);
                                     move || {
@@ -276,11 +707,10 @@ pub fn launch_ui() -> Result<(), Box<dyn std::error::Error>> {
                                             &input_path,
                                             &selected_trackers,
                                             &None,
+                                            &upload_overrides,
+                                            false,
                                             log_output,
                                         );
-        
-                                        // Reset spinner state and notify the main loop
-                                        upload_running = false;
                                     }
                                 });
                             } else {
@@ -297,8 +727,16 @@ pub fn launch_ui() -> Result<(), Box<dyn std::error::Error>> {
                                     // Define the pre-flight log file path
                                     let preflight_log_path = PathBuf::from("pre-flight.log");
         
+                                    let seed_tools_path = match seed_tools_exe() {
+                                        Ok(path) => path,
+                                        Err(e) => {
+                                            log_output.lock().unwrap().push(format!("Failed to run Pre-flight Check: {}", e));
+                                            return;
+                                        }
+                                    };
                                     // Run the seed-tools command with --pre and redirect output to pre-flight.log
-                                    let status = Command::new("./seed-tools")
+                                    let status = Command::new(seed_tools_path)
+                                        .envs(crate::profile::child_env())
                                         .arg("--pre")
                                         .arg(input_path.display().to_string())
                                         .stdout(Stdio::from(
@@ -327,6 +765,10 @@ pub fn launch_ui() -> Result<(), Box<dyn std::error::Error>> {
                             } else {
                                 log_output.lock().unwrap().push("Error: No input path selected.".to_string());
                             }
+                        } else if relative_y == 2 {
+                            // Queue button clicked: queue the current selection
+                            enqueue_selection(&upload_queue, &input_path, &selected_trackers, &log_output);
+                            active_view = ActiveView::Queue;
                         }
                     }
         
@@ -353,8 +795,16 @@ pub fn launch_ui() -> Result<(), Box<dyn std::error::Error>> {
                         }
                     }
         
+                    // Handle queue list clicks
+                    if active_view == ActiveView::Queue && x < middle_chunks[0].x + middle_chunks[0].width && y > middle_chunks[0].y && y < middle_chunks[0].y + middle_chunks[0].height {
+                        let clicked_index = (y - middle_chunks[0].y - 1) as usize;
+                        if clicked_index < upload_queue.lock().unwrap().len() {
+                            selected_queue_index = clicked_index;
+                        }
+                    }
+
                     // Handle file list clicks
-                    if !showing_log && x < middle_chunks[0].x + middle_chunks[0].width && y >= middle_chunks[0].y && y < middle_chunks[0].y + middle_chunks[0].height {
+                    if active_view == ActiveView::Files && x < middle_chunks[0].x + middle_chunks[0].width && y >= middle_chunks[0].y && y < middle_chunks[0].y + middle_chunks[0].height {
                         let relative_y = y - middle_chunks[0].y;
                         let clicked_index = scroll_offset + relative_y as usize;
                         if clicked_index < file_list.len() {
@@ -373,46 +823,41 @@ pub fn launch_ui() -> Result<(), Box<dyn std::error::Error>> {
                                 selected_file_index = 0;
                                 scroll_offset = 0;
                                 input_path = Some(selected_path); // Set as input path
+                                upload_overrides = UploadOverrides::default(); // Overrides belong to the previous path
                             } else if selected_path.is_file() {
                                 input_path = Some(selected_path);
+                                upload_overrides = UploadOverrides::default();
                             }
                         }
                     }
         
                     // Redraw the UI after handling a click
-                    terminal.draw(|f| {
-                        render_ui(
-                            f,
-                            &input_path,
-                            &selected_trackers,
-                            &file_list,
-                            selected_file_index,
-                            scroll_offset,
-                            tracker_scroll_offset,
-                            &tracker_options,
-                            showing_log,
-                            &terminal_emulator, // Pass the terminal emulator for logs
-                            &log_scroll_offset, // Add the missing argument
-                            &preflight_check_result,
-                            upload_running,
-                            preflight_check_running,
-                        );
-                    })?;
+                    draw!();
                 }
                 crossterm::event::MouseEventKind::ScrollUp => {
-                    if showing_log {
-                        if terminal_scroll_offset > 0 {
-                            terminal_scroll_offset -= 1; // Scroll up in the terminal window
+                    if active_view == ActiveView::Queue {
+                        selected_queue_index = selected_queue_index.saturating_sub(1);
+                    } else if active_view == ActiveView::History {
+                        history_view.scroll = history_view.scroll.saturating_sub(1);
+                    } else if active_view == ActiveView::Logs {
+                        let total_lines = log_view.filtered_lines().len();
+                        if log_view.scroll_from_bottom + 1 < total_lines {
+                            log_view.scroll_from_bottom += 1; // Scroll up in the log window
                         }
-                    } else if scroll_offset > 0 {
-                        scroll_offset -= 1; // Scroll up in the file list
+                    } else {
+                        scroll_offset = scroll_offset.saturating_sub(1); // Scroll up in the file list
                     }
                 }
                 crossterm::event::MouseEventKind::ScrollDown => {
-                    if showing_log {
-                        let terminal_output = terminal_emulator.render();
-                        if terminal_scroll_offset + 1 < terminal_output.len() {
-                            terminal_scroll_offset += 1; // Scroll down in the terminal window
+                    if active_view == ActiveView::Queue {
+                        if selected_queue_index + 1 < upload_queue.lock().unwrap().len() {
+                            selected_queue_index += 1;
+                        }
+                    } else if active_view == ActiveView::Logs {
+                        log_view.scroll_from_bottom = log_view.scroll_from_bottom.saturating_sub(1); // Scroll down in the log window
+                    } else if active_view == ActiveView::History {
+                        if history_view.scroll + 1 < history_view.entries.len() {
+                            history_view.scroll += 1;
                         }
                     } else if scroll_offset + 1 < file_list.len() {
                         scroll_offset += 1; // Scroll down in the file list
@@ -422,31 +867,204 @@ pub fn launch_ui() -> Result<(), Box<dyn std::error::Error>> {
             }
         
             // Redraw the UI after handling scroll events
-            terminal.draw(|f| {
-                render_ui(
-                    f,
-                    &input_path,
-                    &selected_trackers,
-                    &file_list,
-                    selected_file_index,
-                    scroll_offset,
-                    tracker_scroll_offset,
-                    &tracker_options,
-                    showing_log,
-                    &terminal_emulator, // Pass the terminal emulator for logs
-                    &log_scroll_offset, // Add the missing argument
-                    &preflight_check_result,
-                    upload_running,
-                    preflight_check_running,
-                );
-            })?;
-        } else if let Event::Key(key) = event::read()? {
-            match key.code {
-                KeyCode::Esc => {
-                    exit_requested = true;
+            draw!();
+        } else if let Event::Key(key) = current_event {
+            if let Some(dialog) = category_dialog.as_mut() {
+                // The custom upload wizard captures all keys while open
+                let mut close_dialog = false;
+                match key.code {
+                    KeyCode::Esc | KeyCode::Backspace => close_dialog = !dialog.back(),
+                    KeyCode::Up => dialog.move_selection(true),
+                    KeyCode::Down => dialog.move_selection(false),
+                    KeyCode::Enter => match dialog.confirm() {
+                        Ok(Some((tracker, code))) => {
+                            close_dialog = true;
+                            info!("Starting custom upload to {} with category/type {}", tracker, code);
+                            active_view = ActiveView::Logs;
+                            log_view.scroll_from_bottom = 0;
+
+                            let input_path = Some(dialog.input_path.clone());
+                            let trackers = vec![tracker];
+                            let log_output = Arc::clone(&log_output);
+                            thread::spawn(move || {
+                                if let Err(e) = activate_upload(&input_path, &trackers, &Some(code), &UploadOverrides::default(), false, log_output) {
+                                    error!("Custom upload failed: {}", e);
+                                }
+                            });
+                        }
+                        Ok(None) => {}
+                        Err(e) => error!("Custom upload: {}", e),
+                    },
+                    _ => {}
+                }
+                if close_dialog {
+                    category_dialog = None;
+                }
+            } else if let Some(form) = edit_form.as_mut() {
+                // The edit form captures all keys while open
+                let mut close_form = false;
+                match key.code {
+                    KeyCode::Esc => close_form = true,
+                    KeyCode::Up | KeyCode::BackTab => form.selected = form.selected.saturating_sub(1),
+                    KeyCode::Down | KeyCode::Tab
+                        if form.selected + 1 < form.fields.len() => {
+                            form.selected += 1;
+                        }
+                    KeyCode::Backspace => {
+                        form.selected_value().pop();
+                    }
+                    KeyCode::Char(c) => form.selected_value().push(c),
+                    KeyCode::Enter => match form.to_overrides() {
+                        Ok(overrides) => {
+                            info!("Upload overrides set: {:?}", overrides);
+                            upload_overrides = overrides;
+                            close_form = true;
+                        }
+                        Err(e) => warn!("Invalid upload override: {}", e),
+                    },
+                    _ => {}
+                }
+                if close_form {
+                    edit_form = None;
+                }
+            } else if log_view.search_input {
+                // Search input captures all keys until Enter/Esc
+                match key.code {
+                    KeyCode::Esc | KeyCode::Enter => log_view.search_input = false,
+                    KeyCode::Backspace => {
+                        log_view.search.pop();
+                    }
+                    KeyCode::Char(c) => log_view.search.push(c),
+                    _ => {}
+                }
+                log_view.scroll_from_bottom = 0;
+            } else {
+                match key.code {
+                    KeyCode::Char('f') | KeyCode::Char('F') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        active_view = ActiveView::Logs;
+                        log_view.search_input = true;
+                    }
+                    KeyCode::Char('l') | KeyCode::Char('L') if active_view == ActiveView::Logs => {
+                        log_view.cycle_level();
+                    }
+                    KeyCode::Char('c') | KeyCode::Char('C') if active_view == ActiveView::Logs => {
+                        // Clear the search query
+                        log_view.search.clear();
+                        log_view.scroll_from_bottom = 0;
+                    }
+                    KeyCode::Up | KeyCode::PageUp if active_view == ActiveView::Logs => {
+                        let step = if key.code == KeyCode::PageUp { 10 } else { 1 };
+                        let total_lines = log_view.filtered_lines().len();
+                        log_view.scroll_from_bottom = (log_view.scroll_from_bottom + step).min(total_lines.saturating_sub(1));
+                    }
+                    KeyCode::Down | KeyCode::PageDown if active_view == ActiveView::Logs => {
+                        let step = if key.code == KeyCode::PageDown { 10 } else { 1 };
+                        log_view.scroll_from_bottom = log_view.scroll_from_bottom.saturating_sub(step);
+                    }
+                    KeyCode::End if active_view == ActiveView::Logs => {
+                        log_view.scroll_from_bottom = 0; // Jump back to the newest lines
+                    }
+                    KeyCode::Esc => {
+                        exit_requested = true;
+                    }
+                    KeyCode::Tab => {
+                        active_view = match active_view {
+                            ActiveView::Files => ActiveView::Logs,
+                            ActiveView::Logs => ActiveView::Queue,
+                            ActiveView::Queue => ActiveView::History,
+                            ActiveView::History => ActiveView::Files,
+                        };
+                        if active_view == ActiveView::History {
+                            history_view.reload(&config.paths);
+                        }
+                    }
+                    KeyCode::Char('a') | KeyCode::Char('A') => {
+                        enqueue_selection(&upload_queue, &input_path, &selected_trackers, &log_output);
+                    }
+                    KeyCode::Char('e') | KeyCode::Char('E') => {
+                        if input_path.is_some() {
+                            edit_form = Some(EditForm::new(&upload_overrides));
+                        } else {
+                            warn!("Edit upload details: no input path selected.");
+                        }
+                    }
+                    KeyCode::Char('c') | KeyCode::Char('C') => {
+                        // Custom category/type upload wizard; asks for the tracker unless exactly one is selected
+                        let trackers: Vec<&String> = selected_trackers
+                            .iter()
+                            .filter(|t| t.as_str() != "✔️ Select All")
+                            .collect();
+                        if let Some(path) = &input_path {
+                            match CategoryDialog::open(path, &tracker_options[1..], &trackers) {
+                                Ok(dialog) => category_dialog = Some(dialog),
+                                Err(e) => error!("Custom upload: {}", e),
+                            }
+                        } else {
+                            warn!("Custom upload: no input path selected.");
+                        }
+                    }
+                    KeyCode::Char('s') | KeyCode::Char('S') => {
+                        start_queue(
+                            Arc::clone(&upload_queue),
+                            &config.ui,
+                            &config.maintenance,
+                            &config.notifications,
+                            Arc::clone(&log_output),
+                            tx.clone(),
+                        );
+                        active_view = ActiveView::Queue;
+                    }
+                    KeyCode::Up | KeyCode::PageUp if active_view == ActiveView::History => {
+                        let step = if key.code == KeyCode::PageUp { 10 } else { 1 };
+                        history_view.scroll = history_view.scroll.saturating_sub(step);
+                    }
+                    KeyCode::Down | KeyCode::PageDown if active_view == ActiveView::History => {
+                        let step = if key.code == KeyCode::PageDown { 10 } else { 1 };
+                        history_view.scroll = (history_view.scroll + step).min(history_view.entries.len().saturating_sub(1));
+                    }
+                    KeyCode::Char('r') | KeyCode::Char('R') if active_view == ActiveView::History => {
+                        history_view.reload(&config.paths);
+                    }
+                    KeyCode::Up if active_view == ActiveView::Queue => {
+                        selected_queue_index = selected_queue_index.saturating_sub(1);
+                    }
+                    KeyCode::Down if active_view == ActiveView::Queue && selected_queue_index + 1 < upload_queue.lock().unwrap().len() => {
+                        selected_queue_index += 1;
+                    }
+                    KeyCode::Char('k') | KeyCode::Char('K') if active_view == ActiveView::Queue => {
+                        // Move the selected item up
+                        let mut queue = upload_queue.lock().unwrap();
+                        if selected_queue_index > 0 && selected_queue_index < queue.len() {
+                            queue.swap(selected_queue_index, selected_queue_index - 1);
+                            selected_queue_index -= 1;
+                        }
+                    }
+                    KeyCode::Char('j') | KeyCode::Char('J') if active_view == ActiveView::Queue => {
+                        // Move the selected item down
+                        let mut queue = upload_queue.lock().unwrap();
+                        if selected_queue_index + 1 < queue.len() {
+                            queue.swap(selected_queue_index, selected_queue_index + 1);
+                            selected_queue_index += 1;
+                        }
+                    }
+                    KeyCode::Delete | KeyCode::Char('d') | KeyCode::Char('D') if active_view == ActiveView::Queue => {
+                        let mut queue = upload_queue.lock().unwrap();
+                        if let Some(item) = queue.get(selected_queue_index) {
+                            if item.status.is_active() {
+                                log_output.lock().unwrap().push("Cannot remove an item that is currently processing.".to_string());
+                            } else {
+                                queue.remove(selected_queue_index);
+                                if selected_queue_index >= queue.len() {
+                                    selected_queue_index = queue.len().saturating_sub(1);
+                                }
+                            }
+                        }
+                    }
+                    _ => {}
                 }
-                _ => {}
             }
+
+            draw!();
         }
     }
 
@@ -457,22 +1075,45 @@ pub fn launch_ui() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-fn render_ui(
-    f: &mut tui::Frame<CrosstermBackend<std::io::Stdout>>,
-    input_path: &Option<PathBuf>,
-    selected_trackers: &Vec<String>,
-    file_list: &Vec<String>,
+/// What `render_ui` draws: the state of `launch_ui`, borrowed for one frame.
+#[derive(Clone, Copy)]
+struct Screen<'a> {
+    input_path: &'a Option<PathBuf>,
+    selected_trackers: &'a [String],
+    file_list: &'a [String],
     selected_file_index: usize,
     scroll_offset: usize,
     tracker_scroll_offset: usize,
-    tracker_options: &[&str],
-    showing_log: bool,
-    terminal_emulator: &Arc<TerminalEmulator>, // Pass terminal_emulator instead of log_output
-    log_scroll_offset: &Arc<Mutex<usize>>,
-    preflight_check_result: &Option<PreflightCheckResult>,
-    upload_running: bool,
-    preflight_check_running: bool,
-) {
+    tracker_options: &'a [&'a str],
+    active_view: ActiveView,
+    log_view: &'a LogViewState,
+    upload_queue: &'a Arc<Mutex<Vec<QueueItem>>>,
+    selected_queue_index: usize,
+    category_dialog: &'a Option<CategoryDialog>,
+    edit_form: &'a Option<EditForm>,
+    upload_overrides: &'a UploadOverrides,
+    history_view: &'a HistoryView,
+}
+
+fn render_ui(f: &mut tui::Frame<CrosstermBackend<std::io::Stdout>>, screen: &Screen) {
+    let Screen {
+        input_path,
+        selected_trackers,
+        file_list,
+        selected_file_index,
+        scroll_offset,
+        tracker_scroll_offset,
+        tracker_options,
+        active_view,
+        log_view,
+        upload_queue,
+        selected_queue_index,
+        category_dialog,
+        edit_form,
+        upload_overrides,
+        history_view,
+    } = *screen;
+    let queue_snapshot = upload_queue.lock().unwrap().clone();
     // Define the layout
     let size = f.size();
 
@@ -575,6 +1216,22 @@ fn render_ui(
         ]));
     }
     
+    // Upload queue summary
+    let waiting = queue_snapshot.iter().filter(|item| item.status == QueueStatus::Waiting).count();
+    let running = queue_snapshot.iter().filter(|item| item.status.is_active()).count();
+    let parked = queue_snapshot.iter().filter(|item| matches!(item.status, QueueStatus::Parked(_))).count();
+    let finished = queue_snapshot.len() - waiting - running - parked;
+    status_lines.push(Spans::from(vec![
+        Span::styled(
+            "Queue: ",
+            Style::default().fg(Color::DarkGray), // DarkGray for the label
+        ),
+        Span::styled(
+            format!("{} waiting, {} running, {} parked, {} finished", waiting, running, parked, finished),
+            Style::default().fg(if running > 0 { Color::Yellow } else { Color::LightCyan }),
+        ),
+    ]));
+    
     // Render the status section in `top_chunks[0]`
     let status_paragraph = Paragraph::new(status_lines)
         .block(Block::default().borders(Borders::ALL).title(" 🌀 Seed-Tools v0.42 "))
@@ -597,6 +1254,13 @@ fn render_ui(
                 .bg(Color::Green) // Background color
                 .add_modifier(Modifier::BOLD),
         )]),
+        Spans::from(vec![Span::styled(
+            "📋 ＱＵＥＵＥ ", // Add to queue button text
+            Style::default()
+                .fg(Color::White) // Text color
+                .bg(Color::Blue) // Background color
+                .add_modifier(Modifier::BOLD),
+        )]),
     ];
 
     let button_paragraph = Paragraph::new(button_lines)
@@ -611,14 +1275,28 @@ fn render_ui(
         Span::styled(
             " 🖥️ Files",
             Style::default()
-                .fg(if !showing_log { Color::Yellow } else { Color::White })
+                .fg(if active_view == ActiveView::Files { Color::Yellow } else { Color::White })
                 .add_modifier(Modifier::BOLD),
         ),
         Span::raw("   "), // Add spacing between buttons
         Span::styled(
             " 📃 Logs",
             Style::default()
-                .fg(if showing_log { Color::Yellow } else { Color::White })
+                .fg(if active_view == ActiveView::Logs { Color::Yellow } else { Color::White })
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw("   "), // Add spacing between buttons
+        Span::styled(
+            format!(" 📋 Queue ({})", queue_snapshot.len()),
+            Style::default()
+                .fg(if active_view == ActiveView::Queue { Color::Yellow } else { Color::White })
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw("   "), // Add spacing between buttons
+        Span::styled(
+            " 📜 History",
+            Style::default()
+                .fg(if active_view == ActiveView::History { Color::Yellow } else { Color::White })
                 .add_modifier(Modifier::BOLD),
         ),
     ]);
@@ -630,22 +1308,112 @@ fn render_ui(
     // Render the buttons section in chunks[1]
     f.render_widget(files_logs_paragraph, chunks[1]);
 
-    // Render File List or Log Section
-    if showing_log {
-        // Render the terminal emulator
-        let mut terminal_scroll_offset = 0; 
-    let terminal_output = terminal_emulator.render();
-    let visible_lines = terminal_output
-        .iter()
-        .skip(terminal_scroll_offset) // Skip lines based on the scroll offset
-        .take(middle_chunks[0].height as usize) // Take only the visible lines
-        .map(|line| Spans::from(Span::raw(line.clone())))
-        .collect::<Vec<_>>();
-
-    let terminal_widget = Paragraph::new(visible_lines)
-        .block(Block::default().borders(Borders::ALL)) // Remove the title
-        .style(Style::default().bg(Color::Black).fg(Color::White));
-    f.render_widget(terminal_widget, middle_chunks[0]);
+    // Render File List, Log or Queue Section
+    if active_view == ActiveView::Queue {
+        let queue_items = if queue_snapshot.is_empty() {
+            vec![ListItem::new(Span::styled(
+                "Queue is empty. Select a path and trackers, then press [A] or 📋 ＱＵＥＵＥ.",
+                Style::default().fg(Color::DarkGray),
+            ))]
+        } else {
+            queue_snapshot
+                .iter()
+                .enumerate()
+                .map(|(i, item)| {
+                    let name = item
+                        .path
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_else(|| item.path.display().to_string());
+                    let name_style = if i == selected_queue_index {
+                        Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+                    } else {
+                        Style::default()
+                    };
+                    ListItem::new(Spans::from(vec![
+                        Span::styled(format!("{:>2}. ", i + 1), Style::default().fg(Color::DarkGray)),
+                        Span::styled(name, name_style),
+                        Span::styled(format!("  [{}]  ", item.trackers.join(", ")), Style::default().fg(Color::LightCyan)),
+                        Span::styled(item.status.label(), Style::default().fg(item.status.color())),
+                    ]))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let queue_widget = List::new(queue_items)
+            .block(Block::default().borders(Borders::ALL).title(" 📋 Upload Queue — [S] start  [K/J] move  [D] remove "))
+            .style(Style::default().bg(Color::Rgb(8, 8, 32))); // Background color
+        f.render_widget(queue_widget, middle_chunks[0]);
+    } else if active_view == ActiveView::History {
+        let history_items = if let Some(e) = &history_view.error {
+            vec![ListItem::new(Span::styled(format!("Failed to read the upload history: {}", e), Style::default().fg(Color::Red)))]
+        } else if history_view.entries.is_empty() {
+            vec![ListItem::new(Span::styled(
+                "No uploads recorded yet.",
+                Style::default().fg(Color::DarkGray),
+            ))]
+        } else {
+            history_view
+                .entries
+                .iter()
+                .skip(history_view.scroll)
+                .take((middle_chunks[0].height as usize).saturating_sub(2)) // Minus the borders
+                .map(|entry| {
+                    let uploaded_at = chrono::DateTime::parse_from_rfc3339(&entry.uploaded_at)
+                        .map(|time| time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+                        .unwrap_or_else(|_| entry.uploaded_at.clone());
+                    ListItem::new(Spans::from(vec![
+                        Span::styled(format!("{}  ", uploaded_at), Style::default().fg(Color::DarkGray)),
+                        Span::styled(format!("{:<12}  ", entry.tracker), Style::default().fg(Color::LightCyan)),
+                        Span::raw(entry.release_name.clone()),
+                        Span::styled(
+                            entry.torrent_id.as_ref().map(|id| format!("  #{}", id)).unwrap_or_default(),
+                            Style::default().fg(Color::Green),
+                        ),
+                        Span::styled(if entry.imported { "  (imported)" } else { "" }, Style::default().fg(Color::DarkGray)),
+                    ]))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let history_widget = List::new(history_items)
+            .block(Block::default().borders(Borders::ALL).title(format!(
+                " 📜 Upload History — {} uploads  [R] reload ",
+                history_view.entries.len()
+            )))
+            .style(Style::default().bg(Color::Rgb(8, 8, 32))); // Background color
+        f.render_widget(history_widget, middle_chunks[0]);
+    } else if active_view == ActiveView::Logs {
+        // Render the in-memory session log, newest lines at the bottom
+        let filtered_lines = log_view.filtered_lines();
+        let height = (middle_chunks[0].height as usize).saturating_sub(2); // Minus the borders
+        let end = filtered_lines.len().saturating_sub(log_view.scroll_from_bottom);
+        let start = end.saturating_sub(height);
+        let visible_lines = filtered_lines[start..end]
+            .iter()
+            .map(|line| highlight_line(&line.text, &log_view.search, Style::default().fg(level_color(line.level))))
+            .collect::<Vec<_>>();
+
+        let search_label = if log_view.search_input {
+            format!("🔍 {}▏", log_view.search)
+        } else if log_view.search.is_empty() {
+            "[Ctrl+F] search".to_string()
+        } else {
+            format!("🔍 \"{}\" [C] clear", log_view.search)
+        };
+        let title = format!(
+            " 📃 Logs — {} of {} lines  level: {} [L]  {}{} ",
+            filtered_lines.len(),
+            log_buffer::snapshot().len(),
+            log_view.level_label(),
+            search_label,
+            if log_view.scroll_from_bottom > 0 { "  [End] follow" } else { "" },
+        );
+
+        let terminal_widget = Paragraph::new(visible_lines)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .style(Style::default().bg(Color::Black).fg(Color::White));
+        f.render_widget(terminal_widget, middle_chunks[0]);
     } else {
         // Render the file list
         let mut visible_files = vec!["🗂️ ..".to_string()];
@@ -680,7 +1448,7 @@ fn render_ui(
     let visible_trackers = &tracker_options[tracker_scroll_offset
         ..(tracker_scroll_offset + middle_chunks[1].height as usize).min(tracker_options.len())];
     let tracker_list_widget = List::new(
-        visible_trackers.iter().enumerate().map(|(i, tracker)| {
+        visible_trackers.iter().map(|tracker| {
             let is_selected = selected_trackers.contains(&tracker.to_string());
             let tracker_name = if is_selected {
                 format!("{} ✔️", tracker) // Append ✔️ to selected trackers
@@ -769,6 +1537,16 @@ fn render_ui(
                         log_data[11].replace("Audio Languages: ", ""),
                         Style::default().fg(Color::LightMagenta), // Magenta for the value
                     ),
+                    Span::raw(" | "),
+                    // Subtitles
+                    Span::styled(
+                        "Subs: ",
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::styled(
+                        log_data[12].replace("Subtitles: ", ""),
+                        Style::default().fg(Color::LightMagenta),
+                    ),
                 ]));
     
                 // Line 2: TMDB, IMDb, TVDB IDs, Season/Episode Numbers
@@ -919,13 +1697,17 @@ fn render_ui(
     }
     
     let preflight_paragraph = Paragraph::new(preflight_lines)
-        .block(Block::default().borders(Borders::ALL).title(" ✅ Pre-flight Check "))
+        .block(Block::default().borders(Borders::ALL).title(if upload_overrides.is_empty() {
+            " ✅ Pre-flight Check — [E] edit details ".to_string()
+        } else {
+            " ✅ Pre-flight Check — ✏️ overrides set, [E] edit ".to_string()
+        }))
         .style(Style::default().bg(Color::Rgb(8, 8, 32))); // Background color
     f.render_widget(preflight_paragraph, chunks[3]);
 
     // Render Bottom Section
     let bottom_lines = vec![Spans::from(vec![Span::styled(
-        "Spam [ESC] to Quit ❌",
+        "[A] Queue selection  [S] Start queue  [C] Custom upload  [E] Edit details  [TAB] Switch view  [Ctrl+F] Search logs  Spam [ESC] to Quit ❌",
         Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
     )])];
     let bottom_paragraph = Paragraph::new(bottom_lines)
@@ -933,17 +1715,86 @@ fn render_ui(
         .alignment(tui::layout::Alignment::Center)
         .style(Style::default().bg(Color::Rgb(8, 8, 32))); // Background color
     f.render_widget(bottom_paragraph, chunks[4]);
+
+    // Render the custom upload wizard on top of everything else
+    if let Some(dialog) = category_dialog {
+        let items = dialog.items();
+        let width = if dialog.step == WizardStep::Preview { 100 } else { 50 }; // Preview lines hold full paths
+        let area = centered_rect(width, (items.len() as u16 + 4).min(20), f.size());
+        let selected = dialog.selected_index();
+        let visible_rows = (area.height as usize).saturating_sub(2);
+        let skip = selected.map_or(0, |selected| (selected + 1).saturating_sub(visible_rows));
+        let list_items = items
+            .iter()
+            .enumerate()
+            .skip(skip)
+            .map(|(i, label)| {
+                let style = if Some(i) == selected {
+                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+                } else if selected.is_none() {
+                    Style::default().fg(Color::LightCyan)
+                } else {
+                    Style::default()
+                };
+                ListItem::new(Span::styled(label.clone(), style))
+            })
+            .collect::<Vec<_>>();
+        let dialog_widget = List::new(list_items)
+            .block(Block::default().borders(Borders::ALL).title(dialog.title()))
+            .style(Style::default().bg(Color::Rgb(16, 16, 48))); // Background color
+        f.render_widget(Clear, area);
+        f.render_widget(dialog_widget, area);
+    }
+
+    // Render the upload details edit form
+    if let Some(form) = edit_form {
+        let area = centered_rect(70, form.fields.len() as u16 + 2, f.size());
+        let form_lines = form
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(i, (label, value))| {
+                let is_selected = i == form.selected;
+                Spans::from(vec![
+                    Span::styled(
+                        format!("{:>15}: ", label),
+                        Style::default().fg(if is_selected { Color::Yellow } else { Color::DarkGray }),
+                    ),
+                    Span::styled(
+                        if is_selected { format!("{}▏", value) } else { value.clone() },
+                        if is_selected {
+                            Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
+                        } else {
+                            Style::default().fg(Color::LightCyan)
+                        },
+                    ),
+                ])
+            })
+            .collect::<Vec<_>>();
+        let form_widget = Paragraph::new(form_lines)
+            .block(Block::default().borders(Borders::ALL).title(" ✏️ Edit upload details — [Enter] save [Esc] cancel, empty = auto "))
+            .style(Style::default().bg(Color::Rgb(16, 16, 48))); // Background color
+        f.render_widget(Clear, area);
+        f.render_widget(form_widget, area);
+    }
+}
+
+/// The running seed-tools binary, which uploads and pre-flight checks are started from.
+fn seed_tools_exe() -> Result<PathBuf, SeedToolsError> {
+    std::env::current_exe().map_err(SeedToolsError::io("Failed to get executable path"))
 }
 
 fn activate_upload(
     input_path: &Option<PathBuf>,
     selected_trackers: &Vec<String>,
     custom_category_type: &Option<String>,
+    overrides: &UploadOverrides,
+    check_ready: bool, // Postpone releases still being copied, for queued uploads
     log_output: Arc<Mutex<Vec<String>>>,
-) -> Result<(), Box<dyn std::error::Error>> {
+) -> Result<(), SeedToolsError> {
     if input_path.is_none() {
         log_output.lock().unwrap().push("Error: No input path selected.".to_string());
-        return Err("Error: No input path selected.".into());
+        return Err(SeedToolsError::config("Error: No input path selected."));
     }
 
     if selected_trackers.is_empty() {
@@ -951,12 +1802,11 @@ fn activate_upload(
         return Err("Error: No trackers selected.".into());
     }
 
-    let log_file_path = Path::new("seed-tools.log");
-    File::create(log_file_path)?; // Open in write mode to truncate the file
-    log_output.lock().unwrap().push("Cleared seed-tools.log for fresh logs.".to_string());
-
     let input_path = input_path.as_ref().unwrap();
     let mut args = vec![input_path.display().to_string()];
+    if check_ready {
+        args.push("--check-ready".to_string());
+    }
 
     for tracker in selected_trackers {
         match tracker.as_str() {
@@ -969,16 +1819,21 @@ fn activate_upload(
     if let Some(category) = custom_category_type {
         args.push("--custom-cat-type".to_string());
         args.push(category.clone());
+    } else {
+        // Overrides only apply to standard uploads
+        args.extend(overrides.to_args()?);
     }
 
     // Specify the full path to seed-tools
-    let seed_tools_path = std::env::current_dir()?
-        .join("seed-tools"); // Adjust the relative path as needed
+    let seed_tools_path = seed_tools_exe()?;
     log_output.lock().unwrap().push(format!("Using seed-tools path: {:?}", seed_tools_path));
 
-    // Start the seed-tools process with piped stdout and stderr
+    // Start the seed-tools process with piped stdout and stderr.
+    // SEED_TOOLS_LOG_STDERR makes the child mirror its log records on stderr for the Logs pane.
     let mut child = Command::new(seed_tools_path)
         .args(&args)
+        .envs(crate::profile::child_env())
+        .env("SEED_TOOLS_LOG_STDERR", "1")
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
         .spawn()?;
@@ -990,10 +1845,9 @@ fn activate_upload(
     let log_output_clone = Arc::clone(&log_output);
     let stdout_thread = thread::spawn(move || {
         let reader = BufReader::new(stdout);
-        for line in reader.lines() {
-            if let Ok(line) = line {
-                log_output_clone.lock().unwrap().push(line);
-            }
+        for line in reader.lines().map_while(Result::ok) {
+            log_buffer::push_raw_line(&line, Level::Info);
+            log_output_clone.lock().unwrap().push(line);
         }
     });
 
@@ -1001,39 +1855,237 @@ fn activate_upload(
     let log_output_clone = Arc::clone(&log_output);
     let stderr_thread = thread::spawn(move || {
         let reader = BufReader::new(stderr);
-        for line in reader.lines() {
-            if let Ok(line) = line {
-                log_output_clone.lock().unwrap().push(format!("ERROR: {}", line));
-            }
+        for line in reader.lines().map_while(Result::ok) {
+            // Log records carry their own level tag; anything else on stderr is an error
+            log_buffer::push_raw_line(&line, Level::Error);
+            log_output_clone.lock().unwrap().push(format!("ERROR: {}", line));
         }
     });
 
     // Wait for the process to complete
     let status = child.wait()?;
+
+    // Ensure threads finish processing
+    let _ = stdout_thread.join();
+    let _ = stderr_thread.join();
+
     if status.success() {
         log_output.lock().unwrap().push("Upload completed successfully.".to_string());
+        Ok(())
     } else {
-        log_output.lock().unwrap().push(format!(
-            "Upload failed with exit code: {}",
-            status.code().unwrap_or(-1)
-        ));
+        let error = match status.code() {
+            Some(TRACKER_UNAVAILABLE_EXIT_CODE) => SeedToolsError::unavailable(&selected_trackers.join(", "), "upload postponed"),
+            Some(NOT_READY_EXIT_CODE) => SeedToolsError::Postponed(readiness::not_ready_error(&input_path.display().to_string())),
+            Some(OUTSIDE_WINDOW_EXIT_CODE) => SeedToolsError::Postponed(schedule::outside_window_error(&input_path.display().to_string())),
+            code => SeedToolsError::tool("seed-tools", format!("Upload failed with exit code: {}", code.unwrap_or(-1))),
+        };
+        log_output.lock().unwrap().push(error.to_string());
+        Err(error)
     }
+}
 
-    // Ensure threads finish processing
-    let _ = stdout_thread.join();
-    let _ = stderr_thread.join();
+/// Adds the currently selected input path and trackers to the upload queue.
+fn enqueue_selection(
+    upload_queue: &Arc<Mutex<Vec<QueueItem>>>,
+    input_path: &Option<PathBuf>,
+    selected_trackers: &[String],
+    log_output: &Arc<Mutex<Vec<String>>>,
+) {
+    let Some(path) = input_path else {
+        log_output.lock().unwrap().push("Error: No input path selected.".to_string());
+        return;
+    };
+    if selected_trackers.is_empty() {
+        log_output.lock().unwrap().push("Error: No trackers selected.".to_string());
+        return;
+    }
 
-    Ok(())
+    let mut queue = upload_queue.lock().unwrap();
+    if queue.iter().any(|item| &item.path == path && !matches!(item.status, QueueStatus::Done | QueueStatus::Failed(_))) {
+        log_output.lock().unwrap().push(format!("Already queued: {}", path.display()));
+        return;
+    }
+    queue.push(QueueItem {
+        id: NEXT_QUEUE_ITEM_ID.fetch_add(1, Ordering::Relaxed),
+        path: path.clone(),
+        trackers: selected_trackers.to_vec(),
+        status: QueueStatus::Waiting,
+        parked_count: 0,
+    });
+    log_output.lock().unwrap().push(format!("Queued: {}", path.display()));
 }
 
-fn help_message(on_main_screen: bool, in_tracker_selection: bool) -> String {
-    if in_tracker_selection {
-        "Use UP/DOWN to navigate, F to toggle trackers, ENTER to confirm.".to_string()
-    } else if on_main_screen {
-        "Press F to select input path, C to set category, U to upload.".to_string()
-    } else {
-        "Use UP/DOWN to navigate, F to select, ENTER to confirm.".to_string()
+/// Starts worker threads that drain the waiting items of the upload queue.
+/// Sequential mode uses a single worker; parallel mode uses `max_parallel_uploads` workers.
+/// A batch notification with the totals is sent once every worker has finished.
+fn start_queue(
+    upload_queue: Arc<Mutex<Vec<QueueItem>>>,
+    ui_config: &UiConfig,
+    maintenance: &MaintenanceConfig,
+    notifications: &NotificationsConfig,
+    log_output: Arc<Mutex<Vec<String>>>,
+    tx: mpsc::Sender<()>,
+) {
+    {
+        let queue = upload_queue.lock().unwrap();
+        // Workers keep running while items are parked and pick up newly queued items
+        if queue.iter().any(|item| item.status.is_active() || matches!(item.status, QueueStatus::Parked(_))) {
+            log_output.lock().unwrap().push("Queue is already running.".to_string());
+            return;
+        }
+        if !queue.iter().any(|item| item.status == QueueStatus::Waiting) {
+            log_output.lock().unwrap().push("Queue has no waiting items.".to_string());
+            return;
+        }
+    }
+
+    let workers = match ui_config.queue_mode {
+        QueueMode::Sequential => 1,
+        QueueMode::Parallel => ui_config.max_parallel_uploads.max(1),
+    };
+    log_output.lock().unwrap().push(format!("Starting upload queue with {} worker(s).", workers));
+
+    let handles: Vec<_> = (0..workers)
+        .map(|_| {
+            let upload_queue = Arc::clone(&upload_queue);
+            let log_output = Arc::clone(&log_output);
+            let tx = tx.clone();
+            let maintenance = maintenance.clone();
+            thread::spawn(move || run_queue_worker(upload_queue, maintenance, log_output, tx))
+        })
+        .collect();
+
+    let notifications = notifications.clone();
+    thread::spawn(move || {
+        let mut succeeded = 0;
+        let mut failed = Vec::new();
+        for handle in handles {
+            if let Ok((done, failures)) = handle.join() {
+                succeeded += done;
+                failed.extend(failures);
+            }
+        }
+        notify(&notifications, &Notification::batch(succeeded, &failed));
+    });
+}
+
+fn set_queue_status(upload_queue: &Arc<Mutex<Vec<QueueItem>>>, id: u64, status: QueueStatus) {
+    let mut queue = upload_queue.lock().unwrap();
+    if let Some(item) = queue.iter_mut().find(|item| item.id == id) {
+        item.status = status;
+    }
+}
+
+fn run_queue_worker(
+    upload_queue: Arc<Mutex<Vec<QueueItem>>>,
+    maintenance: MaintenanceConfig,
+    log_output: Arc<Mutex<Vec<String>>>,
+    tx: mpsc::Sender<()>,
+) -> (usize, Vec<String>) {
+    // Uploads that succeeded and paths that failed, for the batch notification
+    let mut succeeded = 0;
+    let mut failed = Vec::new();
+    loop {
+        // Claim the next waiting item under the lock so parallel workers never share an item.
+        // Parked items whose retry time has come are waiting again.
+        let (next, has_parked) = {
+            let mut queue = upload_queue.lock().unwrap();
+            let now = Instant::now();
+            for item in queue.iter_mut() {
+                if matches!(item.status, QueueStatus::Parked(retry_at) if retry_at <= now) {
+                    item.status = QueueStatus::Waiting;
+                }
+            }
+            let next = queue
+                .iter_mut()
+                .find(|item| item.status == QueueStatus::Waiting)
+                .map(|item| {
+                    item.status = QueueStatus::Preflight;
+                    (item.id, item.path.clone(), item.trackers.clone())
+                });
+            let has_parked = queue.iter().any(|item| matches!(item.status, QueueStatus::Parked(_)));
+            (next, has_parked)
+        };
+        let Some((id, path, trackers)) = next else {
+            if has_parked {
+                // Stay around until the parked items can be retried
+                thread::sleep(Duration::from_secs(1));
+                let _ = tx.send(()); // Refresh the retry countdown
+                continue;
+            }
+            break;
+        };
+        let _ = tx.send(());
+
+        let result = run_queue_preflight(&path).and_then(|_| {
+            set_queue_status(&upload_queue, id, QueueStatus::Uploading);
+            let _ = tx.send(());
+            activate_upload(&Some(path.clone()), &trackers, &None, &UploadOverrides::default(), true, Arc::clone(&log_output))
+        });
+
+        match result {
+            Ok(()) => {
+                succeeded += 1;
+                set_queue_status(&upload_queue, id, QueueStatus::Done);
+            }
+            // Tracker down, release not ready or outside the transfer window: try again later
+            Err(e) if e.is_retryable() => {
+                let mut queue = upload_queue.lock().unwrap();
+                if let Some(item) = queue.iter_mut().find(|item| item.id == id) {
+                    item.parked_count += 1;
+                    if !maintenance.may_retry(item.parked_count) {
+                        log_output.lock().unwrap().push(format!(
+                            "Queue item '{}' failed: {} (gave up after {} retries)",
+                            path.display(),
+                            e,
+                            maintenance.max_retries
+                        ));
+                        failed.push(path.display().to_string());
+                        item.status = QueueStatus::Failed(e.to_string());
+                    } else {
+                        let delay = maintenance.backoff(item.parked_count);
+                        item.status = QueueStatus::Parked(Instant::now() + delay);
+                        log_output.lock().unwrap().push(format!(
+                            "Queue item '{}' parked: {}. Retrying in {}s.",
+                            path.display(),
+                            e,
+                            delay.as_secs()
+                        ));
+                    }
+                }
+            }
+            Err(e) => {
+                log_output.lock().unwrap().push(format!("Queue item '{}' failed: {}", path.display(), e));
+                failed.push(path.display().to_string());
+                set_queue_status(&upload_queue, id, QueueStatus::Failed(e.to_string()));
+            }
+        }
+        let _ = tx.send(());
+    }
+    (succeeded, failed)
+}
+
+/// Runs `seed-tools --pre` for a queued item and fails it when the tracker already has it.
+fn run_queue_preflight(path: &Path) -> Result<(), SeedToolsError> {
+    let output = Command::new(seed_tools_exe()?)
+        .envs(crate::profile::child_env())
+        .arg("--pre")
+        .arg(path.display().to_string())
+        .output()
+        .map_err(|e| SeedToolsError::tool_spawn("seed-tools", e))?;
+
+    if !output.status.success() {
+        return Err(SeedToolsError::tool(
+            "seed-tools",
+            format!("Pre-flight Check failed with exit code: {}", output.status.code().unwrap_or(-1)),
+        ));
     }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if stdout.lines().any(|line| line.starts_with("Dupe Check:") && line.contains("FAIL")) {
+        return Err(SeedToolsError::tracker("Seedpool", None, "duplicate found on tracker"));
+    }
+    Ok(())
 }
 
 fn get_files_in_dir(dir: &Path) -> Vec<String> {
@@ -1080,176 +2132,6 @@ fn get_files_in_dir(dir: &Path) -> Vec<String> {
     entries
 }
 
-fn tracker_select(
-    terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
-    tracker_options: &[&str],
-    selected_tracker_index: &mut usize,
-    tracker_scroll_offset: &mut usize,
-    selected_trackers: &mut Vec<String>,
-) -> Result<(), Box<dyn std::error::Error>> {
-    loop {
-        let size = terminal.size()?;
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([Constraint::Min(1), Constraint::Length(3)].as_ref())
-            .split(size);
-
-        let content_area_height = chunks[0].height.saturating_sub(1) as usize;
-
-        // Ensure scrolling logic
-        if *tracker_scroll_offset > tracker_options.len().saturating_sub(content_area_height) {
-            *tracker_scroll_offset = tracker_options.len().saturating_sub(content_area_height);
-        }
-
-        let visible_trackers = &tracker_options[*tracker_scroll_offset
-            ..(*tracker_scroll_offset + content_area_height).min(tracker_options.len())];
-
-        // Draw the tracker selection UI
-        terminal.draw(|f| {
-            let tracker_list = List::new(
-                visible_trackers
-                    .iter()
-                    .enumerate()
-                    .map(|(i, tracker)| {
-                        let style = if i + *tracker_scroll_offset == *selected_tracker_index {
-                            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
-                        } else if selected_trackers.contains(&tracker.to_string()) {
-                            Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)
-                        } else {
-                            Style::default()
-                        };
-                        ListItem::new(Span::styled(*tracker, style))
-                    })
-                    .collect::<Vec<_>>(),
-            )
-            .block(Block::default().borders(Borders::ALL).title("Select Tracker"));
-
-            f.render_widget(tracker_list, chunks[0]);
-
-            // Render help message
-            let help_message = "Use UP/DOWN to navigate, F to toggle trackers, ENTER to confirm.";
-            let help_paragraph = Paragraph::new(help_message)
-                .block(Block::default().borders(Borders::ALL).title("Help"));
-            f.render_widget(help_paragraph, chunks[1]);
-        })?;
-
-        // Handle keypress events
-        if let Event::Key(key) = event::read()? {
-            match key.code {
-                KeyCode::Up => {
-                    if *selected_tracker_index > 0 {
-                        *selected_tracker_index -= 1;
-                        if *selected_tracker_index < *tracker_scroll_offset {
-                            *tracker_scroll_offset -= 1;
-                        }
-                    }
-                }
-                KeyCode::Down => {
-                    if *selected_tracker_index < tracker_options.len() - 1 {
-                        *selected_tracker_index += 1;
-                        if *selected_tracker_index >= *tracker_scroll_offset + content_area_height {
-                            *tracker_scroll_offset += 1;
-                        }
-                    }
-                }
-                KeyCode::Char('f') | KeyCode::Char('F') => {
-                    let tracker = tracker_options[*selected_tracker_index].to_string();
-                    if tracker == "✔️ Select All" {
-                        if selected_trackers.len() == tracker_options.len() - 1 {
-                            selected_trackers.clear();
-                        } else {
-                            *selected_trackers = tracker_options[1..]
-                                .iter()
-                                .map(|&s| s.to_string())
-                                .collect();
-                        }
-                    } else if selected_trackers.contains(&tracker) {
-                        selected_trackers.retain(|t| t != &tracker);
-                    } else {
-                        selected_trackers.push(tracker);
-                    }
-                }
-                KeyCode::Enter => {
-                    // Confirm tracker selection and exit
-                    return Ok(()); // Exit the tracker selection loop
-                }
-                KeyCode::Esc => {
-                    // Exit tracker selection without changes
-                    return Ok(()); // Exit the tracker selection loop
-                }
-                _ => {}
-            }
-        }
-    }
-}
-
-fn read_log_file(log_file_path: &Path, log_output: Arc<Mutex<Vec<String>>>) {
-    if let Ok(file) = File::open(log_file_path) {
-        let reader = BufReader::new(file);
-        let lines: Vec<String> = reader.lines().filter_map(|line| line.ok()).collect();
-
-        let mut log_output_guard = log_output.lock().unwrap();
-        *log_output_guard = lines;
-    }
-}
-
-fn start_log_refresh(
-    log_file_path: PathBuf,
-    log_output: Arc<Mutex<Vec<String>>>,
-    tx: mpsc::Sender<()>, // Notify the main loop to redraw the UI
-    log_scroll_offset: Arc<Mutex<usize>>, // Shared scroll offset for logs
-) {
-    thread::spawn(move || {
-        let mut file = match File::open(&log_file_path) {
-            Ok(file) => file,
-            Err(_) => return, // Exit if the file cannot be opened
-        };
-
-        let _ = file.seek(SeekFrom::End(0)); // Start tailing from the end of the file
-        let mut reader = BufReader::new(file);
-
-        loop {
-            let mut buffer = String::new();
-            let mut new_lines = Vec::new();
-
-            // Read multiple lines in a batch
-            for _ in 0..10 {
-                match reader.read_line(&mut buffer) {
-                    Ok(0) => break, // No new data
-                    Ok(_) => {
-                        // Filter only `[INFO]` messages
-                        if buffer.contains("[INFO]") {
-                            new_lines.push(buffer.trim_end().to_string());
-                        }
-                        buffer.clear();
-                    }
-                    Err(_) => break, // Exit on error
-                }
-            }
-
-            if !new_lines.is_empty() {
-                // Add the new lines to the log output
-                let mut log_output_guard = log_output.lock().unwrap();
-                log_output_guard.extend(new_lines);
-
-                // Automatically scroll to the bottom if the user hasn't manually scrolled
-                let mut log_scroll_offset_guard = log_scroll_offset.lock().unwrap();
-                let total_lines = log_output_guard.len();
-                let visible_lines = 15; // Adjust this to match the height of your log view
-                if *log_scroll_offset_guard >= total_lines.saturating_sub(visible_lines) {
-                    *log_scroll_offset_guard = total_lines.saturating_sub(visible_lines);
-                }
-
-                // Notify the main loop to redraw the UI
-                let _ = tx.send(());
-            }
-
-            // Sleep briefly to avoid excessive CPU usage
-            thread::sleep(Duration::from_millis(50));
-        }
-    });
-}
-
 fn parse_preflight_log(preflight_log_path: &Path) -> (Vec<String>, bool) {
     let mut log_data = vec![
         "Title: N/A".to_string(),
@@ -1264,6 +2146,7 @@ fn parse_preflight_log(preflight_log_path: &Path) -> (Vec<String>, bool) {
         "Album Cover: N/A".to_string(), // Default value for Album Cover
         "Excluded Files: N/A".to_string(), // Default value for Excluded Files
         "Audio Languages: N/A".to_string(),
+        "Subtitles: N/A".to_string(),
     ];
 
     let mut is_pending = true; // Assume pending until we find meaningful data
@@ -1271,7 +2154,7 @@ fn parse_preflight_log(preflight_log_path: &Path) -> (Vec<String>, bool) {
 
     if let Ok(file) = File::open(preflight_log_path) {
         let reader = BufReader::new(file);
-        for line in reader.lines().filter_map(|line| line.ok()) {
+        for line in reader.lines().map_while(Result::ok) {
             is_pending = false; // Mark as not pending if we find any data
 
             if line.starts_with("Log Type: Music") {
@@ -1325,6 +2208,10 @@ fn parse_preflight_log(preflight_log_path: &Path) -> (Vec<String>, bool) {
                     .trim_end_matches(']')
                     .replace('"', "");
                 log_data[11] = format!("Audio Languages: {}", audio_cleaned);
+            } else if line.starts_with("Subtitles:") {
+                let subtitles = line.replace("Subtitles: ", "");
+                let subtitles_cleaned = subtitles.trim_start_matches('[').trim_end_matches(']').replace('"', "");
+                log_data[12] = format!("Subtitles: {}", if subtitles_cleaned.is_empty() { "None" } else { subtitles_cleaned.as_str() });
             }
         }
     }
@@ -1337,23 +2224,31 @@ fn parse_preflight_log(preflight_log_path: &Path) -> (Vec<String>, bool) {
     (log_data, is_pending)
 }
 
-fn start_log_tail(terminal_emulator: Arc<TerminalEmulator>, log_file_path: &str) {
-    let log_file_path = log_file_path.to_string(); // Clone the path into a String
-    thread::spawn(move || {
-        let mut child = Command::new("tail")
-            .arg("-f")
-            .arg(log_file_path) // Use the cloned String
-            .stdout(Stdio::piped())
-            .spawn()
-            .expect("Failed to start tail process");
-
-        if let Some(stdout) = child.stdout.take() {
-            let reader = BufReader::new(stdout);
-            for line in reader.lines() {
-                if let Ok(line) = line {
-                    terminal_emulator.feed(&line);
-                }
-            }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(path: &str, status: QueueStatus) -> QueueItem {
+        QueueItem {
+            id: NEXT_QUEUE_ITEM_ID.fetch_add(1, Ordering::Relaxed),
+            path: PathBuf::from(path),
+            trackers: vec!["🐳 seedpool [SP]".to_string()],
+            status,
+            parked_count: 0,
         }
-    });
-}
\ No newline at end of file
+    }
+
+    #[test]
+    fn requeued_path_updates_only_its_own_item() {
+        let failed = item("/data/Movie", QueueStatus::Failed("dupe".to_string()));
+        let requeued = item("/data/Movie", QueueStatus::Preflight);
+        let requeued_id = requeued.id;
+        let queue = Arc::new(Mutex::new(vec![failed, requeued]));
+
+        set_queue_status(&queue, requeued_id, QueueStatus::Uploading);
+
+        let queue = queue.lock().unwrap();
+        assert!(queue[0].status == QueueStatus::Failed("dupe".to_string()));
+        assert!(queue[1].status == QueueStatus::Uploading);
+    }
+}
@@ -1,2253 +1,3330 @@
-use reqwest::blocking::{multipart::Form, Client};
-use reqwest::blocking::ClientBuilder;
-use reqwest::cookie::Jar;
-use std::path::{Path, PathBuf};
-use std::sync::Arc;
-use regex::Regex;
-use epub::doc::EpubDoc;
-use log::{info, error, warn};
-use std::process::Command;
-use std::collections::HashSet;
-use serde_json::{Value, json};
-use rand::Rng;
-use std::os::unix::fs::PermissionsExt;
-use std::fs::{self, Permissions};
-use zip::ZipArchive;
-use std::fs::File;
-use std::io::Write;
-use base64::engine::general_purpose::STANDARD;
-use base64::Engine;
-use walkdir::WalkDir;
-use rand::seq::IteratorRandom;
-use crate::types::{PathsConfig, SeedpoolConfig, Config, QbittorrentConfig, VideoSettings, DelugeConfig};
-
-pub fn generate_release_name(base_name: &str) -> String {
-    let mut release_name = base_name.to_string();
-
-    // Remove file extensions
-    release_name = Regex::new(r"\.(epub|mobi|pdf|txt|mkv|mp4|m4b|avi|mov|flv|wmv|ts)$")
-        .unwrap()
-        .replace(&release_name, "")
-        .to_string();
-
-    // Replace non-alphanumeric characters with dots
-    release_name = Regex::new(r"[^A-Za-z0-9+\-]")
-        .unwrap()
-        .replace_all(&release_name, ".")
-        .to_string();
-
-    // Replace multiple dots with a single dot
-    release_name = Regex::new(r"\.\.+")
-        .unwrap()
-        .replace_all(&release_name, ".")
-        .to_string();
-
-    // Replace mixed dot-dash patterns
-    release_name = Regex::new(r"-\.+|\.-+")
-        .unwrap()
-        .replace_all(&release_name, "-")
-        .to_string();
-
-    // Remove trailing dots
-    release_name = Regex::new(r"\.$")
-        .unwrap()
-        .replace(&release_name, "")
-        .to_string();
-
-    // Remove leading dots
-    release_name.trim_start_matches('.').to_string()
-}
-
-pub fn find_video_files<T>(
-    input_path: &str,
-    _paths: &PathsConfig,
-    settings: &T,
-) -> Result<(Vec<String>, Option<String>), String>
-where
-    T: VideoSettings,
-{
-    let supported_extensions = ["mkv", "mp4", "ts", "avi", "mov", "flv", "wmv"];
-    let path = Path::new(input_path);
-
-    let mut video_files = Vec::new();
-    let mut nfo_file: Option<String> = None;
-
-    let exclusions_enabled = settings.stripshit_from_videos();
-    info!("Exclusions enabled: {}", exclusions_enabled);
-
-    fn process_path(
-        file_path: &Path,
-        video_files: &mut Vec<String>,
-        nfo_file: &mut Option<String>,
-        supported_extensions: &[&str],
-        exclusions_enabled: bool,
-    ) -> Result<(), String> {
-        if file_path.is_dir() {
-            for entry in fs::read_dir(file_path).map_err(|e| format!("Failed to read directory: {}", e))? {
-                let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
-                let entry_path = entry.path();
-                process_path(&entry_path, video_files, nfo_file, supported_extensions, exclusions_enabled)?;
-            }
-        } else {
-            log::debug!("Processing file: {}", file_path.display());
-            process_file(file_path, video_files, nfo_file, supported_extensions, exclusions_enabled)?;
-        }
-        Ok(())
-    }
-
-    process_path(path, &mut video_files, &mut nfo_file, &supported_extensions, exclusions_enabled)?;
-
-    if video_files.is_empty() {
-        error!("No valid video files detected after exclusions.");
-        return Err("No valid video files detected.".to_string());
-    }
-
-    info!("Final NFO file: {:?}", nfo_file);
-
-    Ok((video_files, nfo_file))
-}
-
-pub fn create_torrent(
-    input_path: &str,
-    torrent_dir: &str,
-    announce_url: &str,
-    mkbrr_path: &str,
-    stripshit_from_videos: bool,
-) -> Result<String, String> {
-    fs::create_dir_all(torrent_dir)
-        .map_err(|e| format!("Failed to create torrent directory '{}': {}", torrent_dir, e))?;
-
-    let base_name = Path::new(input_path)
-        .file_name()
-        .unwrap_or_default()
-        .to_string_lossy()
-        .to_string();
-
-    let release_name = generate_release_name(&base_name);
-    let torrent_file = format!("{}/{}.torrent", torrent_dir, release_name);
-
-    info!("Creating torrent for input path: {}", input_path);
-    info!("Torrent File: {}", torrent_file);
-
-    // Build the mkbrr command
-    let mut command = Command::new(mkbrr_path);
-    command.args(&[
-        "create",
-        "-t", announce_url,
-        "-o", &torrent_file,
-        "--source", "seedpool.org",
-        input_path,
-    ]);
-
-    // Add the --exclude flag to exclude unwanted terms and non-video files
-    if stripshit_from_videos {
-        command.args(&[
-            "--exclude",
-            "[X]*,*sample*,*proof*,*screens*,*screenshots*,*.txt,*.jpg,*.jpeg,*.png,*.nfo,*.srr,*.doc,*.sfv,*.r??",
-        ]);
-    }
-
-    // Execute the mkbrr command
-    let output = command.output().map_err(|e| format!("Failed to run mkbrr: {}", e))?;
-
-    if !output.stdout.is_empty() {
-        info!("mkbrr stdout:\n{}", String::from_utf8_lossy(&output.stdout));
-    }
-    if !output.stderr.is_empty() {
-        error!("mkbrr stderr:\n{}", String::from_utf8_lossy(&output.stderr));
-    }
-
-    if !output.status.success() {
-        return Err(format!(
-            "mkbrr failed to create torrent for input path: {}. Exit code: {}",
-            input_path,
-            output.status.code().unwrap_or(-1)
-        ));
-    }
-
-    info!("Created torrent: {}", torrent_file);
-    Ok(torrent_file)
-}
-
-pub fn generate_mediainfo(video_file: &str, mediainfo_path: &str) -> Result<String, String> {
-    let output = Command::new(mediainfo_path)
-        .args(&["--Output=TEXT", video_file])
-        .output()
-        .map_err(|e| format!("Failed to run mediainfo: {}", e))?;
-
-    if !output.status.success() {
-        return Err(format!(
-            "Mediainfo command failed with status: {}",
-            output.status
-        ));
-    }
-
-    let mut result = String::from_utf8(output.stdout)
-        .map_err(|e| format!("Failed to parse mediainfo output: {}", e))?;
-
-    // Sanitize the "Complete name" field
-    if let Some(start) = result.find("Complete name") {
-        if let Some(end) = result[start..].find('\n') {
-            let full_line = &result[start..start + end];
-            if let Some(separator) = full_line.find(':') {
-                let sanitized_line = format!(
-                    "Complete name                            : {}",
-                    Path::new(video_file).file_name().unwrap_or_default().to_string_lossy()
-                );
-                result = result.replace(full_line, &sanitized_line);
-            }
-        }
-    }
-
-    Ok(result)
-}
-
-pub fn add_torrent_to_all_qbittorrent_instances(
-    torrent_files: &[String],
-    qbittorrent_configs: &[QbittorrentConfig],
-    deluge_config: &DelugeConfig,
-    input_path: &str,
-    paths_config: &PathsConfig,
-) -> Result<(), String> {
-    info!("Adding torrents to all qBittorrent and Deluge instances.");
-
-    // Add torrents to all qBittorrent instances
-    for config in qbittorrent_configs {
-        for torrent_file in torrent_files {
-            if let Some(executable) = &config.executable {
-                // Call add_torrent_to_qbittorrent for each instance
-                if let Err(e) = add_torrent_to_qbittorrent(
-                    torrent_file,
-                    config,
-                    input_path,
-                    Path::new(input_path).is_dir(),
-                    paths_config,
-                ) {
-                    error!(
-                        "Error adding torrent '{}' to qBittorrent instance '{}': {}",
-                        torrent_file, config.webui_url, e
-                    );
-                } else {
-                    info!(
-                        "Successfully added torrent '{}' to qBittorrent instance '{}'.",
-                        torrent_file, config.webui_url
-                    );
-                }
-            } else {
-                error!(
-                    "No executable specified for qBittorrent instance '{}'. Skipping.",
-                    config.webui_url
-                );
-            }
-        }
-    }
-
-    // Add torrents to Deluge
-    for torrent_file in torrent_files {
-        if let Err(e) = add_torrent_to_deluge(
-            torrent_file,
-            deluge_config,
-            input_path,
-            Path::new(input_path).is_dir(),
-            paths_config,
-        ) {
-            error!("Error adding torrent '{}' to Deluge: {}", torrent_file, e);
-        } else {
-            info!("Successfully added torrent '{}' to Deluge.", torrent_file);
-        }
-    }
-
-    Ok(())
-}
-
-pub fn process_file(
-    file_path: &Path,
-    video_files: &mut Vec<String>,
-    nfo_file: &mut Option<String>,
-    supported_extensions: &[&str],
-    exclusions_enabled: bool,
-) -> Result<(), String> {
-    let file_name = file_path.file_name().unwrap_or_default().to_string_lossy().to_string();
-
-    if let Some(ext) = file_path.extension() {
-        let ext = ext.to_string_lossy().to_lowercase();
-        if supported_extensions.contains(&ext.as_str()) {
-            video_files.push(file_path.to_string_lossy().to_string());
-        } else if ext == "nfo" && nfo_file.is_none() {
-            *nfo_file = Some(file_path.to_string_lossy().to_string());
-        }
-    } else if exclusions_enabled && contains_excluded_keywords(&file_name) {
-        info!("Excluding file due to keywords: {}", file_name);
-    }
-
-    Ok(())
-}
-
-pub fn contains_excluded_keywords(name: &str) -> bool {
-    let keywords = ["sample", "screens", "screenshots", "proof"];
-    let lowercase_name = name.to_lowercase();
-    let result = keywords.iter().any(|keyword| lowercase_name.contains(keyword));
-    info!("Checking if '{}' contains excluded keywords: {}", name, result);
-    result
-}
-
-pub fn generate_sample(
-    video_file: &str,
-    screenshots_dir: &str,
-    remote_path: &str,
-    image_path: &str,
-    ffmpeg_path: &str,
-    input_name: &str,
-) -> Result<String, String> {
-    let sanitized_input_name = generate_release_name(input_name);
-    let sample_file = format!("{}/{}.sample.mkv", screenshots_dir, sanitized_input_name);
-
-    // Generate the sample file
-    let ffmpeg_command = format!(
-        "{} -i \"{}\" -ss 00:05:00 -t 00:00:20 -map 0 -c copy \"{}\"",
-        ffmpeg_path, video_file, sample_file
-    );
-    let output = Command::new("sh")
-        .arg("-c")
-        .arg(ffmpeg_command)
-        .output()
-        .map_err(|e| format!("Failed to execute ffmpeg: {}", e))?;
-
-    if !output.status.success() {
-        return Err(format!(
-            "Failed to generate sample file. ffmpeg output: {}",
-            String::from_utf8_lossy(&output.stderr)
-        ));
-    }
-
-    // Set permissions to 777
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        fs::set_permissions(&sample_file, fs::Permissions::from_mode(0o777))
-            .map_err(|e| format!("Failed to set permissions for sample file '{}': {}", sample_file, e))?;
-    }
-
-    // Upload the sample file
-    upload_to_cdn(&sample_file, remote_path)?;
-
-    // Return the public-facing URL for the sample
-    Ok(format!("{}/{}.sample.mkv", image_path, sanitized_input_name))
-}
-
-pub fn generate_description(
-    screenshots: &[String],
-    _thumbnails: &[String],
-    sample_url: &str,
-    _datestamp: &str,
-    custom_description: Option<&str>,
-    youtube_trailer_url: Option<&str>,
-    _base_url: &str,
-    release_name: &str,
-) -> String {
-    let mut description = String::new();
-
-    // Add screenshots in a 2x2 table pattern
-    if !screenshots.is_empty() {
-        description.push_str("[center][tr]\n");
-
-        for (i, screenshot) in screenshots.iter().enumerate() {
-            description.push_str(&format!(
-                "        [td][url={}][img width=720]{}[/img][/url][/td]\n",
-                screenshot, screenshot
-            ));
-
-            // Add a new row every 2 images
-            if (i + 1) % 2 == 0 {
-                description.push_str("    [/tr]\n    [tr]\n");
-            }
-        }
-
-        // Close the last row properly
-        if screenshots.len() % 2 != 0 {
-            description.push_str("    [/center][/tr]\n");
-        }
-    }
-
-    // Add a blank line after screenshots
-    description.push_str("\n");
-
-    // Add sample link if available
-    if !sample_url.is_empty() {
-        description.push_str(&format!(
-            "[b][spoiler=Sample: {}]{}[/spoiler][/b]\n\n",
-            Path::new(sample_url).file_name().unwrap_or_default().to_string_lossy(),
-            sample_url
-        ));
-    }
-
-    // Add YouTube trailer link if available
-    if let Some(trailer_url) = youtube_trailer_url {
-        description.push_str(&format!(
-            "[center][b][url={}][Trailer on YouTube][/url][/b][/center]\n\n",
-            trailer_url
-        ));
-    }
-
-    // Add custom description (not centered)
-    if let Some(custom_desc) = custom_description {
-        description.push_str(custom_desc);
-        description.push_str("\n\n");
-    }
-
-    // Append the default non-video description
-    description.push_str(&default_non_video_description());
-
-    description
-}
-
-pub fn fetch_tmdb_id(title: &str, year: Option<String>, tmdb_api_key: &str, release_type: &str) -> Result<u32, String> {
-    let sanitized_title = if release_type == "tv" {
-        // Extract everything before the SXX* pattern
-        let season_regex = Regex::new(r"(?i)(S\d{2}.*)").unwrap();
-        let cleaned_title = season_regex.replace(title, "").trim().to_string();
-
-        // Remove the year if present
-        let year_regex = Regex::new(r"\b(19|20)\d{2}\b").unwrap();
-        year_regex.replace(&cleaned_title, "").trim().to_string()
-    } else {
-        // For movies, extract everything before the year
-        let year_regex = Regex::new(r"\b(19|20)\d{2}\b").unwrap();
-        year_regex.replace(title, "").trim().to_string()
-    };
-
-    let encoded_title = urlencoding::encode(&sanitized_title);
-
-    let url = if release_type == "tv" {
-        format!(
-            "https://api.themoviedb.org/3/search/tv?query={}&first_air_date_year={}&api_key={}",
-            encoded_title,
-            year.unwrap_or_default(),
-            tmdb_api_key
-        )
-    } else {
-        format!(
-            "https://api.themoviedb.org/3/search/movie?query={}&year={}&api_key={}",
-            encoded_title,
-            year.unwrap_or_default(),
-            tmdb_api_key
-        )
-    };
-
-    info!("TMDB API URL: {}", url);
-
-    let client = Client::new();
-    let response = client
-        .get(&url)
-        .send()
-        .map_err(|e| format!("Failed to query TMDB for '{}': {}", title, e))?;
-
-    if !response.status().is_success() {
-        return Err(format!(
-            "TMDB API request failed with status: {}",
-            response.status()
-        ));
-    }
-
-    let json: Value = response
-        .json()
-        .map_err(|e| format!("Failed to parse TMDB response for '{}': {}", title, e))?;
-
-    let tmdb_id = json["results"]
-        .as_array()
-        .and_then(|results| results.get(0))
-        .and_then(|result| result["id"].as_u64())
-        .unwrap_or(0) as u32;
-
-    if tmdb_id == 0 {
-        info!("No TMDB ID found for '{}'.", title);
-    }
-
-    Ok(tmdb_id)
-}
-
-pub fn fetch_youtube_trailer(title: &str, year: Option<&str>, youtube_api_key: &str) -> Result<String, String> {
-    let client = Client::new();
-
-    // Construct the search query
-    let query = if let Some(year) = year {
-        format!("{} {} trailer", title, year)
-    } else {
-        format!("{} trailer", title)
-    };
-
-    // Construct the YouTube Data API URL
-    let url = format!(
-        "https://www.googleapis.com/youtube/v3/search?part=snippet&q={}&type=video&key={}&maxResults=1",
-        urlencoding::encode(&query),
-        youtube_api_key
-    );
-
-    // Send the API request
-    let response = client
-        .get(&url)
-        .send()
-        .map_err(|e| format!("Failed to send request to YouTube API: {}", e))?;
-
-    if !response.status().is_success() {
-        return Err(format!(
-            "YouTube API request failed with status: {}",
-            response.status()
-        ));
-    }
-
-    // Parse the JSON response
-    let response_body = response.text().map_err(|e| format!("Failed to read YouTube API response: {}", e))?;
-    let json: Value = serde_json::from_str(&response_body)
-        .map_err(|e| format!("Failed to parse YouTube API response: {}", e))?;
-
-    // Extract the video ID of the first result
-    if let Some(video_id) = json["items"]
-        .as_array()
-        .and_then(|items| items.get(0))
-        .and_then(|item| item["id"]["videoId"].as_str())
-    {
-        let video_url = format!("https://www.youtube.com/watch?v={}", video_id);
-        Ok(video_url)
-    } else {
-        Err("No trailer found on YouTube.".to_string())
-    }
-}
-
-pub fn fetch_external_ids(tmdb_id: u32, release_type: &str, tmdb_api_key: &str) -> Result<(Option<String>, Option<u32>), String> {
-    if tmdb_id == 0 {
-        return Ok((None, None));
-    }
-
-    let tmdb_type = if release_type == "boxset" { "tv" } else { release_type };
-    let url = format!(
-        "https://api.themoviedb.org/3/{}/{}/external_ids?api_key={}",
-        tmdb_type, tmdb_id, tmdb_api_key
-    );
-
-    log::info!("TMDB External IDs API URL: {}", url);
-
-    let client = reqwest::blocking::Client::new();
-    let response = client.get(&url).send().map_err(|e| format!("Failed to fetch external IDs: {}", e))?;
-
-    if !response.status().is_success() {
-        return Err(format!("Failed to fetch external IDs: HTTP {}", response.status()));
-    }
-
-    let json: serde_json::Value = response.json().map_err(|e| format!("Failed to parse external IDs response: {}", e))?;
-    let imdb_id = json["imdb_id"].as_str().map(|s| s.trim_start_matches("tt").to_string());
-    let tvdb_id = json["tvdb_id"].as_u64().map(|id| id as u32);
-
-    log::info!("Fetched IMDb ID: {:?}", imdb_id);
-    log::info!("Fetched TVDB ID: {:?}", tvdb_id);
-
-    Ok((imdb_id, tvdb_id))
-}
-
-pub fn generate_screenshots(
-    video_file: &str,
-    output_dir: &str,
-    ffmpeg_path: &str,
-    ffprobe_path: &str,
-    remote_path: &str,
-    image_path: &str,
-    input_name: &str,
-) -> Result<(Vec<String>, Vec<String>), String> {
-    let mut screenshots_list = Vec::new();
-    let mut thumbnails_list = Vec::new();
-
-    // Ensure the output directory exists
-    fs::create_dir_all(output_dir).map_err(|e| format!("Failed to create output directory: {}", e))?;
-
-    let sanitized_input_name = generate_release_name(input_name); // Sanitize the input name
-    let duration = get_video_duration(video_file, ffprobe_path)?;
-    let timestamps = generate_random_timestamps(duration, 4);
-
-    for (i, shot_time) in timestamps.iter().enumerate() {
-        // Generate sanitized filenames for screenshots and thumbnails
-        let screenshot_file = format!("{}/{}_{}.jpg", output_dir, sanitized_input_name, i + 1);
-        let thumbnail_file = format!("{}/{}_{}_thumb.jpg", output_dir, sanitized_input_name, i + 1);
-
-        // Generate screenshot
-        generate_screenshot(video_file, ffmpeg_path, shot_time, &screenshot_file)?;
-        generate_thumbnail(ffmpeg_path, &screenshot_file, &thumbnail_file)?;
-
-        // Set permissions to 777 for the screenshot and thumbnail locally
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            fs::set_permissions(&screenshot_file, fs::Permissions::from_mode(0o777))
-                .map_err(|e| format!("Failed to set permissions for {}: {}", screenshot_file, e))?;
-            fs::set_permissions(&thumbnail_file, fs::Permissions::from_mode(0o777))
-                .map_err(|e| format!("Failed to set permissions for {}: {}", thumbnail_file, e))?;
-        }
-
-        // Upload files to the CDN
-        upload_to_cdn(&screenshot_file, remote_path)?;
-        upload_to_cdn(&thumbnail_file, remote_path)?;
-
-        // Add public-facing URLs to the lists
-        screenshots_list.push(format!("{}/{}", image_path, Path::new(&screenshot_file).file_name().unwrap().to_string_lossy()));
-        thumbnails_list.push(format!("{}/{}", image_path, Path::new(&thumbnail_file).file_name().unwrap().to_string_lossy()));
-    }
-
-    Ok((screenshots_list, thumbnails_list))
-}
-
-fn get_video_duration(video_file: &str, ffprobe_path: &str) -> Result<f64, String> {
-    let ffprobe_output = Command::new(ffprobe_path)
-        .args(&[
-            "-v", "error",
-            "-show_entries", "format=duration",
-            "-of", "default=noprint_wrappers=1:nokey=1",
-            video_file,
-        ])
-        .output()
-        .map_err(|e| format!("Failed to run ffprobe: {}", e))?;
-
-    if !ffprobe_output.status.success() {
-        return Err(format!(
-            "ffprobe failed with status: {}. Stderr: {}",
-            ffprobe_output.status,
-            String::from_utf8_lossy(&ffprobe_output.stderr)
-        ));
-    }
-
-    let duration_str = String::from_utf8_lossy(&ffprobe_output.stdout).trim().to_string();
-    duration_str.parse::<f64>().map_err(|_| "Failed to parse video duration.".to_string())
-}
-
-fn generate_random_timestamps(duration: f64, count: usize) -> Vec<u32> {
-    let start_time = (duration * 0.15) as u32;
-    let end_time = (duration * 0.85) as u32;
-
-    let mut rng = rand::thread_rng();
-    let mut timestamps: Vec<u32> = (0..count).map(|_| rng.gen_range(start_time..end_time)).collect();
-    timestamps.sort();
-    timestamps
-}
-
-fn generate_screenshot(video_file: &str, ffmpeg_path: &str, timestamp: &u32, output_file: &str) -> Result<(), String> {
-    Command::new(ffmpeg_path)
-        .args(&[
-            "-y", "-loglevel", "error", "-ss", &timestamp.to_string(),
-            "-i", video_file, "-vframes", "1", "-qscale:v", "2", output_file,
-        ])
-        .status()
-        .map_err(|e| format!("Failed to run ffmpeg for screenshot: {}", e))?;
-    Ok(())
-}
-
-fn generate_thumbnail(ffmpeg_path: &str, input_file: &str, output_file: &str) -> Result<(), String> {
-    Command::new(ffmpeg_path)
-        .args(&[
-            "-y", "-loglevel", "error", "-i", input_file,
-            "-vf", "scale=720:-1", output_file,
-        ])
-        .status()
-        .map_err(|e| format!("Failed to run ffmpeg for thumbnail: {}", e))?;
-    Ok(())
-}
-
-pub fn upload_to_cdn(file_path: &str, remote_path: &str) -> Result<(), String> {
-    use std::process::Command;
-
-    info!("Uploading file to CDN: {}", file_path);
-
-    let status = Command::new("scp")
-        .arg(file_path)
-        .arg(remote_path)
-        .status()
-        .map_err(|e| format!("Failed to execute scp: {}", e))?;
-
-    if !status.success() {
-        return Err(format!("Failed to upload file to CDN: {}", file_path));
-    }
-
-    Ok(())
-}
-
-pub fn default_non_video_description() -> String {
-    format!(
-        "[b][size=12][color=#757575]Created with mkbrr, ffmpeg, and mediainfo. Posted to this fine tracker with seed-tools.[/color][/size][/b]
-        
-        [url=https://github.com/seed-pool/seed-tools][img]https://cdn.seedpool.org/sp.png[/img][/url]  \
-        [url=https://github.com/autobrr/mkbrr][img]https://cdn.seedpool.org/mkbrr.png[/img][/url]  \
-        [url=https://www.rust-lang.org][img]https://cdn.seedpool.org/rust.png[/img][/url]"
-    )
-}
-
-pub fn extract_rar_archives(folder_path: &str) -> Result<Option<String>, String> {
-    use std::fs;
-    use std::path::Path;
-    use log::info;
-
-    info!("Checking for RAR archives in folder: {}", folder_path);
-
-    let path = Path::new(folder_path);
-    if !path.is_dir() {
-        return Err(format!("Provided path is not a directory: {}", folder_path));
-    }
-
-    // Collect all .rar, .r00, and .r01 files
-    let mut rar_files = Vec::new();
-    let mut r00_files = Vec::new();
-    let mut r01_files = Vec::new();
-
-    for entry in fs::read_dir(path).map_err(|e| format!("Failed to read directory: {}", e))? {
-        let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
-        let file_path = entry.path();
-        if let Some(ext) = file_path.extension().and_then(|e| e.to_str()) {
-            if ext.eq_ignore_ascii_case("rar") {
-                rar_files.push(file_path.clone());
-            } else if ext.eq_ignore_ascii_case("r00") {
-                r00_files.push(file_path.clone());
-            } else if ext.eq_ignore_ascii_case("r01") {
-                r01_files.push(file_path.clone());
-            }
-        }
-    }
-
-    // Prefer .rar, then .r00, then .r01
-    let to_extract = if !rar_files.is_empty() {
-        rar_files
-    } else if !r00_files.is_empty() {
-        r00_files
-    } else {
-        r01_files
-    };
-
-    if to_extract.is_empty() {
-        info!("No RAR, R00, or R01 archives found in folder: {}", folder_path);
-        return Ok(None); // No extraction occurred
-    }
-
-    info!("Found RAR/R00/R01 archives: {:?}", to_extract);
-
-    for archive_file in to_extract {
-        info!("Extracting archive: {}", archive_file.display());
-
-        let output = std::process::Command::new("unrar")
-            .args(&["x", "-o+", archive_file.to_str().unwrap(), folder_path])
-            .output()
-            .map_err(|e| format!("Failed to execute unrar command: {}", e))?;
-
-        if !output.status.success() {
-            return Err(format!(
-                "Failed to extract archive: {}. Error: {}",
-                archive_file.display(),
-                String::from_utf8_lossy(&output.stderr)
-            ));
-        }
-
-        info!("Successfully extracted archive: {}", archive_file.display());
-    }
-
-    info!("Extraction completed. Extracted files are in: {}", folder_path);
-    Ok(Some(folder_path.to_string()))
-}
-
-pub fn add_torrent_to_qbittorrent(
-    torrent_file: &str,
-    config: &QbittorrentConfig,
-    input_path: &str,
-    is_folder: bool,
-    paths_config: &PathsConfig,
-) -> Result<(), String> {
-    info!("Creating HTTP client with cookie support for qBittorrent.");
-    let client = Client::builder()
-        .cookie_store(true)
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
-
-    let login_url = format!("{}/api/v2/auth/login", config.webui_url);
-    info!("Logging in to qBittorrent at {}...", login_url);
-    let login_response = client
-        .post(&login_url)
-        .form(&[
-            ("username", config.username.as_str()),
-            ("password", config.password.as_str()),
-        ])
-        .send()
-        .map_err(|e| format!("Failed to send login request to qBittorrent: {}", e))?;
-
-    let login_status = login_response.status();
-    let login_body = login_response.text().map_err(|e| format!("Failed to read login response body: {}", e))?;
-
-    if !login_status.is_success() {
-        return Err(format!(
-            "qBittorrent login request failed: {} - Body: {}",
-            login_status, login_body
-        ));
-    }
-
-    if login_body.trim() != "Ok." {
-        return Err(format!(
-            "qBittorrent login failed (unexpected response): {}",
-            login_body
-        ));
-    }
-    info!("Logged in to qBittorrent successfully.");
-
-    if !Path::new(torrent_file).exists() {
-        return Err(format!("Torrent file does not exist: {}", torrent_file));
-    }
-
-    let mut form = Form::new()
-        .file("torrents", torrent_file)
-        .map_err(|e| format!("Failed to attach torrent file: {}", e))?
-        .text("paused", "false")
-        .text("skip_checking", "true");
-
-    if let Some(category) = &config.category {
-        info!("Using category for qBittorrent: {}", category);
-        form = form.text("category", category.clone());
-    }
-
-    let add_url = format!("{}/api/v2/torrents/add", config.webui_url);
-    info!("Injecting torrent into qBittorrent at {}...", add_url);
-    let upload_response = client
-        .post(&add_url)
-        .multipart(form)
-        .send()
-        .map_err(|e| format!("Failed to send add torrent request to qBittorrent: {}", e))?;
-
-    let status = upload_response.status();
-    let response_body = upload_response.text().unwrap_or_else(|_| "Failed to read response body".to_string());
-    info!("qBittorrent API Response [add]: {}", response_body);
-
-    if !status.is_success() || response_body.to_lowercase().contains("fail") {
-        return Err(format!(
-            "Failed to upload torrent to qBittorrent: {}. Response: {}",
-            status, response_body
-        ));
-    }
-
-    info!("Torrent added to qBittorrent successfully.");
-    Ok(())
-}
-
-pub fn add_torrent_to_deluge(
-    torrent_file: &str,
-    config: &DelugeConfig,
-    input_path: &str,
-    is_folder: bool,
-    paths_config: &PathsConfig,
-) -> Result<(), String> {
-    info!("Adding torrent '{}' to Deluge at '{}'", torrent_file, config.webui_url);
-
-    let absolute_torrent_file = fs::canonicalize(torrent_file)
-        .map_err(|e| format!("Failed to resolve absolute path for torrent file '{}': {}", torrent_file, e))?;
-
-    let cookie_jar = Arc::new(Jar::default());
-    let client = ClientBuilder::new()
-        .cookie_store(true)
-        .cookie_provider(cookie_jar.clone())
-        .build()
-        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
-
-    let login_payload = json!({
-        "method": "auth.login",
-        "params": [config.password],
-        "id": 1
-    });
-
-    let login_response = client
-        .post(format!("{}/json", config.webui_url))
-        .json(&login_payload)
-        .send()
-        .map_err(|e| format!("Failed to log in to Deluge: {}", e))?;
-
-    let login_result: serde_json::Value = login_response
-        .json()
-        .map_err(|e| format!("Failed to parse Deluge login response: {}", e))?;
-
-    if !login_result["result"].as_bool().unwrap_or(false) {
-        return Err("Failed to log in to Deluge: Invalid credentials".to_string());
-    }
-
-    info!("Logged in to Deluge successfully.");
-
-    let add_torrent_payload = json!({
-        "method": "web.add_torrents",
-        "params": [[{
-            "path": absolute_torrent_file.to_string_lossy(),
-            "options": {
-                "add_paused": false,
-                "move_completed": false,
-                "skip_checking": true,
-                "label": config.label.clone().unwrap_or_default(),
-            }
-        }]],
-        "id": 2
-    });
-
-    let add_torrent_response = client
-        .post(format!("{}/json", config.webui_url))
-        .json(&add_torrent_payload)
-        .send()
-        .map_err(|e| format!("Failed to add torrent to Deluge: {}", e))?;
-
-    let add_torrent_result: serde_json::Value = add_torrent_response
-        .json()
-        .map_err(|e| format!("Failed to parse Deluge add torrent response: {}", e))?;
-
-    if let Some(error) = add_torrent_result.get("error") {
-        if !error.is_null() {
-            return Err(format!(
-                "Deluge returned an error while adding torrent: {:?}",
-                error
-            ));
-        }
-    }
-
-    info!("Torrent added to Deluge successfully.");
-    Ok(())
-}
-
-pub fn upload_to_imgbb(image_path: &str, imgbb_api_key: &str) -> Result<(String, String), String> {
-    let client = Client::new();
-
-    // Log the image path and API key for debugging
-    log::debug!("Uploading image to ImgBB: path={}, api_key={}", image_path, imgbb_api_key);
-
-    let form = Form::new()
-        .file("image", image_path)
-        .map_err(|e| format!("Failed to attach image file: {}", e))?;
-
-    let url = format!("https://api.imgbb.com/1/upload?key={}", imgbb_api_key);
-    log::debug!("ImgBB API URL: {}", url);
-
-    let response = client
-        .post(&url)
-        .multipart(form)
-        .send()
-        .map_err(|e| format!("Failed to upload image to ImgBB: {}", e))?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let response_body = response.text().unwrap_or_else(|_| "Failed to read response body".to_string());
-        log::error!("ImgBB API Error: HTTP Status: {}, Response: {}", status, response_body);
-        return Err(format!(
-            "Failed to upload image to ImgBB. HTTP Status: {}. Response: {}",
-            status, response_body
-        ));
-    }
-
-    let json: serde_json::Value = response
-        .json()
-        .map_err(|e| format!("Failed to parse ImgBB response: {}", e))?;
-
-    let full_image_url = json["data"]["image"]["url"]
-        .as_str()
-        .ok_or("Failed to extract full image URL from ImgBB response")?
-        .to_string();
-    let thumb_url = json["data"]["thumb"]["url"]
-        .as_str()
-        .ok_or("Failed to extract thumbnail URL from ImgBB response")?
-        .to_string();
-
-    log::info!("ImgBB Upload Successful: full_image_url={}, thumb_url={}", full_image_url, thumb_url);
-
-    Ok((full_image_url, thumb_url))
-}
-
-pub fn generate_screenshots_imgbb(
-    video_file: &str,
-    ffmpeg_path: &Path,
-    ffprobe_path: &Path,
-    imgbb_api_key: &str,
-) -> Result<(Vec<String>, Vec<String>), String> {
-    let mut screenshots = Vec::new();
-    let mut thumbnails = Vec::new();
-
-    // Get video duration
-    let duration = get_video_duration(video_file, ffprobe_path.to_str().unwrap())?;
-    let timestamps = generate_random_timestamps(duration, 4);
-
-    // Generate sanitized base name for screenshots
-    let base_name = Path::new(video_file)
-        .file_stem()
-        .unwrap_or_default()
-        .to_string_lossy()
-        .to_string();
-    let sanitized_base_name = generate_release_name(&base_name);
-
-    for (i, timestamp) in timestamps.iter().enumerate() {
-        // Generate screenshot file name
-        let screenshot_name = format!("{}_{}.jpg", sanitized_base_name, i + 1);
-        let screenshot_path = format!("/tmp/{}", screenshot_name);
-
-        // Generate screenshot
-        generate_screenshot(video_file, ffmpeg_path.to_str().unwrap(), timestamp, &screenshot_path)?;
-
-        // Upload screenshot to ImgBB
-        let (full_image_url, thumb_url) = upload_to_imgbb(&screenshot_path, imgbb_api_key)?;
-        screenshots.push(full_image_url); // Use full_image_url for the description
-        thumbnails.push(thumb_url);
-
-        // Clean up the local screenshot file
-        fs::remove_file(&screenshot_path).map_err(|e| format!("Failed to delete temporary screenshot: {}", e))?;
-    }
-
-    Ok((screenshots, thumbnails))
-}
-
-pub fn process_ebook_upload(input_path: &str, config: &Config, seedpool_config: &SeedpoolConfig) -> Result<(), String> {
-    use reqwest::blocking::Client;
-    use std::fs;
-
-    let mut working_dir = input_path.to_string();
-
-    // If input is a file, get its parent directory for extraction
-    if Path::new(&working_dir).is_file() {
-        if let Some(parent) = Path::new(&working_dir).parent() {
-            working_dir = parent.to_string_lossy().to_string();
-        }
-    }
-
-    // 1. Extract all ZIP files in the directory
-    let zip_files: Vec<_> = fs::read_dir(&working_dir)
-        .map_err(|e| format!("Failed to read directory '{}': {}", working_dir, e))?
-        .filter_map(|entry| {
-            let entry = entry.ok()?;
-            let path = entry.path();
-            if path.extension().map_or(false, |ext| ext.eq_ignore_ascii_case("zip")) {
-                Some(path)
-            } else {
-                None
-            }
-        })
-        .collect();
-
-    for zip_file in &zip_files {
-        log::info!("Extracting ZIP archive: {}", zip_file.display());
-        let output = std::process::Command::new("unzip")
-            .arg("-o")
-            .arg(zip_file)
-            .arg("-d")
-            .arg(&working_dir)
-            .output()
-            .map_err(|e| format!("Failed to execute unzip: {}", e))?;
-        if !output.status.success() {
-            return Err(format!(
-                "Failed to extract ZIP archive: {}. Error: {}",
-                zip_file.display(),
-                String::from_utf8_lossy(&output.stderr)
-            ));
-        }
-    }
-
-    // 2. Extract all RAR files in the directory (using your existing function)
-    extract_rar_archives(&working_dir)?;
-
-    // 3. Find the main ebook file (prefer .epub, fallback to .pdf)
-    let mut found_pdf: Option<String> = None;
-    let mut found_epub: Option<String> = None;
-    for entry in WalkDir::new(&working_dir).into_iter().filter_map(|e| e.ok()) {
-        let path = entry.path();
-        if path.is_file() {
-            if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-                if ext.eq_ignore_ascii_case("epub") {
-                    found_epub = Some(path.to_string_lossy().to_string());
-                    break;
-                } else if ext.eq_ignore_ascii_case("pdf") {
-                    found_pdf = Some(path.to_string_lossy().to_string());
-                }
-            }
-        }
-    }
-    let (ebook_path, is_pdf) = if let Some(epub) = found_epub {
-        (epub, false)
-    } else if let Some(pdf) = found_pdf {
-        (pdf, true)
-    } else {
-        return Err(format!("No .epub or .pdf files found in directory '{}'", working_dir));
-    };
-
-    // 4. Extract metadata and cover
-    let (mut title, mut author) = if is_pdf {
-        extract_metadata_from_pdf(&ebook_path)?
-    } else {
-        extract_metadata_from_epub(&ebook_path)?
-    };
-
-    let mut title = title.unwrap_or_else(|| "Unknown Title".to_string());
-    let mut author = author.unwrap_or_else(|| "Unknown Author".to_string());
-
-    // Sanitize the file name and rename the ebook file
-    let new_ebook_path = if is_pdf {
-        Path::new(&ebook_path).to_path_buf() // Don't rename PDF
-    } else {
-        let sanitized_author = {
-            let parts: Vec<&str> = author.split_whitespace().collect();
-            if parts.len() > 1 {
-                format!("{}, {}", parts.last().unwrap(), parts[..parts.len() - 1].join(" "))
-            } else {
-                author.to_string()
-            }
-        };
-        let sanitized_title = title
-            .replace(".", " ")
-            .replace(":", " ")
-            .replace("'", "")
-            .replace("/", " ")
-            .replace("\\", " ")
-            .replace("&", "and")
-            .replace("?", "")
-            .replace("*", "");
-        let new_ext = "epub";
-        let new_ebook_name = format!("{} - {}.{}", sanitized_author, sanitized_title, new_ext);
-        let new_ebook_path = Path::new(&ebook_path).with_file_name(new_ebook_name);
-        fs::rename(&ebook_path, &new_ebook_path)
-            .map_err(|e| format!("Failed to rename ebook file: {}", e))?;
-        new_ebook_path
-    };
-
-    // Remove any other .epub or .pdf files except the renamed one
-    for entry in fs::read_dir(&working_dir).map_err(|e| format!("Failed to read directory '{}': {}", working_dir, e))? {
-        let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
-        let path = entry.path();
-
-        if is_pdf {
-            // Remove all .epub and .zip files, but NEVER remove the found PDF
-            if (path.extension().map(|ext| ext.eq_ignore_ascii_case("epub")).unwrap_or(false)
-                || path.extension().map(|ext| ext.eq_ignore_ascii_case("zip")).unwrap_or(false))
-            {
-                fs::remove_file(&path)
-                    .map_err(|e| format!("Failed to remove file '{}': {}", path.display(), e))?;
-            }
-            // Do NOT remove the PDF file at ebook_path (or new_ebook_path)
-        } else {
-            // For EPUBs: keep only the renamed epub, remove all other epubs
-            if path.extension().map(|ext| ext.eq_ignore_ascii_case("epub")).unwrap_or(false)
-                && path != new_ebook_path
-            {
-                fs::remove_file(&path)
-                    .map_err(|e| format!("Failed to remove extra epub file '{}': {}", path.display(), e))?;
-            }
-            // Keep all ZIPs for EPUBs
-        }
-    }
-
-    let torrent_input = &working_dir;
-    let torrent_file = create_torrent(
-        torrent_input,
-        &config.paths.torrent_dir,
-        &seedpool_config.settings.announce_url,
-        &config.paths.mkbrr,
-        true,
-    )?;
-
-    // Use the base name of the directory or ebook for the upload form
-    let base_name = Path::new(torrent_input)
-        .file_name()
-        .unwrap_or_default()
-        .to_string_lossy()
-        .to_string();
-
-    let lower_base = base_name.to_lowercase();
-    let type_id = if lower_base.contains("magazine") {
-        "41"
-    } else if lower_base.contains("comic") {
-        "40"
-    } else {
-        "20"
-    };
-
-    let nfo_file = fs::read_dir(&working_dir)
-        .ok()
-        .and_then(|mut entries| {
-            entries.find_map(|entry| {
-                let entry = entry.ok()?;
-                let path = entry.path();
-                if path.extension().map(|ext| ext.eq_ignore_ascii_case("nfo")).unwrap_or(false) {
-                    Some(path.to_string_lossy().to_string())
-                } else {
-                    None
-                }
-            })
-        });
-
-    // --- SKIP OPEN LIBRARY FOR COMICS & MAGAZINES ---
-    let (mut description, mut keywords);
-    let mut cover_id: Option<u64> = None;
-    if is_pdf && (type_id == "40" || type_id == "41") {
-        let torrent_name = generate_release_name(&base_name);
-        description = generate_comic_description(
-            &ebook_path,
-            &torrent_name,
-            &seedpool_config.screenshots.remote_path,
-            &seedpool_config.screenshots.image_path,
-        )?;
-        keywords = if type_id == "41" { "magazine".to_string() } else { "comic".to_string() };
-    } else {
-        // --- ORIGINAL OPEN LIBRARY LOOKUP AND DESCRIPTION LOGIC ---
-        let mut open_library_work_key = String::new();
-        let mut open_library_author_key = String::new();
-        let mut subjects = Vec::new();
-        let mut desc = format!(
-            "[center][b][size=32][color=#2E86C1]{}[/color][/size][/b]\n\
-            [b][size=16][color=#117A65]By:[/color][/size][/b] [i]{}[/i][/center]\n\n\
-            [b][size=15][color=#6C3483]Synopsis:[/color][/size][/b]\n\
-            [quote]No metadata available.[/quote]\n\n\
-            [center]{}[/center]",
-            title,
-            author,
-            default_non_video_description()
-        );
-
-        // Only try Open Library if we have at least a title or author
-        if title != "Unknown Title" || author != "Unknown Author" {
-            let query = format!(
-                "https://openlibrary.org/search.json?title={}&author={}",
-                urlencoding::encode(&title),
-                urlencoding::encode(&author)
-            );
-
-            info!("Querying Open Library API: {}", query);
-
-            let client = Client::new();
-            let response = client
-                .get(&query)
-                .send()
-                .map_err(|e| format!("Failed to query Open Library API: {}", e))?;
-
-            if response.status().is_success() {
-                let json: serde_json::Value = response
-                    .json()
-                    .map_err(|e| format!("Failed to parse Open Library API response: {}", e))?;
-
-                if let Some(first_result) = json["docs"].as_array().and_then(|docs| docs.get(0)) {
-                    // Use Open Library's title and author if available
-                    let ol_title = first_result["title"]
-                        .as_str()
-                        .unwrap_or(&title)
-                        .to_string();
-                    let ol_author = first_result["author_name"]
-                        .as_array()
-                        .and_then(|authors| authors.get(0))
-                        .and_then(|author| author.as_str())
-                        .unwrap_or(&author)
-                        .to_string();
-
-                    info!("Using title: '{}' and author: '{}'", ol_title, ol_author);
-
-                    // Update title and author with Open Library values
-                    title = ol_title;
-                    author = ol_author;
-
-                    // Extract Open Library work and author keys
-                    open_library_work_key = first_result["key"]
-                        .as_str()
-                        .unwrap_or("")
-                        .trim_start_matches("/works/")
-                        .to_string();
-                    open_library_author_key = first_result["author_key"]
-                        .as_array()
-                        .and_then(|keys| keys.get(0))
-                        .and_then(|key| key.as_str())
-                        .unwrap_or("")
-                        .to_string();
-
-                    // Extract cover ID
-                    cover_id = first_result["cover_i"].as_u64();
-
-                    // Generate the BBCode description and fetch subjects
-                    let (desc2, subj) = generate_ebook_bbcode_description(
-                        &title,
-                        &author,
-                        &open_library_work_key,
-                        &open_library_author_key,
-                        &client,
-                    )?;
-                    desc = desc2;
-                    subjects = subj;
-                }
-            }
-        }
-        description = desc;
-        keywords = subjects.join(", ");
-    }
-
-    info!("Processing eBook upload for title: '{}' and author: '{}'", title, author);
-
-    // If PDF, extract cover image from first page using Ghostscript
-    let mut pdf_cover_image_path = None;
-    if is_pdf {
-        let cover_path = format!("{}.cover.jpg", ebook_path);
-        let output = std::process::Command::new("gs")
-            .args(&[
-                "-dBATCH", "-dNOPAUSE",
-                "-sDEVICE=jpeg",
-                "-dFirstPage=1", "-dLastPage=1",
-                "-r150", "-dJPEGQ=95",
-                &format!("-sOutputFile={}", cover_path),
-                &ebook_path,
-            ])
-            .output()
-            .map_err(|e| format!("Failed to run gs: {}", e))?;
-        if !output.status.success() {
-            return Err(format!(
-                "Failed to extract cover from PDF with gs: {}",
-                String::from_utf8_lossy(&output.stderr)
-            ));
-        }
-        pdf_cover_image_path = Some(cover_path);
-    }
-
-    let mut form = Form::new()
-        .file("torrent", &torrent_file)
-        .map_err(|e| format!("Failed to attach torrent file: {}", e))?
-        .text("name", base_name.clone())
-        .text("category_id", "7") // eBooks category
-        .text("type_id", type_id)
-        .text("tmdb", "0")
-        .text("imdb", "0")
-        .text("tvdb", "0")
-        .text("anonymous", "0")
-        .text("description", description)
-        .text("keywords", keywords)
-        .text("mal", "0")
-        .text("igdb", "0")
-        .text("stream", "0")
-        .text("sd", "0");
-
-    if let Some(nfo) = nfo_file {
-        form = form.file("nfo", nfo).map_err(|e| format!("Failed to attach NFO file: {}", e))?;
-    }
-
-    // Send the upload request
-    let client = Client::new();
-    let response = client
-        .post(&seedpool_config.settings.upload_url)
-        .header("Authorization", format!("Bearer {}", seedpool_config.general.api_key))
-        .multipart(form)
-        .send()
-        .map_err(|e| format!("Failed to send request to Seedpool: {}", e))?;
-
-    let status = response.status();
-    let response_text = response.text().unwrap_or_else(|_| "Failed to read response body".to_string());
-    info!("Seedpool API Response: {}", response_text);
-
-    if !status.is_success() {
-        return Err(format!(
-            "Failed to upload to Seedpool. HTTP Status: {}. Response: {}",
-            status, response_text
-        ));
-    }
-
-    // Extract the torrent ID from the response
-    let torrent_id = extract_torrent_id(&response_text)?;
-
-    // --- COVER HANDLING ---
-
-    // For EPUBs: Fetch the cover image using the cover ID from Open Library (existing logic)
-    if !is_pdf && (type_id != "40" && type_id != "41") {
-        let mut cover_handled = false;
-        if let Some(cover_id) = cover_id {
-            let cover_url = format!("https://covers.openlibrary.org/b/id/{}-L.jpg", cover_id);
-            info!("Fetching cover image from: {}", cover_url);
-
-            let cover_response = client
-                .get(&cover_url)
-                .send()
-                .map_err(|e| format!("Failed to fetch cover image: {}", e))?;
-
-            if cover_response.status().is_success() {
-                // Save the cover image locally
-                let cover_path = new_ebook_path.with_extension("jpg");
-                std::fs::write(&cover_path, cover_response.bytes().map_err(|e| format!("Failed to read cover image bytes: {}", e))?)
-                    .map_err(|e| format!("Failed to save cover image: {}", e))?;
-
-                info!("Saved cover image to: {}", cover_path.display());
-
-                // Rename the cover image to include the torrent ID
-                let renamed_cover_path = cover_path.with_file_name(format!("torrent-cover_{}.jpg", torrent_id));
-                std::fs::rename(&cover_path, &renamed_cover_path)
-                    .map_err(|e| format!("Failed to rename cover image: {}", e))?;
-
-                // Set permissions to 777 for the renamed cover image
-                #[cfg(unix)]
-                {
-                    use std::os::unix::fs::PermissionsExt;
-
-                    info!("Setting permissions to 777 for cover image: {}", renamed_cover_path.display());
-                    fs::set_permissions(&renamed_cover_path, fs::Permissions::from_mode(0o777))
-                        .map_err(|e| format!("Failed to set permissions for cover image '{}': {}", renamed_cover_path.display(), e))?;
-                    info!("Successfully set permissions to 777 for cover image: {}", renamed_cover_path.display());
-                }
-
-                // Upload the cover image to the CDN using SCP
-                let remote_covers_path = format!(
-                    "{}/albumcovers",
-                    seedpool_config.screenshots.remote_path.trim_end_matches('/')
-                );
-                let scp_command = std::process::Command::new("scp")
-                    .arg(&renamed_cover_path)
-                    .arg(&remote_covers_path)
-                    .output()
-                    .map_err(|e| format!("Failed to upload cover image via SCP: {}", e))?;
-
-                if !scp_command.status.success() {
-                    return Err(format!(
-                        "Failed to upload cover image via SCP. Error: {}",
-                        String::from_utf8_lossy(&scp_command.stderr)
-                    ));
-                }
-
-                info!("Successfully uploaded cover image to CDN: {}", remote_covers_path);
-                cover_handled = true;
-            } else {
-                warn!("Failed to fetch cover image with status: {}. Skipping cover image fetch.", cover_response.status());
-            }
-        }
-        // If no cover was handled, extract first image from EPUB as cover using Rust
-        if !cover_handled {
-            info!("No Open Library cover found, extracting first image from EPUB as cover.");
-            let temp_dir = std::env::temp_dir().join(format!("{}_cover_extract", base_name));
-            let page_images = extract_epub_images(new_ebook_path.to_str().unwrap(), &temp_dir)?;
-            if let Some(cover_img) = page_images.get(0) {
-                let renamed_cover_path = temp_dir.join(format!("torrent-cover_{}.jpg", torrent_id));
-                fs::copy(&cover_img, &renamed_cover_path)
-                    .map_err(|e| format!("Failed to copy extracted cover image: {}", e))?;
-                #[cfg(unix)]
-                {
-                    use std::os::unix::fs::PermissionsExt;
-                    fs::set_permissions(&renamed_cover_path, fs::Permissions::from_mode(0o777))
-                        .map_err(|e| format!("Failed to set permissions for cover image '{}': {}", renamed_cover_path.display(), e))?;
-                }
-                let remote_covers_path = format!(
-                    "{}/albumcovers",
-                    seedpool_config.screenshots.remote_path.trim_end_matches('/')
-                );
-                let scp_command = std::process::Command::new("scp")
-                    .arg(&renamed_cover_path)
-                    .arg(&remote_covers_path)
-                    .output()
-                    .map_err(|e| format!("Failed to upload extracted cover image via SCP: {}", e))?;
-                if !scp_command.status.success() {
-                    return Err(format!(
-                        "Failed to upload extracted cover image via SCP. Error: {}",
-                        String::from_utf8_lossy(&scp_command.stderr)
-                    ));
-                }
-                info!("Successfully uploaded extracted EPUB cover image to CDN: {}", remote_covers_path);
-            } else {
-                warn!("No images found to use as cover from EPUB.");
-            }
-        }
-    }
-
-    // For PDFs: Upload the extracted cover image (if any)
-    if is_pdf {
-        if let Some(cover_path) = pdf_cover_image_path {
-            // Rename the cover image to include the torrent ID
-            let renamed_cover_path = Path::new(&cover_path)
-                .with_file_name(format!("torrent-cover_{}.jpg", torrent_id));
-            std::fs::rename(&cover_path, &renamed_cover_path)
-                .map_err(|e| format!("Failed to rename PDF cover image: {}", e))?;
-
-            // Set permissions to 777 for the renamed cover image
-            #[cfg(unix)]
-            {
-                use std::os::unix::fs::PermissionsExt;
-                info!("Setting permissions to 777 for cover image: {}", renamed_cover_path.display());
-                std::fs::set_permissions(&renamed_cover_path, std::fs::Permissions::from_mode(0o777))
-                    .map_err(|e| format!("Failed to set permissions for cover image '{}': {}", renamed_cover_path.display(), e))?;
-                info!("Successfully set permissions to 777 for cover image: {}", renamed_cover_path.display());
-            }
-
-            info!("Uploading extracted PDF cover image: {}", renamed_cover_path.display());
-            let remote_covers_path = format!(
-                "{}/albumcovers",
-                seedpool_config.screenshots.remote_path.trim_end_matches('/')
-            );
-            let scp_command = std::process::Command::new("scp")
-                .arg(&renamed_cover_path)
-                .arg(&remote_covers_path)
-                .output()
-                .map_err(|e| format!("Failed to upload cover image via SCP: {}", e))?;
-
-            if !scp_command.status.success() {
-                return Err(format!(
-                    "Failed to upload cover image via SCP. Error: {}",
-                    String::from_utf8_lossy(&scp_command.stderr)
-                ));
-            }
-            info!("Successfully uploaded cover image to CDN: {}", remote_covers_path);
-        }
-    }
-
-    // Add torrent to all qBittorrent instances
-    add_torrent_to_all_qbittorrent_instances(
-        &[torrent_file.clone()],
-        &config.qbittorrent,
-        &config.deluge,
-        new_ebook_path.to_str().unwrap(),
-        &config.paths,
-    )?;
-
-    Ok(())
-}
-
-// Helper for PDF metadata extraction
-fn extract_metadata_from_pdf(pdf_path: &str) -> Result<(Option<String>, Option<String>), String> {
-    use lopdf::{Document, Object};
-
-    let doc = Document::load(pdf_path).map_err(|e| format!("Failed to open PDF: {}", e))?;
-    let info_obj = match doc.trailer.get(b"Info") {
-        Ok(obj) => obj,
-        Err(_) => return Ok((None, None)),
-    };
-    let info_ref = info_obj.as_reference().map_err(|e| format!("Failed to get Info reference: {}", e))?;
-    let dict = doc.get_dictionary(info_ref).map_err(|e| format!("Failed to get PDF info dictionary: {}", e))?;
-
-    fn get_pdf_string(dict: &lopdf::Dictionary, key: &[u8]) -> Option<String> {
-        match dict.get(key) {
-            Ok(Object::String(s, _)) => Some(String::from_utf8_lossy(s).to_string()),
-            Ok(obj) => obj.as_str().ok().map(|s| String::from_utf8_lossy(s).to_string()),
-            _ => None,
-        }
-    }
-
-    let title = get_pdf_string(&dict, b"Title");
-    let author = get_pdf_string(&dict, b"Author");
-    Ok((title, author))
-}
-
-fn extract_torrent_id(response_text: &str) -> Result<String, String> {
-    // Unescape any escaped slashes
-    let response_text = response_text.replace(r"\/", "/");
-
-    // Updated regex to match the numeric ID followed by a dot and a 32-character hash
-    let re = regex::Regex::new(r#"/download/(\d+)\.[a-fA-F0-9]{32}"#).map_err(|e| format!("Failed to compile regex: {}", e))?;
-    if let Some(captures) = re.captures(&response_text) {
-        if let Some(torrent_id) = captures.get(1) {
-            return Ok(torrent_id.as_str().to_string());
-        }
-    }
-    Err("Failed to extract torrent ID from response.".to_string())
-}
-
-fn extract_metadata_from_epub(epub_path: &str) -> Result<(Option<String>, Option<String>), String> {
-    let mut epub = EpubDoc::new(epub_path)
-        .map_err(|e| format!("Failed to open EPUB file '{}': {}", epub_path, e))?;
-
-    // Extract title from metadata
-    let title = epub.metadata.get("title").and_then(|titles| titles.get(0).cloned());
-
-    // Extract author from metadata
-    let author = epub.metadata.get("creator").and_then(|creators| creators.get(0).cloned());
-
-    Ok((title, author))
-}
-
-pub fn generate_ebook_bbcode_description(
-    title: &str,
-    author: &str,
-    open_library_work_key: &str,
-    open_library_author_key: &str,
-    client: &reqwest::blocking::Client,
-) -> Result<(String, Vec<String>), String> {
-    let mut description = String::new();
-    let mut subjects = Vec::new();
-
-    // Fetch book details from Open Library
-    let work_url = format!("https://openlibrary.org/works/{}.json", open_library_work_key);
-    let work_response = client
-        .get(&work_url)
-        .send()
-        .map_err(|e| format!("Failed to fetch book details: {}", e))?;
-    let work_json: Value = work_response
-        .json()
-        .map_err(|e| format!("Failed to parse book details: {}", e))?;
-
-    // Extract subjects (categories) but do not add them to the description
-    if let Some(subjects_array) = work_json["subjects"].as_array() {
-        subjects = subjects_array
-            .iter()
-            .filter_map(|s| s.as_str().map(|s| s.to_string()))
-            .collect();
-    }
-
-    // Fetch author details from Open Library
-    let author_url = format!("https://openlibrary.org/authors/{}.json", open_library_author_key);
-    let author_response = client
-        .get(&author_url)
-        .send()
-        .map_err(|e| format!("Failed to fetch author details: {}", e))?;
-    let author_json: Value = author_response
-        .json()
-        .map_err(|e| format!("Failed to parse author details: {}", e))?;
-
-    // Add book title and author
-    description.push_str(&format!(
-        "[center][b][size=32][color=#2E86C1]{}[/color][/size][/b][/center]\n\n",
-        work_json["title"].as_str().unwrap_or(title)
-    ));
-    description.push_str(&format!(
-        "[center][b][size=16][color=#117A65]By:[/color][/size][/b] [i]{}[/i][/center]\n\n",
-        author_json["name"].as_str().unwrap_or(author)
-    ));
-
-    // Add book description
-    if let Some(book_description) = work_json["description"]
-        .as_str()
-        .or_else(|| work_json["description"]["value"].as_str())
-    {
-        // Detect and extract links from the description
-        let link_regex = regex::Regex::new(r#"https?://[^\s\]]+"#).unwrap();
-        let mut extracted_links = Vec::new();
-
-        for capture in link_regex.captures_iter(book_description) {
-            if let Some(link) = capture.get(0) {
-                extracted_links.push(link.as_str().to_string());
-            }
-        }
-
-        // Remove links and lines containing "Contain" or brackets "[]" from the description
-        let sanitized_description: String = link_regex
-            .replace_all(book_description, "")
-            .to_string()
-            .lines()
-            .filter(|line| !line.contains("Contain") && !line.contains('[') && !line.contains(']'))
-            .collect::<Vec<_>>()
-            .join("\n");
-
-        // Add the sanitized description to the quote block
-        description.push_str("[b][size=15][color=#6C3483]Synopsis:[/color][/size][/b]\n");
-        description.push_str("[quote]\n");
-        description.push_str(&sanitized_description.trim());
-        description.push_str("\n[/quote]\n\n");
-
-        // Append the extracted links below the quote block
-        if !extracted_links.is_empty() {
-            description.push_str("[b][size=14][color=#2874A6]Additional Editions:[/color][/size][/b]\n");
-            for link in extracted_links {
-                description.push_str(&format!("- [url={}][color=#1ABC9C]{}[/color][/url]\n", link.trim_end_matches(')'), link.trim_end_matches(')')));
-            }
-            description.push_str("\n");
-        }
-    }
-
-
-    // Add author bio
-    if let Some(author_bio) = author_json["bio"]
-        .as_str()
-        .or_else(|| author_json["bio"]["value"].as_str())
-    {
-        // Remove the "([Source][1])" line and trim extra blank lines
-        let source_regex = regex::Regex::new(r"\(\[Source\]\[\d+\]\)").unwrap();
-        let sanitized_bio = source_regex
-            .replace_all(author_bio, "")
-            .to_string()
-            .replace("on Wikipedia", "")
-            .replace("*", "") // Remove asterisks
-            .trim() // Remove leading/trailing whitespace
-            .lines()
-            .filter(|line| !line.trim().is_empty()) // Remove empty lines
-            .collect::<Vec<_>>()
-            .join("\n");
-
-        description.push_str("[b][size=15][color=#AF601A]About the Author:[/color][/size][/b]\n");
-        description.push_str(&format!("[quote]{}\n\n", sanitized_bio)); // Add one blank line before the link
-
-        // Extract the Wikipedia link from the bio using a regex
-        let wikipedia_link_regex = regex::Regex::new(r#"href="([^"]+)""#).unwrap();
-        if let Some(captures) = wikipedia_link_regex.captures(author_bio) {
-            if let Some(wikipedia_link) = captures.get(1) {
-                let sanitized_link = wikipedia_link.as_str();
-                description.push_str(&format!(
-                    "\n[b]Source:[/b] [url={}][color=#1ABC9C]Wikipedia[/color][/url]",
-                    sanitized_link
-                ));
-            }
-        }
-
-       description.push_str("[/quote]\n\n");
-    }
-
-    // Fetch and list other books by the author
-    let author_works_url = format!(
-        "https://openlibrary.org/authors/{}/works.json",
-        open_library_author_key
-    );
-    let author_works_response = client
-        .get(&author_works_url)
-        .send()
-        .map_err(|e| format!("Failed to fetch author's other works: {}", e))?;
-    let author_works_json: Value = author_works_response
-        .json()
-        .map_err(|e| format!("Failed to parse author's other works: {}", e))?;
-
-    if let Some(entries) = author_works_json["entries"].as_array() {
-        let mut other_books = HashSet::new();
-        for entry in entries {
-            if let Some(book_title) = entry["title"].as_str() {
-                if book_title != title {
-                    other_books.insert(book_title.to_string());
-                }
-            }
-        }
-
-        if !other_books.is_empty() {
-            description.push_str(&format!(
-                "[b][size=15][color=#1F618D]More by {}:[/color][/size][/b]\n",
-                author
-            ));
-            description.push_str("[list]\n");
-            for book in other_books {
-                description.push_str(&format!("[*] {}\n", book));
-            }
-            description.push_str("[/list]\n\n");
-        }
-    }
-
-    // Add Open Library links
-    description.push_str("[b][size=14][color=#2874A6]Links:[/color][/size][/b]\n");
-    description.push_str(&format!(
-        "- [url=https://openlibrary.org/works/{}][color=#1ABC9C]View this book on Open Library[/color][/url]\n",
-        open_library_work_key
-    ));
-    description.push_str(&format!(
-        "- [url=https://openlibrary.org/authors/{}][color=#1ABC9C]View author on Open Library[/color][/url]\n\n",
-        open_library_author_key
-    ));
-
-    // Append the default non-video description
-    description.push_str(&format!(
-        "[center]{}[/center]",
-        default_non_video_description()
-    ));
-
-    Ok((description, subjects))
-}
-
-pub fn download_igdb_screenshots(
-    image_ids: &[String],
-    base_name: &str,
-    output_dir: &str,
-) -> Result<Vec<String>, String> {
-    let client = Client::new();
-    let mut local_paths = Vec::new();
-
-    fs::create_dir_all(output_dir).map_err(|e| format!("Failed to create output dir: {}", e))?;
-
-    for (i, image_id) in image_ids.iter().enumerate() {
-        let url = format!("https://images.igdb.com/igdb/image/upload/t_screenshot_big/{}.jpg", image_id);
-        let filename = format!("{}/{}_screen{}.jpg", output_dir, base_name, i + 1);
-
-        let mut resp = client.get(&url).send().map_err(|e| format!("Failed to download screenshot: {}", e))?;
-        let mut out = fs::File::create(&filename).map_err(|e| format!("Failed to create file: {}", e))?;
-        std::io::copy(&mut resp, &mut out).map_err(|e| format!("Failed to write screenshot: {}", e))?;
-
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            fs::set_permissions(&filename, fs::Permissions::from_mode(0o777))
-                .map_err(|e| format!("Failed to set permissions for screenshot '{}': {}", filename, e))?;
-        }
-
-        local_paths.push(filename);
-    }
-
-    Ok(local_paths)
-}
-
-pub fn generate_game_description(
-    screenshots: &[String],
-    custom_description: Option<&str>,
-    youtube_trailer_url: Option<&str>,
-    _base_name: &str,
-) -> String {
-    let mut description = String::new();
-
-    // Add screenshots in a 2x2 table pattern
-    if !screenshots.is_empty() {
-        description.push_str("[center]\n");
-        for (i, screenshot) in screenshots.iter().enumerate() {
-            if i % 2 == 0 {
-                description.push_str("[tr]\n");
-            }
-            description.push_str(&format!(
-                "        [td][img width=720]{}[/img][/td]\n",
-                screenshot
-            ));
-            if i % 2 == 1 || i == screenshots.len() - 1 {
-                description.push_str("[/tr]\n");
-            }
-        }
-        description.push_str("[/center]\n\n");
-    }
-
-    // Center the rest of the description
-    description.push_str("[center]\n");
-
-    // Add YouTube trailer link if available
-    if let Some(trailer_url) = youtube_trailer_url {
-        description.push_str(&format!(
-            "[b][url={}][Trailer on YouTube][/url][/b]\n\n",
-            trailer_url
-        ));
-    }
-
-    // Add custom description (not centered)
-    if let Some(custom_desc) = custom_description {
-        description.push_str(custom_desc);
-        description.push_str("\n\n");
-    }
-
-    // Append the default non-video description
-    description.push_str(&default_non_video_description());
-
-    description.push_str("\n[/center]");
-
-    description
-}
-
-pub fn generate_comic_description(
-    pdf_path: &str,
-    torrent_name: &str,
-    remote_path: &str,
-    public_image_path: &str,
-) -> Result<String, String> {
-    use std::fs;
-
-    let mut image_urls = Vec::new();
-
-    // Always extract pages 3-10
-    for page in 3..=10 {
-        let image_name = format!("{}-page{}.jpg", torrent_name, page);
-        let image_path = format!("{}/{}", std::env::temp_dir().to_string_lossy(), image_name);
-
-        // Extract page as JPEG
-        let output = std::process::Command::new("gs")
-            .args(&[
-                "-dBATCH", "-dNOPAUSE",
-                "-sDEVICE=jpeg",
-                &format!("-dFirstPage={}", page),
-                &format!("-dLastPage={}", page),
-                "-r300", "-dJPEGQ=95",
-                &format!("-sOutputFile={}", image_path),
-                pdf_path,
-            ])
-            .output()
-            .map_err(|e| format!("Failed to run gs for page {}: {}", page, e))?;
-
-        if !output.status.success() {
-            return Err(format!(
-                "Failed to extract page {}: {}",
-                page,
-                String::from_utf8_lossy(&output.stderr)
-            ));
-        }
-
-        // Set permissions to 777
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            fs::set_permissions(&image_path, fs::Permissions::from_mode(0o777))
-                .map_err(|e| format!("Failed to set permissions for '{}': {}", image_path, e))?;
-        }
-
-        // SCP to CDN (remote_path as-is)
-        let scp_status = std::process::Command::new("scp")
-            .arg(&image_path)
-            .arg(remote_path)
-            .status()
-            .map_err(|e| format!("Failed to scp '{}': {}", image_path, e))?;
-        if !scp_status.success() {
-            return Err(format!("Failed to scp '{}'", image_path));
-        }
-
-        // Build public URL
-        let cdn_url = format!("{}/{}", public_image_path.trim_end_matches('/'), image_name);
-        image_urls.push(cdn_url);
-    }
-
-    // Build BBCode description
-    let mut description = format!(
-        "[center][b][size=18][color=#2E86C1]{}[/color][/size][/b]\n\n[table]\n",
-        torrent_name
-    );
-    for (i, url) in image_urls.iter().enumerate() {
-        if i % 2 == 0 {
-            description.push_str("  [tr]\n");
-        }
-        description.push_str(&format!("    [td][img width=720]{}[/img][/td]\n", url));
-        if i % 2 == 1 {
-            description.push_str("  [/tr]\n");
-        }
-    }
-    // If odd number of images, close the last row
-    if image_urls.len() % 2 != 0 {
-        description.push_str("    [td][/td]\n  [/tr]\n");
-    }
-    description.push_str("[/table][/center]\n\n");
-    description.push_str(&format!("[center]{}[/center]", default_non_video_description()));
-
-    Ok(description)
-}
-
-pub fn process_newspaper_upload(
-    input_path: &str,
-    config: &Config,
-    seedpool_config: &SeedpoolConfig,
-) -> Result<(), String> {
-    use reqwest::blocking::Client;
-    use std::fs;
-
-    let mut working_dir = input_path.to_string();
-
-    // If input is a file, get its parent directory for extraction
-    if Path::new(&working_dir).is_file() {
-        if let Some(parent) = Path::new(&working_dir).parent() {
-            working_dir = parent.to_string_lossy().to_string();
-        }
-    }
-
-    // 1. Extract all ZIP files in the directory
-    let zip_files: Vec<_> = fs::read_dir(&working_dir)
-        .map_err(|e| format!("Failed to read directory '{}': {}", working_dir, e))?
-        .filter_map(|entry| {
-            let entry = entry.ok()?;
-            let path = entry.path();
-            if path.extension().map_or(false, |ext| ext.eq_ignore_ascii_case("zip")) {
-                Some(path)
-            } else {
-                None
-            }
-        })
-        .collect();
-
-    for zip_file in &zip_files {
-        log::info!("Extracting ZIP archive: {}", zip_file.display());
-        let output = std::process::Command::new("unzip")
-            .arg("-o")
-            .arg(zip_file)
-            .arg("-d")
-            .arg(&working_dir)
-            .output()
-            .map_err(|e| format!("Failed to execute unzip: {}", e))?;
-        if !output.status.success() {
-            return Err(format!(
-                "Failed to extract ZIP archive: {}. Error: {}",
-                zip_file.display(),
-                String::from_utf8_lossy(&output.stderr)
-            ));
-        }
-    }
-
-    // 2. Extract all RAR files in the directory
-    extract_rar_archives(&working_dir)?;
-
-    // 3. Find the main .epub or .pdf file
-    let mut found_pdf: Option<String> = None;
-    let mut found_epub: Option<String> = None;
-    for entry in WalkDir::new(&working_dir).into_iter().filter_map(|e| e.ok()) {
-        let path = entry.path();
-        if path.is_file() {
-            if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-                if ext.eq_ignore_ascii_case("epub") {
-                    found_epub = Some(path.to_string_lossy().to_string());
-                    break;
-                } else if ext.eq_ignore_ascii_case("pdf") {
-                    found_pdf = Some(path.to_string_lossy().to_string());
-                }
-            }
-        }
-    }
-    let (newspaper_path, is_pdf) = if let Some(epub) = found_epub {
-        (epub, false)
-    } else if let Some(pdf) = found_pdf {
-        (pdf, true)
-    } else {
-        return Err(format!("No .epub or .pdf file found in directory '{}'", working_dir));
-    };
-
-    // 4. Extract images for description and cover
-    let mut desc_image_urls = Vec::new();
-    let mut cover_image_path: Option<String> = None;
-    let base_name = Path::new(&newspaper_path)
-        .file_stem()
-        .unwrap_or_default()
-        .to_string_lossy()
-        .to_string();
-
-    if is_pdf {
-        // --- PDF: Use Ghostscript for cover and description images ---
-        let temp_dir = std::env::temp_dir().join(format!("{}_pdf_images", base_name));
-        fs::create_dir_all(&temp_dir)
-            .map_err(|e| format!("Failed to create temp dir for images: {}", e))?;
-
-        // Extract cover (page 1)
-        let cover_path = temp_dir.join("page-1.jpg");
-        let output = std::process::Command::new("gs")
-            .args(&[
-                "-dBATCH", "-dNOPAUSE",
-                "-sDEVICE=jpeg",
-                "-dFirstPage=1", "-dLastPage=1",
-                "-r150", "-dJPEGQ=95",
-                &format!("-sOutputFile={}", cover_path.display()),
-                &newspaper_path,
-            ])
-            .output()
-            .map_err(|e| format!("Failed to run gs for cover: {}", e))?;
-        if !output.status.success() {
-            return Err(format!(
-                "Failed to extract cover from PDF: {}",
-                String::from_utf8_lossy(&output.stderr)
-            ));
-        }
-        cover_image_path = Some(cover_path.to_string_lossy().to_string());
-
-        // Extract pages 2-11 for description
-        for page in 2..=11 {
-            let img_name = format!("{}-page{}.jpg", base_name, page);
-            let img_path = temp_dir.join(&img_name);
-            let output = std::process::Command::new("gs")
-                .args(&[
-                    "-dBATCH", "-dNOPAUSE",
-                    "-sDEVICE=jpeg",
-                    &format!("-dFirstPage={}", page),
-                    &format!("-dLastPage={}", page),
-                    "-r300", "-dJPEGQ=95",
-                    &format!("-sOutputFile={}", img_path.display()),
-                    &newspaper_path,
-                ])
-                .output()
-                .map_err(|e| format!("Failed to run gs for page {}: {}", page, e))?;
-            if !output.status.success() {
-                return Err(format!(
-                    "Failed to extract page {}: {}",
-                    page,
-                    String::from_utf8_lossy(&output.stderr)
-                ));
-            }
-            #[cfg(unix)]
-            {
-                use std::os::unix::fs::PermissionsExt;
-                fs::set_permissions(&img_path, fs::Permissions::from_mode(0o777))
-                    .map_err(|e| format!("Failed to set permissions for '{}': {}", img_path.display(), e))?;
-            }
-            // SCP to CDN
-            let scp = std::process::Command::new("scp")
-                .arg(&img_path)
-                .arg(&seedpool_config.screenshots.remote_path)
-                .output()
-                .map_err(|e| format!("Failed to upload description image via SCP: {}", e))?;
-            if !scp.status.success() {
-                return Err(format!(
-                    "Failed to upload description image via SCP. Error: {}",
-                    String::from_utf8_lossy(&scp.stderr)
-                ));
-            }
-            let url = format!("{}/{}", seedpool_config.screenshots.image_path.trim_end_matches('/'), img_name);
-            desc_image_urls.push(url);
-        }
-    } else {
-        // --- EPUB: Use Rust to extract images for cover and description ---
-        let temp_dir = std::env::temp_dir().join(format!("{}_epub_images", base_name));
-        fs::create_dir_all(&temp_dir)
-            .map_err(|e| format!("Failed to create temp dir for images: {}", e))?;
-
-        let page_images = extract_epub_images(&newspaper_path, &temp_dir)?;
-
-        if page_images.len() < 2 {
-            return Err("Not enough images extracted from EPUB.".to_string());
-        }
-
-        // Pages 2-11 for description
-        for (i, img) in page_images.iter().enumerate().skip(1).take(10) {
-            #[cfg(unix)]
-            {
-                use std::os::unix::fs::PermissionsExt;
-                fs::set_permissions(img, fs::Permissions::from_mode(0o777))
-                    .map_err(|e| format!("Failed to set permissions for image '{}': {}", img.display(), e))?;
-            }
-            let img_name = format!("{}-page{}.jpg", base_name, i + 1);
-            let scp = std::process::Command::new("scp")
-                .arg(img)
-                .arg(&seedpool_config.screenshots.remote_path)
-                .output()
-                .map_err(|e| format!("Failed to upload description image via SCP: {}", e))?;
-            if !scp.status.success() {
-                return Err(format!(
-                    "Failed to upload description image via SCP. Error: {}",
-                    String::from_utf8_lossy(&scp.stderr)
-                ));
-            }
-            let url = format!("{}/{}", seedpool_config.screenshots.image_path.trim_end_matches('/'), img_name);
-            desc_image_urls.push(url);
-        }
-        // Cover image is page 1
-        if let Some(cover_img) = page_images.get(0) {
-            cover_image_path = Some(cover_img.to_string_lossy().to_string());
-        }
-    }
-
-    // 5. Build BBCode description
-    let mut description = format!(
-        "[center][b][size=18][color=#2E86C1]{}[/color][/size][/b]\n\n[table]\n",
-        base_name
-    );
-    for (i, url) in desc_image_urls.iter().enumerate() {
-        if i % 2 == 0 {
-            description.push_str("  [tr]\n");
-        }
-        description.push_str(&format!("    [td][img width=720]{}[/img][/td]\n", url));
-        if i % 2 == 1 {
-            description.push_str("  [/tr]\n");
-        }
-    }
-    if desc_image_urls.len() % 2 != 0 {
-        description.push_str("    [td][/td]\n  [/tr]\n");
-    }
-    description.push_str("[/table][/center]\n\n");
-    description.push_str(&format!("[center]{}[/center]", default_non_video_description()));
-
-    for entry in fs::read_dir(&working_dir).map_err(|e| format!("Failed to read directory '{}': {}", working_dir, e))? {
-        let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
-        let path = entry.path();
-        if path.extension().map(|ext| ext.eq_ignore_ascii_case("zip")).unwrap_or(false) {
-            fs::remove_file(&path)
-                .map_err(|e| format!("Failed to remove zip file '{}': {}", path.display(), e))?;
-        }
-    }
-
-    // 6. Create torrent
-    let torrent_input = &working_dir;
-    let torrent_file = create_torrent(
-        torrent_input,
-        &config.paths.torrent_dir,
-        &seedpool_config.settings.announce_url,
-        &config.paths.mkbrr,
-        true,
-    )?;
-
-    // 7. Prepare upload form and upload to Seedpool
-    let nfo_file = fs::read_dir(&working_dir)
-        .ok()
-        .and_then(|mut entries| {
-            entries.find_map(|entry| {
-                let entry = entry.ok()?;
-                let path = entry.path();
-                if path.extension().map(|ext| ext.eq_ignore_ascii_case("nfo")).unwrap_or(false) {
-                    Some(path.to_string_lossy().to_string())
-                } else {
-                    None
-                }
-            })
-        });
-
-    let mut form = Form::new()
-        .file("torrent", &torrent_file)
-        .map_err(|e| format!("Failed to attach torrent file: {}", e))?
-        .text("name", Path::new(input_path).file_name().unwrap_or_default().to_string_lossy().to_string())
-        .text("category_id", "7") // eBooks category
-        .text("type_id", "42")    // Newspaper type
-        .text("tmdb", "0")
-        .text("imdb", "0")
-        .text("tvdb", "0")
-        .text("anonymous", "0")
-        .text("description", description)
-        .text("keywords", "newspaper")
-        .text("mal", "0")
-        .text("igdb", "0")
-        .text("stream", "0")
-        .text("sd", "0");
-
-    if let Some(nfo) = nfo_file {
-        form = form.file("nfo", nfo).map_err(|e| format!("Failed to attach NFO file: {}", e))?;
-    }
-
-    let client = Client::new();
-    let response = client
-        .post(&seedpool_config.settings.upload_url)
-        .header("Authorization", format!("Bearer {}", seedpool_config.general.api_key))
-        .multipart(form)
-        .send()
-        .map_err(|e| format!("Failed to send request to Seedpool: {}", e))?;
-
-    let status = response.status();
-    let response_text = response.text().unwrap_or_else(|_| "Failed to read response body".to_string());
-    info!("Seedpool API Response: {}", response_text);
-
-    if !status.is_success() {
-        return Err(format!(
-            "Failed to upload to Seedpool. HTTP Status: {}. Response: {}",
-            status, response_text
-        ));
-    }
-
-    // Extract the torrent ID from the response
-    let torrent_id = extract_torrent_id(&response_text)?;
-
-    // 8. Upload cover image to CDN, named with torrent id
-    if let Some(cover_img_path) = cover_image_path {
-        let cover_name = format!("torrent-cover_{}.jpg", torrent_id);
-        let temp_cover_path = std::env::temp_dir().join(&cover_name);
-
-        // Rename or copy the cover image to the correct name in temp
-        fs::copy(&cover_img_path, &temp_cover_path)
-            .map_err(|e| format!("Failed to copy cover image for CDN upload: {}", e))?;
-
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            fs::set_permissions(&temp_cover_path, fs::Permissions::from_mode(0o777))
-                .map_err(|e| format!("Failed to set permissions for cover image '{}': {}", temp_cover_path.display(), e))?;
-        }
-
-        let cover_remote_path = format!("{}/albumcovers", seedpool_config.screenshots.remote_path.trim_end_matches('/'));
-        let cover_scp = std::process::Command::new("scp")
-            .arg(&temp_cover_path)
-            .arg(&cover_remote_path)
-            .output()
-            .map_err(|e| format!("Failed to upload cover image via SCP: {}", e))?;
-        if !cover_scp.status.success() {
-            return Err(format!(
-                "Failed to upload cover image via SCP. Error: {}",
-                String::from_utf8_lossy(&cover_scp.stderr)
-            ));
-        }
-
-        // Optionally clean up the temp file
-        let _ = fs::remove_file(&temp_cover_path);
-    }
-
-    // 9. Add torrent to all qBittorrent instances
-    add_torrent_to_all_qbittorrent_instances(
-        &[torrent_file.clone()],
-        &config.qbittorrent,
-        &config.deluge,
-        newspaper_path.as_str(),
-        &config.paths,
-    )?;
-
-    Ok(())
-}
-
-pub fn extract_epub_images(epub_path: &str, temp_dir: &std::path::Path) -> Result<Vec<std::path::PathBuf>, String> {
-    let file = File::open(epub_path).map_err(|e| format!("Failed to open EPUB: {}", e))?;
-    let mut archive = ZipArchive::new(file).map_err(|e| format!("Failed to read EPUB as zip: {}", e))?;
-
-    std::fs::create_dir_all(temp_dir).map_err(|e| format!("Failed to create temp dir: {}", e))?;
-
-    let mut images = Vec::new();
-
-    for i in 0..archive.len() {
-        let mut file = archive.by_index(i).map_err(|e| format!("Failed to access EPUB entry: {}", e))?;
-        let name = file.name().to_lowercase();
-        if name.ends_with(".jpg") || name.ends_with(".jpeg") || name.ends_with(".png") || name.ends_with(".gif") {
-            let out_path = temp_dir.join(std::path::Path::new(&name).file_name().unwrap());
-            let mut out_file = File::create(&out_path).map_err(|e| format!("Failed to create image file: {}", e))?;
-            std::io::copy(&mut file, &mut out_file).map_err(|e| format!("Failed to extract image: {}", e))?;
-            images.push(out_path);
-        }
-    }
-
-    images.sort();
-    Ok(images)
+use reqwest::blocking::multipart::Form;
+use reqwest::cookie::Jar;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use regex::Regex;
+use epub::doc::EpubDoc;
+use log::{info, error, warn};
+use std::process::Command;
+use serde_json::{Value, json};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::fs;
+use zip::ZipArchive;
+use std::fs::File;
+use walkdir::WalkDir;
+use crate::types::{PathsConfig, SampleConfig, ScreenshotsConfig, SeedpoolConfig, Config, QbittorrentConfig, VideoSettings, DelugeConfig, UploadOverrides, ExclusionsConfig, UnpackConfig, ProofConfig, SeedpoolScreenshots, TorrentOptions, TorrentVersion, Footer, to_client_path};
+use dialoguer::Input;
+use crate::trackers::seedpool::{extract_torrent_id, submit_upload, UploadRequest};
+use crate::trackers::release::Tools;
+use crate::error::SeedToolsError;
+use crate::offline::Offline;
+use crate::seeding::{log_state, wait_for_deluge, wait_for_qbittorrent, SeedingState, TorrentClient};
+
+pub fn generate_release_name(base_name: &str) -> String {
+    let mut release_name = base_name.to_string();
+
+    // Remove file extensions
+    release_name = Regex::new(r"\.(epub|mobi|pdf|txt|mkv|mp4|m4b|avi|mov|flv|wmv|ts)$")
+        .unwrap()
+        .replace(&release_name, "")
+        .to_string();
+
+    // Replace non-alphanumeric characters with dots
+    release_name = Regex::new(r"[^A-Za-z0-9+\-]")
+        .unwrap()
+        .replace_all(&release_name, ".")
+        .to_string();
+
+    // Replace multiple dots with a single dot
+    release_name = Regex::new(r"\.\.+")
+        .unwrap()
+        .replace_all(&release_name, ".")
+        .to_string();
+
+    // Replace mixed dot-dash patterns
+    release_name = Regex::new(r"-\.+|\.-+")
+        .unwrap()
+        .replace_all(&release_name, "-")
+        .to_string();
+
+    // Remove trailing dots
+    release_name = Regex::new(r"\.$")
+        .unwrap()
+        .replace(&release_name, "")
+        .to_string();
+
+    // Remove leading dots
+    release_name.trim_start_matches('.').to_string()
+}
+
+pub fn find_video_files<T>(
+    input_path: &str,
+    _paths: &PathsConfig,
+    settings: &T,
+    exclusions: &ExclusionsConfig,
+) -> Result<(Vec<String>, Option<String>), SeedToolsError>
+where
+    T: VideoSettings,
+{
+    let supported_extensions = ["mkv", "mp4", "ts", "avi", "mov", "flv", "wmv"];
+    let path = Path::new(input_path);
+
+    let mut video_files = Vec::new();
+    let mut nfo_file: Option<String> = None;
+
+    let exclusions_enabled = settings.stripshit_from_videos();
+    info!("Exclusions enabled: {}", exclusions_enabled);
+    let excluded_keywords: &[String] = if exclusions_enabled { &exclusions.keywords } else { &[] };
+
+    fn process_path(
+        file_path: &Path,
+        video_files: &mut Vec<String>,
+        nfo_file: &mut Option<String>,
+        supported_extensions: &[&str],
+        excluded_keywords: &[String],
+    ) -> Result<(), SeedToolsError> {
+        if file_path.is_dir() {
+            for entry in fs::read_dir(file_path).map_err(SeedToolsError::io("Failed to read directory"))? {
+                let entry = entry.map_err(SeedToolsError::io("Failed to read directory entry"))?;
+                let entry_path = entry.path();
+                process_path(&entry_path, video_files, nfo_file, supported_extensions, excluded_keywords)?;
+            }
+        } else {
+            log::debug!("Processing file: {}", file_path.display());
+            process_file(file_path, video_files, nfo_file, supported_extensions, excluded_keywords)?;
+        }
+        Ok(())
+    }
+
+    process_path(path, &mut video_files, &mut nfo_file, &supported_extensions, excluded_keywords)?;
+
+    // Discs are represented by their largest stream file
+    if let Some(disc) = detect_disc(input_path) {
+        info!("{:?} disc structure detected; main video: {}", disc.kind, disc.video_file);
+        video_files = vec![disc.video_file];
+    }
+
+    if video_files.is_empty() {
+        error!("No valid video files detected after exclusions.");
+        return Err(SeedToolsError::Metadata("No valid video files detected.".to_string()));
+    }
+
+    info!("Final NFO file: {:?}", nfo_file);
+
+    Ok((video_files, nfo_file))
+}
+
+/// Layout of a full disc release.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DiscKind {
+    Bluray, // BDMV folder
+    Dvd,    // VIDEO_TS folder
+}
+
+/// A BluRay or DVD folder structure inside a release.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiscRelease {
+    pub kind: DiscKind,
+    pub mediainfo_file: String, // Main playlist (.mpls) or the IFO of the main title set
+    pub video_file: String,     // Largest .m2ts or .VOB, for screenshots
+}
+
+/// Finds a BDMV or VIDEO_TS folder in the release. The main playlist is taken to be the largest
+/// one, since it lists the most clips; the main DVD title set is the one with the largest VOB.
+pub fn detect_disc(input_path: &str) -> Option<DiscRelease> {
+    let largest = |dir: &Path, extension: &str| {
+        fs::read_dir(dir)
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext.eq_ignore_ascii_case(extension)))
+            .max_by_key(|entry| entry.metadata().map(|metadata| metadata.len()).unwrap_or(0))
+            .map(|entry| entry.path())
+    };
+    let child = |dir: &Path, name: &str| {
+        fs::read_dir(dir)
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .find(|entry| entry.file_name().to_string_lossy().eq_ignore_ascii_case(name) && entry.path().is_dir())
+            .map(|entry| entry.path())
+    };
+
+    for entry in WalkDir::new(input_path).max_depth(3).into_iter().filter_map(|entry| entry.ok()) {
+        if !entry.file_type().is_dir() {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_uppercase();
+        if name == "BDMV" {
+            let Some(video_file) = child(entry.path(), "STREAM").and_then(|stream| largest(&stream, "m2ts")) else { continue };
+            let playlist = child(entry.path(), "PLAYLIST").and_then(|playlists| largest(&playlists, "mpls"));
+            return Some(DiscRelease {
+                kind: DiscKind::Bluray,
+                mediainfo_file: playlist.unwrap_or_else(|| video_file.clone()).to_string_lossy().to_string(),
+                video_file: video_file.to_string_lossy().to_string(),
+            });
+        }
+        if name == "VIDEO_TS" {
+            let Some(video_file) = largest(entry.path(), "vob") else { continue };
+            // VTS_01_3.VOB belongs to the title set described by VTS_01_0.IFO
+            let vob_name = video_file.file_name().unwrap_or_default().to_string_lossy().to_string();
+            let ifo = vob_name
+                .get(..6)
+                .map(|title_set| entry.path().join(format!("{}_0.IFO", title_set)))
+                .filter(|ifo| ifo.is_file());
+            return Some(DiscRelease {
+                kind: DiscKind::Dvd,
+                mediainfo_file: ifo.unwrap_or_else(|| video_file.clone()).to_string_lossy().to_string(),
+                video_file: video_file.to_string_lossy().to_string(),
+            });
+        }
+    }
+    None
+}
+
+/// Value of `key` in the first MediaInfo section whose heading starts with `section`
+/// ("General", "Video", "Audio #2", ...).
+pub fn mediainfo_value(mediainfo: &str, section: &str, key: &str) -> Option<String> {
+    let mut in_section = false;
+    for line in mediainfo.lines() {
+        if !line.contains(':') && !line.trim().is_empty() {
+            if in_section {
+                return None; // The next section began
+            }
+            in_section = line.trim().starts_with(section);
+        } else if in_section
+            && let Some((name, value)) = line.split_once(':')
+            && name.trim() == key
+        {
+            return Some(value.trim().to_string());
+        }
+    }
+    None
+}
+
+/// Source type of a release: disc, remux, encode, web-dl, webrip or hdtv (the keys of the
+/// UNIT3D `types` maps), with the reason for it. The video stream in the MediaInfo output
+/// decides where it is telling; the release name otherwise.
+pub fn detect_source_type(release_name: &str, mediainfo: &str, disc: bool) -> (&'static str, String) {
+    if disc {
+        return ("disc", "BDMV/VIDEO_TS structure".to_string());
+    }
+    let name_type = crate::trackers::unit3d::detect_type(release_name);
+    let format = mediainfo_value(mediainfo, "Video", "Format").unwrap_or_default();
+    let settings = mediainfo_value(mediainfo, "Video", "Encoding settings").unwrap_or_default();
+    let digits = |value: Option<String>| value.unwrap_or_default().chars().filter(|c| c.is_ascii_digit() || *c == '.').collect::<String>();
+    let height: u32 = digits(mediainfo_value(mediainfo, "Video", "Height")).parse().unwrap_or(0);
+    let bit_rate = mediainfo_value(mediainfo, "Video", "Bit rate").or_else(|| mediainfo_value(mediainfo, "Video", "Nominal bit rate"));
+    let kbps = {
+        let unit_mbps = bit_rate.as_deref().is_some_and(|rate| rate.contains("Mb/s"));
+        let number: f64 = digits(bit_rate.map(|rate| rate.replace(' ', ""))).parse().unwrap_or(0.0);
+        if unit_mbps { number * 1000.0 } else { number }
+    };
+    let web = name_type == "web-dl" || name_type == "webrip";
+
+    // x264/x265 in constant quality mode means someone re-encoded it; streaming services and discs use bitrate targets
+    if settings.contains("rc=crf") {
+        let source_type = if web { "webrip" } else if name_type == "hdtv" { "hdtv" } else { "encode" };
+        return (source_type, "CRF encoder settings in the video stream".to_string());
+    }
+    // Nobody encodes to these, so at HD they come straight off a disc
+    if (format == "VC-1" || format == "MPEG Video") && height >= 720 && !web {
+        return ("remux", format!("{} video at {}p", format, height));
+    }
+    let remux_kbps = match height {
+        h if h >= 2000 => 40_000.0,
+        h if h >= 1000 => 15_000.0,
+        h if h >= 700 => 8_000.0,
+        _ => f64::MAX,
+    };
+    if settings.is_empty() && kbps >= remux_kbps && !web && name_type != "hdtv" {
+        return ("remux", format!("{:.1} Mb/s {} without encoder settings", kbps / 1000.0, format));
+    }
+    (name_type, "release name".to_string())
+}
+
+/// A sports event parsed from a release name; these are not on TMDB.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SportEvent {
+    pub event: String,        // League or competition, e.g. "Formula 1"
+    pub year: Option<String>,
+    pub date: Option<String>, // YYYY.MM.DD, for releases named by match day
+    pub round: Option<u32>,   // Round, week, matchday, stage or game number
+}
+
+// Leagues and competitions whose releases are named by date or round rather than like TV
+const SPORT_PATTERN: &str = r"(?i)^(formula[. ]?(1|one|e)|f1|motogp|moto2|moto3|nascar|indycar|wrc|wec|dtm|superbike|nba|wnba|nfl|nhl|mlb|mls|ncaa|ufc|bellator|pfl|wwe|aew|boxing|epl|premier[. ]league|la[. ]liga|bundesliga|serie[. ]a|ligue[. ]1|eredivisie|uefa|champions[. ]league|europa[. ]league|fifa|world[. ]cup|euro[. ]20\d{2}|olympics|wimbledon|us[. ]open|roland[. ]garros|super[. ]bowl|tour[. ]de[. ]france|six[. ]nations|afl|nrl|ipl|pga|the[. ]masters|snooker|darts)\b";
+
+/// Recognizes a sports release: a known league or competition at the start of the name, or
+/// a name carrying a match date (2024.03.15) and no SxxEyy.
+pub fn parse_sport_event(release_name: &str) -> Option<SportEvent> {
+    let date_regex = Regex::new(r"\b((?:19|20)\d{2})[. _-](0[1-9]|1[0-2])[. _-](0[1-9]|[12]\d|3[01])\b").unwrap();
+    let year_regex = Regex::new(r"\b(19|20)\d{2}\b").unwrap();
+    let league = Regex::new(SPORT_PATTERN).unwrap().is_match(release_name);
+    let date = date_regex.captures(release_name);
+    let episodic = Regex::new(r"(?i)\bS\d{2}E\d{2}\b").unwrap().is_match(release_name);
+    if !league && (date.is_none() || episodic) {
+        return None;
+    }
+
+    // The event is everything before the date or year
+    let end = date_regex
+        .find(release_name)
+        .or_else(|| year_regex.find(release_name))
+        .map_or(release_name.len(), |found| found.start());
+    let event = release_name[..end].replace(['.', '_'], " ").trim().to_string();
+    let round = Regex::new(r"(?i)\b(?:round|rd|week|wk|matchday|md|stage|game|race)[. _-]?(\d{1,3})\b")
+        .unwrap()
+        .captures(release_name)
+        .and_then(|captures| captures[1].parse().ok());
+    Some(SportEvent {
+        event: if event.is_empty() { release_name.to_string() } else { event },
+        year: date
+            .as_ref()
+            .map(|captures| captures[1].to_string())
+            .or_else(|| year_regex.find(release_name).map(|found| found.as_str().to_string())),
+        date: date.map(|captures| format!("{}.{}.{}", &captures[1], &captures[2], &captures[3])),
+        round,
+    })
+}
+
+/// Writes match dates in a release name as YYYY.MM.DD, the way sports releases are named.
+pub fn normalize_event_date(release_name: &str) -> String {
+    Regex::new(r"\b((?:19|20)\d{2})[ _-](0[1-9]|1[0-2])[ _-](0[1-9]|[12]\d|3[01])\b")
+        .unwrap()
+        .replace_all(release_name, "$1.$2.$3")
+        .to_string()
+}
+
+/// External subtitle files; none of them match the default `exclusions`, so they stay in the torrent.
+pub const SUBTITLE_EXTENSIONS: [&str; 6] = ["srt", "ass", "ssa", "sub", "idx", "sup"];
+
+// Language names accepted as subtitle file name tags besides 2-3 letter codes
+const LANGUAGE_NAMES: [&str; 24] = [
+    "arabic", "chinese", "czech", "danish", "dutch", "english", "finnish", "french", "german", "greek", "hebrew", "hindi",
+    "hungarian", "italian", "japanese", "korean", "norwegian", "polish", "portuguese", "romanian", "russian", "spanish",
+    "swedish", "turkish",
+];
+
+/// Languages of the audio tracks in MediaInfo output.
+pub fn extract_audio_languages(mediainfo_output: &str) -> Vec<String> {
+    let mut audio_languages = Vec::new();
+    let mut in_audio_section = false;
+
+    for line in mediainfo_output.lines() {
+        if line.starts_with("Audio") {
+            in_audio_section = true; // Entering an audio section
+        } else if line.is_empty() {
+            in_audio_section = false; // Exiting the current section
+        }
+
+        if in_audio_section && line.contains("Language")
+            && let Some(language) = line.split(':').nth(1)
+        {
+            audio_languages.push(language.trim().to_string());
+        }
+    }
+
+    audio_languages
+}
+
+/// Distinct spoken languages among `audio_languages`, ignoring tracks without a language and
+/// regional variants ("English (US)" counts as English).
+fn distinct_languages(audio_languages: &[String]) -> Vec<String> {
+    let mut languages: Vec<String> = Vec::new();
+    for language in audio_languages {
+        let name = language.split(['(', ',', '/']).next().unwrap_or_default().trim().to_lowercase();
+        if !name.is_empty() && name != "unknown" && !languages.contains(&name) {
+            languages.push(name);
+        }
+    }
+    languages
+}
+
+/// Adds DUAL-AUDIO (two audio languages) or MULTI (three or more) to a release name, in front
+/// of the resolution or source, unless the name already has such a tag.
+pub fn tag_audio_languages(release_name: &str, audio_languages: &[String]) -> String {
+    let tag = match distinct_languages(audio_languages).len() {
+        0 | 1 => return release_name.to_string(),
+        2 => "DUAL-AUDIO",
+        _ => "MULTI",
+    };
+    if Regex::new(r"(?i)(^|[.-])(DUAL|DUAL[.-]AUDIO|MULTI|MULTI[.-]AUDIO)([.-]|$)").unwrap().is_match(release_name) {
+        return release_name.to_string();
+    }
+    let anchor = Regex::new(r"(?i)\.(\d{3,4}[pi]|UHD|BluRay|Blu-Ray|BDRip|WEB|WEB-DL|WEBRip|HDTV|DVDRip|REMUX)(\.|-|$)").unwrap();
+    match anchor.find(release_name) {
+        Some(found) => format!("{}.{}{}", &release_name[..found.start()], tag, &release_name[found.start()..]),
+        None => match release_name.rfind('-') {
+            Some(group) => format!("{}.{}{}", &release_name[..group], tag, &release_name[group..]),
+            None => format!("{}.{}", release_name, tag),
+        },
+    }
+}
+
+/// Warnings for languages the release name claims (GERMAN, DUAL, MULTI, ...) that the audio
+/// tracks do not have. Says nothing when MediaInfo found no languages.
+pub fn check_claimed_languages(release_name: &str, audio_languages: &[String]) -> Vec<String> {
+    let languages = distinct_languages(audio_languages);
+    if languages.is_empty() {
+        return Vec::new();
+    }
+    let mut warnings = Vec::new();
+    for part in release_name.split(['.', ' ', '_']) {
+        let claim = part.to_lowercase();
+        if LANGUAGE_NAMES.contains(&claim.as_str()) && !languages.contains(&claim) {
+            warnings.push(format!(
+                "'{}' claims {} audio, but the file only has: {}",
+                release_name,
+                part,
+                languages.join(", ")
+            ));
+        } else if (claim == "dual" || claim == "multi") && languages.len() < 2 {
+            warnings.push(format!("'{}' is tagged {}, but the file only has {} audio", release_name, part, languages.join(", ")));
+        }
+    }
+    warnings
+}
+
+/// Languages of the subtitle (Text) tracks in MediaInfo output, e.g. "English (forced)".
+pub fn extract_subtitle_languages(mediainfo_output: &str) -> Vec<String> {
+    let mut languages = Vec::new();
+    let mut in_text_section = false;
+    let mut language: Option<String> = None;
+    let mut forced = false;
+
+    // A trailing blank line closes the last section
+    for line in mediainfo_output.lines().chain(std::iter::once("")) {
+        if line.starts_with("Text") {
+            in_text_section = true;
+        } else if line.trim().is_empty() {
+            if in_text_section {
+                let name = language.take().unwrap_or_else(|| "Unknown".to_string());
+                languages.push(if forced { format!("{} (forced)", name) } else { name });
+            }
+            in_text_section = false;
+            forced = false;
+        } else if in_text_section
+            && let Some((key, value)) = line.split_once(':')
+        {
+            match key.trim() {
+                "Language" => language = Some(value.trim().to_string()),
+                "Forced" => forced = value.trim().eq_ignore_ascii_case("yes"),
+                _ => {}
+            }
+        }
+    }
+
+    languages
+}
+
+/// External subtitle files in a release, described by the language tag in their name and
+/// their format, e.g. "en (srt)" for `Movie.2020.en.srt`.
+pub fn find_external_subtitles(input_path: &str) -> Vec<String> {
+    let mut subtitles = Vec::new();
+    for entry in WalkDir::new(input_path).sort_by_file_name().into_iter().filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+        let Some(extension) = path.extension().and_then(|ext| ext.to_str()).map(str::to_lowercase) else { continue };
+        if !SUBTITLE_EXTENSIONS.contains(&extension.as_str()) {
+            continue;
+        }
+        // The language tag is among the last parts of the name: Movie.en.srt, Movie.English.forced.srt
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+        let parts: Vec<&str> = stem.split(['.', '_', '-']).collect();
+        let forced = parts.iter().rev().take(2).any(|part| part.eq_ignore_ascii_case("forced"));
+        let language = parts
+            .iter()
+            .skip(1)
+            .rev()
+            .take(3)
+            .find(|part| {
+                let lower = part.to_lowercase();
+                ((2..=3).contains(&part.len()) && part.chars().all(|c| c.is_ascii_alphabetic()) && !["sdh", "cc", "hi"].contains(&lower.as_str()))
+                    || LANGUAGE_NAMES.contains(&lower.as_str())
+            })
+            .map(|part| part.to_string())
+            .unwrap_or_else(|| "Unknown".to_string());
+        let format = if forced { format!("{}, forced", extension) } else { extension };
+        subtitles.push(format!("{} ({})", language, format));
+    }
+    subtitles
+}
+
+pub fn create_torrent(
+    input_path: &str,
+    torrent_dir: &str,
+    announce_url: &str,
+    mkbrr_path: &str,
+    exclusions: Option<&ExclusionsConfig>, // The global exclusions when stripshit_from_videos is set
+    options: &TorrentOptions,
+) -> Result<String, SeedToolsError> {
+    fs::create_dir_all(torrent_dir)
+        .map_err(SeedToolsError::io(format!("Failed to create torrent directory '{}'", torrent_dir)))?;
+
+    let base_name = Path::new(input_path)
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+
+    let release_name = generate_release_name(&base_name);
+    let torrent_file = join_path(torrent_dir, &format!("{}.torrent", release_name));
+
+    info!("Creating torrent for input path: {}", input_path);
+    info!("Torrent File: {}", torrent_file);
+    let exclusions = exclusions
+        .map(|global| crate::torrent::Exclusions::new(options.exclusions(global)))
+        .transpose()?;
+
+    // mkbrr only writes v1 torrents
+    if options.version != TorrentVersion::V1 {
+        info!("Creating a {:?} torrent with the built-in torrent builder", options.version);
+        crate::torrent::build(
+            Path::new(input_path),
+            Path::new(&torrent_file),
+            announce_url,
+            "seedpool.org",
+            exclusions.as_ref(),
+            options,
+        )?;
+        info!("Created torrent: {}", torrent_file);
+        crate::metrics::add("seed_tools_bytes_hashed_total", &[], total_size(input_path) as f64);
+        crate::retention::track(crate::retention::Artifact::Torrent, &torrent_file);
+        return Ok(torrent_file);
+    }
+
+    // Build the mkbrr command
+    let mut command = Command::new(mkbrr_path);
+    command.args([
+        "create",
+        "-t", announce_url,
+        "-o", &torrent_file,
+        "--source", "seedpool.org",
+        input_path,
+    ]);
+
+    // mkbrr takes the piece length as a power of two
+    if let Some(length) = crate::torrent::piece_length(options, total_size(input_path))? {
+        info!("Piece size: {}", format_size(length));
+        command.args(["--piece-length", &length.trailing_zeros().to_string()]);
+    }
+
+    // Add the --exclude flag to exclude unwanted terms and non-video files, naming each file so
+    // keyword and regex exclusions reach mkbrr too
+    if let Some(exclusions) = &exclusions {
+        let excluded = crate::torrent::excluded_files(Path::new(input_path), exclusions);
+        if !excluded.is_empty() {
+            command.args(["--exclude", &crate::torrent::mkbrr_exclude_list(&excluded)]);
+        }
+    }
+
+    // Execute the mkbrr command
+    let output = command.output().map_err(|e| SeedToolsError::tool_spawn("mkbrr", e))?;
+
+    if !output.stdout.is_empty() {
+        info!("mkbrr stdout:\n{}", String::from_utf8_lossy(&output.stdout));
+    }
+    if !output.stderr.is_empty() {
+        error!("mkbrr stderr:\n{}", String::from_utf8_lossy(&output.stderr));
+    }
+
+    if !output.status.success() {
+        return Err(SeedToolsError::tool("mkbrr", format!(
+            "mkbrr failed to create torrent for input path: {}. Exit code: {}",
+            input_path,
+            output.status.code().unwrap_or(-1)
+        )));
+    }
+
+    info!("Created torrent: {}", torrent_file);
+    crate::metrics::add("seed_tools_bytes_hashed_total", &[], total_size(input_path) as f64);
+    crate::retention::track(crate::retention::Artifact::Torrent, &torrent_file);
+    Ok(torrent_file)
+}
+
+/// SHA-1 of the bencoded `info` dictionary as lowercase hex, i.e. the (v1) infohash.
+pub fn torrent_info_hash(torrent: &[u8]) -> Result<String, SeedToolsError> {
+    use sha1::{Digest, Sha1};
+
+    let raw = crate::torrent::raw_info(torrent)?;
+    Ok(Sha1::digest(raw).iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+pub fn generate_mediainfo(video_file: &str, mediainfo_path: &str) -> Result<String, SeedToolsError> {
+    let output = Command::new(mediainfo_path)
+        .args(["--Output=TEXT", video_file])
+        .output()
+        .map_err(|e| SeedToolsError::tool_spawn("mediainfo", e))?;
+
+    if !output.status.success() {
+        return Err(SeedToolsError::tool(
+            "mediainfo",
+            format!("Mediainfo command failed with status: {}", output.status),
+        ));
+    }
+
+    let mut result = String::from_utf8(output.stdout)
+        .map_err(|e| SeedToolsError::tool("mediainfo", format!("Failed to parse mediainfo output: {}", e)))?;
+
+    // Sanitize the "Complete name" field
+    if let Some(start) = result.find("Complete name")
+        && let Some(end) = result[start..].find('\n')
+    {
+        let full_line = &result[start..start + end];
+        if full_line.contains(':') {
+            let sanitized_line = format!(
+                "Complete name                            : {}",
+                Path::new(video_file).file_name().unwrap_or_default().to_string_lossy()
+            );
+            result = result.replace(full_line, &sanitized_line);
+        }
+    }
+
+    Ok(result)
+}
+
+/// Makes the content of `input_path` available under `staging_dir` with the release name as
+/// folder (or file) name, using hardlinks so no data is duplicated. Returns the path to create
+/// the torrent from: the staged copy, or `input_path` when staging is off or the name already fits.
+pub fn stage_release(input_path: &str, release_name: &str, paths: &PathsConfig) -> Result<String, SeedToolsError> {
+    let Some(staging_dir) = &paths.staging_dir else { return Ok(input_path.to_string()) };
+    let source = Path::new(input_path);
+    let target_name = match source.extension().filter(|_| source.is_file()) {
+        Some(ext) if !release_name.ends_with(&format!(".{}", ext.to_string_lossy())) => {
+            format!("{}.{}", release_name, ext.to_string_lossy())
+        }
+        _ => release_name.to_string(),
+    };
+    if source.file_name().is_some_and(|name| name.to_string_lossy() == target_name) {
+        return Ok(input_path.to_string());
+    }
+
+    let target = Path::new(staging_dir).join(&target_name);
+    info!("Staging '{}' as '{}'", input_path, target.display());
+    for entry in WalkDir::new(source) {
+        let entry = entry.map_err(SeedToolsError::io(format!("Failed to read '{}'", input_path)))?;
+        let relative = entry.path().strip_prefix(source).unwrap_or(entry.path());
+        let destination = if relative.as_os_str().is_empty() { target.clone() } else { target.join(relative) };
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&destination)
+                .map_err(SeedToolsError::io(format!("Failed to create '{}'", destination.display())))?;
+            continue;
+        }
+        // Kept from an earlier run of the same release
+        if destination.exists() {
+            continue;
+        }
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent).map_err(SeedToolsError::io(format!("Failed to create '{}'", parent.display())))?;
+        }
+        if let Err(e) = fs::hard_link(entry.path(), &destination) {
+            if !paths.staging_copy {
+                return Err(SeedToolsError::Config {
+                    message: format!(
+                        "Failed to hardlink '{}' to '{}': {}. Is staging_dir on the same filesystem? Set staging_copy to copy instead.",
+                        entry.path().display(),
+                        destination.display(),
+                        e
+                    ),
+                    source: Some(Box::new(e)),
+                });
+            }
+            warn!("Hardlinking '{}' failed ({}), copying it instead.", entry.path().display(), e);
+            fs::copy(entry.path(), &destination)
+                .map_err(SeedToolsError::io(format!("Failed to copy '{}' to '{}'", entry.path().display(), destination.display())))?;
+        }
+    }
+    Ok(target.to_string_lossy().to_string())
+}
+
+/// The save path to give a client for content staged by `stage_release`; `None` keeps the
+/// client's default save path.
+fn staged_save_path(input_path: &str, paths: &PathsConfig) -> Option<String> {
+    let staging_dir = paths.staging_dir.as_deref()?;
+    Path::new(input_path).starts_with(staging_dir).then(|| staging_dir.to_string())
+}
+
+pub fn add_torrent_to_all_qbittorrent_instances(
+    torrent_files: &[String],
+    qbittorrent_configs: &[QbittorrentConfig],
+    deluge_config: &DelugeConfig,
+    input_path: &str,
+    paths_config: &PathsConfig,
+    offline: Option<&Offline>,
+) -> Result<Vec<String>, SeedToolsError> {
+    // Offline runs only "seed" in the mock client, which checks the data
+    if let Some(offline) = offline {
+        return Ok(add_torrent_to_clients(torrent_files, &[&offline.client], input_path));
+    }
+    info!("Adding torrents to all qBittorrent and Deluge instances.");
+    let mut clients: Vec<Box<dyn TorrentClient + '_>> = Vec::new();
+    for config in qbittorrent_configs {
+        if config.executable.is_some() {
+            clients.push(Box::new(Qbittorrent { config }));
+        } else {
+            error!(
+                "No executable specified for qBittorrent instance '{}'. Skipping.",
+                config.webui_url
+            );
+        }
+    }
+    clients.push(Box::new(Deluge { config: deluge_config, paths: paths_config }));
+    let clients: Vec<&dyn TorrentClient> = clients.iter().map(|client| client.as_ref()).collect();
+    Ok(add_torrent_to_clients(torrent_files, &clients, input_path))
+}
+
+/// Adds every torrent to every client and returns one line per client and torrent for the
+/// upload report, e.g. "qBittorrent at ...: seeding". A failing client does not stop the others.
+pub fn add_torrent_to_clients(torrent_files: &[String], clients: &[&dyn TorrentClient], input_path: &str) -> Vec<String> {
+    let mut states = Vec::new();
+    for client in clients {
+        let client_name = client.name();
+        for torrent_file in torrent_files {
+            match client.add_torrent(torrent_file, input_path) {
+                Ok(state) => {
+                    info!("Successfully added torrent '{}' to {}.", torrent_file, client_name);
+                    log_state(&client_name, &state);
+                    states.push(format!("{}: {}", client_name, state));
+                }
+                Err(e) => {
+                    error!("Error adding torrent '{}' to {}: {}", torrent_file, client_name, e);
+                    states.push(format!("{}: not added: {}", client_name, e));
+                }
+            }
+        }
+    }
+    states
+}
+
+/// A qBittorrent instance from config.yaml.
+pub struct Qbittorrent<'a> {
+    pub config: &'a QbittorrentConfig,
+}
+
+impl TorrentClient for Qbittorrent<'_> {
+    fn name(&self) -> String {
+        format!("qBittorrent at {}", self.config.webui_url)
+    }
+
+    fn add_torrent(&self, torrent_file: &str, input_path: &str) -> Result<SeedingState, SeedToolsError> {
+        add_torrent_to_qbittorrent(torrent_file, self.config, input_path)
+    }
+}
+
+/// The Deluge instance from config.yaml; `paths` maps staged content to its save path.
+pub struct Deluge<'a> {
+    pub config: &'a DelugeConfig,
+    pub paths: &'a PathsConfig,
+}
+
+impl TorrentClient for Deluge<'_> {
+    fn name(&self) -> String {
+        format!("Deluge at {}", self.config.webui_url)
+    }
+
+    fn add_torrent(&self, torrent_file: &str, input_path: &str) -> Result<SeedingState, SeedToolsError> {
+        add_torrent_to_deluge(torrent_file, self.config, input_path, self.paths)
+    }
+}
+
+pub fn process_file(
+    file_path: &Path,
+    video_files: &mut Vec<String>,
+    nfo_file: &mut Option<String>,
+    supported_extensions: &[&str],
+    excluded_keywords: &[String],
+) -> Result<(), SeedToolsError> {
+    let file_name = file_path.file_name().unwrap_or_default().to_string_lossy().to_string();
+
+    if let Some(ext) = file_path.extension() {
+        let ext = ext.to_string_lossy().to_lowercase();
+        if supported_extensions.contains(&ext.as_str()) {
+            video_files.push(file_path.to_string_lossy().to_string());
+        } else if ext == "nfo" && nfo_file.is_none() {
+            *nfo_file = Some(file_path.to_string_lossy().to_string());
+        }
+    } else if contains_excluded_keywords(&file_name, excluded_keywords) {
+        info!("Excluding file due to keywords: {}", file_name);
+    }
+
+    Ok(())
+}
+
+/// Total size in bytes of a file, or of all files below a directory.
+pub fn total_size(path: &str) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+/// Formats a byte count like "4.37 GiB".
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.2} {}", size, UNITS[unit])
+    }
+}
+
+pub fn contains_excluded_keywords(name: &str, keywords: &[String]) -> bool {
+    let lowercase_name = name.to_lowercase();
+    let result = keywords.iter().any(|keyword| lowercase_name.contains(&keyword.to_lowercase()));
+    info!("Checking if '{}' contains excluded keywords: {}", name, result);
+    result
+}
+
+pub fn generate_sample(
+    video_file: &str,
+    screenshots_dir: &str,
+    screenshots: &SeedpoolScreenshots, // Where the sample goes on the CDN and its name template
+    tools: &Tools,
+    input_name: &str,
+    sample: &SampleConfig,
+    offline: Option<&Offline>,
+) -> Result<String, SeedToolsError> {
+    // An empty URL leaves the sample out of the description
+    let duration = get_video_duration(video_file, &tools.ffprobe.to_string_lossy())?;
+    let Some(sample_start) = sample_start(duration, sample) else {
+        info!("Skipping the sample: '{}' is only {:.0} seconds long.", video_file, duration);
+        return Ok(String::new());
+    };
+
+    let sanitized_input_name = generate_release_name(input_name);
+    let sample_file = join_path(screenshots_dir, &format!("{}.sample.mkv", sanitized_input_name));
+    // The sample's share of the video, by duration
+    let video_size = fs::metadata(video_file).map(|metadata| metadata.len()).unwrap_or(0);
+    let expected_size = (video_size as f64 * (sample.duration_secs as f64 / duration).min(1.0)) as u64;
+    crate::space::ensure_free(crate::space::Stage::Sample, Path::new(screenshots_dir), expected_size)?;
+
+    // Generate the sample file; paths go in as plain arguments, never through a shell
+    let mut command = Command::new(&tools.ffmpeg);
+    command
+        .args(["-y", "-i"])
+        .arg(ffmpeg_file_arg(video_file))
+        .args(["-ss", &sample_start.to_string(), "-t", &sample.duration_secs.to_string()]);
+    for map in &sample.map {
+        command.args(["-map", map]);
+    }
+    let output = command
+        .args(["-c", "copy"])
+        .arg(ffmpeg_file_arg(&sample_file))
+        .output()
+        .map_err(|e| SeedToolsError::tool_spawn("ffmpeg", e))?;
+
+    if !output.status.success() {
+        let _ = fs::remove_file(&sample_file); // A partial sample
+        return Err(SeedToolsError::tool("ffmpeg", format!(
+            "Failed to generate sample file. ffmpeg output: {}",
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+
+    publish_sample(&sample_file, screenshots_dir, &screenshots.remote_path, &screenshots.image_path, &screenshots.file_name, &sanitized_input_name, offline)
+}
+
+/// Uploads a sample shipped with the release instead of cutting a new one.
+pub fn upload_existing_sample(
+    existing_sample: &str,
+    screenshots_dir: &str,
+    remote_path: &str,
+    image_path: &str,
+    file_name: &str,
+    input_name: &str,
+    offline: Option<&Offline>,
+) -> Result<String, SeedToolsError> {
+    let sanitized_input_name = generate_release_name(input_name);
+    let extension = Path::new(existing_sample).extension().unwrap_or_default().to_string_lossy().to_lowercase();
+    let sample_file = join_path(screenshots_dir, &format!("{}.sample.{}", sanitized_input_name, extension));
+    fs::copy(existing_sample, &sample_file)
+        .map_err(SeedToolsError::io(format!("Failed to copy sample '{}'", existing_sample)))?;
+    publish_sample(&sample_file, screenshots_dir, remote_path, image_path, file_name, &sanitized_input_name, offline)
+}
+
+/// Names a sample in `screenshots_dir` after the template, uploads it and returns its URL.
+fn publish_sample(
+    sample_file: &str,
+    screenshots_dir: &str,
+    remote_path: &str,
+    image_path: &str,
+    file_name: &str,
+    sanitized_input_name: &str,
+    offline: Option<&Offline>,
+) -> Result<String, SeedToolsError> {
+    // Set permissions to 777
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(sample_file, fs::Permissions::from_mode(0o777))
+            .map_err(SeedToolsError::io(format!("Failed to set permissions for sample file '{}'", sample_file)))?;
+    }
+
+    // Rename so a re-run never overwrites a sample linked from an older description
+    let extension = Path::new(sample_file).extension().unwrap_or_default().to_string_lossy().to_string();
+    let stem = artifact_stem(file_name, sanitized_input_name, sample_file)?;
+    let named_file = join_path(screenshots_dir, &format!("{}.sample.{}", stem, extension));
+    if let Err(e) = fs::rename(sample_file, &named_file) {
+        let _ = fs::remove_file(sample_file);
+        return Err(SeedToolsError::io(format!("Failed to rename sample file '{}'", sample_file))(e));
+    }
+
+    // Upload the sample file; one that did not make it to the CDN is not kept
+    if let Err(e) = upload_to_cdn(&named_file, remote_path, offline) {
+        let _ = fs::remove_file(&named_file);
+        return Err(e);
+    }
+    crate::retention::track(crate::retention::Artifact::Sample, &named_file);
+
+    // Return the public-facing URL for the sample
+    Ok(format!("{}/{}.sample.{}", image_path, stem, extension))
+}
+
+const EXISTING_SAMPLE_MAX_DURATION: f64 = 300.0; // Longer videos named like samples are real videos
+
+/// What happens to a video in the release that is named like a sample.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExistingSample {
+    /// A short clip of the main video: left out of the torrent and uploaded as the sample.
+    Reuse(String),
+    /// Not a clip of the main video (another codec, or unreadable): left out, and a new sample is cut.
+    Regenerate(String, String), // File, reason
+    /// Too long to be a sample: kept as one of the release's videos.
+    Keep(String, String), // File, reason
+}
+
+/// Whether a path below the release folder has "sample" in a file or folder name.
+fn named_like_sample(input_path: &str, video_file: &str) -> bool {
+    let path = Path::new(video_file);
+    let relative = path.strip_prefix(input_path).unwrap_or(path);
+    relative.iter().any(|part| part.to_string_lossy().to_lowercase().contains("sample"))
+}
+
+/// Codec of the first video stream, e.g. "h264".
+pub fn video_codec(video_file: &str, ffprobe_path: &str) -> Result<String, SeedToolsError> {
+    let output = match Command::new(ffprobe_path)
+        .args(["-v", "error", "-select_streams", "v:0", "-show_entries", "stream=codec_name", "-of", "default=noprint_wrappers=1:nokey=1"])
+        .arg(ffmpeg_file_arg(video_file))
+        .output()
+    {
+        Ok(output) => output,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return crate::probe::video_codec(Path::new(video_file))
+                .map_err(|fallback| SeedToolsError::tool("ffprobe", format!("Failed to run ffprobe: {}; {}", e, fallback)));
+        }
+        Err(e) => return Err(SeedToolsError::tool_spawn("ffprobe", e)),
+    };
+    if !output.status.success() {
+        return Err(SeedToolsError::tool("ffprobe", format!("ffprobe failed: {}", String::from_utf8_lossy(&output.stderr).trim())));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Checks the videos named like samples against the main (largest) video of the release:
+/// returns the videos to process, without the real samples, and what was decided for each
+/// sample-named video.
+pub fn check_existing_samples(input_path: &str, video_files: &[String], ffprobe_path: &str) -> (Vec<String>, Vec<ExistingSample>) {
+    let (samples, mut videos): (Vec<String>, Vec<String>) =
+        video_files.iter().cloned().partition(|video_file| named_like_sample(input_path, video_file));
+    if samples.is_empty() || videos.is_empty() {
+        return (video_files.to_vec(), Vec::new());
+    }
+    let main_video = videos
+        .iter()
+        .max_by_key(|video_file| fs::metadata(video_file).map(|metadata| metadata.len()).unwrap_or(0))
+        .cloned()
+        .unwrap_or_default();
+    let main_codec = video_codec(&main_video, ffprobe_path).ok();
+
+    let mut decisions = Vec::new();
+    for sample in samples {
+        let decision = match (get_video_duration(&sample, ffprobe_path), video_codec(&sample, ffprobe_path)) {
+            (Err(e), _) | (_, Err(e)) => ExistingSample::Regenerate(sample, format!("unreadable: {}", e)),
+            (Ok(duration), _) if duration > EXISTING_SAMPLE_MAX_DURATION => {
+                ExistingSample::Keep(sample, format!("runs {:.0} minutes", duration / 60.0))
+            }
+            (Ok(_), Ok(codec)) if main_codec.as_deref().is_some_and(|main_codec| main_codec != codec) => {
+                let reason = format!("{} instead of the main video's {}", codec, main_codec.clone().unwrap_or_default());
+                ExistingSample::Regenerate(sample, reason)
+            }
+            _ => ExistingSample::Reuse(sample),
+        };
+        match &decision {
+            ExistingSample::Reuse(file) => info!("Existing sample '{}' is a clip of '{}'; uploading it as the sample.", file, main_video),
+            ExistingSample::Regenerate(file, reason) => warn!("'{}' is not a sample of '{}' ({}); cutting a new one.", file, main_video, reason),
+            ExistingSample::Keep(file, reason) => {
+                warn!("'{}' is named like a sample but {}; keeping it as a video of the release.", file, reason);
+                videos.push(file.clone());
+            }
+        }
+        decisions.push(decision);
+    }
+    (videos, decisions)
+}
+
+/// Where the sample starts: `start_secs` in (five minutes by default), or the middle of videos
+/// too short for that. `None` when the video is too short for a sample to be useful.
+pub fn sample_start(duration: f64, sample: &SampleConfig) -> Option<u32> {
+    if !duration.is_finite() || duration < sample.min_video_secs.max(sample.duration_secs) as f64 {
+        return None;
+    }
+    let latest_start = (duration as u32).saturating_sub(sample.duration_secs);
+    Some(if latest_start >= sample.start_secs + sample.duration_secs {
+        sample.start_secs
+    } else {
+        latest_start / 2
+    })
+}
+
+/// Fills the artifact name template: `{name}` is the release name, `{timestamp}` the
+/// current UTC time and `{hash}` a short hash of the artifact's content.
+pub fn artifact_stem(template: &str, name: &str, content_file: &str) -> Result<String, SeedToolsError> {
+    let mut stem = template
+        .replace("{name}", name)
+        .replace("{timestamp}", &chrono::Utc::now().format("%Y%m%d%H%M%S").to_string());
+    if stem.contains("{hash}") {
+        let content = fs::read(content_file)
+            .map_err(SeedToolsError::io(format!("Failed to read '{}' for hashing", content_file)))?;
+        // FNV-1a; only needs to tell different screenshots of the same release apart
+        let hash = content
+            .iter()
+            .fold(0xcbf29ce484222325u64, |hash, byte| (hash ^ *byte as u64).wrapping_mul(0x100000001b3));
+        stem = stem.replace("{hash}", &format!("{:016x}", hash)[..8]);
+    }
+    Ok(stem)
+}
+
+pub fn generate_description(
+    screenshots: &[String],
+    sample_url: &str,
+    subtitles: &[String], // Subtitle languages, embedded and external
+    mkv_extras: Option<&MkvExtras>, // Chapters and attachments of a single film or episode
+    file_tree: &str, // See `render_file_tree`; empty for single-file releases
+    custom_description: Option<&str>,
+    youtube_trailer_url: Option<&str>,
+) -> String {
+    let mut description = String::new();
+
+    // Add screenshots in a 2x2 table pattern
+    if !screenshots.is_empty() {
+        description.push_str("[center][tr]\n");
+
+        for (i, screenshot) in screenshots.iter().enumerate() {
+            description.push_str(&format!(
+                "        [td][url={}][img width=720]{}[/img][/url][/td]\n",
+                screenshot, screenshot
+            ));
+
+            // Add a new row every 2 images
+            if (i + 1) % 2 == 0 {
+                description.push_str("    [/tr]\n    [tr]\n");
+            }
+        }
+
+        // Close the last row properly
+        if !screenshots.len().is_multiple_of(2) {
+            description.push_str("    [/center][/tr]\n");
+        }
+    }
+
+    // Add a blank line after screenshots
+    description.push('\n');
+
+    // Add sample link if available
+    if !sample_url.is_empty() {
+        description.push_str(&format!(
+            "[b][spoiler=Sample: {}]{}[/spoiler][/b]\n\n",
+            Path::new(sample_url).file_name().unwrap_or_default().to_string_lossy(),
+            sample_url
+        ));
+    }
+
+    if !subtitles.is_empty() {
+        description.push_str(&format!("[b]Subtitles:[/b] {}\n\n", subtitles.join(", ")));
+    }
+
+    if let Some(extras) = mkv_extras {
+        description.push_str(&format_mkv_extras(extras));
+    }
+
+    if !file_tree.is_empty() {
+        description.push_str(file_tree);
+        description.push('\n');
+    }
+
+    // Add YouTube trailer link if available
+    if let Some(trailer_url) = youtube_trailer_url {
+        description.push_str(&format!(
+            "[center][b][url={}][Trailer on YouTube][/url][/b][/center]\n\n",
+            trailer_url
+        ));
+    }
+
+    // Add custom description (not centered)
+    if let Some(custom_desc) = custom_description {
+        description.push_str(custom_desc);
+        description.push_str("\n\n");
+    }
+
+    // Append the default non-video description
+    description.push_str(&default_non_video_description());
+
+    description
+}
+
+/// GETs `path` (with its query) from the TMDB API. `tmdb_api_key` may be a v3 API key or a v4
+/// read access token, which is sent as a bearer token instead of in the URL.
+fn tmdb_get(path: &str, tmdb_api_key: &str) -> crate::error::Result<reqwest::blocking::Response> {
+    let separator = if path.contains('?') { '&' } else { '?' };
+    let bearer = tmdb_api_key.starts_with("eyJ"); // v4 tokens are JWTs
+    let url = if bearer {
+        format!("https://api.themoviedb.org/3/{}", path)
+    } else {
+        format!("https://api.themoviedb.org/3/{}{}api_key={}", path, separator, tmdb_api_key)
+    };
+    info!("TMDB API URL: https://api.themoviedb.org/3/{}", path);
+    crate::http::send_with_retry("tmdb", |client| {
+        let request = client.get(&url);
+        if bearer {
+            request.bearer_auth(tmdb_api_key)
+        } else {
+            request
+        }
+    })
+}
+
+pub fn fetch_tmdb_id(title: &str, year: Option<String>, tmdb_api_key: &str, release_type: &str) -> Result<u32, SeedToolsError> {
+    let sanitized_title = if release_type == "tv" {
+        // Extract everything before the SXX* pattern
+        let season_regex = Regex::new(r"(?i)(S\d{2}.*)").unwrap();
+        let cleaned_title = season_regex.replace(title, "").trim().to_string();
+
+        // Remove the year if present
+        let year_regex = Regex::new(r"\b(19|20)\d{2}\b").unwrap();
+        year_regex.replace(&cleaned_title, "").trim().to_string()
+    } else {
+        // For movies, extract everything before the year
+        let year_regex = Regex::new(r"\b(19|20)\d{2}\b").unwrap();
+        year_regex.replace(title, "").trim().to_string()
+    };
+
+    let encoded_title = urlencoding::encode(&sanitized_title);
+
+    let path = if release_type == "tv" {
+        format!("search/tv?query={}&first_air_date_year={}", encoded_title, year.unwrap_or_default())
+    } else {
+        format!("search/movie?query={}&year={}", encoded_title, year.unwrap_or_default())
+    };
+
+    let response = tmdb_get(&path, tmdb_api_key).map_err(|e| e.context(&format!("Failed to query TMDB for '{}'", title)))?;
+
+    if !response.status().is_success() {
+        return Err(SeedToolsError::tracker(
+            "TMDB",
+            Some(response.status().as_u16()),
+            format!("TMDB API request failed with status: {}", response.status()),
+        ));
+    }
+
+    let json: Value = response
+        .json()
+        .map_err(|e| SeedToolsError::request("TMDB", e).context(&format!("Failed to parse TMDB response for '{}'", title)))?;
+
+    let tmdb_id = json["results"]
+        .as_array()
+        .and_then(|results| results.first())
+        .and_then(|result| result["id"].as_u64())
+        .unwrap_or(0) as u32;
+
+    if tmdb_id == 0 {
+        info!("No TMDB ID found for '{}'.", title);
+    }
+
+    Ok(tmdb_id)
+}
+
+pub fn fetch_youtube_trailer(title: &str, year: Option<&str>, youtube_api_key: &str) -> Result<String, SeedToolsError> {
+    let client = crate::http::client();
+
+    // Construct the search query
+    let query = if let Some(year) = year {
+        format!("{} {} trailer", title, year)
+    } else {
+        format!("{} trailer", title)
+    };
+
+    // Construct the YouTube Data API URL
+    let url = format!(
+        "https://www.googleapis.com/youtube/v3/search?part=snippet&q={}&type=video&key={}&maxResults=1",
+        urlencoding::encode(&query),
+        youtube_api_key
+    );
+
+    // Send the API request
+    let response = client
+        .get(&url)
+        .send()
+        .map_err(|e| SeedToolsError::request("YouTube", e).context("Failed to send request to YouTube API"))?;
+
+    if !response.status().is_success() {
+        return Err(SeedToolsError::tracker(
+            "YouTube",
+            Some(response.status().as_u16()),
+            format!("YouTube API request failed with status: {}", response.status()),
+        ));
+    }
+
+    // Parse the JSON response
+    let response_body = response.text().map_err(|e| SeedToolsError::request("YouTube", e).context("Failed to read YouTube API response"))?;
+    let json: Value = serde_json::from_str(&response_body)
+        .map_err(|e| SeedToolsError::tracker("YouTube", None, format!("Failed to parse YouTube API response: {}", e)))?;
+
+    // Extract the video ID of the first result
+    if let Some(video_id) = json["items"]
+        .as_array()
+        .and_then(|items| items.first())
+        .and_then(|item| item["id"]["videoId"].as_str())
+    {
+        let video_url = format!("https://www.youtube.com/watch?v={}", video_id);
+        Ok(video_url)
+    } else {
+        Err(SeedToolsError::Metadata("No trailer found on YouTube.".to_string()))
+    }
+}
+
+pub fn fetch_external_ids(tmdb_id: u32, release_type: &str, tmdb_api_key: &str) -> Result<(Option<String>, Option<u32>), SeedToolsError> {
+    if tmdb_id == 0 {
+        return Ok((None, None));
+    }
+
+    let tmdb_type = if release_type == "boxset" { "tv" } else { release_type };
+    let response = tmdb_get(&format!("{}/{}/external_ids", tmdb_type, tmdb_id), tmdb_api_key)
+        .map_err(|e| e.context("Failed to fetch external IDs"))?;
+
+    if !response.status().is_success() {
+        return Err(SeedToolsError::tracker("TMDB", Some(response.status().as_u16()), format!("Failed to fetch external IDs: HTTP {}", response.status())));
+    }
+
+    let json: serde_json::Value = response.json().map_err(|e| SeedToolsError::request("TMDB", e).context("Failed to parse external IDs response"))?;
+    let imdb_id = json["imdb_id"].as_str().map(|s| s.trim_start_matches("tt").to_string());
+    let tvdb_id = json["tvdb_id"].as_u64().map(|id| id as u32);
+
+    log::info!("Fetched IMDb ID: {:?}", imdb_id);
+    log::info!("Fetched TVDB ID: {:?}", tvdb_id);
+
+    Ok((imdb_id, tvdb_id))
+}
+
+/// Title and air date of one episode on TMDB.
+pub struct EpisodeDetails {
+    pub title: String,
+    pub air_date: Option<String>, // YYYY-MM-DD
+}
+
+impl EpisodeDetails {
+    /// "S02E05 – Episode Title (2023-04-01)", the description header of an episode upload.
+    pub fn header(&self, season: u32, episode: u32) -> String {
+        let mut header = format!("S{:02}E{:02} – {}", season, episode, self.title);
+        if let Some(air_date) = &self.air_date {
+            header.push_str(&format!(" ({})", air_date));
+        }
+        header
+    }
+}
+
+pub fn fetch_episode_details(tmdb_id: u32, season: u32, episode: u32, tmdb_api_key: &str) -> Result<EpisodeDetails, SeedToolsError> {
+    let response = tmdb_get(&format!("tv/{}/season/{}/episode/{}", tmdb_id, season, episode), tmdb_api_key)
+        .map_err(|e| e.context("Failed to fetch episode details"))?;
+    if !response.status().is_success() {
+        return Err(SeedToolsError::tracker("TMDB", Some(response.status().as_u16()), format!("Failed to fetch episode details: HTTP {}", response.status())));
+    }
+    let json: serde_json::Value = response.json().map_err(|e| SeedToolsError::request("TMDB", e).context("Failed to parse episode details"))?;
+    let title = json["name"].as_str().map(str::trim).filter(|title| !title.is_empty())
+        .ok_or_else(|| SeedToolsError::Metadata("TMDB has no title for this episode".to_string()))?;
+    Ok(EpisodeDetails {
+        title: title.to_string(),
+        air_date: json["air_date"].as_str().filter(|date| !date.is_empty()).map(|date| date.to_string()),
+    })
+}
+
+/// Puts `episode_title` after the SxxEyy of `release_name`, scene style:
+/// `Show.S02E05.1080p...` becomes `Show.S02E05.Episode.Title.1080p...`. Names that already
+/// carry the title, or have no episode number, are returned as they are.
+pub fn add_episode_title(release_name: &str, episode_title: &str) -> String {
+    let title = generate_release_name(episode_title);
+    if title.is_empty() || release_name.to_lowercase().contains(&title.to_lowercase()) {
+        return release_name.to_string();
+    }
+    Regex::new(r"(?i)\bS\d{2,}E\d{2,}\b")
+        .unwrap()
+        .replace(release_name, |captures: &regex::Captures| format!("{}.{}", &captures[0], title))
+        .to_string()
+}
+
+/// Detected upload details, shown as defaults when the user reviews them with `--edit`.
+pub struct DetectedUploadDetails {
+    pub release_name: String,
+    pub tmdb_id: u32,
+    pub imdb_id: Option<String>,
+    pub tvdb_id: Option<u32>,
+    pub season_number: Option<u32>,
+    pub episode_number: Option<u32>,
+    pub category_id: u32,
+    pub type_id: u32,
+}
+
+/// Prompts for every editable upload detail on the terminal, pre-filled with the current
+/// override or the detected value. Returns the confirmed values as overrides.
+pub fn prompt_upload_overrides(
+    detected: &DetectedUploadDetails,
+    overrides: &UploadOverrides,
+) -> Result<UploadOverrides, SeedToolsError> {
+    println!("Review upload details (press Enter to keep a value, clear it to unset optional IDs):");
+
+    let prompt = |label: &str, current: String| -> Result<String, SeedToolsError> {
+        Input::<String>::new()
+            .with_prompt(label)
+            .with_initial_text(current)
+            .allow_empty(true)
+            .interact_text()
+            .map(|value| value.trim().to_string())
+            .map_err(SeedToolsError::io(format!("Failed to read {}", label)))
+    };
+    let parse_id = |label: &str, value: String| -> Result<Option<u32>, SeedToolsError> {
+        if value.is_empty() || value == "0" {
+            Ok(None)
+        } else {
+            value
+                .parse::<u32>()
+                .map(Some)
+                .map_err(|_| SeedToolsError::config(format!("Invalid {}: '{}' is not a number", label, value)))
+        }
+    };
+    let show = |value: Option<u32>| value.map(|v| v.to_string()).unwrap_or_default();
+
+    let release_name = prompt(
+        "Release name",
+        overrides.release_name.clone().unwrap_or_else(|| detected.release_name.clone()),
+    )?;
+    let tmdb_id = prompt("TMDB ID", show(overrides.tmdb_id.or(Some(detected.tmdb_id))))?;
+    let imdb_id = prompt(
+        "IMDb ID",
+        overrides.imdb_id.clone().or_else(|| detected.imdb_id.clone()).unwrap_or_default(),
+    )?;
+    let tvdb_id = prompt("TVDB ID", show(overrides.tvdb_id.or(detected.tvdb_id)))?;
+    let season_number = prompt("Season", show(overrides.season_number.or(detected.season_number)))?;
+    let episode_number = prompt("Episode", show(overrides.episode_number.or(detected.episode_number)))?;
+    let category_id = prompt("Category ID", show(overrides.category_id.or(Some(detected.category_id))))?;
+    let type_id = prompt("Type ID", show(overrides.type_id.or(Some(detected.type_id))))?;
+
+    let edited = UploadOverrides {
+        release_name: if release_name.is_empty() { None } else { Some(release_name) },
+        tmdb_id: parse_id("TMDB ID", tmdb_id)?,
+        imdb_id: if imdb_id.is_empty() { None } else { Some(imdb_id) },
+        tvdb_id: parse_id("TVDB ID", tvdb_id)?,
+        season_number: season_number.parse::<u32>().ok(),
+        episode_number: episode_number.parse::<u32>().ok(),
+        category_id: parse_id("Category ID", category_id)?,
+        type_id: parse_id("Type ID", type_id)?,
+        release_type: overrides.release_type.clone(),
+    };
+    info!("Upload details after review: {:?}", edited);
+    Ok(edited)
+}
+
+pub fn generate_screenshots(
+    video_file: &str,
+    output_dir: &str,
+    tools: &Tools,
+    screenshots: &SeedpoolScreenshots, // Where the images go on the CDN and their name template
+    input_name: &str,
+    blur_thumbnails: bool,
+    offline: Option<&Offline>,
+) -> Result<(Vec<String>, Vec<String>), SeedToolsError> {
+    let mut screenshots_list = Vec::new();
+    let mut thumbnails_list = Vec::new();
+
+    // Ensure the output directory exists
+    fs::create_dir_all(output_dir).map_err(SeedToolsError::io("Failed to create output directory"))?;
+    crate::space::ensure_free(crate::space::Stage::Screenshots, Path::new(output_dir), 0)?;
+
+    let sanitized_input_name = generate_release_name(input_name); // Sanitize the input name
+    let duration = get_video_duration(video_file, &tools.ffprobe.to_string_lossy())?;
+    let timestamps = screenshot_timestamps(video_file, duration);
+
+    for (i, shot_time) in timestamps.iter().enumerate() {
+        // Generate the screenshot, then name it and its thumbnail from the template
+        let raw_file = join_path(output_dir, &format!("{}_{}.raw.jpg", sanitized_input_name, i + 1));
+        generate_screenshot(video_file, &tools.ffmpeg.to_string_lossy(), shot_time, &raw_file)?;
+        let stem = artifact_stem(&screenshots.file_name, &sanitized_input_name, &raw_file)?;
+        let screenshot_file = join_path(output_dir, &format!("{}_{}.jpg", stem, i + 1));
+        let thumbnail_file = join_path(output_dir, &format!("{}_{}_thumb.jpg", stem, i + 1));
+        fs::rename(&raw_file, &screenshot_file)
+            .map_err(SeedToolsError::io(format!("Failed to rename screenshot '{}'", raw_file)))?;
+        generate_thumbnail(&tools.ffmpeg.to_string_lossy(), &screenshot_file, &thumbnail_file, blur_thumbnails)?;
+        crate::retention::track(crate::retention::Artifact::Screenshot, &screenshot_file);
+        crate::retention::track(crate::retention::Artifact::Screenshot, &thumbnail_file);
+
+        // Set permissions to 777 for the screenshot and thumbnail locally
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&screenshot_file, fs::Permissions::from_mode(0o777))
+                .map_err(SeedToolsError::io(format!("Failed to set permissions for {}", screenshot_file)))?;
+            fs::set_permissions(&thumbnail_file, fs::Permissions::from_mode(0o777))
+                .map_err(SeedToolsError::io(format!("Failed to set permissions for {}", thumbnail_file)))?;
+        }
+
+        // Upload files to the CDN
+        upload_to_cdn(&screenshot_file, &screenshots.remote_path, offline)?;
+        upload_to_cdn(&thumbnail_file, &screenshots.remote_path, offline)?;
+
+        // Add public-facing URLs to the lists
+        screenshots_list.push(format!("{}/{}", screenshots.image_path, Path::new(&screenshot_file).file_name().unwrap().to_string_lossy()));
+        thumbnails_list.push(format!("{}/{}", screenshots.image_path, Path::new(&thumbnail_file).file_name().unwrap().to_string_lossy()));
+    }
+
+    Ok((screenshots_list, thumbnails_list))
+}
+
+/// Takes screenshots like `generate_screenshots` into `output_dir` without uploading them, for
+/// previews. Returns their absolute paths.
+pub fn capture_screenshots(
+    video_file: &str,
+    output_dir: &str,
+    ffmpeg_path: &str,
+    ffprobe_path: &str,
+    input_name: &str,
+) -> Result<Vec<String>, SeedToolsError> {
+    fs::create_dir_all(output_dir).map_err(SeedToolsError::io("Failed to create output directory"))?;
+    crate::space::ensure_free(crate::space::Stage::Screenshots, Path::new(output_dir), 0)?;
+    let sanitized_input_name = generate_release_name(input_name);
+    let duration = get_video_duration(video_file, ffprobe_path)?;
+    screenshot_timestamps(video_file, duration)
+        .iter()
+        .enumerate()
+        .map(|(i, shot_time)| {
+            let screenshot_file = join_path(output_dir, &format!("{}_preview_{}.jpg", sanitized_input_name, i + 1));
+            generate_screenshot(video_file, ffmpeg_path, shot_time, &screenshot_file)?;
+            Ok(fs::canonicalize(&screenshot_file)
+                .map(|path| path.to_string_lossy().to_string())
+                .unwrap_or(screenshot_file))
+        })
+        .collect()
+}
+
+/// Grabs a frame from the middle of `video_file`, stamps `username` and the current time on it
+/// and copies it to `remote_path`. Returns the image URL below `url`.
+pub fn create_proof_image(
+    video_file: &str,
+    output_dir: &str,
+    tools: &Tools,
+    proof: &ProofConfig, // With remote_path, url and username set
+    release_name: &str,
+    offline: Option<&Offline>,
+) -> Result<String, SeedToolsError> {
+    let remote_path = proof.remote_path.as_deref().ok_or_else(|| SeedToolsError::config("proof.remote_path is not set"))?;
+    let url = proof.url.as_deref().ok_or_else(|| SeedToolsError::config("proof.url is not set"))?;
+    let username = proof.username.as_deref().ok_or_else(|| SeedToolsError::config("proof.username is not set"))?;
+    fs::create_dir_all(output_dir).map_err(SeedToolsError::io("Failed to create output directory"))?;
+    let name = generate_release_name(release_name);
+    let duration = get_video_duration(video_file, &tools.ffprobe.to_string_lossy())?;
+
+    // drawtext reads the text from a file, which avoids escaping user names for the filter graph
+    let text_file = join_path(output_dir, &format!("{}.proof.txt", name));
+    fs::write(&text_file, format!("{} - {}", username, chrono::Utc::now().format("%Y-%m-%d %H:%M UTC")))
+        .map_err(SeedToolsError::io(format!("Failed to write '{}'", text_file)))?;
+    let mut filter = format!(
+        "drawtext=textfile={}:expansion=none:fontcolor=white:fontsize=h/20:box=1:boxcolor=black@0.6:boxborderw=12:x=(w-text_w)/2:y=h-text_h-h/12",
+        escape_filter_value(&text_file)
+    );
+    if let Some(font_file) = &proof.font_file {
+        filter.push_str(&format!(":fontfile={}", escape_filter_value(font_file)));
+    }
+
+    let raw_file = join_path(output_dir, &format!("{}.proof.raw.jpg", name));
+    let output = Command::new(&tools.ffmpeg)
+        .args([
+            "-y", "-loglevel", "error", "-ss", &((duration / 2.0) as u32).to_string(),
+            "-i", &ffmpeg_file_arg(video_file), "-vframes", "1", "-vf", &filter, "-qscale:v", "2",
+            &ffmpeg_file_arg(&raw_file),
+        ])
+        .output()
+        .map_err(|e| SeedToolsError::tool_spawn("ffmpeg", e))?;
+    let _ = fs::remove_file(&text_file);
+    if !output.status.success() {
+        return Err(SeedToolsError::tool("ffmpeg", format!("ffmpeg failed to create the proof image: {}", String::from_utf8_lossy(&output.stderr).trim())));
+    }
+
+    // The content hash keeps the name hard to guess, so only people given the link see it
+    let proof_file = join_path(output_dir, &format!("{}.jpg", artifact_stem("{name}.proof.{hash}", &name, &raw_file)?));
+    fs::rename(&raw_file, &proof_file).map_err(SeedToolsError::io(format!("Failed to rename proof image '{}'", raw_file)))?;
+    upload_to_cdn(&proof_file, remote_path, offline)?;
+    Ok(format!(
+        "{}/{}",
+        url.trim_end_matches('/'),
+        Path::new(&proof_file).file_name().unwrap_or_default().to_string_lossy()
+    ))
+}
+
+pub fn get_video_duration(video_file: &str, ffprobe_path: &str) -> Result<f64, SeedToolsError> {
+    let ffprobe_output = match Command::new(ffprobe_path)
+        .args([
+            "-v", "error",
+            "-show_entries", "format=duration",
+            "-of", "default=noprint_wrappers=1:nokey=1",
+            &ffmpeg_file_arg(video_file),
+        ])
+        .output()
+    {
+        Ok(output) => output,
+        // No ffprobe (e.g. a static build in a bare container): read the container headers instead
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return crate::probe::duration(Path::new(video_file))
+                .map_err(|fallback| SeedToolsError::tool("ffprobe", format!("Failed to run ffprobe: {}; {}", e, fallback)));
+        }
+        Err(e) => return Err(SeedToolsError::tool_spawn("ffprobe", e)),
+    };
+
+    if !ffprobe_output.status.success() {
+        return Err(SeedToolsError::tool(
+            "ffprobe",
+            format!(
+                "ffprobe failed with status: {}. Stderr: {}",
+                ffprobe_output.status,
+                String::from_utf8_lossy(&ffprobe_output.stderr)
+            ),
+        ));
+    }
+
+    let duration_str = String::from_utf8_lossy(&ffprobe_output.stdout).trim().to_string();
+    duration_str.parse::<f64>().map_err(|_| SeedToolsError::tool("ffprobe", "Failed to parse video duration."))
+}
+
+// Entries shown in a description's file tree before the rest is summed up
+const MAX_TREE_ENTRIES: usize = 200;
+
+/// A collapsed `[spoiler]` with the folders and files of a release (name and size per file),
+/// indented by depth. Empty for a single file, where the torrent name says it all.
+pub fn render_file_tree(input_path: &str) -> String {
+    let root = Path::new(input_path);
+    if !root.is_dir() {
+        return String::new();
+    }
+    let entries: Vec<walkdir::DirEntry> = WalkDir::new(root)
+        .min_depth(1)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .collect();
+    let files: Vec<u64> = entries
+        .iter()
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.metadata().map(|metadata| metadata.len()).unwrap_or(0))
+        .collect();
+    if files.len() < 2 {
+        return String::new();
+    }
+
+    let mut tree = format!(
+        "[spoiler=Files ({}, {})][code]{}/\n",
+        files.len(),
+        format_size(files.iter().sum()),
+        root.file_name().unwrap_or_default().to_string_lossy()
+    );
+    for entry in entries.iter().take(MAX_TREE_ENTRIES) {
+        let indent = "  ".repeat(entry.depth());
+        let name = entry.file_name().to_string_lossy();
+        if entry.file_type().is_dir() {
+            tree.push_str(&format!("{}{}/\n", indent, name));
+        } else {
+            let size = entry.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+            tree.push_str(&format!("{}{}  ({})\n", indent, name, format_size(size)));
+        }
+    }
+    if entries.len() > MAX_TREE_ENTRIES {
+        tree.push_str(&format!("... and {} more\n", entries.len() - MAX_TREE_ENTRIES));
+    }
+    tree.push_str("[/code][/spoiler]\n");
+    tree
+}
+
+/// A chapter of a Matroska file: where it starts (seconds) and its name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chapter {
+    pub start: f64,
+    pub title: String,
+}
+
+/// Chapters and attachments (fonts, cover art) of a Matroska file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MkvExtras {
+    pub chapters: Vec<Chapter>,
+    pub attachments: Vec<String>, // "DejaVuSans.ttf (font/ttf)"
+}
+
+impl MkvExtras {
+    pub fn is_empty(&self) -> bool {
+        self.chapters.is_empty() && self.attachments.is_empty()
+    }
+}
+
+/// Reads the chapters and attachments of an MKV with ffprobe. Other containers, and files
+/// ffprobe fails on, have none.
+pub fn probe_mkv_extras(video_file: &str, ffprobe_path: &str) -> MkvExtras {
+    let is_mkv = Path::new(video_file).extension().is_some_and(|ext| ext.eq_ignore_ascii_case("mkv"));
+    if !is_mkv {
+        return MkvExtras::default();
+    }
+    let output = Command::new(ffprobe_path)
+        .args(["-v", "error", "-print_format", "json", "-show_chapters", "-show_streams", &ffmpeg_file_arg(video_file)])
+        .output();
+    let json: Value = match output {
+        Ok(output) if output.status.success() => serde_json::from_slice(&output.stdout).unwrap_or(Value::Null),
+        Ok(output) => {
+            warn!("ffprobe could not read the chapters of '{}': {}", video_file, String::from_utf8_lossy(&output.stderr).trim());
+            return MkvExtras::default();
+        }
+        Err(e) => {
+            warn!("Failed to run ffprobe for chapters: {}", e);
+            return MkvExtras::default();
+        }
+    };
+
+    let chapters = json["chapters"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .enumerate()
+        .map(|(i, chapter)| Chapter {
+            start: chapter["start_time"].as_str().and_then(|start| start.parse().ok()).unwrap_or(0.0),
+            title: chapter["tags"]["title"].as_str().map(str::to_string).unwrap_or_else(|| format!("Chapter {}", i + 1)),
+        })
+        .collect();
+    let attachments = json["streams"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|stream| stream["codec_type"] == "attachment")
+        .filter_map(|stream| {
+            let name = stream["tags"]["filename"].as_str()?;
+            Some(match stream["tags"]["mimetype"].as_str() {
+                Some(mimetype) => format!("{} ({})", name, mimetype),
+                None => name.to_string(),
+            })
+        })
+        .collect();
+    MkvExtras { chapters, attachments }
+}
+
+/// Collapsed chapter and attachment lists for a description; empty when there are none.
+pub fn format_mkv_extras(extras: &MkvExtras) -> String {
+    let mut section = String::new();
+    if !extras.chapters.is_empty() {
+        section.push_str(&format!("[spoiler=Chapters ({})][code]", extras.chapters.len()));
+        for chapter in &extras.chapters {
+            let seconds = chapter.start as u64;
+            section.push_str(&format!("{:02}:{:02}:{:02}  {}\n", seconds / 3600, seconds / 60 % 60, seconds % 60, chapter.title));
+        }
+        section.push_str("[/code][/spoiler]\n\n");
+    }
+    if !extras.attachments.is_empty() {
+        section.push_str(&format!("[spoiler=Attachments ({})]{}[/spoiler]\n\n", extras.attachments.len(), extras.attachments.join("\n")));
+    }
+    section
+}
+
+static SCREENSHOTS: OnceLock<(Option<u64>, Vec<ShotTime>)> = OnceLock::new();
+
+/// A fixed screenshot timestamp from `screenshots.timestamps` or `--timestamps`.
+#[derive(Clone, Copy)]
+enum ShotTime {
+    Percent(f64),
+    Seconds(u32),
+}
+
+fn parse_shot_time(value: &str) -> Result<ShotTime, SeedToolsError> {
+    let value = value.trim();
+    let invalid = || SeedToolsError::config(format!("Invalid screenshot timestamp '{}': use a percentage like 30% or seconds", value));
+    match value.strip_suffix('%') {
+        Some(percent) => {
+            let percent: f64 = percent.trim().parse().map_err(|_| invalid())?;
+            if !(0.0..=100.0).contains(&percent) {
+                return Err(invalid());
+            }
+            Ok(ShotTime::Percent(percent))
+        }
+        None => value.parse().map(ShotTime::Seconds).map_err(|_| invalid()),
+    }
+}
+
+/// Applies the `screenshots` section of config.yaml (after `--seed` and `--timestamps`). Call
+/// once at startup; without it screenshots are taken at random.
+pub fn init_screenshots(config: &ScreenshotsConfig) -> Result<(), SeedToolsError> {
+    let timestamps = config.timestamps.iter().map(|value| parse_shot_time(value)).collect::<Result<Vec<_>, _>>()?;
+    if let Some(seed) = config.seed {
+        info!("Screenshot timestamps are seeded with {}", seed);
+    }
+    let _ = SCREENSHOTS.set((config.seed, timestamps));
+    Ok(())
+}
+
+/// Four screenshots, or fewer for videos under four minutes so they do not all show the same
+/// scene. Fixed timestamps give one screenshot each.
+pub fn screenshot_count(duration: f64) -> usize {
+    if let Some((_, timestamps)) = SCREENSHOTS.get().filter(|(_, timestamps)| !timestamps.is_empty()) {
+        return timestamps.len();
+    }
+    if !duration.is_finite() {
+        return 1;
+    }
+    ((duration / 60.0) as usize).clamp(1, 4)
+}
+
+/// The timestamps (whole seconds) to take `video_file`'s screenshots at: the fixed ones when set,
+/// otherwise random ones, repeatable per file name when a seed is set.
+fn screenshot_timestamps(video_file: &str, duration: f64) -> Vec<u32> {
+    let (seed, fixed) = match SCREENSHOTS.get() {
+        Some((seed, fixed)) => (*seed, fixed.as_slice()),
+        None => (None, &[][..]),
+    };
+    let duration = if duration.is_finite() { duration.clamp(0.0, u32::MAX as f64) } else { 0.0 };
+    if !fixed.is_empty() {
+        // Seconds past the end take the last full second instead
+        let last = (duration as u32).saturating_sub(1);
+        let mut timestamps: Vec<u32> = fixed
+            .iter()
+            .map(|shot_time| match shot_time {
+                ShotTime::Percent(percent) => ((duration * percent / 100.0) as u32).min(last),
+                ShotTime::Seconds(seconds) => (*seconds).min(last),
+            })
+            .collect();
+        timestamps.sort();
+        return timestamps;
+    }
+    let count = screenshot_count(duration);
+    match seed {
+        Some(seed) => {
+            // The file name and not the path, so the same release gives the same shots wherever it is
+            let file_name = Path::new(video_file).file_name().unwrap_or_default().to_string_lossy();
+            let mut rng = StdRng::seed_from_u64(seed ^ fnv1a(file_name.as_bytes()));
+            generate_random_timestamps(duration, count, &mut rng)
+        }
+        None => generate_random_timestamps(duration, count, &mut rand::thread_rng()),
+    }
+}
+
+// FNV-1a, as std's hasher may change between Rust releases and seeds must keep their shots
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| (hash ^ *byte as u64).wrapping_mul(0x100000001b3))
+}
+
+/// Random timestamps (whole seconds) between 15% and 85% of the video, sorted. Windows too small
+/// to pick from, as with clips of a few seconds, fall back to evenly spaced timestamps.
+fn generate_random_timestamps(duration: f64, count: usize, rng: &mut impl Rng) -> Vec<u32> {
+    let start_time = (duration * 0.15) as u32;
+    let end_time = (duration * 0.85) as u32;
+
+    let mut timestamps: Vec<u32> = if end_time.saturating_sub(start_time) >= count as u32 {
+        (0..count).map(|_| rng.gen_range(start_time..end_time)).collect()
+    } else {
+        (1..=count).map(|i| (duration * i as f64 / (count + 1) as f64) as u32).collect()
+    };
+    timestamps.sort();
+    timestamps
+}
+
+fn generate_screenshot(video_file: &str, ffmpeg_path: &str, timestamp: &u32, output_file: &str) -> Result<(), SeedToolsError> {
+    Command::new(ffmpeg_path)
+        .args([
+            "-y", "-loglevel", "error", "-ss", &timestamp.to_string(),
+            "-i", &ffmpeg_file_arg(video_file), "-vframes", "1", "-qscale:v", "2", &ffmpeg_file_arg(output_file),
+        ])
+        .status()
+        .map_err(|e| SeedToolsError::tool_spawn("ffmpeg", e))?;
+    Ok(())
+}
+
+fn generate_thumbnail(ffmpeg_path: &str, input_file: &str, output_file: &str, blur: bool) -> Result<(), SeedToolsError> {
+    // Blurred thumbnails keep adult descriptions safe to open; the linked screenshot is unchanged
+    let filter = if blur { "scale=720:-1,boxblur=20:2" } else { "scale=720:-1" };
+    Command::new(ffmpeg_path)
+        .args([
+            "-y", "-loglevel", "error", "-i", &ffmpeg_file_arg(input_file),
+            "-vf", filter, &ffmpeg_file_arg(output_file),
+        ])
+        .status()
+        .map_err(|e| SeedToolsError::tool_spawn("ffmpeg", e))?;
+    Ok(())
+}
+
+/// Escapes a path for use as a filter option value, e.g. `drawtext=textfile=...`: once for
+/// the option (`\\`, `'`, `:`) and once more for the filter graph (`\\`, `'`, `[`, `]`, `,`, `;`).
+fn escape_filter_value(value: &str) -> String {
+    let escape = |text: &str, special: &[char]| {
+        text.chars().fold(String::new(), |mut escaped, c| {
+            if special.contains(&c) {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+            escaped
+        })
+    };
+    escape(&escape(value, &['\\', '\'', ':']), &['\\', '\'', '[', ']', ',', ';'])
+}
+
+/// ffmpeg reads `name:rest` as a protocol and a leading `-` as an option, so local files are
+/// passed with the explicit `file:` protocol.
+pub fn ffmpeg_file_arg(path: &str) -> String {
+    format!("file:{}", path)
+}
+
+/// `dir/file_name` with the platform's separator, for paths handed to external tools.
+pub fn join_path(dir: impl AsRef<Path>, file_name: &str) -> String {
+    dir.as_ref().join(file_name).to_string_lossy().to_string()
+}
+
+/// Copies `file` to `remote` (`[user@]host:path`) with scp. Relative local paths get a `./`
+/// prefix so a name containing `:` is not taken for a host, and no shell is involved.
+#[cfg(unix)]
+pub fn scp_upload(file: &Path, remote: &str) -> Result<(), SeedToolsError> {
+    let output = scp_command(file, remote, crate::schedule::limit_kbps())
+        .output()
+        .map_err(|e| SeedToolsError::tool_spawn("scp", e))?;
+    if !output.status.success() {
+        return Err(SeedToolsError::tool("scp", format!(
+            "scp of '{}' to '{}' failed: {}",
+            file.display(),
+            remote,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+/// The scp invocation for `scp_upload`, limited to `limit_kbps` when set.
+#[cfg(unix)]
+fn scp_command(file: &Path, remote: &str, limit_kbps: Option<u64>) -> Command {
+    let local = if file.is_absolute() { file.to_path_buf() } else { Path::new(".").join(file) };
+    let mut command = Command::new("scp");
+    if let Some(limit) = limit_kbps {
+        command.arg("-l").arg(limit.to_string());
+    }
+    command.arg("--").arg(local).arg(remote);
+    command
+}
+
+/// Copies `file` to `remote` (`[user@]host:path`) over SFTP, as Windows has no dependable scp.
+/// Authenticates with the SSH agent (Pageant or the OpenSSH agent service), then with
+/// `~/.ssh/id_ed25519` or `~/.ssh/id_rsa`, and refuses hosts missing from `~/.ssh/known_hosts`.
+#[cfg(windows)]
+pub fn scp_upload(file: &Path, remote: &str) -> Result<(), SeedToolsError> {
+    use ssh2::{CheckResult, KnownHostFileKind, Session};
+    use std::net::TcpStream;
+
+    let (host, remote_path) = remote
+        .split_once(':')
+        .ok_or_else(|| SeedToolsError::config(format!("Invalid remote '{}': expected [user@]host:path", remote)))?;
+    let (user, host) = match host.split_once('@') {
+        Some((user, host)) => (user.to_string(), host),
+        None => (std::env::var("USERNAME").unwrap_or_default(), host),
+    };
+    let ssh_dir = Path::new(&std::env::var("USERPROFILE").unwrap_or_default()).join(".ssh");
+
+    let tcp = TcpStream::connect((host, 22)).map_err(SeedToolsError::io(format!("Failed to connect to {}", host)))?;
+    let mut session = Session::new().map_err(|e| SeedToolsError::tool("ssh", format!("Failed to start SSH session: {}", e)))?;
+    session.set_tcp_stream(tcp);
+    session.handshake().map_err(|e| SeedToolsError::tool("ssh", format!("SSH handshake with {} failed: {}", host, e)))?;
+
+    let (key, _) = session.host_key().ok_or_else(|| SeedToolsError::tool("ssh", format!("{} sent no host key", host)))?;
+    let mut known_hosts = session.known_hosts().map_err(|e| SeedToolsError::tool("ssh", e.to_string()))?;
+    known_hosts
+        .read_file(&ssh_dir.join("known_hosts"), KnownHostFileKind::OpenSSH)
+        .map_err(|e| SeedToolsError::tool("ssh", format!("Failed to read {}: {}", ssh_dir.join("known_hosts").display(), e)))?;
+    match known_hosts.check(host, key) {
+        CheckResult::Match => {}
+        CheckResult::Mismatch => return Err(SeedToolsError::tool("ssh", format!("Host key for {} does not match known_hosts", host))),
+        _ => return Err(SeedToolsError::tool("ssh", format!("{} is not in known_hosts; connect once with ssh to add it", host))),
+    }
+
+    if session.userauth_agent(&user).is_err() {
+        for key_name in ["id_ed25519", "id_rsa"] {
+            let key_path = ssh_dir.join(key_name);
+            if key_path.exists() && session.userauth_pubkey_file(&user, None, &key_path, None).is_ok() {
+                break;
+            }
+        }
+    }
+    if !session.authenticated() {
+        return Err(SeedToolsError::tool("ssh", format!("SSH authentication as '{}' on {} failed", user, host)));
+    }
+
+    let sftp = session.sftp().map_err(|e| SeedToolsError::tool("ssh", format!("Failed to start SFTP on {}: {}", host, e)))?;
+    // Like scp, a directory target keeps the local file name
+    let mut target = Path::new(remote_path).to_path_buf();
+    if remote_path.is_empty() || sftp.stat(&target).map_or(false, |stat| stat.is_dir()) {
+        target = target.join(file.file_name().unwrap_or_default());
+    }
+    let mut local = File::open(file).map_err(SeedToolsError::io(format!("Failed to open '{}'", file.display())))?;
+    let mut remote_file = sftp
+        .create(&target)
+        .map_err(|e| SeedToolsError::tool("ssh", format!("Failed to create '{}' on {}: {}", target.display(), host, e)))?;
+    // Copied in chunks so the bandwidth limit can hold back between them
+    let mut buffer = vec![0u8; 64 * 1024];
+    let started = std::time::Instant::now();
+    let mut sent = 0u64;
+    loop {
+        let read = std::io::Read::read(&mut local, &mut buffer)
+            .map_err(SeedToolsError::io(format!("Failed to read '{}'", file.display())))?;
+        if read == 0 {
+            break;
+        }
+        remote_file
+            .write_all(&buffer[..read])
+            .map_err(SeedToolsError::io(format!("SFTP upload of '{}' to '{}' failed", file.display(), remote)))?;
+        sent += read as u64;
+        crate::schedule::throttle(started, sent);
+    }
+    Ok(())
+}
+
+pub fn upload_to_cdn(file_path: &str, remote_path: &str, offline: Option<&Offline>) -> Result<(), SeedToolsError> {
+    info!("Uploading file to CDN: {}", file_path);
+
+    copy_to_cdn(Path::new(file_path), remote_path, offline)
+        .map_err(|e| e.context("Failed to upload file to CDN"))?;
+
+    Ok(())
+}
+
+/// `scp_upload`, or a copy into the offline CDN directory for offline runs.
+pub fn copy_to_cdn(file: &Path, remote: &str, offline: Option<&Offline>) -> Result<(), SeedToolsError> {
+    match offline {
+        Some(offline) => offline.copy_to_cdn(file, remote),
+        None => scp_upload(file, remote),
+    }
+}
+
+/// The footer under a description: none, the credits line, or the credits with their badges
+/// (`default_non_video_description`).
+pub fn description_footer(footer: Footer) -> String {
+    match footer {
+        Footer::None => String::new(),
+        Footer::Plain => "[b]Created with mkbrr, ffmpeg, and mediainfo. Posted to this fine tracker with seed-tools.[/b]".to_string(),
+        Footer::Branded => default_non_video_description(),
+    }
+}
+
+pub fn default_non_video_description() -> String {
+    "[b][size=12][color=#757575]Created with mkbrr, ffmpeg, and mediainfo. Posted to this fine tracker with seed-tools.[/color][/size][/b]
+        
+        [url=https://github.com/seed-pool/seed-tools][img]https://cdn.seedpool.org/sp.png[/img][/url]  \
+        [url=https://github.com/autobrr/mkbrr][img]https://cdn.seedpool.org/mkbrr.png[/img][/url]  \
+        [url=https://www.rust-lang.org][img]https://cdn.seedpool.org/rust.png[/img][/url]".to_string()
+}
+
+pub fn extract_rar_archives(folder_path: &str) -> Result<Option<String>, SeedToolsError> {
+    use std::fs;
+    use std::path::Path;
+    use log::info;
+
+    info!("Checking for RAR archives in folder: {}", folder_path);
+
+    let path = Path::new(folder_path);
+    if !path.is_dir() {
+        return Err(SeedToolsError::Metadata(format!("Provided path is not a directory: {}", folder_path)));
+    }
+
+    // Collect all .rar, .r00, and .r01 files
+    let mut rar_files = Vec::new();
+    let mut r00_files = Vec::new();
+    let mut r01_files = Vec::new();
+
+    for entry in fs::read_dir(path).map_err(SeedToolsError::io("Failed to read directory"))? {
+        let entry = entry.map_err(SeedToolsError::io("Failed to read directory entry"))?;
+        let file_path = entry.path();
+        if let Some(ext) = file_path.extension().and_then(|e| e.to_str()) {
+            if ext.eq_ignore_ascii_case("rar") {
+                rar_files.push(file_path.clone());
+            } else if ext.eq_ignore_ascii_case("r00") {
+                r00_files.push(file_path.clone());
+            } else if ext.eq_ignore_ascii_case("r01") {
+                r01_files.push(file_path.clone());
+            }
+        }
+    }
+
+    // Prefer .rar, then .r00, then .r01
+    let to_extract = if !rar_files.is_empty() {
+        rar_files
+    } else if !r00_files.is_empty() {
+        r00_files
+    } else {
+        r01_files
+    };
+
+    if to_extract.is_empty() {
+        info!("No RAR, R00, or R01 archives found in folder: {}", folder_path);
+        return Ok(None); // No extraction occurred
+    }
+
+    info!("Found RAR/R00/R01 archives: {:?}", to_extract);
+    // Roughly as much again as the archives hold, as rar sets of videos are stored uncompressed
+    let volume = Regex::new(r"(?i)\.(rar|r\d{2})$").unwrap();
+    let archive_size = fs::read_dir(path)
+        .map_err(SeedToolsError::io("Failed to read directory"))?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| volume.is_match(&entry.file_name().to_string_lossy()))
+        .filter_map(|entry| entry.metadata().ok())
+        .map(|metadata| metadata.len())
+        .sum();
+    crate::space::ensure_free(crate::space::Stage::Unpack, path, archive_size)?;
+
+    for archive_file in to_extract {
+        info!("Extracting archive: {}", archive_file.display());
+
+        let output = std::process::Command::new("unrar")
+            .args(["x", "-o+", archive_file.to_str().unwrap(), folder_path])
+            .output()
+            .map_err(|e| SeedToolsError::tool_spawn("unrar", e))?;
+
+        if !output.status.success() {
+            return Err(SeedToolsError::tool("unrar", format!(
+                "Failed to extract archive: {}. Error: {}",
+                archive_file.display(),
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+
+        info!("Successfully extracted archive: {}", archive_file.display());
+    }
+
+    info!("Extraction completed. Extracted files are in: {}", folder_path);
+    Ok(Some(folder_path.to_string()))
+}
+
+/// Extracts a video release that arrived as a rar set, so the video is uploaded instead of the
+/// archives. Releases already holding a video are left alone. Returns whether anything was extracted.
+pub fn unpack_video_release(input_path: &str, unpack: &UnpackConfig) -> Result<bool, SeedToolsError> {
+    let path = Path::new(input_path);
+    if !path.is_dir() {
+        return Ok(false);
+    }
+    let volume = Regex::new(r"(?i)\.(rar|r\d{2})$").unwrap();
+    let volumes: Vec<PathBuf> = fs::read_dir(path)
+        .map_err(SeedToolsError::io("Failed to read directory"))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|file| file.is_file() && volume.is_match(&file.to_string_lossy()))
+        .collect();
+    if volumes.is_empty() {
+        return Ok(false);
+    }
+
+    // Only the top level counts; scene releases keep a sample video in Sample/
+    let has_video = || {
+        WalkDir::new(input_path).max_depth(1).into_iter().filter_map(|entry| entry.ok()).any(|entry| {
+            entry.path().extension().and_then(|ext| ext.to_str()).is_some_and(|ext| {
+                ["mkv", "mp4", "ts", "avi", "mov", "flv", "wmv"].contains(&ext.to_lowercase().as_str())
+            })
+        })
+    };
+    if has_video() {
+        info!("'{}' already holds an unpacked video; leaving its rar archives alone.", input_path);
+        return Ok(false);
+    }
+
+    extract_rar_archives(input_path)?;
+    if !has_video() {
+        warn!("No video came out of the rar archives in '{}'; keeping them.", input_path);
+    } else if unpack.delete_archives {
+        for volume in &volumes {
+            fs::remove_file(volume).map_err(SeedToolsError::io(format!("Failed to delete '{}'", volume.display())))?;
+        }
+        info!("Deleted {} rar volume(s) from '{}'.", volumes.len(), input_path);
+    }
+    Ok(true)
+}
+
+pub fn add_torrent_to_qbittorrent(
+    torrent_file: &str,
+    config: &QbittorrentConfig,
+    input_path: &str,
+) -> Result<SeedingState, SeedToolsError> {
+    info!("Creating HTTP client with cookie support for qBittorrent.");
+    let client = crate::http::client_builder()
+        .cookie_store(true)
+        .build()
+        .map_err(SeedToolsError::client_request("qBittorrent", "Failed to create HTTP client"))?;
+
+    let login_url = format!("{}/api/v2/auth/login", config.webui_url);
+    info!("Logging in to qBittorrent at {}...", login_url);
+    let login_response = client
+        .post(&login_url)
+        .form(&[
+            ("username", config.username.as_str()),
+            ("password", config.password.as_str()),
+        ])
+        .send()
+        .map_err(SeedToolsError::client_request("qBittorrent", "Failed to send login request to qBittorrent"))?;
+
+    let login_status = login_response.status();
+    let login_body = login_response.text().map_err(SeedToolsError::client_request("qBittorrent", "Failed to read login response body"))?;
+
+    if !login_status.is_success() {
+        return Err(SeedToolsError::client("qBittorrent", format!(
+            "qBittorrent login request failed: {} - Body: {}",
+            login_status, login_body
+        )));
+    }
+
+    if login_body.trim() != "Ok." {
+        return Err(SeedToolsError::client("qBittorrent", format!(
+            "qBittorrent login failed (unexpected response): {}",
+            login_body
+        )));
+    }
+    info!("Logged in to qBittorrent successfully.");
+
+    if !Path::new(torrent_file).exists() {
+        return Err(SeedToolsError::client("qBittorrent", format!("Torrent file does not exist: {}", torrent_file)));
+    }
+
+    let mut form = Form::new()
+        .file("torrents", torrent_file)
+        .map_err(SeedToolsError::io("Failed to attach torrent file"))?
+        .text("paused", "false")
+        // Without a recheck the torrent is seeded as-is, so a wrong save path goes unnoticed
+        .text("skip_checking", (!config.recheck).to_string());
+
+    if let Some(category) = &config.category {
+        info!("Using category for qBittorrent: {}", category);
+        form = form.text("category", category.clone());
+    }
+    if config.auto_tmm {
+        // The category decides where the data lives; qBittorrent ignores a savepath then
+        form = form.text("autoTMM", "true");
+    } else if let Some(save_path) = Path::new(input_path).parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        // The torrent's root is the input itself, so it is seeded from the folder holding it
+        let save_path = to_client_path(&config.path_mappings, &save_path.to_string_lossy());
+        info!("Seeding from {}", save_path);
+        form = form.text("autoTMM", "false").text("savepath", save_path);
+    }
+    if !config.tags.is_empty() {
+        form = form.text("tags", config.tags.join(","));
+    }
+
+    let add_url = format!("{}/api/v2/torrents/add", config.webui_url);
+    info!("Injecting torrent into qBittorrent at {}...", add_url);
+    let upload_response = client
+        .post(&add_url)
+        .multipart(form)
+        .send()
+        .map_err(SeedToolsError::client_request("qBittorrent", "Failed to send add torrent request to qBittorrent"))?;
+
+    let status = upload_response.status();
+    let response_body = upload_response.text().unwrap_or_else(|_| "Failed to read response body".to_string());
+    info!("qBittorrent API Response [add]: {}", response_body);
+
+    if !status.is_success() || response_body.to_lowercase().contains("fail") {
+        return Err(SeedToolsError::client("qBittorrent", format!(
+            "Failed to upload torrent to qBittorrent: {}. Response: {}",
+            status, response_body
+        )));
+    }
+
+    info!("Torrent added to qBittorrent successfully.");
+
+    // A 200 only means the torrent was accepted, not that the data was found
+    let torrent = fs::read(torrent_file).map_err(SeedToolsError::io(format!("Failed to read '{}'", torrent_file)))?;
+    Ok(wait_for_qbittorrent(&client, &config.webui_url, &torrent))
+}
+
+pub fn add_torrent_to_deluge(
+    torrent_file: &str,
+    config: &DelugeConfig,
+    input_path: &str,
+    paths_config: &PathsConfig,
+) -> Result<SeedingState, SeedToolsError> {
+    info!("Adding torrent '{}' to Deluge at '{}'", torrent_file, config.webui_url);
+
+    let absolute_torrent_file = fs::canonicalize(torrent_file)
+        .map_err(SeedToolsError::io(format!("Failed to resolve absolute path for torrent file '{}'", torrent_file)))?;
+
+    let cookie_jar = Arc::new(Jar::default());
+    let client = crate::http::client_builder()
+        .cookie_store(true)
+        .cookie_provider(cookie_jar.clone())
+        .build()
+        .map_err(SeedToolsError::client_request("Deluge", "Failed to build HTTP client"))?;
+
+    let login_payload = json!({
+        "method": "auth.login",
+        "params": [config.password],
+        "id": 1
+    });
+
+    let login_response = client
+        .post(format!("{}/json", config.webui_url))
+        .json(&login_payload)
+        .send()
+        .map_err(SeedToolsError::client_request("Deluge", "Failed to log in to Deluge"))?;
+
+    let login_result: serde_json::Value = login_response
+        .json()
+        .map_err(SeedToolsError::client_request("Deluge", "Failed to parse Deluge login response"))?;
+
+    if !login_result["result"].as_bool().unwrap_or(false) {
+        return Err(SeedToolsError::client("Deluge", "Failed to log in to Deluge: Invalid credentials"));
+    }
+
+    info!("Logged in to Deluge successfully.");
+
+    let mut options = json!({
+        "add_paused": false,
+        "move_completed": false,
+        "skip_checking": true,
+        "label": config.label.clone().unwrap_or_default(),
+    });
+    if let Some(save_path) = staged_save_path(input_path, paths_config) {
+        options["download_location"] = json!(to_client_path(&config.path_mappings, &save_path));
+    }
+    let add_torrent_payload = json!({
+        "method": "web.add_torrents",
+        "params": [[{
+            "path": absolute_torrent_file.to_string_lossy(),
+            "options": options,
+        }]],
+        "id": 2
+    });
+
+    let add_torrent_response = client
+        .post(format!("{}/json", config.webui_url))
+        .json(&add_torrent_payload)
+        .send()
+        .map_err(SeedToolsError::client_request("Deluge", "Failed to add torrent to Deluge"))?;
+
+    let add_torrent_result: serde_json::Value = add_torrent_response
+        .json()
+        .map_err(SeedToolsError::client_request("Deluge", "Failed to parse Deluge add torrent response"))?;
+
+    if let Some(error) = add_torrent_result.get("error")
+        && !error.is_null()
+    {
+        return Err(SeedToolsError::client("Deluge", format!(
+            "Deluge returned an error while adding torrent: {:?}",
+            error
+        )));
+    }
+
+    info!("Torrent added to Deluge successfully.");
+
+    let torrent = fs::read(torrent_file).map_err(SeedToolsError::io(format!("Failed to read '{}'", torrent_file)))?;
+    Ok(wait_for_deluge(&client, &config.webui_url, &torrent))
+}
+
+/// Uploads an image to ImgBB, riding out rate limits (see `imgbb::upload`).
+pub fn upload_to_imgbb(image_path: &str, imgbb_api_key: &str) -> Result<(String, String), SeedToolsError> {
+    log::debug!("Uploading image to ImgBB: path={}", image_path);
+    // Keys still rate limited after all retries answer as a 429, so callers can fall back to the CDN
+    crate::imgbb::upload(image_path, imgbb_api_key).map_err(|e| {
+        let status = e.starts_with(crate::imgbb::RATE_LIMITED).then_some(429);
+        SeedToolsError::tracker("ImgBB", status, e)
+    })
+}
+
+pub fn generate_screenshots_imgbb(
+    video_file: &str,
+    ffmpeg_path: &Path,
+    ffprobe_path: &Path,
+    imgbb_api_key: &str,
+    blur_thumbnails: bool,
+) -> Result<(Vec<String>, Vec<String>), SeedToolsError> {
+    let mut screenshots = Vec::new();
+    let mut thumbnails = Vec::new();
+
+    // Get video duration
+    let duration = get_video_duration(video_file, ffprobe_path.to_str().unwrap())?;
+    let timestamps = screenshot_timestamps(video_file, duration);
+
+    // Generate sanitized base name for screenshots
+    let base_name = Path::new(video_file)
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    let sanitized_base_name = generate_release_name(&base_name);
+    let temp_dir = crate::space::temp_dir("imgbb")?;
+
+    for (i, timestamp) in timestamps.iter().enumerate() {
+        // Generate screenshot file name
+        let screenshot_name = format!("{}_{}.jpg", sanitized_base_name, i + 1);
+        let screenshot_path = join_path(temp_dir.path(), &screenshot_name);
+
+        // Generate screenshot
+        generate_screenshot(video_file, ffmpeg_path.to_str().unwrap(), timestamp, &screenshot_path)?;
+
+        // Upload screenshot to ImgBB
+        let (full_image_url, thumb_url) = upload_to_imgbb(&screenshot_path, imgbb_api_key)?;
+        screenshots.push(full_image_url); // Use full_image_url for the description
+        if blur_thumbnails {
+            // ImgBB's own thumbnail is sharp, so a blurred one goes up separately
+            let blurred_path = join_path(temp_dir.path(), &format!("{}_{}_blur.jpg", sanitized_base_name, i + 1));
+            generate_thumbnail(ffmpeg_path.to_str().unwrap(), &screenshot_path, &blurred_path, true)?;
+            let (blurred_url, _) = upload_to_imgbb(&blurred_path, imgbb_api_key)?;
+            thumbnails.push(blurred_url);
+            fs::remove_file(&blurred_path).map_err(SeedToolsError::io("Failed to delete temporary thumbnail"))?;
+        } else {
+            thumbnails.push(thumb_url);
+        }
+
+        // Clean up the local screenshot file
+        fs::remove_file(&screenshot_path).map_err(SeedToolsError::io("Failed to delete temporary screenshot"))?;
+    }
+
+    Ok((screenshots, thumbnails))
+}
+
+pub fn process_ebook_upload(input_path: &str, config: &Config, seedpool_config: &SeedpoolConfig, offline: Option<&Offline>) -> Result<(), SeedToolsError> {
+    use std::fs;
+
+    let mut working_dir = input_path.to_string();
+
+    // If input is a file, get its parent directory for extraction
+    if Path::new(&working_dir).is_file()
+        && let Some(parent) = Path::new(&working_dir).parent()
+    {
+        working_dir = parent.to_string_lossy().to_string();
+    }
+
+    // 1. Extract all ZIP files in the directory
+    let zip_files: Vec<_> = fs::read_dir(&working_dir)
+        .map_err(SeedToolsError::io(format!("Failed to read directory '{}'", working_dir)))?
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("zip")) {
+                Some(path)
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    for zip_file in &zip_files {
+        log::info!("Extracting ZIP archive: {}", zip_file.display());
+        let output = std::process::Command::new("unzip")
+            .arg("-o")
+            .arg(zip_file)
+            .arg("-d")
+            .arg(&working_dir)
+            .output()
+            .map_err(|e| SeedToolsError::tool_spawn("unzip", e))?;
+        if !output.status.success() {
+            return Err(SeedToolsError::tool("unzip", format!(
+                "Failed to extract ZIP archive: {}. Error: {}",
+                zip_file.display(),
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+    }
+
+    // 2. Extract all RAR files in the directory (using your existing function)
+    extract_rar_archives(&working_dir)?;
+
+    // 3. Find the main ebook file (prefer .epub, fallback to .pdf)
+    let mut found_pdf: Option<String> = None;
+    let mut found_epub: Option<String> = None;
+    for entry in WalkDir::new(&working_dir).into_iter().filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if path.is_file()
+            && let Some(ext) = path.extension().and_then(|e| e.to_str())
+        {
+            if ext.eq_ignore_ascii_case("epub") {
+                found_epub = Some(path.to_string_lossy().to_string());
+                break;
+            } else if ext.eq_ignore_ascii_case("pdf") {
+                found_pdf = Some(path.to_string_lossy().to_string());
+            }
+        }
+    }
+    let (ebook_path, is_pdf) = if let Some(epub) = found_epub {
+        (epub, false)
+    } else if let Some(pdf) = found_pdf {
+        (pdf, true)
+    } else {
+        return Err(SeedToolsError::Metadata(format!("No .epub or .pdf files found in directory '{}'", working_dir)));
+    };
+
+    // 4. Extract metadata and cover
+    let (title, author) = if is_pdf {
+        extract_metadata_from_pdf(&ebook_path)?
+    } else {
+        extract_metadata_from_epub(&ebook_path)?
+    };
+
+    let mut title = title.unwrap_or_else(|| "Unknown Title".to_string());
+    let mut author = author.unwrap_or_else(|| "Unknown Author".to_string());
+
+    // Sanitize the file name and rename the ebook file
+    let new_ebook_path = if is_pdf {
+        Path::new(&ebook_path).to_path_buf() // Don't rename PDF
+    } else {
+        let sanitized_author = {
+            let parts: Vec<&str> = author.split_whitespace().collect();
+            if parts.len() > 1 {
+                format!("{}, {}", parts.last().unwrap(), parts[..parts.len() - 1].join(" "))
+            } else {
+                author.to_string()
+            }
+        };
+        let sanitized_title = title
+            .replace(".", " ")
+            .replace(":", " ")
+            .replace("'", "")
+            .replace("/", " ")
+            .replace("\\", " ")
+            .replace("&", "and")
+            .replace("?", "")
+            .replace("*", "");
+        let new_ext = "epub";
+        let new_ebook_name = format!("{} - {}.{}", sanitized_author, sanitized_title, new_ext);
+        let new_ebook_path = Path::new(&ebook_path).with_file_name(new_ebook_name);
+        fs::rename(&ebook_path, &new_ebook_path)
+            .map_err(SeedToolsError::io("Failed to rename ebook file"))?;
+        new_ebook_path
+    };
+
+    // Remove any other .epub or .pdf files except the renamed one
+    for entry in fs::read_dir(&working_dir).map_err(SeedToolsError::io(format!("Failed to read directory '{}'", working_dir)))? {
+        let entry = entry.map_err(SeedToolsError::io("Failed to read directory entry"))?;
+        let path = entry.path();
+
+        if is_pdf {
+            // Remove all .epub and .zip files, but NEVER remove the found PDF
+            if path.extension().map(|ext| ext.eq_ignore_ascii_case("epub")).unwrap_or(false)
+                || path.extension().map(|ext| ext.eq_ignore_ascii_case("zip")).unwrap_or(false) 
+            {
+                fs::remove_file(&path)
+                    .map_err(SeedToolsError::io(format!("Failed to remove file '{}'", path.display())))?;
+            }
+            // Do NOT remove the PDF file at ebook_path (or new_ebook_path)
+        } else {
+            // For EPUBs: keep only the renamed epub, remove all other epubs
+            if path.extension().map(|ext| ext.eq_ignore_ascii_case("epub")).unwrap_or(false)
+                && path != new_ebook_path
+            {
+                fs::remove_file(&path)
+                    .map_err(SeedToolsError::io(format!("Failed to remove extra epub file '{}'", path.display())))?;
+            }
+            // Keep all ZIPs for EPUBs
+        }
+    }
+
+    let torrent_input = &working_dir;
+    crate::limits::check_release_limits(torrent_input, "ebook", &config.limits)?;
+    let torrent_file = create_torrent(
+        torrent_input,
+        &config.paths.torrent_dir,
+        &seedpool_config.settings.announce_url,
+        &config.paths.mkbrr,
+        Some(&config.exclusions),
+        &seedpool_config.torrent,
+    )?;
+
+    // Use the base name of the directory or ebook for the upload form
+    let base_name = Path::new(torrent_input)
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+
+    let lower_base = base_name.to_lowercase();
+    let kind = if lower_base.contains("magazine") {
+        "magazine"
+    } else if lower_base.contains("comic") {
+        "comic"
+    } else {
+        "ebook"
+    };
+
+    let nfo_file = fs::read_dir(&working_dir)
+        .ok()
+        .and_then(|mut entries| {
+            entries.find_map(|entry| {
+                let entry = entry.ok()?;
+                let path = entry.path();
+                if path.extension().map(|ext| ext.eq_ignore_ascii_case("nfo")).unwrap_or(false) {
+                    Some(path.to_string_lossy().to_string())
+                } else {
+                    None
+                }
+            })
+        });
+
+    // --- SKIP OPEN LIBRARY FOR COMICS & MAGAZINES ---
+    let (mut description, keywords);
+    let mut cover_id: Option<u64> = None;
+    if is_pdf && kind != "ebook" {
+        let torrent_name = generate_release_name(&base_name);
+        description = generate_comic_description(
+            &ebook_path,
+            &torrent_name,
+            &seedpool_config.screenshots.remote_path,
+            &seedpool_config.screenshots.image_path,
+            offline,
+        )?;
+        keywords = kind.to_string();
+    } else {
+        // --- ORIGINAL OPEN LIBRARY LOOKUP AND DESCRIPTION LOGIC ---
+        let mut subjects = Vec::new();
+        let mut desc = format!(
+            "[center][b][size=32][color=#2E86C1]{}[/color][/size][/b]\n\
+            [b][size=16][color=#117A65]By:[/color][/size][/b] [i]{}[/i][/center]\n\n\
+            [b][size=15][color=#6C3483]Synopsis:[/color][/size][/b]\n\
+            [quote]No metadata available.[/quote]\n\n\
+            [center]{}[/center]",
+            title,
+            author,
+            default_non_video_description()
+        );
+
+        // Only try Open Library if we have at least a title or author
+        if title != "Unknown Title" || author != "Unknown Author" {
+            let query = format!(
+                "https://openlibrary.org/search.json?title={}&author={}",
+                urlencoding::encode(&title),
+                urlencoding::encode(&author)
+            );
+
+            info!("Querying Open Library API: {}", query);
+
+            let client = crate::http::client();
+            let response = client
+                .get(&query)
+                .send()
+                .map_err(|e| SeedToolsError::request("Open Library", e).context("Failed to query Open Library API"))?;
+
+            if response.status().is_success() {
+                let json: serde_json::Value = response
+                    .json()
+                    .map_err(|e| SeedToolsError::request("Open Library", e).context("Failed to parse Open Library API response"))?;
+
+                if let Some(first_result) = json["docs"].as_array().and_then(|docs| docs.first()) {
+                    // Use Open Library's title and author if available
+                    let ol_title = first_result["title"]
+                        .as_str()
+                        .unwrap_or(&title)
+                        .to_string();
+                    let ol_author = first_result["author_name"]
+                        .as_array()
+                        .and_then(|authors| authors.first())
+                        .and_then(|author| author.as_str())
+                        .unwrap_or(&author)
+                        .to_string();
+
+                    info!("Using title: '{}' and author: '{}'", ol_title, ol_author);
+
+                    // Update title and author with Open Library values
+                    title = ol_title;
+                    author = ol_author;
+
+                    // Extract Open Library work and author keys
+                    let open_library_work_key = first_result["key"]
+                        .as_str()
+                        .unwrap_or("")
+                        .trim_start_matches("/works/")
+                        .to_string();
+                    let open_library_author_key = first_result["author_key"]
+                        .as_array()
+                        .and_then(|keys| keys.first())
+                        .and_then(|key| key.as_str())
+                        .unwrap_or("")
+                        .to_string();
+
+                    // Extract cover ID
+                    cover_id = first_result["cover_i"].as_u64();
+
+                    // Generate the BBCode description and fetch subjects
+                    let (desc2, subj) = generate_ebook_bbcode_description(
+                        &title,
+                        &author,
+                        &open_library_work_key,
+                        &open_library_author_key,
+                        client,
+                    )?;
+                    desc = desc2;
+                    subjects = subj;
+                }
+            }
+        }
+        description = desc;
+        keywords = subjects.join(", ");
+    }
+    // Collections list what they hold
+    description.push_str(&render_file_tree(torrent_input));
+    let custom_description = seedpool_config.settings.custom_description_for("ebook", &base_name, &title);
+    if !custom_description.trim().is_empty() {
+        description.push_str(&format!("\n{}", custom_description));
+    }
+
+    info!("Processing eBook upload for title: '{}' and author: '{}'", title, author);
+
+    // If PDF, extract cover image from first page using Ghostscript
+    let mut pdf_cover_image_path = None;
+    if is_pdf {
+        let cover_path = format!("{}.cover.jpg", ebook_path);
+        let output = std::process::Command::new("gs")
+            .args([
+                "-dBATCH", "-dNOPAUSE",
+                "-sDEVICE=jpeg",
+                "-dFirstPage=1", "-dLastPage=1",
+                "-r150", "-dJPEGQ=95",
+                &format!("-sOutputFile={}", cover_path),
+                &ebook_path,
+            ])
+            .output()
+            .map_err(|e| SeedToolsError::tool_spawn("gs", e))?;
+        if !output.status.success() {
+            return Err(SeedToolsError::tool("gs", format!(
+                "Failed to extract cover from PDF with gs: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+        pdf_cover_image_path = Some(cover_path);
+    }
+
+    let upload = UploadRequest {
+        torrent_file: torrent_file.clone(),
+        name: base_name.clone(),
+        category_id: seedpool_config.ids.category("ebook"),
+        type_id: seedpool_config.ids.type_id(kind),
+        description: Some(description),
+        keywords: Some(keywords),
+        nfo_file,
+        ..Default::default()
+    }
+    .with_flags(&seedpool_config.upload_flags);
+
+    // Send the upload request
+    let response_text = submit_upload(
+        &seedpool_config.settings.upload_url,
+        Some(&seedpool_config.general.api_key),
+        &upload,
+        offline,
+    )?;
+
+    // Extract the torrent ID from the response
+    let torrent_id = extract_torrent_id(&response_text)?;
+
+    // --- COVER HANDLING ---
+
+    // For EPUBs: Fetch the cover image using the cover ID from Open Library (existing logic)
+    if !is_pdf && kind == "ebook" {
+        let mut cover_handled = false;
+        if let Some(cover_id) = cover_id {
+            let cover_url = format!("https://covers.openlibrary.org/b/id/{}-L.jpg", cover_id);
+            info!("Fetching cover image from: {}", cover_url);
+
+            let cover_response = crate::http::client()
+                .get(&cover_url)
+                .send()
+                .map_err(|e| SeedToolsError::request("Open Library", e).context("Failed to fetch cover image"))?;
+
+            if cover_response.status().is_success() {
+                // Save the cover image in the temp dir, not next to the ebook being seeded
+                let cover_dir = crate::space::temp_dir("cover")?;
+                let cover_path = cover_dir.path().join(format!("{}.jpg", base_name));
+                let cover = cover_response
+                    .bytes()
+                    .map_err(|e| SeedToolsError::request("Open Library", e).context("Failed to read cover image bytes"))?;
+                std::fs::write(&cover_path, cover).map_err(SeedToolsError::io("Failed to save cover image"))?;
+
+                info!("Saved cover image to: {}", cover_path.display());
+
+                // Rename the cover image to include the torrent ID
+                let renamed_cover_path = cover_path.with_file_name(format!("torrent-cover_{}.jpg", torrent_id));
+                std::fs::rename(&cover_path, &renamed_cover_path)
+                    .map_err(SeedToolsError::io("Failed to rename cover image"))?;
+
+                // Set permissions to 777 for the renamed cover image
+                #[cfg(unix)]
+                {
+                    use std::os::unix::fs::PermissionsExt;
+
+                    info!("Setting permissions to 777 for cover image: {}", renamed_cover_path.display());
+                    fs::set_permissions(&renamed_cover_path, fs::Permissions::from_mode(0o777))
+                        .map_err(SeedToolsError::io(format!("Failed to set permissions for cover image '{}'", renamed_cover_path.display())))?;
+                    info!("Successfully set permissions to 777 for cover image: {}", renamed_cover_path.display());
+                }
+
+                // Upload the cover image to the CDN using SCP
+                let remote_covers_path = format!(
+                    "{}/albumcovers",
+                    seedpool_config.screenshots.remote_path.trim_end_matches('/')
+                );
+                copy_to_cdn(Path::new(&renamed_cover_path), &remote_covers_path, offline)
+                    .map_err(|e| e.context("Failed to upload cover image via SCP"))?;
+
+                info!("Successfully uploaded cover image to CDN: {}", remote_covers_path);
+                cover_handled = true;
+            } else {
+                warn!("Failed to fetch cover image with status: {}. Skipping cover image fetch.", cover_response.status());
+            }
+        }
+        // If no cover was handled, extract first image from EPUB as cover using Rust
+        if !cover_handled {
+            info!("No Open Library cover found, extracting first image from EPUB as cover.");
+            let temp_dir = crate::space::temp_dir("cover")?;
+            let page_images = extract_epub_images(new_ebook_path.to_str().unwrap(), temp_dir.path())?;
+            if let Some(cover_img) = page_images.first() {
+                let renamed_cover_path = temp_dir.path().join(format!("torrent-cover_{}.jpg", torrent_id));
+                fs::copy(cover_img, &renamed_cover_path)
+                    .map_err(SeedToolsError::io("Failed to copy extracted cover image"))?;
+                #[cfg(unix)]
+                {
+                    use std::os::unix::fs::PermissionsExt;
+                    fs::set_permissions(&renamed_cover_path, fs::Permissions::from_mode(0o777))
+                        .map_err(SeedToolsError::io(format!("Failed to set permissions for cover image '{}'", renamed_cover_path.display())))?;
+                }
+                let remote_covers_path = format!(
+                    "{}/albumcovers",
+                    seedpool_config.screenshots.remote_path.trim_end_matches('/')
+                );
+                copy_to_cdn(Path::new(&renamed_cover_path), &remote_covers_path, offline)
+                    .map_err(|e| e.context("Failed to upload extracted cover image via SCP"))?;
+                info!("Successfully uploaded extracted EPUB cover image to CDN: {}", remote_covers_path);
+            } else {
+                warn!("No images found to use as cover from EPUB.");
+            }
+        }
+    }
+
+    // For PDFs: Upload the extracted cover image (if any)
+    if is_pdf
+        && let Some(cover_path) = pdf_cover_image_path
+    {
+        // Rename the cover image to include the torrent ID
+        let renamed_cover_path = Path::new(&cover_path)
+            .with_file_name(format!("torrent-cover_{}.jpg", torrent_id));
+        std::fs::rename(&cover_path, &renamed_cover_path)
+            .map_err(SeedToolsError::io("Failed to rename PDF cover image"))?;
+
+        // Set permissions to 777 for the renamed cover image
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            info!("Setting permissions to 777 for cover image: {}", renamed_cover_path.display());
+            std::fs::set_permissions(&renamed_cover_path, std::fs::Permissions::from_mode(0o777))
+                .map_err(SeedToolsError::io(format!("Failed to set permissions for cover image '{}'", renamed_cover_path.display())))?;
+            info!("Successfully set permissions to 777 for cover image: {}", renamed_cover_path.display());
+        }
+
+        info!("Uploading extracted PDF cover image: {}", renamed_cover_path.display());
+        let remote_covers_path = format!(
+            "{}/albumcovers",
+            seedpool_config.screenshots.remote_path.trim_end_matches('/')
+        );
+        copy_to_cdn(Path::new(&renamed_cover_path), &remote_covers_path, offline)
+            .map_err(|e| e.context("Failed to upload cover image via SCP"))?;
+        info!("Successfully uploaded cover image to CDN: {}", remote_covers_path);
+    }
+
+    // Add torrent to all qBittorrent instances
+    add_torrent_to_all_qbittorrent_instances(
+        std::slice::from_ref(&torrent_file),
+        &config.qbittorrent,
+        &config.deluge,
+        new_ebook_path.to_str().unwrap(),
+        &config.paths,
+        offline,
+    )?;
+
+    Ok(())
+}
+
+// Helper for PDF metadata extraction
+fn extract_metadata_from_pdf(pdf_path: &str) -> Result<(Option<String>, Option<String>), SeedToolsError> {
+    use lopdf::{Document, Object};
+
+    let doc = Document::load(pdf_path).map_err(|e| SeedToolsError::Metadata(format!("Failed to open PDF: {}", e)))?;
+    let info_obj = match doc.trailer.get(b"Info") {
+        Ok(obj) => obj,
+        Err(_) => return Ok((None, None)),
+    };
+    let info_ref = info_obj.as_reference().map_err(|e| SeedToolsError::Metadata(format!("Failed to get Info reference: {}", e)))?;
+    let dict = doc.get_dictionary(info_ref).map_err(|e| SeedToolsError::Metadata(format!("Failed to get PDF info dictionary: {}", e)))?;
+
+    fn get_pdf_string(dict: &lopdf::Dictionary, key: &[u8]) -> Option<String> {
+        match dict.get(key) {
+            Ok(Object::String(s, _)) => Some(String::from_utf8_lossy(s).to_string()),
+            Ok(obj) => obj.as_str().ok().map(|s| String::from_utf8_lossy(s).to_string()),
+            _ => None,
+        }
+    }
+
+    let title = get_pdf_string(dict, b"Title");
+    let author = get_pdf_string(dict, b"Author");
+    Ok((title, author))
+}
+
+fn extract_metadata_from_epub(epub_path: &str) -> Result<(Option<String>, Option<String>), SeedToolsError> {
+    let epub = EpubDoc::new(epub_path)
+        .map_err(|e| SeedToolsError::Metadata(format!("Failed to open EPUB file '{}': {}", epub_path, e)))?;
+
+    // Extract title from metadata
+    let title = epub.get_title();
+
+    // Extract author from metadata
+    let author = epub.mdata("creator").map(|creator| creator.value.clone());
+
+    Ok((title, author))
+}
+
+pub fn generate_ebook_bbcode_description(
+    title: &str,
+    author: &str,
+    open_library_work_key: &str,
+    open_library_author_key: &str,
+    client: &reqwest::blocking::Client,
+) -> Result<(String, Vec<String>), SeedToolsError> {
+    let fetch = |url: String, what: &str| -> Result<Value, SeedToolsError> {
+        client
+            .get(&url)
+            .send()
+            .map_err(|e| SeedToolsError::request("Open Library", e).context(&format!("Failed to fetch {}", what)))?
+            .json()
+            .map_err(|e| SeedToolsError::request("Open Library", e).context(&format!("Failed to parse {}", what)))
+    };
+    let details = crate::descriptions::EbookDetails {
+        title: title.to_string(),
+        author: author.to_string(),
+        work_key: open_library_work_key.to_string(),
+        author_key: open_library_author_key.to_string(),
+        work: fetch(format!("https://openlibrary.org/works/{}.json", open_library_work_key), "book details")?,
+        author_info: fetch(format!("https://openlibrary.org/authors/{}.json", open_library_author_key), "author details")?,
+        author_works: fetch(format!("https://openlibrary.org/authors/{}/works.json", open_library_author_key), "author's other works")?,
+    };
+    Ok(crate::descriptions::ebook(&details))
+}
+
+pub fn download_igdb_screenshots(
+    image_ids: &[String],
+    base_name: &str,
+    output_dir: &str,
+) -> Result<Vec<String>, SeedToolsError> {
+    let client = crate::http::client();
+    let mut local_paths = Vec::new();
+
+    fs::create_dir_all(output_dir).map_err(SeedToolsError::io("Failed to create output dir"))?;
+
+    for (i, image_id) in image_ids.iter().enumerate() {
+        let url = format!("https://images.igdb.com/igdb/image/upload/t_screenshot_big/{}.jpg", image_id);
+        let filename = join_path(output_dir, &format!("{}_screen{}.jpg", base_name, i + 1));
+
+        let mut resp = client.get(&url).send().map_err(|e| SeedToolsError::request("IGDB", e).context("Failed to download screenshot"))?;
+        let mut out = fs::File::create(&filename).map_err(SeedToolsError::io("Failed to create file"))?;
+        std::io::copy(&mut resp, &mut out).map_err(SeedToolsError::io("Failed to write screenshot"))?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&filename, fs::Permissions::from_mode(0o777))
+                .map_err(SeedToolsError::io(format!("Failed to set permissions for screenshot '{}'", filename)))?;
+        }
+
+        local_paths.push(filename);
+    }
+
+    Ok(local_paths)
+}
+
+pub fn generate_game_description(
+    screenshots: &[String],
+    custom_description: Option<&str>,
+    youtube_trailer_url: Option<&str>,
+    _base_name: &str,
+) -> String {
+    let mut description = String::new();
+
+    // Add screenshots in a 2x2 table pattern
+    if !screenshots.is_empty() {
+        description.push_str("[center]\n");
+        for (i, screenshot) in screenshots.iter().enumerate() {
+            if i % 2 == 0 {
+                description.push_str("[tr]\n");
+            }
+            description.push_str(&format!(
+                "        [td][img width=720]{}[/img][/td]\n",
+                screenshot
+            ));
+            if i % 2 == 1 || i == screenshots.len() - 1 {
+                description.push_str("[/tr]\n");
+            }
+        }
+        description.push_str("[/center]\n\n");
+    }
+
+    // Center the rest of the description
+    description.push_str("[center]\n");
+
+    // Add YouTube trailer link if available
+    if let Some(trailer_url) = youtube_trailer_url {
+        description.push_str(&format!(
+            "[b][url={}][Trailer on YouTube][/url][/b]\n\n",
+            trailer_url
+        ));
+    }
+
+    // Add custom description (not centered)
+    if let Some(custom_desc) = custom_description {
+        description.push_str(custom_desc);
+        description.push_str("\n\n");
+    }
+
+    // Append the default non-video description
+    description.push_str(&default_non_video_description());
+
+    description.push_str("\n[/center]");
+
+    description
+}
+
+pub fn generate_comic_description(
+    pdf_path: &str,
+    torrent_name: &str,
+    remote_path: &str,
+    public_image_path: &str,
+    offline: Option<&Offline>,
+) -> Result<String, SeedToolsError> {
+    use std::fs;
+
+    let mut image_urls = Vec::new();
+    let temp_dir = crate::space::temp_dir("comic")?;
+
+    // Always extract pages 3-10
+    for page in 3..=10 {
+        let image_name = format!("{}-page{}.jpg", torrent_name, page);
+        let image_path = join_path(temp_dir.path(), &image_name);
+
+        // Extract page as JPEG
+        let output = std::process::Command::new("gs")
+            .args([
+                "-dBATCH", "-dNOPAUSE",
+                "-sDEVICE=jpeg",
+                &format!("-dFirstPage={}", page),
+                &format!("-dLastPage={}", page),
+                "-r300", "-dJPEGQ=95",
+                &format!("-sOutputFile={}", image_path),
+                pdf_path,
+            ])
+            .output()
+            .map_err(|e| SeedToolsError::tool_spawn("gs", e))?;
+
+        if !output.status.success() {
+            return Err(SeedToolsError::tool("gs", format!(
+                "Failed to extract page {}: {}",
+                page,
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+
+        // Set permissions to 777
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&image_path, fs::Permissions::from_mode(0o777))
+                .map_err(SeedToolsError::io(format!("Failed to set permissions for '{}'", image_path)))?;
+        }
+
+        // SCP to CDN (remote_path as-is)
+        copy_to_cdn(Path::new(&image_path), remote_path, offline)
+            .map_err(|e| e.context(&format!("Failed to scp '{}'", image_path)))?;
+
+        // Build public URL
+        let cdn_url = format!("{}/{}", public_image_path.trim_end_matches('/'), image_name);
+        image_urls.push(cdn_url);
+    }
+
+    Ok(crate::descriptions::comic(torrent_name, &image_urls))
+}
+
+pub fn process_newspaper_upload(
+    input_path: &str,
+    config: &Config,
+    seedpool_config: &SeedpoolConfig,
+    offline: Option<&Offline>,
+) -> Result<(), SeedToolsError> {
+    
+    use std::fs;
+
+    let mut working_dir = input_path.to_string();
+
+    // If input is a file, get its parent directory for extraction
+    if Path::new(&working_dir).is_file()
+        && let Some(parent) = Path::new(&working_dir).parent()
+    {
+        working_dir = parent.to_string_lossy().to_string();
+    }
+
+    // 1. Extract all ZIP files in the directory
+    let zip_files: Vec<_> = fs::read_dir(&working_dir)
+        .map_err(SeedToolsError::io(format!("Failed to read directory '{}'", working_dir)))?
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("zip")) {
+                Some(path)
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    for zip_file in &zip_files {
+        log::info!("Extracting ZIP archive: {}", zip_file.display());
+        let output = std::process::Command::new("unzip")
+            .arg("-o")
+            .arg(zip_file)
+            .arg("-d")
+            .arg(&working_dir)
+            .output()
+            .map_err(|e| SeedToolsError::tool_spawn("unzip", e))?;
+        if !output.status.success() {
+            return Err(SeedToolsError::tool("unzip", format!(
+                "Failed to extract ZIP archive: {}. Error: {}",
+                zip_file.display(),
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+    }
+
+    // 2. Extract all RAR files in the directory
+    extract_rar_archives(&working_dir)?;
+
+    // 3. Find the main .epub or .pdf file
+    let mut found_pdf: Option<String> = None;
+    let mut found_epub: Option<String> = None;
+    for entry in WalkDir::new(&working_dir).into_iter().filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if path.is_file()
+            && let Some(ext) = path.extension().and_then(|e| e.to_str())
+        {
+            if ext.eq_ignore_ascii_case("epub") {
+                found_epub = Some(path.to_string_lossy().to_string());
+                break;
+            } else if ext.eq_ignore_ascii_case("pdf") {
+                found_pdf = Some(path.to_string_lossy().to_string());
+            }
+        }
+    }
+    let (newspaper_path, is_pdf) = if let Some(epub) = found_epub {
+        (epub, false)
+    } else if let Some(pdf) = found_pdf {
+        (pdf, true)
+    } else {
+        return Err(SeedToolsError::Metadata(format!("No .epub or .pdf file found in directory '{}'", working_dir)));
+    };
+
+    // 4. Extract images for description and cover
+    let mut desc_image_urls = Vec::new();
+    let mut cover_image_path: Option<String> = None;
+    let temp_dir = crate::space::temp_dir("newspaper")?; // Pages and the cover, until the upload is done
+    let base_name = Path::new(&newspaper_path)
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+
+    if is_pdf {
+        // --- PDF: Use Ghostscript for cover and description images ---
+        // Extract cover (page 1)
+        let cover_path = temp_dir.path().join("page-1.jpg");
+        let output = std::process::Command::new("gs")
+            .args([
+                "-dBATCH", "-dNOPAUSE",
+                "-sDEVICE=jpeg",
+                "-dFirstPage=1", "-dLastPage=1",
+                "-r150", "-dJPEGQ=95",
+                &format!("-sOutputFile={}", cover_path.display()),
+                &newspaper_path,
+            ])
+            .output()
+            .map_err(|e| SeedToolsError::tool_spawn("gs", e))?;
+        if !output.status.success() {
+            return Err(SeedToolsError::tool("gs", format!(
+                "Failed to extract cover from PDF: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+        cover_image_path = Some(cover_path.to_string_lossy().to_string());
+
+        // Extract pages 2-11 for description
+        for page in 2..=11 {
+            let img_name = format!("{}-page{}.jpg", base_name, page);
+            let img_path = temp_dir.path().join(&img_name);
+            let output = std::process::Command::new("gs")
+                .args([
+                    "-dBATCH", "-dNOPAUSE",
+                    "-sDEVICE=jpeg",
+                    &format!("-dFirstPage={}", page),
+                    &format!("-dLastPage={}", page),
+                    "-r300", "-dJPEGQ=95",
+                    &format!("-sOutputFile={}", img_path.display()),
+                    &newspaper_path,
+                ])
+                .output()
+                .map_err(|e| SeedToolsError::tool_spawn("gs", e))?;
+            if !output.status.success() {
+                return Err(SeedToolsError::tool("gs", format!(
+                    "Failed to extract page {}: {}",
+                    page,
+                    String::from_utf8_lossy(&output.stderr)
+                )));
+            }
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                fs::set_permissions(&img_path, fs::Permissions::from_mode(0o777))
+                    .map_err(SeedToolsError::io(format!("Failed to set permissions for '{}'", img_path.display())))?;
+            }
+            // SCP to CDN
+            copy_to_cdn(Path::new(&img_path), &seedpool_config.screenshots.remote_path, offline)
+                .map_err(|e| e.context("Failed to upload description image via SCP"))?;
+            let url = format!("{}/{}", seedpool_config.screenshots.image_path.trim_end_matches('/'), img_name);
+            desc_image_urls.push(url);
+        }
+    } else {
+        // --- EPUB: Use Rust to extract images for cover and description ---
+        let page_images = extract_epub_images(&newspaper_path, temp_dir.path())?;
+
+        if page_images.len() < 2 {
+            return Err(SeedToolsError::Metadata("Not enough images extracted from EPUB.".to_string()));
+        }
+
+        // Pages 2-11 for description
+        for (i, img) in page_images.iter().enumerate().skip(1).take(10) {
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                fs::set_permissions(img, fs::Permissions::from_mode(0o777))
+                    .map_err(SeedToolsError::io(format!("Failed to set permissions for image '{}'", img.display())))?;
+            }
+            let img_name = format!("{}-page{}.jpg", base_name, i + 1);
+            copy_to_cdn(img, &seedpool_config.screenshots.remote_path, offline)
+                .map_err(|e| e.context("Failed to upload description image via SCP"))?;
+            let url = format!("{}/{}", seedpool_config.screenshots.image_path.trim_end_matches('/'), img_name);
+            desc_image_urls.push(url);
+        }
+        // Cover image is page 1
+        if let Some(cover_img) = page_images.first() {
+            cover_image_path = Some(cover_img.to_string_lossy().to_string());
+        }
+    }
+
+    // 5. Build BBCode description
+    let mut description = format!(
+        "[center][b][size=18][color=#2E86C1]{}[/color][/size][/b]\n\n[table]\n",
+        base_name
+    );
+    for (i, url) in desc_image_urls.iter().enumerate() {
+        if i % 2 == 0 {
+            description.push_str("  [tr]\n");
+        }
+        description.push_str(&format!("    [td][img width=720]{}[/img][/td]\n", url));
+        if i % 2 == 1 {
+            description.push_str("  [/tr]\n");
+        }
+    }
+    if desc_image_urls.len() % 2 != 0 {
+        description.push_str("    [td][/td]\n  [/tr]\n");
+    }
+    description.push_str("[/table][/center]\n\n");
+    let custom_description = seedpool_config.settings.custom_description_for("newspaper", &base_name, &base_name);
+    if !custom_description.trim().is_empty() {
+        description.push_str(&format!("{}\n", custom_description));
+    }
+    description.push_str(&format!("[center]{}[/center]", default_non_video_description()));
+
+    for entry in fs::read_dir(&working_dir).map_err(SeedToolsError::io(format!("Failed to read directory '{}'", working_dir)))? {
+        let entry = entry.map_err(SeedToolsError::io("Failed to read directory entry"))?;
+        let path = entry.path();
+        if path.extension().map(|ext| ext.eq_ignore_ascii_case("zip")).unwrap_or(false) {
+            fs::remove_file(&path)
+                .map_err(SeedToolsError::io(format!("Failed to remove zip file '{}'", path.display())))?;
+        }
+    }
+
+    // 6. Create torrent
+    let torrent_input = &working_dir;
+    crate::limits::check_release_limits(torrent_input, "ebook", &config.limits)?;
+    let torrent_file = create_torrent(
+        torrent_input,
+        &config.paths.torrent_dir,
+        &seedpool_config.settings.announce_url,
+        &config.paths.mkbrr,
+        Some(&config.exclusions),
+        &seedpool_config.torrent,
+    )?;
+
+    // 7. Prepare upload form and upload to Seedpool
+    let nfo_file = fs::read_dir(&working_dir)
+        .ok()
+        .and_then(|mut entries| {
+            entries.find_map(|entry| {
+                let entry = entry.ok()?;
+                let path = entry.path();
+                if path.extension().map(|ext| ext.eq_ignore_ascii_case("nfo")).unwrap_or(false) {
+                    Some(path.to_string_lossy().to_string())
+                } else {
+                    None
+                }
+            })
+        });
+
+    let upload = UploadRequest {
+        torrent_file: torrent_file.clone(),
+        name: Path::new(input_path).file_name().unwrap_or_default().to_string_lossy().to_string(),
+        category_id: seedpool_config.ids.category("ebook"),
+        type_id: seedpool_config.ids.type_id("newspaper"),
+        description: Some(description),
+        keywords: Some("newspaper".to_string()),
+        nfo_file,
+        ..Default::default()
+    }
+    .with_flags(&seedpool_config.upload_flags);
+
+    let response_text = submit_upload(
+        &seedpool_config.settings.upload_url,
+        Some(&seedpool_config.general.api_key),
+        &upload,
+        offline,
+    )?;
+
+    // Extract the torrent ID from the response
+    let torrent_id = extract_torrent_id(&response_text)?;
+
+    // 8. Upload cover image to CDN, named with torrent id
+    if let Some(cover_img_path) = cover_image_path {
+        let cover_name = format!("torrent-cover_{}.jpg", torrent_id);
+        let temp_cover_path = temp_dir.path().join(&cover_name);
+
+        // Rename or copy the cover image to the correct name in temp
+        fs::copy(&cover_img_path, &temp_cover_path)
+            .map_err(SeedToolsError::io("Failed to copy cover image for CDN upload"))?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&temp_cover_path, fs::Permissions::from_mode(0o777))
+                .map_err(SeedToolsError::io(format!("Failed to set permissions for cover image '{}'", temp_cover_path.display())))?;
+        }
+
+        let cover_remote_path = format!("{}/albumcovers", seedpool_config.screenshots.remote_path.trim_end_matches('/'));
+        copy_to_cdn(Path::new(&temp_cover_path), &cover_remote_path, offline)
+            .map_err(|e| e.context("Failed to upload cover image via SCP"))?;
+    }
+
+    // 9. Add torrent to all qBittorrent instances
+    add_torrent_to_all_qbittorrent_instances(
+        std::slice::from_ref(&torrent_file),
+        &config.qbittorrent,
+        &config.deluge,
+        newspaper_path.as_str(),
+        &config.paths,
+        offline,
+    )?;
+
+    Ok(())
+}
+
+pub fn extract_epub_images(epub_path: &str, temp_dir: &std::path::Path) -> Result<Vec<std::path::PathBuf>, SeedToolsError> {
+    let file = File::open(epub_path).map_err(SeedToolsError::io("Failed to open EPUB"))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| SeedToolsError::Metadata(format!("Failed to read EPUB as zip: {}", e)))?;
+
+    std::fs::create_dir_all(temp_dir).map_err(SeedToolsError::io("Failed to create temp dir"))?;
+
+    let mut images = Vec::new();
+
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i).map_err(|e| SeedToolsError::Metadata(format!("Failed to access EPUB entry: {}", e)))?;
+        let name = file.name().to_lowercase();
+        if name.ends_with(".jpg") || name.ends_with(".jpeg") || name.ends_with(".png") || name.ends_with(".gif") {
+            let out_path = temp_dir.join(std::path::Path::new(&name).file_name().unwrap());
+            let mut out_file = File::create(&out_path).map_err(SeedToolsError::io("Failed to create image file"))?;
+            std::io::copy(&mut file, &mut out_file).map_err(SeedToolsError::io("Failed to extract image"))?;
+            images.push(out_path);
+        }
+    }
+
+    images.sort();
+    Ok(images)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // File names a release can carry that an argument parser or filter graph would misread
+    const HOSTILE: &[&str] = &["-i.mkv", "--help.mkv", "a:b.mkv", "it's.mkv", "a,b;c.mkv", "[x]y.mkv", "two\nlines.mkv"];
+
+    #[test]
+    fn epub_metadata_is_read_from_the_package() {
+        let (title, author) = extract_metadata_from_epub("fixtures/media/book.epub").unwrap();
+        assert_eq!(title.as_deref(), Some("The Fixture Book"));
+        assert_eq!(author.as_deref(), Some("Jane Tester"));
+    }
+
+    #[test]
+    fn ffmpeg_file_arg_always_uses_the_file_protocol() {
+        for name in HOSTILE {
+            assert_eq!(ffmpeg_file_arg(name), format!("file:{}", name));
+        }
+        assert_eq!(ffmpeg_file_arg("/srv/a:b/-x.mkv"), "file:/srv/a:b/-x.mkv");
+    }
+
+    #[test]
+    fn escape_filter_value_escapes_for_the_option_and_the_graph() {
+        assert_eq!(escape_filter_value("plain.txt"), "plain.txt");
+        assert_eq!(escape_filter_value("a:b"), r"a\\:b");
+        assert_eq!(escape_filter_value("it's"), r"it\\\'s");
+        assert_eq!(escape_filter_value("a,b;c"), r"a\,b\;c");
+        assert_eq!(escape_filter_value("[x]y"), r"\[x\]y");
+        assert_eq!(escape_filter_value(r"C:\dir"), r"C\\:\\\\dir");
+        // Nothing to escape in the graph; the value is passed as one argument, not through a shell
+        assert_eq!(escape_filter_value("-i"), "-i");
+        assert_eq!(escape_filter_value("two\nlines"), "two\nlines");
+    }
+
+    #[cfg(unix)]
+    fn scp_args(file: &str, remote: &str, limit_kbps: Option<u64>) -> Vec<String> {
+        scp_command(Path::new(file), remote, limit_kbps)
+            .get_args()
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn scp_command_keeps_hostile_names_local_and_out_of_the_options() {
+        for name in HOSTILE {
+            let args = scp_args(name, "me@cdn:/srv/images", None);
+            assert_eq!(args, ["--".to_string(), format!("./{}", name), "me@cdn:/srv/images".to_string()]);
+        }
+        assert_eq!(scp_args("/tmp/a:b.jpg", "me@cdn:/srv", None), ["--", "/tmp/a:b.jpg", "me@cdn:/srv"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn scp_command_puts_the_rate_limit_before_the_files() {
+        assert_eq!(scp_args("-x.jpg", "me@cdn:/srv", Some(800)), ["-l", "800", "--", "./-x.jpg", "me@cdn:/srv"]);
+    }
 }
\ No newline at end of file
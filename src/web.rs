@@ -0,0 +1,244 @@
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use axum::{
+    extract::{Query, State},
+    http::{header, Request, StatusCode},
+    middleware::{self, Next},
+    response::{sse::{Event, KeepAlive, Sse}, Html, IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use crate::api::token_matches;
+use crate::jobs::JobManager;
+use crate::reload::{watch_config_dir, Live};
+use crate::types::{Config, UploadOverrides, WebConfig};
+
+const INDEX_HTML: &str = include_str!("web_index.html");
+
+#[derive(Clone)]
+struct WebState {
+    settings: Arc<Live<WebSettings>>,
+    jobs: Arc<JobManager>,
+}
+
+/// The parts of the `web` section that a config reload changes on the fly.
+struct WebSettings {
+    root: PathBuf,
+    auth_token: Option<String>,
+}
+
+impl WebSettings {
+    fn new(config: &WebConfig) -> Result<Self, String> {
+        let root = PathBuf::from(&config.root_dir)
+            .canonicalize()
+            .map_err(|e| format!("Invalid web root_dir '{}': {}", config.root_dir, e))?;
+        Ok(WebSettings { root, auth_token: config.auth_token.clone() })
+    }
+}
+
+#[derive(Deserialize)]
+struct BrowseQuery {
+    #[serde(default)]
+    path: String,
+}
+
+#[derive(Serialize)]
+struct BrowseEntry {
+    name: String,
+    path: String, // Relative to the web root
+    is_dir: bool,
+}
+
+#[derive(Deserialize)]
+struct PathRequest {
+    path: String,
+}
+
+#[derive(Deserialize)]
+struct UploadRequest {
+    path: String,
+    trackers: Vec<String>,
+}
+
+fn error_response(status: StatusCode, message: impl Into<String>) -> Response {
+    (status, Json(serde_json::json!({ "error": message.into() }))).into_response()
+}
+
+/// Resolves a path relative to the web root, rejecting anything that escapes it.
+fn resolve_path(root: &Path, relative: &str) -> Result<PathBuf, String> {
+    let candidate = root
+        .join(relative.trim_start_matches('/'))
+        .canonicalize()
+        .map_err(|e| format!("Invalid path '{}': {}", relative, e))?;
+    if !candidate.starts_with(root) {
+        return Err(format!("Path '{}' is outside the web root", relative));
+    }
+    Ok(candidate)
+}
+
+#[derive(Deserialize)]
+struct TokenQuery {
+    token: Option<String>,
+}
+
+/// Checks `Authorization: Bearer <token>` or `?token=<token>` (EventSource cannot send headers).
+async fn require_token<B>(State(state): State<WebState>, request: Request<B>, next: Next<B>) -> Response {
+    if let Some(expected) = &state.settings.get().auth_token {
+        let header_token = request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+        // Percent-decoded, as the browser encodes the token it puts in the URL
+        let query_token = Query::<TokenQuery>::try_from_uri(request.uri()).ok().and_then(|Query(query)| query.token);
+        if !token_matches(header_token, expected) && !token_matches(query_token.as_deref(), expected) {
+            return error_response(StatusCode::UNAUTHORIZED, "Missing or invalid token");
+        }
+    }
+    next.run(request).await
+}
+
+async fn index() -> Html<&'static str> {
+    Html(INDEX_HTML)
+}
+
+/// Prometheus metrics of every upload run with this work_dir; unauthenticated like the index page.
+async fn metrics() -> Response {
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], crate::metrics::render()).into_response()
+}
+
+async fn browse(State(state): State<WebState>, Query(query): Query<BrowseQuery>) -> Response {
+    let root = state.settings.get().root.clone();
+    let dir = match resolve_path(&root, &query.path) {
+        Ok(dir) => dir,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, e),
+    };
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, format!("Failed to read directory: {}", e)),
+    };
+
+    let mut listing: Vec<BrowseEntry> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| {
+            let path = entry.path();
+            BrowseEntry {
+                name: entry.file_name().to_string_lossy().to_string(),
+                path: path
+                    .strip_prefix(&root)
+                    .unwrap_or(&path)
+                    .to_string_lossy()
+                    .to_string(),
+                is_dir: path.is_dir(),
+            }
+        })
+        .collect();
+    // Directories first, then by name (same order as the TUI file list)
+    listing.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase())));
+    Json(listing).into_response()
+}
+
+async fn preflight(State(state): State<WebState>, Json(request): Json<PathRequest>) -> Response {
+    let path = match resolve_path(&state.settings.get().root, &request.path) {
+        Ok(path) => path,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, e),
+    };
+    match state.jobs.run_preflight(&path).await {
+        Ok(result) => Json(result).into_response(),
+        Err(e) => error_response(StatusCode::UNPROCESSABLE_ENTITY, e),
+    }
+}
+
+async fn start_upload(State(state): State<WebState>, Json(request): Json<UploadRequest>) -> Response {
+    let path = match resolve_path(&state.settings.get().root, &request.path) {
+        Ok(path) => path,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, e),
+    };
+    match state.jobs.start_upload(&path, &request.trackers, &UploadOverrides::default()) {
+        Ok(id) => (StatusCode::ACCEPTED, Json(serde_json::json!({ "id": id }))).into_response(),
+        Err(e) => error_response(StatusCode::BAD_REQUEST, e),
+    }
+}
+
+async fn list_uploads(State(state): State<WebState>) -> Response {
+    Json(state.jobs.list()).into_response()
+}
+
+/// Server-sent events with every job output line and status change.
+async fn events(State(state): State<WebState>) -> impl IntoResponse {
+    let receiver = state.jobs.subscribe();
+    let stream = futures_util::stream::unfold(receiver, |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(job_event) => {
+                    let event = Event::default().json_data(&job_event).unwrap_or_default();
+                    return Some((Ok::<Event, Infallible>(event), receiver));
+                }
+                // A slow client missed some lines; keep going with the newest ones
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+/// Runs the `--web` server until the process is stopped. Changes to `config.yaml` in
+/// `config_dir` are applied while it runs, except for the bind address.
+pub async fn launch_web(config: &Config, config_dir: &Path) -> Result<(), String> {
+    let web = config.web.as_ref().ok_or("The --web mode requires a `web` section in config.yaml")?;
+    let addr: SocketAddr = web
+        .bind
+        .parse()
+        .map_err(|e| format!("Invalid web bind address '{}': {}", web.bind, e))?;
+    if web.auth_token.is_none() && !addr.ip().is_loopback() {
+        warn!("Web UI is listening on {} without an auth_token. Anyone who can reach it can upload.", addr);
+    }
+
+    let state = WebState {
+        settings: Arc::new(Live::new(WebSettings::new(web)?)),
+        jobs: JobManager::new(&config.maintenance)?,
+    };
+    let (settings, jobs, bind) = (state.settings.clone(), state.jobs.clone(), web.bind.clone());
+    let reloaded = watch_config_dir(config_dir, move |config| {
+        jobs.set_maintenance(&config.maintenance);
+        let Some(web) = &config.web else {
+            warn!("The reloaded config.yaml has no `web` section; keeping the running web settings");
+            return;
+        };
+        if web.bind != bind {
+            warn!("The web bind address changed to {}; restart seed-tools to listen there", web.bind);
+        }
+        match WebSettings::new(web) {
+            Ok(new_settings) => settings.replace(new_settings),
+            Err(e) => warn!("Keeping the running web settings: {}", e),
+        }
+    });
+    if let Err(e) = reloaded {
+        warn!("{}; config changes need a restart", e);
+    }
+
+    let api = Router::new()
+        .route("/api/browse", get(browse))
+        .route("/api/preflight", post(preflight))
+        .route("/api/uploads", get(list_uploads).post(start_upload))
+        .route("/api/events", get(events))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_token));
+    let app = Router::new()
+        .route("/", get(index))
+        .route("/metrics", get(metrics))
+        .merge(api)
+        .with_state(state);
+
+    info!("Web UI listening on http://{}", addr);
+    println!("Web UI listening on http://{}", addr);
+    axum::Server::bind(&addr)
+        .serve(app.into_make_service())
+        .await
+        .map_err(|e| format!("Web server error: {}", e))
+}